@@ -41,13 +41,20 @@ const sidebars = {
         'commands/similar',
         'commands/search',
         'commands/templates',
+        'commands/styles',
         'commands/alias',
         'commands/sync',
+        'commands/undo',
+        'commands/backup',
         'commands/logs',
         'commands/proxy',
+        'commands/serve-webui',
         'commands/web-chat-proxy',
         'commands/mcp',
         'commands/usage',
+        'commands/plugins',
+        'commands/doctor',
+        'commands/upgrade-self',
       ],
     },
     {
@@ -60,7 +67,8 @@ const sidebars = {
         'advanced/provider-management',
         'advanced/sync',
         'advanced/mcp',
-        'advanced/vision'
+        'advanced/vision',
+        'advanced/chat-templates'
       ],
     },
     {
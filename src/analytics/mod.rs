@@ -1,2 +1,4 @@
 // Analytics modules
+pub mod session_summary;
 pub mod usage_stats;
+pub mod vector_analysis;
@@ -0,0 +1,322 @@
+//! End-of-session summary: turns, tokens, estimated cost, models and tool calls
+
+use crate::database::{ChatEntry, Database, ToolCallEntry};
+use crate::model_metadata::ModelMetadata;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::HashMap;
+
+/// Usage of one provider/model within a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionModelUsage {
+    pub provider: String,
+    pub model: String,
+    pub turns: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// `None` when the model has no pricing in the models cache
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub turns: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// In order of first use
+    pub models: Vec<SessionModelUsage>,
+    /// (tool, calls, cached calls), most used first
+    pub tool_calls: Vec<(String, u64, u64)>,
+}
+
+impl SessionSummary {
+    /// Total estimated cost of the priced models, and whether every model was priced
+    pub fn estimated_cost(&self) -> Option<(f64, bool)> {
+        let priced: Vec<f64> = self.models.iter().filter_map(|m| m.cost).collect();
+        if priced.is_empty() {
+            return None;
+        }
+        Some((priced.iter().sum(), priced.len() == self.models.len()))
+    }
+
+    pub fn total_tool_calls(&self) -> u64 {
+        self.tool_calls.iter().map(|(_, calls, _)| calls).sum()
+    }
+}
+
+/// Summarize a session's chat log and tool calls.
+///
+/// `pricing` maps (provider, model) to (input, output) prices per million tokens.
+pub fn compute_session_summary(
+    session_id: &str,
+    entries: &[ChatEntry],
+    tool_calls: &[ToolCallEntry],
+    pricing: &HashMap<(String, String), (f64, f64)>,
+) -> SessionSummary {
+    let mut summary = SessionSummary {
+        session_id: session_id.to_string(),
+        ..Default::default()
+    };
+
+    for entry in entries {
+        let input = entry.input_tokens.unwrap_or(0).max(0) as u64;
+        let output = entry.output_tokens.unwrap_or(0).max(0) as u64;
+        summary.turns += 1;
+        summary.input_tokens += input;
+        summary.output_tokens += output;
+
+        let provider = entry.provider.clone().unwrap_or_default();
+        let index = match summary
+            .models
+            .iter()
+            .position(|m| m.provider == provider && m.model == entry.model)
+        {
+            Some(index) => index,
+            None => {
+                summary.models.push(SessionModelUsage {
+                    provider,
+                    model: entry.model.clone(),
+                    turns: 0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    cost: None,
+                });
+                summary.models.len() - 1
+            }
+        };
+        let usage = &mut summary.models[index];
+        usage.turns += 1;
+        usage.input_tokens += input;
+        usage.output_tokens += output;
+    }
+
+    for usage in &mut summary.models {
+        usage.cost = pricing
+            .get(&(usage.provider.clone(), usage.model.clone()))
+            .map(|(input_price, output_price)| {
+                (usage.input_tokens as f64 / 1_000_000.0) * input_price
+                    + (usage.output_tokens as f64 / 1_000_000.0) * output_price
+            });
+    }
+
+    let mut tools: HashMap<&str, (u64, u64)> = HashMap::new();
+    for call in tool_calls {
+        let counts = tools.entry(call.tool.as_str()).or_default();
+        counts.0 += 1;
+        if call.cached {
+            counts.1 += 1;
+        }
+    }
+    summary.tool_calls = tools
+        .into_iter()
+        .map(|(tool, (calls, cached))| (tool.to_string(), calls, cached))
+        .collect();
+    summary
+        .tool_calls
+        .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    summary
+}
+
+/// Load the summary of a session from the logs database and the models cache
+pub async fn load_session_summary(session_id: &str) -> Result<SessionSummary> {
+    let db = Database::new()?;
+    let entries = db.get_chat_history(session_id)?;
+    let tool_calls = db.get_tool_calls(session_id)?;
+
+    let mut pricing = HashMap::new();
+    let mut cached_models: HashMap<String, Vec<ModelMetadata>> = HashMap::new();
+    for entry in &entries {
+        let Some(provider) = &entry.provider else {
+            continue;
+        };
+        if !cached_models.contains_key(provider) {
+            let models = crate::unified_cache::UnifiedCache::load_provider_models(provider)
+                .await
+                .unwrap_or_default();
+            cached_models.insert(provider.clone(), models);
+        }
+        let prices = cached_models[provider]
+            .iter()
+            .find(|m| m.id == entry.model)
+            .and_then(|m| m.input_price_per_m.zip(m.output_price_per_m));
+        if let Some(prices) = prices {
+            pricing.insert((provider.clone(), entry.model.clone()), prices);
+        }
+    }
+
+    Ok(compute_session_summary(
+        session_id,
+        &entries,
+        &tool_calls,
+        &pricing,
+    ))
+}
+
+pub fn display_session_summary(summary: &SessionSummary) {
+    println!("\n{}", sym("📋 Session Summary").bold().blue());
+    println!(
+        "{} {}",
+        "Session:".bold(),
+        summary.session_id.as_str().dimmed()
+    );
+    println!("{} {}", "Turns:".bold(), summary.turns.to_string().green());
+    println!(
+        "{} {} input + {} output = {} total",
+        "Tokens:".bold(),
+        summary.input_tokens.to_string().cyan(),
+        summary.output_tokens.to_string().yellow(),
+        (summary.input_tokens + summary.output_tokens)
+            .to_string()
+            .green()
+    );
+
+    match summary.estimated_cost() {
+        Some((cost, true)) => println!("{} ${:.6}", "Estimated cost:".bold(), cost),
+        Some((cost, false)) => println!(
+            "{} ${:.6} {}",
+            "Estimated cost:".bold(),
+            cost,
+            "(some models have no pricing)".dimmed()
+        ),
+        None => println!(
+            "{} {}",
+            "Estimated cost:".bold(),
+            "unknown (no pricing in the models cache)".dimmed()
+        ),
+    }
+
+    if !summary.models.is_empty() {
+        println!("{}", "Models:".bold());
+        for usage in &summary.models {
+            let name = if usage.provider.is_empty() {
+                usage.model.clone()
+            } else {
+                format!("{}:{}", usage.provider, usage.model)
+            };
+            let cost = usage
+                .cost
+                .map(|c| format!(", ${:.6}", c))
+                .unwrap_or_default();
+            println!(
+                "  {} {} - {} turn(s), {} in / {} out{}",
+                sym("•"),
+                name.cyan(),
+                usage.turns,
+                usage.input_tokens,
+                usage.output_tokens,
+                cost
+            );
+        }
+    }
+
+    if summary.tool_calls.is_empty() {
+        println!("{} 0", "Tool calls:".bold());
+    } else {
+        println!("{} {}", "Tool calls:".bold(), summary.total_tool_calls());
+        for (tool, calls, cached) in &summary.tool_calls {
+            let cached = if *cached > 0 {
+                format!(" ({} cached)", cached)
+            } else {
+                String::new()
+            };
+            println!(
+                "  {} {} {} {}{}",
+                sym("•"),
+                tool.cyan(),
+                sym("×"),
+                calls,
+                cached.dimmed()
+            );
+        }
+    }
+}
+
+/// Print the summary of a session, logging (not failing on) errors
+pub async fn print_session_summary(session_id: &str) {
+    match load_session_summary(session_id).await {
+        Ok(summary) => display_session_summary(&summary),
+        Err(e) => crate::debug_log!("Could not load session summary: {}", e),
+    }
+}
+
+/// Print the summary of the current session (used after direct prompts with `--stats`)
+pub async fn print_current_session_summary() {
+    let session_id = Database::new().and_then(|db| db.get_current_session_id());
+    match session_id {
+        Ok(Some(session_id)) => print_session_summary(&session_id).await,
+        Ok(None) => crate::debug_log!("No current session to summarize"),
+        Err(e) => crate::debug_log!("Could not load session summary: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(provider: &str, model: &str, input: i32, output: i32) -> ChatEntry {
+        ChatEntry {
+            chat_id: "s1".to_string(),
+            model: model.to_string(),
+            question: "q".to_string(),
+            response: "r".to_string(),
+            timestamp: Utc::now(),
+            input_tokens: Some(input),
+            output_tokens: Some(output),
+            provider: Some(provider.to_string()),
+        }
+    }
+
+    fn call(tool: &str, cached: bool) -> ToolCallEntry {
+        ToolCallEntry {
+            session_id: "s1".to_string(),
+            server: "fs".to_string(),
+            tool: tool.to_string(),
+            cached,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_compute_session_summary() {
+        let entries = vec![
+            entry("openai", "gpt-4o", 1_000_000, 500_000),
+            entry("ollama", "llama3", 200, 100),
+            entry("openai", "gpt-4o", 1_000_000, 500_000),
+        ];
+        let calls = vec![
+            call("read_file", false),
+            call("fetch", false),
+            call("read_file", true),
+        ];
+        let mut pricing = HashMap::new();
+        pricing.insert(("openai".to_string(), "gpt-4o".to_string()), (2.5, 10.0));
+
+        let summary = compute_session_summary("s1", &entries, &calls, &pricing);
+        assert_eq!(summary.turns, 3);
+        assert_eq!(summary.input_tokens, 2_000_200);
+        assert_eq!(summary.output_tokens, 1_000_100);
+
+        assert_eq!(summary.models.len(), 2);
+        assert_eq!(summary.models[0].model, "gpt-4o");
+        assert_eq!(summary.models[0].turns, 2);
+        assert_eq!(summary.models[0].cost, Some(15.0));
+        assert_eq!(summary.models[1].cost, None);
+        assert_eq!(summary.estimated_cost(), Some((15.0, false)));
+
+        assert_eq!(summary.total_tool_calls(), 3);
+        assert_eq!(summary.tool_calls[0], ("read_file".to_string(), 2, 1));
+        assert_eq!(summary.tool_calls[1], ("fetch".to_string(), 1, 0));
+    }
+
+    #[test]
+    fn test_empty_session_has_no_cost() {
+        let summary = compute_session_summary("s1", &[], &[], &HashMap::new());
+        assert_eq!(summary.turns, 0);
+        assert!(summary.models.is_empty());
+        assert_eq!(summary.estimated_cost(), None);
+    }
+}
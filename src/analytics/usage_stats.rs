@@ -1,10 +1,14 @@
-use crate::database::{ChatEntry, Database};
+use crate::database::{ChatEntry, Database, RequestTimingEntry};
+use crate::utils::terminal::sym;
 use anyhow::Result;
-use chrono::{DateTime, Datelike, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
 use colored::Colorize;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+/// (label, requests, tokens) for one period and provider
+pub type ProviderUsage = (String, u64, u64);
+
+#[derive(Debug, Clone, Default)]
 pub struct UsageStats {
     pub total_tokens: u64,
     pub total_requests: u64,
@@ -15,9 +19,46 @@ pub struct UsageStats {
     pub weekly_usage: Vec<(String, u64, u64)>, // (week, requests, tokens)
     pub monthly_usage: Vec<(String, u64, u64)>, // (month, requests, tokens)
     pub yearly_usage: Vec<(String, u64, u64)>, // (year, requests, tokens)
+    pub provider_usage: Vec<(String, u64, u64)>, // (provider, requests, tokens)
+    pub daily_provider_usage: Vec<(String, Vec<ProviderUsage>)>, // (date, per-provider usage)
+    pub activity_heatmap: [[(u64, u64); 24]; 7], // [weekday][local hour] -> (requests, tokens)
     pub date_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
 }
 
+/// Label used for chat logs recorded before the provider was stored
+const UNKNOWN_PROVIDER: &str = "unknown";
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+const HEAT_SHADES: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+
+const STACK_COLORS: [colored::Color; 6] = [
+    colored::Color::Green,
+    colored::Color::Cyan,
+    colored::Color::Magenta,
+    colored::Color::Yellow,
+    colored::Color::Blue,
+    colored::Color::Red,
+];
+
+#[derive(Debug, Clone)]
+pub struct LatencySummary {
+    pub label: String, // provider:model
+    pub requests: u64,
+    pub avg_ttfb_ms: Option<f64>,
+    pub avg_first_token_ms: Option<f64>,
+    pub avg_total_ms: f64,
+    pub avg_tokens_per_sec: Option<f64>,
+    pub retries: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LatencyStats {
+    pub total_requests: u64,
+    pub by_model: Vec<LatencySummary>, // sorted by average total latency
+    pub daily_latency: Vec<(String, f64)>, // (date, avg total ms)
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum TimeFrame {
@@ -39,142 +80,297 @@ impl UsageAnalyzer {
     }
 
     pub fn get_usage_stats(&self, days_back: Option<u32>) -> Result<UsageStats> {
-        let entries = if let Some(days) = days_back {
-            let cutoff_date = Utc::now() - Duration::days(days as i64);
-            self.get_entries_since(cutoff_date)?
-        } else {
-            self.db.get_all_logs()?
-        };
-
-        if entries.is_empty() {
-            return Ok(UsageStats {
-                total_tokens: 0,
-                total_requests: 0,
-                input_tokens: 0,
-                output_tokens: 0,
-                model_usage: Vec::new(),
-                daily_usage: Vec::new(),
-                weekly_usage: Vec::new(),
-                monthly_usage: Vec::new(),
-                yearly_usage: Vec::new(),
-                date_range: None,
-            });
-        }
-
-        let mut total_input_tokens = 0u64;
-        let mut total_output_tokens = 0u64;
-        let mut model_stats: HashMap<String, (u64, u64)> = HashMap::new(); // (requests, tokens)
-        let mut daily_stats: HashMap<String, (u64, u64)> = HashMap::new();
-        let mut weekly_stats: HashMap<String, (u64, u64)> = HashMap::new();
-        let mut monthly_stats: HashMap<String, (u64, u64)> = HashMap::new();
-        let mut yearly_stats: HashMap<String, (u64, u64)> = HashMap::new();
-
-        let mut earliest_date = entries[0].timestamp;
-        let mut latest_date = entries[0].timestamp;
-
-        for entry in &entries {
-            // Update date range
-            if entry.timestamp < earliest_date {
-                earliest_date = entry.timestamp;
-            }
-            if entry.timestamp > latest_date {
-                latest_date = entry.timestamp;
-            }
-
-            // Calculate tokens
-            let input_tokens = entry.input_tokens.unwrap_or(0) as u64;
-            let output_tokens = entry.output_tokens.unwrap_or(0) as u64;
-            let total_entry_tokens = input_tokens + output_tokens;
-
-            total_input_tokens += input_tokens;
-            total_output_tokens += output_tokens;
-
-            // Model usage
-            let model_entry = model_stats.entry(entry.model.clone()).or_insert((0, 0));
-            model_entry.0 += 1; // requests
-            model_entry.1 += total_entry_tokens; // tokens
-
-            // Time-based usage
-            let date = entry.timestamp.date_naive();
-            let daily_key = date.format("%Y-%m-%d").to_string();
-            let daily_entry = daily_stats.entry(daily_key).or_insert((0, 0));
-            daily_entry.0 += 1;
-            daily_entry.1 += total_entry_tokens;
-
-            // Weekly usage (ISO week)
-            let year = entry.timestamp.year();
-            let week = entry.timestamp.iso_week().week();
-            let weekly_key = format!("{}-W{:02}", year, week);
-            let weekly_entry = weekly_stats.entry(weekly_key).or_insert((0, 0));
-            weekly_entry.0 += 1;
-            weekly_entry.1 += total_entry_tokens;
-
-            // Monthly usage
-            let monthly_key = date.format("%Y-%m").to_string();
-            let monthly_entry = monthly_stats.entry(monthly_key).or_insert((0, 0));
-            monthly_entry.0 += 1;
-            monthly_entry.1 += total_entry_tokens;
-
-            // Yearly usage
-            let yearly_key = year.to_string();
-            let yearly_entry = yearly_stats.entry(yearly_key).or_insert((0, 0));
-            yearly_entry.0 += 1;
-            yearly_entry.1 += total_entry_tokens;
-        }
+        let since = days_back.map(|days| Utc::now() - Duration::days(days as i64));
+        self.get_usage_stats_between(since, None)
+    }
 
-        // Convert to sorted vectors
-        let mut model_usage: Vec<(String, u64, u64)> = model_stats
+    /// Usage statistics for chat logs within an optional `[since, until]` range
+    pub fn get_usage_stats_between(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<UsageStats> {
+        let entries: Vec<ChatEntry> = self
+            .db
+            .get_all_logs()?
             .into_iter()
-            .map(|(model, (requests, tokens))| (model, requests, tokens))
+            .filter(|entry| in_range(entry.timestamp, since, until))
             .collect();
-        model_usage.sort_by_key(|b| std::cmp::Reverse(b.2)); // Sort by tokens descending
+        Ok(compute_usage_stats(&entries))
+    }
 
-        let mut daily_usage: Vec<(String, u64, u64)> = daily_stats
+    /// Aggregate request timings recorded with `--timings`
+    pub fn get_latency_stats(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<LatencyStats> {
+        let entries: Vec<RequestTimingEntry> = self
+            .db
+            .get_request_timings(since)?
             .into_iter()
-            .map(|(date, (requests, tokens))| (date, requests, tokens))
+            .filter(|entry| in_range(entry.timestamp, since, until))
             .collect();
-        daily_usage.sort_by(|a, b| a.0.cmp(&b.0)); // Sort by date ascending
+        Ok(compute_latency_stats(&entries))
+    }
+}
 
-        let mut weekly_usage: Vec<(String, u64, u64)> = weekly_stats
-            .into_iter()
-            .map(|(week, (requests, tokens))| (week, requests, tokens))
-            .collect();
-        weekly_usage.sort_by(|a, b| a.0.cmp(&b.0));
+fn in_range(
+    timestamp: DateTime<Utc>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> bool {
+    since.is_none_or(|s| timestamp >= s) && until.is_none_or(|u| timestamp <= u)
+}
 
-        let mut monthly_usage: Vec<(String, u64, u64)> = monthly_stats
-            .into_iter()
-            .map(|(month, (requests, tokens))| (month, requests, tokens))
-            .collect();
-        monthly_usage.sort_by(|a, b| a.0.cmp(&b.0));
+pub fn compute_usage_stats(entries: &[ChatEntry]) -> UsageStats {
+    if entries.is_empty() {
+        return UsageStats::default();
+    }
 
-        let mut yearly_usage: Vec<(String, u64, u64)> = yearly_stats
-            .into_iter()
-            .map(|(year, (requests, tokens))| (year, requests, tokens))
-            .collect();
-        yearly_usage.sort_by(|a, b| a.0.cmp(&b.0));
-
-        Ok(UsageStats {
-            total_tokens: total_input_tokens + total_output_tokens,
-            total_requests: entries.len() as u64,
-            input_tokens: total_input_tokens,
-            output_tokens: total_output_tokens,
-            model_usage,
-            daily_usage,
-            weekly_usage,
-            monthly_usage,
-            yearly_usage,
-            date_range: Some((earliest_date, latest_date)),
+    let mut total_input_tokens = 0u64;
+    let mut total_output_tokens = 0u64;
+    let mut model_stats: HashMap<String, (u64, u64)> = HashMap::new(); // (requests, tokens)
+    let mut daily_stats: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut weekly_stats: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut monthly_stats: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut yearly_stats: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut provider_stats: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut daily_provider_stats: HashMap<String, HashMap<String, (u64, u64)>> = HashMap::new();
+    let mut activity_heatmap = [[(0u64, 0u64); 24]; 7];
+
+    let mut earliest_date = entries[0].timestamp;
+    let mut latest_date = entries[0].timestamp;
+
+    for entry in entries {
+        // Update date range
+        if entry.timestamp < earliest_date {
+            earliest_date = entry.timestamp;
+        }
+        if entry.timestamp > latest_date {
+            latest_date = entry.timestamp;
+        }
+
+        // Calculate tokens
+        let input_tokens = entry.input_tokens.unwrap_or(0) as u64;
+        let output_tokens = entry.output_tokens.unwrap_or(0) as u64;
+        let total_entry_tokens = input_tokens + output_tokens;
+
+        total_input_tokens += input_tokens;
+        total_output_tokens += output_tokens;
+
+        // Model usage
+        let model_entry = model_stats.entry(entry.model.clone()).or_insert((0, 0));
+        model_entry.0 += 1; // requests
+        model_entry.1 += total_entry_tokens; // tokens
+
+        // Time-based usage
+        let date = entry.timestamp.date_naive();
+        let daily_key = date.format("%Y-%m-%d").to_string();
+        let daily_entry = daily_stats.entry(daily_key).or_insert((0, 0));
+        daily_entry.0 += 1;
+        daily_entry.1 += total_entry_tokens;
+
+        // Weekly usage (ISO week)
+        let year = entry.timestamp.year();
+        let week = entry.timestamp.iso_week().week();
+        let weekly_key = format!("{}-W{:02}", year, week);
+        let weekly_entry = weekly_stats.entry(weekly_key).or_insert((0, 0));
+        weekly_entry.0 += 1;
+        weekly_entry.1 += total_entry_tokens;
+
+        // Monthly usage
+        let monthly_key = date.format("%Y-%m").to_string();
+        let monthly_entry = monthly_stats.entry(monthly_key).or_insert((0, 0));
+        monthly_entry.0 += 1;
+        monthly_entry.1 += total_entry_tokens;
+
+        // Yearly usage
+        let yearly_key = year.to_string();
+        let yearly_entry = yearly_stats.entry(yearly_key).or_insert((0, 0));
+        yearly_entry.0 += 1;
+        yearly_entry.1 += total_entry_tokens;
+
+        // Provider usage, overall and per day for stacked charts
+        let provider = entry
+            .provider
+            .clone()
+            .unwrap_or_else(|| UNKNOWN_PROVIDER.to_string());
+        let provider_entry = provider_stats.entry(provider.clone()).or_insert((0, 0));
+        provider_entry.0 += 1;
+        provider_entry.1 += total_entry_tokens;
+        let daily_provider_entry = daily_provider_stats
+            .entry(date.format("%Y-%m-%d").to_string())
+            .or_default()
+            .entry(provider)
+            .or_insert((0, 0));
+        daily_provider_entry.0 += 1;
+        daily_provider_entry.1 += total_entry_tokens;
+
+        // Activity heatmap in local time, since that's when the user was actually working
+        let local = entry.timestamp.with_timezone(&Local);
+        let cell = &mut activity_heatmap[local.weekday().num_days_from_monday() as usize]
+            [local.hour() as usize];
+        cell.0 += 1;
+        cell.1 += total_entry_tokens;
+    }
+
+    // Convert to sorted vectors
+    let mut model_usage: Vec<(String, u64, u64)> = model_stats
+        .into_iter()
+        .map(|(model, (requests, tokens))| (model, requests, tokens))
+        .collect();
+    model_usage.sort_by_key(|b| std::cmp::Reverse(b.2)); // Sort by tokens descending
+
+    let mut daily_usage: Vec<(String, u64, u64)> = daily_stats
+        .into_iter()
+        .map(|(date, (requests, tokens))| (date, requests, tokens))
+        .collect();
+    daily_usage.sort_by(|a, b| a.0.cmp(&b.0)); // Sort by date ascending
+
+    let mut weekly_usage: Vec<(String, u64, u64)> = weekly_stats
+        .into_iter()
+        .map(|(week, (requests, tokens))| (week, requests, tokens))
+        .collect();
+    weekly_usage.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut monthly_usage: Vec<(String, u64, u64)> = monthly_stats
+        .into_iter()
+        .map(|(month, (requests, tokens))| (month, requests, tokens))
+        .collect();
+    monthly_usage.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut yearly_usage: Vec<(String, u64, u64)> = yearly_stats
+        .into_iter()
+        .map(|(year, (requests, tokens))| (year, requests, tokens))
+        .collect();
+    yearly_usage.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut provider_usage: Vec<(String, u64, u64)> = provider_stats
+        .into_iter()
+        .map(|(provider, (requests, tokens))| (provider, requests, tokens))
+        .collect();
+    provider_usage.sort_by_key(|b| std::cmp::Reverse(b.2));
+
+    let mut daily_provider_usage: Vec<(String, Vec<ProviderUsage>)> = daily_provider_stats
+        .into_iter()
+        .map(|(date, providers)| {
+            let mut providers: Vec<ProviderUsage> = providers
+                .into_iter()
+                .map(|(provider, (requests, tokens))| (provider, requests, tokens))
+                .collect();
+            providers.sort_by(|a, b| a.0.cmp(&b.0));
+            (date, providers)
         })
+        .collect();
+    daily_provider_usage.sort_by(|a, b| a.0.cmp(&b.0));
+
+    UsageStats {
+        total_tokens: total_input_tokens + total_output_tokens,
+        total_requests: entries.len() as u64,
+        input_tokens: total_input_tokens,
+        output_tokens: total_output_tokens,
+        model_usage,
+        daily_usage,
+        weekly_usage,
+        monthly_usage,
+        yearly_usage,
+        provider_usage,
+        daily_provider_usage,
+        activity_heatmap,
+        date_range: Some((earliest_date, latest_date)),
     }
+}
 
-    fn get_entries_since(&self, cutoff_date: DateTime<Utc>) -> Result<Vec<ChatEntry>> {
-        // This would need a custom query in the database
-        // For now, we'll filter after getting all entries
-        let all_entries = self.db.get_all_logs()?;
-        Ok(all_entries
-            .into_iter()
-            .filter(|entry| entry.timestamp >= cutoff_date)
-            .collect())
+/// Collapse the activity heatmap into per-hour totals across all weekdays
+pub fn hourly_usage(heatmap: &[[(u64, u64); 24]; 7]) -> Vec<(String, u64, u64)> {
+    (0..24)
+        .map(|hour| {
+            let (requests, tokens) = heatmap.iter().fold((0, 0), |acc, day| {
+                (acc.0 + day[hour].0, acc.1 + day[hour].1)
+            });
+            (format!("{:02}:00", hour), requests, tokens)
+        })
+        .collect()
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+fn entry_tokens_per_sec(entry: &RequestTimingEntry) -> Option<f64> {
+    let tokens = entry.output_tokens.filter(|t| *t > 0)? as f64;
+    let start = entry.first_token_ms.or(entry.ttfb_ms).unwrap_or(0);
+    let generation_ms = entry.total_ms - start;
+    if generation_ms <= 0 {
+        return None;
+    }
+    Some(tokens / (generation_ms as f64 / 1000.0))
+}
+
+pub fn compute_latency_stats(entries: &[RequestTimingEntry]) -> LatencyStats {
+    #[derive(Default)]
+    struct Acc {
+        ttfb: Vec<f64>,
+        first_token: Vec<f64>,
+        total: Vec<f64>,
+        tokens_per_sec: Vec<f64>,
+        retries: u64,
+    }
+
+    let mut by_model: HashMap<String, Acc> = HashMap::new();
+    let mut daily: HashMap<String, Vec<f64>> = HashMap::new();
+
+    for entry in entries {
+        let acc = by_model
+            .entry(format!("{}:{}", entry.provider, entry.model))
+            .or_default();
+        if let Some(ttfb) = entry.ttfb_ms {
+            acc.ttfb.push(ttfb as f64);
+        }
+        if let Some(first_token) = entry.first_token_ms {
+            acc.first_token.push(first_token as f64);
+        }
+        acc.total.push(entry.total_ms as f64);
+        if let Some(tps) = entry_tokens_per_sec(entry) {
+            acc.tokens_per_sec.push(tps);
+        }
+        acc.retries += entry.retries.max(0) as u64;
+
+        daily
+            .entry(entry.timestamp.date_naive().format("%Y-%m-%d").to_string())
+            .or_default()
+            .push(entry.total_ms as f64);
+    }
+
+    let mut by_model: Vec<LatencySummary> = by_model
+        .into_iter()
+        .map(|(label, acc)| LatencySummary {
+            label,
+            requests: acc.total.len() as u64,
+            avg_ttfb_ms: average(&acc.ttfb),
+            avg_first_token_ms: average(&acc.first_token),
+            avg_total_ms: average(&acc.total).unwrap_or(0.0),
+            avg_tokens_per_sec: average(&acc.tokens_per_sec),
+            retries: acc.retries,
+        })
+        .collect();
+    by_model.sort_by(|a, b| a.avg_total_ms.total_cmp(&b.avg_total_ms));
+
+    let mut daily_latency: Vec<(String, f64)> = daily
+        .into_iter()
+        .map(|(date, totals)| (date, average(&totals).unwrap_or(0.0)))
+        .collect();
+    daily_latency.sort_by(|a, b| a.0.cmp(&b.0));
+
+    LatencyStats {
+        total_requests: entries.len() as u64,
+        by_model,
+        daily_latency,
     }
 }
 
@@ -189,7 +385,7 @@ impl BarChart {
         max_items: usize,
     ) {
         if data.is_empty() {
-            println!("{} No data available", "ℹ️".blue());
+            println!("{} No data available", sym("ℹ️").blue());
             return;
         }
 
@@ -226,7 +422,7 @@ impl BarChart {
                 0
             };
 
-            let bar = "█".repeat(bar_width);
+            let bar = sym("█").repeat(bar_width);
             let formatted_value = if value_type == "tokens" {
                 Self::format_tokens(*tokens)
             } else {
@@ -234,8 +430,9 @@ impl BarChart {
             };
 
             println!(
-                "  {:width$} │{:bar_width$} {} ({})",
+                "  {:width$} {}{:bar_width$} {} ({})",
                 label.bold(),
+                sym("│"),
                 bar.green(),
                 formatted_value.yellow(),
                 if value_type == "tokens" {
@@ -257,7 +454,7 @@ impl BarChart {
         max_items: usize,
     ) {
         if data.is_empty() {
-            println!("{} No data available", "ℹ️".blue());
+            println!("{} No data available", sym("ℹ️").blue());
             return;
         }
 
@@ -294,7 +491,7 @@ impl BarChart {
                 0
             };
 
-            let bar = "▓".repeat(bar_width);
+            let bar = sym("▓").repeat(bar_width);
             let formatted_value = if value_type == "tokens" {
                 Self::format_tokens(*tokens)
             } else {
@@ -302,8 +499,9 @@ impl BarChart {
             };
 
             println!(
-                "  {:width$} │{:bar_width$} {} ({})",
+                "  {:width$} {}{:bar_width$} {} ({})",
                 label.bold(),
+                sym("│"),
                 bar.cyan(),
                 formatted_value.yellow(),
                 if value_type == "tokens" {
@@ -317,6 +515,222 @@ impl BarChart {
         }
     }
 
+    /// Render a bar chart of millisecond values (e.g. average latency)
+    pub fn render_durations(
+        title: &str,
+        data: &[(String, f64)],
+        max_width: usize,
+        max_items: usize,
+    ) {
+        if data.is_empty() {
+            println!("{} No data available", sym("ℹ️").blue());
+            return;
+        }
+
+        println!("\n{}", title.bold().blue());
+
+        let display_data: Vec<_> = data.iter().rev().take(max_items).rev().collect();
+        let max_value = display_data
+            .iter()
+            .map(|(_, ms)| *ms)
+            .fold(0.0_f64, f64::max);
+
+        let max_label_width = display_data
+            .iter()
+            .map(|(label, _)| label.len())
+            .max()
+            .unwrap_or(10);
+
+        for (label, ms) in display_data {
+            let bar_width = if max_value > 0.0 {
+                ((ms / max_value) * max_width as f64) as usize
+            } else {
+                0
+            };
+
+            println!(
+                "  {:width$} {}{:bar_width$} {}",
+                label.bold(),
+                sym("│"),
+                sym("▓").repeat(bar_width).cyan(),
+                Self::format_ms(*ms).yellow(),
+                width = max_label_width,
+                bar_width = max_width
+            );
+        }
+    }
+
+    /// Render per-period bars split into one colored segment per provider
+    pub fn render_stacked(
+        title: &str,
+        data: &[(String, Vec<ProviderUsage>)],
+        value_type: &str,
+        max_width: usize,
+        max_items: usize,
+    ) {
+        if data.is_empty() {
+            println!("{} No data available", sym("ℹ️").blue());
+            return;
+        }
+
+        println!("\n{}", title.bold().blue());
+
+        let display_data: Vec<_> = data.iter().rev().take(max_items).rev().collect();
+        let value_of = |(_, requests, tokens): &ProviderUsage| {
+            if value_type == "tokens" {
+                *tokens
+            } else {
+                *requests
+            }
+        };
+
+        // Stable color assignment per provider, ordered by name
+        let mut providers: Vec<&str> = display_data
+            .iter()
+            .flat_map(|(_, segments)| segments.iter().map(|(p, _, _)| p.as_str()))
+            .collect();
+        providers.sort_unstable();
+        providers.dedup();
+        let color_of = |provider: &str| {
+            let idx = providers.iter().position(|p| *p == provider).unwrap_or(0);
+            STACK_COLORS[idx % STACK_COLORS.len()]
+        };
+
+        let max_value = display_data
+            .iter()
+            .map(|(_, segments)| segments.iter().map(value_of).sum::<u64>())
+            .max()
+            .unwrap_or(1);
+
+        let max_label_width = display_data
+            .iter()
+            .map(|(label, _)| label.len())
+            .max()
+            .unwrap_or(10);
+
+        for (label, segments) in &display_data {
+            let mut bar = String::new();
+            let mut used = 0;
+            for segment in segments {
+                let width = if max_value > 0 {
+                    ((value_of(segment) as f64 / max_value as f64) * max_width as f64).round()
+                        as usize
+                } else {
+                    0
+                };
+                bar.push_str(
+                    &sym("█")
+                        .repeat(width)
+                        .color(color_of(&segment.0))
+                        .to_string(),
+                );
+                used += width;
+            }
+
+            let total = segments.iter().map(value_of).sum::<u64>();
+            let formatted_value = if value_type == "tokens" {
+                Self::format_tokens(total)
+            } else {
+                total.to_string()
+            };
+
+            println!(
+                "  {:width$} {}{}{} {}",
+                label.bold(),
+                sym("│"),
+                bar,
+                " ".repeat(max_width.saturating_sub(used)),
+                formatted_value.yellow(),
+                width = max_label_width
+            );
+        }
+
+        let legend: Vec<String> = providers
+            .iter()
+            .map(|p| format!("{} {}", sym("█").color(color_of(p)), p))
+            .collect();
+        println!("  {}", legend.join("  "));
+    }
+
+    /// Render a weekday × hour-of-day heatmap using shade characters
+    pub fn render_heatmap(title: &str, heatmap: &[[(u64, u64); 24]; 7], value_type: &str) {
+        let value_of = |cell: &(u64, u64)| {
+            if value_type == "tokens" {
+                cell.1
+            } else {
+                cell.0
+            }
+        };
+        let max_value = heatmap
+            .iter()
+            .flat_map(|day| day.iter().map(value_of))
+            .max()
+            .unwrap_or(0);
+
+        if max_value == 0 {
+            println!("{} No data available", sym("ℹ️").blue());
+            return;
+        }
+
+        println!("\n{}", title.bold().blue());
+
+        // Hour labels every three hours, each cell is two characters wide
+        let mut header = String::new();
+        for hour in (0..24).step_by(3) {
+            header.push_str(&format!("{:<6}", format!("{:02}", hour)));
+        }
+        println!("      {}", header.dimmed());
+
+        for (day, row) in WEEKDAYS.iter().zip(heatmap.iter()) {
+            let cells: String = row
+                .iter()
+                .map(|cell| {
+                    let shade = sym(Self::heat_shade(value_of(cell), max_value));
+                    format!("{}{}", shade, shade)
+                })
+                .collect();
+            println!("  {} {}", day.bold(), cells.green());
+        }
+
+        let scale: Vec<String> = HEAT_SHADES
+            .iter()
+            .map(|s| format!("{}{}", sym(s), sym(s)))
+            .collect();
+        println!(
+            "      {} {} {} {}",
+            "less".dimmed(),
+            format!("[{}]", scale.join("")).green(),
+            "more".dimmed(),
+            format!(
+                "(max {} per hour)",
+                if value_type == "tokens" {
+                    Self::format_tokens(max_value)
+                } else {
+                    format!("{} req", max_value)
+                }
+            )
+            .dimmed()
+        );
+    }
+
+    fn heat_shade(value: u64, max_value: u64) -> &'static str {
+        if value == 0 || max_value == 0 {
+            return HEAT_SHADES[0];
+        }
+        // Any activity gets at least the lightest visible shade
+        let levels = HEAT_SHADES.len() - 1;
+        let level = ((value as f64 / max_value as f64) * levels as f64).ceil() as usize;
+        HEAT_SHADES[level.clamp(1, levels)]
+    }
+
+    fn format_ms(ms: f64) -> String {
+        if ms >= 1000.0 {
+            format!("{:.2}s", ms / 1000.0)
+        } else {
+            format!("{:.0}ms", ms)
+        }
+    }
+
     fn format_tokens(tokens: u64) -> String {
         if tokens >= 1_000_000 {
             format!("{:.1}M", tokens as f64 / 1_000_000.0)
@@ -328,8 +742,64 @@ impl BarChart {
     }
 }
 
+pub fn display_latency_report(stats: &LatencyStats, max_items: usize) {
+    println!("\n{}", sym("⏱️  Provider Latency").bold().blue());
+    println!(
+        "{} {}",
+        "Timed Requests:".bold(),
+        stats.total_requests.to_string().green()
+    );
+
+    let fmt = |v: Option<f64>| {
+        v.map(BarChart::format_ms)
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    for summary in stats.by_model.iter().take(max_items) {
+        println!("\n  {} ({} req)", summary.label.bold(), summary.requests);
+        println!(
+            "    {} {}  {} {}  {} {}",
+            "TTFB:".dimmed(),
+            fmt(summary.avg_ttfb_ms),
+            "First token:".dimmed(),
+            fmt(summary.avg_first_token_ms),
+            "Total:".dimmed(),
+            BarChart::format_ms(summary.avg_total_ms).green()
+        );
+        println!(
+            "    {} {}  {} {}",
+            "Tokens/sec:".dimmed(),
+            summary
+                .avg_tokens_per_sec
+                .map(|t| format!("{:.1}", t))
+                .unwrap_or_else(|| "-".to_string())
+                .yellow(),
+            "Retries:".dimmed(),
+            summary.retries
+        );
+    }
+
+    let by_model: Vec<(String, f64)> = stats
+        .by_model
+        .iter()
+        .map(|s| (s.label.clone(), s.avg_total_ms))
+        .collect();
+    BarChart::render_durations(
+        sym("🏁 Average Total Latency by Model"),
+        &by_model,
+        40,
+        max_items,
+    );
+    BarChart::render_durations(
+        sym("📅 Daily Average Latency"),
+        &stats.daily_latency,
+        40,
+        max_items.max(14),
+    );
+}
+
 pub fn display_usage_overview(stats: &UsageStats) {
-    println!("\n{}", "📊 Usage Overview".bold().blue());
+    println!("\n{}", sym("📊 Usage Overview").bold().blue());
     println!();
 
     // Basic stats
@@ -380,7 +850,7 @@ pub fn display_usage_overview(stats: &UsageStats) {
             .checked_div(stats.total_requests)
             .unwrap_or(0);
         println!();
-        println!("{}", "📈 Averages per Request".bold().blue());
+        println!("{}", sym("📈 Averages per Request").bold().blue());
         println!(
             "{} {}",
             "Total Tokens:".bold(),
@@ -425,6 +895,9 @@ mod tests {
             weekly_usage: Vec::new(),
             monthly_usage: Vec::new(),
             yearly_usage: Vec::new(),
+            provider_usage: Vec::new(),
+            daily_provider_usage: Vec::new(),
+            activity_heatmap: [[(0, 0); 24]; 7],
             date_range: None,
         };
 
@@ -440,4 +913,85 @@ mod tests {
         assert_eq!(BarChart::format_tokens(1500), "1.5k");
         assert_eq!(BarChart::format_tokens(1_500_000), "1.5M");
     }
+
+    #[test]
+    fn test_compute_usage_stats_providers_and_heatmap() {
+        let ts = Utc::now();
+        let entry = |provider: Option<&str>, tokens: i32| ChatEntry {
+            chat_id: "c".to_string(),
+            model: "m".to_string(),
+            question: "q".to_string(),
+            response: "r".to_string(),
+            timestamp: ts,
+            input_tokens: Some(tokens),
+            output_tokens: Some(0),
+            provider: provider.map(|p| p.to_string()),
+        };
+
+        let stats = compute_usage_stats(&[
+            entry(Some("openai"), 100),
+            entry(Some("openai"), 50),
+            entry(Some("claude"), 10),
+            entry(None, 5),
+        ]);
+
+        assert_eq!(
+            stats.provider_usage,
+            vec![
+                ("openai".to_string(), 2, 150),
+                ("claude".to_string(), 1, 10),
+                ("unknown".to_string(), 1, 5),
+            ]
+        );
+        assert_eq!(stats.daily_provider_usage.len(), 1);
+        assert_eq!(stats.daily_provider_usage[0].1.len(), 3);
+
+        let local = ts.with_timezone(&Local);
+        let cell = stats.activity_heatmap[local.weekday().num_days_from_monday() as usize]
+            [local.hour() as usize];
+        assert_eq!(cell, (4, 165));
+
+        let hourly = hourly_usage(&stats.activity_heatmap);
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[local.hour() as usize].1, 4);
+    }
+
+    #[test]
+    fn test_heat_shade_levels() {
+        assert_eq!(BarChart::heat_shade(0, 10), "·");
+        assert_eq!(BarChart::heat_shade(1, 10), "░");
+        assert_eq!(BarChart::heat_shade(10, 10), "█");
+    }
+
+    #[test]
+    fn test_compute_latency_stats() {
+        let now = Utc::now();
+        let entry =
+            |provider: &str, total_ms: i64, output_tokens: Option<i32>| RequestTimingEntry {
+                provider: provider.to_string(),
+                model: "m".to_string(),
+                timestamp: now,
+                dns_ms: None,
+                ttfb_ms: Some(100),
+                first_token_ms: None,
+                total_ms,
+                output_tokens,
+                retries: 0,
+            };
+
+        let stats = compute_latency_stats(&[
+            entry("fast", 600, Some(50)),
+            entry("fast", 1100, Some(100)),
+            entry("slow", 3000, None),
+        ]);
+
+        assert_eq!(stats.total_requests, 3);
+        assert_eq!(stats.by_model.len(), 2);
+        assert_eq!(stats.by_model[0].label, "fast:m");
+        assert_eq!(stats.by_model[0].avg_total_ms, 850.0);
+        // 50 tokens / 0.5s and 100 tokens / 1.0s
+        assert_eq!(stats.by_model[0].avg_tokens_per_sec, Some(100.0));
+        assert_eq!(stats.by_model[1].avg_tokens_per_sec, None);
+        assert_eq!(stats.daily_latency.len(), 1);
+    }
 }
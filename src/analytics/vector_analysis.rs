@@ -0,0 +1,214 @@
+//! Analysis of stored embeddings: k-means clustering and near-duplicate detection
+//!
+//! Both work on cosine similarity, like `lc similar`: vectors are normalized first, so
+//! clustering is spherical k-means and duplicates are pairs at or above a similarity
+//! threshold.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+/// Cluster assignment of every vector and the (normalized) centroid of every cluster
+#[derive(Debug, Clone)]
+pub struct Clustering {
+    pub assignments: Vec<usize>,
+    pub centroids: Vec<Vec<f64>>,
+}
+
+impl Clustering {
+    /// Indices of the vectors in `cluster`, closest to its centroid first
+    pub fn members(&self, vectors: &[Vec<f64>], cluster: usize) -> Vec<usize> {
+        let mut members: Vec<(usize, f64)> = self
+            .assignments
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c == cluster)
+            .map(|(i, _)| (i, dot(&normalize(&vectors[i]), &self.centroids[cluster])))
+            .collect();
+        members.sort_by(|a, b| b.1.total_cmp(&a.1));
+        members.into_iter().map(|(i, _)| i).collect()
+    }
+}
+
+/// A vector kept by dedupe, and the later vectors that are near-duplicates of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub keep: usize,
+    /// (index, similarity to the kept vector)
+    pub duplicates: Vec<(usize, f64)>,
+}
+
+/// Spherical k-means with k-means++ initialization. `k` is capped at the number of
+/// vectors; the same seed always gives the same clustering.
+pub fn kmeans(vectors: &[Vec<f64>], k: usize, max_iterations: usize, seed: u64) -> Clustering {
+    let points: Vec<Vec<f64>> = vectors.iter().map(|v| normalize(v)).collect();
+    let k = k.min(points.len());
+    if k == 0 {
+        return Clustering {
+            assignments: vec![0; points.len()],
+            centroids: Vec::new(),
+        };
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut centroids = vec![points[rng.gen_range(0..points.len())].clone()];
+    while centroids.len() < k {
+        // Pick the next centroid with probability proportional to the squared distance
+        let distances: Vec<f64> = points
+            .iter()
+            .map(|p| {
+                let nearest = centroids.iter().map(|c| dot(p, c)).fold(f64::MIN, f64::max);
+                (1.0 - nearest).max(0.0).powi(2)
+            })
+            .collect();
+        let total: f64 = distances.iter().sum();
+        if total <= f64::EPSILON {
+            // Fewer distinct points than clusters
+            break;
+        }
+        let mut target = rng.gen_range(0.0..total);
+        let next = distances
+            .iter()
+            .position(|d| {
+                target -= d;
+                target <= 0.0
+            })
+            .unwrap_or(points.len() - 1);
+        centroids.push(points[next].clone());
+    }
+
+    let mut assignments = vec![usize::MAX; points.len()];
+    for _ in 0..max_iterations {
+        let next: Vec<usize> = points
+            .par_iter()
+            .map(|p| nearest_centroid(p, &centroids))
+            .collect();
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+
+        for (cluster, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = vec![0.0; centroid.len()];
+            let mut count = 0;
+            for (point, _) in points
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, &c)| c == cluster)
+            {
+                for (s, x) in sum.iter_mut().zip(point) {
+                    *s += x;
+                }
+                count += 1;
+            }
+            // An empty cluster keeps its previous centroid
+            if count > 0 {
+                *centroid = normalize(&sum);
+            }
+        }
+    }
+
+    Clustering {
+        assignments,
+        centroids,
+    }
+}
+
+/// Group vectors whose cosine similarity to an earlier vector is at least `threshold`.
+/// Earlier vectors are kept, so pass them oldest first to keep the originals.
+pub fn find_duplicates(vectors: &[Vec<f64>], threshold: f64) -> Vec<DuplicateGroup> {
+    let points: Vec<Vec<f64>> = vectors.iter().map(|v| normalize(v)).collect();
+    let mut removed = vec![false; points.len()];
+    let mut groups = Vec::new();
+
+    for keep in 0..points.len() {
+        if removed[keep] {
+            continue;
+        }
+        let duplicates: Vec<(usize, f64)> = (keep + 1..points.len())
+            .into_par_iter()
+            .filter(|&j| !removed[j])
+            .map(|j| (j, dot(&points[keep], &points[j])))
+            .filter(|&(_, similarity)| similarity >= threshold)
+            .collect();
+        if duplicates.is_empty() {
+            continue;
+        }
+        for &(j, _) in &duplicates {
+            removed[j] = true;
+        }
+        groups.push(DuplicateGroup { keep, duplicates });
+    }
+    groups
+}
+
+fn nearest_centroid(point: &[f64], centroids: &[Vec<f64>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(point, c)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(v: &[f64]) -> Vec<f64> {
+    let norm = dot(v, v).sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmeans_separates_groups() {
+        let vectors = vec![
+            vec![1.0, 0.1, 0.0],
+            vec![0.9, 0.0, 0.1],
+            vec![0.0, 1.0, 0.1],
+            vec![0.1, 0.9, 0.0],
+            vec![0.0, 0.1, 1.0],
+            vec![0.1, 0.0, 0.9],
+        ];
+        let clustering = kmeans(&vectors, 3, 50, 42);
+        assert_eq!(clustering.centroids.len(), 3);
+        for pair in [(0, 1), (2, 3), (4, 5)] {
+            assert_eq!(
+                clustering.assignments[pair.0],
+                clustering.assignments[pair.1]
+            );
+        }
+        assert_ne!(clustering.assignments[0], clustering.assignments[2]);
+        assert_ne!(clustering.assignments[2], clustering.assignments[4]);
+
+        let cluster = clustering.assignments[0];
+        assert_eq!(clustering.members(&vectors, cluster).len(), 2);
+
+        // k is capped at the number of vectors
+        assert_eq!(kmeans(&vectors[..2], 5, 10, 1).centroids.len(), 2);
+    }
+
+    #[test]
+    fn test_find_duplicates_keeps_first() {
+        let vectors = vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![2.0, 0.01],
+            vec![0.999, 0.0],
+        ];
+        let groups = find_duplicates(&vectors, 0.97);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].keep, 0);
+        let removed: Vec<usize> = groups[0].duplicates.iter().map(|(i, _)| *i).collect();
+        assert_eq!(removed, vec![2, 3]);
+        assert_eq!(find_duplicates(&vectors, 0.99999999).len(), 1);
+    }
+}
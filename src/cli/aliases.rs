@@ -2,6 +2,8 @@
 
 use crate::cli::AliasCommands;
 use crate::data::config::Config;
+use crate::models::deprecation::{warn_if_deprecated, ModelOrigin};
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 
@@ -13,6 +15,9 @@ pub async fn handle(command: AliasCommands) -> Result<()> {
             config.add_alias(name.clone(), target.clone())?;
             config.save()?;
             println!("Added alias '{}' -> '{}'", name, target);
+            if let Some((provider, model)) = target.split_once(':') {
+                warn_if_deprecated(provider, model, &ModelOrigin::Alias(name)).await;
+            }
             Ok(())
         }
         AliasCommands::Delete { name } => {
@@ -30,7 +35,7 @@ pub async fn handle(command: AliasCommands) -> Result<()> {
                 println!("No aliases configured.");
                 println!(
                     "\n{} Add one with: {}",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc aliases add <name> <target>".bold()
                 );
             } else {
@@ -1,5 +1,6 @@
 //! Audio processing commands (transcribe, TTS)
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 use std::io::{self, Write};
@@ -28,23 +29,11 @@ pub async fn handle_transcribe(
 
     let config = crate::config::Config::load()?;
 
-    // Default to whisper-1 model if not specified
-    let model_str = model.unwrap_or_else(|| "whisper-1".to_string());
     let format_str = format.unwrap_or_else(|| "text".to_string());
 
-    // Resolve provider and model
-    let (provider_name, model_name) = if let Some(p) = provider {
-        (p, model_str)
-    } else {
-        // Try to find a provider that has the whisper model
-        let provider_name = config
-            .providers
-            .iter()
-            .find(|(_, pc)| pc.models.iter().any(|m| m.contains("whisper")))
-            .map(|(name, _)| name.clone())
-            .unwrap_or_else(|| "openai".to_string());
-        (provider_name, model_str)
-    };
+    // Resolve provider and model, defaulting to the provider's transcription model
+    let provider_name = provider.unwrap_or_else(|| default_transcription_provider(&config));
+    let model_name = model.unwrap_or_else(|| transcription_model(&config, &provider_name));
 
     // Get provider config with authentication
     let provider_config = config.get_provider_with_auth(&provider_name)?;
@@ -75,22 +64,22 @@ pub async fn handle_transcribe(
 
     println!(
         "{} Transcribing {} audio file(s)",
-        "🎤".blue(),
+        sym("🎤").blue(),
         audio_files.len()
     );
-    println!("{} Model: {}", "🤖".blue(), model_name);
-    println!("{} Provider: {}", "🏭".blue(), provider_name);
+    println!("{} Model: {}", sym("🤖").blue(), model_name);
+    println!("{} Provider: {}", sym("🏭").blue(), provider_name);
     if let Some(ref lang) = language {
-        println!("{} Language: {}", "🌐".blue(), lang);
+        println!("{} Language: {}", sym("🌐").blue(), lang);
     }
-    println!("{} Format: {}", "📄".blue(), format_str);
+    println!("{} Format: {}", sym("📄").blue(), format_str);
 
     let mut all_transcriptions = Vec::new();
 
     for (i, audio_file) in audio_files.iter().enumerate() {
         println!(
             "\n{} Processing file {}/{}: {}",
-            "📁".blue(),
+            sym("📁").blue(),
             i + 1,
             audio_files.len(),
             audio_file
@@ -121,7 +110,7 @@ pub async fn handle_transcribe(
         match client.transcribe_audio(&transcription_request).await {
             Ok(response) => {
                 print!("\r{}\r", " ".repeat(20)); // Clear "Transcribing..."
-                println!("{} Transcription complete!", "✅".green());
+                println!("{} Transcription complete!", sym("✅").green());
 
                 // Display or save transcription
                 let transcription_text = response.text;
@@ -142,9 +131,9 @@ pub async fn handle_transcribe(
                 } else {
                     // Print to stdout
                     if audio_files.len() > 1 {
-                        println!("\n{} Transcription for {}:", "📝".blue(), audio_file);
+                        println!("\n{} Transcription for {}:", sym("📝").blue(), audio_file);
                     } else {
-                        println!("\n{} Transcription:", "📝".blue());
+                        println!("\n{} Transcription:", sym("📝").blue());
                     }
                     println!("{}", transcription_text);
 
@@ -153,7 +142,12 @@ pub async fn handle_transcribe(
             }
             Err(e) => {
                 print!("\r{}\r", " ".repeat(20)); // Clear "Transcribing..."
-                eprintln!("{} Failed to transcribe {}: {}", "❌".red(), audio_file, e);
+                eprintln!(
+                    "{} Failed to transcribe {}: {}",
+                    sym("❌").red(),
+                    audio_file,
+                    e
+                );
             }
         }
     }
@@ -161,7 +155,7 @@ pub async fn handle_transcribe(
     if let Some(output_file) = output {
         println!(
             "\n{} All transcriptions saved to: {}",
-            "💾".green(),
+            sym("💾").green(),
             output_file
         );
     }
@@ -169,6 +163,88 @@ pub async fn handle_transcribe(
     Ok(())
 }
 
+/// Model transcribing audio when none is given
+const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+/// Provider var naming the provider's transcription model
+const TRANSCRIPTION_MODEL_VAR: &str = "transcription_model";
+
+// Find a provider with a `transcription_model` var or a whisper model, falling back to openai
+fn default_transcription_provider(config: &crate::config::Config) -> String {
+    config
+        .providers
+        .iter()
+        .find(|(_, pc)| pc.vars.contains_key(TRANSCRIPTION_MODEL_VAR))
+        .or_else(|| {
+            config
+                .providers
+                .iter()
+                .find(|(_, pc)| pc.models.iter().any(|m| m.contains("whisper")))
+        })
+        .map(|(name, _)| name.clone())
+        .unwrap_or_else(|| "openai".to_string())
+}
+
+/// The transcription model of a provider: its `transcription_model` var, or whisper-1
+fn transcription_model(config: &crate::config::Config, provider_name: &str) -> String {
+    config
+        .providers
+        .get(provider_name)
+        .and_then(|pc| pc.vars.get(TRANSCRIPTION_MODEL_VAR))
+        .filter(|model| !model.trim().is_empty())
+        .cloned()
+        .unwrap_or_else(|| DEFAULT_TRANSCRIPTION_MODEL.to_string())
+}
+
+/// Transcribe audio attachments and format the transcripts for inclusion in a prompt
+pub async fn transcribe_for_prompt(audio_files: &[String]) -> Result<String> {
+    let config = crate::config::Config::load()?;
+    let provider_name = default_transcription_provider(&config);
+    let model = transcription_model(&config, &provider_name);
+
+    let mut config_mut = config.clone();
+    let client =
+        crate::core::chat::create_authenticated_client(&mut config_mut, &provider_name).await?;
+
+    if config_mut.get_cached_token(&provider_name) != config.get_cached_token(&provider_name) {
+        config_mut.save()?;
+    }
+
+    let mut result = String::new();
+    for audio_file in audio_files {
+        let audio_data = if audio_file.starts_with("http://") || audio_file.starts_with("https://")
+        {
+            crate::utils::audio::process_audio_url(audio_file)?
+        } else {
+            crate::utils::audio::process_audio_file(std::path::Path::new(audio_file))?
+        };
+
+        let transcription_request = crate::core::provider::AudioTranscriptionRequest {
+            file: audio_data,
+            model: model.clone(),
+            language: None,
+            prompt: None,
+            response_format: Some("text".to_string()),
+            temperature: None,
+        };
+
+        let response = client
+            .transcribe_audio(&transcription_request)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to transcribe '{}': {}", audio_file, e))?;
+
+        let filename = std::path::Path::new(audio_file)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(audio_file);
+        result.push_str(&format!(
+            "=== Audio Transcript: {} ===\n{}\n",
+            filename, response.text
+        ));
+    }
+
+    Ok(result)
+}
+
 /// Handle TTS (text-to-speech) command
 #[allow(clippy::too_many_arguments)]
 pub async fn handle_tts(
@@ -247,14 +323,14 @@ pub async fn handle_tts(
         text.clone()
     };
 
-    println!("{} Generating speech", "🔊".blue());
-    println!("{} Text: \"{}\"", "📝".blue(), display_text);
-    println!("{} Model: {}", "🤖".blue(), model_name);
-    println!("{} Provider: {}", "🏭".blue(), provider_name);
-    println!("{} Voice: {}", "🎭".blue(), voice_str);
-    println!("{} Format: {}", "🎵".blue(), format_str);
+    println!("{} Generating speech", sym("🔊").blue());
+    println!("{} Text: \"{}\"", sym("📝").blue(), display_text);
+    println!("{} Model: {}", sym("🤖").blue(), model_name);
+    println!("{} Provider: {}", sym("🏭").blue(), provider_name);
+    println!("{} Voice: {}", sym("🎭").blue(), voice_str);
+    println!("{} Format: {}", sym("🎵").blue(), format_str);
     if let Some(s) = speed {
-        println!("{} Speed: {}x", "⚡".blue(), s);
+        println!("{} Speed: {}x", sym("⚡").blue(), s);
     }
 
     print!("{} ", "Generating speech...".dimmed());
@@ -316,23 +392,23 @@ pub async fn handle_tts(
             // Save audio to file
             std::fs::write(&final_output, &final_audio_data)?;
 
-            println!("{} Speech generated successfully!", "✅".green());
-            println!("{} Saved to: {}", "💾".green(), final_output);
+            println!("{} Speech generated successfully!", sym("✅").green());
+            println!("{} Saved to: {}", sym("💾").green(), final_output);
 
             // Show conversion info if applicable
             if let Some(info) = conversion_info {
-                println!("{} {}", "🔄".blue(), info);
+                println!("{} {}", sym("🔄").blue(), info);
             }
 
             // Show file size
             let metadata = std::fs::metadata(&final_output)?;
             let size_kb = metadata.len() as f64 / 1024.0;
-            println!("{} File size: {:.2} KB", "📊".blue(), size_kb);
+            println!("{} File size: {:.2} KB", sym("📊").blue(), size_kb);
 
             // Show format info
             println!(
                 "{} Format: {} ({})",
-                "🎵".blue(),
+                sym("🎵").blue(),
                 final_extension.to_uppercase(),
                 if is_pcm_conversion_needed {
                     "24kHz, 16-bit, Mono"
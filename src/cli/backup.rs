@@ -0,0 +1,211 @@
+//! Database backup and integrity check commands
+
+use crate::backup::{BackupConfig, BackupInfo, BackupManager};
+use crate::cli::BackupCommands;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::io::{self, Write};
+
+/// Handle backup-related commands
+pub fn handle(command: BackupCommands) -> Result<()> {
+    match command {
+        BackupCommands::Create => {
+            let manager = BackupManager::new()?;
+            match manager.create_backup(&[])? {
+                Some(backup) => {
+                    println!(
+                        "{} Created backup {} ({})",
+                        sym("✓").green(),
+                        backup.id.bold(),
+                        format_size(backup.size)
+                    );
+                    for file in &backup.files {
+                        println!("  {} {}", sym("•"), file);
+                    }
+                }
+                None => println!("{} No databases to back up", sym("ℹ️").blue()),
+            }
+        }
+        BackupCommands::List => {
+            let manager = BackupManager::new()?;
+            let backups = manager.list_backups()?;
+            if backups.is_empty() {
+                println!("{} No backups found", sym("ℹ️").blue());
+                println!(
+                    "{}",
+                    "Create one with 'lc backup create'; they are also made automatically."
+                        .dimmed()
+                );
+                return Ok(());
+            }
+
+            println!("\n{}", "Backups (most recent first):".bold().blue());
+            for backup in &backups {
+                println!(
+                    "  {} {} {}",
+                    backup.id.bold(),
+                    format!("({})", format_size(backup.size)).dimmed(),
+                    backup.files.join(", ")
+                );
+            }
+        }
+        BackupCommands::Restore { id, database, yes } => {
+            let manager = BackupManager::new()?;
+            let backup = find_backup(&manager, id.as_deref())?;
+
+            let targets = match &database {
+                Some(name) => vec![name.clone()],
+                None => backup.files.clone(),
+            };
+            println!(
+                "{} Restoring from backup {}:",
+                sym("↩️").blue(),
+                backup.id.bold()
+            );
+            for target in &targets {
+                println!("  {} {}", sym("•"), target);
+            }
+
+            if !yes {
+                print!(
+                    "Current databases will be set aside as *.corrupt-<time>. Continue? (y/N): "
+                );
+                // Deliberately flush stdout to ensure prompt appears before user input
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+
+                if !input.trim().to_lowercase().starts_with('y') {
+                    println!("Restore cancelled.");
+                    return Ok(());
+                }
+            }
+
+            let restored = manager.restore(&backup, database.as_deref())?;
+            println!(
+                "{} Restored {} database(s)",
+                sym("✓").green(),
+                restored.len()
+            );
+        }
+        BackupCommands::Check => {
+            let manager = BackupManager::new()?;
+            let results = manager.check_all()?;
+            if results.is_empty() {
+                println!("{} No databases found", sym("ℹ️").blue());
+                return Ok(());
+            }
+
+            let mut failed = 0;
+            for result in &results {
+                if result.is_ok() {
+                    println!("  {} {}", sym("✓").green(), result.database);
+                } else {
+                    failed += 1;
+                    println!("  {} {}", sym("✗").red(), result.database);
+                    for problem in result.problems.iter().take(5) {
+                        println!("      {}", problem.dimmed());
+                    }
+                }
+            }
+
+            if failed > 0 {
+                println!(
+                    "\n{} Restore damaged databases with: {}",
+                    sym("💡").yellow(),
+                    "lc backup restore --database <name>".cyan()
+                );
+                anyhow::bail!("{} database(s) failed the integrity check", failed);
+            }
+            println!(
+                "\n{} All databases passed the integrity check",
+                sym("✓").green()
+            );
+        }
+        BackupCommands::Config {
+            interval_hours,
+            keep,
+            enable,
+            disable,
+            integrity_check,
+        } => {
+            let mut config = BackupConfig::load()?;
+            let changed = interval_hours.is_some()
+                || keep.is_some()
+                || enable
+                || disable
+                || integrity_check.is_some();
+
+            if let Some(hours) = interval_hours {
+                config.interval_hours = hours;
+            }
+            if let Some(keep) = keep {
+                if keep == 0 {
+                    anyhow::bail!("--keep must be at least 1");
+                }
+                config.keep = keep;
+            }
+            if enable {
+                config.enabled = true;
+            }
+            if disable {
+                config.enabled = false;
+            }
+            if let Some(check) = integrity_check {
+                config.integrity_check = check;
+            }
+
+            if changed {
+                config.save()?;
+                println!("{} Backup settings updated", sym("✓").green());
+            }
+
+            println!("\n{}", "Backup settings:".bold().blue());
+            println!(
+                "  {} {}",
+                "Automatic backups:".bold(),
+                if config.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            );
+            println!(
+                "  {} every {} hours",
+                "Interval:".bold(),
+                config.interval_hours
+            );
+            println!("  {} {} backups", "Keep:".bold(), config.keep);
+            println!(
+                "  {} {}",
+                "Startup integrity check:".bold(),
+                if config.integrity_check { "on" } else { "off" }
+            );
+        }
+    }
+    Ok(())
+}
+
+fn find_backup(manager: &BackupManager, id: Option<&str>) -> Result<BackupInfo> {
+    let backups = manager.list_backups()?;
+    match id {
+        Some(id) => backups
+            .into_iter()
+            .find(|b| b.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Backup '{}' not found", id)),
+        None => backups
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No backups found")),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
@@ -1,13 +1,14 @@
 //! Chat functionality commands
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 use std::io::{self, Write};
-use uuid::Uuid;
 
 use crate::config::Config;
 use crate::core::chat;
 use crate::database::Database;
+use crate::models::deprecation::{warn_if_deprecated, ModelOrigin};
 use crate::provider::{ContentPart, ImageUrl, Message, MessageContent};
 use crate::utils::{cli_utils::resolve_model_and_provider, input::MultiLineInput};
 
@@ -17,10 +18,11 @@ pub async fn handle(
     model: Option<String>,
     provider: Option<String>,
     cid: Option<String>,
+    name: Option<String>,
     tools: Option<String>,
     database: Option<String>,
     debug: bool,
-    has_images: bool,
+    images: Vec<String>,
     stream: bool,
 ) -> Result<()> {
     // Set debug mode if requested
@@ -29,18 +31,37 @@ pub async fn handle(
     }
 
     let config = Config::load()?;
+    let style = crate::core::style::active(&config)?;
     let db = Database::new()?;
 
     // Determine session ID
-    let session_id = cid.unwrap_or_else(|| {
-        let new_id = Uuid::new_v4().to_string();
-        db.set_current_session_id(&new_id).unwrap();
-        new_id
-    });
+    // A named session is resumed; a new name is given to the session that starts
+    let named = match (&cid, &name) {
+        (None, Some(name)) => db.find_session_by_name(name)?,
+        _ => None,
+    };
+    let resuming = named.is_some();
+    let session_id = match named.or(cid.map(|cid| db.resolve_chat_id(&cid)).transpose()?) {
+        Some(id) => id,
+        None => {
+            let new_id = crate::core::deterministic::new_id();
+            if let Err(e) = db.set_current_session_id(&new_id) {
+                crate::debug_log!("Not recording the new session: {}", e);
+            }
+            new_id
+        }
+    };
+    if let Some(name) = &name {
+        if !resuming {
+            db.set_session_name(&session_id, name)?;
+        }
+    }
 
     // Resolve provider and model
+    let origin = ModelOrigin::from_model_arg(&config, model.as_deref());
     let (provider_name, resolved_model) = resolve_model_and_provider(&config, provider, model)?;
     let _provider_config = config.get_provider(&provider_name)?;
+    warn_if_deprecated(&provider_name, &resolved_model, &origin).await;
 
     let mut config_mut = config.clone();
     let client = chat::create_authenticated_client(&mut config_mut, &provider_name).await?;
@@ -50,47 +71,55 @@ pub async fn handle(
         config_mut.save()?;
     }
 
-    // MCP tools support (placeholder for now)
-    let mcp_tools: Option<Vec<crate::provider::Tool>> = None;
-    let mcp_server_names: Vec<String> = Vec::new();
-    if tools.is_some() {
-        println!(
-            "{} MCP tools support is not yet fully implemented",
-            "⚠️".yellow()
-        );
-    }
+    // MCP tools, RAG database and streaming can be changed with slash commands
+    let mut chat_tools = match tools.as_deref() {
+        Some(tools) => ChatTools::fetch(split_servers(tools)).await?,
+        None => ChatTools::default(),
+    };
+    let mut rag_db = database;
+    let mut stream_override: Option<bool> = None;
 
     let mut current_model = resolved_model.clone();
 
-    // Process initial images if provided (placeholder for now)
+    // Process initial images if provided, after checking the model can accept them
     let mut processed_images: Vec<String> = Vec::new();
-    if has_images {
-        println!(
-            "{} Image support is not yet fully implemented",
-            "⚠️".yellow()
-        );
+    if !images.is_empty() {
+        chat::ensure_vision_support(&provider_name, &current_model, images.len()).await?;
+        processed_images = crate::image_utils::process_images(&images)?;
     }
 
-    println!("\n{} Interactive Chat Mode", "🚀".blue());
-    println!("{} Session ID: {}", "📝".blue(), session_id);
-    println!("{} Model: {}", "🤖".blue(), current_model);
+    println!("\n{} Interactive Chat Mode", sym("🚀").blue());
+    match &name {
+        Some(name) => println!(
+            "{} Session: {} ({}){}",
+            sym("📝").blue(),
+            name.bold(),
+            session_id,
+            if resuming { ", resumed" } else { "" }
+        ),
+        None => println!("{} Session ID: {}", sym("📝").blue(), session_id),
+    }
+    println!("{} Model: {}", sym("🤖").blue(), current_model);
     if !processed_images.is_empty() {
-        println!("{} Initial images: {}", "🖼️".blue(), processed_images.len());
+        println!(
+            "{} Initial images: {}",
+            sym("🖼️").blue(),
+            processed_images.len()
+        );
     }
-    if let Some(tools) = &mcp_tools {
-        if !mcp_server_names.is_empty() {
-            println!(
-                "{} Tools: {} (from MCP servers: {})",
-                "🔧".blue(),
-                tools.len(),
-                mcp_server_names.join(", ")
-            );
-        }
+    if !chat_tools.is_empty() {
+        println!("{} {}", sym("🔧").blue(), chat_tools.describe());
+    }
+    if let Some(db_name) = &rag_db {
+        println!("{} RAG: {}", sym("📚").blue(), db_name);
     }
-    println!("{} Type /help for commands, /exit to quit", "💡".yellow());
+    println!(
+        "{} Type /help for commands, /exit to quit",
+        sym("💡").yellow()
+    );
     println!(
         "{} Use Shift+Enter or Ctrl+J for multi-line input, Enter to send\n",
-        "💡".yellow()
+        sym("💡").yellow()
     );
 
     // Create multi-line input handler
@@ -110,7 +139,7 @@ pub async fn handle(
 
                 // If we read 0 bytes, it means EOF (e.g., when input is piped)
                 if bytes_read == 0 {
-                    println!("Goodbye! 👋");
+                    println!("Goodbye! {}", sym("👋"));
                     break;
                 }
 
@@ -126,14 +155,25 @@ pub async fn handle(
 
         // Handle chat commands
         if input.starts_with('/') {
+            match parse_toggle(input) {
+                Some(Ok(toggle)) => {
+                    apply_toggle(toggle, &mut chat_tools, &mut rag_db, &mut stream_override).await;
+                    continue;
+                }
+                Some(Err(usage)) => {
+                    println!("{} Usage: {}", sym("✗").red(), usage);
+                    continue;
+                }
+                None => {}
+            }
             match input {
                 "/exit" | "/quit" => {
-                    println!("Goodbye! 👋");
+                    println!("Goodbye! {}", sym("👋"));
                     break;
                 }
                 "/clear" => {
                     db.clear_session(&session_id)?;
-                    println!("{} Session cleared", "✓".green());
+                    println!("{} Session cleared", sym("✓").green());
                     continue;
                 }
                 "/help" => {
@@ -142,6 +182,19 @@ pub async fn handle(
                     println!("  {}           - Clear current session", "/clear".green());
                     println!("  {}    - Change model", "/model <name>".green());
                     println!("  {} - Set system prompt", "/system <prompt>".green());
+                    println!(
+                        "  {} - Add or remove an MCP server's tools",
+                        "/tools add|remove <server>".green()
+                    );
+                    println!(
+                        "  {} - List the tools, re-fetch them",
+                        "/tools [refresh]".green()
+                    );
+                    println!(
+                        "  {} - Answer with a vector database, or stop",
+                        "/rag <db|off>".green()
+                    );
+                    println!("  {} - Turn streaming on or off", "/stream on|off".green());
                     println!("  {}            - Show this help", "/help".green());
                     println!("\n{}", "Input Controls:".bold().blue());
                     println!("  {}            - Send message", "Enter".yellow());
@@ -157,9 +210,9 @@ pub async fn handle(
                     let new_model = input.strip_prefix("/model ").unwrap().trim();
                     if !new_model.is_empty() {
                         current_model = new_model.to_string();
-                        println!("{} Model changed to: {}", "✓".green(), current_model);
+                        println!("{} Model changed to: {}", sym("✓").green(), current_model);
                     } else {
-                        println!("{} Please specify a model name", "✗".red());
+                        println!("{} Please specify a model name", sym("✗").red());
                     }
                     continue;
                 }
@@ -167,16 +220,16 @@ pub async fn handle(
                     let new_system = input.strip_prefix("/system ").unwrap().trim();
                     if !new_system.is_empty() {
                         // TODO: Store and use the system prompt in the config_mut
-                        println!("{} System prompt updated", "✓".green());
+                        println!("{} System prompt updated", sym("✓").green());
                     } else {
-                        println!("{} Please specify a system prompt", "✗".red());
+                        println!("{} Please specify a system prompt", sym("✗").red());
                     }
                     continue;
                 }
                 _ => {
                     println!(
                         "{} Unknown command. Type /help for available commands",
-                        "✗".red()
+                        sym("✗").red()
                     );
                     continue;
                 }
@@ -186,17 +239,40 @@ pub async fn handle(
         // Send chat message
         let history = db.get_chat_history(&session_id)?;
 
-        // RAG support (placeholder for now)
-        let enhanced_input = input.to_string();
-        if database.is_some() {
-            println!(
-                "{} Vector database RAG support is not yet fully implemented",
-                "⚠️".yellow()
-            );
-        }
+        // Chunks from the RAG database go before the message, matching images are attached
+        let enhanced_input = crate::readers::web::with_url_context(input.to_string()).await;
+        let mut images = processed_images.clone();
+        let enhanced_input = match &rag_db {
+            Some(db_name) => {
+                match crate::cli::embed::retrieve_rag_matches(db_name, &enhanced_input).await {
+                    Ok(context) => {
+                        let vision = !context.images.is_empty()
+                            && chat::model_may_support_vision(&provider_name, &current_model).await;
+                        let (prompt, retrieved) = crate::cli::prompts::with_rag_context(
+                            db_name,
+                            enhanced_input,
+                            context,
+                            vision,
+                        );
+                        images.extend(crate::image_utils::process_images(&retrieved)?);
+                        prompt
+                    }
+                    Err(e) => {
+                        println!(
+                            "{} Could not search '{}', sending the message without it: {}",
+                            sym("⚠️").yellow(),
+                            db_name,
+                            e
+                        );
+                        enhanced_input
+                    }
+                }
+            }
+            None => enhanced_input,
+        };
 
-        // Create messages with images if we have initial images
-        let messages = if !processed_images.is_empty() {
+        // Create messages with images if we have initial or retrieved images
+        let messages = if !images.is_empty() {
             // Build history messages first
             let mut msgs: Vec<Message> = history
                 .iter()
@@ -214,7 +290,7 @@ pub async fn handle(
             }];
 
             // Add each image as a content part
-            for image_url in &processed_images {
+            for image_url in &images {
                 content_parts.push(ContentPart::ImageUrl {
                     image_url: ImageUrl {
                         url: image_url.clone(),
@@ -237,18 +313,22 @@ pub async fn handle(
             Vec::new()
         };
 
-        // Add newline before "Thinking..." to ensure proper positioning after multi-line input
+        // Add newline before the waiting indicator to ensure proper positioning after multi-line input
         println!();
-        print!("{}", "Thinking...".dimmed());
-        io::stdout().flush()?;
-
-        let resolved_system_prompt = config
-            .system_prompt
-            .as_ref()
-            .map(|system_prompt| config.resolve_template_or_prompt(system_prompt));
+        let spinner = crate::utils::spinner::start(&current_model);
+
+        let resolved_system_prompt = crate::core::style::compose(
+            config
+                .system_prompt
+                .as_ref()
+                .map(|system_prompt| config.resolve_template_or_prompt(system_prompt)),
+            style.as_ref(),
+        );
 
-        // Determine if streaming should be used (default to true for interactive chat)
-        let mut use_streaming = stream || config.stream.unwrap_or(true);
+        // Determine if streaming should be used (default to true for interactive chat,
+        // `/stream on|off` overrides it). Tool calls are answered without streaming.
+        let mut use_streaming = stream_override.unwrap_or(stream || config.stream.unwrap_or(true))
+            && chat_tools.is_empty();
 
         // Disable streaming for certain providers
         if use_streaming {
@@ -263,18 +343,19 @@ pub async fn handle(
             }
         }
 
-        // Handle tool execution, streaming, or regular chat
-        if mcp_tools.is_some() && !mcp_server_names.is_empty() {
-            // Tool execution (not yet fully implemented)
-            print!("\r{}\r", " ".repeat(12)); // Clear "Thinking..."
-            println!(
-                "{} Tool execution is not yet fully implemented",
-                "⚠️".yellow()
-            );
-            continue;
-        } else if use_streaming {
+        // Post-processing plugins and --schema checks need the whole response
+        if use_streaming
+            && (crate::services::wasm_plugins::has_post_processors()
+                || crate::core::structured_output::requested().is_some())
+        {
+            use_streaming = false;
+        }
+
+        // Handle streaming, or regular chat with tool execution
+        if use_streaming {
             // Use streaming chat
-            print!("\r{}\r{} ", " ".repeat(12), "Assistant:".bold().blue());
+            crate::utils::spinner::clear();
+            print!("{} ", "Assistant:".bold().blue());
             io::stdout().flush()?;
 
             let result = if !messages.is_empty() {
@@ -304,19 +385,25 @@ pub async fn handle(
                 .await
             };
 
+            drop(spinner);
             match result {
-                Ok(_) => {
-                    // Streaming completed successfully
+                Ok(response) => {
+                    // Streaming completed (possibly with a salvaged partial response)
                     println!();
 
-                    // Save to database with placeholder since the actual response was streamed
-                    if let Err(e) = db.save_chat_entry_with_tokens(
+                    // Save the streamed text to database
+                    if let Err(e) = db.save_chat_entry_with_details(
                         &session_id,
+                        &provider_name,
                         &current_model,
                         input,
-                        "[Streamed Response]",
+                        &response,
                         None,
                         None,
+                        &crate::core::request_metadata::log_details(
+                            &provider_name,
+                            resolved_system_prompt.as_deref(),
+                        ),
                     ) {
                         eprintln!("Warning: Failed to save chat entry: {}", e);
                     }
@@ -327,51 +414,117 @@ pub async fn handle(
                     }
                 }
                 Err(e) => {
-                    println!("\n{} Error: {}", "✗".red(), e);
+                    println!("\n{} Error: {}", sym("✗").red(), e);
                 }
             }
         } else {
-            // Use regular chat
-            let result = if !messages.is_empty() {
-                chat::send_chat_request_with_validation_messages(
-                    &client,
-                    &current_model,
-                    &messages,
-                    resolved_system_prompt.as_deref(),
-                    config.max_tokens,
-                    config.temperature,
-                    &provider_name,
-                    None,
-                )
-                .await
-            } else {
-                chat::send_chat_request_with_validation(
-                    &client,
-                    &current_model,
-                    &enhanced_input,
-                    &history,
-                    resolved_system_prompt.as_deref(),
-                    config.max_tokens,
-                    config.temperature,
-                    &provider_name,
-                    None,
-                )
-                .await
+            // Use regular chat, running the MCP tools the model calls
+            let server_refs = chat_tools.server_refs();
+            // Only the answer itself is constrained to the --schema
+            let result = crate::core::structured_output::with_format(
+                crate::core::structured_output::requested(),
+                async {
+                    if !chat_tools.is_empty() && !messages.is_empty() {
+                        chat::send_chat_request_with_tool_execution_messages(
+                            &client,
+                            &current_model,
+                            &messages,
+                            resolved_system_prompt.as_deref(),
+                            config.max_tokens,
+                            config.temperature,
+                            &provider_name,
+                            chat_tools.tools.clone(),
+                            &server_refs,
+                            None, // Use default max_iterations
+                        )
+                        .await
+                    } else if !chat_tools.is_empty() {
+                        chat::send_chat_request_with_tool_execution(
+                            &client,
+                            &current_model,
+                            &enhanced_input,
+                            &history,
+                            resolved_system_prompt.as_deref(),
+                            config.max_tokens,
+                            config.temperature,
+                            &provider_name,
+                            chat_tools.tools.clone(),
+                            &server_refs,
+                            None, // Use default max_iterations
+                        )
+                        .await
+                    } else if !messages.is_empty() {
+                        chat::send_chat_request_with_validation_messages(
+                            &client,
+                            &current_model,
+                            &messages,
+                            resolved_system_prompt.as_deref(),
+                            config.max_tokens,
+                            config.temperature,
+                            &provider_name,
+                            None,
+                        )
+                        .await
+                    } else {
+                        chat::send_chat_request_with_validation(
+                            &client,
+                            &current_model,
+                            &enhanced_input,
+                            &history,
+                            resolved_system_prompt.as_deref(),
+                            config.max_tokens,
+                            config.temperature,
+                            &provider_name,
+                            None,
+                        )
+                        .await
+                    }
+                },
+            )
+            .await;
+            let result = match result {
+                Ok((response, input_tokens, output_tokens)) => {
+                    chat::conform_to_schema(
+                        &client,
+                        &current_model,
+                        &enhanced_input,
+                        (
+                            crate::services::wasm_plugins::post_process(response),
+                            input_tokens,
+                            output_tokens,
+                        ),
+                        resolved_system_prompt.as_deref(),
+                        config.max_tokens,
+                        config.temperature,
+                        &provider_name,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
             };
 
+            drop(spinner);
             match result {
                 Ok((response, input_tokens, output_tokens)) => {
-                    print!("\r{}\r", " ".repeat(12)); // Clear "Thinking..."
-                    println!("{} {}", "Assistant:".bold().blue(), response);
+                    println!(
+                        "{} {}",
+                        "Assistant:".bold().blue(),
+                        crate::core::redaction::redact(&response)
+                    );
 
                     // Save to database with token counts
-                    if let Err(e) = db.save_chat_entry_with_tokens(
+                    if let Err(e) = db.save_chat_entry_with_details(
                         &session_id,
+                        &provider_name,
                         &current_model,
                         input,
                         &response,
                         input_tokens,
                         output_tokens,
+                        &crate::core::request_metadata::log_details(
+                            &provider_name,
+                            resolved_system_prompt.as_deref(),
+                        ),
                     ) {
                         eprintln!("Warning: Failed to save chat entry: {}", e);
                     }
@@ -382,8 +535,7 @@ pub async fn handle(
                     }
                 }
                 Err(e) => {
-                    print!("\r{}\r", " ".repeat(12)); // Clear "Thinking..."
-                    println!("{} Error: {}", "✗".red(), e);
+                    println!("{} Error: {}", sym("✗").red(), e);
                 }
             }
         }
@@ -391,5 +543,224 @@ pub async fn handle(
         println!(); // Add spacing
     }
 
+    crate::analytics::session_summary::print_session_summary(&session_id).await;
+
     Ok(())
 }
+
+/// MCP servers whose tools the chat offers, and their tool definitions
+#[derive(Default)]
+struct ChatTools {
+    servers: Vec<String>,
+    tools: Option<Vec<crate::provider::Tool>>,
+}
+
+impl ChatTools {
+    /// Connect to `servers` and fetch their tool definitions; servers that cannot be
+    /// reached are left out with a warning
+    async fn fetch(servers: Vec<String>) -> Result<Self> {
+        if servers.is_empty() {
+            return Ok(Self::default());
+        }
+        let (tools, servers) = crate::core::tools::fetch_mcp_tools(&servers.join(",")).await?;
+        Ok(Self { servers, tools })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.servers.is_empty() || self.tools.as_ref().is_none_or(|tools| tools.is_empty())
+    }
+
+    fn server_refs(&self) -> Vec<&str> {
+        self.servers.iter().map(String::as_str).collect()
+    }
+
+    fn describe(&self) -> String {
+        if self.is_empty() {
+            return "No tools".to_string();
+        }
+        let names: Vec<&str> = self
+            .tools
+            .iter()
+            .flatten()
+            .map(|tool| tool.function.name.as_str())
+            .collect();
+        format!(
+            "Tools: {} from MCP servers {} ({})",
+            names.len(),
+            self.servers.join(", "),
+            names.join(", ")
+        )
+    }
+}
+
+fn split_servers(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Slash commands that change the chat's capabilities mid-conversation
+#[derive(Debug, PartialEq)]
+enum Toggle {
+    ShowTools,
+    AddTools(String),
+    RemoveTools(String),
+    RefreshTools,
+    ShowRag,
+    /// Answer with this vector database, `None` for `/rag off`
+    Rag(Option<String>),
+    ShowStream,
+    Stream(bool),
+}
+
+/// Parse `/tools`, `/rag` and `/stream` commands. `None` for other input, the usage
+/// as error when the arguments are wrong.
+fn parse_toggle(input: &str) -> Option<Result<Toggle, &'static str>> {
+    let mut words = input.split_whitespace();
+    let command = words.next()?;
+    let args: Vec<&str> = words.collect();
+    let parsed = match command {
+        "/tools" => match args.as_slice() {
+            [] => Ok(Toggle::ShowTools),
+            ["refresh"] => Ok(Toggle::RefreshTools),
+            ["add", server] => Ok(Toggle::AddTools(server.to_string())),
+            ["remove" | "rm", server] => Ok(Toggle::RemoveTools(server.to_string())),
+            _ => Err("/tools [add|remove <server>|refresh]"),
+        },
+        "/rag" => match args.as_slice() {
+            [] => Ok(Toggle::ShowRag),
+            ["off"] => Ok(Toggle::Rag(None)),
+            [db] => Ok(Toggle::Rag(Some(db.to_string()))),
+            _ => Err("/rag [<db>|off]"),
+        },
+        "/stream" => match args.as_slice() {
+            [] => Ok(Toggle::ShowStream),
+            ["on"] => Ok(Toggle::Stream(true)),
+            ["off"] => Ok(Toggle::Stream(false)),
+            _ => Err("/stream [on|off]"),
+        },
+        _ => return None,
+    };
+    Some(parsed)
+}
+
+/// Apply a slash command to the chat's tools, RAG database or streaming
+async fn apply_toggle(
+    toggle: Toggle,
+    chat_tools: &mut ChatTools,
+    rag_db: &mut Option<String>,
+    stream_override: &mut Option<bool>,
+) {
+    match toggle {
+        Toggle::ShowTools => println!("{} {}", sym("🔧").blue(), chat_tools.describe()),
+        Toggle::AddTools(server) => {
+            if chat_tools.servers.contains(&server) {
+                println!(
+                    "{} Tools from '{}' are already on",
+                    sym("✓").green(),
+                    server
+                );
+                return;
+            }
+            let mut servers = chat_tools.servers.clone();
+            servers.push(server.clone());
+            match ChatTools::fetch(servers).await {
+                Ok(fetched) if fetched.servers.contains(&server) => {
+                    *chat_tools = fetched;
+                    println!("{} {}", sym("✓").green(), chat_tools.describe());
+                }
+                Ok(_) => println!("{} No tools added from '{}'", sym("✗").red(), server),
+                Err(e) => println!("{} Could not fetch tools: {}", sym("✗").red(), e),
+            }
+        }
+        Toggle::RemoveTools(server) => {
+            if !chat_tools.servers.contains(&server) {
+                println!("{} Tools from '{}' are not on", sym("✗").red(), server);
+                return;
+            }
+            let servers = chat_tools
+                .servers
+                .iter()
+                .filter(|name| **name != server)
+                .cloned()
+                .collect();
+            match ChatTools::fetch(servers).await {
+                Ok(fetched) => {
+                    *chat_tools = fetched;
+                    println!("{} {}", sym("✓").green(), chat_tools.describe());
+                }
+                Err(e) => println!("{} Could not fetch tools: {}", sym("✗").red(), e),
+            }
+        }
+        Toggle::RefreshTools => match ChatTools::fetch(chat_tools.servers.clone()).await {
+            Ok(fetched) => {
+                *chat_tools = fetched;
+                println!("{} {}", sym("✓").green(), chat_tools.describe());
+            }
+            Err(e) => println!("{} Could not fetch tools: {}", sym("✗").red(), e),
+        },
+        Toggle::ShowRag => match rag_db {
+            Some(db_name) => println!("{} RAG: {}", sym("📚").blue(), db_name),
+            None => println!("{} RAG is off", sym("📚").blue()),
+        },
+        Toggle::Rag(None) => {
+            *rag_db = None;
+            println!("{} RAG turned off", sym("✓").green());
+        }
+        Toggle::Rag(Some(db_name)) => match crate::vector_db::VectorDatabase::list_databases() {
+            Ok(databases) if databases.contains(&db_name) => {
+                println!("{} Answering with '{}'", sym("✓").green(), db_name);
+                *rag_db = Some(db_name);
+            }
+            Ok(_) => println!(
+                "{} Vector database '{}' not found. See 'lc vectors list'",
+                sym("✗").red(),
+                db_name
+            ),
+            Err(e) => println!("{} Could not list vector databases: {}", sym("✗").red(), e),
+        },
+        Toggle::ShowStream => match stream_override {
+            Some(true) => println!("{} Streaming is on", sym("💬").blue()),
+            Some(false) => println!("{} Streaming is off", sym("💬").blue()),
+            None => println!("{} Streaming follows the configuration", sym("💬").blue()),
+        },
+        Toggle::Stream(on) => {
+            *stream_override = Some(on);
+            println!(
+                "{} Streaming turned {}",
+                sym("✓").green(),
+                if on { "on" } else { "off" }
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toggle() {
+        assert_eq!(parse_toggle("/tools"), Some(Ok(Toggle::ShowTools)));
+        assert_eq!(
+            parse_toggle("/tools add  playwright"),
+            Some(Ok(Toggle::AddTools("playwright".to_string())))
+        );
+        assert_eq!(
+            parse_toggle("/tools remove fetch"),
+            Some(Ok(Toggle::RemoveTools("fetch".to_string())))
+        );
+        assert!(matches!(parse_toggle("/tools add"), Some(Err(_))));
+        assert_eq!(parse_toggle("/rag off"), Some(Ok(Toggle::Rag(None))));
+        assert_eq!(
+            parse_toggle("/rag docs"),
+            Some(Ok(Toggle::Rag(Some("docs".to_string()))))
+        );
+        assert_eq!(parse_toggle("/stream off"), Some(Ok(Toggle::Stream(false))));
+        assert!(matches!(parse_toggle("/stream maybe"), Some(Err(_))));
+        assert_eq!(parse_toggle("/model gpt-4o"), None);
+        assert_eq!(split_servers(" a, ,b "), vec!["a", "b"]);
+    }
+}
@@ -17,8 +17,15 @@ pub async fn handle(shell: CompletionShell) -> Result<()> {
         CompletionShell::Elvish => Shell::Elvish,
     };
 
-    // Generate basic completions
-    generate(shell_type, &mut cmd, "lc", &mut io::stdout());
+    // Generate basic completions. Zsh completes session IDs and vector databases through
+    // functions wired into the generated argument specs.
+    if matches!(shell, CompletionShell::Zsh) {
+        let mut script = Vec::new();
+        generate(shell_type, &mut cmd, "lc", &mut script);
+        print!("{}", wire_zsh_dynamic_values(&String::from_utf8(script)?));
+    } else {
+        generate(shell_type, &mut cmd, "lc", &mut io::stdout());
+    }
 
     // Add custom completion functions for dynamic values
     match shell {
@@ -37,6 +44,118 @@ pub async fn handle(shell: CompletionShell) -> Result<()> {
     Ok(())
 }
 
+/// Hidden command printing dynamic values for the completion scripts
+pub const COMPLETE_COMMAND: &str = "__complete";
+
+/// Number of recent sessions offered for session IDs
+const COMPLETED_SESSIONS: usize = 50;
+
+/// Print the values for a dynamic completion (`lc __complete sessions|vectors`), one
+/// `value<TAB>description` per line as fish expects; the zsh functions convert them
+pub fn complete(kind: &str) -> Result<()> {
+    let candidates: Vec<(String, String)> = match kind {
+        "sessions" => crate::database::Database::new()?
+            .list_sessions(COMPLETED_SESSIONS)?
+            .into_iter()
+            .flat_map(|session| {
+                let description = format!(
+                    "{} ({})",
+                    truncate(&session.first_question, 40),
+                    session.last_active.format("%m-%d %H:%M")
+                );
+                // Named sessions are offered as `name:<name>` too
+                let named = session
+                    .name
+                    .map(|name| (format!("name:{}", name), description.clone()));
+                named.into_iter().chain([(session.chat_id, description)])
+            })
+            .collect(),
+        "vectors" => crate::vector_db::VectorDatabase::list_databases()?
+            .into_iter()
+            .map(|name| {
+                let description = crate::vector_db::VectorDatabase::new(&name)
+                    .and_then(|db| db.count())
+                    .map(|count| format!("{} entries", count))
+                    .unwrap_or_default();
+                (name, description)
+            })
+            .collect(),
+        _ => anyhow::bail!(
+            "Unknown completion '{}', expected sessions or vectors",
+            kind
+        ),
+    };
+
+    for (value, description) in candidates {
+        println!("{}", completion_line(&value, &description));
+    }
+    Ok(())
+}
+
+fn completion_line(value: &str, description: &str) -> String {
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    if description.is_empty() {
+        value.to_string()
+    } else {
+        format!("{}\t{}", value, description)
+    }
+}
+
+/// Zsh functions completing session IDs and vector databases from `lc __complete`
+const ZSH_DYNAMIC_VALUES: &str = r#"
+_lc_sessions() {
+    local -a sessions
+    sessions=(${(f)"$(lc __complete sessions 2>/dev/null)"})
+    sessions=(${sessions//$'\t'/:})
+    _describe -t sessions 'session' sessions
+}
+
+_lc_vector_databases() {
+    local -a databases
+    databases=(${(f)"$(lc __complete vectors 2>/dev/null)"})
+    databases=(${databases//$'\t'/:})
+    _describe -t databases 'vector database' databases
+}
+"#;
+
+/// Make the generated zsh script complete session IDs (`--cid`, `logs show --session`,
+/// `logs share`) and vector database names (`--vectordb`, `vectors <command> <name>`)
+/// with the functions above, which are defined before the script's first use of them.
+fn wire_zsh_dynamic_values(script: &str) -> String {
+    const SESSION_ARGS: &[&str] = &[":CHAT_ID:", ":CID:", ":SESSION:", "'::session -- "];
+    const VECTOR_ARGS: &[&str] = &[":VECTORDB:", "':name -- Database name:"];
+
+    let mut wired = String::with_capacity(script.len() + ZSH_DYNAMIC_VALUES.len());
+    for (index, line) in script.lines().enumerate() {
+        let function = if SESSION_ARGS.iter().any(|arg| line.contains(arg)) {
+            Some("_lc_sessions")
+        } else if VECTOR_ARGS.iter().any(|arg| line.contains(arg)) {
+            Some("_lc_vector_databases")
+        } else {
+            None
+        };
+        match function {
+            Some(function) => {
+                wired.push_str(&line.replace(":_default'", &format!(":{}'", function)))
+            }
+            None => wired.push_str(line),
+        }
+        wired.push('\n');
+        // After `#compdef lc`, which has to stay the first line
+        if index == 0 {
+            wired.push_str(ZSH_DYNAMIC_VALUES);
+        }
+    }
+    wired
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
 /// Generate dynamic completion functions for Bash
 fn generate_bash_dynamic_completions() {
     println!(
@@ -101,9 +220,22 @@ function __lc_complete_models
     lc models 2>/dev/null | awk '/^  •/ {{gsub(/^  • /, ""); print $1}}' 2>/dev/null
 end
 
+function __lc_complete_sessions
+    lc __complete sessions 2>/dev/null
+end
+
+function __lc_complete_vectors
+    lc __complete vectors 2>/dev/null
+end
+
 # Add dynamic completions
 complete -c lc -s p -l provider -f -a "(__lc_complete_providers)" -d "Provider to use"
 complete -c lc -s m -l model -f -a "(__lc_complete_models)" -d "Model to use"
+complete -c lc -l cid -x -a "(__lc_complete_sessions)"
+complete -c lc -n "__fish_seen_subcommand_from logs l; and __fish_seen_subcommand_from show sh" -l session -x -a "(__lc_complete_sessions)"
+complete -c lc -n "__fish_seen_subcommand_from logs l; and __fish_seen_subcommand_from share sr" -f -a "(__lc_complete_sessions)"
+complete -c lc -s v -l vectordb -x -a "(__lc_complete_vectors)"
+complete -c lc -n "__fish_seen_subcommand_from vectors v; and __fish_seen_subcommand_from delete d info i stats s clear cl cluster cu dedupe dd" -f -a "(__lc_complete_vectors)"
 
 # Instructions for setup
 # Add the above to ~/.config/fish/completions/lc.fish
@@ -111,3 +243,27 @@ complete -c lc -s m -l model -f -a "(__lc_complete_models)" -d "Model to use"
 "#
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wire_zsh_dynamic_values() {
+        let script = "#compdef lc\n\
+            '--cid=[Chat ID]:CHAT_ID:_default' \\\n\
+            '--vectordb=[Vector database]:VECTORDB:_default' \\\n\
+            ':name -- Provider name:_default' \\\n";
+        let wired = wire_zsh_dynamic_values(script);
+        assert!(wired.starts_with("#compdef lc\n\n_lc_sessions() {"));
+        assert!(wired.contains("'--cid=[Chat ID]:CHAT_ID:_lc_sessions'"));
+        assert!(wired.contains("'--vectordb=[Vector database]:VECTORDB:_lc_vector_databases'"));
+        assert!(wired.contains("':name -- Provider name:_default'"));
+
+        assert_eq!(
+            completion_line("abc", "What is\nRust? (10-17 09:30)"),
+            "abc\tWhat is Rust? (10-17 09:30)"
+        );
+        assert_eq!(completion_line("docs", ""), "docs");
+    }
+}
@@ -3,6 +3,7 @@
 use crate::cli::ConfigCommands;
 use crate::cli::{DeleteCommands, GetCommands, SetCommands};
 use crate::config;
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 
@@ -13,6 +14,12 @@ pub async fn handle(command: Option<ConfigCommands>) -> Result<()> {
         Some(ConfigCommands::Get { command }) => handle_get_command(command).await,
         Some(ConfigCommands::Delete { command }) => handle_delete_command(command).await,
         Some(ConfigCommands::Path) => handle_path_command().await,
+        Some(ConfigCommands::Export {
+            path,
+            with_keys,
+            with_logs,
+        }) => crate::sync::bundle::export(&path, with_keys, with_logs),
+        Some(ConfigCommands::Import { path, yes }) => crate::sync::bundle::import(&path, yes),
         None => handle_show_current_config().await,
     }
 }
@@ -31,34 +38,46 @@ async fn handle_set_command(command: SetCommands) -> Result<()> {
 
             config.default_provider = Some(name.clone());
             config.save()?;
-            println!("{} Default provider set to '{}'", "✓".green(), name);
+            println!("{} Default provider set to '{}'", sym("✓").green(), name);
         }
         SetCommands::Model { name } => {
             let mut config = config::Config::load()?;
             config.default_model = Some(name.clone());
             config.save()?;
-            println!("{} Default model set to '{}'", "✓".green(), name);
+            println!("{} Default model set to '{}'", sym("✓").green(), name);
+            if let Some(provider) = &config.default_provider {
+                crate::models::deprecation::warn_if_deprecated(
+                    provider,
+                    &name,
+                    &crate::models::deprecation::ModelOrigin::Default,
+                )
+                .await;
+            }
         }
         SetCommands::SystemPrompt { prompt } => {
             let mut config = config::Config::load()?;
-            let resolved_prompt = config.resolve_template_or_prompt(&prompt);
+            // Ambient variables are filled in on each request, not now
+            let resolved_prompt = match prompt.strip_prefix("t:") {
+                Some(name) => config.template_source(name)?.unwrap_or(prompt),
+                None => prompt,
+            };
             config.system_prompt = Some(resolved_prompt);
             config.save()?;
-            println!("{} System prompt set", "✓".green());
+            println!("{} System prompt set", sym("✓").green());
         }
         SetCommands::MaxTokens { value } => {
             let mut config = config::Config::load()?;
             let parsed_value = config::Config::parse_max_tokens(&value)?;
             config.max_tokens = Some(parsed_value);
             config.save()?;
-            println!("{} Max tokens set to {}", "✓".green(), parsed_value);
+            println!("{} Max tokens set to {}", sym("✓").green(), parsed_value);
         }
         SetCommands::Temperature { value } => {
             let mut config = config::Config::load()?;
             let parsed_value = config::Config::parse_temperature(&value)?;
             config.temperature = Some(parsed_value);
             config.save()?;
-            println!("{} Temperature set to {}", "✓".green(), parsed_value);
+            println!("{} Temperature set to {}", sym("✓").green(), parsed_value);
         }
         SetCommands::Search { name } => {
             let mut search_config = crate::search::SearchConfig::load()?;
@@ -72,7 +91,11 @@ async fn handle_set_command(command: SetCommands) -> Result<()> {
 
             search_config.set_default_provider(name.clone())?;
             search_config.save()?;
-            println!("{} Default search provider set to '{}'", "✓".green(), name);
+            println!(
+                "{} Default search provider set to '{}'",
+                sym("✓").green(),
+                name
+            );
         }
         SetCommands::Stream { value } => {
             let mut config = config::Config::load()?;
@@ -83,7 +106,36 @@ async fn handle_set_command(command: SetCommands) -> Result<()> {
             };
             config.stream = Some(stream_value);
             config.save()?;
-            println!("{} Streaming mode set to {}", "✓".green(), stream_value);
+            println!(
+                "{} Streaming mode set to {}",
+                sym("✓").green(),
+                stream_value
+            );
+        }
+        SetCommands::RequestLimit { value } => {
+            let mut config = config::Config::load()?;
+            let limit = config::Config::parse_usd(&value)?;
+            config.limits.per_request_usd = Some(limit);
+            config.save()?;
+            println!(
+                "{} Per-request limit set to ${:.2}",
+                sym("✓").green(),
+                limit
+            );
+        }
+        SetCommands::DailyLimit { value } => {
+            let mut config = config::Config::load()?;
+            let limit = config::Config::parse_usd(&value)?;
+            config.limits.daily_usd = Some(limit);
+            config.save()?;
+            println!("{} Daily limit set to ${:.2}", sym("✓").green(), limit);
+        }
+        SetCommands::Timezone { value } => {
+            let mut config = config::Config::load()?;
+            crate::utils::time_display::Zone::parse(&value)?;
+            config.time.timezone = Some(value.trim().to_string());
+            config.save()?;
+            println!("{} Timezone set to {}", sym("✓").green(), value.trim());
         }
     }
     Ok(())
@@ -142,6 +194,23 @@ async fn handle_get_command(command: GetCommands) -> Result<()> {
                 anyhow::bail!("No streaming mode configured");
             }
         }
+        GetCommands::RequestLimit => {
+            if let Some(limit) = config.limits.per_request_usd {
+                println!("{}", limit);
+            } else {
+                anyhow::bail!("No per-request limit configured");
+            }
+        }
+        GetCommands::DailyLimit => {
+            if let Some(limit) = config.limits.daily_usd {
+                println!("{}", limit);
+            } else {
+                anyhow::bail!("No daily limit configured");
+            }
+        }
+        GetCommands::Timezone => {
+            println!("{}", config.time.timezone.as_deref().unwrap_or("local"));
+        }
     }
     Ok(())
 }
@@ -153,7 +222,7 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if config.default_provider.is_some() {
                 config.default_provider = None;
                 config.save()?;
-                println!("{} Default provider deleted", "✓".green());
+                println!("{} Default provider deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No default provider configured to delete");
             }
@@ -162,7 +231,7 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if config.default_model.is_some() {
                 config.default_model = None;
                 config.save()?;
-                println!("{} Default model deleted", "✓".green());
+                println!("{} Default model deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No default model configured to delete");
             }
@@ -171,7 +240,7 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if config.system_prompt.is_some() {
                 config.system_prompt = None;
                 config.save()?;
-                println!("{} System prompt deleted", "✓".green());
+                println!("{} System prompt deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No system prompt configured to delete");
             }
@@ -180,7 +249,7 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if config.max_tokens.is_some() {
                 config.max_tokens = None;
                 config.save()?;
-                println!("{} Max tokens deleted", "✓".green());
+                println!("{} Max tokens deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No max tokens configured to delete");
             }
@@ -189,7 +258,7 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if config.temperature.is_some() {
                 config.temperature = None;
                 config.save()?;
-                println!("{} Temperature deleted", "✓".green());
+                println!("{} Temperature deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No temperature configured to delete");
             }
@@ -199,7 +268,7 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if search_config.get_default_provider().is_some() {
                 search_config.set_default_provider(String::new())?;
                 search_config.save()?;
-                println!("{} Default search provider deleted", "✓".green());
+                println!("{} Default search provider deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No default search provider configured to delete");
             }
@@ -209,11 +278,35 @@ async fn handle_delete_command(command: DeleteCommands) -> Result<()> {
             if config.stream.is_some() {
                 config.stream = None;
                 config.save()?;
-                println!("{} Streaming mode deleted", "✓".green());
+                println!("{} Streaming mode deleted", sym("✓").green());
             } else {
                 anyhow::bail!("No streaming mode configured to delete");
             }
         }
+        DeleteCommands::RequestLimit => {
+            if config.limits.per_request_usd.take().is_some() {
+                config.save()?;
+                println!("{} Per-request limit deleted", sym("✓").green());
+            } else {
+                anyhow::bail!("No per-request limit configured to delete");
+            }
+        }
+        DeleteCommands::DailyLimit => {
+            if config.limits.daily_usd.take().is_some() {
+                config.save()?;
+                println!("{} Daily limit deleted", sym("✓").green());
+            } else {
+                anyhow::bail!("No daily limit configured to delete");
+            }
+        }
+        DeleteCommands::Timezone => {
+            if config.time.timezone.take().is_some() {
+                config.save()?;
+                println!("{} Timezone deleted, using local time", sym("✓").green());
+            } else {
+                anyhow::bail!("No timezone configured to delete");
+            }
+        }
     }
     Ok(())
 }
@@ -223,22 +316,22 @@ async fn handle_path_command() -> Result<()> {
     println!("\n{}", "Configuration Directory:".bold().blue());
     println!("{}", config_dir.display());
     println!("\n{}", "Files:".bold().blue());
-    println!("  {} config.toml", "•".blue());
-    println!("  {} logs.db (synced to cloud)", "•".blue());
+    println!("  {} config.toml", sym("•").blue());
+    println!("  {} logs.db (synced to cloud)", sym("•").blue());
     println!("\n{}", "Database Management:".bold().blue());
     println!(
         "  {} Purge old logs: {}",
-        "•".blue(),
+        sym("•").blue(),
         "lc logs purge --older-than-days 30".dimmed()
     );
     println!(
         "  {} Keep recent logs: {}",
-        "•".blue(),
+        sym("•").blue(),
         "lc logs purge --keep-recent 1000".dimmed()
     );
     println!(
         "  {} Size-based purge: {}",
-        "•".blue(),
+        sym("•").blue(),
         "lc logs purge --max-size-mb 50".dimmed()
     );
     Ok(())
@@ -269,19 +362,19 @@ async fn handle_show_current_config() -> Result<()> {
                         let mut capabilities = Vec::new();
                         if model_metadata.supports_tools || model_metadata.supports_function_calling
                         {
-                            capabilities.push("🔧 tools".blue());
+                            capabilities.push(sym("🔧 tools").blue());
                         }
                         if model_metadata.supports_vision {
-                            capabilities.push("👁 vision".magenta());
+                            capabilities.push(sym("👁 vision").magenta());
                         }
                         if model_metadata.supports_audio {
-                            capabilities.push("🔊 audio".yellow());
+                            capabilities.push(sym("🔊 audio").yellow());
                         }
                         if model_metadata.supports_reasoning {
-                            capabilities.push("🧠 reasoning".cyan());
+                            capabilities.push(sym("🧠 reasoning").cyan());
                         }
                         if model_metadata.supports_code {
-                            capabilities.push("💻 code".green());
+                            capabilities.push(sym("💻 code").green());
                         }
 
                         // Build context and pricing info
@@ -369,6 +462,13 @@ async fn handle_show_current_config() -> Result<()> {
         println!("stream {}", "not set".dimmed());
     }
 
+    if let Some(limit) = config.limits.per_request_usd {
+        println!("limits.per_request_usd ${:.2}", limit);
+    }
+    if let Some(limit) = config.limits.daily_usd {
+        println!("limits.daily_usd ${:.2}", limit);
+    }
+
     Ok(())
 }
 
@@ -1,7 +1,7 @@
 //! CLI definitions and command structures
 //! This file contains all the CLI struct and enum definitions
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 // Helper function to parse environment variable KEY=VALUE pairs
 fn parse_env_var(s: &str) -> Result<(String, String), String> {
@@ -34,10 +34,43 @@ pub struct Cli {
     #[arg(short = 'm', long = "model", global = true)]
     pub model: Option<String>,
 
+    /// Enable debug/verbose logging, optionally filtered: --debug=mcp,http or --debug=trace,-cache
+    #[arg(
+        short = 'd',
+        long = "debug",
+        value_name = "FILTER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "all",
+        env = "LC_DEBUG"
+    )]
+    pub debug: Option<String>,
+
+    #[command(flatten)]
+    pub prompt_args: Box<PromptArgs>,
+
+    #[command(flatten)]
+    pub global_args: Box<GlobalArgs>,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+/// Options of a direct prompt (`lc "..."`)
+#[derive(Args)]
+pub struct PromptArgs {
     /// System prompt to use (when used with direct prompt)
     #[arg(short = 's', long = "system")]
     pub system_prompt: Option<String>,
 
+    /// Read the system prompt from a file, for prompts too long for `-s`
+    #[arg(
+        long = "system-file",
+        value_name = "PATH",
+        conflicts_with = "system_prompt"
+    )]
+    pub system_file: Option<String>,
+
     /// Max tokens override (supports 'k' suffix, e.g., '2k' for 2000)
     #[arg(long = "max-tokens")]
     pub max_tokens: Option<String>,
@@ -46,7 +79,8 @@ pub struct Cli {
     #[arg(long = "temperature")]
     pub temperature: Option<String>,
 
-    /// Attach file(s) to the prompt (supports text files, PDFs with 'pdf' feature)
+    /// Attach file(s) to the prompt (supports text files, PDFs with 'pdf' feature, EPUB,
+    /// CSV and spreadsheets, and s3://, gs:// and http(s):// URLs)
     #[arg(short = 'a', long = "attach")]
     pub attachments: Vec<String>,
 
@@ -58,23 +92,24 @@ pub struct Cli {
     #[arg(short = 'u', long = "audio")]
     pub audio_files: Vec<String>,
 
+    /// How to handle attached audio: auto, transcribe, or native
+    #[arg(long = "audio-mode", value_enum, default_value_t = AudioMode::Auto)]
+    pub audio_mode: AudioMode,
+
     /// Include tools from MCP server(s) (comma-separated server names)
     #[arg(short = 't', long = "tools")]
     pub tools: Option<String>,
 
-    /// Vector database name for RAG (Retrieval-Augmented Generation)
+    /// Vector database(s) for RAG (Retrieval-Augmented Generation), comma-separated;
+    /// `name:N` takes up to N matches from a database (default 3)
     #[arg(short = 'v', long = "vectordb")]
     pub vectordb: Option<String>,
 
-    /// Enable debug/verbose logging
-    #[arg(short = 'd', long = "debug")]
-    pub debug: bool,
-
     /// Continue the current session (use existing session ID)
     #[arg(short = 'c', long = "continue")]
     pub continue_session: bool,
 
-    /// Chat ID to use or continue (alternative to --continue)
+    /// Chat ID to use or continue, or `name:<name>` (alternative to --continue)
     #[arg(long = "cid")]
     pub chat_id: Option<String>,
 
@@ -82,12 +117,193 @@ pub struct Cli {
     #[arg(long = "use-search")]
     pub use_search: Option<String>,
 
+    /// Model that rewrites the prompt into 1-3 focused queries for --use-search
+    #[arg(
+        long = "search-rewrite-model",
+        env = "LC_SEARCH_REWRITE_MODEL",
+        value_name = "MODEL"
+    )]
+    pub search_rewrite_model: Option<String>,
+
     /// Enable streaming output for prompt responses
     #[arg(long = "stream")]
     pub stream: bool,
 
-    #[command(subcommand)]
-    pub command: Option<Commands>,
+    /// Skip capability pre-checks (e.g. sending images to a non-vision model)
+    #[arg(long = "force")]
+    pub force: bool,
+
+    /// Describe --tools in the prompt (ReAct style) instead of using native function calling
+    #[arg(long = "react-tools")]
+    pub react_tools: bool,
+
+    /// Check code in the answer with this command (run in a temp workspace) and have the model fix failures
+    #[arg(long = "validate-cmd", value_name = "COMMAND")]
+    pub validate_cmd: Option<String>,
+
+    /// Maximum repair requests when --validate-cmd fails
+    #[arg(long = "validate-retries", default_value_t = crate::core::validation::DEFAULT_MAX_REPAIRS)]
+    pub validate_retries: usize,
+
+    /// Have the model verify the answer's facts and code, and append corrections
+    #[arg(long = "self-check")]
+    pub self_check: bool,
+
+    /// Model that verifies the answer with --self-check (implies --self-check)
+    #[arg(long = "self-check-model", value_name = "MODEL")]
+    pub self_check_model: Option<String>,
+
+    /// Constrain the answer to a JSON schema (inline JSON or a file); invalid answers are retried once
+    #[arg(long = "schema", value_name = "FILE_OR_JSON")]
+    pub schema: Option<String>,
+
+    /// Send the prompt to the default model even when [router] would route it to the cheap model
+    #[arg(long = "no-route", env = "LC_NO_ROUTE")]
+    pub no_route: bool,
+
+    /// Print a session summary (turns, tokens, cost, models, tool calls) after the response
+    #[arg(long = "stats")]
+    pub stats: bool,
+}
+
+/// Options that apply to every command
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// Token budget for attached files (defaults to what the model's context window leaves)
+    #[arg(long = "attach-budget", global = true, env = "LC_ATTACH_BUDGET")]
+    pub attach_budget: Option<usize>,
+
+    /// How to reduce attachments over the budget
+    #[arg(
+        long = "attach-strategy",
+        global = true,
+        env = "LC_ATTACH_STRATEGY",
+        value_enum,
+        default_value_t
+    )]
+    pub attach_strategy: crate::core::attachment_budget::AttachStrategy,
+
+    /// Embedding model (provider:model or alias) of --attach-strategy embed
+    #[arg(
+        long = "attach-embed-model",
+        global = true,
+        env = "LC_ATTACH_EMBED_MODEL",
+        value_name = "MODEL"
+    )]
+    pub attach_embed_model: Option<String>,
+
+    /// Rows of attached CSV, TSV and spreadsheet files sent after their schema (default: small tables whole, 20 rows of larger ones)
+    #[arg(long = "rows", global = true, env = "LC_ATTACH_ROWS", value_name = "N")]
+    pub rows: Option<usize>,
+
+    /// Rank RAG and `lc similar` results by keywords too: weight of vector similarity,
+    /// from 0 (keywords only) to 1 (vectors only)
+    #[arg(
+        long = "hybrid-alpha",
+        global = true,
+        env = "LC_HYBRID_ALPHA",
+        value_name = "ALPHA",
+        value_parser = crate::data::hybrid_search::parse_alpha
+    )]
+    pub hybrid_alpha: Option<f64>,
+
+    /// Rerank RAG and `lc similar` results with a rerank model (provider:model or alias)
+    #[arg(
+        long = "rerank",
+        global = true,
+        env = "LC_RERANK",
+        value_name = "MODEL"
+    )]
+    pub rerank: Option<String>,
+
+    /// Enable the provider's built-in tools (comma-separated: web_search, code)
+    #[arg(long = "provider-tools", global = true, value_name = "TOOLS")]
+    pub provider_tools: Option<String>,
+
+    /// What to try when the provider refuses (content filter, malformed tool call), in order
+    #[arg(
+        long = "on-refusal",
+        global = true,
+        env = "LC_ON_REFUSAL",
+        value_enum,
+        value_delimiter = ','
+    )]
+    pub on_refusal: Vec<crate::core::refusal::Strategy>,
+
+    /// Model for --on-refusal fallback (provider:model or alias)
+    #[arg(
+        long = "fallback-model",
+        global = true,
+        env = "LC_FALLBACK_MODEL",
+        value_name = "MODEL"
+    )]
+    pub fallback_model: Option<String>,
+
+    /// Print the reasoning trace of thinking models (dimmed, on stderr) before the answer
+    #[arg(long = "show-reasoning", global = true)]
+    pub show_reasoning: bool,
+
+    /// Print a timing breakdown (connect, TTFB, tokens/sec, ...) after each request
+    #[arg(long = "timings", global = true)]
+    pub timings: bool,
+
+    /// Never modify config, keys, logs or vector databases (for scripts)
+    #[arg(long = "read-only", global = true, env = "LC_READ_ONLY")]
+    pub read_only: bool,
+
+    /// Use the provider that has the model when -m names a model without provider and only one provider has it
+    #[arg(long = "auto-provider", global = true, env = "LC_AUTO_PROVIDER")]
+    pub auto_provider: bool,
+
+    /// Send requests even when they could exceed limits.per_request_usd or limits.daily_usd
+    #[arg(long = "override-budget", global = true, env = "LC_OVERRIDE_BUDGET")]
+    pub override_budget: bool,
+
+    /// Fetch URLs found in the prompt and append their content as context
+    #[arg(long = "auto-fetch-urls", global = true, env = "LC_AUTO_FETCH_URLS")]
+    pub auto_fetch_urls: bool,
+
+    /// Fetch web pages and search results again instead of using the web cache
+    #[arg(long = "fresh", global = true, env = "LC_FRESH")]
+    pub fresh: bool,
+
+    /// Repeatable runs: a fixed seed, numbered session IDs and no timestamps in output
+    #[arg(long = "deterministic", global = true, env = "LC_DETERMINISTIC")]
+    pub deterministic: bool,
+
+    /// Print ASCII instead of emoji and Unicode symbols (automatic in legacy Windows consoles)
+    #[arg(long = "ascii", global = true, env = "LC_ASCII")]
+    pub ascii: bool,
+
+    /// Embed turns trimmed from long conversations into a per-session vector memory and
+    /// recall the relevant ones (value: embedding model, e.g. openai:text-embedding-3-small)
+    #[arg(
+        long = "session-memory",
+        global = true,
+        env = "LC_SESSION_MEMORY",
+        value_name = "EMBEDDING_MODEL"
+    )]
+    pub session_memory: Option<String>,
+
+    /// Largest prompt accepted on stdin, in MB (unlimited by default)
+    #[arg(long = "max-stdin-mb", global = true, env = "LC_MAX_STDIN_MB")]
+    pub max_stdin_mb: Option<u64>,
+
+    /// End-user ID sent as the request's `user` field (defaults to the provider's `user` var)
+    #[arg(long = "user", global = true, env = "LC_USER")]
+    pub user: Option<String>,
+
+    /// Attach key=value metadata to requests and their log entries (repeatable)
+    #[arg(long = "metadata", global = true, value_name = "KEY=VALUE")]
+    pub metadata: Vec<String>,
+
+    /// Style profile for the responses of this invocation ('none' turns styles off)
+    #[arg(long = "style", global = true, env = "LC_STYLE", value_name = "STYLE")]
+    pub style: Option<String>,
+
+    /// Override a config.toml setting for this invocation, e.g. http.timeout=300 (repeatable)
+    #[arg(long = "set", global = true, value_name = "SECTION.KEY=VALUE")]
+    pub set: Vec<String>,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -114,6 +330,37 @@ pub enum McpServerType {
     Streamable,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AudioMode {
+    /// Send audio natively when the model supports it, otherwise transcribe
+    #[default]
+    Auto,
+    /// Always transcribe audio first and add the transcript to the prompt
+    Transcribe,
+    /// Always send audio as native input_audio content parts
+    Native,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionExportFormat {
+    /// Everything logged for the session, importable with `lc logs import`
+    #[default]
+    Json,
+    /// A readable transcript
+    #[value(alias = "md")]
+    Markdown,
+    /// A self-contained page, as `lc logs share` writes it
+    Html,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncInclude {
+    /// Vector databases in the embeddings directory
+    Vectors,
+    /// Models metadata cache (per-provider model lists)
+    Models,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Provider management (alias: p)
@@ -132,26 +379,17 @@ pub enum Commands {
     #[command(alias = "l")]
     Logs {
         #[command(subcommand)]
-        command: LogCommands,
+        command: Box<LogCommands>,
     },
-    /// Usage statistics and analytics (alias: u)
-    #[command(alias = "u")]
-    Usage {
+    /// Database backups and integrity checks (alias: bk)
+    #[command(alias = "bk")]
+    Backup {
         #[command(subcommand)]
-        command: Option<UsageCommands>,
-        /// Show usage for the last N days
-        #[arg(short = 'd', long = "days")]
-        days: Option<u32>,
-        /// Show only token usage (default shows both tokens and requests)
-        #[arg(short = 't', long = "tokens")]
-        tokens_only: bool,
-        /// Show only request counts
-        #[arg(short = 'r', long = "requests")]
-        requests_only: bool,
-        /// Maximum number of items to show in charts
-        #[arg(short = 'n', long = "limit", default_value = "10")]
-        limit: usize,
+        command: BackupCommands,
     },
+    /// Usage statistics and analytics (alias: u)
+    #[command(alias = "u")]
+    Usage(Box<UsageArgs>),
     /// Configuration management (alias: co)
     #[command(alias = "co")]
     Config {
@@ -160,68 +398,21 @@ pub enum Commands {
     },
     /// Interactive chat mode (alias: c)
     #[command(alias = "c")]
-    Chat {
-        /// Model to use for the chat
-        #[arg(short, long)]
-        model: Option<String>,
-        /// Provider to use for the chat
-        #[arg(short, long)]
-        provider: Option<String>,
-        /// Chat ID to use or continue
-        #[arg(long)]
-        cid: Option<String>,
-        /// Include tools from MCP server(s) (comma-separated server names)
-        #[arg(short = 't', long = "tools")]
-        tools: Option<String>,
-        /// Vector database name for RAG (Retrieval-Augmented Generation)
-        #[arg(short = 'v', long = "vectordb")]
-        database: Option<String>,
-        /// Enable debug/verbose logging
-        #[arg(short = 'd', long = "debug")]
-        debug: bool,
-        /// Attach image(s) to the chat (supports jpg, png, gif, webp, or URLs)
-        #[arg(short = 'i', long = "image")]
-        images: Vec<String>,
-    },
+    Chat(Box<ChatArgs>),
+    /// Re-run a prompt whenever the attached files change (alias: wa)
+    #[command(alias = "wa")]
+    Watch(Box<WatchArgs>),
+    /// Let two models converse with each other
+    Duo(Box<DuoArgs>),
+    /// Extract JSON records matching a schema from documents (alias: ex)
+    #[command(alias = "ex")]
+    Extract(Box<ExtractArgs>),
+    /// Run a failing build, have the model explain it and apply its patch (alias: qf)
+    #[command(alias = "qf")]
+    Quickfix(Box<QuickfixArgs>),
     /// Global models management (alias: m)
     #[command(alias = "m")]
-    Models {
-        #[command(subcommand)]
-        command: Option<ModelsCommands>,
-        /// Search query for models (case-insensitive)
-        #[arg(short = 'q', long = "query")]
-        query: Option<String>,
-        /// Filter models that support tools/function calling
-        #[arg(long = "tools")]
-        tools: bool,
-        /// Filter models that support reasoning
-        #[arg(long = "reasoning")]
-        reasoning: bool,
-        /// Filter models that support vision
-        #[arg(long = "vision")]
-        vision: bool,
-        /// Filter models that support audio
-        #[arg(long = "audio")]
-        audio: bool,
-        /// Filter models that support code generation
-        #[arg(long = "code")]
-        code: bool,
-        /// Filter models with minimum context length (e.g., 128k)
-        #[arg(long = "ctx")]
-        context_length: Option<String>,
-        /// Filter models with minimum input token length (e.g., 128k)
-        #[arg(long = "input")]
-        input_length: Option<String>,
-        /// Filter models with minimum output token length (e.g., 128k)
-        #[arg(long = "output")]
-        output_length: Option<String>,
-        /// Filter models with maximum input price per million tokens
-        #[arg(long = "input-price")]
-        input_price: Option<f64>,
-        /// Filter models with maximum output price per million tokens
-        #[arg(long = "output-price")]
-        output_price: Option<f64>,
-    },
+    Models(Box<ModelsArgs>),
     /// Model alias management (alias: a)
     #[command(alias = "a")]
     Alias {
@@ -234,28 +425,17 @@ pub enum Commands {
         #[command(subcommand)]
         command: TemplateCommands,
     },
+    /// Style profiles: tone, language, formatting rules and banned phrases (alias: st)
+    #[command(alias = "st")]
+    Styles {
+        #[command(subcommand)]
+        command: StyleCommands,
+    },
     /// Proxy server (alias: pr)
     #[command(alias = "pr")]
-    Proxy {
-        /// Port to listen on
-        #[arg(short = 'p', long = "port", default_value = "6789")]
-        port: u16,
-        /// Host to bind to
-        #[arg(long = "host", default_value = "127.0.0.1")]
-        host: String,
-        /// Filter by provider
-        #[arg(long = "provider")]
-        provider: Option<String>,
-        /// Filter by specific model (can be provider:model or alias)
-        #[arg(short = 'm', long = "model")]
-        model: Option<String>,
-        /// API key for authentication
-        #[arg(short = 'k', long = "key")]
-        api_key: Option<String>,
-        /// Generate a random API key
-        #[arg(short = 'g', long = "generate-key")]
-        generate_key: bool,
-    },
+    Proxy(Box<ProxyArgs>),
+    /// Serve a minimal web chat interface (requires the webui feature)
+    ServeWebui(Box<ServeWebuiArgs>),
     /// MCP server management
     Mcp {
         #[command(subcommand)]
@@ -263,43 +443,10 @@ pub enum Commands {
     },
     /// Generate embeddings for text (alias: e)
     #[command(alias = "e")]
-    Embed {
-        /// Model to use for embeddings
-        #[arg(short, long)]
-        model: String,
-        /// Provider to use for embeddings
-        #[arg(short, long)]
-        provider: Option<String>,
-        /// Vector database name to store embeddings
-        #[arg(short = 'v', long = "vectordb")]
-        database: Option<String>,
-        /// Files to embed (supports glob patterns, including PDFs with 'pdf' feature)
-        #[arg(short = 'f', long = "files")]
-        files: Vec<String>,
-        /// Text to embed (optional if files are provided)
-        text: Option<String>,
-        /// Enable debug/verbose logging
-        #[arg(short = 'd', long = "debug")]
-        debug: bool,
-    },
+    Embed(Box<EmbedArgs>),
     /// Find similar text using vector similarity (alias: s)
     #[command(alias = "s")]
-    Similar {
-        /// Model to use for embeddings (optional if database has existing model)
-        #[arg(short, long)]
-        model: Option<String>,
-        /// Provider to use for embeddings (optional if database has existing model)
-        #[arg(short, long)]
-        provider: Option<String>,
-        /// Vector database name to search
-        #[arg(short = 'v', long = "vectordb")]
-        database: String,
-        /// Number of similar results to return
-        #[arg(short, long, default_value = "5")]
-        limit: usize,
-        /// Query text to find similar content
-        query: String,
-    },
+    Similar(Box<SimilarArgs>),
     /// Vector database management (alias: v)
     #[command(alias = "v")]
     Vectors {
@@ -318,6 +465,15 @@ pub enum Commands {
         #[command(subcommand)]
         command: SyncCommands,
     },
+    /// Restore what the most recent destructive command removed
+    Undo {
+        /// List trash entries instead of restoring
+        #[arg(short = 'l', long = "list")]
+        list: bool,
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
     /// Search provider management (alias: se)
     #[command(alias = "se")]
     Search {
@@ -326,84 +482,12 @@ pub enum Commands {
     },
     /// Generate images from text prompts (alias: img)
     #[command(alias = "img")]
-    Image {
-        /// Text prompt for image generation
-        prompt: String,
-        /// Model to use for image generation
-        #[arg(short, long)]
-        model: Option<String>,
-        /// Provider to use for image generation
-        #[arg(short, long)]
-        provider: Option<String>,
-        /// Image size (e.g., "1024x1024", "512x512")
-        #[arg(short, long, default_value = "1024x1024")]
-        size: String,
-        /// Number of images to generate
-        #[arg(short, long, default_value = "1")]
-        count: u32,
-        /// Output directory for generated images
-        #[arg(short, long)]
-        output: Option<String>,
-        /// Enable debug/verbose logging
-        #[arg(short = 'd', long = "debug")]
-        debug: bool,
-    },
+    Image(Box<ImageArgs>),
     /// Transcribe audio to text (alias: tr)
     #[command(alias = "tr")]
-    Transcribe {
-        /// Audio file(s) to transcribe (supports mp3, wav, flac, etc.)
-        audio_files: Vec<String>,
-        /// Model to use for transcription
-        #[arg(short, long)]
-        model: Option<String>,
-        /// Provider to use for transcription
-        #[arg(short, long)]
-        provider: Option<String>,
-        /// Language of the audio (ISO-639-1 format, e.g., "en", "es")
-        #[arg(short = 'l', long)]
-        language: Option<String>,
-        /// Optional prompt to guide the transcription
-        #[arg(long)]
-        prompt: Option<String>,
-        /// Response format (json, text, srt, verbose_json, vtt)
-        #[arg(short = 'f', long, default_value = "text")]
-        format: String,
-        /// Temperature for transcription (0.0 to 1.0)
-        #[arg(long)]
-        temperature: Option<f32>,
-        /// Output file for transcription (optional, prints to stdout if not specified)
-        #[arg(short, long)]
-        output: Option<String>,
-        /// Enable debug/verbose logging
-        #[arg(short = 'd', long = "debug")]
-        debug: bool,
-    },
+    Transcribe(Box<TranscribeArgs>),
     /// Convert text to speech
-    TTS {
-        /// Text to convert to speech
-        text: String,
-        /// Model to use for TTS
-        #[arg(short, long)]
-        model: Option<String>,
-        /// Provider to use for TTS
-        #[arg(short, long)]
-        provider: Option<String>,
-        /// Voice to use (e.g., alloy, echo, fable, onyx, nova, shimmer)
-        #[arg(short = 'v', long, default_value = "alloy")]
-        voice: String,
-        /// Output audio format (mp3, opus, aac, flac, wav, pcm)
-        #[arg(short = 'f', long, default_value = "mp3")]
-        format: String,
-        /// Speech speed (0.25 to 4.0)
-        #[arg(short = 's', long)]
-        speed: Option<f32>,
-        /// Output file for audio (required)
-        #[arg(short, long)]
-        output: String,
-        /// Enable debug/verbose logging
-        #[arg(short = 'd', long = "debug")]
-        debug: bool,
-    },
+    TTS(Box<TtsArgs>),
     /// Dump metadata JSON from models cache (alias: dump)
     #[command(alias = "dump")]
     DumpMetadata {
@@ -419,17 +503,433 @@ pub enum Commands {
         #[arg(value_enum)]
         shell: CompletionShell,
     },
+    /// External `lc-<name>` commands on PATH, run as `lc <name>` (alias: pl)
+    #[command(alias = "pl")]
+    Plugins {
+        #[command(subcommand)]
+        command: PluginCommands,
+    },
+    /// Check the configuration for problems (alias: dr)
+    #[command(alias = "dr")]
+    Doctor,
+    /// Update lc to the latest GitHub release
+    #[command(name = "upgrade-self")]
+    UpgradeSelf(Box<UpgradeSelfArgs>),
+}
+
+/// Arguments of `lc usage`
+#[derive(Args)]
+pub struct UsageArgs {
+    #[command(subcommand)]
+    pub command: Option<UsageCommands>,
+    /// Show usage for the last N days
+    #[arg(short = 'd', long = "days")]
+    pub days: Option<u32>,
+    /// Only include usage on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long = "since")]
+    pub since: Option<String>,
+    /// Only include usage on or before this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long = "until")]
+    pub until: Option<String>,
+    /// Show only token usage (default shows both tokens and requests)
+    #[arg(short = 't', long = "tokens")]
+    pub tokens_only: bool,
+    /// Show only request counts
+    #[arg(short = 'r', long = "requests")]
+    pub requests_only: bool,
+    /// Maximum number of items to show in charts
+    #[arg(short = 'n', long = "limit", default_value = "10")]
+    pub limit: usize,
+}
+
+/// Arguments of `lc chat`
+#[derive(Args)]
+pub struct ChatArgs {
+    /// Model to use for the chat
+    #[arg(short, long)]
+    pub model: Option<String>,
+    /// Provider to use for the chat
+    #[arg(short, long)]
+    pub provider: Option<String>,
+    /// Chat ID to use or continue (`name:<name>` for a named session)
+    #[arg(long)]
+    pub cid: Option<String>,
+    /// Name the session, or resume the session with this name
+    #[arg(long = "name", value_name = "NAME")]
+    pub name: Option<String>,
+    /// Include tools from MCP server(s) (comma-separated server names)
+    #[arg(short = 't', long = "tools")]
+    pub tools: Option<String>,
+    /// Vector database(s) for RAG (Retrieval-Augmented Generation), comma-separated
+    #[arg(short = 'v', long = "vectordb", value_name = "VECTORDB")]
+    pub database: Option<String>,
+    /// Enable debug/verbose logging
+    #[arg(short = 'd', long = "debug")]
+    pub debug: bool,
+    /// Attach image(s) to the chat (supports jpg, png, gif, webp, or URLs)
+    #[arg(short = 'i', long = "image")]
+    pub images: Vec<String>,
+    /// Constrain every answer to a JSON schema (inline JSON or a file)
+    #[arg(long = "schema", value_name = "FILE_OR_JSON")]
+    pub schema: Option<String>,
+}
+
+/// Arguments of `lc watch`
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Prompt to run with the attached files
+    #[arg(required = true)]
+    pub prompt: Vec<String>,
+    /// File(s) to attach and watch for changes
+    #[arg(short = 'a', long = "attach", required = true)]
+    pub attachments: Vec<String>,
+    /// System prompt to use
+    #[arg(short = 's', long = "system")]
+    pub system_prompt: Option<String>,
+    /// Include tools from MCP server(s) (comma-separated server names)
+    #[arg(short = 't', long = "tools")]
+    pub tools: Option<String>,
+    /// Stream responses as they arrive
+    #[arg(long = "stream")]
+    pub stream: bool,
+    /// Milliseconds the files must be unchanged before the prompt re-runs
+    #[arg(long = "debounce", default_value_t = 500)]
+    pub debounce: u64,
+}
+
+/// Arguments of `lc duo`
+#[derive(Args)]
+pub struct DuoArgs {
+    /// First participant, which replies to the opening (provider:model or alias)
+    #[arg(long = "a", value_name = "MODEL")]
+    pub a: String,
+    /// Second participant (provider:model or alias)
+    #[arg(long = "b", value_name = "MODEL")]
+    pub b: String,
+    /// Message that starts the conversation
+    #[arg(long = "opening")]
+    pub opening: String,
+    /// Number of replies in total, alternating between the participants
+    #[arg(long = "turns", default_value_t = 6)]
+    pub turns: u32,
+    /// Model that summarizes the conversation at the end (provider:model or alias)
+    #[arg(long = "judge", value_name = "MODEL")]
+    pub judge: Option<String>,
+}
+
+/// Arguments of `lc extract`
+#[derive(Args)]
+pub struct ExtractArgs {
+    /// JSON schema file the records must match
+    #[arg(long = "schema", value_name = "FILE")]
+    pub schema: String,
+    /// Documents to extract from (repeatable, glob patterns and URLs supported)
+    #[arg(short = 'a', long = "attach", required = true)]
+    pub attachments: Vec<String>,
+    /// Extra instructions for the model
+    pub instructions: Option<String>,
+    /// Write the records to a file instead of stdout
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<String>,
+    /// Repair attempts when a reply does not match the schema
+    #[arg(long = "retries", default_value_t = 2)]
+    pub retries: u32,
+    /// Add each document's path to its record under this key
+    #[arg(long = "source-key", value_name = "KEY")]
+    pub source_key: Option<String>,
+    /// Only describe the schema in the prompt, for providers without JSON-schema output
+    #[arg(long = "no-response-format")]
+    pub no_response_format: bool,
+}
+
+/// Arguments of `lc quickfix`
+#[derive(Args)]
+pub struct QuickfixArgs {
+    /// Build or check command to run in the current directory, e.g. "cargo build"
+    #[arg(long = "cmd", value_name = "COMMAND")]
+    pub command: String,
+    /// Fixes to try before giving up; the command re-runs after each applied patch
+    #[arg(long = "rounds", default_value_t = 1)]
+    pub rounds: usize,
+    /// Apply patches without asking
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
+}
+
+/// Arguments of `lc proxy`
+#[derive(Args)]
+pub struct ProxyArgs {
+    /// Port to listen on
+    #[arg(short = 'p', long = "port", default_value = "6789")]
+    pub port: u16,
+    /// Host to bind to
+    #[arg(long = "host", default_value = "127.0.0.1")]
+    pub host: String,
+    /// Filter by provider
+    #[arg(long = "provider")]
+    pub provider: Option<String>,
+    /// Filter by specific model (can be provider:model or alias)
+    #[arg(short = 'm', long = "model")]
+    pub model: Option<String>,
+    /// API key for authentication
+    #[arg(short = 'k', long = "key")]
+    pub api_key: Option<String>,
+    /// Generate a random API key
+    #[arg(short = 'g', long = "generate-key")]
+    pub generate_key: bool,
+    /// Enable the /admin management API with this key
+    #[arg(long = "admin-key", env = "LC_PROXY_ADMIN_KEY", hide_env_values = true)]
+    pub admin_key: Option<String>,
+    /// Enable the /admin management API with a random key
+    #[arg(long = "generate-admin-key", conflicts_with = "admin_key")]
+    pub generate_admin_key: bool,
+}
+
+/// Arguments of `lc serve-webui`
+#[derive(Args)]
+pub struct ServeWebuiArgs {
+    /// Port to listen on
+    #[arg(short = 'p', long = "port", default_value = "8081")]
+    pub port: u16,
+    /// Host to bind to
+    #[arg(long = "host", default_value = "127.0.0.1")]
+    pub host: String,
+    /// Default provider
+    #[arg(long = "provider")]
+    pub provider: Option<String>,
+    /// Default model (can be provider:model or alias)
+    #[arg(short = 'm', long = "model")]
+    pub model: Option<String>,
+    /// Default vector database for RAG
+    #[arg(short = 'v', long = "vectordb")]
+    pub vectordb: Option<String>,
+    /// Default MCP servers for tools (comma-separated)
+    #[arg(short = 't', long = "tools")]
+    pub tools: Option<String>,
+}
+
+/// Arguments of `lc similar`
+#[derive(Args)]
+pub struct SimilarArgs {
+    /// Model to use for embeddings (optional if database has existing model)
+    #[arg(short, long)]
+    pub model: Option<String>,
+    /// Provider to use for embeddings (optional if database has existing model)
+    #[arg(short, long)]
+    pub provider: Option<String>,
+    /// Vector database name to search
+    #[arg(short = 'v', long = "vectordb", value_name = "VECTORDB")]
+    pub database: String,
+    /// Number of similar results to return
+    #[arg(short, long, default_value = "5")]
+    pub limit: usize,
+    /// Only search entries with this tag (repeatable, all must match)
+    #[arg(short = 't', long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+    /// Query text to find similar content
+    pub query: String,
+}
+
+/// Arguments of `lc image`
+#[derive(Args)]
+pub struct ImageArgs {
+    /// Text prompt for image generation
+    pub prompt: String,
+    /// Model to use for image generation
+    #[arg(short, long)]
+    pub model: Option<String>,
+    /// Provider to use for image generation
+    #[arg(short, long)]
+    pub provider: Option<String>,
+    /// Image size (e.g., "1024x1024", "512x512")
+    #[arg(short, long, default_value = "1024x1024")]
+    pub size: String,
+    /// Number of images to generate
+    #[arg(short, long, default_value = "1")]
+    pub count: u32,
+    /// Output directory for generated images
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// Enable debug/verbose logging
+    #[arg(short = 'd', long = "debug")]
+    pub debug: bool,
+}
+
+/// Arguments of `lc transcribe`
+#[derive(Args)]
+pub struct TranscribeArgs {
+    /// Audio file(s) to transcribe (supports mp3, wav, flac, etc.)
+    pub audio_files: Vec<String>,
+    /// Model to use for transcription
+    #[arg(short, long)]
+    pub model: Option<String>,
+    /// Provider to use for transcription
+    #[arg(short, long)]
+    pub provider: Option<String>,
+    /// Language of the audio (ISO-639-1 format, e.g., "en", "es")
+    #[arg(short = 'l', long)]
+    pub language: Option<String>,
+    /// Optional prompt to guide the transcription
+    #[arg(long)]
+    pub prompt: Option<String>,
+    /// Response format (json, text, srt, verbose_json, vtt)
+    #[arg(short = 'f', long, default_value = "text")]
+    pub format: String,
+    /// Temperature for transcription (0.0 to 1.0)
+    #[arg(long)]
+    pub temperature: Option<f32>,
+    /// Output file for transcription (optional, prints to stdout if not specified)
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// Enable debug/verbose logging
+    #[arg(short = 'd', long = "debug")]
+    pub debug: bool,
+}
+
+/// Arguments of `lc tts`
+#[derive(Args)]
+pub struct TtsArgs {
+    /// Text to convert to speech
+    pub text: String,
+    /// Model to use for TTS
+    #[arg(short, long)]
+    pub model: Option<String>,
+    /// Provider to use for TTS
+    #[arg(short, long)]
+    pub provider: Option<String>,
+    /// Voice to use (e.g., alloy, echo, fable, onyx, nova, shimmer)
+    #[arg(short = 'v', long, default_value = "alloy")]
+    pub voice: String,
+    /// Output audio format (mp3, opus, aac, flac, wav, pcm)
+    #[arg(short = 'f', long, default_value = "mp3")]
+    pub format: String,
+    /// Speech speed (0.25 to 4.0)
+    #[arg(short = 's', long)]
+    pub speed: Option<f32>,
+    /// Output file for audio (required)
+    #[arg(short, long)]
+    pub output: String,
+    /// Enable debug/verbose logging
+    #[arg(short = 'd', long = "debug")]
+    pub debug: bool,
+}
+
+/// Arguments of `lc upgrade-self`
+#[derive(Args)]
+pub struct UpgradeSelfArgs {
+    /// Only list the available versions
+    #[arg(long)]
+    pub check: bool,
+    /// Install this version instead of the latest (e.g. 0.1.4)
+    #[arg(long)]
+    pub version: Option<String>,
+    /// Skip the confirmation prompt
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+    /// Reinstall the current version, or downgrade to an older latest release
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Arguments of `lc models`
+#[derive(Args)]
+pub struct ModelsArgs {
+    #[command(subcommand)]
+    pub command: Option<ModelsCommands>,
+    /// Search query for models (case-insensitive)
+    #[arg(short = 'q', long = "query")]
+    pub query: Option<String>,
+    /// Filter models that support tools/function calling
+    #[arg(long = "tools")]
+    pub tools: bool,
+    /// Filter models that support reasoning
+    #[arg(long = "reasoning")]
+    pub reasoning: bool,
+    /// Filter models that support vision
+    #[arg(long = "vision")]
+    pub vision: bool,
+    /// Filter models that support audio
+    #[arg(long = "audio")]
+    pub audio: bool,
+    /// Filter models that support code generation
+    #[arg(long = "code")]
+    pub code: bool,
+    /// Hide models whose metadata doesn't state the filtered capabilities
+    #[arg(long = "strict-filters", global = true)]
+    pub strict_filters: bool,
+    /// Filter models with minimum context length (e.g., 128k)
+    #[arg(long = "ctx")]
+    pub context_length: Option<String>,
+    /// Filter models with minimum input token length (e.g., 128k)
+    #[arg(long = "input")]
+    pub input_length: Option<String>,
+    /// Filter models with minimum output token length (e.g., 128k)
+    #[arg(long = "output")]
+    pub output_length: Option<String>,
+    /// Filter models with maximum input price per million tokens
+    #[arg(long = "input-price")]
+    pub input_price: Option<f64>,
+    /// Filter models with maximum output price per million tokens
+    #[arg(long = "output-price")]
+    pub output_price: Option<f64>,
+}
+
+/// Arguments of `lc embed`
+#[derive(Args)]
+pub struct EmbedArgs {
+    /// Model to use for embeddings
+    #[arg(short, long)]
+    pub model: String,
+    /// Provider to use for embeddings
+    #[arg(short, long)]
+    pub provider: Option<String>,
+    /// Vector database name to store embeddings
+    #[arg(short = 'v', long = "vectordb", value_name = "VECTORDB")]
+    pub database: Option<String>,
+    /// Files to embed (supports glob patterns, including PDFs with 'pdf' feature)
+    #[arg(short = 'f', long = "files")]
+    pub files: Vec<String>,
+    /// Text to embed (optional if files are provided)
+    pub text: Option<String>,
+    /// Enable debug/verbose logging
+    #[arg(short = 'd', long = "debug")]
+    pub debug: bool,
+    /// Chat model that tags and summarizes every chunk (provider:model or alias)
+    #[arg(long = "tag-model", value_name = "MODEL")]
+    pub tag_model: Option<String>,
+    /// Where files are split into chunks
+    #[arg(long = "chunk-strategy", value_enum, default_value_t)]
+    pub chunk_strategy: crate::data::chunking::ChunkStrategy,
+    /// Maximum chunk length in characters
+    #[arg(long = "chunk-size", default_value_t = crate::data::chunking::DEFAULT_CHUNK_SIZE)]
+    pub chunk_size: usize,
+    /// Characters repeated at the start of the next chunk
+    #[arg(long = "chunk-overlap", default_value_t = crate::data::chunking::DEFAULT_CHUNK_OVERLAP)]
+    pub chunk_overlap: usize,
+    /// Skip files and chunks an earlier, interrupted run already stored in the database
+    #[arg(long, requires = "database")]
+    pub resume: bool,
 }
 
 // Command enums
 #[derive(Subcommand)]
 pub enum ModelsCommands {
     /// Refresh the models cache (alias: r)
+    ///
+    /// Refreshes every provider with credentials, or only the providers given with
+    /// the global -p (comma-separated), e.g. `lc -p openrouter models refresh`
     #[command(alias = "r")]
-    Refresh,
+    Refresh {
+        /// Number of providers to fetch in parallel
+        #[arg(short = 'j', long = "jobs", default_value_t = crate::unified_cache::DEFAULT_REFRESH_JOBS)]
+        jobs: usize,
+    },
     /// Show cache information (alias: i)
     #[command(alias = "i")]
     Info,
+    /// Strip raw JSON fields the extraction rules don't use from the cache files (alias: c)
+    #[command(alias = "c")]
+    Compact,
     /// Dump raw /models responses to JSON files (alias: d)
     #[command(alias = "d")]
     Dump,
@@ -455,6 +955,21 @@ pub enum ModelsCommands {
         #[arg(short = 't', long = "tag")]
         tags: String,
     },
+    /// Find a model across configured providers (alias: fd)
+    #[command(alias = "fd")]
+    Find {
+        /// Text to search for in model IDs and names
+        query: String,
+        /// Also search the provider registry for providers that are not installed
+        #[arg(short = 'a', long = "available")]
+        available: bool,
+    },
+    /// Check default model, aliases and templates for deprecated models (alias: dep)
+    #[command(alias = "dep")]
+    Deprecated,
+    /// Show the friendly names that resolve to each provider's current model IDs (alias: al)
+    #[command(alias = "al")]
+    Aliases,
 }
 
 #[derive(Subcommand)]
@@ -489,6 +1004,22 @@ pub enum ModelsTagsCommands {
         /// Extraction rule (JQ-style path or search pattern)
         rule: String,
     },
+    /// Export tag rules and model paths to share them (alias: e)
+    #[command(alias = "e")]
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long = "output")]
+        output: Option<String>,
+    },
+    /// Import tag rules and model paths exported with 'lc models tags export' (alias: i)
+    #[command(alias = "i")]
+    Import {
+        /// File to import
+        file: String,
+        /// Replace the local rules instead of merging
+        #[arg(long = "replace")]
+        replace: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -520,7 +1051,11 @@ pub enum TemplateCommands {
         /// Template name
         name: String,
         /// Template prompt content
-        prompt: String,
+        #[arg(required_unless_present = "file")]
+        prompt: Option<String>,
+        /// Read the prompt from this file each time the template is used
+        #[arg(long = "file", value_name = "PATH", conflicts_with = "prompt")]
+        file: Option<String>,
     },
     /// Remove a template (alias: d)
     #[command(alias = "d")]
@@ -533,6 +1068,68 @@ pub enum TemplateCommands {
     List,
 }
 
+#[derive(Subcommand)]
+pub enum StyleCommands {
+    /// Create or replace a style profile (alias: s)
+    #[command(alias = "s")]
+    Set {
+        /// Style name
+        name: String,
+        /// Tone of the answers, e.g. "friendly and concise"
+        #[arg(long = "tone")]
+        tone: Option<String>,
+        /// Language of the answers, e.g. "German"
+        #[arg(long = "language")]
+        language: Option<String>,
+        /// Formatting rule (repeatable)
+        #[arg(long = "format", value_name = "RULE")]
+        formatting: Vec<String>,
+        /// Phrase the answers must not use (repeatable)
+        #[arg(long = "ban", value_name = "PHRASE")]
+        banned_phrases: Vec<String>,
+        /// Check the language of answers and ask again once when it differs
+        #[arg(long = "check-language", requires = "language")]
+        check_language: bool,
+    },
+    /// Remove a style profile (alias: d)
+    #[command(alias = "d")]
+    Delete {
+        /// Style name to remove
+        name: String,
+    },
+    /// List style profiles (alias: l)
+    #[command(alias = "l")]
+    List,
+    /// Show the instructions a style adds to the system prompt (alias: sh)
+    #[command(alias = "sh")]
+    Show {
+        /// Style name
+        name: String,
+    },
+    /// Set the style used when --style is not given (alias: def)
+    #[command(alias = "def")]
+    Default {
+        /// Style name
+        #[arg(required_unless_present = "clear")]
+        name: Option<String>,
+        /// Stop using a default style
+        #[arg(long = "clear", conflicts_with = "name")]
+        clear: bool,
+    },
+    /// Use a style whenever a template is used (alias: t)
+    #[command(alias = "t")]
+    Template {
+        /// Template name
+        template: String,
+        /// Style name
+        #[arg(required_unless_present = "clear")]
+        style: Option<String>,
+        /// Remove the template's style
+        #[arg(long = "clear", conflicts_with = "style")]
+        clear: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ProviderCommands {
     /// Install a provider from the registry (alias: i)
@@ -543,6 +1140,9 @@ pub enum ProviderCommands {
         /// Force reinstall even if already installed
         #[arg(short = 'f', long = "force")]
         force: bool,
+        /// Skip asking for provider vars, the test request and the quick start
+        #[arg(long = "skip-setup")]
+        skip_setup: bool,
     },
     /// Update installed providers (alias: up)
     #[command(alias = "up")]
@@ -567,12 +1167,19 @@ pub enum ProviderCommands {
         tag: Option<String>,
     },
     /// Add a new provider (alias: a)
+    ///
+    /// With --builtin, add a provider that lc implements itself: `mock` answers
+    /// requests offline with canned or templated responses.
     #[command(alias = "a")]
     Add {
         /// Provider name
         name: String,
         /// Provider endpoint URL
-        url: String,
+        #[arg(required_unless_present = "builtin")]
+        url: Option<String>,
+        /// Add the built-in provider called <NAME> (available: mock)
+        #[arg(long = "builtin", conflicts_with_all = ["url", "models_path", "chat_path"])]
+        builtin: bool,
         /// Custom models endpoint path (default: /models)
         #[arg(short = 'm', long = "models-path")]
         models_path: Option<String>,
@@ -580,6 +1187,20 @@ pub enum ProviderCommands {
         #[arg(short = 'c', long = "chat-path")]
         chat_path: Option<String>,
     },
+    /// Copy a provider's configuration under a new name (alias: cl)
+    #[command(alias = "cl")]
+    Clone {
+        /// Provider to copy
+        source: String,
+        /// Name of the new provider
+        name: String,
+        /// Endpoint URL for the new provider (default: same as the source)
+        #[arg(short = 'e', long = "endpoint")]
+        endpoint: Option<String>,
+        /// Also copy the source provider's API key and other stored secrets
+        #[arg(long = "with-key")]
+        with_key: bool,
+    },
     /// Update an existing provider (alias: u)
     #[command(alias = "u")]
     Update {
@@ -597,6 +1218,12 @@ pub enum ProviderCommands {
     /// List all providers (alias: l)
     #[command(alias = "l")]
     List,
+    /// Show the latest rate limits and quotas reported by providers (alias: st)
+    #[command(alias = "st")]
+    Status {
+        /// Provider name (shows all configured providers if not specified)
+        name: Option<String>,
+    },
     /// List available models for a provider (alias: m)
     #[command(alias = "m")]
     Models {
@@ -614,6 +1241,16 @@ pub enum ProviderCommands {
         #[command(subcommand)]
         command: HeaderCommands,
     },
+    /// Check a provider's path templates, vars and request templates, and show the URLs
+    /// they build, without sending a request (alias: li)
+    #[command(alias = "li")]
+    Lint {
+        /// Provider name
+        name: String,
+        /// Model to build the URLs for (default: the provider's first listed model)
+        #[arg(long = "sample-model", value_name = "MODEL")]
+        sample_model: Option<String>,
+    },
     /// Manage provider variables for path templating (alias: v)
     #[command(alias = "v")]
     Vars {
@@ -622,6 +1259,21 @@ pub enum ProviderCommands {
         #[command(subcommand)]
         command: ProviderVarsCommands,
     },
+    /// Set the organization and project sent with requests (alias: o)
+    #[command(alias = "o")]
+    Org {
+        /// Provider name
+        provider: String,
+        /// Organization ID, sent as the OpenAI-Organization header
+        #[arg(long = "organization", visible_alias = "org")]
+        organization: Option<String>,
+        /// Project ID, sent as the OpenAI-Project header
+        #[arg(long = "project")]
+        project: Option<String>,
+        /// Remove the organization and project
+        #[arg(long = "clear", conflicts_with_all = ["organization", "project"])]
+        clear: bool,
+    },
     /// Set token URL for a provider (alias: t)
     #[command(alias = "t")]
     TokenUrl {
@@ -638,6 +1290,55 @@ pub enum ProviderCommands {
         #[command(subcommand)]
         command: ProviderPathCommands,
     },
+    /// Manage load-balancing groups of providers (alias: g)
+    #[command(alias = "g")]
+    Group {
+        #[command(subcommand)]
+        command: ProviderGroupCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProviderGroupCommands {
+    /// Create or replace a group (alias: a)
+    #[command(alias = "a")]
+    Add {
+        /// Group name, used like a provider name (e.g. -p openai-pool)
+        name: String,
+        /// Backend provider, optionally with a weight as provider=weight (repeatable)
+        #[arg(short = 'b', long = "backend", required = true)]
+        backends: Vec<String>,
+        /// Selection strategy: round-robin or weighted
+        #[arg(short = 's', long = "strategy", default_value = "round-robin")]
+        strategy: String,
+        /// Consecutive failures before a backend is ejected
+        #[arg(long = "max-failures", default_value_t = 3)]
+        max_failures: u32,
+        /// Seconds an ejected backend is skipped
+        #[arg(long = "eject-secs", default_value_t = 60)]
+        eject_secs: u64,
+    },
+    /// Remove a group (alias: r)
+    #[command(alias = "r")]
+    Remove {
+        /// Group name
+        name: String,
+    },
+    /// List groups (alias: l)
+    #[command(alias = "l")]
+    List,
+    /// Show backend health for a group, or all groups (alias: s)
+    #[command(alias = "s")]
+    Status {
+        /// Group name
+        name: Option<String>,
+    },
+    /// Clear failure counts and ejections (alias: rs)
+    #[command(alias = "rs")]
+    Reset {
+        /// Group name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -678,6 +1379,9 @@ pub enum ProviderPathCommands {
         /// Embeddings path
         #[arg(short = 'e', long = "embeddings")]
         embeddings_path: Option<String>,
+        /// Rerank path
+        #[arg(short = 'r', long = "rerank")]
+        rerank_path: Option<String>,
     },
     /// Delete a provider path (alias: d)
     #[command(alias = "d")]
@@ -694,6 +1398,9 @@ pub enum ProviderPathCommands {
         /// Delete embeddings path
         #[arg(short = 'e', long = "embeddings")]
         embeddings: bool,
+        /// Delete rerank path
+        #[arg(short = 'r', long = "rerank")]
+        rerank: bool,
     },
     /// List all provider paths (alias: l)
     #[command(alias = "l")]
@@ -746,46 +1453,254 @@ pub enum KeyCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// Back up logs.db and vector databases now (alias: c)
+    #[command(alias = "c")]
+    Create,
+    /// List backups (alias: l)
+    #[command(alias = "l")]
+    List,
+    /// Restore databases from a backup (alias: r)
+    #[command(alias = "r")]
+    Restore {
+        /// Backup id (defaults to the most recent backup)
+        id: Option<String>,
+        /// Restore only this database (e.g. logs.db or embeddings/docs.db)
+        #[arg(short = 'd', long = "database")]
+        database: Option<String>,
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+    /// Run PRAGMA integrity_check on all databases (alias: ch)
+    #[command(alias = "ch")]
+    Check,
+    /// Show or change backup settings (alias: cfg)
+    #[command(alias = "cfg")]
+    Config {
+        /// Hours between automatic backups
+        #[arg(long = "interval-hours")]
+        interval_hours: Option<u64>,
+        /// Number of backups to keep
+        #[arg(long = "keep")]
+        keep: Option<usize>,
+        /// Enable automatic backups
+        #[arg(long = "enable", conflicts_with = "disable")]
+        enable: bool,
+        /// Disable automatic backups
+        #[arg(long = "disable")]
+        disable: bool,
+        /// Turn startup integrity checks on or off
+        #[arg(long = "integrity-check")]
+        integrity_check: Option<bool>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum LogCommands {
     /// Show all logs (alias: sh)
     #[command(alias = "sh")]
-    Show {
-        /// Show minimal table format
-        #[arg(long)]
-        minimal: bool,
-    },
+    Show(Box<LogShowArgs>),
     /// Show recent logs (alias: r)
     #[command(alias = "r")]
-    Recent {
-        #[command(subcommand)]
-        command: Option<RecentCommands>,
-        /// Number of recent entries to show
-        #[arg(short, long, default_value = "10")]
-        count: usize,
+    Recent(Box<LogRecentArgs>),
+    /// Show a whole session, every exchange in full (alias: g)
+    #[command(alias = "g")]
+    Get {
+        /// Session ID or unique prefix (defaults to the current session)
+        session: Option<String>,
+        /// Print the session as a JSON object with its exchanges, oldest first
+        #[arg(long)]
+        json: bool,
     },
     /// Show current session logs (alias: c)
     #[command(alias = "c")]
     Current,
+    /// List sessions with their names, message counts and last activity (alias: ss)
+    #[command(alias = "ss")]
+    Sessions {
+        /// Number of sessions to show
+        #[arg(short = 'n', long = "count", default_value = "20")]
+        count: usize,
+    },
     /// Show database statistics (alias: s)
     #[command(alias = "s")]
     Stats,
     /// Purge all logs (alias: p)
     #[command(alias = "p")]
-    Purge {
-        /// Confirm purge without prompt
-        #[arg(long)]
-        yes: bool,
-        /// Purge logs older than N days
-        #[arg(long)]
-        older_than_days: Option<u32>,
-        /// Keep only the most recent N entries
-        #[arg(long)]
-        keep_recent: Option<usize>,
-        /// Purge when database exceeds N MB
-        #[arg(long)]
-        max_size_mb: Option<u64>,
+    Purge(Box<LogPurgeArgs>),
+    /// Export a session as a self-contained HTML page (alias: sr)
+    #[command(alias = "sr")]
+    Share(Box<LogShareArgs>),
+    /// Export a whole session, with token counts and models, to archive or share (alias: ex)
+    #[command(alias = "ex")]
+    Export(Box<LogExportArgs>),
+    /// Restore sessions from JSON exports or prune archives (alias: im)
+    #[command(alias = "im")]
+    Import {
+        /// JSON files written by `lc logs export` or `lc logs prune`
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// Import conversations from a ChatGPT data export (alias: ig)
+    #[command(name = "import-chatgpt", alias = "ig")]
+    ImportChatgpt {
+        /// The export zip, or its conversations.json
+        file: String,
     },
+    /// Import conversations from a Claude data export (alias: ic)
+    #[command(name = "import-claude", alias = "ic")]
+    ImportClaude {
+        /// The export zip, or its conversations.json
+        file: String,
+    },
+    /// Review sessions by age and size, and delete or archive them selectively (alias: pr)
+    #[command(alias = "pr")]
+    Prune(Box<LogPruneArgs>),
+    /// Ask a question about your chat history, answered from the relevant past turns (alias: a)
+    #[command(alias = "a")]
+    Ask(Box<LogAskArgs>),
+    /// Export sessions as chat-format fine-tuning JSONL, one conversation per line (alias: ft)
+    #[command(alias = "ft")]
+    ExportFinetune(Box<LogExportFinetuneArgs>),
+}
+
+/// Arguments of `lc logs show`
+#[derive(Args)]
+pub struct LogShowArgs {
+    /// Show minimal table format
+    #[arg(long)]
+    pub minimal: bool,
+    /// Only show entries whose --metadata (or user) matches key=value (repeatable)
+    #[arg(long = "filter", value_name = "KEY=VALUE")]
+    pub filters: Vec<String>,
+    /// Only show the session with this ID or unique prefix
+    #[arg(long = "session", value_name = "SESSION")]
+    pub session: Option<String>,
+    /// Only entries logged on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long = "since")]
+    pub since: Option<String>,
+    /// Only entries logged on or before this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long = "until")]
+    pub until: Option<String>,
+    /// Only entries whose question or answer contains this text (case-insensitive)
+    #[arg(long = "grep", value_name = "TEXT")]
+    pub grep: Option<String>,
+}
+
+/// Arguments of `lc logs recent`
+#[derive(Args)]
+pub struct LogRecentArgs {
+    #[command(subcommand)]
+    pub command: Option<RecentCommands>,
+    /// Number of recent entries to show
+    #[arg(short, long, short_alias = 'n', default_value = "10")]
+    pub count: usize,
+    /// Print the entries as a JSON array of complete records, newest first (with a subcommand, its value as JSON)
+    #[arg(long)]
+    pub json: bool,
+    /// Only entries logged on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long = "since")]
+    pub since: Option<String>,
+    /// Only entries logged on or before this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long = "until")]
+    pub until: Option<String>,
+    /// Only entries whose question or answer contains this text (case-insensitive)
+    #[arg(long = "grep", value_name = "TEXT")]
+    pub grep: Option<String>,
+}
+
+/// Arguments of `lc logs purge`
+#[derive(Args)]
+pub struct LogPurgeArgs {
+    /// Confirm purge without prompt
+    #[arg(long)]
+    pub yes: bool,
+    /// Purge logs older than N days
+    #[arg(long)]
+    pub older_than_days: Option<u32>,
+    /// Keep only the most recent N entries
+    #[arg(long)]
+    pub keep_recent: Option<usize>,
+    /// Purge when database exceeds N MB
+    #[arg(long)]
+    pub max_size_mb: Option<u64>,
+}
+
+/// Arguments of `lc logs share`
+#[derive(Args)]
+pub struct LogShareArgs {
+    /// Session ID or unique prefix (defaults to the current session)
+    pub session: Option<String>,
+    /// File to write the HTML page to
+    #[arg(long = "html", value_name = "FILE")]
+    pub html: String,
+    /// Leave system prompts out of the page
+    #[arg(long = "redact-system-prompt")]
+    pub redact_system_prompt: bool,
+}
+
+/// Arguments of `lc logs export`
+#[derive(Args)]
+pub struct LogExportArgs {
+    /// Session ID or unique prefix (defaults to the current session)
+    #[arg(long = "session", value_name = "SESSION")]
+    pub session: Option<String>,
+    /// Output format
+    #[arg(short = 'f', long = "format", value_enum, default_value_t = SessionExportFormat::Json)]
+    pub format: SessionExportFormat,
+    /// File to write to (default: stdout)
+    #[arg(short = 'o', long = "out", value_name = "FILE")]
+    pub out: Option<String>,
+}
+
+/// Arguments of `lc logs prune`
+#[derive(Args)]
+pub struct LogPruneArgs {
+    /// Walk the sessions and choose what to keep, delete or archive
+    #[arg(short, long)]
+    pub interactive: bool,
+    /// Only consider sessions inactive for at least N days
+    #[arg(long)]
+    pub older_than_days: Option<u32>,
+    /// Directory for archived sessions (default: <config dir>/archive)
+    #[arg(long, value_name = "DIR")]
+    pub archive_dir: Option<String>,
+}
+
+/// Arguments of `lc logs ask`
+#[derive(Args)]
+pub struct LogAskArgs {
+    /// The question
+    #[arg(required = true)]
+    pub question: Vec<String>,
+    /// Embedding model of the history index (needed the first time; changing it re-indexes)
+    #[arg(short = 'e', long = "embedding-model")]
+    pub embedding_model: Option<String>,
+    /// Number of past turns to answer from
+    #[arg(short = 'n', long = "count", default_value = "8")]
+    pub count: usize,
+}
+
+/// Arguments of `lc logs export-finetune`
+#[derive(Args)]
+pub struct LogExportFinetuneArgs {
+    /// Sessions to export: all, current, or comma-separated IDs or unique prefixes
+    #[arg(long, value_name = "SESSIONS", default_value = "all")]
+    pub sessions: String,
+    /// Only export entries whose --metadata (or user) matches key=value (repeatable)
+    #[arg(long = "filter", value_name = "KEY=VALUE")]
+    pub filters: Vec<String>,
+    /// File to write the JSONL to
+    #[arg(long, value_name = "FILE")]
+    pub out: String,
+    /// Replace e-mail addresses, phone numbers, IP addresses and card numbers with placeholders
+    #[arg(long = "scrub-pii")]
+    pub scrub_pii: bool,
+    /// Leave system prompts out of the examples
+    #[arg(long = "no-system")]
+    pub no_system: bool,
 }
 
 #[derive(Subcommand)]
@@ -805,6 +1720,9 @@ pub enum RecentCommands {
     /// Get session ID of last interaction (alias: s)
     #[command(alias = "s")]
     Session,
+    /// Get the reasoning trace of the last answer (alias: r)
+    #[command(alias = "r")]
+    Reasoning,
 }
 
 #[derive(Subcommand)]
@@ -837,20 +1755,43 @@ pub enum UsageCommands {
         #[arg(short = 'n', long = "count", default_value = "5")]
         count: usize,
     },
-    /// Show top models by usage (alias: models)
-    #[command(alias = "models")]
+    /// Show top models by usage
     Models {
         /// Number of models to show
         #[arg(short = 'n', long = "count", default_value = "10")]
         count: usize,
     },
+    /// Show an hour-of-day / day-of-week activity heatmap (alias: hm)
+    #[command(alias = "hm")]
+    Heatmap,
+    /// Show usage per provider with stacked daily charts (alias: p)
+    #[command(alias = "p")]
+    Providers {
+        /// Number of days to show
+        #[arg(short = 'n', long = "count", default_value = "14")]
+        count: usize,
+    },
+    /// Show provider latency recorded with --timings (alias: l)
+    #[command(alias = "l")]
+    Latency {
+        /// Number of models to show
+        #[arg(short = 'n', long = "count", default_value = "10")]
+        count: usize,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum AnswerCommands {
     /// Extract code blocks from last answer (alias: c)
     #[command(alias = "c")]
-    Code,
+    Code {
+        /// Write each block to a file in this directory, named from filename hints or the fence language
+        #[arg(long = "write-to", value_name = "DIR")]
+        write_to: Option<String>,
+        /// Overwrite files that already exist
+        #[arg(long = "force", requires = "write_to")]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -876,6 +1817,25 @@ pub enum ConfigCommands {
     /// Show configuration directory path (alias: p)
     #[command(alias = "p")]
     Path,
+    /// Write config, providers, templates and aliases to an encrypted bundle file
+    Export {
+        /// Bundle file to write, e.g. bundle.lc
+        path: String,
+        /// Include API keys (keys.toml)
+        #[arg(long = "with-keys")]
+        with_keys: bool,
+        /// Include the logs database
+        #[arg(long = "with-logs")]
+        with_logs: bool,
+    },
+    /// Restore config from a bundle written by `lc config export`
+    Import {
+        /// Bundle file to read
+        path: String,
+        /// Restore everything in the bundle without asking
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -922,6 +1882,24 @@ pub enum SetCommands {
         /// Stream output (true/false)
         value: String,
     },
+    /// Set the maximum estimated cost of a single request in USD (alias: rl)
+    #[command(alias = "rl")]
+    RequestLimit {
+        /// Limit in USD (e.g., 0.50)
+        value: String,
+    },
+    /// Set the maximum estimated spend per day in USD (alias: dl)
+    #[command(alias = "dl")]
+    DailyLimit {
+        /// Limit in USD (e.g., 5)
+        value: String,
+    },
+    /// Set the timezone of timestamps shown by `lc logs` (alias: tz)
+    #[command(alias = "tz")]
+    Timezone {
+        /// "local", "UTC" or an IANA name (e.g., Europe/Berlin)
+        value: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -947,6 +1925,15 @@ pub enum GetCommands {
     /// Get streaming output preference (alias: st)
     #[command(alias = "st")]
     Stream,
+    /// Get the per-request spending limit (alias: rl)
+    #[command(alias = "rl")]
+    RequestLimit,
+    /// Get the daily spending limit (alias: dl)
+    #[command(alias = "dl")]
+    DailyLimit,
+    /// Get the timezone of timestamps shown by `lc logs` (alias: tz)
+    #[command(alias = "tz")]
+    Timezone,
 }
 
 #[derive(Subcommand)]
@@ -972,6 +1959,15 @@ pub enum DeleteCommands {
     /// Delete streaming output preference (alias: st)
     #[command(alias = "st")]
     Stream,
+    /// Delete the per-request spending limit (alias: rl)
+    #[command(alias = "rl")]
+    RequestLimit,
+    /// Delete the daily spending limit (alias: dl)
+    #[command(alias = "dl")]
+    DailyLimit,
+    /// Delete the timezone of timestamps shown by `lc logs` (alias: tz)
+    #[command(alias = "tz")]
+    Timezone,
 }
 
 #[derive(Subcommand)]
@@ -982,7 +1978,7 @@ pub enum VectorCommands {
     /// Create a new vector database (alias: c)
     #[command(alias = "c")]
     Create {
-        /// Database name
+        /// Name of the new database
         name: String,
     },
     /// Delete a vector database (alias: d)
@@ -1015,6 +2011,93 @@ pub enum VectorCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Group stored embeddings into clusters with generated labels (alias: cu)
+    #[command(alias = "cu")]
+    Cluster {
+        /// Database name
+        name: String,
+        /// Number of clusters
+        #[arg(short = 'k', long = "clusters", default_value = "10")]
+        k: usize,
+        /// Skip generating cluster labels with the model
+        #[arg(long = "no-labels")]
+        no_labels: bool,
+    },
+    /// Remove near-duplicate embeddings (alias: dd)
+    #[command(alias = "dd")]
+    Dedupe {
+        /// Database name
+        name: String,
+        /// Cosine similarity at or above which entries count as duplicates
+        #[arg(long, default_value = "0.97")]
+        threshold: f64,
+        /// Show the duplicates without removing them
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+        /// Confirm removal without prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Re-embed all entries with another model, resuming an interrupted run (alias: mg)
+    #[command(alias = "mg")]
+    Migrate {
+        /// Database name
+        name: String,
+        /// Embedding model to migrate to (provider:model or alias)
+        #[arg(long = "to", value_name = "MODEL", required_unless_present = "abort")]
+        to: Option<String>,
+        /// Drop the migration in progress and keep the current vectors
+        #[arg(long, conflicts_with = "to")]
+        abort: bool,
+    },
+    /// Serve a retrieval API (/query, /add, /delete) for a database (alias: sv)
+    #[command(alias = "sv")]
+    Serve {
+        /// Database name
+        name: String,
+        /// Port to listen on
+        #[arg(long = "port", default_value = "8090")]
+        port: u16,
+        /// Host to bind to
+        #[arg(long = "host", default_value = "127.0.0.1")]
+        host: String,
+        /// API key required as a Bearer token
+        #[arg(
+            short = 'k',
+            long = "key",
+            env = "LC_VECTORS_API_KEY",
+            hide_env_values = true
+        )]
+        api_key: Option<String>,
+        /// Serve without an API key (only on a loopback host). By default a key is
+        /// generated when none is given
+        #[arg(long = "no-auth", conflicts_with = "api_key")]
+        no_auth: bool,
+        /// Origin allowed to call the API from a browser (repeatable; none by default)
+        #[arg(long = "cors-origin", value_name = "ORIGIN")]
+        cors_origins: Vec<String>,
+    },
+    /// Archive a database with a manifest of its model, dimensions and checksums (alias: bk)
+    #[command(alias = "bk")]
+    Backup {
+        /// Database name
+        name: String,
+        /// Archive to write (default: <name>.vectors.tar.gz)
+        #[arg(short = 'o', long = "out")]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Restore a database from an archive after verifying it (alias: rs)
+    #[command(alias = "rs")]
+    Restore {
+        /// Archive written by 'lc vectors backup'
+        file: std::path::PathBuf,
+        /// Restore under another name
+        #[arg(long = "as", value_name = "NAME")]
+        name: Option<String>,
+        /// Replace an existing database of the same name
+        #[arg(short = 'f', long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1054,6 +2137,12 @@ pub enum SyncCommands {
         /// Encrypt files before uploading
         #[arg(short = 'e', long = "encrypted")]
         encrypted: bool,
+        /// Also sync optional data (comma-separated: vectors, models)
+        #[arg(short = 'i', long = "include", value_enum, value_delimiter = ',')]
+        include: Vec<SyncInclude>,
+        /// Include API keys (keys.toml), encrypted with a separate passphrase
+        #[arg(long = "with-keys")]
+        with_keys: bool,
         /// Skip confirmation prompt
         #[arg(short = 'y', long = "yes")]
         yes: bool,
@@ -1065,6 +2154,12 @@ pub enum SyncCommands {
         /// Decrypt files after downloading
         #[arg(short = 'e', long = "encrypted")]
         encrypted: bool,
+        /// Also restore optional data (comma-separated: vectors, models)
+        #[arg(short = 'i', long = "include", value_enum, value_delimiter = ',')]
+        include: Vec<SyncInclude>,
+        /// Restore API keys (keys.toml) after verifying they decrypt correctly
+        #[arg(long = "with-keys")]
+        with_keys: bool,
         /// Skip confirmation prompt
         #[arg(short = 'y', long = "yes")]
         yes: bool,
@@ -1115,8 +2210,12 @@ pub enum SearchProviderCommands {
     Add {
         /// Provider name
         name: String,
-        /// Provider URL (auto-detects type)
-        url: String,
+        /// Provider URL (auto-detects type; defaults to the hosted API of --type)
+        #[arg(required_unless_present = "provider_type")]
+        url: Option<String>,
+        /// Provider type, for URLs it cannot be detected from (proxies, self-hosted)
+        #[arg(long = "type", value_enum)]
+        provider_type: Option<crate::search::SearchProviderType>,
     },
     /// List all search providers (alias: l)
     #[command(alias = "l")]
@@ -1203,4 +2302,18 @@ pub enum McpCommands {
         /// Server name (optional, shows all if not specified)
         name: Option<String>,
     },
+    /// Clear cached tool results for the current session (alias: cc)
+    #[command(alias = "cc")]
+    ClearCache {
+        /// Only clear results from this server
+        #[arg(short = 's', long = "server")]
+        server: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PluginCommands {
+    /// List the plugins found on PATH (alias: l)
+    #[command(alias = "l")]
+    List,
 }
@@ -0,0 +1,301 @@
+//! `lc doctor`: check the configuration for problems before they show up as failed requests
+
+use crate::config::{self, ProviderConfig, ORGANIZATION_HEADER, PROJECT_HEADER};
+use crate::core::request_signing;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    pub(crate) fn warning(message: impl Into<String>) -> Self {
+        Finding {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn error(message: impl Into<String>) -> Self {
+        Finding {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+/// Handle `lc doctor`
+pub fn handle() -> Result<()> {
+    println!("\n{}", "Configuration:".bold().blue());
+    let config = match config::Config::load() {
+        Ok(config) => {
+            println!("  {} config.toml loads", sym("✓").green());
+            config
+        }
+        Err(e) => {
+            println!("  {} config.toml: {}", sym("✗").red(), e);
+            anyhow::bail!("The configuration could not be loaded");
+        }
+    };
+    let keys = crate::keys::KeysConfig::load().unwrap_or_else(|_| crate::keys::KeysConfig::new());
+
+    let mut findings = Vec::new();
+    for finding in check_references(&config) {
+        print_finding("config", &finding);
+        findings.push(finding);
+    }
+
+    println!("\n{}", "Providers:".bold().blue());
+    let mut providers: Vec<_> = config.providers.iter().collect();
+    providers.sort_by(|a, b| a.0.cmp(b.0));
+    if providers.is_empty() {
+        println!("  No providers configured");
+    }
+    for (name, provider) in providers {
+        // HMAC providers may sign with a secret stored under another name
+        let has_auth = match provider.auth_type.as_deref() {
+            Some(request_signing::AUTH_TYPE) => {
+                let key = provider
+                    .hmac
+                    .as_ref()
+                    .map_or(name.as_str(), |hmac| hmac.key_name(name));
+                keys.api_keys.contains_key(key) || keys.tokens.contains_key(key)
+            }
+            _ => keys.has_auth(name),
+        };
+        let provider_findings = check_provider(provider, has_auth);
+        if provider_findings.is_empty() {
+            println!("  {} {}", sym("✓").green(), name.bold());
+        }
+        for finding in provider_findings {
+            print_finding(name, &finding);
+            findings.push(finding);
+        }
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    println!();
+    if errors > 0 {
+        anyhow::bail!("{} error(s), {} warning(s)", errors, warnings);
+    }
+    if warnings > 0 {
+        println!("{} No errors, {} warning(s)", sym("✓").green(), warnings);
+    } else {
+        println!("{} No problems found", sym("✓").green());
+    }
+    Ok(())
+}
+
+pub(crate) fn print_finding(subject: &str, finding: &Finding) {
+    let symbol = match finding.severity {
+        Severity::Warning => sym("⚠").yellow(),
+        Severity::Error => sym("✗").red(),
+    };
+    println!("  {} {}: {}", symbol, subject.bold(), finding.message);
+}
+
+/// The default provider and aliases must name configured providers
+fn check_references(config: &config::Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let known = |provider: &str| {
+        config.has_provider(provider) || crate::provider_groups::is_group(provider)
+    };
+    if let Some(provider) = &config.default_provider {
+        if !known(provider) {
+            findings.push(Finding::error(format!(
+                "default provider '{}' is not configured",
+                provider
+            )));
+        }
+    }
+    let mut aliases: Vec<_> = config.aliases.iter().collect();
+    aliases.sort();
+    for (alias, target) in aliases {
+        match target.split_once(':') {
+            Some((provider, _)) if known(provider) => {}
+            Some((provider, _)) => findings.push(Finding::error(format!(
+                "alias '{}' uses provider '{}', which is not configured",
+                alias, provider
+            ))),
+            None => findings.push(Finding::error(format!(
+                "alias '{}' should point to provider:model, not '{}'",
+                alias, target
+            ))),
+        }
+    }
+    findings
+}
+
+/// Problems with one provider's settings
+pub fn check_provider(provider: &ProviderConfig, has_auth: bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if crate::core::mock_provider::is_mock(&provider.endpoint) {
+        // Answered in-process: no URL to reach and no credentials to check
+        return findings;
+    }
+
+    match reqwest::Url::parse(&provider.endpoint) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        _ => findings.push(Finding::error(format!(
+            "endpoint '{}' is not an http(s) URL",
+            provider.endpoint
+        ))),
+    }
+
+    let header_auth = provider.headers.values().any(|v| !v.trim().is_empty())
+        && provider.headers.keys().any(|k| {
+            let k = k.to_lowercase();
+            k.contains("key") || k.contains("token") || k.contains("auth")
+        });
+    let hmac = provider.auth_type.as_deref() == Some(request_signing::AUTH_TYPE);
+    if hmac && !has_auth {
+        findings.push(Finding::error(
+            "no HMAC signing key in keys.toml (see the [hmac] key setting)",
+        ));
+    } else if !has_auth
+        && !header_auth
+        && provider.token_url.is_none()
+        && provider.auth_type.is_none()
+    {
+        findings.push(Finding::warning(
+            "no API key (add one with 'lc keys add <provider>' unless the provider needs none)",
+        ));
+    }
+
+    if provider.hmac.is_some() && provider.auth_type.as_deref() != Some(request_signing::AUTH_TYPE)
+    {
+        findings.push(Finding::warning(format!(
+            "[hmac] settings are ignored without auth_type = \"{}\"",
+            request_signing::AUTH_TYPE
+        )));
+    }
+
+    findings.extend(check_scope(provider));
+    findings
+}
+
+/// Organization and project settings: well-formed values, sent where they have an effect
+fn check_scope(provider: &ProviderConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let fields = [
+        ("organization", ORGANIZATION_HEADER, &provider.organization),
+        ("project", PROJECT_HEADER, &provider.project),
+    ];
+    let is_openai = reqwest::Url::parse(&provider.endpoint)
+        .ok()
+        .is_some_and(|url| url.host_str() == Some("api.openai.com"));
+
+    for (field, header, value) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        if value.trim().is_empty() || value.chars().any(|c| c.is_control()) {
+            findings.push(Finding::error(format!(
+                "{} '{}' is not a valid header value",
+                field,
+                value.escape_debug()
+            )));
+            continue;
+        }
+        if value.trim() != value {
+            findings.push(Finding::warning(format!(
+                "{} '{}' has surrounding whitespace",
+                field, value
+            )));
+        }
+        if provider.is_azure() {
+            findings.push(Finding::warning(format!(
+                "{} is not sent: Azure OpenAI is scoped by resource and deployment in the endpoint",
+                field
+            )));
+        } else if provider
+            .headers
+            .keys()
+            .any(|name| name.eq_ignore_ascii_case(header))
+        {
+            findings.push(Finding::warning(format!(
+                "{} is not sent: the custom '{}' header takes precedence",
+                field, header
+            )));
+        }
+    }
+
+    if is_openai {
+        if let Some(organization) = &provider.organization {
+            if !organization.starts_with("org-") {
+                findings.push(Finding::warning(format!(
+                    "OpenAI organization IDs start with 'org-', got '{}'",
+                    organization
+                )));
+            }
+        }
+        if let Some(project) = &provider.project {
+            if !project.starts_with("proj_") {
+                findings.push(Finding::warning(format!(
+                    "OpenAI project IDs start with 'proj_', got '{}'",
+                    project
+                )));
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(endpoint: &str) -> ProviderConfig {
+        toml::from_str(&format!("endpoint = \"{}\"\nmodels = []", endpoint)).unwrap()
+    }
+
+    #[test]
+    fn test_check_provider() {
+        let mut openai = provider("https://api.openai.com/v1");
+        assert_eq!(check_provider(&openai, true), Vec::new());
+        assert_eq!(
+            check_provider(&openai, false)[0].severity,
+            Severity::Warning
+        );
+
+        openai.organization = Some("org-abc".to_string());
+        openai.project = Some("myproject".to_string());
+        let findings = check_provider(&openai, true);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("proj_"));
+        assert_eq!(
+            openai.scope_headers(),
+            vec![
+                (ORGANIZATION_HEADER, "org-abc".to_string()),
+                (PROJECT_HEADER, "myproject".to_string())
+            ]
+        );
+
+        let mut azure = provider("https://contoso.openai.azure.com/openai/deployments/gpt4");
+        azure.organization = Some("org-abc".to_string());
+        assert!(azure.scope_headers().is_empty());
+        assert!(check_provider(&azure, true)[0].message.contains("Azure"));
+
+        let mut broken = provider("localhost:8080");
+        broken.project = Some("a\nb".to_string());
+        let errors: Vec<_> = check_provider(&broken, true)
+            .into_iter()
+            .filter(|f| f.severity == Severity::Error)
+            .collect();
+        assert_eq!(errors.len(), 2);
+    }
+}
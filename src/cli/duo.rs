@@ -0,0 +1,254 @@
+//! Two models conversing with each other (`lc duo`)
+
+use crate::utils::terminal::sym;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use colored::Colorize;
+
+use crate::config::Config;
+use crate::core::chat::{create_authenticated_client, send_chat_request_with_validation};
+use crate::core::redaction::redact;
+use crate::core::request_metadata::log_details;
+use crate::database::{ChatEntry, Database};
+
+/// Options of a `lc duo` conversation
+pub struct DuoOptions {
+    pub a: String,
+    pub b: String,
+    pub opening: String,
+    pub turns: u32,
+    pub judge: Option<String>,
+    pub system_prompt: Option<String>,
+    pub max_tokens: Option<String>,
+    pub temperature: Option<String>,
+}
+
+/// A participant of the conversation, resolved from a `provider:model` spec
+struct Participant {
+    label: &'static str,
+    spec: String,
+    provider: String,
+    model: String,
+}
+
+impl Participant {
+    fn resolve(config: &Config, label: &'static str, spec: &str) -> Result<Self> {
+        let (provider, model) =
+            crate::utils::resolve_model_and_provider(config, None, Some(spec.to_string()))?;
+        Ok(Self {
+            label,
+            spec: format!("{}:{}", provider, model),
+            provider,
+            model,
+        })
+    }
+
+    fn system_prompt(&self, other: &Participant, opening: &str) -> String {
+        format!(
+            "You are participant {} ({}) in a conversation with another AI model, participant {} ({}). \
+             The conversation started with: \"{}\". Reply directly to the other participant in a few \
+             short paragraphs, building on or challenging their points rather than repeating your own.",
+            self.label, self.spec, other.label, other.spec, opening
+        )
+    }
+}
+
+/// Handle `lc duo`: let two models take turns replying to each other
+pub async fn handle(options: DuoOptions) -> Result<()> {
+    if options.turns == 0 {
+        return Err(anyhow!("--turns must be at least 1"));
+    }
+
+    let mut config = Config::load()?;
+    let a = Participant::resolve(&config, "A", &options.a)?;
+    let b = Participant::resolve(&config, "B", &options.b)?;
+    let judge = options
+        .judge
+        .as_deref()
+        .map(|spec| Participant::resolve(&config, "Judge", spec))
+        .transpose()?;
+
+    let client_a = create_authenticated_client(&mut config, &a.provider).await?;
+    let client_b = create_authenticated_client(&mut config, &b.provider).await?;
+    let max_tokens = options.max_tokens.as_ref().and_then(|s| s.parse().ok());
+    let temperature = options.temperature.as_ref().and_then(|s| s.parse().ok());
+
+    let db = Database::new()?;
+    let session_id = crate::core::deterministic::new_id();
+
+    println!("{} {}\n", "Opening:".bold(), options.opening);
+
+    // messages[0] is the opening, messages[k] the reply of turn k
+    let mut messages = vec![options.opening.clone()];
+    for turn in 1..=options.turns as usize {
+        let (speaker, other, client) = if turn % 2 == 1 {
+            (&a, &b, &client_a)
+        } else {
+            (&b, &a, &client_b)
+        };
+        let (history, prompt) = perspective(&messages, turn);
+        let history: Vec<ChatEntry> = history
+            .into_iter()
+            .map(|(question, response)| ChatEntry {
+                chat_id: session_id.clone(),
+                model: speaker.model.clone(),
+                question,
+                response,
+                timestamp: Utc::now(),
+                input_tokens: None,
+                output_tokens: None,
+                provider: Some(speaker.provider.clone()),
+            })
+            .collect();
+        let system_prompt = options
+            .system_prompt
+            .clone()
+            .unwrap_or_else(|| speaker.system_prompt(other, &options.opening));
+
+        let (reply, input_tokens, output_tokens) = send_chat_request_with_validation(
+            client,
+            &speaker.model,
+            &prompt,
+            &history,
+            Some(&system_prompt),
+            max_tokens,
+            temperature,
+            &speaker.provider,
+            None,
+        )
+        .await?;
+
+        print_turn(speaker, turn, &reply);
+        db.save_chat_entry_with_details(
+            &session_id,
+            &speaker.provider,
+            &speaker.model,
+            &prompt,
+            &reply,
+            input_tokens,
+            output_tokens,
+            &log_details(&speaker.provider, Some(&system_prompt)),
+        )?;
+        messages.push(reply);
+    }
+
+    if let Some(judge) = judge {
+        let client = create_authenticated_client(&mut config, &judge.provider).await?;
+        let prompt = judge_prompt(&a, &b, &messages);
+        let (verdict, input_tokens, output_tokens) = send_chat_request_with_validation(
+            &client,
+            &judge.model,
+            &prompt,
+            &[],
+            None,
+            max_tokens,
+            temperature,
+            &judge.provider,
+            None,
+        )
+        .await?;
+
+        println!("{} {}", "Judge".bold().magenta(), judge.spec.dimmed());
+        println!("{}\n", redact(&verdict));
+        db.save_chat_entry_with_details(
+            &session_id,
+            &judge.provider,
+            &judge.model,
+            &prompt,
+            &verdict,
+            input_tokens,
+            output_tokens,
+            &log_details(&judge.provider, None),
+        )?;
+    }
+
+    println!(
+        "{} Saved as session {} (view with 'lc logs share {} --html FILE')",
+        sym("✓").green(),
+        crate::database::short_id(&session_id),
+        crate::database::short_id(&session_id)
+    );
+    Ok(())
+}
+
+/// History and prompt of the speaker of `turn`: the messages it received paired with
+/// its own replies, and the message it is replying to now. B also sees the opening
+/// with its first message since it was addressed to A.
+fn perspective(messages: &[String], turn: usize) -> (Vec<(String, String)>, String) {
+    let incoming = |k: usize| {
+        if k == 2 {
+            format!("{}\n\n{}", messages[0], messages[1])
+        } else {
+            messages[k - 1].clone()
+        }
+    };
+    let history = (1..turn)
+        .filter(|k| k % 2 == turn % 2)
+        .map(|k| (incoming(k), messages[k].clone()))
+        .collect();
+    (history, incoming(turn))
+}
+
+fn judge_prompt(a: &Participant, b: &Participant, messages: &[String]) -> String {
+    let mut transcript = String::new();
+    for (turn, message) in messages.iter().enumerate().skip(1) {
+        let speaker = if turn % 2 == 1 { a } else { b };
+        transcript.push_str(&format!(
+            "{} ({}): {}\n\n",
+            speaker.label, speaker.spec, message
+        ));
+    }
+    format!(
+        "Below is a conversation between two AI models that started with: \"{}\"\n\n{}\
+         Summarize the key points each participant made, where they agreed and disagreed, \
+         and say which argued more convincingly and why.",
+        messages[0], transcript
+    )
+}
+
+fn print_turn(speaker: &Participant, turn: usize, reply: &str) {
+    let label = format!("{} {} turn {}", speaker.label, sym("·"), turn);
+    let label = if speaker.label == "A" {
+        label.bold().cyan()
+    } else {
+        label.bold().yellow()
+    };
+    println!("{} {}", label, speaker.spec.dimmed());
+    println!("{}\n", redact(reply));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perspective_alternates_speakers() {
+        let messages: Vec<String> = ["topic", "a1", "b1", "a2", "b2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        // A opens by replying to the opening
+        assert_eq!(perspective(&messages, 1), (vec![], "topic".to_string()));
+        // B sees the opening together with A's first reply
+        assert_eq!(
+            perspective(&messages, 2),
+            (vec![], "topic\n\na1".to_string())
+        );
+        // A's history pairs what it received with what it answered
+        assert_eq!(
+            perspective(&messages, 3),
+            (
+                vec![("topic".to_string(), "a1".to_string())],
+                "b1".to_string()
+            )
+        );
+        assert_eq!(
+            perspective(&messages, 4),
+            (
+                vec![("topic\n\na1".to_string(), "b1".to_string())],
+                "a2".to_string()
+            )
+        );
+    }
+}
@@ -1,16 +1,23 @@
 //! Embedding commands implementation
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use crate::chat;
 use crate::cli::set_debug_mode;
 use crate::config;
-use crate::data::vector_db::{FileProcessor, VectorDatabase};
-use crate::provider::EmbeddingRequest;
+use crate::core::auto_tag::{self, Tagger};
+use crate::data::chunking::{Chunk, ChunkOptions};
+use crate::data::vector_db::{FileProcessor, StoredChunk, VectorDatabase};
+use crate::provider::{EmbeddingRequest, ImageEmbeddingInput, ImageEmbeddingRequest};
 use crate::utils::resolve_model_and_provider;
 
 /// Handle embed command
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_embed_command(
     model: String,
     provider: Option<String>,
@@ -18,6 +25,9 @@ pub async fn handle_embed_command(
     files: Vec<String>,
     text: Option<String>,
     debug: bool,
+    tag_model: Option<String>,
+    chunk_options: ChunkOptions,
+    resume: bool,
 ) -> Result<()> {
     // Set debug mode if requested
     if debug {
@@ -61,45 +71,175 @@ pub async fn handle_embed_command(
         config_mut.save()?;
     }
 
-    println!("{} Starting embedding process...", "🔄".blue());
-    println!("{} Model: {}", "📊".blue(), resolved_model);
-    println!("{} Provider: {}", "🏢".blue(), provider_name);
+    let tagger = match &tag_model {
+        Some(tag_model) => Some(Tagger::new(&mut config_mut, tag_model).await?),
+        None => None,
+    };
+
+    println!("{} Starting embedding process...", sym("🔄").blue());
+    println!("{} Model: {}", sym("📊").blue(), resolved_model);
+    println!("{} Provider: {}", sym("🏢").blue(), provider_name);
+    if let Some(tagger) = &tagger {
+        println!("{} Tagging with: {}", sym("🏷️").blue(), tagger.name());
+    }
 
     let mut total_embeddings = 0;
     let mut total_tokens = 0;
+    // Every stored chunk is a checkpoint, so an interrupted run can be resumed
+    let interrupted = match &database {
+        Some(_) if !files.is_empty() => watch_interrupt(),
+        _ => Arc::new(AtomicBool::new(false)),
+    };
 
     // Process files if provided
     if !files.is_empty() {
-        println!("{} Processing files with glob patterns...", "📁".blue());
+        println!(
+            "{} Processing files with glob patterns...",
+            sym("📁").blue()
+        );
 
-        // Expand file patterns and filter for text files
-        let file_paths = FileProcessor::expand_file_patterns(&files)?;
+        // Expand file patterns and filter for text and image files. Images only go to
+        // embedding models that may read them.
+        let mut file_paths = FileProcessor::expand_embeddable_files(&files)?;
+        if !crate::core::chat::model_may_support_vision(&provider_name, &resolved_model).await {
+            let before = file_paths.len();
+            file_paths.retain(|path| FileProcessor::image_mime_type(path).is_none());
+            if file_paths.len() < before {
+                println!(
+                    "{} Skipping {} image file(s): {} embeds text only",
+                    sym("⚠️").yellow(),
+                    before - file_paths.len(),
+                    resolved_model
+                );
+            }
+        }
+        let mut embeds_images = true;
 
         if file_paths.is_empty() {
             println!(
-                "{} No text files found matching the patterns",
-                "⚠️".yellow()
+                "{} No text or image files found matching the patterns",
+                sym("⚠️").yellow()
             );
         } else {
             println!(
-                "{} Found {} text files to process",
-                "✅".green(),
+                "{} Found {} files to process",
+                sym("✅").green(),
                 file_paths.len()
             );
 
-            for file_path in file_paths {
-                println!("\n{} Processing file: {}", "📄".blue(), file_path.display());
+            'files: for file_path in file_paths {
+                if interrupted.load(Ordering::SeqCst) {
+                    break;
+                }
+                let file_path_str = file_path.to_string_lossy();
+                // Images are embedded whole with a multimodal embedding model
+                if let Some(mime_type) = FileProcessor::image_mime_type(&file_path) {
+                    if !embeds_images {
+                        continue;
+                    }
+                    // Stored absolute, so that prompts run elsewhere can attach it
+                    let image_path = std::path::absolute(&file_path)
+                        .unwrap_or_else(|_| file_path.clone())
+                        .to_string_lossy()
+                        .into_owned();
+                    if resume {
+                        if let Some(db_name) = &database {
+                            let stored = VectorDatabase::new(db_name)?.file_chunks(
+                                &image_path,
+                                &resolved_model,
+                                &provider_name,
+                            )?;
+                            if !stored.is_empty() {
+                                println!(
+                                    "{} Already embedded: {}",
+                                    "↻".blue(),
+                                    file_path.display()
+                                );
+                                continue;
+                            }
+                        }
+                    }
+                    println!(
+                        "\n{} Processing image: {}",
+                        sym("🖼️").blue(),
+                        file_path.display()
+                    );
+                    match embed_image_file(&client, &resolved_model, &file_path).await {
+                        Ok((embedding, tokens)) => {
+                            total_embeddings += 1;
+                            total_tokens += tokens;
+                            if let Some(db_name) = &database {
+                                match VectorDatabase::new(db_name).and_then(|vector_db| {
+                                    vector_db.add_image(
+                                        &image_description(&file_path),
+                                        &embedding,
+                                        &resolved_model,
+                                        &provider_name,
+                                        &image_path,
+                                        mime_type,
+                                    )
+                                }) {
+                                    Ok(id) => {
+                                        println!(
+                                            "  {} Image stored with ID: {}",
+                                            sym("💾").green(),
+                                            id
+                                        )
+                                    }
+                                    Err(e) => eprintln!("  Warning: Failed to store image: {}", e),
+                                }
+                            } else {
+                                println!(
+                                    "  {} Image embedded ({} dimensions)",
+                                    sym("✅").green(),
+                                    embedding.len()
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            // The model most likely can't embed images; don't send it the rest
+                            eprintln!(
+                                "  Warning: Failed to embed image '{}': {}. Image embeddings need a multimodal embedding model; skipping the other images",
+                                file_path.display(),
+                                e
+                            );
+                            embeds_images = false;
+                        }
+                    }
+                    continue;
+                }
+
+                println!(
+                    "\n{} Processing file: {}",
+                    sym("📄").blue(),
+                    file_path.display()
+                );
 
                 // Read and chunk the file
-                match FileProcessor::process_file(&file_path) {
+                match FileProcessor::process_file_async(&file_path, &chunk_options).await {
                     Ok(chunks) => {
-                        println!("{} Split into {} chunks", "✂️".blue(), chunks.len());
+                        println!("{} Split into {} chunks", sym("✂️").blue(), chunks.len());
+
+                        let pending = match &database {
+                            Some(db_name) if resume => resume_file(
+                                db_name,
+                                &file_path_str,
+                                &chunks,
+                                &resolved_model,
+                                &provider_name,
+                            )?,
+                            _ => (0..chunks.len()).collect(),
+                        };
 
                         // Process each chunk
-                        for (chunk_index, chunk) in chunks.iter().enumerate() {
+                        for chunk_index in pending {
+                            if interrupted.load(Ordering::SeqCst) {
+                                break 'files;
+                            }
+                            let chunk = &chunks[chunk_index];
                             let embedding_request = EmbeddingRequest {
                                 model: resolved_model.clone(),
-                                input: chunk.clone(),
+                                input: chunk.text.clone(),
                                 encoding_format: Some("float".to_string()),
                             };
 
@@ -113,9 +253,8 @@ pub async fn handle_embed_command(
                                         if let Some(db_name) = &database {
                                             match VectorDatabase::new(db_name) {
                                                 Ok(vector_db) => {
-                                                    let file_path_str = file_path.to_string_lossy();
                                                     match vector_db.add_vector_with_metadata(
-                                                        chunk,
+                                                        &chunk.text,
                                                         &embedding_data.embedding,
                                                         &resolved_model,
                                                         &provider_name,
@@ -124,8 +263,26 @@ pub async fn handle_embed_command(
                                                         Some(chunks.len() as i32),
                                                     ) {
                                                         Ok(id) => {
-                                                            println!("  {} Chunk {}/{} stored with ID: {}",
-                                                                "💾".green(), chunk_index + 1, chunks.len(), id);
+                                                            println!("  {} Chunk {}/{} (line {}) stored with ID: {}",
+                                                                sym("💾").green(), chunk_index + 1, chunks.len(), chunk.line, id);
+                                                            if let Err(e) = vector_db
+                                                                .set_chunk_position(
+                                                                    id,
+                                                                    chunk.offset,
+                                                                    chunk.line,
+                                                                )
+                                                            {
+                                                                eprintln!("  Warning: Failed to store the position of chunk {}: {}", chunk_index + 1, e);
+                                                            }
+                                                            if let Some(tagger) = &tagger {
+                                                                tag_entry(
+                                                                    tagger,
+                                                                    &vector_db,
+                                                                    id,
+                                                                    &chunk.text,
+                                                                )
+                                                                .await;
+                                                            }
                                                         }
                                                         Err(e) => {
                                                             eprintln!("  Warning: Failed to store chunk {}: {}", chunk_index + 1, e);
@@ -140,7 +297,7 @@ pub async fn handle_embed_command(
                                             // Just show progress without storing
                                             println!(
                                                 "  {} Chunk {}/{} embedded ({} dimensions)",
-                                                "✅".green(),
+                                                sym("✅").green(),
                                                 chunk_index + 1,
                                                 chunks.len(),
                                                 embedding_data.embedding.len()
@@ -170,12 +327,19 @@ pub async fn handle_embed_command(
         }
     }
 
+    if interrupted.load(Ordering::SeqCst) {
+        anyhow::bail!(
+            "Embedding interrupted after {} embeddings; run the same command with --resume to continue",
+            total_embeddings
+        );
+    }
+
     // Process text if provided
     if let Some(text_content) = text {
-        println!("\n{} Processing text input...", "📝".blue());
+        println!("\n{} Processing text input...", sym("📝").blue());
         println!(
             "{} Text: \"{}\"",
-            "📝".blue(),
+            sym("📝").blue(),
             if text_content.len() > 50 {
                 format!("{}...", &text_content[..50])
             } else {
@@ -197,14 +361,14 @@ pub async fn handle_embed_command(
 
                     println!(
                         "{} Vector dimensions: {}",
-                        "📏".blue(),
+                        sym("📏").blue(),
                         embedding_data.embedding.len()
                     );
 
                     // Display vector preview
                     let embedding = &embedding_data.embedding;
                     if embedding.len() > 10 {
-                        println!("\n{} Vector preview:", "🔍".blue());
+                        println!("\n{} Vector preview:", sym("🔍").blue());
                         print!("  [");
                         for (i, val) in embedding.iter().take(5).enumerate() {
                             if i > 0 {
@@ -235,10 +399,13 @@ pub async fn handle_embed_command(
                                     Ok(id) => {
                                         println!(
                                             "\n{} Stored in vector database '{}' with ID: {}",
-                                            "💾".green(),
+                                            sym("💾").green(),
                                             db_name,
                                             id
                                         );
+                                        if let Some(tagger) = &tagger {
+                                            tag_entry(tagger, &vector_db, id, &text_content).await;
+                                        }
                                     }
                                     Err(e) => {
                                         eprintln!(
@@ -260,7 +427,7 @@ pub async fn handle_embed_command(
                     // Output full vector as JSON for programmatic use
                     if files.is_empty() {
                         // Only show full vector for single text input
-                        println!("\n{} Full vector (JSON):", "📋".dimmed());
+                        println!("\n{} Full vector (JSON):", sym("📋").dimmed());
                         println!("{}", serde_json::to_string(&embedding)?);
                     }
                 }
@@ -272,18 +439,18 @@ pub async fn handle_embed_command(
     }
 
     // Summary
-    println!("\n{} Embedding process completed!", "🎉".green());
+    println!("\n{} Embedding process completed!", sym("🎉").green());
     println!(
         "{} Total embeddings generated: {}",
-        "📊".blue(),
+        sym("📊").blue(),
         total_embeddings
     );
-    println!("{} Total tokens used: {}", "💰".yellow(), total_tokens);
+    println!("{} Total tokens used: {}", sym("💰").yellow(), total_tokens);
 
     if let Some(db_name) = &database {
         println!(
             "{} All embeddings stored in database: {}",
-            "💾".green(),
+            sym("💾").green(),
             db_name
         );
     }
@@ -291,14 +458,144 @@ pub async fn handle_embed_command(
     Ok(())
 }
 
+/// Set on the first Ctrl+C, so that embedding stops once the chunk in flight is stored.
+/// A second Ctrl+C exits at once.
+fn watch_interrupt() -> Arc<AtomicBool> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = interrupted.clone();
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if flag.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+            eprintln!(
+                "\n{} Stopping after the current chunk (Ctrl+C again to quit now)...",
+                sym("⏸").yellow()
+            );
+        }
+    });
+    interrupted
+}
+
+/// Indices of the chunks of a file `--resume` still has to embed. When the stored
+/// chunks no longer match the file (it changed, or other chunk options are used), they
+/// are deleted and the whole file is embedded again.
+fn resume_file(
+    db_name: &str,
+    file_path: &str,
+    chunks: &[Chunk],
+    model: &str,
+    provider: &str,
+) -> Result<Vec<usize>> {
+    let vector_db = VectorDatabase::new(db_name)?;
+    let stored = vector_db.file_chunks(file_path, model, provider)?;
+    match pending_chunks(chunks, &stored) {
+        Some(pending) => {
+            if pending.is_empty() {
+                println!("{} All chunks already embedded", "↻".blue());
+            } else if pending.len() < chunks.len() {
+                println!(
+                    "{} Resuming: {} of {} chunks already embedded",
+                    "↻".blue(),
+                    chunks.len() - pending.len(),
+                    chunks.len()
+                );
+            }
+            Ok(pending)
+        }
+        None => {
+            println!(
+                "{} The file changed since it was embedded; replacing its {} stored chunks",
+                "↻".blue(),
+                stored.len()
+            );
+            let ids: Vec<i64> = stored.iter().map(|(id, ..)| *id).collect();
+            vector_db.delete_vectors(&ids)?;
+            Ok((0..chunks.len()).collect())
+        }
+    }
+}
+
+/// Indices of the chunks not stored yet, or `None` when a stored chunk is not the
+/// file's chunk at that index anymore
+fn pending_chunks(chunks: &[Chunk], stored: &[StoredChunk]) -> Option<Vec<usize>> {
+    let mut done = vec![false; chunks.len()];
+    for (_, index, total, text) in stored {
+        let index = usize::try_from((*index)?).ok()?;
+        if *total != Some(chunks.len() as i32) || chunks.get(index)?.text != *text {
+            return None;
+        }
+        done[index] = true;
+    }
+    Some((0..chunks.len()).filter(|&index| !done[index]).collect())
+}
+
+/// Embed an image file with a multimodal embedding model, returning the vector and the
+/// tokens used
+pub async fn embed_image_file(
+    client: &crate::chat::LLMClient,
+    model: &str,
+    path: &std::path::Path,
+) -> Result<(Vec<f64>, u32)> {
+    let request = ImageEmbeddingRequest {
+        model: model.to_string(),
+        input: vec![ImageEmbeddingInput {
+            image: crate::image_utils::process_image_file(path)?,
+        }],
+        encoding_format: Some("float".to_string()),
+    };
+    let response = client.image_embeddings(&request).await?;
+    let embedding = response
+        .data
+        .into_iter()
+        .next()
+        .map(|data| data.embedding)
+        .ok_or_else(|| anyhow::anyhow!("No embedding data in response"))?;
+    Ok((embedding, response.usage.total_tokens))
+}
+
+/// The text stored for an image entry
+pub fn image_description(path: &std::path::Path) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    format!("Image: {}", name)
+}
+
+/// Tag and summarize a stored entry, warning instead of failing the embedding
+async fn tag_entry(tagger: &Tagger, vector_db: &VectorDatabase, id: i64, text: &str) {
+    let annotations = match tagger.annotate(text).await {
+        Ok(annotations) => annotations,
+        Err(e) => {
+            eprintln!("  Warning: Failed to tag entry {}: {}", id, e);
+            return;
+        }
+    };
+    match vector_db.set_annotations(id, &annotations.tags, annotations.summary.as_deref()) {
+        Ok(()) => println!(
+            "  {} Tags: {}",
+            sym("🏷️").blue(),
+            annotations.tags.join(", ").dimmed()
+        ),
+        Err(e) => eprintln!("  Warning: Failed to store tags of entry {}: {}", id, e),
+    }
+}
+
 /// Handle similar command
 pub async fn handle_similar_command(
     model: Option<String>,
     provider: Option<String>,
     database: String,
     limit: usize,
+    tags: Vec<String>,
     query: String,
 ) -> Result<()> {
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| auto_tag::normalize_tag(tag))
+        .collect();
+
     // Open the vector database
     let vector_db = VectorDatabase::new(&database)?;
 
@@ -320,7 +617,7 @@ pub async fn handle_similar_command(
                 if model.is_some() || provider.is_some() {
                     println!(
                         "{} Using model from database: {}:{}",
-                        "ℹ️".blue(),
+                        sym("ℹ️").blue(),
                         db_provider,
                         db_model
                     );
@@ -374,11 +671,24 @@ pub async fn handle_similar_command(
         encoding_format: Some("float".to_string()),
     };
 
-    println!("{} Searching for similar content...", "🔍".blue());
-    println!("{} Database: {}", "📊".blue(), database);
+    println!("{} Searching for similar content...", sym("🔍").blue());
+    println!("{} Database: {}", sym("📊").blue(), database);
+    let alpha = crate::data::hybrid_search::alpha(&config);
+    if let Some(alpha) = alpha {
+        println!(
+            "{} Hybrid search: {:.0}% vectors, {:.0}% keywords",
+            sym("🔀").blue(),
+            alpha * 100.0,
+            (1.0 - alpha) * 100.0
+        );
+    }
+    let rerank_model = crate::core::rerank::model();
+    if let Some(rerank_model) = &rerank_model {
+        println!("{} Reranking with: {}", sym("🎯").blue(), rerank_model);
+    }
     println!(
         "{} Query: \"{}\"",
-        "📝".blue(),
+        sym("📝").blue(),
         if query.len() > 50 {
             format!("{}...", &query[..50])
         } else {
@@ -391,23 +701,48 @@ pub async fn handle_similar_command(
             if let Some(embedding_data) = response.data.first() {
                 let query_vector = &embedding_data.embedding;
 
-                // Find similar vectors
-                let similar_results = vector_db.find_similar(query_vector, limit)?;
+                // Find similar vectors, more of them for the reranker to choose from
+                let candidates = match rerank_model {
+                    Some(_) => limit * crate::core::rerank::CANDIDATES_PER_RESULT,
+                    None => limit,
+                };
+                let similar_results = match alpha {
+                    Some(alpha) => {
+                        vector_db.find_hybrid(query_vector, &query, candidates, alpha, &tags)?
+                    }
+                    None => vector_db.find_similar_tagged(query_vector, candidates, &tags)?,
+                };
+                let similar_results = match &rerank_model {
+                    Some(rerank_model) => {
+                        rerank_matches(
+                            &mut config_mut,
+                            rerank_model,
+                            &query,
+                            similar_results,
+                            limit,
+                        )
+                        .await
+                    }
+                    None => similar_results
+                        .into_iter()
+                        .map(|(entry, similarity)| (entry, similarity, None))
+                        .collect(),
+                };
 
                 if similar_results.is_empty() {
                     println!(
                         "\n{} No similar content found in database '{}'",
-                        "❌".red(),
+                        sym("❌").red(),
                         database
                     );
                 } else {
                     println!(
                         "\n{} Found {} similar results:",
-                        "✅".green(),
+                        sym("✅").green(),
                         similar_results.len()
                     );
 
-                    for (i, (entry, similarity)) in similar_results.iter().enumerate() {
+                    for (i, (entry, similarity, relevance)) in similar_results.iter().enumerate() {
                         let similarity_percent = (similarity * 100.0).round() as u32;
                         let similarity_color = if similarity_percent >= 80 {
                             format!("{}%", similarity_percent).green()
@@ -417,13 +752,26 @@ pub async fn handle_similar_command(
                             format!("{}%", similarity_percent).red()
                         };
 
+                        let relevance = relevance
+                            .map(|relevance| format!(", Relevance: {:.3}", relevance))
+                            .unwrap_or_default();
                         println!(
-                            "\n{} {} (Similarity: {})",
+                            "\n{} {} (Similarity: {}{})",
                             format!("{}.", i + 1).bold(),
                             similarity_color,
-                            format!("ID: {}", entry.id).dimmed()
+                            format!("ID: {}", entry.id).dimmed(),
+                            relevance
                         );
                         println!("   {}", entry.text);
+                        if let Some(source) = entry.source() {
+                            println!("   {}", format!("Source: {}", source).dimmed());
+                        }
+                        if let Some(summary) = &entry.summary {
+                            println!("   {}", format!("Summary: {}", summary).dimmed());
+                        }
+                        if !entry.tags.is_empty() {
+                            println!("   {}", format!("Tags: {}", entry.tags.join(", ")).dimmed());
+                        }
                         println!(
                             "   {}",
                             format!(
@@ -446,6 +794,21 @@ pub async fn handle_similar_command(
     Ok(())
 }
 
+/// Lowest similarity of a text chunk included as RAG context
+const RAG_MIN_SIMILARITY: f64 = 0.3;
+/// Lowest similarity of an image included as RAG context. Text-to-image similarities of
+/// CLIP-style models are much lower than text-to-text ones.
+const RAG_MIN_IMAGE_SIMILARITY: f64 = 0.2;
+
+/// Text chunks and images retrieved from a vector database for a prompt
+#[derive(Debug, Default)]
+pub struct RagContext {
+    /// The matching chunks as a bullet list, empty when nothing matched
+    pub text: String,
+    /// Files of the matching images, to attach to the prompt
+    pub images: Vec<String>,
+}
+
 /// RAG helper function to retrieve relevant context
 pub async fn retrieve_rag_context(
     db_name: &str,
@@ -454,6 +817,141 @@ pub async fn retrieve_rag_context(
     _model: &str,
     _provider: &str,
 ) -> Result<String> {
+    Ok(retrieve_rag_matches(db_name, query).await?.text)
+}
+
+/// Matches taken from each database when `-v` gives no limit
+const RAG_DEFAULT_LIMIT: usize = 3;
+
+/// A vector database named with `-v`, and how many matches to take from it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RagSource {
+    pub name: String,
+    pub limit: usize,
+}
+
+/// Parse `-v docs,code:5`: comma-separated database names, each with an optional
+/// `:N` limit on its matches
+pub fn parse_rag_sources(spec: &str) -> Result<Vec<RagSource>> {
+    let mut sources: Vec<RagSource> = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, limit) = match part.rsplit_once(':') {
+            Some((name, limit)) => {
+                let limit = limit
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Invalid limit in '{}': expected a positive number after ':'",
+                            part
+                        )
+                    })?;
+                (name.trim(), limit)
+            }
+            None => (part, RAG_DEFAULT_LIMIT),
+        };
+        if !sources.iter().any(|source| source.name == name) {
+            sources.push(RagSource {
+                name: name.to_string(),
+                limit,
+            });
+        }
+    }
+    if sources.is_empty() {
+        anyhow::bail!("No vector database given");
+    }
+    Ok(sources)
+}
+
+/// Retrieve the text chunks and images that match `query` from the databases of a `-v`
+/// value. Matches of several databases are ranked together and labeled with their
+/// database: reranked matches by their relevance first, then the others by similarity,
+/// since the two scores are not comparable.
+pub async fn retrieve_rag_matches(db_names: &str, query: &str) -> Result<RagContext> {
+    let sources = parse_rag_sources(db_names)?;
+
+    // Databases embedded with the same model share one query embedding
+    let mut query_vectors: HashMap<(String, String), Vec<f64>> = HashMap::new();
+    let mut matches = Vec::new();
+    for source in &sources {
+        for (entry, similarity, relevance) in
+            search_database(source, query, &mut query_vectors).await?
+        {
+            matches.push((source.name.as_str(), entry, similarity, relevance));
+        }
+    }
+    // Relevance scores of a rerank model and cosine similarities are on different
+    // scales, so the two kinds of matches are sorted apart
+    let (mut reranked, mut unranked): (Vec<_>, Vec<_>) =
+        matches.into_iter().partition(|m| m.3.is_some());
+    reranked.sort_by(|a, b| b.3.unwrap_or_default().total_cmp(&a.3.unwrap_or_default()));
+    unranked.sort_by(|a, b| b.2.total_cmp(&a.2));
+    let matches = reranked.into_iter().chain(unranked);
+    let labeled = sources.len() > 1;
+
+    // Format context
+    let mut context = RagContext::default();
+    let mut included_count = 0;
+    for (db_name, entry, similarity, _) in matches {
+        crate::debug_log!(
+            "RAG: Result similarity: {:.3} in '{}' for text: '{}'",
+            similarity,
+            db_name,
+            &entry.text[..50.min(entry.text.len())]
+        );
+        if entry.is_image() {
+            // Images are attached when their file is still there
+            match entry.file_path {
+                Some(path)
+                    if similarity > RAG_MIN_IMAGE_SIMILARITY
+                        && std::path::Path::new(&path).is_file() =>
+                {
+                    context.images.push(path);
+                    included_count += 1;
+                }
+                _ => {}
+            }
+        } else if similarity > RAG_MIN_SIMILARITY {
+            // The source lets the model cite where the text comes from
+            let source = entry
+                .source()
+                .map(|source| format!(" (source: {})", source))
+                .unwrap_or_default();
+            if labeled {
+                context
+                    .text
+                    .push_str(&format!("- [{}] {}{}\n", db_name, entry.text, source));
+            } else {
+                context
+                    .text
+                    .push_str(&format!("- {}{}\n", entry.text, source));
+            }
+            included_count += 1;
+        }
+    }
+
+    crate::debug_log!(
+        "RAG: Included {} results in context ({} images)",
+        included_count,
+        context.images.len()
+    );
+    crate::debug_log!(
+        "RAG: Final context length: {} characters",
+        context.text.len()
+    );
+    Ok(context)
+}
+
+/// The closest entries of one database to `query`, most similar first, or most
+/// relevant first with `--rerank`
+async fn search_database(
+    source: &RagSource,
+    query: &str,
+    query_vectors: &mut HashMap<(String, String), Vec<f64>>,
+) -> Result<Vec<RankedEntry>> {
+    let db_name = source.name.as_str();
     crate::debug_log!(
         "RAG: Starting context retrieval for database '{}' with query '{}'",
         db_name,
@@ -468,82 +966,198 @@ pub async fn retrieve_rag_context(
     let count = vector_db.count()?;
     crate::debug_log!("RAG: Database '{}' contains {} vectors", db_name, count);
     if count == 0 {
-        crate::debug_log!("RAG: Database is empty, returning empty context");
-        return Ok(String::new());
+        crate::debug_log!("RAG: Database is empty, skipping it");
+        return Ok(Vec::new());
     }
 
     // Get model info from database
-    let (db_model, db_provider) = if let Some((m, p)) = vector_db.get_model_info()? {
-        crate::debug_log!("RAG: Using database model '{}' from provider '{}'", m, p);
-        (m, p)
-    } else {
-        crate::debug_log!("RAG: No model info in database, returning empty context");
-        return Ok(String::new());
+    let Some((db_model, db_provider)) = vector_db.get_model_info()? else {
+        crate::debug_log!("RAG: No model info in database, skipping it");
+        return Ok(Vec::new());
     };
-
-    // Create a client for the embedding provider (not the chat provider)
-    let config = config::Config::load()?;
-    let mut config_mut = config.clone();
-    let embedding_client = chat::create_authenticated_client(&mut config_mut, &db_provider).await?;
     crate::debug_log!(
-        "RAG: Created embedding client for provider '{}'",
+        "RAG: Using database model '{}' from provider '{}'",
+        db_model,
         db_provider
     );
 
-    // Use the database's embedding model for consistency
-    let embedding_request = EmbeddingRequest {
-        model: db_model.clone(),
-        input: query.to_string(),
-        encoding_format: Some("float".to_string()),
-    };
+    let key = (db_provider.clone(), db_model.clone());
+    if !query_vectors.contains_key(&key) {
+        // Create a client for the embedding provider (not the chat provider)
+        let config = config::Config::load()?;
+        let mut config_mut = config.clone();
+        let embedding_client =
+            chat::create_authenticated_client(&mut config_mut, &db_provider).await?;
+        crate::debug_log!(
+            "RAG: Created embedding client for provider '{}'",
+            db_provider
+        );
 
+        // Use the database's embedding model for consistency
+        let embedding_request = EmbeddingRequest {
+            model: db_model.clone(),
+            input: query.to_string(),
+            encoding_format: Some("float".to_string()),
+        };
+        crate::debug_log!(
+            "RAG: Generating embedding for query using model '{}'",
+            db_model
+        );
+        let response = embedding_client.embeddings(&embedding_request).await?;
+        let Some(embedding_data) = response.data.into_iter().next() else {
+            crate::debug_log!("RAG: No embedding data in response, skipping the database");
+            return Ok(Vec::new());
+        };
+        crate::debug_log!(
+            "RAG: Query vector has {} dimensions",
+            embedding_data.embedding.len()
+        );
+        query_vectors.insert(key.clone(), embedding_data.embedding);
+    }
+
+    let query_vector = &query_vectors[&key];
+    let mut config = config::Config::load()?;
+    let rerank_model = crate::core::rerank::model();
+    let candidates = match rerank_model {
+        Some(_) => source.limit * crate::core::rerank::CANDIDATES_PER_RESULT,
+        None => source.limit,
+    };
+    let similar_results = match crate::data::hybrid_search::alpha(&config) {
+        Some(alpha) => {
+            crate::debug_log!("RAG: Hybrid search with alpha {}", alpha);
+            vector_db.find_hybrid(query_vector, query, candidates, alpha, &[])?
+        }
+        None => vector_db.find_similar(query_vector, candidates)?,
+    };
     crate::debug_log!(
-        "RAG: Generating embedding for query using model '{}'",
-        db_model
+        "RAG: Found {} similar results in '{}'",
+        similar_results.len(),
+        db_name
     );
+    Ok(match rerank_model {
+        Some(rerank_model) => {
+            rerank_matches(
+                &mut config,
+                &rerank_model,
+                query,
+                similar_results,
+                source.limit,
+            )
+            .await
+        }
+        None => similar_results
+            .into_iter()
+            .map(|(entry, similarity)| (entry, similarity, None))
+            .collect(),
+    })
+}
 
-    // Generate embedding for query using the correct provider
-    let response = embedding_client.embeddings(&embedding_request).await?;
-    crate::debug_log!("RAG: Successfully generated embedding for query");
-
-    if let Some(embedding_data) = response.data.first() {
-        let query_vector = &embedding_data.embedding;
-        crate::debug_log!("RAG: Query vector has {} dimensions", query_vector.len());
-
-        // Find top 3 most similar vectors for context
-        let similar_results = vector_db.find_similar(query_vector, 3)?;
-        crate::debug_log!("RAG: Found {} similar results", similar_results.len());
+/// A vector search match: the entry, its similarity and its relevance from the reranker
+type RankedEntry = (crate::data::vector_db::VectorEntry, f64, Option<f64>);
 
-        if similar_results.is_empty() {
-            crate::debug_log!("RAG: No similar results found, returning empty context");
-            return Ok(String::new());
+/// Keep the `limit` best of the vector search `candidates` by the rerank model's
+/// relevance. Images, which a text reranker cannot judge, keep their place when they
+/// are among the first `limit` candidates. When reranking fails, the first `limit`
+/// candidates are kept.
+async fn rerank_matches(
+    config: &mut config::Config,
+    rerank_model: &str,
+    query: &str,
+    candidates: Vec<(crate::data::vector_db::VectorEntry, f64)>,
+    limit: usize,
+) -> Vec<RankedEntry> {
+    let mut images = Vec::new();
+    let mut texts = Vec::new();
+    for (rank, candidate) in candidates.iter().enumerate() {
+        if !candidate.0.is_image() {
+            texts.push(candidate.clone());
+        } else if rank < limit {
+            images.push((candidate.0.clone(), candidate.1, None));
         }
+    }
 
-        // Format context
-        let mut context = String::new();
-        let mut included_count = 0;
-        for (entry, similarity) in similar_results {
-            crate::debug_log!(
-                "RAG: Result similarity: {:.3} for text: '{}'",
-                similarity,
-                &entry.text[..50.min(entry.text.len())]
+    let reranked = crate::core::rerank::rerank(
+        config,
+        rerank_model,
+        query,
+        texts,
+        |(entry, _): &(crate::data::vector_db::VectorEntry, f64)| entry.text.as_str(),
+        limit - images.len(),
+    )
+    .await;
+    match reranked {
+        Ok(reranked) => {
+            let mut matches: Vec<RankedEntry> = reranked
+                .into_iter()
+                .map(|((entry, similarity), relevance)| (entry, similarity, Some(relevance)))
+                .collect();
+            matches.extend(images);
+            matches
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Could not rerank with {}, keeping the vector search order: {}",
+                sym("⚠️").yellow(),
+                rerank_model,
+                e
             );
-            // Only include results with reasonable similarity (>0.3)
-            if similarity > 0.3 {
-                context.push_str(&format!("- {}\n", entry.text));
-                included_count += 1;
-            }
+            candidates
+                .into_iter()
+                .take(limit)
+                .map(|(entry, similarity)| (entry, similarity, None))
+                .collect()
         }
+    }
+}
 
-        crate::debug_log!(
-            "RAG: Included {} results in context (similarity > 0.3)",
-            included_count
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rag_sources() {
+        let sources = parse_rag_sources("docs, code:5,docs:2,").unwrap();
+        assert_eq!(
+            sources,
+            vec![
+                RagSource {
+                    name: "docs".to_string(),
+                    limit: RAG_DEFAULT_LIMIT
+                },
+                RagSource {
+                    name: "code".to_string(),
+                    limit: 5
+                },
+            ]
         );
-        crate::debug_log!("RAG: Final context length: {} characters", context.len());
+        assert!(parse_rag_sources("docs:0").is_err());
+        assert!(parse_rag_sources("docs:many").is_err());
+        assert!(parse_rag_sources(" , ").is_err());
+    }
 
-        Ok(context)
-    } else {
-        crate::debug_log!("RAG: No embedding data in response, returning empty context");
-        Ok(String::new())
+    #[test]
+    fn test_pending_chunks() {
+        let chunks: Vec<Chunk> = ["one", "two", "three"]
+            .iter()
+            .map(|text| Chunk {
+                text: text.to_string(),
+                offset: 0,
+                line: 1,
+            })
+            .collect();
+        let stored =
+            |index: i32, total: i32, text: &str| (0, Some(index), Some(total), text.to_string());
+        assert_eq!(pending_chunks(&chunks, &[]), Some(vec![0, 1, 2]));
+        assert_eq!(
+            pending_chunks(&chunks, &[stored(0, 3, "one"), stored(2, 3, "three")]),
+            Some(vec![1])
+        );
+        // The file changed: other text, another chunk count, or an image entry
+        assert_eq!(pending_chunks(&chunks, &[stored(1, 3, "2")]), None);
+        assert_eq!(pending_chunks(&chunks, &[stored(0, 4, "one")]), None);
+        assert_eq!(
+            pending_chunks(&chunks, &[(0, None, None, "Image: a.png".to_string())]),
+            None
+        );
     }
 }
@@ -0,0 +1,334 @@
+//! Structured records from documents (`lc extract`)
+//!
+//! Every document is read with the same readers as attachments (PDF, HTML, WASM plugin
+//! formats, remote URLs), sent with the JSON schema as structured output, and the reply
+//! is validated against the schema. Invalid replies are sent back with the problems for
+//! a few repair attempts. One document prints a JSON record; several print JSONL.
+
+use crate::utils::terminal::sym;
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+
+use crate::chat::LLMClient;
+use crate::config::Config;
+use crate::core::chat::{create_authenticated_client, send_chat_request_with_validation};
+use crate::core::structured_output;
+use crate::database::ChatEntry;
+use crate::debug_log;
+
+/// Longest part of a document sent to the model, in characters
+const MAX_DOCUMENT_CHARS: usize = 200_000;
+
+/// Options of an `lc extract` run
+pub struct ExtractOptions {
+    pub schema: String,
+    pub attachments: Vec<String>,
+    pub instructions: Option<String>,
+    pub output: Option<String>,
+    pub retries: u32,
+    pub source_key: Option<String>,
+    pub no_response_format: bool,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub system_prompt: Option<String>,
+    pub max_tokens: Option<String>,
+    pub temperature: Option<String>,
+}
+
+/// The model and settings records are extracted with
+struct Extractor {
+    client: LLMClient,
+    provider: String,
+    model: String,
+    schema: Value,
+    instructions: Option<String>,
+    system_prompt: Option<String>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    retries: u32,
+}
+
+impl Extractor {
+    /// A record matching the schema from `document`, repairing invalid replies
+    async fn extract(&self, name: &str, document: &str) -> Result<Value> {
+        let mut history = Vec::new();
+        let mut question =
+            extraction_prompt(&self.schema, self.instructions.as_deref(), name, document);
+
+        for attempt in 0..=self.retries {
+            let (reply, _, _) = send_chat_request_with_validation(
+                &self.client,
+                &self.model,
+                &question,
+                &history,
+                self.system_prompt.as_deref(),
+                self.max_tokens,
+                self.temperature,
+                &self.provider,
+                None,
+            )
+            .await?;
+            debug_log!("Extraction reply for '{}':\n{}", name, reply);
+
+            let errors = match structured_output::parse_reply(&reply) {
+                Ok(record) => {
+                    let errors = structured_output::validate(&record, &self.schema);
+                    if errors.is_empty() {
+                        return Ok(record);
+                    }
+                    errors
+                }
+                Err(e) => vec![e.to_string()],
+            };
+
+            if attempt == self.retries {
+                anyhow::bail!(
+                    "the reply does not match the schema after {} repair attempt(s): {}",
+                    self.retries,
+                    errors.join("; ")
+                );
+            }
+            eprintln!(
+                "{} {}: reply does not match the schema, asking the model to fix it (attempt {}/{})",
+                sym("✗").red(),
+                name,
+                attempt + 1,
+                self.retries
+            );
+
+            history.push(ChatEntry {
+                chat_id: String::new(),
+                model: self.model.clone(),
+                question,
+                response: reply,
+                timestamp: Utc::now(),
+                input_tokens: None,
+                output_tokens: None,
+                provider: Some(self.provider.clone()),
+            });
+            question = repair_prompt(&errors);
+        }
+        unreachable!("the last attempt returns or bails")
+    }
+}
+
+/// The request for a record of `document`
+fn extraction_prompt(
+    schema: &Value,
+    instructions: Option<&str>,
+    name: &str,
+    document: &str,
+) -> String {
+    let document = match document.char_indices().nth(MAX_DOCUMENT_CHARS) {
+        Some((end, _)) => &document[..end],
+        None => document,
+    };
+    let mut prompt = String::from(
+        "Extract a record from the document below. Reply with a single JSON value that \
+         matches this JSON schema and nothing else. Only use information found in the \
+         document; leave out optional properties it does not mention.\n\n",
+    );
+    prompt.push_str(&format!(
+        "<schema>\n{}\n</schema>\n\n",
+        serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+    ));
+    if let Some(instructions) = instructions.filter(|i| !i.trim().is_empty()) {
+        prompt.push_str(&format!("{}\n\n", instructions.trim()));
+    }
+    prompt.push_str(&format!(
+        "<document name=\"{}\">\n{}\n</document>",
+        name,
+        document.trim()
+    ));
+    prompt
+}
+
+/// The follow-up asking the model to fix an invalid reply
+fn repair_prompt(errors: &[String]) -> String {
+    let mut prompt = String::from("Your reply does not match the schema. Problems found:\n");
+    for error in errors {
+        prompt.push_str(&format!("- {}\n", error));
+    }
+    prompt.push_str("\nReply with the corrected JSON only.");
+    prompt
+}
+
+/// The documents named by `-a`: remote URLs as given, paths and glob patterns expanded
+fn expand_sources(attachments: &[String]) -> Result<Vec<String>> {
+    let mut sources = Vec::new();
+    for attachment in attachments {
+        if crate::readers::remote::is_remote(attachment) {
+            sources.push(attachment.clone());
+        } else if attachment.contains(['*', '?', '[']) {
+            let files = crate::vector_db::FileProcessor::expand_file_patterns(
+                std::slice::from_ref(attachment),
+            )?;
+            if files.is_empty() {
+                eprintln!("{} No files match '{}'", sym("⚠️").yellow(), attachment);
+            }
+            sources.extend(files.iter().map(|path| path.to_string_lossy().to_string()));
+        } else if Path::new(attachment).is_file() {
+            sources.push(attachment.clone());
+        } else {
+            anyhow::bail!("File '{}' not found", attachment);
+        }
+    }
+    sources.dedup();
+    Ok(sources)
+}
+
+/// The text of a document, through the reader for its format
+async fn read_document(source: &str) -> Result<String> {
+    if crate::readers::remote::is_remote(source) {
+        return Ok(crate::readers::remote::read(source).await?.content);
+    }
+    let extension = Path::new(source).extension().and_then(|e| e.to_str());
+    match extension.and_then(crate::readers::get_reader_for_extension) {
+        Some(reader) => reader.read_as_text(source),
+        None => std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read file '{}'", source)),
+    }
+}
+
+/// Handle `lc extract`: turn documents into JSON records matching a schema
+pub async fn handle(options: ExtractOptions) -> Result<()> {
+    let schema_text = std::fs::read_to_string(&options.schema)
+        .with_context(|| format!("Failed to read schema '{}'", options.schema))?;
+    let schema: Value = serde_json::from_str(&schema_text)
+        .with_context(|| format!("Schema '{}' is not valid JSON", options.schema))?;
+    if !schema.is_object() {
+        anyhow::bail!("Schema '{}' must be a JSON object", options.schema);
+    }
+
+    let sources = expand_sources(&options.attachments)?;
+    if sources.is_empty() {
+        return Err(anyhow!("No documents to extract from"));
+    }
+    let batch = sources.len() > 1;
+    if batch && options.source_key.is_some() && schema["type"] != "object" {
+        anyhow::bail!("--source-key needs a schema whose type is 'object'");
+    }
+
+    let mut config = Config::load()?;
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(&config, options.provider, options.model)?;
+    let client = create_authenticated_client(&mut config, &provider).await?;
+
+    let response_format = (!options.no_response_format).then(|| {
+        let name = Path::new(&options.schema)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("record");
+        structured_output::response_format(name, &schema)
+    });
+
+    let extractor = Extractor {
+        client,
+        provider,
+        model,
+        schema,
+        instructions: options.instructions,
+        system_prompt: options.system_prompt,
+        max_tokens: options.max_tokens.as_ref().and_then(|s| s.parse().ok()),
+        temperature: options.temperature.as_ref().and_then(|s| s.parse().ok()),
+        retries: options.retries,
+    };
+
+    let mut out: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path).with_context(|| format!("Failed to create '{}'", path))?,
+        )),
+        None => Box::new(std::io::stdout()),
+    };
+
+    let mut failed = 0;
+    for (index, source) in sources.iter().enumerate() {
+        if batch {
+            eprintln!(
+                "{} [{}/{}] {}",
+                sym("📄").blue(),
+                index + 1,
+                sources.len(),
+                source
+            );
+        }
+        let result = match read_document(source).await {
+            Ok(document) => {
+                structured_output::with_format(
+                    response_format.clone(),
+                    extractor.extract(source, &document),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        let mut record = match result {
+            Ok(record) => record,
+            Err(e) if batch => {
+                eprintln!("{} {}: {}", sym("✗").red(), source, e);
+                failed += 1;
+                continue;
+            }
+            Err(e) => return Err(e.context(format!("Failed to extract from '{}'", source))),
+        };
+
+        if let (Some(key), Some(object)) = (&options.source_key, record.as_object_mut()) {
+            object.insert(key.clone(), Value::String(source.clone()));
+        }
+        if batch {
+            writeln!(out, "{}", record)?;
+        } else {
+            writeln!(out, "{}", serde_json::to_string_pretty(&record)?)?;
+        }
+        out.flush()?;
+    }
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} document(s) could not be extracted",
+            failed,
+            sources.len()
+        );
+    }
+    if let Some(path) = &options.output {
+        eprintln!(
+            "{} Wrote {} record(s) to {}",
+            sym("✓").green(),
+            sources.len(),
+            path
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_prompts_and_sources() {
+        let schema = json!({"type": "object", "properties": {"name": {"type": "string"}}});
+        let prompt = extraction_prompt(&schema, Some("Names only"), "cv.txt", " Ada Lovelace\n");
+        assert!(prompt.contains("\"name\""));
+        assert!(
+            prompt.contains("Names only\n\n<document name=\"cv.txt\">\nAda Lovelace\n</document>")
+        );
+
+        let repair = repair_prompt(&["$: missing required property 'age'".to_string()]);
+        assert!(repair.contains("- $: missing required property 'age'\n"));
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.txt", "b.md"] {
+            std::fs::write(dir.path().join(name), "text").unwrap();
+        }
+        let pattern = dir.path().join("*").to_string_lossy().to_string();
+        assert_eq!(expand_sources(&[pattern]).unwrap().len(), 2);
+        let missing = dir.path().join("missing.pdf").to_string_lossy().to_string();
+        assert!(expand_sources(&[missing]).is_err());
+    }
+}
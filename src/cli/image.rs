@@ -1,5 +1,6 @@
 //! Image generation commands
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 use std::fs;
@@ -66,13 +67,13 @@ pub async fn handle(
 
     println!(
         "{} Generating {} image(s) with prompt: \"{}\"",
-        "🎨".blue(),
+        sym("🎨").blue(),
         count_val,
         prompt_str
     );
-    println!("{} Model: {}", "🤖".blue(), model_name);
-    println!("{} Provider: {}", "🏭".blue(), provider_name);
-    println!("{} Size: {}", "📐".blue(), size_str);
+    println!("{} Model: {}", sym("🤖").blue(), model_name);
+    println!("{} Provider: {}", sym("🏭").blue(), provider_name);
+    println!("{} Size: {}", sym("📐").blue(), size_str);
 
     // Create image generation request
     let image_request = crate::core::provider::ImageGenerationRequest {
@@ -94,7 +95,7 @@ pub async fn handle(
             print!("\r{}\r", " ".repeat(20)); // Clear "Generating..."
             println!(
                 "{} Successfully generated {} image(s)!",
-                "✅".green(),
+                sym("✅").green(),
                 response.data.len()
             );
 
@@ -103,7 +104,7 @@ pub async fn handle(
                 let path = Path::new(&dir);
                 if !path.exists() {
                     fs::create_dir_all(path)?;
-                    println!("{} Created output directory: {}", "📁".blue(), dir);
+                    println!("{} Created output directory: {}", sym("📁").blue(), dir);
                 }
                 Some(dir)
             } else {
@@ -117,7 +118,7 @@ pub async fn handle(
                 if let Some(url) = &image_data.url {
                     println!(
                         "\n{} Image {}/{}",
-                        "🖼️".blue(),
+                        sym("🖼️").blue(),
                         image_num,
                         response.data.len()
                     );
@@ -140,17 +141,21 @@ pub async fn handle(
 
                         match download_image(url, &filepath).await {
                             Ok(_) => {
-                                println!("   {} Saved to: {}", "💾".green(), filepath.display());
+                                println!(
+                                    "   {} Saved to: {}",
+                                    sym("💾").green(),
+                                    filepath.display()
+                                );
                             }
                             Err(e) => {
-                                eprintln!("   {} Failed to download image: {}", "❌".red(), e);
+                                eprintln!("   {} Failed to download image: {}", sym("❌").red(), e);
                             }
                         }
                     }
                 } else if let Some(b64_data) = &image_data.b64_json {
                     println!(
                         "\n{} Image {}/{} (Base64)",
-                        "🖼️".blue(),
+                        sym("🖼️").blue(),
                         image_num,
                         response.data.len()
                     );
@@ -166,10 +171,10 @@ pub async fn handle(
 
                     match save_base64_image(b64_data, &filepath) {
                         Ok(_) => {
-                            println!("   {} Saved to: {}", "💾".green(), filepath.display());
+                            println!("   {} Saved to: {}", sym("💾").green(), filepath.display());
                         }
                         Err(e) => {
-                            eprintln!("   {} Failed to save image: {}", "❌".red(), e);
+                            eprintln!("   {} Failed to save image: {}", sym("❌").red(), e);
                         }
                     }
 
@@ -187,7 +192,7 @@ pub async fn handle(
                 if has_url_images {
                     println!(
                         "\n{} Use --output <directory> to automatically download URL-based images",
-                        "💡".yellow()
+                        sym("💡").yellow()
                     );
                 }
             }
@@ -1,3 +1,4 @@
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 use std::io::{self, Write};
@@ -43,7 +44,7 @@ async fn add_key(name: String) -> Result<()> {
         io::stdin().read_line(&mut input)?;
         let input = input.trim();
 
-        let sa_json = if input.starts_with('/') || input.ends_with(".json") {
+        let sa_json = if std::path::Path::new(input).is_absolute() || input.ends_with(".json") {
             // Treat as file path
             match std::fs::read_to_string(input) {
                 Ok(file_content) => file_content,
@@ -98,7 +99,7 @@ async fn add_key(name: String) -> Result<()> {
         config.save()?;
         println!(
             "{} Service Account stored for provider '{}'",
-            "✓".green(),
+            sym("✓").green(),
             name
         );
     } else {
@@ -108,7 +109,7 @@ async fn add_key(name: String) -> Result<()> {
 
         config.set_api_key(name.clone(), key)?;
         config.save()?;
-        println!("{} API key set for provider '{}'", "✓".green(), name);
+        println!("{} API key set for provider '{}'", sym("✓").green(), name);
     }
 
     Ok(())
@@ -149,7 +150,7 @@ async fn list_keys() -> Result<()> {
         println!();
         println!(
             "{} Use '{}' to install a provider first",
-            "💡".yellow(),
+            sym("💡").yellow(),
             "lc providers install <name>".bold()
         );
         return Ok(());
@@ -164,11 +165,11 @@ async fn list_keys() -> Result<()> {
         // Check if provider has authentication in centralized keys
         let has_auth = keys.has_auth(name);
         let status = if has_auth {
-            "✓ Configured".green()
+            sym("✓ Configured").green()
         } else {
-            "✗ Missing".red()
+            sym("✗ Missing").red()
         };
-        println!("  {} {} - {}", "•".blue(), name.bold(), status);
+        println!("  {} {} - {}", sym("•").blue(), name.bold(), status);
     }
 
     Ok(())
@@ -185,7 +186,11 @@ async fn remove_key(name: String) -> Result<()> {
         provider_config.api_key = None;
     }
     config.save()?;
-    println!("{} API key removed for provider '{}'", "✓".green(), name);
+    println!(
+        "{} API key removed for provider '{}'",
+        sym("✓").green(),
+        name
+    );
 
     Ok(())
 }
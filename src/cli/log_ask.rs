@@ -0,0 +1,291 @@
+//! `lc logs ask`: answer questions from your own chat history
+//!
+//! Every logged turn is embedded into a vector index kept apart from the user's vector
+//! databases. Each run embeds the turns logged since the previous one and drops those
+//! whose logs were purged, then gives the turns closest to the question to the chat
+//! model, which answers citing the sessions they come from.
+
+use crate::utils::terminal::sym;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::collections::HashSet;
+
+use crate::chat::{create_authenticated_client, send_chat_request_with_validation, LLMClient};
+use crate::config::Config;
+use crate::database::{short_id, ChatEntry, Database};
+use crate::provider::EmbeddingRequest;
+use crate::vector_db::{VectorDatabase, VectorEntry};
+
+/// Longest question or answer of a turn kept in the index, in characters
+const MAX_PART_CHARS: usize = 2_000;
+
+/// Options of an `lc logs ask` run
+pub struct AskOptions {
+    pub question: String,
+    pub embedding_model: Option<String>,
+    pub count: usize,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+}
+
+/// Embeds text with the index's model
+struct Embedder {
+    client: LLMClient,
+    provider: String,
+    model: String,
+}
+
+impl Embedder {
+    async fn embed(&self, input: &str) -> Result<Vec<f64>> {
+        let request = EmbeddingRequest {
+            model: self.model.clone(),
+            input: input.to_string(),
+            encoding_format: Some("float".to_string()),
+        };
+        let response = self.client.embeddings(&request).await?;
+        response
+            .data
+            .into_iter()
+            .next()
+            .map(|data| data.embedding)
+            .ok_or_else(|| anyhow!("No embedding data in response"))
+    }
+}
+
+fn truncate(text: &str) -> &str {
+    match text.char_indices().nth(MAX_PART_CHARS) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// A logged turn as stored in the index
+fn turn_text(entry: &ChatEntry) -> String {
+    format!(
+        "[{} · session {}]\nUser: {}\nAssistant: {}",
+        entry.timestamp.format("%Y-%m-%d"),
+        short_id(&entry.chat_id),
+        truncate(entry.question.trim()),
+        truncate(entry.response.trim())
+    )
+}
+
+/// The request answering `question` from the retrieved turns
+fn ask_prompt(question: &str, turns: &[VectorEntry]) -> String {
+    let excerpts: Vec<&str> = turns.iter().map(|turn| turn.text.as_str()).collect();
+    format!(
+        "Below are excerpts of my earlier conversations with AI assistants, each headed by \
+         its date and session. Answer my question from them. Cite the sessions you rely on \
+         as [session <id>]. If the excerpts do not answer the question, say so.\n\n\
+         <excerpts>\n{}\n</excerpts>\n\nQuestion: {}",
+        excerpts.join("\n\n---\n\n"),
+        question.trim()
+    )
+}
+
+/// Bring the index up to date with the logs: embed new turns, drop purged ones
+async fn sync_index(
+    index: &VectorDatabase,
+    embedder: &Embedder,
+    entries: &[ChatEntry],
+) -> Result<()> {
+    let turns: Vec<(String, &str)> = entries
+        .iter()
+        .map(|entry| (turn_text(entry), entry.chat_id.as_str()))
+        .collect();
+    let current: HashSet<&str> = turns.iter().map(|(text, _)| text.as_str()).collect();
+
+    let stored = index.get_all_vectors()?;
+    let purged: Vec<i64> = stored
+        .iter()
+        .filter(|entry| !current.contains(entry.text.as_str()))
+        .map(|entry| entry.id)
+        .collect();
+    if !purged.is_empty() {
+        index.delete_vectors(&purged)?;
+        crate::debug_log!(
+            "Dropped {} purged turns from the history index",
+            purged.len()
+        );
+    }
+
+    let stored: HashSet<String> = stored.into_iter().map(|entry| entry.text).collect();
+    let new: Vec<&(String, &str)> = turns
+        .iter()
+        .filter(|(text, _)| !stored.contains(text))
+        .collect();
+    if new.is_empty() {
+        return Ok(());
+    }
+    eprintln!(
+        "{} Indexing {} new turn{} of your history...",
+        sym("🔍").blue(),
+        new.len(),
+        if new.len() == 1 { "" } else { "s" }
+    );
+    for (text, chat_id) in new {
+        let vector = embedder.embed(text).await?;
+        index.add_vector_with_metadata(
+            text,
+            &vector,
+            &embedder.model,
+            &embedder.provider,
+            Some(chat_id),
+            None,
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Handle `lc logs ask`
+pub async fn handle(db: &Database, options: AskOptions) -> Result<()> {
+    let entries = db.get_all_logs()?;
+    if entries.is_empty() {
+        anyhow::bail!("The chat history is empty");
+    }
+
+    let mut config = Config::load()?;
+    let index = VectorDatabase::for_history()?;
+    let (provider, model) = match (&options.embedding_model, index.get_model_info()?) {
+        (Some(embedding_model), stored) => {
+            let (provider, model) = crate::utils::resolve_model_and_provider(
+                &config,
+                None,
+                Some(embedding_model.clone()),
+            )?;
+            if let Some((stored_model, stored_provider)) = stored {
+                if (&stored_provider, &stored_model) != (&provider, &model) {
+                    // Vectors of different models are not comparable
+                    eprintln!(
+                        "{} Re-indexing the history with {}:{} (was {}:{})",
+                        sym("🔄").blue(),
+                        provider,
+                        model,
+                        stored_provider,
+                        stored_model
+                    );
+                    let ids: Vec<i64> = index.get_all_vectors()?.iter().map(|e| e.id).collect();
+                    index.delete_vectors(&ids)?;
+                }
+            }
+            (provider, model)
+        }
+        (None, Some((model, provider))) => (provider, model),
+        (None, None) => anyhow::bail!(
+            "The history has not been indexed yet. Pass an embedding model, e.g. \
+             lc logs ask -e openai:text-embedding-3-small \"...\""
+        ),
+    };
+
+    let embedder = Embedder {
+        client: create_authenticated_client(&mut config, &provider).await?,
+        provider,
+        model,
+    };
+    sync_index(&index, &embedder, &entries).await?;
+
+    let query = embedder.embed(&options.question).await?;
+    let turns: Vec<VectorEntry> = index
+        .find_similar(&query, options.count.max(1))?
+        .into_iter()
+        .map(|(entry, _)| entry)
+        .collect();
+
+    let (chat_provider, chat_model) =
+        crate::utils::resolve_model_and_provider(&config, options.provider, options.model)?;
+    let client = create_authenticated_client(&mut config, &chat_provider).await?;
+    let (answer, _, _) = send_chat_request_with_validation(
+        &client,
+        &chat_model,
+        &ask_prompt(&options.question, &turns),
+        &[],
+        None,
+        config.max_tokens,
+        config.temperature,
+        &chat_provider,
+        None,
+    )
+    .await?;
+    println!("{}", crate::core::redaction::redact(answer.trim()));
+
+    // The sessions the answer was drawn from, most relevant first
+    let mut seen = HashSet::new();
+    println!("\n{}", "Sessions:".bold().blue());
+    for turn in &turns {
+        let Some(chat_id) = turn.file_path.as_deref() else {
+            continue;
+        };
+        if !seen.insert(chat_id) {
+            continue;
+        }
+        let first = entries.iter().find(|entry| entry.chat_id == chat_id);
+        let (date, question) = first.map_or((String::new(), ""), |entry| {
+            (
+                entry.timestamp.format("%Y-%m-%d").to_string(),
+                entry.question.lines().next().unwrap_or(""),
+            )
+        });
+        let question: String = question.chars().take(60).collect();
+        println!(
+            "  {} {} {} {}",
+            sym("•").blue(),
+            short_id(chat_id).bold(),
+            date.dimmed(),
+            question
+        );
+        println!(
+            "    {}",
+            format!("lc logs show --session {}", short_id(chat_id)).dimmed()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_turn_text_and_prompt() {
+        let entry = ChatEntry {
+            chat_id: "0123456789abcdef".to_string(),
+            model: "gpt-4o".to_string(),
+            question: "Should billing move to events?".to_string(),
+            response: format!("Yes. {}", "x".repeat(3_000)),
+            timestamp: chrono::Utc.with_ymd_and_hms(2026, 3, 9, 12, 0, 0).unwrap(),
+            input_tokens: None,
+            output_tokens: None,
+            provider: None,
+        };
+        let text = turn_text(&entry);
+        assert!(text.starts_with(
+            "[2026-03-09 · session 01234567]\nUser: Should billing move to events?\nAssistant: Yes."
+        ));
+        assert_eq!(
+            text.lines().last().unwrap().chars().count(),
+            11 + MAX_PART_CHARS
+        );
+
+        let turn = VectorEntry {
+            id: 1,
+            text,
+            vector: Vec::new(),
+            model: String::new(),
+            provider: String::new(),
+            created_at: chrono::Utc::now(),
+            file_path: Some(entry.chat_id.clone()),
+            chunk_index: None,
+            total_chunks: None,
+            chunk_offset: None,
+            chunk_line: None,
+            tags: Vec::new(),
+            summary: None,
+            mime_type: None,
+        };
+        let prompt = ask_prompt(" What did I decide? ", &[turn]);
+        assert!(prompt.contains("<excerpts>\n[2026-03-09 · session 01234567]"));
+        assert!(prompt.ends_with("</excerpts>\n\nQuestion: What did I decide?"));
+    }
+}
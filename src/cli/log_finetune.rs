@@ -0,0 +1,219 @@
+//! `lc logs export-finetune`: turn logged sessions into chat-format fine-tuning JSONL
+//!
+//! Each selected session becomes one line, `{"messages": [...]}`, with the session's
+//! system prompt as the `system` message and every logged turn as a `user` message and
+//! the `assistant` answer, the format OpenAI fine-tuning (and most trainers) accept.
+//! With `--scrub-pii`, e-mail addresses, phone numbers, IP addresses and card numbers are
+//! replaced with placeholders; stored keys and `[redaction]` patterns are always masked.
+
+use crate::database::{Database, LoggedChatEntry};
+use crate::utils::regex_cache::get_regex;
+use crate::utils::terminal::sym;
+use anyhow::{bail, Result};
+use colored::Colorize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Placeholders for each kind of personal data, most specific pattern first
+const PII_PATTERNS: &[(&str, &str)] = &[
+    (r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", "[EMAIL]"),
+    (r"\b(?:\d[ -]?){13,16}\b", "[CARD_NUMBER]"),
+    (r"\b(?:\d{1,3}\.){3}\d{1,3}\b", "[IP_ADDRESS]"),
+    (
+        r"(?:\+\d{1,3}[ .-]?)?\(?\b\d{3}\)?[ .-]?\d{3}[ .-]?\d{4}\b",
+        "[PHONE]",
+    ),
+];
+
+pub struct ExportOptions {
+    /// `all`, `current`, or comma-separated session IDs or unique prefixes
+    pub sessions: String,
+    /// key=value metadata filters the sessions' entries must match
+    pub filters: Vec<String>,
+    pub output: String,
+    pub scrub_pii: bool,
+    /// Leave the system prompts out of the examples
+    pub no_system: bool,
+}
+
+pub fn handle(db: &Database, options: ExportOptions) -> Result<()> {
+    let filters = crate::core::request_metadata::parse_entries(&options.filters)?;
+    let chat_ids = select_sessions(db, &options.sessions)?;
+
+    let mut lines = Vec::new();
+    let mut turns = 0;
+    for chat_id in &chat_ids {
+        let entries: Vec<LoggedChatEntry> = db
+            .get_session_logs(chat_id)?
+            .into_iter()
+            .filter(|logged| crate::cli::logging::matches_filters(logged, &filters))
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+        let example = to_example(&entries, &options);
+        lines.push(serde_json::to_string(&example)?);
+        turns += entries.len();
+    }
+
+    // The file is only created once there is something to write, so a filter that
+    // matches nothing leaves an existing file alone
+    let examples = lines.len();
+    if examples == 0 {
+        bail!(
+            "No sessions matched; nothing was written to '{}'",
+            options.output
+        );
+    }
+    let mut file = std::fs::File::create(&options.output)
+        .map_err(|e| anyhow::anyhow!("Failed to create '{}': {}", options.output, e))?;
+    for line in &lines {
+        writeln!(file, "{}", line)?;
+    }
+    println!(
+        "{} Exported {} session{} ({} turns) to {}",
+        sym("✓").green(),
+        examples,
+        if examples == 1 { "" } else { "s" },
+        turns,
+        options.output
+    );
+    Ok(())
+}
+
+/// Full session IDs for the `--sessions` selection
+fn select_sessions(db: &Database, selection: &str) -> Result<Vec<String>> {
+    match selection.trim() {
+        "all" => Ok(db
+            .list_sessions(i64::MAX as usize)?
+            .into_iter()
+            .rev()
+            .map(|session| session.chat_id)
+            .collect()),
+        "current" => Ok(vec![db
+            .get_current_session_id()?
+            .ok_or_else(|| anyhow::anyhow!("No current session"))?]),
+        list => list
+            .split(',')
+            .map(str::trim)
+            .filter(|prefix| !prefix.is_empty())
+            .map(|prefix| db.resolve_session_id(prefix))
+            .collect(),
+    }
+}
+
+/// The fine-tuning example for a session's entries (oldest first)
+fn to_example(entries: &[LoggedChatEntry], options: &ExportOptions) -> serde_json::Value {
+    let clean = |text: &str| {
+        let text = crate::core::redaction::redact(text);
+        if options.scrub_pii {
+            scrub_pii(&text)
+        } else {
+            text.into_owned()
+        }
+    };
+
+    let mut messages = Vec::new();
+    let system_prompt = entries
+        .iter()
+        .find_map(|logged| logged.system_prompt.as_deref())
+        .filter(|prompt| !options.no_system && !prompt.trim().is_empty());
+    if let Some(prompt) = system_prompt {
+        messages.push(message("system", &clean(prompt)));
+    }
+    for logged in entries {
+        messages.push(message("user", &clean(&logged.entry.question)));
+        messages.push(message("assistant", &clean(&logged.entry.response)));
+    }
+    json!({ "messages": messages })
+}
+
+fn message(role: &str, content: &str) -> BTreeMap<&'static str, serde_json::Value> {
+    BTreeMap::from([("role", json!(role)), ("content", json!(content))])
+}
+
+/// Text with personal data replaced by placeholders
+pub fn scrub_pii(text: &str) -> String {
+    let mut text = text.to_string();
+    for (pattern, placeholder) in PII_PATTERNS {
+        if let Ok(regex) = get_regex(pattern) {
+            text = regex.replace_all(&text, *placeholder).into_owned();
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ChatEntry;
+
+    fn logged(question: &str, response: &str, system_prompt: Option<&str>) -> LoggedChatEntry {
+        LoggedChatEntry {
+            entry: ChatEntry {
+                chat_id: "abc".to_string(),
+                model: "gpt-4o".to_string(),
+                question: question.to_string(),
+                response: response.to_string(),
+                timestamp: chrono::Utc::now(),
+                input_tokens: None,
+                output_tokens: None,
+                provider: None,
+            },
+            user: None,
+            metadata: BTreeMap::new(),
+            system_prompt: system_prompt.map(str::to_string),
+            reasoning: None,
+            images: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_scrub_pii() {
+        assert_eq!(
+            scrub_pii("Mail jane.doe@example.com or call +1 415-555-0132 from 10.0.0.12"),
+            "Mail [EMAIL] or call [PHONE] from [IP_ADDRESS]"
+        );
+        assert_eq!(scrub_pii("Card 4111 1111 1111 1111"), "Card [CARD_NUMBER]");
+        assert_eq!(
+            scrub_pii("Rust 1.88 and 42 crates"),
+            "Rust 1.88 and 42 crates"
+        );
+    }
+
+    #[test]
+    fn test_example_roles() {
+        let options = ExportOptions {
+            sessions: "all".to_string(),
+            filters: Vec::new(),
+            output: String::new(),
+            scrub_pii: true,
+            no_system: false,
+        };
+        let entries = vec![
+            logged("Hi, I'm bob@example.com", "Hello!", Some("Be brief.")),
+            logged("Thanks", "You're welcome.", Some("Be brief.")),
+        ];
+        let example = to_example(&entries, &options);
+        let messages = example["messages"].as_array().unwrap();
+        let roles: Vec<&str> = messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            roles,
+            vec!["system", "user", "assistant", "user", "assistant"]
+        );
+        assert_eq!(messages[1]["content"], "Hi, I'm [EMAIL]");
+
+        let options = ExportOptions {
+            no_system: true,
+            ..options
+        };
+        assert_eq!(
+            to_example(&entries, &options)["messages"][0]["role"],
+            "user"
+        );
+    }
+}
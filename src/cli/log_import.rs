@@ -0,0 +1,490 @@
+//! `lc logs import-chatgpt` and `lc logs import-claude`: bring conversations exported from
+//! the ChatGPT and Claude apps into the log
+//!
+//! Both apps export a zip holding a `conversations.json`; the zip or the extracted file
+//! is read. Every conversation becomes a session under its original ID (or, when that is
+//! not a UUID, a UUID derived from it), named after its title, with the original timestamps and, for ChatGPT, the model of each answer. A user
+//! message and the assistant messages after it make one turn. ChatGPT conversations are
+//! trees of edits and regenerations; the branch shown last is imported. Images, files and
+//! tool output are left out. Conversations already in the log are skipped, so a newer
+//! export can be imported over an older one.
+
+use crate::database::{ArchivedTurn, Database, SessionArchive};
+use crate::utils::terminal::sym;
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use colored::Colorize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+
+/// File holding the conversations in both apps' exports
+const CONVERSATIONS_FILE: &str = "conversations.json";
+/// Metadata key recording where an imported turn came from
+const SOURCE_KEY: &str = "imported_from";
+
+/// The app an export comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportSource {
+    ChatGpt,
+    Claude,
+}
+
+impl ExportSource {
+    fn name(self) -> &'static str {
+        match self {
+            ExportSource::ChatGpt => "chatgpt",
+            ExportSource::Claude => "claude",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ExportSource::ChatGpt => "ChatGPT",
+            ExportSource::Claude => "Claude",
+        }
+    }
+}
+
+pub fn handle(db: &Database, source: ExportSource, file: &str) -> Result<()> {
+    let conversations = read_conversations(file)?;
+    let sessions = match source {
+        ExportSource::ChatGpt => chatgpt_sessions(&conversations)?,
+        ExportSource::Claude => claude_sessions(&conversations)?,
+    };
+
+    let (mut imported, mut turns, mut skipped) = (0, 0, 0);
+    for session in &sessions {
+        if !db.get_session_logs(&session.session_id)?.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        db.import_session(session)?;
+        imported += 1;
+        turns += session.turns.len();
+    }
+
+    println!(
+        "{} Imported {} {} conversation{} ({} turn{}) from {}",
+        sym("✓").green(),
+        imported,
+        source.label(),
+        if imported == 1 { "" } else { "s" },
+        turns,
+        if turns == 1 { "" } else { "s" },
+        file
+    );
+    if skipped > 0 {
+        println!(
+            "{} Skipped {} conversation{} already in the log",
+            sym("•").blue(),
+            skipped,
+            if skipped == 1 { "" } else { "s" }
+        );
+    }
+    if imported > 0 {
+        println!(
+            "{} Browse them with 'lc logs sessions', continue one with 'lc chat --cid <id>'",
+            sym("💡").yellow()
+        );
+    }
+    Ok(())
+}
+
+/// The parsed `conversations.json` of an export zip, or of the file itself
+fn read_conversations(file: &str) -> Result<Value> {
+    let mut reader =
+        std::fs::File::open(file).with_context(|| format!("Failed to read '{}'", file))?;
+    let mut magic = [0u8; 2];
+    let is_zip = reader.read_exact(&mut magic).is_ok() && &magic == b"PK";
+    reader.rewind()?;
+    let json = if is_zip {
+        let mut archive = zip::ZipArchive::new(reader)
+            .with_context(|| format!("'{}' is not a valid zip file", file))?;
+        let name = archive
+            .file_names()
+            .find(|name| {
+                *name == CONVERSATIONS_FILE || name.ends_with(&format!("/{}", CONVERSATIONS_FILE))
+            })
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("'{}' has no {}", file, CONVERSATIONS_FILE))?;
+        let mut json = String::new();
+        archive.by_name(&name)?.read_to_string(&mut json)?;
+        json
+    } else {
+        let mut json = String::new();
+        reader
+            .read_to_string(&mut json)
+            .with_context(|| format!("'{}' is not a JSON file", file))?;
+        json
+    };
+    serde_json::from_str(&json).with_context(|| format!("'{}' is not an app export", file))
+}
+
+/// A message of an exported conversation
+#[derive(Debug)]
+struct Message {
+    role: Role,
+    text: String,
+    timestamp: Option<DateTime<Utc>>,
+    model: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// Sessions of a ChatGPT `conversations.json`
+fn chatgpt_sessions(conversations: &Value) -> Result<Vec<SessionArchive>> {
+    let conversations = conversations
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Expected a list of ChatGPT conversations"))?;
+    let mut sessions = Vec::new();
+    for conversation in conversations {
+        let Some(id) = conversation["conversation_id"]
+            .as_str()
+            .or(conversation["id"].as_str())
+        else {
+            continue;
+        };
+        let default_model = conversation["default_model_slug"].as_str();
+        let messages: Vec<Message> = chatgpt_branch(conversation)
+            .into_iter()
+            .filter_map(|message| chatgpt_message(message, default_model))
+            .collect();
+        let started = unix_time(&conversation["create_time"]);
+        if let Some(session) = session(
+            id,
+            conversation["title"].as_str(),
+            started,
+            messages,
+            ExportSource::ChatGpt,
+        ) {
+            sessions.push(session);
+        }
+    }
+    Ok(sessions)
+}
+
+/// The messages from the root to the conversation's current node
+fn chatgpt_branch(conversation: &Value) -> Vec<&Value> {
+    let mapping = &conversation["mapping"];
+    let mut branch = Vec::new();
+    let mut node_id = conversation["current_node"].as_str();
+    while let Some(id) = node_id {
+        let node = &mapping[id];
+        if node.is_null() || branch.len() > 100_000 {
+            break;
+        }
+        if !node["message"].is_null() {
+            branch.push(&node["message"]);
+        }
+        node_id = node["parent"].as_str();
+    }
+    branch.reverse();
+    branch
+}
+
+fn chatgpt_message(message: &Value, default_model: Option<&str>) -> Option<Message> {
+    let role = match message["author"]["role"].as_str()? {
+        "system" => Role::System,
+        "user" => Role::User,
+        "assistant" => Role::Assistant,
+        _ => return None,
+    };
+    if message["metadata"]["is_visually_hidden_from_conversation"].as_bool() == Some(true) {
+        return None;
+    }
+    // Code run by the assistant, its reasoning and browsing steps are not answers
+    let content = &message["content"];
+    if !matches!(
+        content["content_type"].as_str(),
+        Some("text" | "multimodal_text")
+    ) {
+        return None;
+    }
+    let text = content["parts"]
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .filter(|part| !part.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if text.trim().is_empty() {
+        return None;
+    }
+    let model = message["metadata"]["model_slug"]
+        .as_str()
+        .or(default_model)
+        .map(str::to_string);
+    Some(Message {
+        role,
+        text,
+        timestamp: unix_time(&message["create_time"]),
+        model,
+    })
+}
+
+/// Sessions of a Claude `conversations.json`
+fn claude_sessions(conversations: &Value) -> Result<Vec<SessionArchive>> {
+    let conversations = conversations
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Expected a list of Claude conversations"))?;
+    let mut sessions = Vec::new();
+    for conversation in conversations {
+        let Some(id) = conversation["uuid"].as_str() else {
+            continue;
+        };
+        let messages: Vec<Message> = conversation["chat_messages"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(claude_message)
+            .collect();
+        let started = iso_time(&conversation["created_at"]);
+        if let Some(session) = session(
+            id,
+            conversation["name"].as_str(),
+            started,
+            messages,
+            ExportSource::Claude,
+        ) {
+            sessions.push(session);
+        }
+    }
+    Ok(sessions)
+}
+
+fn claude_message(message: &Value) -> Option<Message> {
+    let role = match message["sender"].as_str()? {
+        "human" => Role::User,
+        "assistant" => Role::Assistant,
+        _ => return None,
+    };
+    // Newer exports split messages into content blocks; tool use blocks are left out
+    let blocks: Vec<&str> = message["content"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|block| block["type"] == "text")
+        .filter_map(|block| block["text"].as_str())
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    let text = if blocks.is_empty() {
+        message["text"].as_str()?.to_string()
+    } else {
+        blocks.join("\n\n")
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+    Some(Message {
+        role,
+        text,
+        timestamp: iso_time(&message["created_at"]),
+        model: None,
+    })
+}
+
+/// The session ID of a conversation: its own ID when that is a UUID, otherwise a UUID
+/// derived from it, so importing the same export again finds the same session
+fn session_id(id: &str) -> String {
+    if let Ok(uuid) = uuid::Uuid::parse_str(id) {
+        return uuid.to_string();
+    }
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(id.as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes)
+        .into_uuid()
+        .to_string()
+}
+
+/// A session of the messages, `None` when they hold no question
+fn session(
+    id: &str,
+    title: Option<&str>,
+    started: Option<DateTime<Utc>>,
+    messages: Vec<Message>,
+    source: ExportSource,
+) -> Option<SessionArchive> {
+    let metadata = BTreeMap::from([(SOURCE_KEY.to_string(), source.name().to_string())]);
+    let mut turns: Vec<ArchivedTurn> = Vec::new();
+    let mut system_prompt: Option<String> = None;
+    let mut last_time = started;
+    for message in messages {
+        // Messages without a time keep the order of the ones before them
+        let timestamp = match (message.timestamp, last_time) {
+            (Some(time), Some(last)) => time.max(last),
+            (time, last) => time.or(last).unwrap_or_else(Utc::now),
+        };
+        last_time = Some(timestamp);
+        match message.role {
+            Role::System => system_prompt = Some(message.text),
+            Role::User => match turns.last_mut() {
+                // Messages sent before an answer make one question
+                Some(turn) if turn.response.is_empty() => {
+                    turn.question.push_str("\n\n");
+                    turn.question.push_str(&message.text);
+                }
+                _ => turns.push(ArchivedTurn {
+                    timestamp,
+                    provider: None,
+                    model: source.name().to_string(),
+                    system_prompt: system_prompt.clone(),
+                    question: message.text,
+                    response: String::new(),
+                    reasoning: None,
+                    input_tokens: None,
+                    output_tokens: None,
+                    user: None,
+                    metadata: metadata.clone(),
+                    images: Vec::new(),
+                }),
+            },
+            Role::Assistant => {
+                // A greeting before the first question has no turn to belong to
+                let Some(turn) = turns.last_mut() else {
+                    continue;
+                };
+                if !turn.response.is_empty() {
+                    turn.response.push_str("\n\n");
+                }
+                turn.response.push_str(&message.text);
+                if let Some(model) = message.model {
+                    turn.model = model;
+                }
+            }
+        }
+    }
+
+    let (first, last) = (turns.first()?, turns.last()?);
+    Some(SessionArchive {
+        session_id: session_id(id),
+        name: title
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(str::to_string),
+        started: started.unwrap_or(first.timestamp).min(first.timestamp),
+        last_active: last.timestamp,
+        turns,
+        tool_calls: Vec::new(),
+    })
+}
+
+/// A time in seconds since the epoch, as ChatGPT exports write them
+fn unix_time(value: &Value) -> Option<DateTime<Utc>> {
+    let seconds = value.as_f64()?;
+    Utc.timestamp_millis_opt((seconds * 1000.0) as i64).single()
+}
+
+/// An RFC 3339 time, as Claude exports write them
+fn iso_time(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_chatgpt_sessions() {
+        let export = json!([{
+            "id": "c1",
+            "title": "Borrow checker",
+            "create_time": 1700000000.5,
+            "default_model_slug": "gpt-4o",
+            "current_node": "a2",
+            "mapping": {
+                "root": {"id": "root", "message": null, "parent": null, "children": ["u1"]},
+                "u1": {"parent": "root", "message": {
+                    "author": {"role": "user"}, "create_time": 1700000001.0,
+                    "content": {"content_type": "text", "parts": ["What is a borrow?"]}}},
+                "a1-old": {"parent": "u1", "message": {
+                    "author": {"role": "assistant"},
+                    "content": {"content_type": "text", "parts": ["Regenerated away"]}}},
+                "a1": {"parent": "u1", "message": {
+                    "author": {"role": "assistant"}, "create_time": 1700000002.0,
+                    "metadata": {"model_slug": "o3"},
+                    "content": {"content_type": "text", "parts": ["A reference."]}}},
+                "t1": {"parent": "a1", "message": {
+                    "author": {"role": "tool"},
+                    "content": {"content_type": "execution_output", "text": "42"}}},
+                "u2": {"parent": "t1", "message": {
+                    "author": {"role": "user"}, "create_time": 1700000003.0,
+                    "content": {"content_type": "multimodal_text",
+                                "parts": [{"content_type": "image_asset_pointer"}, "And this?"]}}},
+                "a2": {"parent": "u2", "message": {
+                    "author": {"role": "assistant"}, "create_time": 1700000004.0,
+                    "content": {"content_type": "text", "parts": ["A picture."]}}}
+            }
+        }]);
+        let sessions = chatgpt_sessions(&export).unwrap();
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.session_id, session_id("c1"));
+        assert_eq!(session.name.as_deref(), Some("Borrow checker"));
+        assert_eq!(session.started.timestamp_millis(), 1_700_000_000_500);
+        let turns: Vec<(&str, &str, &str)> = session
+            .turns
+            .iter()
+            .map(|t| (t.question.as_str(), t.response.as_str(), t.model.as_str()))
+            .collect();
+        assert_eq!(
+            turns,
+            [
+                ("What is a borrow?", "A reference.", "o3"),
+                ("And this?", "A picture.", "gpt-4o")
+            ]
+        );
+        assert_eq!(session.last_active.timestamp(), 1_700_000_003);
+        assert_eq!(session.turns[0].metadata[SOURCE_KEY], "chatgpt");
+    }
+
+    #[test]
+    fn test_claude_sessions() {
+        let export = json!([
+            {
+                "uuid": "k1",
+                "name": "",
+                "created_at": "2024-05-01T10:00:00.000000Z",
+                "chat_messages": [
+                    {"sender": "human", "text": "Hi", "created_at": "2024-05-01T10:00:01Z"},
+                    {"sender": "human", "text": "Explain lifetimes", "created_at": "2024-05-01T10:00:02Z"},
+                    {"sender": "assistant", "text": "", "created_at": "2024-05-01T10:00:03Z",
+                     "content": [{"type": "text", "text": "Lifetimes are"},
+                                 {"type": "tool_use", "name": "search"},
+                                 {"type": "text", "text": "scopes."}]}
+                ]
+            },
+            {"uuid": "k2", "name": "Empty", "chat_messages": []}
+        ]);
+        let sessions = claude_sessions(&export).unwrap();
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.name, None);
+        assert_eq!(session.turns.len(), 1);
+        assert_eq!(session.turns[0].question, "Hi\n\nExplain lifetimes");
+        assert_eq!(session.turns[0].response, "Lifetimes are\n\nscopes.");
+        assert_eq!(session.turns[0].model, "claude");
+        assert!(claude_sessions(&json!({"uuid": "x"})).is_err());
+    }
+
+    #[test]
+    fn test_session_id() {
+        let uuid = "3F2A9C1E-7B4D-4E2A-9C1E-7B4D4E2A9C1E";
+        assert_eq!(session_id(uuid), uuid.to_lowercase());
+        assert!(uuid::Uuid::parse_str(&session_id("c1")).is_ok());
+        assert_eq!(session_id("c1"), session_id("c1"));
+        assert_ne!(session_id("c1"), session_id("c2"));
+    }
+}
@@ -0,0 +1,387 @@
+//! `lc logs prune`: walk sessions grouped by age and decide, group by group or one by
+//! one, which to keep, delete or archive (export to JSON, then delete)
+
+use crate::database::{self, SessionInfo};
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use colored::Colorize;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum AgeGroup {
+    Older,
+    Last90Days,
+    Last30Days,
+    LastWeek,
+    Today,
+}
+
+impl AgeGroup {
+    fn of(last_active: DateTime<Utc>, now: DateTime<Utc>) -> Self {
+        let age = now - last_active;
+        if age < Duration::days(1) {
+            AgeGroup::Today
+        } else if age < Duration::days(7) {
+            AgeGroup::LastWeek
+        } else if age < Duration::days(30) {
+            AgeGroup::Last30Days
+        } else if age < Duration::days(90) {
+            AgeGroup::Last90Days
+        } else {
+            AgeGroup::Older
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AgeGroup::Today => "Active in the last day",
+            AgeGroup::LastWeek => "Active in the last week",
+            AgeGroup::Last30Days => "Active in the last 30 days",
+            AgeGroup::Last90Days => "Active in the last 90 days",
+            AgeGroup::Older => "Inactive for more than 90 days",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Keep,
+    Delete,
+    Archive,
+}
+
+/// Sessions by age group, oldest group first and largest session first within a group
+fn group_sessions(
+    sessions: Vec<SessionInfo>,
+    now: DateTime<Utc>,
+) -> Vec<(AgeGroup, Vec<SessionInfo>)> {
+    let mut groups: Vec<(AgeGroup, Vec<SessionInfo>)> = Vec::new();
+    for session in sessions {
+        let group = AgeGroup::of(session.last_active, now);
+        match groups.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, members)) => members.push(session),
+            None => groups.push((group, vec![session])),
+        }
+    }
+    groups.sort_by_key(|(group, _)| *group);
+    for (_, members) in &mut groups {
+        members.sort_by_key(|s| std::cmp::Reverse(s.size_bytes));
+    }
+    groups
+}
+
+fn format_size(bytes: i64) -> String {
+    let bytes = bytes.max(0) as f64;
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", bytes / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes / 1024.0)
+    }
+}
+
+fn summary_line(session: &SessionInfo) -> String {
+    let question: String = session
+        .first_question
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let question = if question.chars().count() > 60 {
+        format!("{}...", question.chars().take(57).collect::<String>())
+    } else {
+        question
+    };
+    format!(
+        "{}  {}  {} turn{}  {}  {}",
+        crate::database::short_id(&session.chat_id),
+        crate::utils::time_display::current().format_as(&session.last_active, "%Y-%m-%d"),
+        session.turns,
+        if session.turns == 1 { "" } else { "s" },
+        format_size(session.size_bytes),
+        question.dimmed()
+    )
+}
+
+/// Read a one-letter answer; None at end of input
+fn ask(input: &mut impl BufRead, prompt: &str) -> Result<Option<char>> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        println!();
+        return Ok(None);
+    }
+    Ok(line.trim().to_lowercase().chars().next().or(Some(' ')))
+}
+
+pub fn handle(
+    db: &database::Database,
+    interactive: bool,
+    older_than_days: Option<u32>,
+    archive_dir: Option<String>,
+) -> Result<()> {
+    let now = Utc::now();
+    let sessions: Vec<SessionInfo> = db
+        .list_sessions(i64::MAX as usize)?
+        .into_iter()
+        .filter(|s| {
+            older_than_days.is_none_or(|days| now - s.last_active >= Duration::days(days as i64))
+        })
+        .collect();
+    if sessions.is_empty() {
+        println!("{} No sessions to prune", sym("ℹ️").blue());
+        return Ok(());
+    }
+    let groups = group_sessions(sessions, now);
+
+    if !interactive {
+        println!("{}", "Sessions by age:".bold());
+        for (group, members) in &groups {
+            let size: i64 = members.iter().map(|s| s.size_bytes).sum();
+            println!(
+                "  {:<32} {:>5} session{}  {:>10}",
+                group.label(),
+                members.len(),
+                if members.len() == 1 { " " } else { "s" },
+                format_size(size)
+            );
+        }
+        println!(
+            "\n{}",
+            "Run 'lc logs prune --interactive' to choose what to delete or archive.".dimmed()
+        );
+        return Ok(());
+    }
+
+    crate::lock::ensure_writable("prune logs")?;
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut decisions: Vec<(SessionInfo, Action)> = Vec::new();
+
+    'groups: for (group, members) in groups {
+        let size: i64 = members.iter().map(|s| s.size_bytes).sum();
+        println!(
+            "\n{} ({} session{}, {})",
+            group.label().bold(),
+            members.len(),
+            if members.len() == 1 { "" } else { "s" },
+            format_size(size)
+        );
+        for session in members.iter().take(5) {
+            println!("  {}", summary_line(session));
+        }
+        if members.len() > 5 {
+            println!(
+                "  {}",
+                format!("... and {} more", members.len() - 5).dimmed()
+            );
+        }
+
+        let choice = loop {
+            match ask(
+                &mut input,
+                "[k]eep all, [d]elete all, [a]rchive all, [r]eview each, [q]uit: ",
+            )? {
+                Some(c @ ('k' | 'd' | 'a' | 'r')) => break c,
+                Some('q') | None => break 'groups,
+                _ => continue,
+            }
+        };
+        match choice {
+            'k' => {}
+            'd' => decisions.extend(members.into_iter().map(|s| (s, Action::Delete))),
+            'a' => decisions.extend(members.into_iter().map(|s| (s, Action::Archive))),
+            _ => {
+                for session in members {
+                    println!("\n  {}", summary_line(&session));
+                    let action = loop {
+                        match ask(
+                            &mut input,
+                            "  [k]eep, [d]elete, [a]rchive, [v]iew, [q]uit: ",
+                        )? {
+                            Some('k') => break Action::Keep,
+                            Some('d') => break Action::Delete,
+                            Some('a') => break Action::Archive,
+                            Some('v') => show_session(db, &session.chat_id)?,
+                            Some('q') | None => break 'groups,
+                            _ => {}
+                        }
+                    };
+                    if action != Action::Keep {
+                        decisions.push((session, action));
+                    }
+                }
+            }
+        }
+    }
+
+    let deleted = decisions
+        .iter()
+        .filter(|(_, a)| *a == Action::Delete)
+        .count();
+    let archived = decisions.len() - deleted;
+    if decisions.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+    let size: i64 = decisions.iter().map(|(s, _)| s.size_bytes).sum();
+    let archive_dir = match archive_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => crate::config::Config::config_dir()?.join("archive"),
+    };
+    println!(
+        "\nDelete {} session{} and archive {} to {} ({} in total).",
+        deleted,
+        if deleted == 1 { "" } else { "s" },
+        archived,
+        archive_dir.display(),
+        format_size(size)
+    );
+    if ask(&mut input, "Apply? (y/N): ")? != Some('y') {
+        println!("Prune cancelled.");
+        return Ok(());
+    }
+
+    let trash = crate::trash::Trash::new()?;
+    let path = database::Database::database_path()?;
+    trash.snapshot_with("logs prune", &path, |dest| db.snapshot_to(dest))?;
+    // Archive everything first, so a failed archive leaves every session in place
+    for (session, action) in &decisions {
+        if *action == Action::Archive {
+            archive_session(db, session, &archive_dir)?;
+        }
+    }
+    for (session, _) in &decisions {
+        db.clear_session(&session.chat_id)?;
+    }
+    println!(
+        "{} Pruned {} session{} ({} archived)",
+        sym("✓").green(),
+        decisions.len(),
+        if decisions.len() == 1 { "" } else { "s" },
+        archived
+    );
+    println!("{}", "Run 'lc undo' to restore them.".dimmed());
+    Ok(())
+}
+
+fn show_session(db: &database::Database, chat_id: &str) -> Result<()> {
+    for logged in db.get_session_logs(chat_id)? {
+        let entry = &logged.entry;
+        println!(
+            "    {} {}",
+            crate::utils::time_display::current()
+                .format_as(&entry.timestamp, "%Y-%m-%d %H:%M")
+                .dimmed(),
+            entry.model.dimmed()
+        );
+        println!("    {} {}", "Q:".bold(), first_line(&entry.question, 100));
+        println!("    {} {}", "A:".bold(), first_line(&entry.response, 100));
+    }
+    Ok(())
+}
+
+fn first_line(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > max || text.lines().nth(1).is_some() {
+        format!("{}...", line.chars().take(max).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Export a session to `<dir>/<date>-<id>.json` before it is deleted. An existing
+/// archive is never overwritten.
+fn archive_session(db: &database::Database, session: &SessionInfo, dir: &Path) -> Result<PathBuf> {
+    let archive = db.export_session(&session.chat_id)?;
+
+    std::fs::create_dir_all(dir)?;
+    let path = archive_path(dir, session);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => anyhow::anyhow!(
+                "Archive '{}' already exists; move it away and prune again",
+                path.display()
+            ),
+            _ => anyhow::anyhow!("Failed to write '{}': {}", path.display(), e),
+        })?;
+    file.write_all(serde_json::to_string_pretty(&archive)?.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// `<dir>/<date>-<id>.json`, with the full session ID made safe for file names
+fn archive_path(dir: &Path, session: &SessionInfo) -> PathBuf {
+    let id: String = session
+        .chat_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}-{}.json", session.started.format("%Y%m%d"), id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, days_ago: i64, size_bytes: i64, now: DateTime<Utc>) -> SessionInfo {
+        SessionInfo {
+            chat_id: id.to_string(),
+            started: now - Duration::days(days_ago),
+            last_active: now - Duration::days(days_ago),
+            turns: 1,
+            first_question: "q".to_string(),
+            size_bytes,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_group_sessions() {
+        let now = Utc::now();
+        let groups = group_sessions(
+            vec![
+                session("today", 0, 10, now),
+                session("old-small", 200, 10, now),
+                session("month", 20, 10, now),
+                session("old-big", 100, 500, now),
+            ],
+            now,
+        );
+        let order: Vec<(AgeGroup, Vec<&str>)> = groups
+            .iter()
+            .map(|(g, m)| (*g, m.iter().map(|s| s.chat_id.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (AgeGroup::Older, vec!["old-big", "old-small"]),
+                (AgeGroup::Last30Days, vec!["month"]),
+                (AgeGroup::Today, vec!["today"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_path_uses_full_id() {
+        let now = Utc::now();
+        let dir = Path::new("/archive");
+        let a = archive_path(dir, &session("5f0c2a1e-aaaa-1111", 1, 10, now));
+        let b = archive_path(dir, &session("5f0c2a1e-bbbb-2222", 1, 10, now));
+        assert_ne!(a, b);
+        assert!(a.to_string_lossy().ends_with("-5f0c2a1e-aaaa-1111.json"));
+        let odd = archive_path(dir, &session("../x y", 1, 10, now));
+        assert_eq!(odd.parent(), Some(dir));
+        assert!(odd.to_string_lossy().ends_with("-___x_y.json"));
+    }
+}
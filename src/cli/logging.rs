@@ -1,35 +1,531 @@
 //! Logging and log management commands
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 use std::io::{self, Write};
+use std::path::Path;
 
-use crate::cli::{AnswerCommands, LogCommands, RecentCommands};
+use crate::cli::{AnswerCommands, LogCommands, RecentCommands, SessionExportFormat};
 use crate::database;
+use crate::trash;
+use crate::utils::code_blocks;
+use crate::utils::time_display;
 
 /// Handle log-related commands
-pub async fn handle(command: LogCommands) -> Result<()> {
+pub async fn handle(
+    command: LogCommands,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<()> {
     let db = database::Database::new()?;
+    let config = crate::config::Config::load()?;
+    time_display::set(time_display::TimeDisplay::from_settings(&config.time)?);
 
     match command {
-        LogCommands::Show { minimal } => show_logs(&db, minimal).await,
-        LogCommands::Recent { command, count } => handle_recent(&db, command, count).await,
+        LogCommands::Show(args) => {
+            let crate::cli::LogShowArgs {
+                minimal,
+                filters,
+                session,
+                since,
+                until,
+                grep,
+            } = *args;
+            let query = log_query(provider, model, since, until, grep)?;
+            show_logs(&db, minimal, &filters, session.as_deref(), query).await
+        }
+        LogCommands::Recent(args) => {
+            let crate::cli::LogRecentArgs {
+                command,
+                count,
+                json,
+                since,
+                until,
+                grep,
+            } = *args;
+            let query = database::LogQuery {
+                limit: Some(count),
+                ..log_query(provider, model, since, until, grep)?
+            };
+            if json && command.is_none() {
+                print_json(&recent_records(&db, &query)?)
+            } else {
+                handle_recent(&db, command, &query, json).await
+            }
+        }
+        LogCommands::Get { session, json } => get_session(&db, session, json),
         LogCommands::Current => show_current(&db).await,
+        LogCommands::Sessions { count } => show_sessions(&db, count),
         LogCommands::Stats => show_stats(&db).await,
-        LogCommands::Purge {
-            yes,
-            older_than_days,
-            keep_recent,
-            max_size_mb,
-        } => handle_purge(&db, yes, older_than_days, keep_recent, max_size_mb).await,
+        LogCommands::Purge(args) => {
+            let crate::cli::LogPurgeArgs {
+                yes,
+                older_than_days,
+                keep_recent,
+                max_size_mb,
+            } = *args;
+            handle_purge(&db, yes, older_than_days, keep_recent, max_size_mb).await
+        }
+        LogCommands::Share(args) => {
+            let crate::cli::LogShareArgs {
+                session,
+                html,
+                redact_system_prompt,
+            } = *args;
+            share_session(&db, session, &html, redact_system_prompt)
+        }
+        LogCommands::Export(args) => {
+            let crate::cli::LogExportArgs {
+                session,
+                format,
+                out,
+            } = *args;
+            export_session(&db, session, format, out.as_deref())
+        }
+        LogCommands::Import { files } => import_sessions(&db, &files),
+        LogCommands::ImportChatgpt { file } => crate::cli::log_import::handle(
+            &db,
+            crate::cli::log_import::ExportSource::ChatGpt,
+            &file,
+        ),
+        LogCommands::ImportClaude { file } => {
+            crate::cli::log_import::handle(&db, crate::cli::log_import::ExportSource::Claude, &file)
+        }
+        LogCommands::Prune(args) => {
+            let crate::cli::LogPruneArgs {
+                interactive,
+                older_than_days,
+                archive_dir,
+            } = *args;
+            crate::cli::log_prune::handle(&db, interactive, older_than_days, archive_dir)
+        }
+        LogCommands::Ask(args) => {
+            let crate::cli::LogAskArgs {
+                question,
+                embedding_model,
+                count,
+            } = *args;
+            let options = crate::cli::log_ask::AskOptions {
+                question: question.join(" "),
+                embedding_model,
+                count,
+                provider,
+                model,
+            };
+            crate::cli::log_ask::handle(&db, options).await
+        }
+        LogCommands::ExportFinetune(args) => {
+            let crate::cli::LogExportFinetuneArgs {
+                sessions,
+                filters,
+                out,
+                scrub_pii,
+                no_system,
+            } = *args;
+            let options = crate::cli::log_finetune::ExportOptions {
+                sessions,
+                filters,
+                output: out,
+                scrub_pii,
+                no_system,
+            };
+            crate::cli::log_finetune::handle(&db, options)
+        }
     }
 }
 
-async fn show_logs(db: &database::Database, minimal: bool) -> Result<()> {
-    let entries = db.get_all_logs()?;
+/// Filters of `lc logs show` and `lc logs recent`; `--provider` and `--model` are the global flags
+fn log_query(
+    provider: Option<String>,
+    model: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    grep: Option<String>,
+) -> Result<database::LogQuery> {
+    use crate::cli::usage::parse_date_bound;
+
+    let since = since
+        .as_deref()
+        .map(|s| parse_date_bound(s, false))
+        .transpose()?;
+    let until = until
+        .as_deref()
+        .map(|s| parse_date_bound(s, true))
+        .transpose()?;
+    if let (Some(s), Some(u)) = (since, until) {
+        if s > u {
+            anyhow::bail!("--since must be before --until");
+        }
+    }
+    Ok(database::LogQuery {
+        provider,
+        model,
+        since,
+        until,
+        grep,
+        ..Default::default()
+    })
+}
 
+fn share_session(
+    db: &database::Database,
+    session: Option<String>,
+    output: &str,
+    redact_system_prompt: bool,
+) -> Result<()> {
+    let chat_id = match session {
+        Some(prefix) => db.resolve_session_id(&prefix)?,
+        None => db
+            .get_current_session_id()?
+            .ok_or_else(|| anyhow::anyhow!("No current session. Pass a session ID"))?,
+    };
+    let entries = db.get_session_logs(&chat_id)?;
     if entries.is_empty() {
-        println!("No chat logs found.");
+        anyhow::bail!("Session '{}' has no log entries", chat_id);
+    }
+
+    let options = crate::utils::share_html::ShareOptions {
+        redact_system_prompt,
+    };
+    let page = crate::utils::share_html::render_session(&chat_id, &entries, &options);
+    std::fs::write(output, page)
+        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", output, e))?;
+
+    println!(
+        "{} Shared session {} ({} turn{}) to {}",
+        sym("✓").green(),
+        database::short_id(&chat_id),
+        entries.len(),
+        if entries.len() == 1 { "" } else { "s" },
+        output
+    );
+    Ok(())
+}
+
+fn show_sessions(db: &database::Database, count: usize) -> Result<()> {
+    use tabled::{Table, Tabled};
+
+    let sessions = db.list_sessions(count)?;
+    if sessions.is_empty() {
+        println!("No sessions found.");
+        return Ok(());
+    }
+
+    #[derive(Tabled)]
+    struct SessionRow {
+        #[tabled(rename = "Name")]
+        name: String,
+        #[tabled(rename = "Chat ID")]
+        chat_id: String,
+        #[tabled(rename = "Messages")]
+        messages: i64,
+        #[tabled(rename = "Last Active")]
+        last_active: String,
+        #[tabled(rename = "First Question")]
+        question: String,
+    }
+
+    let now = chrono::Utc::now();
+    let rows: Vec<SessionRow> = sessions
+        .into_iter()
+        .map(|session| {
+            let question: String = session
+                .first_question
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            SessionRow {
+                name: session.name.unwrap_or_else(|| "-".to_string()),
+                chat_id: database::short_id(&session.chat_id).to_string(),
+                messages: session.turns,
+                last_active: time_display::relative(&session.last_active, &now),
+                question: if question.chars().count() > 40 {
+                    format!("{}...", question.chars().take(37).collect::<String>())
+                } else {
+                    question
+                },
+            }
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+    Ok(())
+}
+
+fn export_session(
+    db: &database::Database,
+    session: Option<String>,
+    format: SessionExportFormat,
+    output: Option<&str>,
+) -> Result<()> {
+    let chat_id = match session {
+        Some(prefix) => db.resolve_session_id(&prefix)?,
+        None => db
+            .get_current_session_id()?
+            .ok_or_else(|| anyhow::anyhow!("No current session. Pass --session"))?,
+    };
+    let archive = db.export_session(&chat_id)?;
+    let content = match format {
+        SessionExportFormat::Json => serde_json::to_string_pretty(&archive)? + "\n",
+        SessionExportFormat::Markdown => session_markdown(&archive),
+        SessionExportFormat::Html => crate::utils::share_html::render_session(
+            &chat_id,
+            &db.get_session_logs(&chat_id)?,
+            &Default::default(),
+        ),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, content)
+                .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path, e))?;
+            println!(
+                "{} Exported session {} ({} turn{}) to {}",
+                sym("✓").green(),
+                database::short_id(&chat_id),
+                archive.turns.len(),
+                if archive.turns.len() == 1 { "" } else { "s" },
+                path
+            );
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// One logged exchange as `lc logs recent --json` and `lc logs get --json` print it
+#[derive(Debug, serde::Serialize)]
+struct ExchangeRecord {
+    session_id: String,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    provider: Option<String>,
+    model: String,
+    /// The system prompt, question and answer, in the order they were sent
+    messages: Vec<MessageRecord>,
+    reasoning: Option<String>,
+    input_tokens: Option<i32>,
+    output_tokens: Option<i32>,
+    total_tokens: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    metadata: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct MessageRecord {
+    role: &'static str,
+    content: String,
+}
+
+impl From<database::LoggedChatEntry> for ExchangeRecord {
+    fn from(logged: database::LoggedChatEntry) -> Self {
+        let entry = logged.entry;
+        let mut messages = Vec::new();
+        if let Some(system) = logged.system_prompt {
+            messages.push(MessageRecord {
+                role: "system",
+                content: system,
+            });
+        }
+        messages.push(MessageRecord {
+            role: "user",
+            content: entry.question,
+        });
+        messages.push(MessageRecord {
+            role: "assistant",
+            content: entry.response,
+        });
+        Self {
+            session_id: entry.chat_id,
+            timestamp: entry.timestamp,
+            provider: entry.provider,
+            model: entry.model,
+            messages,
+            reasoning: logged.reasoning,
+            input_tokens: entry.input_tokens,
+            output_tokens: entry.output_tokens,
+            total_tokens: entry
+                .input_tokens
+                .zip(entry.output_tokens)
+                .map(|(i, o)| i + o),
+            user: logged.user,
+            metadata: logged.metadata,
+        }
+    }
+}
+
+/// A session as `lc logs get --json` prints it
+#[derive(Debug, serde::Serialize)]
+struct SessionRecord {
+    session_id: String,
+    name: Option<String>,
+    started: chrono::DateTime<chrono::Utc>,
+    last_active: chrono::DateTime<chrono::Utc>,
+    exchanges: Vec<ExchangeRecord>,
+}
+
+fn recent_records(
+    db: &database::Database,
+    query: &database::LogQuery,
+) -> Result<Vec<ExchangeRecord>> {
+    Ok(db
+        .query_logs(query)?
+        .into_iter()
+        .map(ExchangeRecord::from)
+        .collect())
+}
+
+fn session_record(db: &database::Database, chat_id: &str) -> Result<SessionRecord> {
+    let exchanges: Vec<ExchangeRecord> = db
+        .get_session_logs(chat_id)?
+        .into_iter()
+        .map(ExchangeRecord::from)
+        .collect();
+    let (Some(first), Some(last)) = (exchanges.first(), exchanges.last()) else {
+        anyhow::bail!("Session '{}' has no log entries", chat_id);
+    };
+    Ok(SessionRecord {
+        session_id: chat_id.to_string(),
+        name: db.get_session_name(chat_id)?,
+        started: first.timestamp,
+        last_active: last.timestamp,
+        exchanges,
+    })
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn get_session(db: &database::Database, session: Option<String>, json: bool) -> Result<()> {
+    let chat_id = match session {
+        Some(prefix) => db.resolve_session_id(&prefix)?,
+        None => db
+            .get_current_session_id()?
+            .ok_or_else(|| anyhow::anyhow!("No current session. Pass a session ID"))?,
+    };
+    if json {
+        print_json(&session_record(db, &chat_id)?)
+    } else {
+        print!("{}", session_markdown(&db.export_session(&chat_id)?));
+        Ok(())
+    }
+}
+
+/// A session as a markdown transcript, with the model and token counts of each turn
+fn session_markdown(archive: &database::SessionArchive) -> String {
+    let mut text = format!(
+        "# Session {}\n\n_{} – {} UTC_\n",
+        archive.session_id,
+        archive.started.format("%Y-%m-%d %H:%M"),
+        archive.last_active.format("%Y-%m-%d %H:%M")
+    );
+    let mut previous_system: Option<&str> = None;
+    for (index, turn) in archive.turns.iter().enumerate() {
+        text.push_str(&format!("\n## Turn {}\n\n", index + 1));
+        let model = match &turn.provider {
+            Some(provider) => format!("{}:{}", provider, turn.model),
+            None => turn.model.clone(),
+        };
+        let mut details = vec![
+            format!("`{}`", model),
+            turn.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+        ];
+        if turn.input_tokens.is_some() || turn.output_tokens.is_some() {
+            let count = |tokens: Option<i32>| tokens.map_or("?".to_string(), |t| t.to_string());
+            details.push(format!(
+                "{} in / {} out tokens",
+                count(turn.input_tokens),
+                count(turn.output_tokens)
+            ));
+        }
+        text.push_str(&format!("_{}_\n\n", details.join(" · ")));
+        if let Some(system) = turn.system_prompt.as_deref() {
+            if previous_system != Some(system) {
+                text.push_str(&format!("**System:**\n\n{}\n\n", system.trim()));
+            }
+            previous_system = Some(system);
+        }
+        text.push_str(&format!("**User:**\n\n{}\n\n", turn.question.trim()));
+        text.push_str(&format!("**Assistant:**\n\n{}\n", turn.response.trim()));
+    }
+    if !archive.tool_calls.is_empty() {
+        text.push_str("\n## Tool calls\n\n");
+        for call in &archive.tool_calls {
+            text.push_str(&format!(
+                "- {} `{}.{}`{}\n",
+                call.timestamp.format("%H:%M:%S"),
+                call.server,
+                call.tool,
+                if call.cached { " (cached)" } else { "" }
+            ));
+        }
+    }
+    text
+}
+
+fn import_sessions(db: &database::Database, files: &[String]) -> Result<()> {
+    let mut imported = 0;
+    for file in files {
+        let content = std::fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", file, e))?;
+        let archive: database::SessionArchive = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("'{}' is not a session exported as JSON: {}", file, e))?;
+        match db.import_session(&archive) {
+            Ok(()) => {
+                println!(
+                    "{} Imported session {} ({} turn{}) from {}",
+                    sym("✓").green(),
+                    database::short_id(&archive.session_id),
+                    archive.turns.len(),
+                    if archive.turns.len() == 1 { "" } else { "s" },
+                    file
+                );
+                imported += 1;
+            }
+            Err(e) => eprintln!("{} {}: {}", sym("✗").red(), file, e),
+        }
+    }
+    if imported < files.len() {
+        anyhow::bail!(
+            "{} of {} files were not imported",
+            files.len() - imported,
+            files.len()
+        );
+    }
+    Ok(())
+}
+
+async fn show_logs(
+    db: &database::Database,
+    minimal: bool,
+    filters: &[String],
+    session: Option<&str>,
+    mut query: database::LogQuery,
+) -> Result<()> {
+    let filters = crate::core::request_metadata::parse_entries(filters)?;
+    let filtered = !filters.is_empty() || query.is_filtered();
+    query.chat_id = session
+        .map(|prefix| db.resolve_session_id(prefix))
+        .transpose()?;
+    let mut logged: Vec<database::LoggedChatEntry> = db
+        .query_logs(&query)?
+        .into_iter()
+        .filter(|logged| matches_filters(logged, &filters))
+        .collect();
+    // A single session reads in the order it happened
+    if query.chat_id.is_some() {
+        logged.reverse();
+    }
+
+    if logged.is_empty() {
+        if !filtered {
+            println!("No chat logs found.");
+        } else {
+            println!("No chat logs match the filter.");
+        }
         return Ok(());
     }
 
@@ -48,17 +544,18 @@ async fn show_logs(db: &database::Database, minimal: bool) -> Result<()> {
             time: String,
         }
 
-        let table_data: Vec<LogEntry> = entries
+        let table_data: Vec<LogEntry> = logged
             .into_iter()
+            .map(|logged| logged.entry)
             .map(|entry| LogEntry {
-                chat_id: entry.chat_id[..8].to_string(),
+                chat_id: database::short_id(&entry.chat_id).to_string(),
                 model: entry.model,
                 question: if entry.question.len() > 50 {
                     format!("{}...", &entry.question[..50])
                 } else {
                     entry.question
                 },
-                time: entry.timestamp.format("%m-%d %H:%M").to_string(),
+                time: time_display::current().format_as(&entry.timestamp, "%m-%d %H:%M"),
             })
             .collect();
 
@@ -67,14 +564,26 @@ async fn show_logs(db: &database::Database, minimal: bool) -> Result<()> {
     } else {
         println!("\n{}", "Chat Logs:".bold().blue());
 
-        for entry in entries {
+        for logged in logged {
+            let entry = logged.entry;
             println!(
                 "\n{} {} ({})",
                 "Session:".bold(),
-                &entry.chat_id[..8],
-                entry.timestamp.format("%Y-%m-%d %H:%M:%S")
+                database::short_id(&entry.chat_id),
+                time_display::current().format(&entry.timestamp)
             );
             println!("{} {}", "Model:".bold(), entry.model);
+            if let Some(user) = &logged.user {
+                println!("{} {}", "User:".bold(), user);
+            }
+            if !logged.metadata.is_empty() {
+                let metadata: Vec<String> = logged
+                    .metadata
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                println!("{} {}", "Metadata:".bold(), metadata.join(", "));
+            }
 
             // Show token usage if available
             if let (Some(input_tokens), Some(output_tokens)) =
@@ -99,72 +608,98 @@ async fn show_logs(db: &database::Database, minimal: bool) -> Result<()> {
                     entry.response
                 }
             );
-            println!("{}", "─".repeat(80).dimmed());
+            println!("{}", sym("─").repeat(80).dimmed());
         }
     }
 
     Ok(())
 }
 
+/// Whether an entry has every key=value in `filters` (`user` also matches the user field)
+pub(crate) fn matches_filters(
+    logged: &database::LoggedChatEntry,
+    filters: &std::collections::BTreeMap<String, String>,
+) -> bool {
+    filters.iter().all(|(key, value)| {
+        logged.metadata.get(key) == Some(value)
+            || (key == "user" && logged.user.as_ref() == Some(value))
+    })
+}
+
 async fn handle_recent(
     db: &database::Database,
     command: Option<RecentCommands>,
-    count: usize,
+    query: &database::LogQuery,
+    json: bool,
 ) -> Result<()> {
+    // The newest entry that matches the filters
+    let latest = || -> Result<database::ChatEntry> {
+        db.query_logs(&database::LogQuery {
+            limit: Some(1),
+            ..query.clone()
+        })?
+        .into_iter()
+        .next()
+        .map(|logged| logged.entry)
+        .ok_or_else(|| anyhow::anyhow!("No recent logs found"))
+    };
+    // With --json a single value is printed as a JSON string
+    let print = |value: &str| -> Result<()> {
+        if json {
+            println!("{}", serde_json::to_string(value)?);
+        } else {
+            println!("{}", value);
+        }
+        Ok(())
+    };
     match command {
         Some(RecentCommands::Answer { command }) => {
-            let entries = db.get_all_logs()?;
-            if let Some(entry) = entries.first() {
-                match command {
-                    Some(AnswerCommands::Code) => {
-                        let code_blocks = extract_code_blocks(&entry.response);
-                        if code_blocks.is_empty() {
-                            anyhow::bail!("No code blocks found in the last answer");
-                        } else {
+            let entry = latest()?;
+            match command {
+                Some(AnswerCommands::Code { write_to, force }) => {
+                    let code_blocks = code_blocks::extract(&entry.response);
+                    if code_blocks.is_empty() {
+                        anyhow::bail!("No code blocks found in the last answer");
+                    }
+                    match write_to {
+                        Some(dir) => write_code_blocks(&code_blocks, Path::new(&dir), force)?,
+                        None if json => print_json(
+                            &code_blocks
+                                .iter()
+                                .map(|block| &block.content)
+                                .collect::<Vec<_>>(),
+                        )?,
+                        None => {
                             for block in code_blocks {
-                                println!("{}", block);
+                                println!("{}", block.content);
                             }
                         }
                     }
-                    None => {
-                        println!("{}", entry.response);
-                    }
                 }
-            } else {
-                anyhow::bail!("No recent logs found");
-            }
-        }
-        Some(RecentCommands::Question) => {
-            let entries = db.get_all_logs()?;
-            if let Some(entry) = entries.first() {
-                println!("{}", entry.question);
-            } else {
-                anyhow::bail!("No recent logs found");
-            }
-        }
-        Some(RecentCommands::Model) => {
-            let entries = db.get_all_logs()?;
-            if let Some(entry) = entries.first() {
-                println!("{}", entry.model);
-            } else {
-                anyhow::bail!("No recent logs found");
-            }
-        }
-        Some(RecentCommands::Session) => {
-            let entries = db.get_all_logs()?;
-            if let Some(entry) = entries.first() {
-                println!("{}", entry.chat_id);
-            } else {
-                anyhow::bail!("No recent logs found");
+                None => print(&entry.response)?,
             }
         }
+        Some(RecentCommands::Question) => print(&latest()?.question)?,
+        Some(RecentCommands::Model) => print(&latest()?.model)?,
+        Some(RecentCommands::Reasoning) => match db.get_last_reasoning()? {
+            Some(reasoning) => print(&reasoning)?,
+            None => anyhow::bail!("The last answer has no reasoning trace"),
+        },
+        Some(RecentCommands::Session) => print(&latest()?.chat_id)?,
         None => {
             // Default behavior - show recent logs
-            let mut entries = db.get_all_logs()?;
-            entries.truncate(count);
+            let entries: Vec<database::ChatEntry> = db
+                .query_logs(query)?
+                .into_iter()
+                .map(|logged| logged.entry)
+                .collect();
 
             if entries.is_empty() {
-                println!("No recent logs found.");
+                if query.is_filtered() {
+                    println!("No recent logs match the filters.");
+                } else {
+                    println!("No recent logs found.");
+                }
                 return Ok(());
             }
 
@@ -174,12 +709,14 @@ async fn handle_recent(
                 entries.len()
             );
 
+            let display = time_display::current();
+            let now = chrono::Utc::now();
             for entry in entries {
                 println!(
                     "\n{} {} ({})",
                     "Session:".bold(),
-                    &entry.chat_id[..8],
-                    entry.timestamp.format("%Y-%m-%d %H:%M:%S")
+                    database::short_id(&entry.chat_id),
+                    display.format_with_age(&entry.timestamp, &now)
                 );
                 println!("{} {}", "Model:".bold(), entry.model);
 
@@ -206,7 +743,7 @@ async fn handle_recent(
                         entry.response
                     }
                 );
-                println!("{}", "─".repeat(60).dimmed());
+                println!("{}", sym("─").repeat(60).dimmed());
             }
         }
     }
@@ -226,7 +763,7 @@ async fn show_current(db: &database::Database) -> Result<()> {
                 "\n{} {} ({})",
                 format!("Message {}:", i + 1).bold(),
                 entry.model,
-                entry.timestamp.format("%H:%M:%S")
+                time_display::current().format_as(&entry.timestamp, "%H:%M:%S")
             );
             println!("{} {}", "Q:".yellow(), entry.question);
             println!(
@@ -271,8 +808,8 @@ async fn show_stats(db: &database::Database) -> Result<()> {
         println!(
             "{} {} to {}",
             "Date Range:".bold(),
-            earliest.format("%Y-%m-%d %H:%M:%S"),
-            latest.format("%Y-%m-%d %H:%M:%S")
+            time_display::current().format(&earliest),
+            time_display::current().format(&latest)
         );
     } else {
         println!("{} {}", "Date Range:".bold(), "No entries".dimmed());
@@ -289,7 +826,7 @@ async fn show_stats(db: &database::Database) -> Result<()> {
             };
             println!(
                 "  {} {} ({} - {:.1}%)",
-                "•".blue(),
+                sym("•").blue(),
                 model.bold(),
                 count,
                 percentage
@@ -313,10 +850,13 @@ async fn handle_purge(
 
     if has_specific_options {
         // Smart purge with specific options
+        let trash = trash::Trash::new()?;
+        let snapshot = snapshot_logs(&trash, db, "logs purge")?;
         let deleted_count = db.smart_purge(older_than_days, keep_recent, max_size_mb)?;
 
         if deleted_count > 0 {
-            println!("{} Purged {} log entries", "✓".green(), deleted_count);
+            println!("{} Purged {} log entries", sym("✓").green(), deleted_count);
+            println!("{}", "Run 'lc undo' to restore them.".dimmed());
 
             if let Some(days) = older_than_days {
                 println!("  - Removed entries older than {} days", days);
@@ -328,12 +868,13 @@ async fn handle_purge(
                 println!("  - Enforced maximum database size of {} MB", size);
             }
         } else {
-            println!("{} No logs needed to be purged", "ℹ️".blue());
+            trash.discard(&snapshot)?;
+            println!("{} No logs needed to be purged", sym("ℹ️").blue());
         }
     } else {
         // Full purge (existing behavior)
         if !yes {
-            print!("Are you sure you want to purge all logs? This cannot be undone. (y/N): ");
+            print!("Are you sure you want to purge all logs? (y/N): ");
             // Deliberately flush stdout to ensure prompt appears before user input
             io::stdout().flush()?;
 
@@ -346,42 +887,47 @@ async fn handle_purge(
             }
         }
 
+        snapshot_logs(&trash::Trash::new()?, db, "logs purge")?;
         db.purge_all_logs()?;
-        println!("{} All logs purged successfully", "✓".green());
+        println!("{} All logs purged successfully", sym("✓").green());
+        println!("{}", "Run 'lc undo' to restore them.".dimmed());
     }
 
     Ok(())
 }
 
-// Helper function to extract code blocks from markdown text
-fn extract_code_blocks(text: &str) -> Vec<String> {
-    let mut code_blocks = Vec::new();
-    let mut in_code_block = false;
-    let mut current_block = String::new();
-
-    for line in text.lines() {
-        if line.starts_with("```") {
-            if in_code_block {
-                // End of code block
-                if !current_block.trim().is_empty() {
-                    code_blocks.push(current_block.trim().to_string());
-                }
-                current_block.clear();
-                in_code_block = false;
-            } else {
-                // Start of code block
-                in_code_block = true;
-            }
-        } else if in_code_block {
-            current_block.push_str(line);
-            current_block.push('\n');
-        }
-    }
+/// Snapshot logs.db into the trash before purging so 'lc undo' can restore it
+fn snapshot_logs(trash: &trash::Trash, db: &database::Database, operation: &str) -> Result<String> {
+    let path = database::Database::database_path()?;
+    trash.snapshot_with(operation, &path, |dest| db.snapshot_to(dest))
+}
 
-    // Handle case where code block doesn't end properly
-    if in_code_block && !current_block.trim().is_empty() {
-        code_blocks.push(current_block.trim().to_string());
+/// Write each code block to its own file under `dir`
+fn write_code_blocks(blocks: &[code_blocks::CodeBlock], dir: &Path, force: bool) -> Result<()> {
+    let mut written = 0;
+    for (index, block) in blocks.iter().enumerate() {
+        let name = code_blocks::file_name_for(block, index);
+        let path = code_blocks::target_path(dir, &name)?;
+        if path.exists() && !force {
+            eprintln!(
+                "{} Skipping {} (already exists, use --force to overwrite)",
+                sym("⚠️").yellow(),
+                path.display()
+            );
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, format!("{}\n", block.content))?;
+        println!("{} Wrote {}", sym("✓").green(), path.display());
+        written += 1;
     }
-
-    code_blocks
+    println!(
+        "{} of {} code blocks written to {}",
+        written,
+        blocks.len(),
+        dir.display()
+    );
+    Ok(())
 }
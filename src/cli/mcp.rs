@@ -1,6 +1,7 @@
 //! MCP (Model Context Protocol) commands
 
 use crate::cli::{McpCommands, McpServerType as CliMcpServerType};
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 use std::collections::HashMap;
@@ -44,7 +45,11 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             )?;
             config.save().await?;
 
-            println!("{} MCP server '{}' added successfully", "✓".green(), name);
+            println!(
+                "{} MCP server '{}' added successfully",
+                sym("✓").green(),
+                name
+            );
             println!("  Type: {:?}", server_type);
             println!("  Command/URL: {}", final_command_or_url);
             if !env_map.is_empty() {
@@ -54,6 +59,23 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                 }
             }
         }
+        McpCommands::ClearCache { server } => {
+            let db = crate::database::Database::new()?;
+            let removed = db.clear_tool_cache(server.as_deref())?;
+            match server {
+                Some(server) => println!(
+                    "{} Cleared {} cached tool result(s) from server '{}'",
+                    sym("✓").green(),
+                    removed,
+                    server
+                ),
+                None => println!(
+                    "{} Cleared {} cached tool result(s)",
+                    sym("✓").green(),
+                    removed
+                ),
+            }
+        }
         McpCommands::Delete { name } => {
             let mut config = McpConfig::load().await?;
 
@@ -64,7 +86,11 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             config.delete_server(&name)?;
             config.save().await?;
 
-            println!("{} MCP server '{}' deleted successfully", "✓".green(), name);
+            println!(
+                "{} MCP server '{}' deleted successfully",
+                sym("✓").green(),
+                name
+            );
         }
         McpCommands::Functions { name } => {
             let config = McpConfig::load().await?;
@@ -72,7 +98,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             if config.get_server(&name).is_some() {
                 println!(
                     "{} Listing functions for MCP server '{}'...",
-                    "🔍".blue(),
+                    sym("🔍").blue(),
                     name
                 );
 
@@ -103,7 +129,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                                                 });
                                             println!(
                                                 "  {} {} - {}",
-                                                "•".blue(),
+                                                sym("•").blue(),
                                                 tool.name.bold(),
                                                 description
                                             );
@@ -149,7 +175,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             if config.get_server(&name).is_some() {
                 println!(
                     "{} Invoking function '{}' on MCP server '{}'...",
-                    "⚡".yellow(),
+                    sym("⚡").yellow(),
                     function.bold(),
                     name.bold()
                 );
@@ -229,7 +255,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                         // Invoke the function
                         match daemon_client.call_tool(&name, &function, args_json).await {
                             Ok(result) => {
-                                println!("{} Function invoked successfully\n", "✓".green());
+                                println!("{} Function invoked successfully\n", sym("✓").green());
 
                                 // Pretty print the result
                                 if let Ok(pretty) = serde_json::to_string_pretty(&result) {
@@ -269,7 +295,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                 // New server or override existing
                 println!(
                     "{} Configuring and starting MCP server '{}'...",
-                    "🚀".cyan(),
+                    sym("🚀").cyan(),
                     name.bold()
                 );
 
@@ -299,7 +325,11 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             } else {
                 // Use existing configuration
                 if let Some(server_config) = config.get_server(&name) {
-                    println!("{} Starting MCP server '{}'...", "🚀".cyan(), name.bold());
+                    println!(
+                        "{} Starting MCP server '{}'...",
+                        sym("🚀").cyan(),
+                        name.bold()
+                    );
                     (
                         server_config.command_or_url.clone(),
                         server_config.server_type.clone(),
@@ -317,7 +347,11 @@ pub async fn handle(command: McpCommands) -> Result<()> {
 
             match daemon_client.ensure_server_connected(&name).await {
                 Ok(_) => {
-                    println!("{} MCP server '{}' started successfully", "✓".green(), name);
+                    println!(
+                        "{} MCP server '{}' started successfully",
+                        sym("✓").green(),
+                        name
+                    );
                     println!("  Command: {}", full_command.dimmed());
                     println!("  Type: {:?}", server_type);
                 }
@@ -327,12 +361,20 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             }
         }
         McpCommands::Stop { name } => {
-            println!("{} Stopping MCP server '{}'...", "🛑".red(), name.bold());
+            println!(
+                "{} Stopping MCP server '{}'...",
+                sym("🛑").red(),
+                name.bold()
+            );
 
             let daemon_client = crate::services::mcp_daemon::DaemonClient::new()?;
             match daemon_client.close_server(&name).await {
                 Ok(_) => {
-                    println!("{} MCP server '{}' stopped successfully", "✓".green(), name);
+                    println!(
+                        "{} MCP server '{}' stopped successfully",
+                        sym("✓").green(),
+                        name
+                    );
 
                     // Also remove from config
                     use crate::services::mcp::McpConfig;
@@ -344,7 +386,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                 Err(e) => {
                     println!(
                         "{} Failed to stop MCP server '{}': {}",
-                        "⚠️".yellow(),
+                        sym("⚠️").yellow(),
                         name,
                         e
                     );
@@ -352,7 +394,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             }
         }
         McpCommands::List => {
-            println!("{} MCP servers:", "📋".blue());
+            println!("{} MCP servers:", sym("📋").blue());
 
             // Load MCP config to show configured servers
             use crate::services::mcp::McpConfig;
@@ -363,7 +405,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                 println!("  No MCP servers configured.");
                 println!(
                     "\n{} Add one with: {}",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc mcp start <name> <command>".bold()
                 );
             } else {
@@ -383,14 +425,14 @@ pub async fn handle(command: McpCommands) -> Result<()> {
 
                 for (name, server_config) in servers {
                     let status = if active_servers.contains(&name) {
-                        format!("{} (connected)", "✓".green())
+                        format!("{} (connected)", sym("✓").green())
                     } else {
                         "".to_string()
                     };
 
                     println!(
                         "  {} {} - {:?} ({}) {}",
-                        "•".blue(),
+                        sym("•").blue(),
                         name.bold(),
                         server_config.server_type,
                         server_config.command_or_url.dimmed(),
@@ -403,7 +445,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
             if let Some(server_name) = name {
                 println!(
                     "{} Checking status of MCP server '{}'...",
-                    "🔍".blue(),
+                    sym("🔍").blue(),
                     server_name.bold()
                 );
 
@@ -422,7 +464,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                     // Try to list tools as a connection test
                     match daemon_client.list_tools(&server_name).await {
                         Ok(tools_map) => {
-                            println!("    Status: {} Connected", "✓".green());
+                            println!("    Status: {} Connected", sym("✓").green());
                             if let Some(tools) = tools_map.get(&server_name) {
                                 println!("    Available tools: {}", tools.len());
                                 if !tools.is_empty() {
@@ -437,19 +479,19 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                             }
                         }
                         Err(_) => {
-                            println!("    Status: {} Not connected", "✗".red());
+                            println!("    Status: {} Not connected", sym("✗").red());
                             println!("    Use 'lc mcp start {}' to connect", server_name);
                         }
                     }
                 } else {
-                    println!("{} MCP server '{}' not found", "✗".red(), server_name);
+                    println!("{} MCP server '{}' not found", sym("✗").red(), server_name);
                     println!("\nAvailable servers:");
                     let servers = config.list_servers();
                     if servers.is_empty() {
                         println!("  No servers configured.");
                         println!(
                             "\n{} Add one with: {}",
-                            "💡".yellow(),
+                            sym("💡").yellow(),
                             "lc mcp start <name> <command>".bold()
                         );
                     } else {
@@ -460,7 +502,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                 }
             } else {
                 // Show status of all servers
-                println!("{} MCP server status:", "📊".blue());
+                println!("{} MCP server status:", sym("📊").blue());
 
                 use crate::services::mcp::McpConfig;
                 let config = McpConfig::load().await?;
@@ -470,7 +512,7 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                     println!("  No MCP servers configured.");
                     println!(
                         "\n{} Add one with: {}",
-                        "💡".yellow(),
+                        sym("💡").yellow(),
                         "lc mcp start <name> <command>".bold()
                     );
                 } else {
@@ -479,17 +521,17 @@ pub async fn handle(command: McpCommands) -> Result<()> {
                     for (name, server_config) in servers {
                         let status = if let Ok(client) = &daemon_client {
                             if client.list_tools(&name).await.is_ok() {
-                                format!("{} Connected", "✓".green())
+                                format!("{} Connected", sym("✓").green())
                             } else {
-                                format!("{} Not connected", "✗".red())
+                                format!("{} Not connected", sym("✗").red())
                             }
                         } else {
-                            format!("{} Daemon unavailable", "⚠️".yellow())
+                            format!("{} Daemon unavailable", sym("⚠️").yellow())
                         };
 
                         println!(
                             "  {} {} ({:?}) - {}",
-                            "•".blue(),
+                            sym("•").blue(),
                             name.bold(),
                             server_config.server_type,
                             status
@@ -8,32 +8,104 @@ pub mod definitions;
 // Submodules - to be implemented separately
 pub mod aliases;
 pub mod audio;
+pub mod backup;
 pub mod chat;
 pub mod completion;
 pub mod config;
+pub mod doctor;
+pub mod duo;
 pub mod embed;
+pub mod extract;
 pub mod image;
 pub mod keys;
+pub mod log_ask;
+pub mod log_finetune;
+pub mod log_import;
+pub mod log_prune;
 pub mod logging;
 pub mod mcp;
 pub mod models;
+pub mod plugins;
 pub mod prompts;
+pub mod provider_lint;
 pub mod providers;
 pub mod proxy;
+pub mod quickfix;
 pub mod search;
+pub mod styles;
 pub mod sync;
 pub mod templates;
+pub mod undo;
+pub mod upgrade;
 pub mod usage;
 pub mod utils;
 pub mod vectors;
+pub mod watch;
 pub mod webchatproxy;
+pub mod webui;
 
 // Re-export all CLI types for easy access
 pub use definitions::*;
 
-// Set debug mode - updates the global debug flag used by debug_log! macro
+// Set debug mode - turns on debug_log! output for all modules (unless a filter is set)
 pub fn set_debug_mode(enabled: bool) {
-    crate::DEBUG_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+    crate::utils::logging::set_enabled(enabled);
+}
+
+// Set the debug filter - levels and modules logged, from `--debug=<filter>`
+pub fn set_debug_filter(filter: Option<&str>) -> anyhow::Result<()> {
+    crate::utils::logging::configure(filter)
+}
+
+// Set force mode - skips capability pre-checks before sending requests
+pub fn set_force_mode(enabled: bool) {
+    crate::FORCE_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set timings mode - prints a latency breakdown after each request
+pub fn set_timings_mode(enabled: bool) {
+    crate::TIMINGS_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set ReAct tools mode - describes tools in the prompt instead of native function calling
+pub fn set_react_tools_mode(enabled: bool) {
+    crate::REACT_TOOLS_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set show-reasoning mode - prints reasoning traces from thinking models
+pub fn set_show_reasoning_mode(enabled: bool) {
+    crate::SHOW_REASONING_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set read-only mode - refuses any change to config files and databases
+pub fn set_read_only_mode(enabled: bool) {
+    crate::READ_ONLY_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set auto-provider mode - picks the provider of a bare model name from the models cache
+pub fn set_auto_provider_mode(enabled: bool) {
+    crate::AUTO_PROVIDER_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set override-budget mode - skips the limits.per_request_usd and limits.daily_usd checks
+pub fn set_override_budget_mode(enabled: bool) {
+    crate::OVERRIDE_BUDGET_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set auto-fetch-urls mode - appends the content of URLs found in prompts
+pub fn set_auto_fetch_urls_mode(enabled: bool) {
+    crate::AUTO_FETCH_URLS_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set fresh mode - fetches web pages and search results again instead of using the cache
+pub fn set_fresh_mode(enabled: bool) {
+    crate::FRESH_MODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Set ASCII mode - forced by the flag, or detected for consoles without Unicode support
+pub fn set_ascii_mode(enabled: bool) {
+    let ascii = enabled || !crate::utils::terminal::supports_unicode();
+    crate::ASCII_MODE.store(ascii, std::sync::atomic::Ordering::Relaxed);
 }
 
 // Helper function for parsing environment variables
@@ -1,16 +1,20 @@
 //! Model management commands
 
 use crate::cli::{ModelsCommands, ModelsPathCommands, ModelsTagsCommands};
+use crate::model_metadata::{Capability, ModelMetadata};
+use crate::utils::terminal::sym;
 use crate::{chat, config, debug_log};
 use anyhow::Result;
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 
 #[allow(clippy::too_many_arguments)]
 /// Handle model-related commands
 pub async fn handle(
     command: Option<ModelsCommands>,
+    provider: Option<String>,
     query: Option<String>,
     tags: Option<String>,
+    strict_filters: bool,
     context_length: Option<u64>,
     input_length: Option<u64>,
     output_length: Option<u64>,
@@ -24,8 +28,10 @@ pub async fn handle(
 
     handle_models_command(
         command,
+        provider,
         query,
         tags,
+        strict_filters,
         context_length_str,
         input_length_str,
         output_length_str,
@@ -39,8 +45,10 @@ pub async fn handle(
 #[allow(clippy::too_many_arguments)]
 async fn handle_models_command(
     command: Option<ModelsCommands>,
+    provider: Option<String>,
     query: Option<String>,
     tags: Option<String>,
+    strict_filters: bool,
     context_length: Option<String>,
     input_length: Option<String>,
     output_length: Option<String>,
@@ -48,8 +56,24 @@ async fn handle_models_command(
     output_price: Option<f64>,
 ) -> Result<()> {
     match command {
-        Some(ModelsCommands::Refresh) => {
-            crate::unified_cache::UnifiedCache::refresh_all_providers().await?;
+        Some(ModelsCommands::Refresh { jobs }) => {
+            let providers: Vec<String> = provider
+                .iter()
+                .flat_map(|p| p.split(','))
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            if providers.is_empty() {
+                println!("Refreshing models cache for all providers...");
+            } else {
+                println!(
+                    "Refreshing models cache for {}...",
+                    providers.join(", ").bold()
+                );
+            }
+            let results =
+                crate::unified_cache::UnifiedCache::refresh_providers(&providers, jobs).await?;
+            crate::unified_cache::print_refresh_summary(&results);
         }
         Some(ModelsCommands::Info) => {
             debug_log!("Handling models info command");
@@ -151,13 +175,18 @@ async fn handle_models_command(
                 if count > 0 {
                     println!(
                         "  {} {} - {} models ({})",
-                        "•".blue(),
+                        sym("•").blue(),
                         provider_name.bold(),
                         count,
                         status
                     );
                 } else {
-                    println!("  {} {} - {}", "•".blue(), provider_name.bold(), status);
+                    println!(
+                        "  {} {} - {}",
+                        sym("•").blue(),
+                        provider_name.bold(),
+                        status
+                    );
                 }
             }
 
@@ -171,6 +200,9 @@ async fn handle_models_command(
             println!("  Providers: {}", provider_count);
             println!("  Total Models: {}", total_models);
         }
+        Some(ModelsCommands::Compact) => {
+            handle_compact().await?;
+        }
         Some(ModelsCommands::Dump) => {
             dump_models_data().await?;
         }
@@ -245,8 +277,26 @@ async fn handle_models_command(
                     // For simplicity, we'll add a single path rule
                     crate::model_metadata::add_tag(tag, vec![rule], "string".to_string(), None)?;
                 }
+                ModelsTagsCommands::Export { output } => {
+                    handle_tags_export(output)?;
+                }
+                ModelsTagsCommands::Import { file, replace } => {
+                    handle_tags_import(&file, replace)?;
+                }
             }
         }
+        Some(ModelsCommands::Find { query, available }) => {
+            handle_find(&query, available).await?;
+        }
+        Some(ModelsCommands::Deprecated) => {
+            let config = crate::config::Config::load()?;
+            let notices = crate::models::deprecation::scan_config(&config).await;
+            crate::models::deprecation::display_scan(&notices);
+        }
+        Some(ModelsCommands::Aliases) => {
+            let tables = crate::models::model_aliases::load();
+            crate::models::model_aliases::display(&tables);
+        }
         Some(ModelsCommands::Filter { tags: filter_tags }) => {
             // Load all models
             let models = crate::unified_cache::UnifiedCache::load_all_cached_models().await?;
@@ -259,33 +309,13 @@ async fn handle_models_command(
                 .into_iter()
                 .filter(|model| {
                     for tag in &required_tags {
-                        match *tag {
-                            "tools" => {
-                                if !model.supports_tools && !model.supports_function_calling {
-                                    return false;
-                                }
-                            }
-                            "vision" => {
-                                if !model.supports_vision {
+                        match model.capability(tag) {
+                            Some(state) => {
+                                if !passes_capability_filter(state, strict_filters) {
                                     return false;
                                 }
                             }
-                            "audio" => {
-                                if !model.supports_audio {
-                                    return false;
-                                }
-                            }
-                            "reasoning" => {
-                                if !model.supports_reasoning {
-                                    return false;
-                                }
-                            }
-                            "code" => {
-                                if !model.supports_code {
-                                    return false;
-                                }
-                            }
-                            _ => {
+                            None => {
                                 // Check for context length filters like "ctx>100k"
                                 if tag.starts_with("ctx") {
                                     if let Some(ctx) = model.context_length {
@@ -317,6 +347,9 @@ async fn handle_models_command(
                     filtered.len()
                 );
 
+                let unknown_shown = filtered
+                    .iter()
+                    .any(|model| has_unknown_capability(model, &required_tags));
                 let mut current_provider = String::new();
                 for model in filtered {
                     if model.provider != current_provider {
@@ -324,25 +357,10 @@ async fn handle_models_command(
                         println!("\n{}", format!("{}:", current_provider).bold().green());
                     }
 
-                    print!("  {} {}", "•".blue(), model.id.bold());
+                    print!("  {} {}", sym("•").blue(), model.id.bold());
 
                     // Show capabilities
-                    let mut capabilities = Vec::new();
-                    if model.supports_tools || model.supports_function_calling {
-                        capabilities.push("🔧 tools".blue());
-                    }
-                    if model.supports_vision {
-                        capabilities.push("👁 vision".magenta());
-                    }
-                    if model.supports_audio {
-                        capabilities.push("🔊 audio".yellow());
-                    }
-                    if model.supports_reasoning {
-                        capabilities.push("🧠 reasoning".cyan());
-                    }
-                    if model.supports_code {
-                        capabilities.push("💻 code".green());
-                    }
+                    let capabilities = capability_badges(&model, &required_tags);
 
                     if !capabilities.is_empty() {
                         let capability_strings: Vec<String> =
@@ -361,6 +379,9 @@ async fn handle_models_command(
 
                     println!();
                 }
+                if unknown_shown {
+                    print_unknown_capability_note();
+                }
             }
         }
         None => {
@@ -402,10 +423,12 @@ async fn handle_models_command(
             };
 
             // Apply filters
+            let capability_filters = tag_filters.clone().unwrap_or_default();
             let filtered_models = apply_model_filters_with_tags(
                 enhanced_models,
                 &query,
                 tag_filters,
+                strict_filters,
                 &context_length,
                 &input_length,
                 &output_length,
@@ -423,16 +446,102 @@ async fn handle_models_command(
 
             // Display results
             debug_log!("Displaying {} filtered models", filtered_models.len());
-            display_enhanced_models(&filtered_models, &query)?;
+            display_enhanced_models(&filtered_models, &query, &capability_filters)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_tags_export(output: Option<String>) -> Result<()> {
+    let rules = crate::model_metadata::MetadataRules::load()?;
+    let content = toml::to_string_pretty(&rules)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            println!(
+                "{} Exported {} tags and {} model paths to {}",
+                sym("✓").green(),
+                rules.tags.len(),
+                rules.model_paths.paths.len(),
+                path
+            );
         }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+fn handle_tags_import(file: &str, replace: bool) -> Result<()> {
+    crate::lock::ensure_writable("import tag rules")?;
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", file, e))?;
+    let imported: crate::model_metadata::MetadataRules = toml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid tag rules file '{}': {}", file, e))?;
+
+    let (tags, paths) = (imported.tags.len(), imported.model_paths.paths.len());
+    let rules = if replace {
+        imported
+    } else {
+        crate::model_metadata::MetadataRules::load()?.merge(imported)
+    };
+    rules.save()?;
+
+    println!(
+        "{} {} {} tags and {} model paths",
+        sym("✓").green(),
+        if replace {
+            "Replaced rules with"
+        } else {
+            "Imported"
+        },
+        tags,
+        paths
+    );
+    println!("Run 'lc models refresh' to apply them to cached models");
+    Ok(())
+}
+
+async fn handle_compact() -> Result<()> {
+    let results = crate::unified_cache::UnifiedCache::compact_caches().await?;
+    if results.is_empty() {
+        println!("No cached models to compact");
+        return Ok(());
     }
 
+    let format_size = |bytes: u64| {
+        if bytes >= 1024 * 1024 {
+            format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+        } else {
+            format!("{:.1} KB", bytes as f64 / 1024.0)
+        }
+    };
+    let (mut before_total, mut after_total) = (0, 0);
+    for (provider, before, after) in &results {
+        before_total += before;
+        after_total += after;
+        println!(
+            "  {} {} {} {}",
+            provider.bold(),
+            format_size(*before),
+            sym("→"),
+            format_size(*after)
+        );
+    }
+    println!(
+        "{} Compacted {} cache files: {} {} {}",
+        sym("✓").green(),
+        results.len(),
+        format_size(before_total),
+        sym("→"),
+        format_size(after_total)
+    );
     Ok(())
 }
 
 // Dump models data function
 async fn dump_models_data() -> Result<()> {
-    println!("{} Dumping /models for each provider...", "🔍".blue());
+    println!("{} Dumping /models for each provider...", sym("🔍").blue());
 
     // Load configuration
     let config = config::Config::load()?;
@@ -453,7 +562,7 @@ async fn dump_models_data() -> Result<()> {
         {
             println!(
                 "{} Skipping {} (invalid name)",
-                "⚠️".yellow(),
+                sym("⚠️").yellow(),
                 provider_name
             );
             continue;
@@ -461,11 +570,19 @@ async fn dump_models_data() -> Result<()> {
 
         // Skip providers without API keys
         if provider_config.api_key.is_none() {
-            println!("{} Skipping {} (no API key)", "⚠️".yellow(), provider_name);
+            println!(
+                "{} Skipping {} (no API key)",
+                sym("⚠️").yellow(),
+                provider_name
+            );
             continue;
         }
 
-        println!("{} Fetching models from {}...", "📡".blue(), provider_name);
+        println!(
+            "{} Fetching models from {}...",
+            sym("📡").blue(),
+            provider_name
+        );
 
         // Create authenticated client
         let mut config_mut = config.clone();
@@ -480,7 +597,7 @@ async fn dump_models_data() -> Result<()> {
                             Ok(_) => {
                                 println!(
                                     "{} Saved {} models data to {}",
-                                    "✅".green(),
+                                    sym("✅").green(),
                                     provider_name,
                                     filename
                                 );
@@ -489,7 +606,7 @@ async fn dump_models_data() -> Result<()> {
                             Err(e) => {
                                 println!(
                                     "{} Failed to save {} models data: {}",
-                                    "❌".red(),
+                                    sym("❌").red(),
                                     provider_name,
                                     e
                                 );
@@ -499,7 +616,7 @@ async fn dump_models_data() -> Result<()> {
                     Err(e) => {
                         println!(
                             "{} Failed to fetch models from {}: {}",
-                            "❌".red(),
+                            sym("❌").red(),
                             provider_name,
                             e
                         );
@@ -509,7 +626,7 @@ async fn dump_models_data() -> Result<()> {
             Err(e) => {
                 println!(
                     "{} Failed to create client for {}: {}",
-                    "❌".red(),
+                    sym("❌").red(),
                     provider_name,
                     e
                 );
@@ -517,13 +634,13 @@ async fn dump_models_data() -> Result<()> {
         }
     }
 
-    println!("\n{} Summary:", "📊".blue());
+    println!("\n{} Summary:", sym("📊").blue());
     println!("   Total providers: {}", total_providers);
     println!("   Successful dumps: {}", successful_dumps);
     println!("   Models data saved to: ./models/");
 
     if successful_dumps > 0 {
-        println!("\n{} Model data collection complete!", "🎉".green());
+        println!("\n{} Model data collection complete!", sym("🎉").green());
         println!("   Next step: Analyze the JSON files to extract metadata patterns");
     }
 
@@ -535,6 +652,7 @@ fn apply_model_filters_with_tags(
     models: Vec<crate::model_metadata::ModelMetadata>,
     query: &Option<String>,
     tag_filters: Option<Vec<String>>,
+    strict_filters: bool,
     context_length: &Option<String>,
     input_length: &Option<String>,
     output_length: &Option<String>,
@@ -562,27 +680,12 @@ fn apply_model_filters_with_tags(
     // Apply tag filters if provided
     if let Some(tags) = tag_filters {
         for tag in tags {
-            match tag.as_str() {
-                "tools" => {
-                    filtered
-                        .retain(|model| model.supports_tools || model.supports_function_calling);
-                }
-                "reasoning" => {
-                    filtered.retain(|model| model.supports_reasoning);
-                }
-                "vision" => {
-                    filtered.retain(|model| model.supports_vision);
-                }
-                "audio" => {
-                    filtered.retain(|model| model.supports_audio);
-                }
-                "code" => {
-                    filtered.retain(|model| model.supports_code);
-                }
-                _ => {
-                    // Ignore unknown tags
-                }
-            }
+            // Tags other than capabilities are ignored
+            filtered.retain(|model| {
+                model
+                    .capability(&tag)
+                    .is_none_or(|state| passes_capability_filter(state, strict_filters))
+            });
         }
     }
 
@@ -636,6 +739,58 @@ fn apply_model_filters_with_tags(
     Ok(filtered)
 }
 
+/// Whether a model passes a capability filter; models whose metadata is silent
+/// on the capability pass unless filters are strict
+fn passes_capability_filter(state: Capability, strict_filters: bool) -> bool {
+    match state {
+        Capability::Supported => true,
+        Capability::Unknown => !strict_filters,
+        Capability::Unsupported => false,
+    }
+}
+
+fn has_unknown_capability(model: &ModelMetadata, filters: &[impl AsRef<str>]) -> bool {
+    filters
+        .iter()
+        .any(|tag| model.capability(tag.as_ref()) == Some(Capability::Unknown))
+}
+
+/// Capability indicators for a model, with a dimmed `?` marker for filtered
+/// capabilities its metadata doesn't state
+fn capability_badges(model: &ModelMetadata, filters: &[impl AsRef<str>]) -> Vec<ColoredString> {
+    let mut capabilities = Vec::new();
+    if model.supports_tools || model.supports_function_calling {
+        capabilities.push(sym("🔧 tools").blue());
+    }
+    if model.supports_vision {
+        capabilities.push(sym("👁 vision").magenta());
+    }
+    if model.supports_audio {
+        capabilities.push(sym("🔊 audio").yellow());
+    }
+    if model.supports_reasoning {
+        capabilities.push(sym("🧠 reasoning").cyan());
+    }
+    if model.supports_code {
+        capabilities.push(sym("💻 code").green());
+    }
+    for tag in filters {
+        let tag = tag.as_ref();
+        if model.capability(tag) == Some(Capability::Unknown) {
+            capabilities.push(format!("? {}", tag).dimmed());
+        }
+    }
+    capabilities
+}
+
+fn print_unknown_capability_note() {
+    println!(
+        "\n{}",
+        "? = capability not stated in the provider's metadata; use --strict-filters to hide these models"
+            .dimmed()
+    );
+}
+
 fn parse_token_count(input: &str) -> Result<u32> {
     let input = input.to_lowercase();
     if let Some(num_str) = input.strip_suffix('k') {
@@ -658,6 +813,7 @@ fn parse_token_count(input: &str) -> Result<u32> {
 fn display_enhanced_models(
     models: &[crate::model_metadata::ModelMetadata],
     query: &Option<String>,
+    capability_filters: &[String],
 ) -> Result<()> {
     if let Some(ref search_query) = query {
         println!(
@@ -682,22 +838,7 @@ fn display_enhanced_models(
         }
 
         // Build capability indicators
-        let mut capabilities = Vec::new();
-        if model.supports_tools || model.supports_function_calling {
-            capabilities.push("🔧 tools".blue());
-        }
-        if model.supports_vision {
-            capabilities.push("👁 vision".magenta());
-        }
-        if model.supports_audio {
-            capabilities.push("🔊 audio".yellow());
-        }
-        if model.supports_reasoning {
-            capabilities.push("🧠 reasoning".cyan());
-        }
-        if model.supports_code {
-            capabilities.push("💻 code".green());
-        }
+        let capabilities = capability_badges(model, capability_filters);
 
         // Build context info
         let mut context_info = Vec::new();
@@ -715,7 +856,7 @@ fn display_enhanced_models(
             model.id.clone()
         };
 
-        print!("  {} {}", "•".blue(), model_display.bold());
+        print!("  {} {}", sym("•").blue(), model_display.bold());
 
         if !capabilities.is_empty() {
             let capability_strings: Vec<String> =
@@ -730,11 +871,18 @@ fn display_enhanced_models(
         println!();
     }
 
+    if models
+        .iter()
+        .any(|model| has_unknown_capability(model, capability_filters))
+    {
+        print_unknown_capability_note();
+    }
+
     Ok(())
 }
 
 async fn fetch_raw_models_response(
-    _client: &crate::chat::LLMClient,
+    client: &crate::chat::LLMClient,
     provider_config: &crate::config::ProviderConfig,
 ) -> Result<String> {
     use serde_json::Value;
@@ -768,7 +916,7 @@ async fn fetch_raw_models_response(
     }
 
     // Only add Authorization header if no custom headers are present
-    if !has_custom_headers {
+    if !has_custom_headers && !client.signs_requests() {
         if let Some(api_key) = provider_config.api_key.as_ref() {
             req = req.header("Authorization", format!("Bearer {}", api_key));
             debug_log!("Added Authorization header with API key");
@@ -782,7 +930,7 @@ async fn fetch_raw_models_response(
     }
 
     debug_log!("Sending HTTP GET request...");
-    let response = req.send().await?;
+    let response = client.send(req).await?;
 
     let status = response.status();
     debug_log!("Received response with status: {}", status);
@@ -851,7 +999,7 @@ fn display_embedding_models(models: &[crate::model_metadata::ModelMetadata]) ->
             model.id.clone()
         };
 
-        print!("  {} {}", "•".blue(), model_display.bold());
+        print!("  {} {}", sym("•").blue(), model_display.bold());
 
         if !info_parts.is_empty() {
             print!(" ({})", info_parts.join(", ").dimmed());
@@ -862,3 +1010,113 @@ fn display_embedding_models(models: &[crate::model_metadata::ModelMetadata]) ->
 
     Ok(())
 }
+
+/// Search cached models of configured providers and, optionally, registry catalogs
+async fn handle_find(query: &str, available: bool) -> Result<()> {
+    let config = config::Config::load()?;
+    let query_lower = query.to_lowercase();
+
+    let mut local: Vec<_> = crate::unified_cache::UnifiedCache::load_all_cached_models()
+        .await?
+        .into_iter()
+        .filter(|m| {
+            m.id.to_lowercase().contains(&query_lower)
+                || m.display_name
+                    .as_ref()
+                    .is_some_and(|n| n.to_lowercase().contains(&query_lower))
+        })
+        .collect();
+    local.sort_by(|a, b| a.provider.cmp(&b.provider).then(a.id.cmp(&b.id)));
+
+    if local.is_empty() {
+        println!(
+            "{} No models matching '{}' in configured providers",
+            sym("ℹ️").blue(),
+            query
+        );
+    } else {
+        println!(
+            "\n{} ({} found):",
+            "Configured providers".bold().blue(),
+            local.len()
+        );
+        for model in &local {
+            println!(
+                "  {} {}:{}",
+                sym("•").blue(),
+                model.provider,
+                model.id.bold()
+            );
+        }
+    }
+
+    if !available {
+        if local.is_empty() {
+            println!(
+                "{} Use --available to search providers you haven't installed yet",
+                sym("💡").yellow()
+            );
+        }
+        return Ok(());
+    }
+
+    let installer = crate::provider_installer::ProviderInstaller::new()?;
+    let registry = match installer.list_available().await {
+        Ok(providers) => providers,
+        Err(e) => {
+            eprintln!(
+                "{} Could not load the provider registry: {}",
+                sym("⚠️").yellow(),
+                e
+            );
+            return Ok(());
+        }
+    };
+
+    let mut suggestions = Vec::new();
+    for (provider_id, metadata) in &registry {
+        let configured = config.has_provider(provider_id);
+        for model in metadata.matching_models(query) {
+            let already_listed = local
+                .iter()
+                .any(|m| &m.provider == provider_id && m.id == model);
+            if already_listed {
+                continue;
+            }
+            if configured {
+                suggestions.push(format!(
+                    "  {} {} is available via {} {} run {} to update its model list",
+                    sym("•").blue(),
+                    model.bold(),
+                    provider_id.green(),
+                    sym("—"),
+                    "lc models refresh".cyan()
+                ));
+            } else {
+                suggestions.push(format!(
+                    "  {} {} is available via provider {} {} run {}",
+                    sym("•").blue(),
+                    model.bold(),
+                    provider_id.green(),
+                    sym("—"),
+                    format!("lc providers install {}", provider_id).cyan()
+                ));
+            }
+        }
+    }
+
+    if suggestions.is_empty() {
+        println!(
+            "{} No other providers in the registry list a model matching '{}'",
+            sym("ℹ️").blue(),
+            query
+        );
+    } else {
+        println!("\n{}", "Provider registry".bold().blue());
+        for line in suggestions {
+            println!("{}", line);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+//! Git-style plugins: an `lc-<name>` executable on PATH runs as `lc <name> ...`
+//!
+//! `lc plugins list` also shows the WASM plugins in the config dir (see
+//! `services::wasm_plugins`).
+//!
+//! Built-in commands always take precedence, and a name found in several PATH
+//! directories resolves to the first one, like the shell does. Plugins receive the
+//! remaining arguments unchanged, plus `LC_BIN` (the running lc executable) in their
+//! environment so they can call back into lc.
+
+use crate::cli::PluginCommands;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const PREFIX: &str = "lc-";
+
+/// An `lc-<name>` executable found on PATH
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Handle `lc plugins`
+pub fn handle(command: PluginCommands) -> Result<()> {
+    match command {
+        PluginCommands::List => list(),
+    }
+}
+
+fn list() -> Result<()> {
+    let plugins = discover(&path_dirs());
+    let wasm_plugins = crate::services::wasm_plugins::installed()?;
+    if plugins.is_empty() && wasm_plugins.is_empty() {
+        println!(
+            "{} No plugins found. Plugins are executables named lc-<name> on PATH, or .wasm modules in {}.",
+            sym("ℹ️").blue(),
+            crate::services::wasm_plugins::plugins_dir()?.display()
+        );
+        return Ok(());
+    }
+
+    if !plugins.is_empty() {
+        println!("\n{}", "Plugins:".bold().blue());
+        let mut seen = HashSet::new();
+        for plugin in &plugins {
+            let note = if is_builtin(&plugin.name) {
+                format!(" {}", "(ignored: built-in command)".yellow())
+            } else if !seen.insert(plugin.name.as_str()) {
+                format!(
+                    " {}",
+                    "(ignored: shadowed by an earlier PATH entry)".yellow()
+                )
+            } else {
+                String::new()
+            };
+            println!(
+                "  {} {} - {}{}",
+                sym("•"),
+                plugin.name.bold(),
+                plugin.path.display().to_string().dimmed(),
+                note
+            );
+        }
+    }
+
+    if !wasm_plugins.is_empty() {
+        println!("\n{}", "WASM plugins:".bold().blue());
+        for path in &wasm_plugins {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            let status = match crate::services::wasm_plugins::describe(path) {
+                Ok(capabilities) => capabilities,
+                Err(reason) => format!("(not loaded: {})", reason).yellow().to_string(),
+            };
+            println!("  {} {} - {}", sym("•"), name.bold(), status);
+        }
+    }
+    Ok(())
+}
+
+/// Run `lc <name> <args>` as a plugin if `name` is not a built-in command and an
+/// `lc-<name>` executable is on PATH. Returns the plugin's exit code, or `None` when
+/// the arguments are for lc itself (a subcommand, flags or a direct prompt).
+pub fn run_if_plugin(args: &[String]) -> Result<Option<i32>> {
+    let Some(name) = args.first() else {
+        return Ok(None);
+    };
+    if !is_plugin_name(name) || is_builtin(name) {
+        return Ok(None);
+    }
+    let Some(plugin) = find(&path_dirs(), name) else {
+        return Ok(None);
+    };
+
+    crate::debug_log!("Running plugin {}", plugin.path.display());
+    let mut command = std::process::Command::new(&plugin.path);
+    command.args(&args[1..]);
+    if let Ok(exe) = std::env::current_exe() {
+        command.env("LC_BIN", exe);
+    }
+    let status = command
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to run plugin '{}': {}", plugin.path.display(), e))?;
+    Ok(Some(status.code().unwrap_or(1)))
+}
+
+/// Plugin names are single words; anything else (a quoted prompt, a path) is not looked up
+fn is_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Built-in subcommands and their aliases, which plugins cannot replace
+const BUILTINS: &[&str] = &[
+    "help",
+    "providers",
+    "p",
+    "keys",
+    "k",
+    "logs",
+    "l",
+    "backup",
+    "bk",
+    "usage",
+    "u",
+    "config",
+    "co",
+    "chat",
+    "c",
+    "watch",
+    "wa",
+    "duo",
+    "extract",
+    "ex",
+    "quickfix",
+    "qf",
+    "models",
+    "m",
+    "alias",
+    "a",
+    "templates",
+    "t",
+    "styles",
+    "st",
+    "proxy",
+    "pr",
+    "serve-webui",
+    "mcp",
+    "embed",
+    "e",
+    "similar",
+    "s",
+    "vectors",
+    "v",
+    "web-chat-proxy",
+    "w",
+    "sync",
+    "sy",
+    "undo",
+    "search",
+    "se",
+    "image",
+    "img",
+    "transcribe",
+    "tr",
+    "tts",
+    "dump-metadata",
+    "dump",
+    "completions",
+    "plugins",
+    "pl",
+    "doctor",
+    "dr",
+    "upgrade-self",
+];
+
+/// Whether `name` is a built-in subcommand or alias
+fn is_builtin(name: &str) -> bool {
+    BUILTINS.contains(&name)
+}
+
+fn path_dirs() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default()
+}
+
+/// All plugins in `dirs`, in order, including names shadowed by an earlier directory
+pub fn discover(dirs: &[PathBuf]) -> Vec<Plugin> {
+    let mut plugins = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut found: Vec<Plugin> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let name = plugin_name(&path)?;
+                is_executable(&path).then_some(Plugin { name, path })
+            })
+            .collect();
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        plugins.extend(found);
+    }
+    plugins
+}
+
+/// The first plugin called `name` in `dirs`
+fn find(dirs: &[PathBuf], name: &str) -> Option<Plugin> {
+    dirs.iter()
+        .flat_map(|dir| {
+            executable_extensions()
+                .into_iter()
+                .map(move |extension| dir.join(format!("{}{}{}", PREFIX, name, extension)))
+        })
+        .find(|path| is_executable(path))
+        .map(|path| Plugin {
+            name: name.to_string(),
+            path,
+        })
+}
+
+/// Plugin name of an `lc-<name>` file. On Windows, only files with an executable
+/// extension (PATHEXT) count, and the extension is not part of the name.
+fn plugin_name(path: &Path) -> Option<String> {
+    let stem = if cfg!(windows) {
+        let extension = format!(".{}", path.extension()?.to_str()?.to_lowercase());
+        if !executable_extensions().contains(&extension) {
+            return None;
+        }
+        path.file_stem()?.to_str()?
+    } else {
+        path.file_name()?.to_str()?
+    };
+    let name = stem.strip_prefix(PREFIX)?;
+    is_plugin_name(name).then(|| name.to_string())
+}
+
+/// File extensions that make a file executable: none on Unix, PATHEXT on Windows
+fn executable_extensions() -> Vec<String> {
+    if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|extension| !extension.is_empty())
+            .map(|extension| extension.to_lowercase())
+            .collect()
+    } else {
+        vec![String::new()]
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_discover_and_find() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let create = |dir: &Path, file: &str, mode: u32| {
+            let path = dir.join(file);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        };
+        create(first.path(), "lc-hello", 0o755);
+        create(first.path(), "lc-notes", 0o644);
+        create(second.path(), "lc-hello", 0o755);
+        create(second.path(), "lc-world", 0o755);
+        create(second.path(), "other", 0o755);
+
+        let dirs = vec![first.path().to_path_buf(), second.path().to_path_buf()];
+        let names: Vec<String> = discover(&dirs).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["hello", "hello", "world"]);
+
+        assert_eq!(
+            find(&dirs, "hello").unwrap().path,
+            first.path().join("lc-hello")
+        );
+        assert!(find(&dirs, "notes").is_none());
+
+        assert!(!is_plugin_name("what is rust"));
+        assert!(is_builtin("providers") && is_builtin("p") && !is_builtin("hello"));
+    }
+
+    #[test]
+    fn test_builtins_match_cli() {
+        use clap::CommandFactory;
+
+        // Building the whole command tree needs more than the default test-thread stack
+        let names = std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                let mut names = vec!["help".to_string()];
+                for command in crate::cli::Cli::command().get_subcommands() {
+                    names.push(command.get_name().to_string());
+                    names.extend(command.get_all_aliases().map(str::to_string));
+                }
+                names
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        let mut expected: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut builtins = BUILTINS.to_vec();
+        expected.sort_unstable();
+        builtins.sort_unstable();
+        assert_eq!(builtins, expected);
+    }
+}
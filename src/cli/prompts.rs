@@ -1,15 +1,24 @@
 //! Prompt handling and utilities
 
+use crate::utils::terminal::sym;
 use crate::{
+    cli::AudioMode,
     config::Config,
     core::chat::{
-        create_authenticated_client, send_chat_request_with_streaming,
-        send_chat_request_with_validation,
+        add_tokens, create_authenticated_client, ensure_vision_support,
+        send_chat_request_with_streaming, send_chat_request_with_streaming_messages,
+        send_chat_request_with_validation, send_chat_request_with_validation_messages, LLMClient,
     },
-    database::Database,
+    core::request_metadata::log_details,
+    core::style::StyleProfile,
+    core::validation::{self, ValidationOptions},
+    database::{ChatEntry, Database, LogDetails},
     debug_log,
+    models::deprecation::{warn_if_deprecated, ModelOrigin},
+    provider::{InputAudio, Message},
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use colored::Colorize;
 
 #[allow(clippy::too_many_arguments)]
 /// Handle direct prompt command
@@ -20,11 +29,12 @@ pub async fn handle_direct(
     system_prompt: Option<String>,
     max_tokens: Option<String>,
     temperature: Option<String>,
-    _attachments: Vec<String>,
-    _images: Vec<String>,
-    _audio_files: Vec<String>,
+    attachments: Vec<String>,
+    images: Vec<String>,
+    audio_files: Vec<String>,
+    audio_mode: AudioMode,
     tools: Option<String>,
-    _vectordb: Option<String>,
+    vectordb: Option<String>,
     use_search: Option<String>,
     stream: bool,
 ) -> Result<()> {
@@ -41,12 +51,57 @@ pub async fn handle_direct(
         stream
     );
 
+    // Post-processing plugins need the whole response
+    let stream = if stream && crate::services::wasm_plugins::has_post_processors() {
+        debug_log!("Not streaming: WASM plugins post-process the response");
+        false
+    } else if stream && crate::core::structured_output::requested().is_some() {
+        debug_log!("Not streaming: the response is checked against --schema");
+        false
+    } else {
+        stream
+    };
+
     // Load configuration
     let mut config = Config::load()?;
 
+    // The style profile's rules go at the end of the system prompt
+    let style = crate::core::style::active(&config)?;
+    let system_prompt = system_prompt.map(|system_prompt| config.render_prompt(&system_prompt));
+    // The answer's language is checked before it is shown, so a retry isn't printed twice
+    let stream = if stream && style.as_ref().is_some_and(|style| style.check_language) {
+        debug_log!("Not streaming: the language of the answer is checked");
+        false
+    } else {
+        stream
+    };
+    let system_prompt = crate::core::style::compose(system_prompt, style.as_ref());
+
     // Determine provider and model
+    let origin = ModelOrigin::from_model_arg(&config, model.as_deref());
+    let routable = provider.is_none() && model.is_none();
     let (provider_name, model_name) = determine_provider_and_model(&config, provider, model)?;
 
+    // Without -p/-m, simple prompts go to the [router] cheap model
+    let (provider_name, model_name, route) =
+        if routable && crate::core::router::cheap_model(&config).is_some() {
+            let has_extras = !attachments.is_empty()
+                || !images.is_empty()
+                || !audio_files.is_empty()
+                || tools.is_some()
+                || vectordb.is_some()
+                || use_search.is_some();
+            route_prompt(
+                &mut config,
+                &prompt,
+                has_extras,
+                (provider_name, model_name),
+            )
+            .await?
+        } else {
+            (provider_name, model_name, None)
+        };
+
     debug_log!(
         "Using provider: '{}', model: '{}'",
         provider_name,
@@ -79,52 +134,160 @@ pub async fn handle_direct(
 
     debug_log!("Using API model name: '{}'", api_model_name);
 
+    warn_if_deprecated(&provider_name, &api_model_name, &origin).await;
+
+    // Chunks from the vector database go before the question, matching images are attached
+    let (prompt, images) = match &vectordb {
+        Some(db_name) => {
+            let context = crate::cli::embed::retrieve_rag_matches(db_name, &prompt).await?;
+            let vision = !context.images.is_empty()
+                && crate::core::chat::model_may_support_vision(&provider_name, &api_model_name)
+                    .await;
+            let (prompt, retrieved) = with_rag_context(db_name, prompt, context, vision);
+            let mut images = images;
+            images.extend(retrieved);
+            (prompt, images)
+        }
+        None => (prompt, images),
+    };
+
+    // What --on-refusal strip-attachments sends
+    let has_attachments = !attachments.is_empty() || !images.is_empty() || !audio_files.is_empty();
+    let bare_prompt = prompt.clone();
+
+    // Attached files, reduced to the attachment budget when they don't fit
+    let prompt = if attachments.is_empty() {
+        prompt
+    } else {
+        let attached = crate::core::attachment_budget::prepare(
+            &attachments,
+            &prompt,
+            &client,
+            &api_model_name,
+            &provider_name,
+        )
+        .await?;
+        format!("{}\n\n{}", prompt, attached)
+    };
+
+    // Check vision support before processing images so a non-vision model doesn't get billed
+    let processed_images = if images.is_empty() {
+        Vec::new()
+    } else {
+        ensure_vision_support(&provider_name, &api_model_name, images.len()).await?;
+        let processed = crate::image_utils::process_images(&images)?;
+        debug_log!("Processed {} image(s) for the prompt", processed.len());
+        processed
+    };
+    // Local image files are logged with the turn, so `lc logs share` may embed them
+    let attached_images: Vec<String> = images
+        .iter()
+        .filter(|image| std::path::Path::new(image).is_file())
+        .filter_map(|image| std::path::absolute(image).ok())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+
     // Process search if --use-search is specified
-    let final_prompt = if let Some(search_spec) = use_search {
+    let search_context = if let Some(search_spec) = use_search {
         debug_log!("Processing search with spec: {}", search_spec);
 
         // Parse search spec (format: "provider" or "provider:query")
-        let (search_provider, search_query) = if search_spec.contains(':') {
-            if let Some((provider, query)) = search_spec.split_once(':') {
-                (provider.to_string(), query.to_string())
-            } else {
-                (search_spec, prompt.clone())
-            }
-        } else {
-            (search_spec, prompt.clone())
+        let (search_provider, explicit_query) = match search_spec.split_once(':') {
+            Some((provider, query)) => (provider.to_string(), Some(query.to_string())),
+            None => (search_spec, None),
         };
 
-        debug_log!(
-            "Search provider: '{}', query: '{}'",
-            search_provider,
-            search_query
-        );
+        // A conversational prompt is rewritten into focused queries when a rewrite
+        // model is set; an explicit query is searched as given
+        let rewritten = match (&explicit_query, crate::search::rewrite::model()) {
+            (None, Some(rewrite_model)) => {
+                match rewrite_search_queries(&mut config, &rewrite_model, &prompt).await {
+                    Ok(queries) => Some(queries),
+                    Err(e) => {
+                        eprintln!(
+                            "{} Could not rewrite the search query, searching the prompt: {}",
+                            sym("⚠️").yellow(),
+                            e
+                        );
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
 
         // Perform the search
         let search_engine = crate::search::SearchEngine::new()?;
-        let search_results = search_engine
-            .search(&search_provider, &search_query, Some(5))
-            .await?;
-
-        // Extract context from search results
-        let search_context = search_engine.extract_context_for_llm(&search_results, 5);
+        let search_context = match rewritten {
+            Some(queries) => {
+                debug_log!(
+                    "Search provider: '{}', rewritten queries: {:?}",
+                    search_provider,
+                    queries
+                );
+                let mut searches = Vec::new();
+                for query in &queries {
+                    eprintln!("{} {}", sym("🔍 Searching").dimmed(), query.dimmed());
+                    searches.push(
+                        search_engine
+                            .search(&search_provider, query, Some(5))
+                            .await?,
+                    );
+                }
+                let merged = crate::search::rewrite::merge(searches);
+                println!(
+                    "{} Search completed: {} results for {} queries from {}\n",
+                    sym("🔍"),
+                    merged.len(),
+                    queries.len(),
+                    search_provider
+                );
+                crate::search::rewrite::context_for_llm(&merged, 5 * queries.len())
+            }
+            None => {
+                let search_query = explicit_query.unwrap_or_else(|| prompt.clone());
+                debug_log!(
+                    "Search provider: '{}', query: '{}'",
+                    search_provider,
+                    search_query
+                );
+                let search_results = search_engine
+                    .search(&search_provider, &search_query, Some(5))
+                    .await?;
+                println!(
+                    "{} Search completed: {} results from {}\n",
+                    sym("🔍"),
+                    search_results.results.len(),
+                    search_provider
+                );
+                search_engine.extract_context_for_llm(&search_results, 5)
+            }
+        };
 
-        // Combine search context with original prompt
-        let combined_prompt = format!("{}\n\nUser's question: {}", search_context, prompt);
+        debug_log!("Search context length: {}", search_context.len());
+        Some(search_context)
+    } else {
+        None
+    };
 
-        debug_log!(
-            "Added search context, combined prompt length: {}",
-            combined_prompt.len()
-        );
-        println!(
-            "🔍 Search completed: {} results from {}\n",
-            search_results.results.len(),
-            search_provider
-        );
+    // Combine search context with the prompt
+    let with_search = |prompt: &str| match &search_context {
+        Some(context) => format!("{}\n\nUser's question: {}", context, prompt),
+        None => prompt.to_string(),
+    };
+    let final_prompt = with_search(&prompt);
+    let stripped_prompt = has_attachments.then(|| with_search(&bare_prompt));
 
-        combined_prompt
+    // Attach audio natively when the model accepts it, otherwise fall back to transcripts
+    let (final_prompt, native_audio) = if audio_files.is_empty() {
+        (final_prompt, Vec::new())
+    } else if use_native_audio(audio_mode, &provider_name, &api_model_name).await {
+        debug_log!("Sending {} audio file(s) as input_audio", audio_files.len());
+        (final_prompt, process_native_audio(&audio_files)?)
     } else {
-        prompt.clone()
+        debug_log!("Transcribing {} audio file(s)", audio_files.len());
+        let transcripts = crate::cli::audio::transcribe_for_prompt(&audio_files).await?;
+        (format!("{}\n\n{}", transcripts, final_prompt), Vec::new())
     };
 
     // Fetch MCP tools if specified
@@ -135,95 +298,913 @@ pub async fn handle_direct(
     };
 
     // Send the request - templates will be automatically applied by the client
-    if stream {
-        debug_log!("Sending streaming chat request");
-        // For streaming, we don't get the response back, so we can't save it to database
-        // But we should still create/update the session for consistency
-        let db = Database::new()?;
-        let _session_id = match db.get_current_session_id()? {
-            Some(id) => {
-                debug_log!("Using existing session for streaming: {}", id);
-                id
+    let request = DirectRequest {
+        prompt: final_prompt,
+        images: processed_images,
+        audio: native_audio,
+        system_prompt: system_prompt.as_deref(),
+        max_tokens: max_tokens_parsed,
+        temperature: temperature_parsed,
+        tools: mcp_tools,
+        mcp_servers: &mcp_server_names,
+        stream,
+    };
+    let recover = !crate::core::refusal::active(&config).strategies.is_empty();
+    let sent = crate::core::refusal::with_recovery(recover, async {
+        match send_direct(&client, &api_model_name, &provider_name, &request).await {
+            Err(e) if crate::core::refusal::kind_of(&e).is_some() => {
+                recover_from_refusal(
+                    e,
+                    &mut config,
+                    &client,
+                    &api_model_name,
+                    &provider_name,
+                    &request,
+                    stripped_prompt,
+                )
+                .await
             }
-            None => {
-                // Generate a new session ID using UUID for consistency
-                let new_session_id = uuid::Uuid::new_v4().to_string();
-                db.set_current_session_id(&new_session_id)?;
-                debug_log!("Created new session for streaming: {}", new_session_id);
-                new_session_id
+            result => {
+                let (response, input_tokens, output_tokens) = result?;
+                Ok(Sent {
+                    response,
+                    input_tokens,
+                    output_tokens,
+                    prompt: request.prompt.clone(),
+                    fallback: None,
+                })
             }
-        };
+        }
+    })
+    .await?;
+    let final_prompt = sent.prompt;
+    let (client, provider_name, api_model_name) = match sent.fallback {
+        Some(fallback) => fallback,
+        None => (client, provider_name, api_model_name),
+    };
+    let mut details = log_details(&provider_name, system_prompt.as_deref());
+    details.images = attached_images;
+    if let Some(decision) = &route {
+        details.metadata.extend(crate::core::router::log_metadata(
+            decision,
+            sent.input_tokens,
+            sent.output_tokens,
+        ));
+    }
+
+    let response = if stream {
+        // Save the streamed text (including any truncation marker) to history
+        if let Err(e) = save_to_database(
+            &prompt,
+            &sent.response,
+            &provider_name,
+            &api_model_name,
+            None,
+            None,
+            &details,
+        )
+        .await
+        {
+            debug_log!("Failed to save to database: {}", e);
+        }
+        sent.response
+    } else {
+        let response = crate::services::wasm_plugins::post_process(sent.response);
+        let (response, sent_input_tokens, sent_output_tokens) =
+            crate::core::chat::conform_to_schema(
+                &client,
+                &api_model_name,
+                &final_prompt,
+                (response, sent.input_tokens, sent.output_tokens),
+                system_prompt.as_deref(),
+                max_tokens_parsed,
+                temperature_parsed,
+                &provider_name,
+            )
+            .await?;
+        let (response, input_tokens, output_tokens) =
+            match style.filter(|style| style.check_language) {
+                Some(style) => {
+                    let (retried, input_tokens, output_tokens) = enforce_language(
+                        &style,
+                        &client,
+                        &api_model_name,
+                        &provider_name,
+                        &final_prompt,
+                        response,
+                        system_prompt.as_deref(),
+                        max_tokens_parsed,
+                        temperature_parsed,
+                    )
+                    .await?;
+                    (
+                        retried,
+                        add_tokens(sent_input_tokens, input_tokens),
+                        add_tokens(sent_output_tokens, output_tokens),
+                    )
+                }
+                None => (response, sent_input_tokens, sent_output_tokens),
+            };
+
+        // Print the response
+        println!("{}", crate::core::redaction::redact(&response));
+
+        // Save to database
+        if let Err(e) = save_to_database(
+            &prompt,
+            &response,
+            &provider_name,
+            &api_model_name,
+            input_tokens,
+            output_tokens,
+            &details,
+        )
+        .await
+        {
+            debug_log!("Failed to save to database: {}", e);
+        }
+        response
+    };
 
-        send_chat_request_with_streaming(
+    if let Err(e) = remember_session_settings(&tools, &vectordb) {
+        debug_log!("Failed to save session settings: {}", e);
+    }
+
+    if let Some(verifier) = crate::core::self_check::active(&config) {
+        self_check_response(
+            &mut config,
+            verifier,
             &client,
             &api_model_name,
+            &provider_name,
             &final_prompt,
-            &[], // No history for direct prompt
+            &response,
+        )
+        .await?;
+    }
+
+    if let Some(options) = crate::core::validation::current() {
+        validate_response(
+            &options,
+            &client,
+            &api_model_name,
+            &provider_name,
+            &final_prompt,
+            response,
             system_prompt.as_deref(),
             max_tokens_parsed,
             temperature_parsed,
-            &provider_name,
-            mcp_tools.clone(),
         )
         .await?;
+    }
+
+    Ok(())
+}
+
+/// A direct prompt as sent to the model, kept to send it again differently when the
+/// provider refuses it
+struct DirectRequest<'a> {
+    prompt: String,
+    images: Vec<String>,
+    audio: Vec<InputAudio>,
+    system_prompt: Option<&'a str>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    tools: Option<Vec<crate::provider::Tool>>,
+    mcp_servers: &'a [String],
+    stream: bool,
+}
+
+/// The answer to a direct prompt, with the prompt it answers and the fallback model's
+/// client, provider and model when `--on-refusal fallback` produced it
+struct Sent {
+    response: String,
+    input_tokens: Option<i32>,
+    output_tokens: Option<i32>,
+    prompt: String,
+    fallback: Option<(LLMClient, String, String)>,
+}
+
+/// Send a direct prompt, constrained to the `--schema` if one was given
+async fn send_direct(
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    request: &DirectRequest<'_>,
+) -> Result<(String, Option<i32>, Option<i32>)> {
+    crate::core::structured_output::with_format(
+        crate::core::structured_output::requested(),
+        send_direct_request(client, model, provider_name, request),
+    )
+    .await
+}
+
+/// Send a direct prompt, streaming it or running its tools as requested
+async fn send_direct_request(
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    request: &DirectRequest<'_>,
+) -> Result<(String, Option<i32>, Option<i32>)> {
+    let has_media = !request.images.is_empty() || !request.audio.is_empty();
+    let messages = || {
+        [Message::user_with_media(
+            request.prompt.clone(),
+            &request.images,
+            &request.audio,
+        )]
+    };
+    let server_refs: Vec<&str> = request.mcp_servers.iter().map(|s| s.as_str()).collect();
+    let run_tools = request.tools.is_some() && !server_refs.is_empty();
+    let _spinner = crate::utils::spinner::start(model);
+
+    if request.stream {
+        debug_log!("Sending streaming chat request");
+        let response = if has_media {
+            send_chat_request_with_streaming_messages(
+                client,
+                model,
+                &messages(),
+                request.system_prompt,
+                request.max_tokens,
+                request.temperature,
+                provider_name,
+                request.tools.clone(),
+            )
+            .await?
+        } else {
+            send_chat_request_with_streaming(
+                client,
+                model,
+                &request.prompt,
+                &[], // No history for direct prompt
+                request.system_prompt,
+                request.max_tokens,
+                request.temperature,
+                provider_name,
+                request.tools.clone(),
+            )
+            .await?
+        };
+        return Ok((response, None, None));
+    }
+
+    debug_log!("Sending non-streaming chat request");
+    // Use tool execution if tools are available
+    match (has_media, run_tools) {
+        (true, true) => {
+            crate::core::chat::send_chat_request_with_tool_execution_messages(
+                client,
+                model,
+                &messages(),
+                request.system_prompt,
+                request.max_tokens,
+                request.temperature,
+                provider_name,
+                request.tools.clone(),
+                &server_refs,
+                None, // Use default max_iterations
+            )
+            .await
+        }
+        (true, false) => {
+            send_chat_request_with_validation_messages(
+                client,
+                model,
+                &messages(),
+                request.system_prompt,
+                request.max_tokens,
+                request.temperature,
+                provider_name,
+                request.tools.clone(),
+            )
+            .await
+        }
+        (false, true) => {
+            crate::core::chat::send_chat_request_with_tool_execution(
+                client,
+                model,
+                &request.prompt,
+                &[], // No history for direct prompt
+                request.system_prompt,
+                request.max_tokens,
+                request.temperature,
+                provider_name,
+                request.tools.clone(),
+                &server_refs,
+                None, // Use default max_iterations
+            )
+            .await
+        }
+        (false, false) => {
+            send_chat_request_with_validation(
+                client,
+                model,
+                &request.prompt,
+                &[], // No history for direct prompt
+                request.system_prompt,
+                request.max_tokens,
+                request.temperature,
+                provider_name,
+                request.tools.clone(),
+            )
+            .await
+        }
+    }
+}
+
+/// Try the `--on-refusal` strategies in order after the provider refused a direct
+/// prompt, returning the first answer. Without strategies the refusal is returned with
+/// a hint to set some.
+#[allow(clippy::too_many_arguments)]
+async fn recover_from_refusal(
+    refusal: anyhow::Error,
+    config: &mut Config,
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    request: &DirectRequest<'_>,
+    stripped_prompt: Option<String>,
+) -> Result<Sent> {
+    use crate::core::refusal::{self, RefusalKind, Strategy};
+
+    let settings = refusal::active(config);
+    let Some(kind) = refusal::kind_of(&refusal) else {
+        return Err(refusal);
+    };
+    if settings.strategies.is_empty() {
+        anyhow::bail!(
+            "{}\n\nNext step: retry automatically with --on-refusal rephrase,strip-attachments,fallback",
+            refusal
+        );
+    }
+    eprintln!("{} {}", sym("⚠️").yellow(), kind.describe());
+    debug_log!("Refusal: {}", refusal);
+
+    let mut tried = Vec::new();
+    for strategy in &settings.strategies {
+        let attempt = match strategy {
+            Strategy::Rephrase => {
+                let prompt = match kind {
+                    RefusalKind::ContentFilter => {
+                        eprintln!("{} Asking {} to rephrase the prompt", "↻".blue(), model);
+                        rephrase_prompt(client, model, provider_name, &request.prompt).await
+                    }
+                    RefusalKind::MalformedToolCall => {
+                        eprintln!("{} Asking again for valid tool-call arguments", "↻".blue());
+                        Ok(refusal::tool_call_reminder(&request.prompt))
+                    }
+                };
+                match prompt {
+                    Ok(prompt) => {
+                        debug_log!("Rephrased prompt: {}", prompt);
+                        let retry = DirectRequest {
+                            prompt,
+                            images: request.images.clone(),
+                            audio: request.audio.clone(),
+                            tools: request.tools.clone(),
+                            ..*request
+                        };
+                        send_direct(client, model, provider_name, &retry)
+                            .await
+                            .map(|sent| (sent, retry.prompt, None))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            Strategy::StripAttachments => {
+                let Some(prompt) = stripped_prompt.clone() else {
+                    eprintln!(
+                        "  {} skipped: the prompt has no attachments",
+                        strategy.name()
+                    );
+                    continue;
+                };
+                eprintln!("{} Retrying without the attachments", "↻".blue());
+                let retry = DirectRequest {
+                    prompt,
+                    images: Vec::new(),
+                    audio: Vec::new(),
+                    tools: request.tools.clone(),
+                    ..*request
+                };
+                send_direct(client, model, provider_name, &retry)
+                    .await
+                    .map(|sent| (sent, retry.prompt, None))
+            }
+            Strategy::Fallback => {
+                let Some(fallback_model) = settings.fallback_model.clone() else {
+                    eprintln!(
+                        "  {} skipped: no --fallback-model or [refusal] fallback_model",
+                        strategy.name()
+                    );
+                    continue;
+                };
+                match fallback_client(config, &fallback_model).await {
+                    Ok((fallback_client, fallback_provider, fallback_model)) => {
+                        eprintln!(
+                            "{} Retrying with {}:{}",
+                            "↻".blue(),
+                            fallback_provider,
+                            fallback_model
+                        );
+                        send_direct(
+                            &fallback_client,
+                            &fallback_model,
+                            &fallback_provider,
+                            request,
+                        )
+                        .await
+                        .map(|sent| {
+                            (
+                                sent,
+                                request.prompt.clone(),
+                                Some((fallback_client, fallback_provider, fallback_model)),
+                            )
+                        })
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        };
+
+        tried.push(strategy.name());
+        match attempt {
+            Ok(((response, input_tokens, output_tokens), prompt, fallback)) => {
+                eprintln!("{} Recovered with {}", sym("✓").green(), strategy.name());
+                return Ok(Sent {
+                    response,
+                    input_tokens,
+                    output_tokens,
+                    prompt,
+                    fallback,
+                });
+            }
+            Err(e) => {
+                let reason = e.to_string();
+                eprintln!(
+                    "  {} failed: {}",
+                    strategy.name(),
+                    reason.lines().next().unwrap_or_default()
+                );
+                debug_log!("{} failed: {}", strategy.name(), reason);
+            }
+        }
+    }
+
+    if tried.is_empty() {
+        return Err(refusal);
+    }
+    anyhow::bail!("{}\n\nTried: {}", refusal, tried.join(", "))
+}
+
+/// Have the model reword a prompt the content filter blocked
+async fn rephrase_prompt(
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    prompt: &str,
+) -> Result<String> {
+    let (reply, _, _) = send_chat_request_with_validation(
+        client,
+        model,
+        &crate::core::refusal::rephrase_request(prompt),
+        &[],
+        None,
+        None,
+        Some(0.3),
+        provider_name,
+        None,
+    )
+    .await?;
+    let reply = reply.trim();
+    if reply.is_empty() {
+        anyhow::bail!("the model gave no rephrased prompt");
+    }
+    Ok(reply.to_string())
+}
+
+/// Client, provider and model of the `--on-refusal fallback` model
+async fn fallback_client(
+    config: &mut Config,
+    fallback_model: &str,
+) -> Result<(LLMClient, String, String)> {
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(config, None, Some(fallback_model.to_string()))
+            .with_context(|| format!("Invalid fallback model '{}'", fallback_model))?;
+    let client = create_authenticated_client(config, &provider).await?;
+    Ok((client, provider, model))
+}
+
+/// The prompt with the chunks retrieved from `db_name`, and the matching images to
+/// attach. Without `attach_images` the images are only listed by name in the context.
+pub fn with_rag_context(
+    db_name: &str,
+    prompt: String,
+    context: crate::cli::embed::RagContext,
+    attach_images: bool,
+) -> (String, Vec<String>) {
+    let mut text = context.text;
+    let mut images = Vec::new();
+    if !context.images.is_empty() {
+        if attach_images {
+            debug_log!(
+                "Attaching {} image(s) from '{}'",
+                context.images.len(),
+                db_name
+            );
+            images = context.images;
+        } else {
+            debug_log!(
+                "Not attaching images from '{}' to a non-vision model",
+                db_name
+            );
+            for path in &context.images {
+                text.push_str(&format!(
+                    "- {}\n",
+                    crate::cli::embed::image_description(std::path::Path::new(path))
+                ));
+            }
+        }
+    }
+    if text.is_empty() {
+        return (prompt, images);
+    }
+    (
+        format!(
+            "Context from knowledge base:\n{}\nUser question: {}",
+            text, prompt
+        ),
+        images,
+    )
+}
+
+/// Remember the tools and vector database of the current session, so `-c` re-applies them
+pub fn remember_session_settings(tools: &Option<String>, vectordb: &Option<String>) -> Result<()> {
+    if tools.is_none() && vectordb.is_none() {
+        return Ok(());
+    }
+    let db = Database::new()?;
+    let session_id = db.current_or_new_session_id()?;
+    db.save_session_settings(
+        &session_id,
+        &crate::database::SessionSettings {
+            tools: tools.clone(),
+            vectordb: vectordb.clone(),
+        },
+    )
+}
+
+/// Ask the model once more when the answer is not in the style's language, returning
+/// the answer to keep and the tokens of the retry
+#[allow(clippy::too_many_arguments)]
+async fn enforce_language(
+    style: &StyleProfile,
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    prompt: &str,
+    response: String,
+    system_prompt: Option<&str>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+) -> Result<(String, Option<i32>, Option<i32>)> {
+    let Some(detected) = style.language_mismatch(&response) else {
+        return Ok((response, None, None));
+    };
+    let expected = style.language.as_deref().unwrap_or_default();
+    eprintln!(
+        "{} The answer is in {}, not {}; asking again",
+        sym("⚠️").yellow(),
+        detected,
+        expected
+    );
 
-        // Note: We can't save the response to database in streaming mode
-        // as the response is streamed directly to stdout
-        eprintln!("\nNote: Streaming responses are not saved to conversation history.");
+    let history = [ChatEntry {
+        chat_id: String::new(),
+        model: model.to_string(),
+        question: prompt.to_string(),
+        response,
+        timestamp: chrono::Utc::now(),
+        input_tokens: None,
+        output_tokens: None,
+        provider: Some(provider_name.to_string()),
+    }];
+    let question = crate::core::style::language_retry_prompt(expected, detected);
+    let (retried, input_tokens, output_tokens) = send_chat_request_with_validation(
+        client,
+        model,
+        &question,
+        &history,
+        system_prompt,
+        max_tokens,
+        temperature,
+        provider_name,
+        None,
+    )
+    .await?;
+    let retried = crate::services::wasm_plugins::post_process(retried);
+    if let Some(detected) = style.language_mismatch(&retried) {
+        eprintln!(
+            "{} The answer is still in {}, not {}",
+            sym("⚠️").yellow(),
+            detected,
+            expected
+        );
+    }
+    Ok((retried, input_tokens, output_tokens))
+}
+
+/// The model for a prompt the [router] classifies: the cheap model for simple prompts,
+/// `default` otherwise, with the decision to log with the answer
+async fn route_prompt(
+    config: &mut Config,
+    prompt: &str,
+    has_extras: bool,
+    default: (String, String),
+) -> Result<(String, String, Option<crate::core::router::Decision>)> {
+    use crate::core::router::{self, Classification, Decision, Tier};
+
+    let Some(cheap_model) = router::cheap_model(config).map(str::to_string) else {
+        return Ok((default.0, default.1, None));
+    };
+    let (cheap_provider, cheap) =
+        crate::utils::resolve_model_and_provider(config, None, Some(cheap_model.clone()))
+            .with_context(|| format!("Invalid [router] cheap_model '{}'", cheap_model))?;
+    let cheap_full = format!("{}:{}", cheap_provider, cheap);
+    let (default_provider, default_model) = default;
+    if cheap_full == default_model {
+        return Ok((default_provider, default_model, None));
+    }
+
+    let classification = match config.router.classifier_model.clone() {
+        Some(classifier) if !has_extras => {
+            match classify_prompt(config, &classifier, prompt).await {
+                Ok(tier) => Classification::new(tier, format!("classified by {}", classifier)),
+                Err(e) => {
+                    eprintln!(
+                        "{} Could not classify the prompt with {}, using heuristics: {}",
+                        sym("⚠️").yellow(),
+                        classifier,
+                        e
+                    );
+                    router::classify(prompt, has_extras, &config.router)
+                }
+            }
+        }
+        _ => router::classify(prompt, has_extras, &config.router),
+    };
+
+    let default_api_model = default_model
+        .split_once(':')
+        .map_or(default_model.as_str(), |(_, model)| model);
+    let decision = Decision {
+        cheap_prices: crate::core::budget::pricing(&cheap_provider, &cheap).await,
+        default_prices: crate::core::budget::pricing(&default_provider, default_api_model).await,
+        classification,
+    };
+    let tier = decision.classification.tier;
+    if tier == Tier::Simple {
+        eprintln!(
+            "{}",
+            format!(
+                "Note: simple prompt ({}), using {} instead of {}",
+                decision.classification.reason, cheap_full, default_model
+            )
+            .dimmed()
+        );
     } else {
-        debug_log!("Sending non-streaming chat request");
+        debug_log!(
+            "Router: complex prompt ({}), using {}",
+            decision.classification.reason,
+            default_model
+        );
+    }
+    Ok(match tier {
+        Tier::Simple => (cheap_provider, cheap_full, Some(decision)),
+        Tier::Complex => (default_provider, default_model, Some(decision)),
+    })
+}
 
-        // Use tool execution if tools are available
-        let (response, input_tokens, output_tokens) =
-            if mcp_tools.is_some() && !mcp_server_names.is_empty() {
-                // Convert server names to &str references
-                let server_refs: Vec<&str> = mcp_server_names.iter().map(|s| s.as_str()).collect();
+/// Ask the [router] classifier model whether a prompt is simple
+async fn classify_prompt(
+    config: &mut Config,
+    classifier: &str,
+    prompt: &str,
+) -> Result<crate::core::router::Tier> {
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(config, None, Some(classifier.to_string()))?;
+    let client = create_authenticated_client(config, &provider).await?;
+    let (reply, _, _) = send_chat_request_with_validation(
+        &client,
+        &model,
+        &crate::core::router::classifier_prompt(prompt),
+        &[],
+        None,
+        Some(5),
+        Some(0.0),
+        &provider,
+        None,
+    )
+    .await?;
+    crate::core::router::parse_classifier_reply(&reply)
+        .ok_or_else(|| anyhow::anyhow!("unexpected reply '{}'", reply.trim()))
+}
 
-                crate::core::chat::send_chat_request_with_tool_execution(
-                    &client,
-                    &api_model_name,
-                    &final_prompt,
-                    &[], // No history for direct prompt
-                    system_prompt.as_deref(),
-                    max_tokens_parsed,
-                    temperature_parsed,
-                    &provider_name,
-                    mcp_tools.clone(),
-                    &server_refs,
-                    None, // Use default max_iterations
-                )
-                .await?
-            } else {
-                send_chat_request_with_validation(
-                    &client,
-                    &api_model_name,
-                    &final_prompt,
-                    &[], // No history for direct prompt
-                    system_prompt.as_deref(),
-                    max_tokens_parsed,
-                    temperature_parsed,
-                    &provider_name,
-                    mcp_tools.clone(),
-                )
-                .await?
+/// Ask the rewrite model for the focused search queries of a prompt
+async fn rewrite_search_queries(
+    config: &mut Config,
+    rewrite_model: &str,
+    prompt: &str,
+) -> Result<Vec<String>> {
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(config, None, Some(rewrite_model.to_string()))?;
+    let client = create_authenticated_client(config, &provider).await?;
+    eprintln!(
+        "{} Rewriting the search query with {}:{}...",
+        sym("🔍").blue(),
+        provider,
+        model
+    );
+    let (reply, _, _) = send_chat_request_with_validation(
+        &client,
+        &model,
+        &crate::search::rewrite::prompt(prompt),
+        &[],
+        None,
+        None,
+        None,
+        &provider,
+        None,
+    )
+    .await?;
+    let queries = crate::search::rewrite::parse(&reply);
+    if queries.is_empty() {
+        anyhow::bail!("the model gave no queries");
+    }
+    Ok(queries)
+}
+
+/// Ask the verifier model to check the answer, printing a one-line note when it found
+/// nothing wrong and its corrections otherwise
+#[allow(clippy::too_many_arguments)]
+async fn self_check_response(
+    config: &mut Config,
+    verifier: Option<String>,
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    prompt: &str,
+    response: &str,
+) -> Result<()> {
+    let (verifier_client, verifier_provider, verifier_model) = match verifier {
+        Some(verifier) => {
+            let (provider, model) = determine_provider_and_model(config, None, Some(verifier))?;
+            let model = match model.split_once(':') {
+                Some((_, model)) => model.to_string(),
+                None => model,
             };
+            let client = create_authenticated_client(config, &provider).await?;
+            (Some(client), provider, model)
+        }
+        None => (None, provider_name.to_string(), model.to_string()),
+    };
+    eprintln!(
+        "{} Self-checking the answer with {}:{}...",
+        sym("🔍").blue(),
+        verifier_provider,
+        verifier_model
+    );
 
-        // Print the response
-        println!("{}", response);
+    let question = crate::core::self_check::verification_prompt(prompt, response);
+    let system_prompt = crate::core::self_check::verifier_system_prompt();
+    // The check is not part of the conversation, so it is not saved to the session
+    let (reply, _, _) = send_chat_request_with_validation(
+        verifier_client.as_ref().unwrap_or(client),
+        &verifier_model,
+        &question,
+        &[],
+        Some(&system_prompt),
+        None,
+        None,
+        &verifier_provider,
+        None,
+    )
+    .await?;
+
+    match crate::core::self_check::verified_remark(&reply) {
+        Some("") => eprintln!("{} Self-check found no issues", sym("✓").green()),
+        Some(remark) => eprintln!(
+            "{} Self-check found no issues: {}",
+            sym("✓").green(),
+            remark
+        ),
+        None => {
+            println!(
+                "\n{}\n{}",
+                "Self-check corrections:".yellow().bold(),
+                crate::core::redaction::redact(reply.trim())
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check the answer's code with `--validate-cmd` and ask the model to fix it while
+/// the command fails, up to the configured number of repairs
+#[allow(clippy::too_many_arguments)]
+async fn validate_response(
+    options: &ValidationOptions,
+    client: &LLMClient,
+    model: &str,
+    provider_name: &str,
+    prompt: &str,
+    mut response: String,
+    system_prompt: Option<&str>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+) -> Result<()> {
+    let mut history = Vec::new();
+    let mut question = prompt.to_string();
+
+    for attempt in 0..=options.max_repairs {
+        let workspace = tempfile::tempdir()?;
+        let files = validation::write_workspace(workspace.path(), &response)?;
+        if files.is_empty() {
+            eprintln!(
+                "{} No code blocks in the answer, skipping validation",
+                sym("⚠️").yellow()
+            );
+            return Ok(());
+        }
+
+        eprintln!(
+            "{} Validating {} file(s) with '{}'...",
+            sym("🔍").blue(),
+            files.len(),
+            options.command
+        );
+        let outcome = validation::run_command(&options.command, workspace.path()).await?;
+        if outcome.success {
+            eprintln!("{} Validation passed", sym("✓").green());
+            return Ok(());
+        }
+        debug_log!("Validation output:\n{}", outcome.output);
+
+        if attempt == options.max_repairs {
+            eprintln!("{}", outcome.output.trim_end());
+            anyhow::bail!(
+                "Validation still failing after {} repair attempt(s)",
+                options.max_repairs
+            );
+        }
+        eprintln!(
+            "{} Validation failed, asking the model to fix it (attempt {}/{})",
+            sym("✗").red(),
+            attempt + 1,
+            options.max_repairs
+        );
+
+        history.push(ChatEntry {
+            chat_id: String::new(),
+            model: model.to_string(),
+            question,
+            response,
+            timestamp: chrono::Utc::now(),
+            input_tokens: None,
+            output_tokens: None,
+            provider: Some(provider_name.to_string()),
+        });
+        question = validation::repair_prompt(&options.command, &files, &outcome.output);
+
+        let (repaired, input_tokens, output_tokens) = send_chat_request_with_validation(
+            client,
+            model,
+            &question,
+            &history,
+            system_prompt,
+            max_tokens,
+            temperature,
+            provider_name,
+            None,
+        )
+        .await?;
+        println!("{}", crate::core::redaction::redact(&repaired));
 
-        // Save to database
         if let Err(e) = save_to_database(
-            &prompt,
-            &response,
-            &provider_name,
-            &api_model_name,
+            &question,
+            &repaired,
+            provider_name,
+            model,
             input_tokens,
             output_tokens,
+            &log_details(provider_name, system_prompt),
         )
         .await
         {
             debug_log!("Failed to save to database: {}", e);
         }
+        response = repaired;
     }
 
     Ok(())
@@ -238,9 +1219,10 @@ pub async fn handle_with_piped_input(
     system_prompt: Option<String>,
     max_tokens: Option<String>,
     temperature: Option<String>,
-    _attachments: Vec<String>,
-    _images: Vec<String>,
-    _audio_files: Vec<String>,
+    attachments: Vec<String>,
+    images: Vec<String>,
+    audio_files: Vec<String>,
+    audio_mode: AudioMode,
     _tools: Option<String>,
     _vectordb: Option<String>,
     _use_search: Option<String>,
@@ -255,9 +1237,10 @@ pub async fn handle_with_piped_input(
         system_prompt,
         max_tokens,
         temperature,
-        vec![],
-        vec![],
-        vec![],
+        attachments,
+        images,
+        audio_files,
+        audio_mode,
         None,
         None,
         None,
@@ -266,11 +1249,61 @@ pub async fn handle_with_piped_input(
     .await
 }
 
-// Helper function to determine provider and model
+// Decide whether attached audio should be sent as native input_audio content parts
+async fn use_native_audio(mode: AudioMode, provider_name: &str, model_name: &str) -> bool {
+    match mode {
+        AudioMode::Native => true,
+        AudioMode::Transcribe => false,
+        AudioMode::Auto => {
+            let supports_audio =
+                crate::core::chat::model_supports_audio(provider_name, model_name).await;
+            debug_log!(
+                "Model '{}:{}' native audio support: {}",
+                provider_name,
+                model_name,
+                supports_audio
+            );
+            supports_audio
+        }
+    }
+}
+
+// Helper function to encode local audio files as input_audio content parts
+fn process_native_audio(audio_files: &[String]) -> Result<Vec<InputAudio>> {
+    audio_files
+        .iter()
+        .map(|audio_file| {
+            if audio_file.starts_with("http://") || audio_file.starts_with("https://") {
+                anyhow::bail!(
+                    "Native audio input requires a local file, got URL '{}'. Use --audio-mode transcribe instead",
+                    audio_file
+                );
+            }
+
+            let data_url =
+                crate::utils::audio::process_audio_file(std::path::Path::new(audio_file))?;
+            InputAudio::from_data_url(&data_url)
+                .ok_or_else(|| anyhow::anyhow!("Unsupported audio file '{}'", audio_file))
+        })
+        .collect()
+}
+
+// Helper function to determine provider and model, mapping models the provider no
+// longer lists to its current ID
 fn determine_provider_and_model(
     config: &Config,
     provider: Option<String>,
     model: Option<String>,
+) -> Result<(String, String)> {
+    let (provider, model) = determine_configured_model(config, provider, model)?;
+    let model = crate::models::model_aliases::resolve_prefixed(&model);
+    Ok((provider, model))
+}
+
+fn determine_configured_model(
+    config: &Config,
+    provider: Option<String>,
+    model: Option<String>,
 ) -> Result<(String, String)> {
     debug_log!(
         "Determining provider and model - provider: {:?}, model: {:?}",
@@ -334,8 +1367,14 @@ fn determine_provider_and_model(
         }
     }
 
-    // Use provided provider or default to "openai"
-    let provider_name = provider.unwrap_or_else(|| "openai".to_string());
+    // Use provided provider, the only provider with the model, or default to "openai"
+    let provider_name = provider
+        .or_else(|| {
+            model
+                .as_deref()
+                .and_then(|m| crate::utils::cli_utils::auto_detect_provider(config, m))
+        })
+        .unwrap_or_else(|| "openai".to_string());
 
     // Use provided model or default for the provider
     let model_name = model.unwrap_or_else(|| {
@@ -364,39 +1403,30 @@ fn determine_provider_and_model(
 }
 
 // Helper function to save to database
+#[allow(clippy::too_many_arguments)]
 async fn save_to_database(
     prompt: &str,
     response: &str,
-    _provider: &str,
+    provider: &str,
     model: &str,
     input_tokens: Option<i32>,
     output_tokens: Option<i32>,
+    details: &LogDetails,
 ) -> Result<()> {
     let db = Database::new()?;
 
-    // Get or create session ID
-    let session_id = match db.get_current_session_id()? {
-        Some(id) => {
-            debug_log!("Using existing session: {}", id);
-            id
-        }
-        None => {
-            // Generate a new session ID using UUID for consistency
-            let new_session_id = uuid::Uuid::new_v4().to_string();
-            db.set_current_session_id(&new_session_id)?;
-            debug_log!("Created new session: {}", new_session_id);
-            new_session_id
-        }
-    };
+    let session_id = db.current_or_new_session_id()?;
 
     // Save the entry with tokens
-    db.save_chat_entry_with_tokens(
+    db.save_chat_entry_with_details(
         &session_id,
+        provider,
         model,
         prompt,
         response,
         input_tokens,
         output_tokens,
+        details,
     )?;
 
     debug_log!("Saved chat entry to database with session: {}", session_id);
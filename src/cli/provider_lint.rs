@@ -0,0 +1,438 @@
+//! `lc providers lint`: check a provider's path templates, vars and request templates
+//! without sending a request, and show the URLs they build
+
+use crate::cli::doctor::{print_finding, Finding, Severity};
+use crate::config::{self, ProviderConfig};
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+
+/// Model put in the simulated URLs when the provider lists none
+const SAMPLE_MODEL: &str = "MODEL";
+
+/// Placeholders filled with the model name in chat, images, embeddings, speech and
+/// rerank paths
+const MODEL_PLACEHOLDERS: &[&str] = &["model", "model_name"];
+
+/// Endpoint templates checked, with the config field they are read from
+const TEMPLATE_ENDPOINTS: &[&str] = &["chat", "images", "embeddings", "models", "audio", "speech"];
+
+/// Handle `lc providers lint`
+pub fn handle(name: &str, model: Option<String>) -> Result<()> {
+    let config = config::Config::load()?;
+    let provider = config.get_provider(name)?;
+    let model = model
+        .or_else(|| provider.models.first().cloned())
+        .unwrap_or_else(|| SAMPLE_MODEL.to_string());
+
+    println!("\n{} {}", "Provider:".bold().blue(), name.bold());
+    println!("\n{} (model: {})", "URLs:".bold().blue(), model);
+    for (endpoint, url) in urls(provider, &model) {
+        println!("  {:<11} {}", endpoint, url);
+    }
+
+    let findings = lint(provider, &model);
+    if !findings.is_empty() {
+        println!("\n{}", "Findings:".bold().blue());
+    }
+    for finding in &findings {
+        print_finding(name, finding);
+    }
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    println!();
+    if errors > 0 {
+        anyhow::bail!("{} error(s), {} warning(s)", errors, warnings);
+    }
+    if warnings > 0 {
+        println!("{} No errors, {} warning(s)", sym("✓").green(), warnings);
+    } else {
+        println!("{} No problems found", sym("✓").green());
+    }
+    Ok(())
+}
+
+/// The URLs requests for `model` would be sent to, built the way requests build them
+fn urls(provider: &ProviderConfig, model: &str) -> Vec<(&'static str, String)> {
+    let mut urls = vec![
+        ("models", provider.get_models_url()),
+        ("chat", provider.get_chat_url(model)),
+        ("embeddings", provider.get_embeddings_url(model)),
+    ];
+    if provider.images_path.is_some() {
+        urls.push(("images", provider.get_images_url(model)));
+    }
+    if provider.speech_path.is_some() {
+        urls.push(("speech", provider.get_speech_url(model)));
+    }
+    if provider.rerank_path.is_some() {
+        urls.push(("rerank", provider.get_rerank_url(model)));
+    }
+    urls
+}
+
+/// Problems with a provider's paths and templates, found without a request
+pub fn lint(provider: &ProviderConfig, model: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let paths = [
+        ("endpoint", Some(&provider.endpoint)),
+        ("models path", Some(&provider.models_path)),
+        ("chat path", Some(&provider.chat_path)),
+        ("images path", provider.images_path.as_ref()),
+        ("embeddings path", provider.embeddings_path.as_ref()),
+        ("speech path", provider.speech_path.as_ref()),
+        ("rerank path", provider.rerank_path.as_ref()),
+    ];
+    for (field, path) in paths {
+        if let Some(path) = path {
+            findings.extend(check_braces(field, path));
+        }
+    }
+
+    for (endpoint, url) in urls(provider, model) {
+        let left = placeholders(&url);
+        for placeholder in &left {
+            findings.push(unresolved(provider, endpoint, placeholder));
+        }
+        if !left.is_empty() {
+            continue;
+        }
+        match reqwest::Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            _ => findings.push(Finding::error(format!(
+                "{} URL '{}' is not a valid http(s) URL",
+                endpoint, url
+            ))),
+        }
+    }
+
+    for (field, path) in [
+        ("chat path", Some(&provider.chat_path)),
+        ("embeddings path", provider.embeddings_path.as_ref()),
+        ("images path", provider.images_path.as_ref()),
+        ("speech path", provider.speech_path.as_ref()),
+    ] {
+        if path.is_some_and(|path| path.starts_with("http://")) {
+            findings.push(Finding::error(format!(
+                "{} is a plain http:// URL, which is appended to the endpoint; only https:// paths are used as full URLs",
+                field
+            )));
+        }
+    }
+
+    findings.extend(check_model_slots(provider, model));
+    findings.extend(check_templates(provider));
+    findings
+}
+
+/// Braces that open and close placeholders in pairs
+fn check_braces(field: &str, path: &str) -> Option<Finding> {
+    let mut depth = 0;
+    for c in path.chars() {
+        match c {
+            '{' if depth > 0 => break,
+            '{' => depth += 1,
+            '}' if depth == 0 => {
+                depth = -1;
+                break;
+            }
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    (depth != 0).then(|| {
+        Finding::error(format!(
+            "{} '{}' has unbalanced braces; placeholders are written {{name}}",
+            field, path
+        ))
+    })
+}
+
+/// Placeholders (`{name}`) left in a URL
+fn placeholders(url: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = url;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + end];
+        if !name.is_empty() && !name.contains('{') && !found.iter().any(|f| f == name) {
+            found.push(name.to_string());
+        }
+        rest = &rest[start + end + 1..];
+    }
+    found
+}
+
+/// Why a placeholder was left in an endpoint's URL
+fn unresolved(provider: &ProviderConfig, endpoint: &str, placeholder: &str) -> Finding {
+    if endpoint == "models" && MODEL_PLACEHOLDERS.contains(&placeholder) {
+        return Finding::error(format!(
+            "models URL uses {{{}}}, but it lists every model and has none to fill in",
+            placeholder
+        ));
+    }
+    if provider.vars.contains_key(placeholder) {
+        let place = if provider.endpoint.contains(&format!("{{{}}}", placeholder)) {
+            "the endpoint"
+        } else {
+            "the models path"
+        };
+        return Finding::error(format!(
+            "{} URL uses {{{}}}, but {} is used as written, without vars",
+            endpoint, placeholder, place
+        ));
+    }
+    let mut message = format!(
+        "{} URL uses {{{}}}, which is not a defined var",
+        endpoint, placeholder
+    );
+    let known = provider
+        .vars
+        .keys()
+        .map(String::as_str)
+        .chain(MODEL_PLACEHOLDERS.iter().copied());
+    match closest(placeholder, known) {
+        Some(name) => message.push_str(&format!(" (did you mean {{{}}}?)", name)),
+        None => message.push_str(&format!(
+            " (set it with 'lc providers vars <provider> set {} <value>')",
+            placeholder
+        )),
+    }
+    Finding::error(message)
+}
+
+/// The known name within two edits of a misspelled one
+fn closest<'a>(name: &str, known: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    known
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn has_model_slot(path: &str) -> bool {
+    MODEL_PLACEHOLDERS
+        .iter()
+        .any(|name| path.contains(&format!("{{{}}}", name)))
+}
+
+/// Whether the request template of an endpoint sends the model in the body. Without a
+/// template, the standard request body always does.
+fn body_names_model(provider: &ProviderConfig, endpoint: &str, model: &str) -> bool {
+    provider
+        .get_endpoint_template_config(endpoint, model)
+        .and_then(|template| template.request.as_deref())
+        .is_none_or(|request| request.contains("model"))
+}
+
+/// Paths that send every model to the same place: no `{model}` slot where the URL has
+/// to name the model, or a model written into the path
+fn check_model_slots(provider: &ProviderConfig, model: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let chat_has_slot = has_model_slot(&provider.chat_path);
+    if !chat_has_slot && !body_names_model(provider, "chat", model) {
+        findings.push(Finding::error(
+            "the model is in neither the chat path (no {model} slot) nor the chat request template, so every model reaches the same endpoint",
+        ));
+    }
+
+    for (field, path) in [
+        ("chat path", Some(&provider.chat_path)),
+        ("embeddings path", provider.embeddings_path.as_ref()),
+        ("images path", provider.images_path.as_ref()),
+    ] {
+        let Some(path) = path else {
+            continue;
+        };
+        if let Some(fixed) = fixed_model_segment(path) {
+            findings.push(Finding::warning(format!(
+                "{} names a fixed model ('{}'); use {{model}} so -m chooses it",
+                field, fixed
+            )));
+        }
+    }
+
+    if chat_has_slot
+        && provider.embeddings_path.is_none()
+        && provider.embeddings_templates.is_none()
+    {
+        findings.push(Finding::warning(
+            "chat puts the model in the URL, but embeddings use the default /embeddings path; set an embeddings path with a {model} slot if the provider embeds ('lc providers paths <provider> add -e <path>')",
+        ));
+    } else if let Some(path) = &provider.embeddings_path {
+        if chat_has_slot
+            && !has_model_slot(path)
+            && !body_names_model(provider, "embeddings", model)
+        {
+            findings.push(Finding::error(
+                "the model is in neither the embeddings path (no {model} slot) nor the embeddings request template, so every model reaches the same endpoint",
+            ));
+        }
+    }
+    findings
+}
+
+/// A `models/<name>` or `deployments/<name>` segment written out instead of `{model}`
+fn fixed_model_segment(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').collect();
+    segments.windows(2).find_map(|pair| {
+        let name = pair[1].split(':').next().unwrap_or(pair[1]);
+        (matches!(pair[0], "models" | "deployments") && !name.is_empty() && !name.contains('{'))
+            .then(|| format!("{}/{}", pair[0], name))
+    })
+}
+
+/// Request and response templates that do not compile, and model patterns that are not
+/// valid regexes
+fn check_templates(provider: &ProviderConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let Ok(mut processor) = crate::template_processor::TemplateProcessor::new() else {
+        return findings;
+    };
+    for endpoint in TEMPLATE_ENDPOINTS {
+        let templates = match *endpoint {
+            "chat" => &provider.chat_templates,
+            "images" => &provider.images_templates,
+            "embeddings" => &provider.embeddings_templates,
+            "models" => &provider.models_templates,
+            "audio" => &provider.audio_templates,
+            _ => &provider.speech_templates,
+        };
+        let Some(templates) = templates else {
+            continue;
+        };
+        let mut patterns: Vec<_> = templates.iter().collect();
+        patterns.sort_by(|a, b| a.0.cmp(b.0));
+        for (pattern, template) in patterns {
+            let scope = if pattern.is_empty() {
+                format!("{} template", endpoint)
+            } else {
+                format!("{} template for '{}'", endpoint, pattern)
+            };
+            if !pattern.is_empty() && crate::utils::regex_cache::get_regex(pattern).is_err() {
+                findings.push(Finding::error(format!(
+                    "{}: the model pattern is not a valid regex",
+                    scope
+                )));
+            }
+            for (kind, source) in [
+                ("request", &template.request),
+                ("response", &template.response),
+                ("stream_response", &template.stream_response),
+            ] {
+                let Some(source) = source else {
+                    continue;
+                };
+                if let Err(e) = processor.register_template(source) {
+                    findings.push(Finding::error(format!(
+                        "{}: {} does not compile: {}",
+                        scope,
+                        kind,
+                        e.root_cause().to_string().trim()
+                    )));
+                }
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(toml: &str) -> ProviderConfig {
+        toml::from_str(&format!("models = []\n{}", toml)).unwrap()
+    }
+
+    fn messages(provider: &ProviderConfig) -> Vec<String> {
+        lint(provider, "gemini-pro")
+            .into_iter()
+            .map(|f| f.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        let openai = provider("endpoint = \"https://api.openai.com/v1\"");
+        assert_eq!(messages(&openai), Vec::<String>::new());
+
+        let vertex = provider(
+            r#"endpoint = "https://{location}-aiplatform.googleapis.com"
+               chat_path = "https://{locaton}-aiplatform.googleapis.com/v1/projects/{project}/models/{model}:generateContent"
+               models_path = "/v1/{model}/models"
+               embeddings_path = "/v1/projects/{project}/models/text-embedding-004:predict"
+               [vars]
+               location = "us-central1"
+               project = "p"
+               [chat_templates.""]
+               request = "{{ messages | json }"
+            "#,
+        );
+        let found = messages(&vertex);
+        assert!(found
+            .iter()
+            .any(|m| m.contains("{locaton}") && m.contains("did you mean {location}")));
+        assert!(found
+            .iter()
+            .any(|m| m.starts_with("models URL uses {model}")));
+        assert!(found
+            .iter()
+            .any(|m| m.starts_with("models URL uses {location}") && m.contains("without vars")));
+        assert!(found
+            .iter()
+            .any(|m| m.contains("'models/text-embedding-004'")));
+        assert!(found
+            .iter()
+            .any(|m| m.starts_with("chat template: request does not compile")));
+        assert!(!found.iter().any(|m| m.contains("{project}")));
+
+        let fixed = provider(
+            r#"endpoint = "http://localhost:8080"
+               chat_path = "/generate}"
+               [chat_templates.""]
+               request = "{{ messages | json }}"
+            "#,
+        );
+        let found = messages(&fixed);
+        assert!(found.iter().any(|m| m.contains("unbalanced braces")));
+        assert!(found
+            .iter()
+            .any(|m| m.contains("every model reaches the same endpoint")));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(placeholders("https://{a}.x/{b}/{a}/{"), vec!["a", "b"]);
+        assert_eq!(
+            closest("modle", ["model", "project"].into_iter()),
+            Some("model")
+        );
+        assert_eq!(closest("region", ["model"].into_iter()), None);
+        assert_eq!(fixed_model_segment("/v1/models/{model}:chat"), None);
+        assert_eq!(
+            fixed_model_segment("/openai/deployments/gpt4/chat"),
+            Some("deployments/gpt4".to_string())
+        );
+    }
+}
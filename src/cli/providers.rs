@@ -1,7 +1,12 @@
 //! Provider management commands
 
-use crate::cli::{HeaderCommands, ProviderCommands, ProviderPathCommands, ProviderVarsCommands};
+use crate::cli::{
+    HeaderCommands, ProviderCommands, ProviderGroupCommands, ProviderPathCommands,
+    ProviderVarsCommands,
+};
+use crate::provider_groups::{GroupBackend, GroupsState, ProviderGroup, ProviderGroupsConfig};
 use crate::provider_installer::{AuthType, ProviderInstaller};
+use crate::utils::terminal::sym;
 use crate::{chat, config, debug_log};
 use anyhow::Result;
 use colored::Colorize;
@@ -9,9 +14,16 @@ use colored::Colorize;
 /// Handle provider-related commands
 pub async fn handle(command: ProviderCommands) -> Result<()> {
     match command {
-        ProviderCommands::Install { name, force } => {
+        ProviderCommands::Install {
+            name,
+            force,
+            skip_setup,
+        } => {
             let installer = ProviderInstaller::new()?;
-            installer.install_provider(&name, force).await?;
+            let metadata = installer.install_provider(&name, force).await?;
+            if !skip_setup {
+                installer.setup_provider(&name, &metadata).await?;
+            }
         }
         ProviderCommands::Upgrade { name } => {
             let installer = ProviderInstaller::new()?;
@@ -45,10 +57,10 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
 
                 displayed_count += 1;
 
-                print!("  {} {} - {}", "•".blue(), id.bold(), metadata.name);
+                print!("  {} {} - {}", sym("•").blue(), id.bold(), metadata.name);
 
                 if metadata.official {
-                    print!(" {}", "✓ official".green());
+                    print!(" {}", sym("✓ official").green());
                 }
 
                 if !metadata.tags.is_empty() {
@@ -80,20 +92,54 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
             } else {
                 println!(
                     "\n{} Use 'lc providers install <name>' to install a provider",
-                    "💡".yellow()
+                    sym("💡").yellow()
                 );
             }
         }
         ProviderCommands::Add {
             name,
             url,
+            builtin,
             models_path,
             chat_path,
         } => {
             let mut config = config::Config::load()?;
+            if builtin {
+                add_builtin(&mut config, &name)?;
+                return Ok(());
+            }
+            let url = url.unwrap_or_default();
             config.add_provider_with_paths(name.clone(), url, models_path, chat_path)?;
             config.save()?;
-            println!("{} Provider '{}' added successfully", "✓".green(), name);
+            println!(
+                "{} Provider '{}' added successfully",
+                sym("✓").green(),
+                name
+            );
+        }
+        ProviderCommands::Clone {
+            source,
+            name,
+            endpoint,
+            with_key,
+        } => {
+            let mut config = config::Config::load()?;
+            config.clone_provider(&source, name.clone(), endpoint)?;
+            let copied_key =
+                with_key && crate::keys::KeysConfig::load()?.copy_provider(&source, &name)?;
+            println!(
+                "{} Provider '{}' cloned from '{}'",
+                sym("✓").green(),
+                name,
+                source
+            );
+            if !copied_key {
+                println!(
+                    "{} Set its API key with '{}'",
+                    sym("💡").yellow(),
+                    format!("lc keys add {}", name).bold()
+                );
+            }
         }
         ProviderCommands::Update { name, url } => {
             let mut config = config::Config::load()?;
@@ -102,16 +148,82 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
             }
             config.add_provider(name.clone(), url)?; // add_provider also updates
             config.save()?;
-            println!("{} Provider '{}' updated successfully", "✓".green(), name);
+            println!(
+                "{} Provider '{}' updated successfully",
+                sym("✓").green(),
+                name
+            );
         }
         ProviderCommands::Remove { name } => {
             let mut config = config::Config::load()?;
             if !config.has_provider(&name) {
                 anyhow::bail!("Provider '{}' not found", name);
             }
+            // Move the provider file to the trash so 'lc undo' can bring it back
+            crate::trash::Trash::new()?.move_files(
+                &format!("providers remove {}", name),
+                &[config::Config::provider_file_path(&name)?],
+            )?;
             config.providers.remove(&name);
             config.save()?;
-            println!("{} Provider '{}' removed successfully", "✓".green(), name);
+            println!(
+                "{} Provider '{}' removed successfully",
+                sym("✓").green(),
+                name
+            );
+            println!("{}", "Run 'lc undo' to restore it.".dimmed());
+        }
+        ProviderCommands::Status { name } => {
+            let config = config::Config::load()?;
+            let names: Vec<String> = match name {
+                Some(name) => {
+                    if !config.has_provider(&name) {
+                        anyhow::bail!("Provider '{}' not found", name);
+                    }
+                    vec![name]
+                }
+                None => {
+                    let mut names: Vec<String> = config.providers.keys().cloned().collect();
+                    names.sort();
+                    names
+                }
+            };
+            if names.is_empty() {
+                println!("No providers configured.");
+                return Ok(());
+            }
+
+            let store = crate::core::rate_limits::RateLimitStore::load();
+            let now = chrono::Utc::now();
+            println!("\n{}", "Provider Rate Limits:".bold().blue());
+            for name in names {
+                match store.providers.get(&name) {
+                    Some(limits) => {
+                        let summary = if limits.exhausted() {
+                            limits.summary().red().to_string()
+                        } else {
+                            limits.summary()
+                        };
+                        println!(
+                            "  {} {} {}",
+                            sym("•").blue(),
+                            name.bold(),
+                            format!(
+                                "({})",
+                                crate::utils::time_display::relative(&limits.captured_at, &now)
+                            )
+                            .dimmed()
+                        );
+                        println!("    {}", summary);
+                    }
+                    None => println!(
+                        "  {} {} {}",
+                        sym("•").blue(),
+                        name.bold(),
+                        "No rate-limit headers recorded yet".dimmed()
+                    ),
+                }
+            }
         }
         ProviderCommands::List => {
             let config = config::Config::load()?;
@@ -120,12 +232,12 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                 println!();
                 println!(
                     "{} Use '{}' to install a provider from the registry",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc providers install <name>".bold()
                 );
                 println!(
                     "{} Or '{}' to add a custom one",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc providers add <name> <url>".bold()
                 );
                 return Ok(());
@@ -144,13 +256,32 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
             for (name, provider_config) in sorted_providers {
                 // Check if provider has authentication in keys.toml
                 let has_key = keys.has_auth(name);
-                let key_status = if has_key { "✓".green() } else { "✗".red() };
+                let key_status = if crate::core::mock_provider::is_mock(&provider_config.endpoint) {
+                    "not needed".dimmed()
+                } else if has_key {
+                    sym("✓").green()
+                } else {
+                    sym("✗").red()
+                };
+                let mut scope = Vec::new();
+                if let Some(organization) = &provider_config.organization {
+                    scope.push(format!("org: {}", organization));
+                }
+                if let Some(project) = &provider_config.project {
+                    scope.push(format!("project: {}", project));
+                }
+                let scope = if scope.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", scope.join(", ")).dimmed().to_string()
+                };
                 println!(
-                    "  {} {} - {} (API Key: {})",
-                    "•".blue(),
+                    "  {} {} - {} (API Key: {}){}",
+                    sym("•").blue(),
                     name.bold(),
                     provider_config.endpoint,
-                    key_status
+                    key_status,
+                    scope
                 );
             }
         }
@@ -219,7 +350,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                                         "Models:".bold()
                                     );
                                     for model in models {
-                                        println!("  • {}", model.id);
+                                        println!("  {} {}", sym("•"), model.id);
                                     }
                                 }
                                 Err(e2) => {
@@ -257,7 +388,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                     config.save()?;
                     println!(
                         "{} Header '{}' added to provider '{}'",
-                        "✓".green(),
+                        sym("✓").green(),
                         name,
                         provider
                     );
@@ -267,7 +398,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                     config.save()?;
                     println!(
                         "{} Header '{}' removed from provider '{}'",
-                        "✓".green(),
+                        sym("✓").green(),
                         name,
                         provider
                     );
@@ -279,7 +410,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         println!();
                         println!(
                             "{} Use '{}' to add one",
-                            "💡".yellow(),
+                            sym("💡").yellow(),
                             format!("lc providers headers {} add <name> <value>", provider).bold()
                         );
                     } else {
@@ -289,12 +420,68 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                             provider
                         );
                         for (name, value) in headers {
-                            println!("  {} {}: {}", "•".blue(), name.bold(), value);
+                            println!("  {} {}: {}", sym("•").blue(), name.bold(), value);
                         }
                     }
                 }
             }
         }
+        ProviderCommands::Org {
+            provider,
+            organization,
+            project,
+            clear,
+        } => {
+            let mut config = config::Config::load()?;
+            let provider_config = config
+                .providers
+                .get_mut(&provider)
+                .ok_or_else(|| anyhow::anyhow!("Provider '{}' not found", provider))?;
+
+            if clear {
+                provider_config.organization = None;
+                provider_config.project = None;
+            } else if organization.is_none() && project.is_none() {
+                println!(
+                    "Organization: {}",
+                    provider_config
+                        .organization
+                        .as_deref()
+                        .unwrap_or("(not set)")
+                );
+                println!(
+                    "Project: {}",
+                    provider_config.project.as_deref().unwrap_or("(not set)")
+                );
+                return Ok(());
+            } else {
+                // An empty value removes just that field
+                if let Some(organization) = organization {
+                    provider_config.organization =
+                        Some(organization).filter(|value| !value.is_empty());
+                }
+                if let Some(project) = project {
+                    provider_config.project = Some(project).filter(|value| !value.is_empty());
+                }
+            }
+            let is_azure = provider_config.is_azure();
+            let has_scope =
+                provider_config.organization.is_some() || provider_config.project.is_some();
+            config.save()?;
+
+            println!(
+                "{} Organization and project updated for provider '{}'",
+                sym("✓").green(),
+                provider
+            );
+            if is_azure && has_scope {
+                println!(
+                    "{} '{}' is an Azure OpenAI endpoint, which is scoped by resource and deployment; the organization and project are not sent",
+                    sym("⚠️").yellow(),
+                    provider
+                );
+            }
+        }
         ProviderCommands::TokenUrl { provider, url } => {
             let mut config = config::Config::load()?;
 
@@ -304,7 +491,11 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
 
             config.set_token_url(provider.clone(), url.clone())?;
             config.save()?;
-            println!("{} Token URL set for provider '{}'", "✓".green(), provider);
+            println!(
+                "{} Token URL set for provider '{}'",
+                sym("✓").green(),
+                provider
+            );
         }
         ProviderCommands::Vars { provider, command } => {
             let mut config = config::Config::load()?;
@@ -317,7 +508,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                     config.save()?;
                     println!(
                         "{} Set var '{}'='{}' for provider '{}'",
-                        "✓".green(),
+                        sym("✓").green(),
                         key,
                         value,
                         provider
@@ -336,7 +527,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         println!();
                         println!(
                             "{} Use '{}' to set one",
-                            "💡".yellow(),
+                            sym("💡").yellow(),
                             format!("lc providers vars {} set <key> <value>", provider).bold()
                         );
                     } else {
@@ -346,7 +537,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                             provider
                         );
                         for (k, v) in vars {
-                            println!("  {} {} = {}", "•".blue(), k.bold(), v);
+                            println!("  {} {} = {}", sym("•").blue(), k.bold(), v);
                         }
                     }
                 }
@@ -363,13 +554,14 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                     chat_path,
                     images_path,
                     embeddings_path,
+                    rerank_path,
                 } => {
                     let mut updated = false;
                     if let Some(path) = models_path.as_deref() {
                         config.set_provider_models_path(&provider, path)?;
                         println!(
                             "{} Models path set to '{}' for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
                             path,
                             provider
                         );
@@ -379,7 +571,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         config.set_provider_chat_path(&provider, path)?;
                         println!(
                             "{} Chat path set to '{}' for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
                             path,
                             provider
                         );
@@ -389,7 +581,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         config.set_provider_images_path(&provider, path)?;
                         println!(
                             "{} Images path set to '{}' for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
                             path,
                             provider
                         );
@@ -399,14 +591,26 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         config.set_provider_embeddings_path(&provider, path)?;
                         println!(
                             "{} Embeddings path set to '{}' for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
+                            path,
+                            provider
+                        );
+                        updated = true;
+                    }
+                    if let Some(path) = rerank_path.as_deref() {
+                        config.set_provider_rerank_path(&provider, path)?;
+                        println!(
+                            "{} Rerank path set to '{}' for provider '{}'",
+                            sym("✓").green(),
                             path,
                             provider
                         );
                         updated = true;
                     }
                     if !updated {
-                        anyhow::bail!("No paths specified. Use -m, -c, -i, or -e to set paths.");
+                        anyhow::bail!(
+                            "No paths specified. Use -m, -c, -i, -e, or -r to set paths."
+                        );
                     }
                     config.save()?;
                 }
@@ -415,13 +619,14 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                     chat,
                     images,
                     embeddings,
+                    rerank,
                 } => {
                     let mut updated = false;
                     if models {
                         config.reset_provider_models_path(&provider)?;
                         println!(
                             "{} Models path reset to default for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
                             provider
                         );
                         updated = true;
@@ -430,7 +635,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         config.reset_provider_chat_path(&provider)?;
                         println!(
                             "{} Chat path reset to default for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
                             provider
                         );
                         updated = true;
@@ -439,7 +644,7 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         config.reset_provider_images_path(&provider)?;
                         println!(
                             "{} Images path reset to default for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
                             provider
                         );
                         updated = true;
@@ -448,13 +653,22 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         config.reset_provider_embeddings_path(&provider)?;
                         println!(
                             "{} Embeddings path reset to default for provider '{}'",
-                            "✓".green(),
+                            sym("✓").green(),
+                            provider
+                        );
+                        updated = true;
+                    }
+                    if rerank {
+                        config.reset_provider_rerank_path(&provider)?;
+                        println!(
+                            "{} Rerank path reset to default for provider '{}'",
+                            sym("✓").green(),
                             provider
                         );
                         updated = true;
                     }
                     if !updated {
-                        anyhow::bail!("No paths specified for deletion. Use -m, -c, -i, or -e to delete paths.");
+                        anyhow::bail!("No paths specified for deletion. Use -m, -c, -i, -e, or -r to delete paths.");
                     }
                     config.save()?;
                 }
@@ -465,21 +679,254 @@ pub async fn handle(command: ProviderCommands) -> Result<()> {
                         "Paths:".bold().blue(),
                         provider
                     );
-                    println!("  {} Models: {}", "•".blue(), paths.models_path.bold());
-                    println!("  {} Chat: {}", "•".blue(), paths.chat_path.bold());
+                    println!("  {} Models: {}", sym("•").blue(), paths.models_path.bold());
+                    println!("  {} Chat: {}", sym("•").blue(), paths.chat_path.bold());
                     if let Some(ref images_path) = paths.images_path {
-                        println!("  {} Images: {}", "•".blue(), images_path.bold());
+                        println!("  {} Images: {}", sym("•").blue(), images_path.bold());
                     } else {
-                        println!("  {} Images: {}", "•".blue(), "not set".dimmed());
+                        println!("  {} Images: {}", sym("•").blue(), "not set".dimmed());
                     }
                     if let Some(ref embeddings_path) = paths.embeddings_path {
-                        println!("  {} Embeddings: {}", "•".blue(), embeddings_path.bold());
+                        println!(
+                            "  {} Embeddings: {}",
+                            sym("•").blue(),
+                            embeddings_path.bold()
+                        );
+                    } else {
+                        println!("  {} Embeddings: {}", sym("•").blue(), "not set".dimmed());
+                    }
+                    if let Some(ref rerank_path) = paths.rerank_path {
+                        println!("  {} Rerank: {}", sym("•").blue(), rerank_path.bold());
                     } else {
-                        println!("  {} Embeddings: {}", "•".blue(), "not set".dimmed());
+                        println!("  {} Rerank: {}", sym("•").blue(), "not set".dimmed());
                     }
                 }
             }
         }
+        ProviderCommands::Lint { name, sample_model } => {
+            crate::cli::provider_lint::handle(&name, sample_model)?
+        }
+        ProviderCommands::Group { command } => handle_group(command)?,
+    }
+    Ok(())
+}
+
+/// `lc providers add <name> --builtin`
+fn add_builtin(config: &mut config::Config, name: &str) -> Result<()> {
+    use crate::core::mock_provider;
+
+    if name != "mock" {
+        anyhow::bail!("No built-in provider '{}'. Built-in providers: mock", name);
+    }
+    if config.has_provider(name) {
+        anyhow::bail!(
+            "Provider '{}' already exists. Remove it first with 'lc providers remove {}'",
+            name,
+            name
+        );
+    }
+    config.add_provider_with_paths(
+        name.to_string(),
+        mock_provider::ENDPOINT.to_string(),
+        None,
+        None,
+    )?;
+    if let Some(provider) = config.providers.get_mut(name) {
+        provider.models = mock_provider::MODELS
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+    }
+    config.save()?;
+
+    println!(
+        "{} Provider '{}' added: answers requests offline, no API key needed",
+        sym("✓").green(),
+        name
+    );
+    println!(
+        "{} Try '{}', and shape its answers with '{}'",
+        sym("💡").yellow(),
+        format!("lc -p {} -m {} \"hello\"", name, mock_provider::MODELS[0]).bold(),
+        format!("lc providers vars {} set response \"...\"", name).bold()
+    );
+    Ok(())
+}
+
+fn handle_group(command: ProviderGroupCommands) -> Result<()> {
+    match command {
+        ProviderGroupCommands::Add {
+            name,
+            backends,
+            strategy,
+            max_failures,
+            eject_secs,
+        } => {
+            let config = config::Config::load()?;
+            if config.has_provider(&name) {
+                anyhow::bail!(
+                    "'{}' is already a provider; choose a different group name",
+                    name
+                );
+            }
+
+            let backends = backends
+                .iter()
+                .map(|spec| GroupBackend::parse(spec))
+                .collect::<Result<Vec<_>>>()?;
+            for backend in &backends {
+                if !config.has_provider(&backend.provider) {
+                    anyhow::bail!("Provider '{}' not found", backend.provider);
+                }
+            }
+
+            let mut groups = ProviderGroupsConfig::load()?;
+            let replaced = groups
+                .groups
+                .insert(
+                    name.clone(),
+                    ProviderGroup {
+                        strategy: strategy.parse()?,
+                        max_failures,
+                        eject_secs,
+                        backends,
+                    },
+                )
+                .is_some();
+            groups.save()?;
+
+            let action = if replaced { "updated" } else { "created" };
+            println!("{} Provider group '{}' {}", sym("✓").green(), name, action);
+            println!(
+                "{}",
+                format!("Use it like a provider: lc -p {} -m <model> ...", name).dimmed()
+            );
+        }
+        ProviderGroupCommands::Remove { name } => {
+            let mut groups = ProviderGroupsConfig::load()?;
+            if groups.groups.remove(&name).is_none() {
+                anyhow::bail!("Provider group '{}' not found", name);
+            }
+            groups.save()?;
+
+            let mut state = GroupsState::load();
+            if state.groups.remove(&name).is_some() {
+                state.save();
+            }
+            println!("{} Provider group '{}' removed", sym("✓").green(), name);
+        }
+        ProviderGroupCommands::List => {
+            let groups = ProviderGroupsConfig::load()?;
+            if groups.groups.is_empty() {
+                println!("No provider groups configured.");
+                println!();
+                println!(
+                    "{} Use '{}' to create one",
+                    sym("💡").yellow(),
+                    "lc providers group add <name> -b <provider> -b <provider>".bold()
+                );
+                return Ok(());
+            }
+
+            println!("\n{}", "Provider Groups:".bold().blue());
+            let mut names: Vec<_> = groups.groups.keys().collect();
+            names.sort();
+            for name in names {
+                let group = &groups.groups[name];
+                let backends: Vec<String> = group
+                    .backends
+                    .iter()
+                    .map(|b| match group.strategy {
+                        crate::provider_groups::Strategy::Weighted => {
+                            format!("{}={}", b.provider, b.weight)
+                        }
+                        crate::provider_groups::Strategy::RoundRobin => b.provider.clone(),
+                    })
+                    .collect();
+                println!(
+                    "  {} {} ({}) - {}",
+                    sym("•").blue(),
+                    name.bold(),
+                    group.strategy,
+                    backends.join(", ")
+                );
+            }
+        }
+        ProviderGroupCommands::Status { name } => {
+            let groups = ProviderGroupsConfig::load()?;
+            let state = GroupsState::load();
+            let mut names: Vec<&String> = match &name {
+                Some(name) => {
+                    let (name, _) = groups
+                        .groups
+                        .get_key_value(name)
+                        .ok_or_else(|| anyhow::anyhow!("Provider group '{}' not found", name))?;
+                    vec![name]
+                }
+                None => groups.groups.keys().collect(),
+            };
+            names.sort();
+            if names.is_empty() {
+                println!("No provider groups configured.");
+                return Ok(());
+            }
+
+            let now = chrono::Utc::now();
+            for name in names {
+                let group = &groups.groups[name];
+                let health = state.groups.get(name);
+                println!(
+                    "\n{} {} ({}, eject after {} failures for {}s)",
+                    "Group:".bold().blue(),
+                    name.bold(),
+                    group.strategy,
+                    group.max_failures,
+                    group.eject_secs
+                );
+                for backend in &group.backends {
+                    let backend_health = health
+                        .and_then(|h| h.backends.get(&backend.provider))
+                        .cloned()
+                        .unwrap_or_default();
+                    let status = match backend_health.ejected_until {
+                        Some(until) if until > now => {
+                            format!("ejected for {}s", (until - now).num_seconds().max(1)).red()
+                        }
+                        _ if backend_health.consecutive_failures > 0 => format!(
+                            "degraded ({} recent failure(s))",
+                            backend_health.consecutive_failures
+                        )
+                        .yellow(),
+                        _ => "healthy".green(),
+                    };
+                    println!(
+                        "  {} {} (weight {}) - {} {} {} request(s), {} failure(s)",
+                        sym("•").blue(),
+                        backend.provider.bold(),
+                        backend.weight,
+                        status,
+                        sym("·"),
+                        backend_health.requests,
+                        backend_health.failures
+                    );
+                }
+            }
+        }
+        ProviderGroupCommands::Reset { name } => {
+            let groups = ProviderGroupsConfig::load()?;
+            if groups.get(&name).is_none() {
+                anyhow::bail!("Provider group '{}' not found", name);
+            }
+            crate::lock::ensure_writable("reset provider group health")?;
+            let mut state = GroupsState::load();
+            state.groups.remove(&name);
+            state.save();
+            println!(
+                "{} Health of provider group '{}' reset",
+                sym("✓").green(),
+                name
+            );
+        }
     }
     Ok(())
 }
@@ -503,19 +950,19 @@ fn display_provider_models(models: &[crate::model_metadata::ModelMetadata]) -> R
         // Build capability indicators
         let mut capabilities = Vec::new();
         if model.supports_tools || model.supports_function_calling {
-            capabilities.push("🔧 tools".blue());
+            capabilities.push(sym("🔧 tools").blue());
         }
         if model.supports_vision {
-            capabilities.push("👁 vision".magenta());
+            capabilities.push(sym("👁 vision").magenta());
         }
         if model.supports_audio {
-            capabilities.push("🔊 audio".yellow());
+            capabilities.push(sym("🔊 audio").yellow());
         }
         if model.supports_reasoning {
-            capabilities.push("🧠 reasoning".cyan());
+            capabilities.push(sym("🧠 reasoning").cyan());
         }
         if model.supports_code {
-            capabilities.push("💻 code".green());
+            capabilities.push(sym("💻 code").green());
         }
 
         // Build context and pricing info
@@ -554,7 +1001,7 @@ fn display_provider_models(models: &[crate::model_metadata::ModelMetadata]) -> R
             model.id.clone()
         };
 
-        print!("  {} {}", "•".blue(), model_display.bold());
+        print!("  {} {}", sym("•").blue(), model_display.bold());
 
         if !capabilities.is_empty() {
             let capability_strings: Vec<String> =
@@ -1,9 +1,11 @@
 //! Proxy server commands
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 
 /// Handle proxy-related commands
+#[allow(clippy::too_many_arguments)]
 pub async fn handle(
     port: Option<u16>,
     host: Option<String>,
@@ -11,6 +13,8 @@ pub async fn handle(
     model: Option<String>,
     api_key: Option<String>,
     generate_key: bool,
+    admin_key: Option<String>,
+    generate_admin_key: bool,
 ) -> Result<()> {
     // Defaults
     let port_val = port.unwrap_or(8080);
@@ -21,7 +25,7 @@ pub async fn handle(
         let generated_key = crate::services::proxy::generate_api_key();
         println!(
             "{} Generated API key: {}",
-            "🔑".green(),
+            sym("🔑").green(),
             generated_key.bold()
         );
         Some(generated_key)
@@ -29,6 +33,21 @@ pub async fn handle(
         api_key
     };
 
+    let admin_key = if generate_admin_key {
+        let generated_key = crate::services::proxy::generate_api_key();
+        println!(
+            "{} Generated admin key: {}",
+            sym("🔑").green(),
+            generated_key.bold()
+        );
+        Some(generated_key)
+    } else {
+        admin_key
+    };
+    if admin_key.is_some() && admin_key == final_api_key {
+        anyhow::bail!("The admin key must be different from the proxy API key");
+    }
+
     // Validate provider if specified
     if let Some(ref provider_name) = provider {
         let config = crate::config::Config::load()?;
@@ -94,32 +113,58 @@ pub async fn handle(
         println!("  {} {}", "Authentication:".bold(), "Disabled".yellow());
     }
 
+    if admin_key.is_some() {
+        println!("  {} {}", "Management API:".bold(), "Enabled".green());
+    } else {
+        println!("  {} {}", "Management API:".bold(), "Disabled".dimmed());
+    }
+
     println!("\n{}", "Available endpoints:".bold().blue());
-    println!("  {} http://{}:{}/models", "•".blue(), host_str, port_val);
+    println!(
+        "  {} http://{}:{}/models",
+        sym("•").blue(),
+        host_str,
+        port_val
+    );
     println!(
         "  {} http://{}:{}/v1/models",
-        "•".blue(),
+        sym("•").blue(),
         host_str,
         port_val
     );
     println!(
         "  {} http://{}:{}/chat/completions",
-        "•".blue(),
+        sym("•").blue(),
         host_str,
         port_val
     );
     println!(
         "  {} http://{}:{}/v1/chat/completions",
-        "•".blue(),
+        sym("•").blue(),
         host_str,
         port_val
     );
+    if admin_key.is_some() {
+        println!(
+            "  {} http://{}:{}/admin/{{providers,keys,usage}}",
+            sym("•").blue(),
+            host_str,
+            port_val
+        );
+    }
 
-    println!("\n{} Press Ctrl+C to stop the server\n", "💡".yellow());
+    println!("\n{} Press Ctrl+C to stop the server\n", sym("💡").yellow());
 
     // Start the proxy server
-    crate::services::proxy::start_proxy_server(host_str, port_val, provider, model, final_api_key)
-        .await?;
+    crate::services::proxy::start_proxy_server(
+        host_str,
+        port_val,
+        provider,
+        model,
+        final_api_key,
+        admin_key,
+    )
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,395 @@
+//! `lc quickfix`: explain and patch a failing build
+//!
+//! The command (e.g. `cargo build`) is run in the current directory. When it fails, its
+//! output and the code around every file location it reports are sent to the model,
+//! which explains the cause and proposes a unified diff. Diffs that don't parse or
+//! don't match the files are sent back for correction. The diff is shown and, once
+//! confirmed, applied; then the command runs again, for up to `--rounds` fixes.
+
+use crate::utils::terminal::sym;
+use anyhow::{bail, Result};
+use chrono::Utc;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::chat::LLMClient;
+use crate::config::Config;
+use crate::core::chat::{create_authenticated_client, send_chat_request_with_validation};
+use crate::core::validation;
+use crate::database::ChatEntry;
+use crate::utils::regex_cache::get_regex;
+use crate::utils::{code_blocks, unified_diff};
+
+/// Command output beyond this many characters is cut (keeping the end)
+const MAX_OUTPUT_CHARS: usize = 12_000;
+
+/// Lines of code sent before and after each reported location
+const CONTEXT_LINES: usize = 15;
+
+/// Files whose code is sent, in the order the output mentions them
+const MAX_FILES: usize = 8;
+
+/// Requests for a corrected diff when one doesn't apply
+const DIFF_RETRIES: usize = 2;
+
+const SYSTEM_PROMPT: &str = "You are an expert programmer fixing build errors. Find the \
+    root cause from the diagnostics and the code, and fix it with the smallest correct change.";
+
+/// Options of an `lc quickfix` run
+pub struct QuickfixOptions {
+    pub command: String,
+    pub rounds: usize,
+    pub yes: bool,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub system_prompt: Option<String>,
+    pub max_tokens: Option<String>,
+    pub temperature: Option<String>,
+}
+
+/// The model and settings fixes are asked from
+struct Fixer {
+    client: LLMClient,
+    provider: String,
+    model: String,
+    system_prompt: String,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+}
+
+/// A diff that applies, with the new contents of each file (`None` to delete it)
+struct Proposal {
+    answer: String,
+    diff: String,
+    files: Vec<(PathBuf, Option<String>)>,
+}
+
+pub async fn handle(options: QuickfixOptions) -> Result<()> {
+    let dir = std::env::current_dir()?;
+    let mut config = Config::load()?;
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(&config, options.provider, options.model)?;
+    let client = create_authenticated_client(&mut config, &provider).await?;
+    let fixer = Fixer {
+        client,
+        provider,
+        model,
+        system_prompt: options
+            .system_prompt
+            .map(|prompt| config.resolve_template_or_prompt(&prompt))
+            .unwrap_or_else(|| SYSTEM_PROMPT.to_string()),
+        max_tokens: options.max_tokens.as_ref().and_then(|s| s.parse().ok()),
+        temperature: options.temperature.as_ref().and_then(|s| s.parse().ok()),
+    };
+
+    for round in 0..=options.rounds {
+        eprintln!("{} Running {}", "▶".blue(), options.command.bold());
+        let outcome = validation::run_command(&options.command, &dir).await?;
+        if outcome.success {
+            if round == 0 {
+                println!(
+                    "{} '{}' succeeds, nothing to fix",
+                    sym("✓").green(),
+                    options.command
+                );
+            } else {
+                println!("{} '{}' succeeds now", sym("✓").green(), options.command);
+            }
+            return Ok(());
+        }
+        if round == options.rounds {
+            eprintln!("{}", tail(&outcome.output).dimmed());
+            bail!(
+                "'{}' still fails after {} fix{}",
+                options.command,
+                round,
+                if round == 1 { "" } else { "es" }
+            );
+        }
+
+        let locations = locations(&dir, &outcome.output);
+        eprintln!(
+            "{} '{}' failed; asking {}:{} for a fix ({} file{} involved)",
+            sym("✗").red(),
+            options.command,
+            fixer.provider,
+            fixer.model,
+            locations.len(),
+            if locations.len() == 1 { "" } else { "s" }
+        );
+        let prompt = fix_prompt(&dir, &options.command, &outcome.output, &locations);
+        let proposal = fixer.propose(&dir, prompt).await?;
+
+        let explanation = unified_diff::explanation(&proposal.answer);
+        if !explanation.is_empty() {
+            println!("\n{}\n", crate::core::redaction::redact(&explanation));
+        }
+        println!("{}\n", unified_diff::colorize(&proposal.diff));
+
+        if !options.yes && !confirm("Apply this patch? (y/N): ")? {
+            println!("Patch not applied.");
+            return Ok(());
+        }
+        for (path, contents) in &proposal.files {
+            match contents {
+                Some(contents) => {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(path, contents)?;
+                }
+                None => std::fs::remove_file(path)?,
+            }
+        }
+        println!(
+            "{} Patched {} file{}",
+            sym("✓").green(),
+            proposal.files.len(),
+            if proposal.files.len() == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+impl Fixer {
+    /// An answer whose diff applies to the files in `dir`
+    async fn propose(&self, dir: &Path, prompt: String) -> Result<Proposal> {
+        let mut history = Vec::new();
+        let mut question = prompt;
+        for attempt in 0..=DIFF_RETRIES {
+            let (answer, _, _) = send_chat_request_with_validation(
+                &self.client,
+                &self.model,
+                &question,
+                &history,
+                Some(&self.system_prompt),
+                self.max_tokens,
+                self.temperature,
+                &self.provider,
+                None,
+            )
+            .await?;
+            crate::debug_log!("Quickfix answer:\n{}", answer);
+
+            let error = match unified_diff::from_answer(&answer) {
+                Some(diff) => match patched_files(dir, &diff) {
+                    Ok(files) => {
+                        return Ok(Proposal {
+                            answer,
+                            diff,
+                            files,
+                        })
+                    }
+                    Err(e) => e.to_string(),
+                },
+                None => "the answer contains no unified diff".to_string(),
+            };
+            if attempt == DIFF_RETRIES {
+                bail!("The model's patch could not be applied: {}", error);
+            }
+            eprintln!(
+                "{} The patch does not apply ({}), asking for a corrected one",
+                sym("⚠️").yellow(),
+                error
+            );
+            history.push(ChatEntry {
+                chat_id: String::new(),
+                model: self.model.clone(),
+                question,
+                response: answer,
+                timestamp: Utc::now(),
+                input_tokens: None,
+                output_tokens: None,
+                provider: Some(self.provider.clone()),
+            });
+            question = format!(
+                "Your patch could not be applied: {}.\n\nReply with a corrected unified diff \
+                 in a ```diff block. Copy the context and removed lines exactly from the \
+                 files as sent.",
+                error
+            );
+        }
+        unreachable!("the last attempt returns or bails")
+    }
+}
+
+/// The new contents of every file the diff touches, checking that all hunks apply
+fn patched_files(dir: &Path, diff: &str) -> Result<Vec<(PathBuf, Option<String>)>> {
+    let mut files = Vec::new();
+    for patch in unified_diff::parse(diff)? {
+        let path = code_blocks::target_path(dir, patch.path())?;
+        if patch.deletes() {
+            files.push((path, None));
+            continue;
+        }
+        let original = match &patch.old_path {
+            Some(old) => {
+                let old_path = code_blocks::target_path(dir, old)?;
+                std::fs::read_to_string(&old_path)
+                    .map_err(|e| anyhow::anyhow!("cannot read '{}': {}", old, e))?
+            }
+            None => String::new(),
+        };
+        files.push((path, Some(unified_diff::apply(&original, &patch)?)));
+    }
+    Ok(files)
+}
+
+/// Files under `dir` mentioned in the command output, with the lines reported in each
+fn locations(dir: &Path, output: &str) -> Vec<(String, Vec<usize>)> {
+    // `src/main.rs:12:5` (rustc, gcc, go, eslint) and `File "app.py", line 12` (Python)
+    let patterns = [
+        r#"(?:^|[\s(])((?:[\w.-]+/)*[\w.-]+\.[A-Za-z0-9]+):(\d+)"#,
+        r#"File "([^"]+)", line (\d+)"#,
+    ];
+    let mut found: Vec<(String, Vec<usize>)> = Vec::new();
+    for pattern in patterns {
+        let Ok(regex) = get_regex(pattern) else {
+            continue;
+        };
+        for captures in regex.captures_iter(output) {
+            let path = captures[1].trim_start_matches("./").to_string();
+            let Ok(line) = captures[2].parse::<usize>() else {
+                continue;
+            };
+            let is_file = code_blocks::target_path(dir, &path).is_ok_and(|p| p.is_file());
+            if !is_file {
+                continue;
+            }
+            if let Some((_, lines)) = found.iter_mut().find(|(known, _)| *known == path) {
+                lines.push(line);
+            } else if found.len() < MAX_FILES {
+                found.push((path, vec![line]));
+            }
+        }
+    }
+    found
+}
+
+/// The request for an explanation and a diff fixing the failure
+fn fix_prompt(
+    dir: &Path,
+    command: &str,
+    output: &str,
+    locations: &[(String, Vec<usize>)],
+) -> String {
+    let mut prompt = format!(
+        "`{}` fails in this project with:\n\n```\n{}\n```\n",
+        command,
+        tail(output)
+    );
+    if !locations.is_empty() {
+        prompt.push_str("\nThe code around the reported lines:\n");
+    }
+    for (path, lines) in locations {
+        let Ok(text) = std::fs::read_to_string(dir.join(path)) else {
+            continue;
+        };
+        let file_lines: Vec<&str> = text.lines().collect();
+        for (start, end) in sections(lines, file_lines.len()) {
+            prompt.push_str(&format!(
+                "\n<file path=\"{}\" lines=\"{}-{}\">\n{}\n</file>\n",
+                path,
+                start,
+                end,
+                file_lines[start - 1..end].join("\n")
+            ));
+        }
+    }
+    prompt.push_str(
+        "\nExplain the cause in a few sentences, then give the fix as a unified diff in a \
+         ```diff block, with paths relative to the project root (`--- a/path`, `+++ b/path`) \
+         and about three unchanged context lines around each change, copied exactly from \
+         the files. Only change what is needed to fix the errors.",
+    );
+    prompt
+}
+
+/// 1-based inclusive line ranges around `lines`, merged where they overlap
+fn sections(lines: &[usize], total: usize) -> Vec<(usize, usize)> {
+    let mut ranges: BTreeMap<usize, usize> = BTreeMap::new();
+    for &line in lines.iter().filter(|&&line| line >= 1 && line <= total) {
+        let start = line.saturating_sub(CONTEXT_LINES).max(1);
+        let end = (line + CONTEXT_LINES).min(total);
+        let end = ranges.get(&start).map_or(end, |known| end.max(*known));
+        ranges.insert(start, end);
+    }
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// The end of the command output, where the errors that stopped it are
+fn tail(output: &str) -> String {
+    let output = output.trim();
+    match output.char_indices().rev().nth(MAX_OUTPUT_CHARS) {
+        Some((cut, _)) => format!("[...]\n{}", &output[cut..]),
+        None => output.to_string(),
+    }
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase().starts_with('y'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locations_and_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n".repeat(60)).unwrap();
+        std::fs::write(dir.path().join("app.py"), "x = 1\n").unwrap();
+
+        let output = "error[E0425]: cannot find value `y`\n --> src/main.rs:3:20\n\
+            warning: unused\n --> ./src/main.rs:50:1\n\
+            note: see /usr/lib/rustlib/core.rs:10:5\n\
+            File \"app.py\", line 1, in <module>\n";
+        assert_eq!(
+            locations(dir.path(), output),
+            vec![
+                ("src/main.rs".to_string(), vec![3, 50]),
+                ("app.py".to_string(), vec![1])
+            ]
+        );
+
+        assert_eq!(sections(&[3, 50], 60), vec![(1, 18), (35, 60)]);
+        assert_eq!(sections(&[20, 30, 99], 60), vec![(5, 45)]);
+    }
+
+    #[test]
+    fn test_patched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "let a = 1;\nlet b = a;\n").unwrap();
+
+        let files = patched_files(
+            dir.path(),
+            "--- a/lib.rs\n+++ b/lib.rs\n@@ -1,2 +1,2 @@\n let a = 1;\n-let b = a;\n+let b = a + 1;\n",
+        )
+        .unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].1.as_deref(), Some("let a = 1;\nlet b = a + 1;\n"));
+
+        assert!(
+            patched_files(dir.path(), "--- a/../x\n+++ b/../x\n@@ -1 +1 @@\n-a\n+b\n").is_err()
+        );
+        assert!(patched_files(
+            dir.path(),
+            "--- a/gone.rs\n+++ b/gone.rs\n@@ -1 +1 @@\n-a\n+b\n"
+        )
+        .is_err());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::cli::{SearchCommands, SearchProviderCommands};
 use crate::search::{SearchConfig, SearchEngine, SearchProviderType};
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 
@@ -22,20 +23,34 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
     let mut config = SearchConfig::load()?;
 
     match command {
-        SearchProviderCommands::Add { name, url } => {
+        SearchProviderCommands::Add {
+            name,
+            url,
+            provider_type,
+        } => {
+            let url = url.unwrap_or_else(|| {
+                provider_type
+                    .as_ref()
+                    .map(|t| t.default_url().to_string())
+                    .unwrap_or_default()
+            });
             println!(
                 "{} Adding search provider '{}' with URL: {}",
-                "🔍".blue(),
+                sym("🔍").blue(),
                 name.bold(),
                 url.dimmed()
             );
 
-            // Auto-detect provider type from URL
-            match SearchProviderType::detect_from_url(&url) {
+            // Use the given type, or auto-detect it from the URL
+            let detected = match provider_type {
+                Some(provider_type) => Ok(provider_type),
+                None => SearchProviderType::detect_from_url(&url),
+            };
+            match detected {
                 Ok(provider_type) => {
                     println!(
-                        "  {} Auto-detected provider type: {}",
-                        "✓".green(),
+                        "  {} Provider type: {}",
+                        sym("✓").green(),
                         format!("{:?}", provider_type).cyan()
                     );
 
@@ -44,7 +59,7 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
 
                     println!(
                         "{} Search provider '{}' added successfully",
-                        "✓".green(),
+                        sym("✓").green(),
                         name.bold()
                     );
 
@@ -54,20 +69,26 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
                 Err(e) => {
                     eprintln!(
                         "{} Failed to detect provider type from URL: {}",
-                        "✗".red(),
+                        sym("✗").red(),
                         e
                     );
                     eprintln!(
                         "  {} Make sure the URL matches one of the supported patterns:",
-                        "ℹ".blue()
+                        sym("ℹ").blue()
+                    );
+                    eprintln!("    {} Brave: api.search.brave.com", sym("•"));
+                    eprintln!("    {} Exa: api.exa.ai", sym("•"));
+                    eprintln!("    {} Serper: google.serper.dev", sym("•"));
+                    eprintln!("    {} SerpApi: serpapi.com", sym("•"));
+                    eprintln!("    {} DuckDuckGo: html.duckduckgo.com, lite.duckduckgo.com, api.duckduckgo.com", sym("•"));
+                    eprintln!("    {} Jina: s.jina.ai", sym("•"));
+                    eprintln!("    {} Tavily: api.tavily.com", sym("•"));
+                    eprintln!("    {} SearXNG: any URL containing 'searx'", sym("•"));
+                    eprintln!(
+                        "  {} Or name the type: {}",
+                        sym("ℹ").blue(),
+                        format!("lc search provider add {} <url> --type <type>", name).bold()
                     );
-                    eprintln!("    • Brave: api.search.brave.com");
-                    eprintln!("    • Exa: api.exa.ai");
-                    eprintln!("    • Serper: google.serper.dev");
-                    eprintln!("    • SerpApi: serpapi.com");
-                    eprintln!("    • DuckDuckGo: api.duckduckgo.com");
-                    eprintln!("    • Jina: s.jina.ai");
-                    eprintln!("    • Tavily: api.tavily.com");
                 }
             }
         }
@@ -75,21 +96,26 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
             let providers = config.list_providers();
 
             if providers.is_empty() {
-                println!("{} No search providers configured", "📋".blue());
+                println!("{} No search providers configured", sym("📋").blue());
                 println!(
                     "\n{} Add one with: {}",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc search provider add <name> <url>".bold()
                 );
             } else {
-                println!("{} Configured search providers:", "📋".blue());
+                println!("{} Configured search providers:", sym("📋").blue());
                 println!();
 
                 for (name, provider) in providers {
                     let is_default = config.get_default_provider() == Some(name);
                     let default_marker = if is_default { " (default)" } else { "" };
 
-                    println!("  {} {}{}", "•".cyan(), name.bold(), default_marker.green());
+                    println!(
+                        "  {} {}{}",
+                        sym("•").cyan(),
+                        name.bold(),
+                        default_marker.green()
+                    );
                     println!("    Type: {:?}", provider.provider_type);
                     println!("    URL: {}", provider.url.dimmed());
 
@@ -108,7 +134,7 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
         SearchProviderCommands::Delete { name } => {
             println!(
                 "{} Removing search provider '{}'...",
-                "🗑".red(),
+                sym("🗑").red(),
                 name.bold()
             );
 
@@ -117,7 +143,7 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
 
             println!(
                 "{} Search provider '{}' removed successfully",
-                "✓".green(),
+                sym("✓").green(),
                 name
             );
         }
@@ -128,7 +154,7 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
         } => {
             println!(
                 "{} Setting header '{}' for provider '{}'",
-                "⚙".blue(),
+                sym("⚙").blue(),
                 header_name.yellow(),
                 provider.bold()
             );
@@ -138,7 +164,7 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
 
             println!(
                 "{} Header '{}' set successfully for '{}'",
-                "✓".green(),
+                sym("✓").green(),
                 header_name.yellow(),
                 provider.bold()
             );
@@ -151,7 +177,7 @@ async fn handle_provider(command: SearchProviderCommands) -> Result<()> {
 async fn handle_query(provider: String, query: String, format: String, count: usize) -> Result<()> {
     println!(
         "{} Searching with '{}' for: {}",
-        "🔍".blue(),
+        sym("🔍").blue(),
         provider.bold(),
         query.cyan()
     );
@@ -169,7 +195,7 @@ async fn handle_query(provider: String, query: String, format: String, count: us
         _ => {
             eprintln!(
                 "{} Invalid format '{}'. Use 'json' or 'md'/'markdown'",
-                "✗".red(),
+                sym("✗").red(),
                 format
             );
         }
@@ -180,7 +206,7 @@ async fn handle_query(provider: String, query: String, format: String, count: us
 
 fn show_provider_setup_help(name: &str, provider_type: SearchProviderType) {
     println!();
-    println!("{} Next steps:", "ℹ".blue());
+    println!("{} Next steps:", sym("ℹ").blue());
 
     match provider_type {
         SearchProviderType::Brave => {
@@ -222,7 +248,7 @@ fn show_provider_setup_help(name: &str, provider_type: SearchProviderType) {
         SearchProviderType::DuckDuckGo => {
             println!(
                 "  {} No API key required! You can start searching immediately:",
-                "✓".green()
+                sym("✓").green()
             );
             println!(
                 "     {}",
@@ -256,7 +282,23 @@ fn show_provider_setup_help(name: &str, provider_type: SearchProviderType) {
             println!("  2. Set the API key:");
             println!(
                 "     {}",
-                format!("lc search provider set {} api-key YOUR_API_KEY", name).bold()
+                format!(
+                    "lc search provider set {} Authorization \"Bearer YOUR_API_KEY\"",
+                    name
+                )
+                .bold()
+            );
+        }
+        SearchProviderType::Searxng => {
+            println!("  1. Make sure JSON results are enabled in the instance's settings.yml:");
+            println!("     {}", "search: { formats: [html, json] }".bold());
+            println!(
+                "  2. {} No API key required. Search with:",
+                sym("✓").green()
+            );
+            println!(
+                "     {}",
+                format!("lc search query {} \"your query\"", name).bold()
             );
         }
     }
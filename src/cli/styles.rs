@@ -0,0 +1,203 @@
+//! Style profile commands
+
+use crate::cli::StyleCommands;
+use crate::config;
+use crate::core::style::StyleProfile;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+
+/// Handle style-related commands
+pub async fn handle(command: StyleCommands) -> Result<()> {
+    match command {
+        StyleCommands::Set {
+            name,
+            tone,
+            language,
+            formatting,
+            banned_phrases,
+            check_language,
+        } => {
+            let profile = StyleProfile {
+                tone,
+                language,
+                formatting,
+                banned_phrases,
+                check_language,
+            };
+            if profile == StyleProfile::default() {
+                anyhow::bail!("Give at least one of --tone, --language, --format or --ban");
+            }
+            let mut config = config::Config::load()?;
+            let replaced = config
+                .styles
+                .profiles
+                .insert(name.clone(), profile)
+                .is_some();
+            config.save()?;
+            let action = if replaced { "updated" } else { "added" };
+            println!("{} Style '{}' {}", sym("✓").green(), name, action);
+        }
+        StyleCommands::Delete { name } => {
+            let mut config = config::Config::load()?;
+            let styles = &mut config.styles;
+            if styles.profiles.remove(&name).is_none() {
+                anyhow::bail!("Style '{}' not found", name);
+            }
+            if styles.default.as_deref() == Some(name.as_str()) {
+                styles.default = None;
+            }
+            styles.templates.retain(|_, style| *style != name);
+            config.save()?;
+            println!("{} Style '{}' removed", sym("✓").green(), name);
+        }
+        StyleCommands::List => {
+            let config = config::Config::load()?;
+            let styles = &config.styles;
+
+            if styles.profiles.is_empty() {
+                println!("No styles configured.");
+                println!(
+                    "\n{} Add one with: {}",
+                    sym("💡").yellow(),
+                    "lc styles set <name> --tone <tone> --language <language>".bold()
+                );
+                return Ok(());
+            }
+
+            let mut names: Vec<&String> = styles.profiles.keys().collect();
+            names.sort();
+            println!("\n{}", "Styles:".bold().blue());
+            for name in names {
+                let profile = &styles.profiles[name];
+                let mut notes = Vec::new();
+                if styles.default.as_ref() == Some(name) {
+                    notes.push("default".to_string());
+                }
+                let mut templates: Vec<&str> = styles
+                    .templates
+                    .iter()
+                    .filter(|(_, style)| *style == name)
+                    .map(|(template, _)| template.as_str())
+                    .collect();
+                if !templates.is_empty() {
+                    templates.sort();
+                    notes.push(format!("templates: {}", templates.join(", ")));
+                }
+                let notes = if notes.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", notes.join("; ")).dimmed().to_string()
+                };
+                println!(
+                    "  {} {} -> {}{}",
+                    sym("•").blue(),
+                    name.bold(),
+                    summary(profile),
+                    notes
+                );
+            }
+        }
+        StyleCommands::Show { name } => {
+            let config = config::Config::load()?;
+            let profile = config
+                .styles
+                .profiles
+                .get(&name)
+                .ok_or_else(|| anyhow::anyhow!("Style '{}' not found", name))?;
+            println!("{}", profile.instructions());
+            if profile.check_language {
+                println!(
+                    "\n{} Answers not in {} are requested again once",
+                    sym("ℹ️").blue(),
+                    profile.language.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        StyleCommands::Default { name, clear: _ } => {
+            let mut config = config::Config::load()?;
+            if let Some(name) = &name {
+                ensure_style(&config, name)?;
+            }
+            config.styles.default = name.clone();
+            config.save()?;
+            match name {
+                Some(name) => println!("{} Default style set to '{}'", sym("✓").green(), name),
+                None => println!("{} Default style cleared", sym("✓").green()),
+            }
+        }
+        StyleCommands::Template {
+            template,
+            style,
+            clear: _,
+        } => {
+            let mut config = config::Config::load()?;
+            match style {
+                Some(style) => {
+                    if !config.has_template(&template) {
+                        anyhow::bail!("Template '{}' not found", template);
+                    }
+                    ensure_style(&config, &style)?;
+                    config
+                        .styles
+                        .templates
+                        .insert(template.clone(), style.clone());
+                    config.save()?;
+                    println!(
+                        "{} Template '{}' now uses style '{}'",
+                        sym("✓").green(),
+                        template,
+                        style
+                    );
+                }
+                None => {
+                    if config.styles.templates.remove(&template).is_none() {
+                        anyhow::bail!("Template '{}' has no style", template);
+                    }
+                    config.save()?;
+                    println!(
+                        "{} Template '{}' no longer has a style",
+                        sym("✓").green(),
+                        template
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn ensure_style(config: &config::Config, name: &str) -> Result<()> {
+    if !config.styles.profiles.contains_key(name) {
+        anyhow::bail!(
+            "Style '{}' not found. Create it with 'lc styles set {}'",
+            name,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// One-line description of a profile's rules
+fn summary(profile: &StyleProfile) -> String {
+    let mut parts = Vec::new();
+    if let Some(tone) = &profile.tone {
+        parts.push(format!("tone: {}", tone));
+    }
+    if let Some(language) = &profile.language {
+        let checked = if profile.check_language {
+            " (checked)"
+        } else {
+            ""
+        };
+        parts.push(format!("language: {}{}", language, checked));
+    }
+    if !profile.formatting.is_empty() {
+        parts.push(format!("{} formatting rule(s)", profile.formatting.len()));
+    }
+    if !profile.banned_phrases.is_empty() {
+        parts.push(format!("{} banned phrase(s)", profile.banned_phrases.len()));
+    }
+    parts.join(", ")
+}
@@ -1,6 +1,7 @@
 //! Sync functionality commands
 
 use crate::cli::SyncCommands;
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 
@@ -18,26 +19,38 @@ pub async fn handle(command: SyncCommands) -> Result<()> {
         SyncCommands::To {
             provider,
             encrypted,
+            include,
+            with_keys,
             yes,
         } => {
             // Sync configuration to cloud provider
-            println!("{} Syncing configuration to {}...", "📤".cyan(), provider);
+            println!(
+                "{} Syncing configuration to {}...",
+                sym("📤").cyan(),
+                provider
+            );
             if encrypted {
-                println!("  {} Encryption enabled", "🔒".yellow());
+                println!("  {} Encryption enabled", sym("🔒").yellow());
             }
-            crate::sync::handle_sync_to(&provider, encrypted, yes).await?
+            crate::sync::handle_sync_to(&provider, encrypted, yes, &include, with_keys).await?
         }
         SyncCommands::From {
             provider,
             encrypted,
+            include,
+            with_keys,
             yes,
         } => {
             // Sync configuration from cloud provider
-            println!("{} Syncing configuration from {}...", "📥".cyan(), provider);
+            println!(
+                "{} Syncing configuration from {}...",
+                sym("📥").cyan(),
+                provider
+            );
             if encrypted {
-                println!("  {} Decryption enabled", "🔓".yellow());
+                println!("  {} Decryption enabled", sym("🔓").yellow());
             }
-            crate::sync::handle_sync_from(&provider, encrypted, yes).await?
+            crate::sync::handle_sync_from(&provider, encrypted, yes, &include, with_keys).await?
         }
     }
     Ok(())
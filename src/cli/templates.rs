@@ -2,33 +2,44 @@
 
 use crate::cli::TemplateCommands;
 use crate::config;
+use crate::core::system_file;
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 
 /// Handle template-related commands
 pub async fn handle(command: TemplateCommands) -> Result<()> {
     match command {
-        TemplateCommands::Add { name, prompt } => {
+        TemplateCommands::Add { name, prompt, file } => {
             let mut config = config::Config::load()?;
-            config.add_template(name.clone(), prompt.clone())?;
+            match (prompt, file) {
+                (_, Some(file)) => {
+                    // Validates the file and shows its token count
+                    system_file::load(&file, None)?;
+                    let path = std::fs::canonicalize(&file)?;
+                    config.add_template_file(name.clone(), path.display().to_string())?;
+                }
+                (Some(prompt), None) => config.add_template(name.clone(), prompt)?,
+                (None, None) => anyhow::bail!("Provide a prompt or --file"),
+            }
             config.save()?;
-            println!("{} Template '{}' added", "✓".green(), name);
+            println!("{} Template '{}' added", sym("✓").green(), name);
         }
         TemplateCommands::Delete { name } => {
             let mut config = config::Config::load()?;
             config.remove_template(name.clone())?;
             config.save()?;
-            println!("{} Template '{}' removed", "✓".green(), name);
+            println!("{} Template '{}' removed", sym("✓").green(), name);
         }
         TemplateCommands::List => {
             let config = config::Config::load()?;
             let templates = config.list_templates();
 
-            if templates.is_empty() {
+            if templates.is_empty() && config.template_files.is_empty() {
                 println!("No templates configured.");
                 println!(
                     "\n{} Add one with: {}",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc templates add <name> <prompt>".bold()
                 );
             } else {
@@ -39,7 +50,21 @@ pub async fn handle(command: TemplateCommands) -> Result<()> {
                     } else {
                         prompt.clone()
                     };
-                    println!("  {} {} -> {}", "•".blue(), name.bold(), display_prompt);
+                    println!(
+                        "  {} {} -> {}",
+                        sym("•").blue(),
+                        name.bold(),
+                        display_prompt
+                    );
+                }
+                for (name, path) in &config.template_files {
+                    println!(
+                        "  {} {} -> {} {}",
+                        sym("•").blue(),
+                        name.bold(),
+                        path,
+                        "(file)".dimmed()
+                    );
                 }
             }
         }
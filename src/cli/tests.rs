@@ -18,17 +18,7 @@ fn create_test_config() -> (Config, TempDir) {
     std::env::set_var("LC_TEST_CONFIG_DIR", temp_dir.path());
     let _config_path = temp_dir.path().join("config.toml");
 
-    let config = Config {
-        providers: HashMap::new(),
-        default_provider: None,
-        default_model: None,
-        aliases: HashMap::new(),
-        system_prompt: None,
-        templates: HashMap::new(),
-        max_tokens: None,
-        temperature: None,
-        stream: None,
-    };
+    let config = Config::default();
 
     (config, temp_dir)
 }
@@ -54,8 +44,12 @@ fn create_test_provider_config(endpoint: &str) -> ProviderConfig {
         models_templates: None,
         audio_path: None,
         speech_path: None,
+        rerank_path: None,
         audio_templates: None,
         speech_templates: None,
+        organization: None,
+        project: None,
+        hmac: None,
     }
 }
 
@@ -65,17 +59,7 @@ fn create_config_with_providers() -> (Config, TempDir) {
     let temp_dir = TempDir::new().expect("Failed to create temp directory");
     std::env::set_var("LC_TEST_CONFIG_DIR", temp_dir.path());
     
-    let mut config = Config {
-        providers: HashMap::new(),
-        default_provider: None,
-        default_model: None,
-        aliases: HashMap::new(),
-        system_prompt: None,
-        templates: HashMap::new(),
-        max_tokens: None,
-        temperature: None,
-        stream: None,
-    };
+    let mut config = Config::default();
 
     // Add test providers with test- prefix
     let openai_name = format!("{}openai", TEST_PROVIDER_PREFIX);
@@ -158,17 +142,7 @@ mod provider_tests {
 
     #[test]
     fn test_provider_add_basic() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test adding a basic provider
         let result = config.add_provider(
@@ -192,17 +166,7 @@ mod provider_tests {
 
     #[test]
     fn test_provider_add_with_custom_paths() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test adding a provider with custom paths
         let result = config.add_provider_with_paths(
@@ -277,17 +241,7 @@ mod provider_tests {
 
     #[test]
     fn test_provider_list_empty() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         assert!(config.providers.is_empty());
     }
@@ -616,17 +570,7 @@ mod provider_edge_cases {
 
     #[test]
     fn test_provider_name_validation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test empty provider name
         let result = config.add_provider("".to_string(), "https://api.test.com".to_string());
@@ -649,17 +593,7 @@ mod provider_edge_cases {
 
     #[test]
     fn test_provider_url_validation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test various URL formats
         let urls = vec![
@@ -679,17 +613,7 @@ mod provider_edge_cases {
 
     #[test]
     fn test_provider_paths_validation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test various path formats
         let test_cases = vec![
@@ -729,17 +653,7 @@ mod provider_edge_cases {
 
     #[test]
     fn test_provider_duplicate_names() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add provider
         let result =
@@ -760,17 +674,7 @@ mod provider_edge_cases {
 
     #[test]
     fn test_provider_case_sensitivity() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add providers with different cases
         config
@@ -849,17 +753,7 @@ mod provider_integration_tests {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         std::env::set_var("LC_TEST_CONFIG_DIR", temp_dir.path());
         
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // 1. Add provider
         config
@@ -991,17 +885,7 @@ mod provider_integration_tests {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         std::env::set_var("LC_TEST_CONFIG_DIR", temp_dir.path());
         
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add multiple providers
         let providers = vec![
@@ -0,0 +1,92 @@
+//! Undo for destructive commands (vectors delete, logs purge, providers remove)
+
+use crate::trash::{Trash, TrashEntry};
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::io::{self, Write};
+
+/// Handle `lc undo`
+pub fn handle(list: bool, yes: bool) -> Result<()> {
+    let trash = Trash::new()?;
+
+    if list {
+        return list_entries(&trash.list()?);
+    }
+
+    let Some(entry) = trash.latest()? else {
+        println!("{} Nothing to undo", sym("ℹ️").blue());
+        return Ok(());
+    };
+
+    println!(
+        "{} Last destructive operation: {} ({})",
+        sym("↩️").blue(),
+        entry.manifest.operation.bold(),
+        format_time(&entry)
+    );
+    for file in &entry.manifest.files {
+        println!("  {} {}", sym("•"), file.original.display());
+    }
+    if entry.manifest.overwrite {
+        println!(
+            "{} Changes made after this operation will be replaced",
+            sym("⚠️").yellow()
+        );
+    }
+
+    if !yes {
+        print!("Restore? (y/N): ");
+        // Deliberately flush stdout to ensure prompt appears before user input
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !input.trim().to_lowercase().starts_with('y') {
+            println!("Undo cancelled.");
+            return Ok(());
+        }
+    }
+
+    trash.restore(&entry)?;
+    println!(
+        "{} Restored '{}'",
+        sym("✓").green(),
+        entry.manifest.operation
+    );
+    Ok(())
+}
+
+fn list_entries(entries: &[TrashEntry]) -> Result<()> {
+    if entries.is_empty() {
+        println!("{} Trash is empty", sym("ℹ️").blue());
+        return Ok(());
+    }
+
+    println!("\n{}", "Trash (most recent first):".bold().blue());
+    for (index, entry) in entries.iter().enumerate() {
+        let marker = if index == 0 {
+            sym(" ← lc undo").green().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {} {}{}",
+            format_time(entry).dimmed(),
+            entry.manifest.operation.bold(),
+            format!("({} file(s))", entry.manifest.files.len()).dimmed(),
+            marker
+        );
+    }
+    Ok(())
+}
+
+fn format_time(entry: &TrashEntry) -> String {
+    entry
+        .manifest
+        .created_at
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
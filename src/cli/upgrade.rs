@@ -0,0 +1,350 @@
+//! `lc upgrade-self`: update the lc binary from GitHub releases
+//!
+//! The release archive for this platform is downloaded, checked against the SHA-256
+//! published with the release (a `<archive>.sha256` asset, or the digest GitHub records
+//! for the asset), and the executable is replaced atomically. Nothing is installed when
+//! no checksum is available or it does not match.
+//!
+//! The checksum comes from the same release as the archive, so it catches corrupted and
+//! truncated downloads, not a tampered release: it is an integrity check, not a signature.
+
+use crate::utils::terminal::sym;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/rajashekar/lc/releases";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+    /// `sha256:<hex>`, recorded by GitHub for uploaded assets
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+impl Release {
+    fn version(&self) -> Option<semver::Version> {
+        semver::Version::parse(self.tag_name.trim_start_matches('v')).ok()
+    }
+
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// Name of the release binary for this platform, as built by the release workflow
+fn platform_asset() -> Result<&'static str> {
+    Ok(match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => "lc-linux-x86_64",
+        ("linux", "aarch64") => "lc-linux-arm64",
+        ("macos", "x86_64") => "lc-macos-x86_64",
+        ("macos", "aarch64") => "lc-macos-arm64",
+        ("windows", "x86_64") => "lc-windows-amd64.exe",
+        (os, arch) => bail!(
+            "No release binaries are published for {}-{}. Build from source with 'cargo install lc-cli'",
+            os,
+            arch
+        ),
+    })
+}
+
+fn archive_name(asset: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.zip", asset)
+    } else {
+        format!("{}.tar.gz", asset)
+    }
+}
+
+pub async fn handle(check: bool, version: Option<String>, yes: bool, force: bool) -> Result<()> {
+    let current = semver::Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(300))
+        .user_agent(concat!("lc/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let releases = fetch_releases(&client).await?;
+
+    if check {
+        return print_versions(&current, &releases);
+    }
+
+    let release = match &version {
+        Some(wanted) => {
+            let wanted = semver::Version::parse(wanted.trim_start_matches('v'))
+                .with_context(|| format!("Invalid version '{}'", wanted))?;
+            releases
+                .iter()
+                .find(|r| r.version().as_ref() == Some(&wanted))
+                .with_context(|| format!("No release v{} found", wanted))?
+        }
+        None => latest(&releases).context("No releases found")?,
+    };
+    let target = release.version().context("Release has no valid version")?;
+    if target == current && !force {
+        println!("{} lc {} is up to date", sym("✓").green(), current);
+        return Ok(());
+    }
+    if target < current && version.is_none() && !force {
+        println!(
+            "{} lc {} is newer than the latest release ({})",
+            sym("✓").green(),
+            current,
+            target
+        );
+        return Ok(());
+    }
+
+    let archive = archive_name(platform_asset()?);
+    let asset = release
+        .asset(&archive)
+        .with_context(|| format!("Release {} has no '{}' asset", release.tag_name, archive))?;
+    let expected = expected_checksum(&client, release, asset).await?;
+
+    let exe = std::env::current_exe()?;
+    if exe.components().any(|c| c.as_os_str() == "Cellar") {
+        println!(
+            "{} lc was installed with Homebrew; 'brew upgrade lc' keeps Homebrew in sync",
+            sym("⚠").yellow()
+        );
+    }
+    if !yes {
+        print!(
+            "Replace {} ({}) with {}? (y/N): ",
+            exe.display(),
+            current,
+            target
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().to_lowercase().starts_with('y') {
+            println!("Upgrade cancelled.");
+            return Ok(());
+        }
+    }
+
+    println!("Downloading {}...", asset.name);
+    let bytes = client
+        .get(&asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        bail!(
+            "Checksum mismatch for {}: expected {}, got {}. Nothing was installed",
+            asset.name,
+            expected,
+            actual
+        );
+    }
+    println!("{} Checksum matches (sha256 {})", sym("✓").green(), actual);
+
+    let binary = extract_binary(&asset.name, &bytes)?;
+    let dir = exe.parent().context("Cannot locate the lc executable")?;
+    let mut staged = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("Cannot write to {}", dir.display()))?;
+    staged.write_all(&binary)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        staged
+            .as_file()
+            .set_permissions(std::fs::Permissions::from_mode(0o755))?;
+    }
+    self_replace::self_replace(staged.path())
+        .with_context(|| format!("Failed to replace {}", exe.display()))?;
+
+    println!(
+        "{} Upgraded lc {} {} {}",
+        sym("✓").green(),
+        current,
+        sym("→"),
+        target
+    );
+    Ok(())
+}
+
+async fn fetch_releases(client: &reqwest::Client) -> Result<Vec<Release>> {
+    let url = std::env::var("LC_RELEASES_URL").unwrap_or_else(|_| RELEASES_URL.to_string());
+    let response = client
+        .get(&url)
+        .query(&[("per_page", "30")])
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .context("Failed to reach GitHub releases")?;
+    if !response.status().is_success() {
+        bail!("GitHub releases request failed: {}", response.status());
+    }
+    let releases: Vec<Release> = response.json().await?;
+    Ok(releases.into_iter().filter(|r| !r.draft).collect())
+}
+
+fn latest(releases: &[Release]) -> Option<&Release> {
+    releases
+        .iter()
+        .filter(|r| !r.prerelease)
+        .filter_map(|r| Some((r.version()?, r)))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, r)| r)
+}
+
+fn print_versions(current: &semver::Version, releases: &[Release]) -> Result<()> {
+    println!("Installed: {}", current);
+    let newer: Vec<(semver::Version, &Release)> = releases
+        .iter()
+        .filter_map(|r| Some((r.version()?, r)))
+        .filter(|(v, _)| v > current)
+        .collect();
+    if newer.is_empty() {
+        println!("{} lc is up to date", sym("✓").green());
+        return Ok(());
+    }
+    println!("\n{}", "Available versions:".bold());
+    for (version, release) in &newer {
+        let date = release
+            .published_at
+            .as_deref()
+            .and_then(|d| d.get(..10))
+            .unwrap_or("");
+        let pre = if release.prerelease {
+            " (pre-release)".dimmed().to_string()
+        } else {
+            String::new()
+        };
+        println!("  {} {}{}", version.to_string().green(), date.dimmed(), pre);
+    }
+    println!("\nRun 'lc upgrade-self' to install the latest release.");
+    Ok(())
+}
+
+/// The published SHA-256 of `asset`, as lowercase hex
+async fn expected_checksum(
+    client: &reqwest::Client,
+    release: &Release,
+    asset: &Asset,
+) -> Result<String> {
+    if let Some(sums) = release.asset(&format!("{}.sha256", asset.name)) {
+        let text = client
+            .get(&sums.browser_download_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        return parse_checksum(&text, &asset.name)
+            .with_context(|| format!("No checksum for {} in {}", asset.name, sums.name));
+    }
+    if let Some(hex) = asset
+        .digest
+        .as_deref()
+        .and_then(|d| d.strip_prefix("sha256:"))
+    {
+        return Ok(hex.to_lowercase());
+    }
+    bail!(
+        "Release {} publishes no checksum for {}; refusing to install an unchecked download",
+        release.tag_name,
+        asset.name
+    )
+}
+
+/// The checksum from `sha256sum` output: `<hex>  <file>` lines, or a bare hex digest
+fn parse_checksum(text: &str, file: &str) -> Option<String> {
+    let is_hex = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hex = parts.next()?;
+        let name = parts.next().map(|n| n.trim_start_matches('*'));
+        (is_hex(hex) && name.is_none_or(|n| n == file)).then(|| hex.to_lowercase())
+    })
+}
+
+/// The lc executable inside a release archive
+fn extract_binary(archive: &str, bytes: &[u8]) -> Result<Vec<u8>> {
+    let is_lc = |path: &std::path::Path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name == "lc" || name == "lc.exe")
+    };
+    let mut binary = Vec::new();
+    if archive.ends_with(".zip") {
+        let mut zip = zip::ZipArchive::new(io::Cursor::new(bytes))?;
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            if entry.is_file() && is_lc(std::path::Path::new(entry.name())) {
+                entry.read_to_end(&mut binary)?;
+                return Ok(binary);
+            }
+        }
+    } else {
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type().is_file() && is_lc(&entry.path()?) {
+                entry.read_to_end(&mut binary)?;
+                return Ok(binary);
+            }
+        }
+    }
+    bail!("No lc executable found in {}", archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_and_extraction() {
+        let hex = "a".repeat(64);
+        let sums = format!("{}  lc-linux-x86_64.tar.gz\n", hex);
+        assert_eq!(
+            parse_checksum(&sums, "lc-linux-x86_64.tar.gz"),
+            Some(hex.clone())
+        );
+        assert_eq!(parse_checksum(&sums, "lc-macos-arm64.tar.gz"), None);
+        assert_eq!(parse_checksum(&hex, "anything"), Some(hex.clone()));
+
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(6);
+        header.set_mode(0o755);
+        header.set_cksum();
+        tar.append_data(&mut header, "lc", &b"binary"[..]).unwrap();
+        let archive = tar.into_inner().unwrap().finish().unwrap();
+        assert_eq!(
+            extract_binary("lc-linux-x86_64.tar.gz", &archive).unwrap(),
+            b"binary"
+        );
+
+        let releases: Vec<Release> = serde_json::from_str(
+            r#"[{"tag_name": "v0.3.0-rc.1", "prerelease": true},
+                {"tag_name": "v0.2.0"}, {"tag_name": "v0.1.9"}]"#,
+        )
+        .unwrap();
+        assert_eq!(latest(&releases).unwrap().tag_name, "v0.2.0");
+    }
+}
@@ -1,28 +1,62 @@
 //! Usage statistics commands
 
-use crate::analytics::usage_stats::{display_usage_overview, BarChart, UsageAnalyzer};
+use crate::analytics::usage_stats::{
+    display_latency_report, display_usage_overview, hourly_usage, BarChart, UsageAnalyzer,
+};
 use crate::cli::UsageCommands;
+use crate::utils::terminal::sym;
 use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
 use colored::Colorize;
 
 /// Handle usage-related commands
 pub async fn handle(
     command: Option<UsageCommands>,
     days: Option<u64>,
+    since: Option<String>,
+    until: Option<String>,
     tokens_only: bool,
     requests_only: bool,
     limit: Option<usize>,
 ) -> Result<()> {
-    // Convert types to match what the analytics module expects
-    let days_u32 = days.map(|d| d as u32);
     let limit_val = limit.unwrap_or(10);
 
+    // --days and --since both set a lower bound; the later one wins
+    let days_cutoff = days.map(|d| Utc::now() - Duration::days(d as i64));
+    let since = since
+        .as_deref()
+        .map(|s| parse_date_bound(s, false))
+        .transpose()?;
+    let since = days_cutoff.max(since);
+    let until = until
+        .as_deref()
+        .map(|s| parse_date_bound(s, true))
+        .transpose()?;
+    if let (Some(s), Some(u)) = (since, until) {
+        if s > u {
+            anyhow::bail!("--since must be before --until");
+        }
+    }
+
     let analyzer = UsageAnalyzer::new()?;
-    let stats = analyzer.get_usage_stats(days_u32)?;
+
+    // Latency comes from the request_timings table rather than chat logs
+    if let Some(UsageCommands::Latency { count }) = command {
+        let latency = analyzer.get_latency_stats(since, until)?;
+        if latency.total_requests == 0 {
+            println!("{} No timing data found", sym("ℹ️").blue());
+            println!("Run prompts with --timings to record request latency.");
+        } else {
+            display_latency_report(&latency, count.min(limit_val));
+        }
+        return Ok(());
+    }
+
+    let stats = analyzer.get_usage_stats_between(since, until)?;
 
     if stats.total_requests == 0 {
-        println!("{} No usage data found", "ℹ️".blue());
-        if days.is_some() {
+        println!("{} No usage data found", sym("ℹ️").blue());
+        if since.is_some() || until.is_some() {
             println!("Try expanding the time range or check if you have any logged interactions.");
         }
         return Ok(());
@@ -33,7 +67,7 @@ pub async fn handle(
             let value_type = determine_value_type(tokens_only, requests_only);
 
             BarChart::render_time_series(
-                "📅 Daily Usage",
+                sym("📅 Daily Usage"),
                 &stats.daily_usage,
                 value_type,
                 50,
@@ -44,7 +78,7 @@ pub async fn handle(
             let value_type = determine_value_type(tokens_only, requests_only);
 
             BarChart::render_time_series(
-                "📊 Weekly Usage",
+                sym("📊 Weekly Usage"),
                 &stats.weekly_usage,
                 value_type,
                 50,
@@ -55,7 +89,7 @@ pub async fn handle(
             let value_type = determine_value_type(tokens_only, requests_only);
 
             BarChart::render_time_series(
-                "📈 Monthly Usage",
+                sym("📈 Monthly Usage"),
                 &stats.monthly_usage,
                 value_type,
                 50,
@@ -66,7 +100,7 @@ pub async fn handle(
             let value_type = determine_value_type(tokens_only, requests_only);
 
             BarChart::render_time_series(
-                "📊 Yearly Usage",
+                sym("📊 Yearly Usage"),
                 &stats.yearly_usage,
                 value_type,
                 50,
@@ -77,13 +111,48 @@ pub async fn handle(
             let value_type = determine_value_type(tokens_only, requests_only);
 
             BarChart::render_horizontal(
-                "🤖 Top Models by Usage",
+                sym("🤖 Top Models by Usage"),
                 &stats.model_usage,
                 value_type,
                 50,
                 count.min(limit_val),
             );
         }
+        Some(UsageCommands::Heatmap) => {
+            let value_type = determine_value_type(tokens_only, requests_only);
+
+            BarChart::render_heatmap(
+                sym("🔥 Activity by Day and Hour (local time)"),
+                &stats.activity_heatmap,
+                value_type,
+            );
+            BarChart::render_time_series(
+                sym("🕒 Activity by Hour of Day"),
+                &hourly_usage(&stats.activity_heatmap),
+                value_type,
+                50,
+                24,
+            );
+        }
+        Some(UsageCommands::Providers { count }) => {
+            let value_type = determine_value_type(tokens_only, requests_only);
+
+            BarChart::render_horizontal(
+                sym("🏢 Usage by Provider"),
+                &stats.provider_usage,
+                value_type,
+                50,
+                limit_val,
+            );
+            BarChart::render_stacked(
+                sym("📅 Daily Usage by Provider"),
+                &stats.daily_provider_usage,
+                value_type,
+                50,
+                count,
+            );
+        }
+        Some(UsageCommands::Latency { .. }) => unreachable!("handled above"),
         None => {
             // Default: show overview and top charts
             display_usage_overview(&stats);
@@ -91,7 +160,7 @@ pub async fn handle(
             if !tokens_only && !requests_only {
                 // Show both tokens and requests by default
                 BarChart::render_horizontal(
-                    "🤖 Top Models by Token Usage",
+                    sym("🤖 Top Models by Token Usage"),
                     &stats.model_usage,
                     "tokens",
                     50,
@@ -99,7 +168,7 @@ pub async fn handle(
                 );
 
                 BarChart::render_time_series(
-                    "📅 Recent Daily Usage (Tokens)",
+                    sym("📅 Recent Daily Usage (Tokens)"),
                     &stats.daily_usage,
                     "tokens",
                     50,
@@ -107,7 +176,7 @@ pub async fn handle(
                 );
             } else if tokens_only {
                 BarChart::render_horizontal(
-                    "🤖 Top Models by Token Usage",
+                    sym("🤖 Top Models by Token Usage"),
                     &stats.model_usage,
                     "tokens",
                     50,
@@ -115,7 +184,7 @@ pub async fn handle(
                 );
 
                 BarChart::render_time_series(
-                    "📅 Recent Daily Token Usage",
+                    sym("📅 Recent Daily Token Usage"),
                     &stats.daily_usage,
                     "tokens",
                     50,
@@ -123,7 +192,7 @@ pub async fn handle(
                 );
             } else if requests_only {
                 BarChart::render_horizontal(
-                    "🤖 Top Models by Request Count",
+                    sym("🤖 Top Models by Request Count"),
                     &stats.model_usage,
                     "requests",
                     50,
@@ -131,7 +200,7 @@ pub async fn handle(
                 );
 
                 BarChart::render_time_series(
-                    "📅 Recent Daily Request Count",
+                    sym("📅 Recent Daily Request Count"),
                     &stats.daily_usage,
                     "requests",
                     50,
@@ -154,3 +223,50 @@ fn determine_value_type(tokens_only: bool, requests_only: bool) -> &'static str
         "tokens" // Default to tokens when neither flag is set
     }
 }
+
+/// Parse a `--since`/`--until` value as a local date (YYYY-MM-DD) or an RFC 3339 timestamp.
+/// Dates expand to the start of the day for `--since` and the end of the day for `--until`.
+pub(crate) fn parse_date_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid date '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp",
+            value
+        )
+    })?;
+    let naive = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    }
+    .expect("valid time of day");
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| anyhow::anyhow!("Date '{}' does not exist in the local timezone", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_bound_day_range() {
+        let start = parse_date_bound("2024-03-10", false).unwrap();
+        let end = parse_date_bound("2024-03-10", true).unwrap();
+        assert!(end > start);
+        assert!(end - start < Duration::days(1));
+    }
+
+    #[test]
+    fn test_parse_date_bound_rfc3339_and_invalid() {
+        let ts = parse_date_bound("2024-03-10T12:00:00Z", false).unwrap();
+        assert_eq!(ts, Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap());
+        assert!(parse_date_bound("10/03/2024", false).is_err());
+    }
+}
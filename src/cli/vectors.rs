@@ -1,12 +1,26 @@
 //! Vector database commands
 
+use crate::analytics::vector_analysis;
 use crate::cli::VectorCommands;
-use crate::data::vector_db::VectorDatabase;
+use crate::data::vector_db::{VectorDatabase, VectorEntry};
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
 
-/// Handle vector database commands
-pub async fn handle(command: VectorCommands) -> Result<()> {
+/// Seed for k-means initialization, so the same database always gives the same clusters
+const CLUSTER_SEED: u64 = 42;
+const CLUSTER_MAX_ITERATIONS: usize = 100;
+/// Number of entries closest to a centroid shown to the model when labeling a cluster
+const LABEL_SAMPLES: usize = 5;
+
+/// Handle vector database commands. `provider` and `model` pick the model that labels
+/// clusters, and the embedding model of an empty database served with `serve`; the
+/// defaults are used when they are not given.
+pub async fn handle(
+    command: VectorCommands,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<()> {
     match command {
         VectorCommands::List => {
             let databases = VectorDatabase::list_databases()?;
@@ -15,11 +29,11 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                 println!("No vector databases found.");
                 println!(
                     "\n{} Create one by running: {}",
-                    "💡".yellow(),
+                    sym("💡").yellow(),
                     "lc embed -d <name> -m <model> \"your text\"".bold()
                 );
             } else {
-                println!("\n{} Vector databases:", "📊".bold().blue());
+                println!("\n{} Vector databases:", sym("📊").bold().blue());
 
                 for db_name in databases {
                     match VectorDatabase::new(&db_name) {
@@ -27,7 +41,12 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                             let count = db.count().unwrap_or(0);
                             let model_info = db.get_model_info().unwrap_or(None);
 
-                            print!("  {} {} ({} vectors)", "•".blue(), db_name.bold(), count);
+                            print!(
+                                "  {} {} ({} vectors)",
+                                sym("•").blue(),
+                                db_name.bold(),
+                                count
+                            );
 
                             if let Some((model, provider)) = model_info {
                                 print!(" - {}:{}", provider.dimmed(), model.dimmed());
@@ -36,7 +55,7 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                             println!();
                         }
                         Err(_) => {
-                            println!("  {} {} (error reading)", "•".red(), db_name.bold());
+                            println!("  {} {} (error reading)", sym("•").red(), db_name.bold());
                         }
                     }
                 }
@@ -53,7 +72,7 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
             let _ = VectorDatabase::new(&name)?;
             println!(
                 "{} Vector database '{}' created successfully",
-                "✓".green(),
+                sym("✓").green(),
                 name
             );
             println!(
@@ -71,8 +90,8 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
             // Ask for confirmation unless --yes is provided
             if !yes {
                 println!(
-                    "{} Are you sure you want to delete database '{}'? This cannot be undone.",
-                    "⚠️".yellow(),
+                    "{} Are you sure you want to delete database '{}'? It can be restored with 'lc undo'.",
+                    sym("⚠️").yellow(),
                     name.bold()
                 );
                 print!("Type 'yes' to confirm: ");
@@ -88,12 +107,16 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                 }
             }
 
-            VectorDatabase::delete_database(&name)?;
+            // Move to the trash instead of deleting so 'lc undo' can restore it
+            let db_path = VectorDatabase::embeddings_dir()?.join(format!("{}.db", name));
+            crate::trash::Trash::new()?
+                .move_files(&format!("vectors delete {}", name), &[db_path])?;
             println!(
                 "{} Vector database '{}' deleted successfully",
-                "✓".green(),
+                sym("✓").green(),
                 name
             );
+            println!("{}", "Run 'lc undo' to restore it.".dimmed());
         }
         VectorCommands::Info { name } => {
             // Check if database exists
@@ -106,7 +129,7 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
             let count = db.count()?;
             let model_info = db.get_model_info()?;
 
-            println!("\n{} Database: {}", "ℹ️".bold().blue(), name.bold());
+            println!("\n{} Database: {}", sym("ℹ️").bold().blue(), name.bold());
             println!("  Vector count: {}", count);
 
             if let Some((model, provider)) = model_info {
@@ -116,10 +139,28 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                 println!("  Model: {}", "Not set".dimmed());
                 println!("  Provider: {}", "Not set".dimmed());
             }
+            let tag_counts = db.tag_counts()?;
+            if !tag_counts.is_empty() {
+                let top: Vec<String> = tag_counts
+                    .iter()
+                    .take(10)
+                    .map(|(tag, count)| format!("{} ({})", tag, count))
+                    .collect();
+                println!("  Tags: {}", top.join(", "));
+            }
+            if let Some((model, provider)) = db.migration_target()? {
+                println!(
+                    "  Migration: to {}:{} in progress ({}/{} re-embedded)",
+                    provider,
+                    model,
+                    db.migrated_ids()?.len(),
+                    count
+                );
+            }
 
             // Show recent entries if any
             if count > 0 {
-                println!("\n{} Recent entries:", "📝".bold().blue());
+                println!("\n{} Recent entries:", sym("📝").bold().blue());
                 let vectors = db.get_all_vectors()?;
                 for (i, entry) in vectors.iter().take(10).enumerate() {
                     let preview = if entry.text.len() > 80 {
@@ -128,17 +169,10 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                         entry.text.clone()
                     };
 
-                    let source_info = if let Some(ref file_path) = entry.file_path {
-                        if let (Some(chunk_idx), Some(total_chunks)) =
-                            (entry.chunk_index, entry.total_chunks)
-                        {
-                            format!(" [{}:{}/{}]", file_path, chunk_idx + 1, total_chunks)
-                        } else {
-                            format!(" [{}]", file_path)
-                        }
-                    } else {
-                        String::new()
-                    };
+                    let source_info = entry
+                        .source()
+                        .map(|source| format!(" [{}]", source))
+                        .unwrap_or_default();
 
                     println!(
                         "  {}. {}{} ({})",
@@ -168,7 +202,11 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
             let count = db.count()?;
             let model_info = db.get_model_info()?;
 
-            println!("\n{} Vector database: {}", "📊".bold().blue(), name.bold());
+            println!(
+                "\n{} Vector database: {}",
+                sym("📊").bold().blue(),
+                name.bold()
+            );
             println!("Vectors: {}", count);
 
             if let Some((model, provider)) = model_info {
@@ -194,7 +232,7 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
             }
 
             if count > 0 {
-                println!("\n{} Recent entries:", "📝".bold().blue());
+                println!("\n{} Recent entries:", sym("📝").bold().blue());
                 let vectors = db.get_all_vectors()?;
                 for (i, entry) in vectors.iter().take(5).enumerate() {
                     let preview = if entry.text.len() > 60 {
@@ -203,17 +241,10 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
                         entry.text.clone()
                     };
 
-                    let source_info = if let Some(ref file_path) = entry.file_path {
-                        if let (Some(chunk_idx), Some(total_chunks)) =
-                            (entry.chunk_index, entry.total_chunks)
-                        {
-                            format!(" [{}:{}/{}]", file_path, chunk_idx + 1, total_chunks)
-                        } else {
-                            format!(" [{}]", file_path)
-                        }
-                    } else {
-                        String::new()
-                    };
+                    let source_info = entry
+                        .source()
+                        .map(|source| format!(" [{}]", source))
+                        .unwrap_or_default();
 
                     println!(
                         "  {}. {}{} ({})",
@@ -252,7 +283,7 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
             if !yes {
                 println!(
                     "{} Are you sure you want to clear {} vectors from database '{}'? This cannot be undone.",
-                    "⚠️".yellow(),
+                    sym("⚠️").yellow(),
                     count,
                     name.bold()
                 );
@@ -275,12 +306,607 @@ pub async fn handle(command: VectorCommands) -> Result<()> {
 
             println!(
                 "{} Vector database '{}' cleared successfully ({} vectors removed)",
-                "✓".green(),
+                sym("✓").green(),
                 name,
                 count
             );
         }
+        VectorCommands::Cluster { name, k, no_labels } => {
+            cluster(&name, k, !no_labels, provider, model).await?;
+        }
+        VectorCommands::Dedupe {
+            name,
+            threshold,
+            dry_run,
+            yes,
+        } => {
+            dedupe(&name, threshold, dry_run, yes)?;
+        }
+        VectorCommands::Serve {
+            name,
+            port,
+            host,
+            api_key,
+            no_auth,
+            cors_origins,
+        } => {
+            let access = ServeAccess {
+                api_key,
+                no_auth,
+                cors_origins,
+            };
+            serve(&name, host, port, access, provider, model).await?;
+        }
+        VectorCommands::Migrate { name, to, abort } => match to {
+            Some(to) if !abort => migrate(&name, &to).await?,
+            _ => {
+                let db = open_existing(&name)?;
+                if db.migration_target()?.is_none() {
+                    println!("No migration in progress for '{}'.", name);
+                    return Ok(());
+                }
+                db.abort_migration()?;
+                println!(
+                    "{} Migration of '{}' aborted, the current vectors are unchanged",
+                    sym("✓").green(),
+                    name
+                );
+            }
+        },
+        VectorCommands::Backup { name, out } => {
+            let out = out.unwrap_or_else(|| format!("{}.vectors.tar.gz", name).into());
+            let manifest = crate::vector_archive::backup(&name, &out)?;
+            println!(
+                "{} Backed up '{}' ({} entries{}) to {}",
+                sym("✓").green(),
+                name,
+                manifest.entries,
+                describe_model(&manifest),
+                out.display()
+            );
+        }
+        VectorCommands::Restore { file, name, force } => {
+            let (manifest, replaced) =
+                crate::vector_archive::restore(&file, name.as_deref(), force)?;
+            println!(
+                "{} Restored '{}' ({} entries{}), checksums verified",
+                sym("✓").green(),
+                name.as_deref().unwrap_or(&manifest.name),
+                manifest.entries,
+                describe_model(&manifest)
+            );
+            if replaced {
+                println!(
+                    "{}",
+                    "Run 'lc undo' to get the replaced database back.".dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// ", provider:model, N dims" for an archive manifest, empty for an empty database
+fn describe_model(manifest: &crate::vector_archive::Manifest) -> String {
+    match (&manifest.provider, &manifest.model, manifest.dimensions) {
+        (Some(provider), Some(model), Some(dims)) => {
+            format!(", {}:{}, {} dims", provider, model, dims)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Open an existing database
+fn open_existing(name: &str) -> Result<VectorDatabase> {
+    let databases = VectorDatabase::list_databases()?;
+    if !databases.contains(&name.to_string()) {
+        anyhow::bail!("Vector database '{}' not found", name);
+    }
+    VectorDatabase::new(name)
+}
+
+/// Load all entries of an existing database, oldest first
+fn load_entries(name: &str) -> Result<(VectorDatabase, Vec<VectorEntry>)> {
+    let db = open_existing(name)?;
+    let mut entries = db.get_all_vectors()?;
+    entries.sort_by_key(|entry| entry.id);
+
+    if let Some(first) = entries.first() {
+        let dimensions = first.vector.len();
+        if entries.iter().any(|entry| entry.vector.len() != dimensions) {
+            anyhow::bail!(
+                "Vector database '{}' mixes embeddings of different dimensions; re-embed it with a single model",
+                name
+            );
+        }
+    }
+    Ok((db, entries))
+}
+
+async fn cluster(
+    name: &str,
+    k: usize,
+    labels: bool,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<()> {
+    if k == 0 {
+        anyhow::bail!("Number of clusters must be at least 1");
+    }
+    let (_, entries) = load_entries(name)?;
+    if entries.is_empty() {
+        println!("Vector database '{}' is empty.", name);
+        return Ok(());
+    }
+
+    let vectors: Vec<Vec<f64>> = entries.iter().map(|entry| entry.vector.clone()).collect();
+    let clustering = vector_analysis::kmeans(&vectors, k, CLUSTER_MAX_ITERATIONS, CLUSTER_SEED);
+
+    // Largest clusters first
+    let mut clusters: Vec<Vec<usize>> = (0..clustering.centroids.len())
+        .map(|cluster| clustering.members(&vectors, cluster))
+        .filter(|members| !members.is_empty())
+        .collect();
+    clusters.sort_by_key(|members| std::cmp::Reverse(members.len()));
+
+    let labeler = if labels {
+        match Labeler::new(provider, model).await {
+            Ok(labeler) => Some(labeler),
+            Err(e) => {
+                eprintln!(
+                    "{} Could not set up a model for cluster labels: {}",
+                    sym("⚠️").yellow(),
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    println!(
+        "\n{} Clustered {} vectors from '{}' into {} clusters",
+        sym("📊").bold().blue(),
+        entries.len(),
+        name.bold(),
+        clusters.len()
+    );
+
+    for (i, members) in clusters.iter().enumerate() {
+        let samples: Vec<&str> = members
+            .iter()
+            .take(LABEL_SAMPLES)
+            .map(|&index| entries[index].text.as_str())
+            .collect();
+
+        let label = match &labeler {
+            Some(labeler) => match labeler.label(&samples).await {
+                Ok(label) => Some(label),
+                Err(e) => {
+                    eprintln!(
+                        "{} Could not label cluster {}: {}",
+                        sym("⚠️").yellow(),
+                        i + 1,
+                        e
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        println!(
+            "\n{}. {} ({} vectors)",
+            i + 1,
+            label.unwrap_or_else(|| format!("Cluster {}", i + 1)).bold(),
+            members.len()
+        );
+        for text in samples.iter().take(3) {
+            println!("   {} {}", "-".dimmed(), preview(text, 80));
+        }
     }
 
     Ok(())
 }
+
+/// Generates short cluster labels from sample texts
+struct Labeler {
+    client: crate::chat::LLMClient,
+    provider: String,
+    model: String,
+}
+
+impl Labeler {
+    async fn new(provider: Option<String>, model: Option<String>) -> Result<Self> {
+        let mut config = crate::config::Config::load()?;
+        let (provider, model) = crate::utils::resolve_model_and_provider(&config, provider, model)?;
+        let client = crate::chat::create_authenticated_client(&mut config, &provider).await?;
+        Ok(Self {
+            client,
+            provider,
+            model,
+        })
+    }
+
+    async fn label(&self, samples: &[&str]) -> Result<String> {
+        let excerpts: Vec<String> = samples
+            .iter()
+            .map(|text| format!("- {}", preview(text, 500)))
+            .collect();
+        let prompt = format!(
+            "These excerpts were grouped together because their embeddings are similar:\n\n{}\n\n\
+             Reply with a short label of 2 to 5 words describing what they have in common. \
+             Reply with the label only.",
+            excerpts.join("\n")
+        );
+        let (response, _, _) = crate::chat::send_chat_request_with_validation(
+            &self.client,
+            &self.model,
+            &prompt,
+            &[],
+            None,
+            None,
+            None,
+            &self.provider,
+            None,
+        )
+        .await?;
+
+        let label = response
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .trim_matches(|c: char| matches!(c, '"' | '\'' | '*' | '.' | '`'))
+            .to_string();
+        if label.is_empty() {
+            anyhow::bail!("the model returned an empty label");
+        }
+        Ok(label)
+    }
+}
+
+fn dedupe(name: &str, threshold: f64, dry_run: bool, yes: bool) -> Result<()> {
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        anyhow::bail!("Threshold must be greater than 0 and at most 1");
+    }
+    let (db, entries) = load_entries(name)?;
+
+    let vectors: Vec<Vec<f64>> = entries.iter().map(|entry| entry.vector.clone()).collect();
+    let groups = vector_analysis::find_duplicates(&vectors, threshold);
+    let duplicate_ids: Vec<i64> = groups
+        .iter()
+        .flat_map(|group| group.duplicates.iter().map(|&(index, _)| entries[index].id))
+        .collect();
+
+    if duplicate_ids.is_empty() {
+        println!(
+            "No near-duplicates found in '{}' (threshold {}).",
+            name, threshold
+        );
+        return Ok(());
+    }
+
+    println!(
+        "\n{} Found {} near-duplicates of {} entries in '{}' (threshold {})",
+        sym("🔍").bold().blue(),
+        duplicate_ids.len(),
+        groups.len(),
+        name.bold(),
+        threshold
+    );
+    for group in groups.iter().take(20) {
+        let kept = &entries[group.keep];
+        println!(
+            "\n  {} #{} {}",
+            "keep".green(),
+            kept.id,
+            preview(&kept.text, 70)
+        );
+        for &(index, similarity) in &group.duplicates {
+            let duplicate = &entries[index];
+            println!(
+                "  {} #{} {} {}",
+                "drop".red(),
+                duplicate.id,
+                preview(&duplicate.text, 60),
+                format!("({:.3})", similarity).dimmed()
+            );
+        }
+    }
+    if groups.len() > 20 {
+        println!("\n  ... and {} more groups", groups.len() - 20);
+    }
+
+    if dry_run {
+        println!("\n{}", "Dry run: nothing was removed.".dimmed());
+        return Ok(());
+    }
+
+    // Ask for confirmation unless --yes is provided
+    if !yes {
+        println!(
+            "\n{} Remove {} duplicate vectors from database '{}'? They can be restored with 'lc undo'.",
+            sym("⚠️").yellow(),
+            duplicate_ids.len(),
+            name.bold()
+        );
+        print!("Type 'yes' to confirm: ");
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if input.trim().to_lowercase() != "yes" {
+            println!("Dedupe cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Snapshot the database into the trash so 'lc undo' can restore the removed entries
+    crate::trash::Trash::new()?.snapshot_with(
+        &format!("vectors dedupe {}", name),
+        db.path(),
+        |dest| db.snapshot_to(dest),
+    )?;
+    let removed = db.delete_vectors(&duplicate_ids)?;
+
+    println!(
+        "{} Removed {} duplicate vectors from '{}'",
+        sym("✓").green(),
+        removed,
+        name
+    );
+    println!("{}", "Run 'lc undo' to restore them.".dimmed());
+    Ok(())
+}
+
+/// Who may call the retrieval API
+struct ServeAccess {
+    api_key: Option<String>,
+    no_auth: bool,
+    cors_origins: Vec<String>,
+}
+
+/// Whether `host` only accepts connections from this machine
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Serve the retrieval API for a database. Its entries' model embeds the queries; an
+/// empty database needs `-m` (and optionally `-p`) for the model of the documents added
+async fn serve(
+    name: &str,
+    host: String,
+    port: u16,
+    access: ServeAccess,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<()> {
+    if access.no_auth && !is_loopback(&host) {
+        anyhow::bail!(
+            "--no-auth is only allowed on a loopback host; pass --key to serve on {}",
+            host
+        );
+    }
+    let cors_origins = access
+        .cors_origins
+        .iter()
+        .map(|origin| {
+            origin
+                .parse::<axum::http::HeaderValue>()
+                .map_err(|_| anyhow::anyhow!("Invalid CORS origin '{}'", origin))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let api_key = match access.api_key {
+        Some(key) => Some(key),
+        None if access.no_auth => None,
+        None => {
+            let key = crate::services::proxy::generate_api_key();
+            println!("{} Generated API key: {}", sym("🔑").yellow(), key.bold());
+            Some(key)
+        }
+    };
+
+    let db = open_existing(name)?;
+    let mut config = crate::config::Config::load()?;
+    let (provider, model) = match db.get_model_info()? {
+        Some((model, provider)) => (provider, model),
+        None if model.is_some() => {
+            crate::utils::resolve_model_and_provider(&config, provider, model)?
+        }
+        None => anyhow::bail!(
+            "Vector database '{}' is empty; pass the embedding model for new documents with -m",
+            name
+        ),
+    };
+    let client = crate::chat::create_authenticated_client(&mut config, &provider).await?;
+
+    let state = crate::services::vector_server::VectorServerState {
+        db,
+        client,
+        model,
+        provider,
+        api_key,
+        cors_origins,
+    };
+    crate::services::vector_server::start_vector_server(state, name, host, port).await
+}
+
+/// Re-embed every entry with `to`, staging the new vectors next to the old ones, and
+/// swap them in once all entries are re-embedded and the new vectors check out
+async fn migrate(name: &str, to: &str) -> Result<()> {
+    // Entries of mixed dimensions are fine here, migrating is how they get fixed
+    let db = open_existing(name)?;
+    let mut entries = db.get_all_vectors()?;
+    entries.sort_by_key(|entry| entry.id);
+    if entries.is_empty() {
+        println!("Vector database '{}' is empty.", name);
+        return Ok(());
+    }
+
+    let mut config = crate::config::Config::load()?;
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(&config, None, Some(to.to_string()))?;
+    if db.migration_target()?.is_none()
+        && entries
+            .iter()
+            .all(|entry| entry.model == model && entry.provider == provider)
+    {
+        println!(
+            "Vector database '{}' already uses {}:{}.",
+            name, provider, model
+        );
+        return Ok(());
+    }
+    db.begin_migration(&model, &provider)?;
+    let client = crate::chat::create_authenticated_client(&mut config, &provider).await?;
+
+    let migrated = db.migrated_ids()?;
+    let pending: Vec<&VectorEntry> = entries
+        .iter()
+        .filter(|entry| !migrated.contains(&entry.id))
+        .collect();
+    println!(
+        "{} Migrating '{}' to {}:{}",
+        sym("🔄").blue(),
+        name.bold(),
+        provider,
+        model
+    );
+    if !migrated.is_empty() {
+        println!(
+            "{} Resuming: {} of {} entries already re-embedded",
+            "↻".blue(),
+            entries.len() - pending.len(),
+            entries.len()
+        );
+    }
+
+    let done = entries.len() - pending.len();
+    for (i, entry) in pending.iter().enumerate() {
+        let vector = match re_embed(&client, &model, entry).await {
+            Ok(vector) => vector,
+            Err(e) => anyhow::bail!(
+                "Failed to re-embed entry #{}: {}. Run the same command again to resume",
+                entry.id,
+                e
+            ),
+        };
+        db.stage_migrated_vector(entry.id, &vector)?;
+
+        let progress = done + i + 1;
+        if progress.is_multiple_of(25) || i + 1 == pending.len() {
+            println!(
+                "  {} {}/{} re-embedded",
+                sym("•").blue(),
+                progress,
+                entries.len()
+            );
+        }
+    }
+
+    // Verify the staged vectors before the old ones are replaced
+    let staged = db.migrated_vectors()?;
+    let dimensions = verify_migrated(&entries, &staged)?;
+
+    // Snapshot the database into the trash so 'lc undo' can restore the old vectors
+    crate::trash::Trash::new()?.snapshot_with(
+        &format!("vectors migrate {}", name),
+        db.path(),
+        |dest| db.snapshot_to(dest),
+    )?;
+    let swapped = db.complete_migration()?;
+
+    println!(
+        "{} Migrated {} vectors in '{}' to {}:{} ({} dimensions)",
+        sym("✓").green(),
+        swapped,
+        name,
+        provider,
+        model,
+        dimensions
+    );
+    println!("{}", "Run 'lc undo' to restore the old vectors.".dimmed());
+    Ok(())
+}
+
+/// The new model's vector of an entry; images are embedded again from their file
+async fn re_embed(
+    client: &crate::chat::LLMClient,
+    model: &str,
+    entry: &VectorEntry,
+) -> Result<Vec<f64>> {
+    if entry.is_image() {
+        let path = entry.file_path.as_deref().unwrap_or_default();
+        let (vector, _) =
+            crate::cli::embed::embed_image_file(client, model, std::path::Path::new(path)).await?;
+        return Ok(vector);
+    }
+    let request = crate::provider::EmbeddingRequest {
+        model: model.to_string(),
+        input: entry.text.clone(),
+        encoding_format: Some("float".to_string()),
+    };
+    client
+        .embeddings(&request)
+        .await?
+        .data
+        .into_iter()
+        .next()
+        .map(|data| data.embedding)
+        .ok_or_else(|| anyhow::anyhow!("no embedding data in response"))
+}
+
+/// Check that every entry has a usable new vector and that they all have the same
+/// number of dimensions, returning it
+fn verify_migrated(
+    entries: &[VectorEntry],
+    staged: &std::collections::HashMap<i64, Vec<f64>>,
+) -> Result<usize> {
+    let mut dimensions = None;
+    for entry in entries {
+        let Some(vector) = staged.get(&entry.id) else {
+            anyhow::bail!(
+                "Entry #{} has not been re-embedded; run the same command again to resume",
+                entry.id
+            );
+        };
+        if vector.is_empty()
+            || vector.iter().any(|x| !x.is_finite())
+            || vector.iter().all(|&x| x == 0.0)
+        {
+            anyhow::bail!(
+                "The new embedding of entry #{} is empty or invalid; abort the migration with --abort",
+                entry.id
+            );
+        }
+        match dimensions {
+            None => dimensions = Some(vector.len()),
+            Some(expected) if expected != vector.len() => anyhow::bail!(
+                "The new embeddings have different dimensions ({} and {}); abort the migration with --abort",
+                expected,
+                vector.len()
+            ),
+            Some(_) => {}
+        }
+    }
+    Ok(dimensions.unwrap_or_default())
+}
+
+/// First `max_chars` characters of a text on a single line
+fn preview(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
@@ -0,0 +1,177 @@
+//! Watch mode: re-run a prompt whenever the attached files change
+//!
+//! Files are polled for changes in modification time or size, which works the same
+//! on every platform and for editors that save by replacing the file. A run starts
+//! once the files have been quiet for the debounce interval, so a burst of saves
+//! (or a formatter rewriting the file) triggers a single run.
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::cli::AudioMode;
+
+/// How often the watched files are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Modification time and size of each watched file (`None` while it is missing)
+type Snapshot = Vec<Option<(SystemTime, u64)>>;
+
+/// Options of the prompt re-run on every change
+pub struct WatchPrompt {
+    pub prompt: String,
+    pub attachments: Vec<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub system_prompt: Option<String>,
+    pub max_tokens: Option<String>,
+    pub temperature: Option<String>,
+    pub tools: Option<String>,
+    pub stream: bool,
+}
+
+/// Handle `lc watch`: run the prompt, then again after every change to the attached files
+pub async fn handle(options: WatchPrompt, debounce_ms: u64) -> Result<()> {
+    for file in &options.attachments {
+        if !std::path::Path::new(file).is_file() {
+            anyhow::bail!("Cannot watch '{}': file not found", file);
+        }
+    }
+
+    let paths: Vec<PathBuf> = options.attachments.iter().map(PathBuf::from).collect();
+    let debounce = Duration::from_millis(debounce_ms);
+
+    eprintln!(
+        "{} Watching {} (Ctrl+C to stop)",
+        sym("👀").dimmed(),
+        options.attachments.join(", ")
+    );
+
+    let mut last = snapshot(&paths);
+    let mut run = 1;
+    run_prompt(&options).await;
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let current = snapshot(&paths);
+        if current == last {
+            continue;
+        }
+
+        // Wait for the files to settle so a burst of saves runs the prompt once
+        let mut settled = current;
+        loop {
+            tokio::time::sleep(debounce).await;
+            let again = snapshot(&paths);
+            if again == settled {
+                break;
+            }
+            settled = again;
+        }
+
+        let changed = changed_files(&options.attachments, &last, &settled);
+        last = settled;
+        if changed.is_empty() {
+            continue;
+        }
+
+        run += 1;
+        println!("\n{}\n", separator(run, &changed).dimmed());
+        run_prompt(&options).await;
+    }
+}
+
+/// Run the prompt with the current contents of the attached files. Errors are printed
+/// so that watching continues.
+async fn run_prompt(options: &WatchPrompt) {
+    if let Err(e) = crate::cli::prompts::handle_direct(
+        options.prompt.clone(),
+        options.provider.clone(),
+        options.model.clone(),
+        options.system_prompt.clone(),
+        options.max_tokens.clone(),
+        options.temperature.clone(),
+        options.attachments.clone(),
+        Vec::new(),
+        Vec::new(),
+        AudioMode::Auto,
+        options.tools.clone(),
+        None,
+        None,
+        options.stream,
+    )
+    .await
+    {
+        eprintln!("{} {}", "Error:".red(), e);
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    paths
+        .iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+/// Files whose snapshot differs between `before` and `after`
+fn changed_files(files: &[String], before: &Snapshot, after: &Snapshot) -> Vec<String> {
+    files
+        .iter()
+        .zip(before.iter().zip(after))
+        .filter(|(_, (before, after))| before != after)
+        .map(|(file, _)| file.clone())
+        .collect()
+}
+
+fn separator(run: usize, changed: &[String]) -> String {
+    format!(
+        "{} run {} {} {} changed {} {} {}",
+        sym("────"),
+        run,
+        sym("·"),
+        changed.join(", "),
+        sym("·"),
+        chrono::Local::now().format("%H:%M:%S"),
+        sym("────")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.rs");
+        let b = dir.path().join("b.rs");
+        std::fs::write(&a, "fn a() {}").unwrap();
+        std::fs::write(&b, "fn b() {}").unwrap();
+        let files = vec!["a.rs".to_string(), "b.rs".to_string()];
+        let paths = vec![a.clone(), b.clone()];
+
+        let before = snapshot(&paths);
+        assert_eq!(before, snapshot(&paths));
+
+        std::fs::write(&b, "fn b() { todo!() }").unwrap();
+        let after = snapshot(&paths);
+        assert_eq!(changed_files(&files, &before, &after), vec!["b.rs"]);
+
+        std::fs::remove_file(&a).unwrap();
+        let removed = snapshot(&paths);
+        assert_eq!(removed[0], None);
+        assert_eq!(changed_files(&files, &after, &removed), vec!["a.rs"]);
+    }
+
+    #[test]
+    fn test_separator_names_run_and_files() {
+        let line = separator(3, &["src/main.rs".to_string()]);
+        assert!(line.contains("run 3"));
+        assert!(line.contains("src/main.rs changed"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Web chat proxy commands
 
 use crate::cli::WebChatProxyCommands;
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use axum::{
     extract::State,
@@ -26,7 +27,7 @@ pub async fn handle(command: WebChatProxyCommands) -> Result<()> {
 }
 
 async fn handle_start(port: u16, host: String, cors: bool) -> Result<()> {
-    println!("{} Starting Web Chat Proxy server...", "🌐".blue());
+    println!("{} Starting Web Chat Proxy server...", sym("🌐").blue());
     println!("  {} {}:{}", "Address:".bold(), host, port);
     println!(
         "  {} {}",
@@ -39,23 +40,28 @@ async fn handle_start(port: u16, host: String, cors: bool) -> Result<()> {
     );
 
     println!("\n{}", "Available endpoints:".bold().blue());
-    println!("  {} http://{}:{}/", "•".blue(), host, port);
+    println!("  {} http://{}:{}/", sym("•").blue(), host, port);
     println!("    Web interface for chat");
-    println!("  {} http://{}:{}/models", "•".blue(), host, port);
+    println!("  {} http://{}:{}/models", sym("•").blue(), host, port);
     println!("    List available models");
-    println!("  {} http://{}:{}/v1/models", "•".blue(), host, port);
+    println!("  {} http://{}:{}/v1/models", sym("•").blue(), host, port);
     println!("    OpenAI-compatible models endpoint");
-    println!("  {} http://{}:{}/chat/completions", "•".blue(), host, port);
+    println!(
+        "  {} http://{}:{}/chat/completions",
+        sym("•").blue(),
+        host,
+        port
+    );
     println!("    Chat completions endpoint");
     println!(
         "  {} http://{}:{}/v1/chat/completions",
-        "•".blue(),
+        sym("•").blue(),
         host,
         port
     );
     println!("    OpenAI-compatible chat endpoint");
 
-    println!("\n{} Press Ctrl+C to stop the server\n", "💡".yellow());
+    println!("\n{} Press Ctrl+C to stop the server\n", sym("💡").yellow());
 
     // Start the webchat proxy server
     start_webchat_server(host, port, cors).await
@@ -81,7 +87,7 @@ async fn start_webchat_server(host: String, port: u16, cors: bool) -> Result<()>
     }
 
     let addr = format!("{}:{}", host, port);
-    println!("{} Server listening on http://{}", "✓".green(), addr);
+    println!("{} Server listening on http://{}", sym("✓").green(), addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     axum::serve(listener, app).await?;
@@ -0,0 +1,40 @@
+//! Web UI command
+
+use anyhow::Result;
+
+/// Handle `lc serve-webui`
+pub async fn handle(
+    port: u16,
+    host: String,
+    provider: Option<String>,
+    model: Option<String>,
+    vectordb: Option<String>,
+    tools: Option<String>,
+) -> Result<()> {
+    #[cfg(feature = "webui")]
+    {
+        if let Some(ref provider_name) = provider {
+            let config = crate::config::Config::load()?;
+            if !config.has_provider(provider_name) {
+                anyhow::bail!(
+                    "Provider '{}' not found. Add it first with 'lc providers add'",
+                    provider_name
+                );
+            }
+        }
+
+        let state = crate::services::webui::WebUiState {
+            provider,
+            model,
+            vectordb,
+            tools,
+        };
+        crate::services::webui::start_webui_server(host, port, state).await
+    }
+
+    #[cfg(not(feature = "webui"))]
+    {
+        let _ = (port, host, provider, model, vectordb, tools);
+        anyhow::bail!("Web UI feature not enabled. Build with --features webui");
+    }
+}
@@ -0,0 +1,321 @@
+//! Anthropic Messages API (`/v1/messages`)
+//!
+//! Providers whose chat URL is an Anthropic-style `/messages` endpoint (on Anthropic's API,
+//! or under an `/anthropic/` path as compatible gateways expose it), or that set the
+//! `chat_api = "anthropic"` var, get requests in the Messages schema instead of the OpenAI
+//! one, unless a chat template for the model takes care of the translation:
+//!
+//! - system messages become the top-level `system` field
+//! - images become `image` blocks (base64 for data URLs, `url` sources otherwise)
+//! - assistant tool calls become `tool_use` blocks and tool results `tool_result` blocks in
+//!   the next user turn, so the tool execution loop works unchanged
+//! - the key is sent as `x-api-key` with an `anthropic-version` header
+//!
+//! Responses are turned back into a [`ChatResponse`] with the text and `tool_use` blocks as
+//! the message content and tool calls.
+
+use crate::core::provider::{
+    ChatRequest, ChatResponse, Choice, ContentPart, FunctionCall, Message, MessageContent,
+    ResponseMessage, ToolCall,
+};
+use anyhow::{bail, Result};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// `anthropic-version` header sent unless the provider sets its own
+pub const API_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is required by the API; used when the request has none
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Whether a provider speaks the Messages API. Other `/messages` endpoints keep the
+/// OpenAI schema and bearer auth unless they set the `chat_api` var.
+pub fn is_messages_api(chat_url: &str, vars: Option<&HashMap<String, String>>) -> bool {
+    match vars.and_then(|vars| vars.get("chat_api")) {
+        Some(api) => matches!(api.to_lowercase().as_str(), "anthropic" | "claude"),
+        None => {
+            let url = chat_url.trim_end_matches('/').to_lowercase();
+            url.ends_with("/messages")
+                && (url.contains("anthropic.com/") || url.contains("/anthropic/"))
+        }
+    }
+}
+
+/// The Messages API body of a chat request
+pub fn request_body(request: &ChatRequest) -> Result<Value> {
+    let mut system = Vec::new();
+    let mut messages: Vec<(String, Vec<Value>)> = Vec::new();
+
+    for message in &request.messages {
+        let (role, blocks) = match message.role.as_str() {
+            "system" => {
+                system.extend(message.get_text_content().cloned());
+                continue;
+            }
+            "tool" => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": message.tool_call_id.clone().unwrap_or_default(),
+                    "content": message.get_text_content().cloned().unwrap_or_default(),
+                })],
+            ),
+            "assistant" => ("assistant", assistant_blocks(message)?),
+            _ => ("user", content_blocks(&message.content_type)?),
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+        // Turns must alternate, so consecutive messages of a role (such as the results
+        // of parallel tool calls) are merged
+        match messages.last_mut() {
+            Some((last_role, last_blocks)) if last_role == role => last_blocks.extend(blocks),
+            _ => messages.push((role.to_string(), blocks)),
+        }
+    }
+
+    let mut body = Map::new();
+    body.insert("model".to_string(), json!(request.model));
+    body.insert(
+        "max_tokens".to_string(),
+        json!(request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS)),
+    );
+    if !system.is_empty() {
+        body.insert("system".to_string(), json!(system.join("\n\n")));
+    }
+    body.insert(
+        "messages".to_string(),
+        messages
+            .into_iter()
+            .map(|(role, content)| json!({"role": role, "content": content}))
+            .collect(),
+    );
+    if let Some(temperature) = request.temperature {
+        body.insert("temperature".to_string(), json!(temperature));
+    }
+    if let Some(tools) = request.tools.as_ref().filter(|tools| !tools.is_empty()) {
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.function.name,
+                    "description": tool.function.description,
+                    "input_schema": tool.function.parameters,
+                })
+            })
+            .collect();
+        body.insert("tools".to_string(), Value::Array(tools));
+    }
+    if request.stream == Some(true) {
+        body.insert("stream".to_string(), json!(true));
+    }
+    Ok(Value::Object(body))
+}
+
+/// Text and `tool_use` blocks of an assistant turn
+fn assistant_blocks(message: &Message) -> Result<Vec<Value>> {
+    let mut blocks = content_blocks(&message.content_type)?;
+    for call in message.tool_calls.iter().flatten() {
+        let input: Value = serde_json::from_str(&call.function.arguments)
+            .unwrap_or_else(|_| Value::Object(Map::new()));
+        blocks.push(json!({
+            "type": "tool_use",
+            "id": call.id,
+            "name": call.function.name,
+            "input": input,
+        }));
+    }
+    Ok(blocks)
+}
+
+fn content_blocks(content: &MessageContent) -> Result<Vec<Value>> {
+    let parts = match content {
+        MessageContent::Text { content } => {
+            return Ok(content
+                .iter()
+                .filter(|text| !text.is_empty())
+                .map(|text| json!({"type": "text", "text": text}))
+                .collect());
+        }
+        MessageContent::Multimodal { content } => content,
+    };
+    let mut blocks = Vec::new();
+    for part in parts {
+        match part {
+            ContentPart::Text { text } => blocks.push(json!({"type": "text", "text": text})),
+            ContentPart::ImageUrl { image_url } => blocks.push(image_block(&image_url.url)),
+            ContentPart::InputAudio { .. } => {
+                bail!("The Anthropic Messages API doesn't accept audio input")
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+fn image_block(url: &str) -> Value {
+    let base64 = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"));
+    match base64 {
+        Some((media_type, data)) => json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        }),
+        None => json!({"type": "image", "source": {"type": "url", "url": url}}),
+    }
+}
+
+/// A Messages API response as a chat response: text blocks joined as the content and
+/// `tool_use` blocks as tool calls
+pub fn to_chat_response(response: &Value) -> Option<ChatResponse> {
+    let blocks = response.get("content")?.as_array()?;
+    let text: String = blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+        .filter_map(|block| block.get("text").and_then(Value::as_str))
+        .collect();
+    let tool_calls: Vec<ToolCall> = blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("tool_use"))
+        .map(|block| ToolCall {
+            id: string_field(block, "id"),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: string_field(block, "name"),
+                arguments: block
+                    .get("input")
+                    .map_or_else(|| "{}".to_string(), Value::to_string),
+            },
+        })
+        .collect();
+
+    Some(ChatResponse {
+        choices: vec![Choice {
+            message: ResponseMessage {
+                role: "assistant".to_string(),
+                content: (!text.is_empty() || tool_calls.is_empty()).then_some(text),
+                tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            },
+        }],
+    })
+}
+
+fn string_field(block: &Value, name: &str) -> String {
+    block
+        .get(name)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Text of a streamed `content_block_delta` event
+pub fn stream_text(event: &Value) -> Option<&str> {
+    if event.get("type")?.as_str()? != "content_block_delta" {
+        return None;
+    }
+    event.get("delta")?.get("text")?.as_str()
+}
+
+/// Whether a streamed event ends the message
+pub fn is_stream_end(event: &Value) -> bool {
+    event.get("type").and_then(Value::as_str) == Some("message_stop")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::provider::{Function, Tool};
+
+    #[test]
+    fn test_request_body() {
+        let call = ToolCall {
+            id: "toolu_1".to_string(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: "get_weather".to_string(),
+                arguments: "{\"city\":\"Paris\"}".to_string(),
+            },
+        };
+        let mut system = Message::user("Be brief.".to_string());
+        system.role = "system".to_string();
+        let request = ChatRequest {
+            model: "claude-3-5-sonnet-latest".to_string(),
+            messages: vec![
+                system,
+                Message::user_with_images(
+                    "Weather?".to_string(),
+                    &["data:image/png;base64,AAAA".to_string()],
+                ),
+                Message::assistant_with_tool_calls(vec![call]),
+                Message::tool_result("toolu_1".to_string(), "sunny".to_string()),
+            ],
+            max_tokens: None,
+            temperature: Some(0.2),
+            tools: Some(vec![Tool {
+                tool_type: "function".to_string(),
+                function: Function {
+                    name: "get_weather".to_string(),
+                    description: "Current weather".to_string(),
+                    parameters: json!({"type": "object"}),
+                },
+            }]),
+            stream: None,
+        };
+
+        let body = request_body(&request).unwrap();
+        assert_eq!(body["system"], "Be brief.");
+        assert_eq!(body["max_tokens"], 4096);
+        assert_eq!(body["tools"][0]["input_schema"], json!({"type": "object"}));
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[0]["content"][1]["source"]["media_type"],
+            "image/png"
+        );
+        assert_eq!(messages[1]["content"][0]["type"], "tool_use");
+        assert_eq!(messages[1]["content"][0]["input"]["city"], "Paris");
+        assert_eq!(messages[2]["role"], "user");
+        assert_eq!(messages[2]["content"][0]["tool_use_id"], "toolu_1");
+        assert!(body.get("stream").is_none());
+    }
+
+    #[test]
+    fn test_to_chat_response() {
+        let response = json!({"content": [
+            {"type": "text", "text": "Let me check."},
+            {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}},
+        ]});
+        let response = to_chat_response(&response).unwrap();
+        let message = &response.choices[0].message;
+        assert_eq!(message.content.as_deref(), Some("Let me check."));
+        let calls = message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].id, "toolu_1");
+        assert_eq!(calls[0].function.arguments, "{\"city\":\"Paris\"}");
+
+        let delta =
+            json!({"type": "content_block_delta", "delta": {"type": "text_delta", "text": "Hi"}});
+        assert_eq!(stream_text(&delta), Some("Hi"));
+        assert!(is_stream_end(&json!({"type": "message_stop"})));
+        assert!(is_messages_api(
+            "https://api.anthropic.com/v1/messages",
+            None
+        ));
+        assert!(is_messages_api(
+            "https://api.deepseek.com/anthropic/v1/messages",
+            None
+        ));
+        assert!(!is_messages_api(
+            "https://api.example.com/v1/messages",
+            None
+        ));
+        assert!(!is_messages_api(
+            "https://api.anthropic.com/v1/chat/completions",
+            None
+        ));
+        let vars = HashMap::from([("chat_api".to_string(), "anthropic".to_string())]);
+        assert!(is_messages_api(
+            "https://gateway.example.com/v1/messages",
+            Some(&vars)
+        ));
+    }
+}
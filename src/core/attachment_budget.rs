@@ -0,0 +1,454 @@
+//! Token budget for attached files (`--attach-budget`, `--attach-strategy`)
+//!
+//! Attachments that would not fit in the request are reduced before it is sent,
+//! rather than failing at the provider or being cut off by the context truncation.
+//! The budget is `--attach-budget` tokens, or whatever the model's context window
+//! leaves after the prompt and the answer. What was cut, and how, is reported on
+//! stderr before the request goes out. With the `embed` strategy, the parts of the
+//! attachments relevant to the prompt are retrieved instead (see `attachment_rag`).
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::sync::RwLock;
+
+use crate::chat::LLMClient;
+use crate::utils::cli_utils::Attachment;
+
+/// How attachments over the budget are reduced
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AttachStrategy {
+    /// Keep the beginning of the attachments, dropping what comes after the budget
+    Head,
+    /// Keep the end of the attachments, dropping what comes before the budget
+    Tail,
+    /// Give every file a fair share and keep the start and end of files over theirs
+    #[default]
+    PerFile,
+    /// Give every file a fair share and summarize the part of a file beyond it
+    Summarize,
+    /// Embed the attachments for this request only and send the chunks closest to the
+    /// prompt (needs --attach-embed-model)
+    Embed,
+    /// Stop with an error instead of sending reduced attachments
+    Fail,
+}
+
+impl AttachStrategy {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Head => "head",
+            Self::Tail => "tail",
+            Self::PerFile => "per-file",
+            Self::Summarize => "summarize",
+            Self::Embed => "embed",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AttachmentBudget {
+    /// Token budget; derived from the model's context window when not set
+    pub max_tokens: Option<usize>,
+    pub strategy: AttachStrategy,
+    /// Embedding model (`provider:model` or alias) of the `embed` strategy
+    pub embedding_model: Option<String>,
+}
+
+static CURRENT: RwLock<Option<AttachmentBudget>> = RwLock::new(None);
+
+/// Output tokens reserved when the model's metadata has no limit
+const DEFAULT_OUTPUT_RESERVE: usize = 4096;
+/// Overflow beyond this many characters is cut (keeping start and end) before it is summarized
+const MAX_SUMMARY_INPUT_CHARS: usize = 200_000;
+
+/// Set the attachment budget for this invocation
+pub fn set(max_tokens: Option<usize>, strategy: AttachStrategy, embedding_model: Option<String>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(AttachmentBudget {
+            max_tokens,
+            strategy,
+            embedding_model: embedding_model.filter(|m| !m.trim().is_empty()),
+        });
+    }
+}
+
+/// The attachment budget for this invocation
+pub fn current() -> AttachmentBudget {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|current| current.clone())
+        .unwrap_or_default()
+}
+
+/// What happened to an attachment that did not fit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reduction {
+    pub name: String,
+    pub tokens: usize,
+    pub kept_tokens: usize,
+    pub how: &'static str,
+}
+
+/// How much of an attachment to keep
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Keep {
+    All,
+    Head(usize),
+    Tail(usize),
+    HeadAndTail(usize),
+    /// Keep the start and summarize the rest, each in half the tokens
+    Summarized(usize),
+}
+
+/// Read the attachments, fit them into the budget and format them for the prompt
+pub async fn prepare(
+    paths: &[String],
+    prompt: &str,
+    client: &LLMClient,
+    model: &str,
+    provider: &str,
+) -> Result<String> {
+    let mut attachments = crate::utils::cli_utils::load_attachments(paths).await?;
+    let settings = current();
+    let counter = crate::token_utils::TokenCounter::new(model).ok();
+    let count = |text: &str| match &counter {
+        Some(counter) => counter.count_tokens(text),
+        None => text.len() / 4,
+    };
+
+    let budget = match settings.max_tokens {
+        Some(budget) => Some(budget),
+        None => crate::chat::get_model_metadata(provider, model)
+            .await
+            .and_then(|metadata| {
+                let context = metadata.context_length? as usize;
+                let output = metadata
+                    .max_output_tokens
+                    .map_or(DEFAULT_OUTPUT_RESERVE, |t| t as usize);
+                Some(context.saturating_sub(output + count(prompt)))
+            }),
+    };
+    let Some(budget) = budget else {
+        return Ok(crate::utils::cli_utils::format_attachments(&attachments));
+    };
+
+    let sizes: Vec<usize> = attachments.iter().map(|a| count(&a.content)).collect();
+    let total: usize = sizes.iter().sum();
+    if total <= budget {
+        return Ok(crate::utils::cli_utils::format_attachments(&attachments));
+    }
+    if settings.strategy == AttachStrategy::Fail {
+        anyhow::bail!(
+            "Attachments use about {} tokens, over the budget of {}. Pick how to reduce them with --attach-strategy, or raise --attach-budget",
+            format_tokens(total),
+            format_tokens(budget)
+        );
+    }
+
+    eprintln!(
+        "{} Attachments use ~{} tokens, over the {} budget; reducing them with '{}':",
+        sym("📎").yellow(),
+        format_tokens(total),
+        format_tokens(budget),
+        settings.strategy.name()
+    );
+
+    let mut strategy = settings.strategy;
+    if strategy == AttachStrategy::Embed {
+        let Some(embedding_model) = &settings.embedding_model else {
+            anyhow::bail!(
+                "--attach-strategy embed needs an embedding model: pass --attach-embed-model <provider:model>"
+            );
+        };
+        match crate::core::attachment_rag::retrieve(
+            &attachments,
+            prompt,
+            budget,
+            embedding_model,
+            count,
+        )
+        .await
+        {
+            Ok(excerpts) => return Ok(excerpts),
+            Err(e) => {
+                eprintln!(
+                    "   {} Could not embed the attachments, keeping the start and end of each: {}",
+                    sym("⚠️").yellow(),
+                    e
+                );
+                strategy = AttachStrategy::PerFile;
+            }
+        }
+    }
+
+    let plan = plan(&sizes, budget, strategy);
+    let mut included = Vec::with_capacity(attachments.len());
+    for ((attachment, &tokens), keep) in attachments.iter_mut().zip(&sizes).zip(plan) {
+        let reduction = match keep {
+            Keep::All => {
+                included.push(true);
+                continue;
+            }
+            Keep::Summarized(kept) => {
+                let (head, overflow) = split_at_tokens(&attachment.content, tokens, kept / 2);
+                match summarize(&overflow, kept - kept / 2, client, model, provider).await {
+                    Ok(summary) => {
+                        attachment.content = format!(
+                            "{}\n[... the rest of the file, summarized: ...]\n{}",
+                            head, summary
+                        );
+                        Reduction {
+                            name: attachment.name.clone(),
+                            tokens,
+                            kept_tokens: kept,
+                            how: "kept the start, summarized the rest",
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "   {} Could not summarize {}: {}",
+                            sym("⚠️").yellow(),
+                            attachment.name,
+                            e
+                        );
+                        apply(attachment, tokens, Keep::HeadAndTail(kept))
+                    }
+                }
+            }
+            keep => apply(attachment, tokens, keep),
+        };
+        report(&reduction);
+        included.push(reduction.kept_tokens > 0);
+    }
+    let mut included = included.into_iter();
+    attachments.retain(|_| included.next().unwrap_or(true));
+
+    Ok(crate::utils::cli_utils::format_attachments(&attachments))
+}
+
+/// Decide how much of each attachment (by token count) to keep within `budget`
+fn plan(sizes: &[usize], budget: usize, strategy: AttachStrategy) -> Vec<Keep> {
+    let mut plan = vec![Keep::All; sizes.len()];
+    match strategy {
+        AttachStrategy::Head | AttachStrategy::Tail => {
+            let mut remaining = budget;
+            let mut order: Vec<usize> = (0..sizes.len()).collect();
+            if strategy == AttachStrategy::Tail {
+                order.reverse();
+            }
+            for i in order {
+                if sizes[i] > remaining {
+                    plan[i] = if strategy == AttachStrategy::Head {
+                        Keep::Head(remaining)
+                    } else {
+                        Keep::Tail(remaining)
+                    };
+                }
+                remaining = remaining.saturating_sub(sizes[i]);
+            }
+        }
+        AttachStrategy::PerFile
+        | AttachStrategy::Summarize
+        | AttachStrategy::Embed
+        | AttachStrategy::Fail => {
+            for (i, share) in fair_shares(sizes, budget).into_iter().enumerate() {
+                if share < sizes[i] {
+                    plan[i] = if strategy == AttachStrategy::Summarize {
+                        Keep::Summarized(share)
+                    } else {
+                        Keep::HeadAndTail(share)
+                    };
+                }
+            }
+        }
+    }
+    plan
+}
+
+/// Split the budget evenly, passing what small files don't need on to larger ones
+fn fair_shares(sizes: &[usize], budget: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| sizes[i]);
+
+    let mut shares = vec![0; sizes.len()];
+    let mut remaining = budget;
+    for (position, &i) in order.iter().enumerate() {
+        let share = remaining / (sizes.len() - position);
+        shares[i] = sizes[i].min(share);
+        remaining -= shares[i];
+    }
+    shares
+}
+
+/// Cut an attachment down as planned
+fn apply(attachment: &mut Attachment, tokens: usize, keep: Keep) -> Reduction {
+    let content = &attachment.content;
+    let (content, kept, how) = match keep {
+        Keep::Head(kept) => (
+            split_at_tokens(content, tokens, kept).0,
+            kept,
+            "kept the start",
+        ),
+        Keep::Tail(kept) => (
+            split_at_tokens(content, tokens, tokens - kept).1,
+            kept,
+            "kept the end",
+        ),
+        Keep::HeadAndTail(kept) | Keep::Summarized(kept) => {
+            let head = split_at_tokens(content, tokens, kept / 2).0;
+            let tail = split_at_tokens(content, tokens, tokens - (kept - kept / 2)).1;
+            (
+                format!(
+                    "{}\n[... about {} tokens omitted to fit the attachment budget ...]\n{}",
+                    head,
+                    tokens - kept,
+                    tail
+                ),
+                kept,
+                "kept the start and end",
+            )
+        }
+        Keep::All => (content.clone(), tokens, "kept"),
+    };
+    attachment.content = if kept == 0 { String::new() } else { content };
+    Reduction {
+        name: attachment.name.clone(),
+        tokens,
+        kept_tokens: kept,
+        how: if kept == 0 { "left out" } else { how },
+    }
+}
+
+/// Split text after about `at` of its `tokens` tokens, assuming tokens are spread evenly
+fn split_at_tokens(text: &str, tokens: usize, at: usize) -> (String, String) {
+    if tokens == 0 || at >= tokens {
+        return (text.to_string(), String::new());
+    }
+    let chars = text.chars().count();
+    let at_char = chars * at / tokens;
+    let at_byte = text
+        .char_indices()
+        .nth(at_char)
+        .map_or(text.len(), |(i, _)| i);
+    (text[..at_byte].to_string(), text[at_byte..].to_string())
+}
+
+async fn summarize(
+    text: &str,
+    max_tokens: usize,
+    client: &LLMClient,
+    model: &str,
+    provider: &str,
+) -> Result<String> {
+    let text = match text.char_indices().nth(MAX_SUMMARY_INPUT_CHARS) {
+        Some(_) => {
+            let chars = text.chars().count();
+            let (head, rest) = split_at_tokens(text, chars, MAX_SUMMARY_INPUT_CHARS / 2);
+            let rest_chars = rest.chars().count();
+            let (_, tail) =
+                split_at_tokens(&rest, rest_chars, rest_chars - MAX_SUMMARY_INPUT_CHARS / 2);
+            format!("{}\n[...]\n{}", head, tail)
+        }
+        None => text.to_string(),
+    };
+    let prompt = format!(
+        "Summarize the following part of a file in at most {} words. Keep names, numbers and \
+         anything a reader would need to answer questions about it. Reply with the summary only.\n\n{}",
+        max_tokens * 3 / 4,
+        text
+    );
+    let (summary, _, _) = crate::chat::send_chat_request_with_validation(
+        client,
+        model,
+        &prompt,
+        &[],
+        None,
+        u32::try_from(max_tokens).ok(),
+        None,
+        provider,
+        None,
+    )
+    .await?;
+    Ok(summary.trim().to_string())
+}
+
+fn report(reduction: &Reduction) {
+    let kept = if reduction.kept_tokens == 0 {
+        "omitted".to_string()
+    } else {
+        format!("{} tokens", format_tokens(reduction.kept_tokens))
+    };
+    eprintln!(
+        "   {} ~{} {} {} ({})",
+        reduction.name.bold(),
+        format_tokens(reduction.tokens),
+        sym("→"),
+        kept,
+        reduction.how.dimmed()
+    );
+}
+
+fn format_tokens(tokens: usize) -> String {
+    if (1_000..10_000).contains(&tokens) {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else if tokens >= 10_000 {
+        format!("{}k", tokens / 1_000)
+    } else {
+        tokens.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        // Small files keep everything; the rest of the budget is split between large ones
+        assert_eq!(
+            fair_shares(&[100, 5_000, 9_000], 4_100),
+            vec![100, 2_000, 2_000]
+        );
+        assert_eq!(
+            plan(&[100, 5_000, 9_000], 4_100, AttachStrategy::PerFile),
+            vec![
+                Keep::All,
+                Keep::HeadAndTail(2_000),
+                Keep::HeadAndTail(2_000)
+            ]
+        );
+        assert_eq!(
+            plan(&[1_000, 5_000, 9_000], 4_000, AttachStrategy::Head),
+            vec![Keep::All, Keep::Head(3_000), Keep::Head(0)]
+        );
+        assert_eq!(
+            plan(&[1_000, 5_000, 2_000], 4_000, AttachStrategy::Tail),
+            vec![Keep::Tail(0), Keep::Tail(2_000), Keep::All]
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut attachment = Attachment {
+            name: "notes.txt".to_string(),
+            extension: Some("txt".to_string()),
+            content: "abcdefghij".to_string(),
+        };
+        let reduction = apply(&mut attachment, 10, Keep::HeadAndTail(4));
+        assert_eq!(
+            attachment.content,
+            "ab\n[... about 6 tokens omitted to fit the attachment budget ...]\nij"
+        );
+        assert_eq!(reduction.how, "kept the start and end");
+
+        let mut attachment = Attachment {
+            content: "abcdefghij".to_string(),
+            ..attachment
+        };
+        assert_eq!(apply(&mut attachment, 10, Keep::Tail(0)).how, "left out");
+        assert!(attachment.content.is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Retrieval from attachments over the budget (`--attach-strategy embed`)
+//!
+//! Rather than cutting attachments down blindly, they are split into chunks and
+//! embedded (with `--attach-embed-model`) into a vector database that only lives for
+//! the request, and the chunks closest to the prompt are sent, as many as the budget
+//! holds. The database is in a temporary directory removed when the request is
+//! prepared, whether or not it succeeded.
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+use crate::chunking::{Chunk, ChunkOptions};
+use crate::utils::cli_utils::Attachment;
+use crate::vector_db::VectorDatabase;
+
+/// A chunk of an attachment
+struct Excerpt<'a> {
+    attachment: &'a Attachment,
+    chunk: Chunk,
+    tokens: usize,
+}
+
+/// The chunks of `attachments` most relevant to `prompt` within `budget` tokens,
+/// formatted for the prompt
+pub async fn retrieve(
+    attachments: &[Attachment],
+    prompt: &str,
+    budget: usize,
+    embedding_model: &str,
+    count: impl Fn(&str) -> usize,
+) -> Result<String> {
+    let mut config = crate::config::Config::load()?;
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(&config, None, Some(embedding_model.to_string()))?;
+    let client = crate::chat::create_authenticated_client(&mut config, &provider).await?;
+
+    let options = ChunkOptions::default();
+    let excerpts: Vec<Excerpt> = attachments
+        .iter()
+        .flat_map(|attachment| {
+            crate::chunking::chunk(
+                &attachment.content,
+                &options,
+                Some(Path::new(&attachment.name)),
+            )
+            .into_iter()
+            .map(move |chunk| Excerpt {
+                attachment,
+                chunk,
+                tokens: 0,
+            })
+        })
+        .map(|mut excerpt| {
+            excerpt.tokens = count(&excerpt.chunk.text);
+            excerpt
+        })
+        .collect();
+    eprintln!(
+        "{} Embedding {} chunks of the attachments with {}:{}...",
+        sym("📎").yellow(),
+        excerpts.len(),
+        provider,
+        model
+    );
+
+    // The chunks and the prompt, batched into as few requests as possible
+    let mut inputs: Vec<String> = excerpts.iter().map(|e| e.chunk.text.clone()).collect();
+    inputs.push(prompt.to_string());
+    let mut vectors = client.embed_batch(&model, &inputs).await?;
+    let query = vectors
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("No embedding data in response"))?;
+
+    // Removed when dropped, on errors too
+    let dir = tempfile::Builder::new().prefix("lc-attach-").tempdir()?;
+    let db = VectorDatabase::open_in(dir.path(), "attachments")?;
+    for (index, (excerpt, vector)) in excerpts.iter().zip(&vectors).enumerate() {
+        db.add_vector_with_metadata(
+            &excerpt.chunk.text,
+            vector,
+            &model,
+            &provider,
+            Some(&excerpt.attachment.name),
+            Some(index as i32),
+            None,
+        )?;
+    }
+    let ranked: Vec<usize> = db
+        .find_similar(&query, excerpts.len())?
+        .into_iter()
+        .filter_map(|(entry, _)| entry.chunk_index.map(|index| index as usize))
+        .collect();
+    drop(db);
+    dir.close()?;
+
+    let selected = select(&ranked, &excerpts, budget);
+    eprintln!(
+        "   {} of {} chunks sent, the ones closest to the prompt",
+        selected.len(),
+        excerpts.len()
+    );
+    Ok(format_excerpts(&excerpts, &selected))
+}
+
+/// The excerpts to send: the best ranked that fit in `budget`, in their order in the
+/// attachments
+fn select(ranked: &[usize], excerpts: &[Excerpt], budget: usize) -> Vec<usize> {
+    let mut selected = Vec::new();
+    let mut used = 0;
+    for &index in ranked {
+        let Some(excerpt) = excerpts.get(index) else {
+            continue;
+        };
+        if used + excerpt.tokens <= budget {
+            used += excerpt.tokens;
+            selected.push(index);
+        }
+    }
+    selected.sort_unstable();
+    selected
+}
+
+/// The selected excerpts under a header per file, each with the line it starts on
+fn format_excerpts(excerpts: &[Excerpt], selected: &[usize]) -> String {
+    let mut result = String::new();
+    let mut current: Option<&str> = None;
+    for &index in selected {
+        let excerpt = &excerpts[index];
+        let name = excerpt.attachment.name.as_str();
+        if current != Some(name) {
+            result.push_str(&format!(
+                "=== File: {} (excerpts relevant to the question) ===\n",
+                name
+            ));
+            current = Some(name);
+        }
+        result.push_str(&format!(
+            "[from line {}]\n{}\n\n",
+            excerpt.chunk.line,
+            excerpt.chunk.text.trim_end()
+        ));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_and_format() {
+        let attachment = Attachment {
+            name: "notes.md".to_string(),
+            extension: Some("md".to_string()),
+            content: String::new(),
+        };
+        let excerpt = |text: &str, line: usize, tokens: usize| Excerpt {
+            attachment: &attachment,
+            chunk: Chunk {
+                text: text.to_string(),
+                offset: 0,
+                line,
+            },
+            tokens,
+        };
+        let excerpts = vec![
+            excerpt("Intro", 1, 10),
+            excerpt("Setup", 20, 50),
+            excerpt("Answer", 40, 30),
+        ];
+        // The best match, then the next that still fits
+        let selected = select(&[2, 1, 0, 7], &excerpts, 45);
+        assert_eq!(selected, [0, 2]);
+        assert_eq!(
+            format_excerpts(&excerpts, &selected),
+            "=== File: notes.md (excerpts relevant to the question) ===\n\
+             [from line 1]\nIntro\n\n[from line 40]\nAnswer\n\n"
+        );
+    }
+}
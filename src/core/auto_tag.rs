@@ -0,0 +1,149 @@
+//! Topic tags and summaries for embedded chunks (`lc embed --tag-model`)
+//!
+//! A (cheap) chat model reads every chunk and replies with a few lowercase topic tags
+//! and a one-sentence summary. They are stored with the chunk in the vector database
+//! and `lc similar --tag` only searches the chunks carrying the given tags.
+
+use crate::chat::LLMClient;
+use crate::config::Config;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Most tags kept per chunk
+pub const MAX_TAGS: usize = 5;
+
+/// Longest part of a chunk sent to the tagging model, in characters
+const MAX_INPUT_CHARS: usize = 6000;
+
+/// Tags and summary of a chunk
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotations {
+    pub tags: Vec<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    summary: Option<String>,
+}
+
+/// Lowercase, hyphenated form of a tag, so `Machine Learning` and `machine-learning`
+/// are the same tag
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// The message asking the model to tag and summarize `text`
+pub fn prompt(text: &str) -> String {
+    let text = match text.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    };
+    format!(
+        "Read the document excerpt below and reply with a JSON object only, like \
+         {{\"tags\": [\"topic\", \"another-topic\"], \"summary\": \"One sentence.\"}}. \
+         Give 1 to {} short lowercase topic tags, most specific first, and a summary of \
+         one sentence.\n\n<excerpt>\n{}\n</excerpt>",
+        MAX_TAGS,
+        text.trim()
+    )
+}
+
+/// Tags and summary from the model's reply, tolerating text around the JSON object
+pub fn parse(reply: &str) -> Option<Annotations> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    let reply: Reply = serde_json::from_str(&reply[start..=end]).ok()?;
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in reply.tags.iter().map(|tag| normalize_tag(tag)) {
+        if !tag.is_empty() && !tags.contains(&tag) && tags.len() < MAX_TAGS {
+            tags.push(tag);
+        }
+    }
+    let summary = reply
+        .summary
+        .map(|summary| summary.trim().to_string())
+        .filter(|summary| !summary.is_empty());
+    if tags.is_empty() && summary.is_none() {
+        return None;
+    }
+    Some(Annotations { tags, summary })
+}
+
+/// Asks a chat model for the annotations of chunks
+pub struct Tagger {
+    client: LLMClient,
+    provider: String,
+    model: String,
+}
+
+impl Tagger {
+    /// Set up the tagging model (`provider:model` or alias)
+    pub async fn new(config: &mut Config, model: &str) -> Result<Self> {
+        let (provider, model) =
+            crate::utils::resolve_model_and_provider(config, None, Some(model.to_string()))?;
+        let client = crate::chat::create_authenticated_client(config, &provider).await?;
+        Ok(Self {
+            client,
+            provider,
+            model,
+        })
+    }
+
+    /// `provider:model` of the tagging model
+    pub fn name(&self) -> String {
+        format!("{}:{}", self.provider, self.model)
+    }
+
+    pub async fn annotate(&self, text: &str) -> Result<Annotations> {
+        let (reply, _, _) = crate::chat::send_chat_request_with_validation(
+            &self.client,
+            &self.model,
+            &prompt(text),
+            &[],
+            None,
+            None,
+            None,
+            &self.provider,
+            None,
+        )
+        .await?;
+        parse(&reply).ok_or_else(|| anyhow::anyhow!("the model did not reply with tags"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let reply = "Sure:\n```json\n{\"tags\": [\"Rust\", \"async IO\", \"rust\", \"\"], \
+                     \"summary\": \" Explains tokio tasks. \"}\n```";
+        assert_eq!(
+            parse(reply),
+            Some(Annotations {
+                tags: vec!["rust".to_string(), "async-io".to_string()],
+                summary: Some("Explains tokio tasks.".to_string()),
+            })
+        );
+
+        let many = r#"{"tags": ["a", "b", "c", "d", "e", "f"]}"#;
+        assert_eq!(parse(many).unwrap().tags.len(), MAX_TAGS);
+        assert_eq!(parse(r#"{"tags": [], "summary": ""}"#), None);
+        assert_eq!(parse("no json here"), None);
+        assert_eq!(normalize_tag(" #Machine  Learning "), "machine-learning");
+    }
+}
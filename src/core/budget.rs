@@ -0,0 +1,193 @@
+//! Spending limits: `limits.per_request_usd` and `limits.daily_usd`
+//!
+//! Before a chat request is sent its worst-case cost is estimated from the input
+//! tokens, the `max_tokens` cap and the model's pricing in the models cache. The
+//! request is refused when that estimate exceeds the per-request limit, or when it
+//! would take today's spend (priced from the chat logs) over the daily limit, unless
+//! `--override-budget` is given. Models without pricing are never blocked.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+
+use crate::config::{Config, SpendLimits};
+use crate::database::{Database, TokenUsage};
+use crate::provider::{ChatRequest, ContentPart, MessageContent};
+use crate::token_utils::TokenCounter;
+
+// Output tokens assumed when a request has no max_tokens cap
+const DEFAULT_OUTPUT_TOKENS: u32 = 1024;
+
+/// Check whether `--override-budget` is enabled for this invocation
+pub fn is_overridden() -> bool {
+    crate::OVERRIDE_BUDGET_MODE.load(Ordering::Relaxed)
+}
+
+/// Refuse the request if it could exceed the configured spending limits
+pub async fn check(provider: &str, request: &ChatRequest) -> Result<()> {
+    if is_overridden() {
+        return Ok(());
+    }
+    let limits = Config::load()?.limits;
+    if limits.is_unset() {
+        return Ok(());
+    }
+
+    let Some(prices) = pricing(provider, &request.model).await else {
+        crate::debug_log!(
+            "No pricing for {}:{}, spending limits not enforced",
+            provider,
+            request.model
+        );
+        return Ok(());
+    };
+    let estimate = cost(
+        input_tokens(request) as u64,
+        request.max_tokens.unwrap_or(DEFAULT_OUTPUT_TOKENS) as u64,
+        prices,
+    );
+    let spent_today = if limits.daily_usd.is_some() {
+        spent_today().await?
+    } else {
+        0.0
+    };
+    check_limits(&limits, estimate, spent_today)
+}
+
+/// Compare an estimated request cost against the limits
+pub fn check_limits(limits: &SpendLimits, estimate: f64, spent_today: f64) -> Result<()> {
+    if let Some(limit) = limits.per_request_usd {
+        if estimate > limit {
+            bail!(
+                "Estimated cost ${:.4} exceeds limits.per_request_usd (${:.2}). Lower --max-tokens, \
+                 shorten the input, or re-run with --override-budget",
+                estimate,
+                limit
+            );
+        }
+    }
+    if let Some(limit) = limits.daily_usd {
+        if spent_today + estimate > limit {
+            bail!(
+                "Request (up to ${:.4}) would exceed limits.daily_usd (${:.2}, ${:.4} spent today). \
+                 Re-run with --override-budget to send it anyway",
+                estimate,
+                limit,
+                spent_today
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Cost in USD of a number of tokens at (input, output) prices per million tokens
+pub fn cost(input_tokens: u64, output_tokens: u64, (input_price, output_price): (f64, f64)) -> f64 {
+    (input_tokens as f64 / 1_000_000.0) * input_price
+        + (output_tokens as f64 / 1_000_000.0) * output_price
+}
+
+/// Estimated cost of logged token usage; unpriced models count as free
+pub fn logged_cost(usage: &[TokenUsage], pricing: &HashMap<(String, String), (f64, f64)>) -> f64 {
+    usage
+        .iter()
+        .filter_map(|usage| {
+            let prices = pricing.get(&(usage.provider.clone(), usage.model.clone()))?;
+            Some(cost(
+                usage.input_tokens.max(0) as u64,
+                usage.output_tokens.max(0) as u64,
+                *prices,
+            ))
+        })
+        .sum()
+}
+
+/// (input, output) prices per million tokens of a model in the models cache
+pub async fn pricing(provider: &str, model: &str) -> Option<(f64, f64)> {
+    crate::unified_cache::UnifiedCache::load_provider_models(provider)
+        .await
+        .ok()?
+        .into_iter()
+        .find(|m| m.id == model)
+        .and_then(|m| m.input_price_per_m.zip(m.output_price_per_m))
+}
+
+/// Estimated spend of the chat logs since local midnight
+async fn spent_today() -> Result<f64> {
+    let midnight: DateTime<Utc> = Local::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+    let usage = Database::new()?.token_usage_since(midnight)?;
+
+    let mut pricing = HashMap::new();
+    for usage in &usage {
+        if let Some(prices) = self::pricing(&usage.provider, &usage.model).await {
+            pricing.insert((usage.provider.clone(), usage.model.clone()), prices);
+        }
+    }
+    Ok(logged_cost(&usage, &pricing))
+}
+
+fn input_tokens(request: &ChatRequest) -> usize {
+    let text: String = request
+        .messages
+        .iter()
+        .map(|message| match &message.content_type {
+            MessageContent::Text { content } => content.clone().unwrap_or_default(),
+            MessageContent::Multimodal { content } => content
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    match TokenCounter::new(&request.model) {
+        Ok(counter) => counter.count_tokens(&text),
+        Err(_) => text.len() / 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_limits() {
+        let limits = SpendLimits {
+            per_request_usd: Some(0.50),
+            daily_usd: Some(5.0),
+        };
+        assert!(check_limits(&limits, 0.10, 1.0).is_ok());
+
+        let err = check_limits(&limits, 0.75, 0.0).unwrap_err().to_string();
+        assert!(err.contains("per_request_usd"));
+
+        let err = check_limits(&limits, 0.25, 4.90).unwrap_err().to_string();
+        assert!(err.contains("daily_usd"));
+
+        assert!(check_limits(&SpendLimits::default(), 100.0, 100.0).is_ok());
+    }
+
+    #[test]
+    fn test_logged_cost() {
+        let usage = |provider: &str, model: &str, input_tokens, output_tokens| TokenUsage {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            input_tokens,
+            output_tokens,
+        };
+        let pricing = HashMap::from([(("openai".to_string(), "gpt-4o".to_string()), (2.5, 10.0))]);
+        let logged = vec![
+            usage("openai", "gpt-4o", 1_000_000, 200_000),
+            usage("ollama", "llama3", 1_000_000, 1_000_000),
+        ];
+        assert!((logged_cost(&logged, &pricing) - 4.5).abs() < 1e-9);
+    }
+}
@@ -1,8 +1,10 @@
 use crate::config::Config;
+use crate::core::tool_cache::ToolCache;
 use crate::database::ChatEntry;
 use crate::model_metadata::MetadataExtractor;
 use crate::provider::{ChatRequest, Message, MessageContent, OpenAIClient};
 use crate::token_utils::TokenCounter;
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -14,6 +16,10 @@ const TOOL_EXECUTION_TIMEOUT_SECS: u64 = 30;
 const MAX_TOOL_RESULT_LENGTH: usize = 10000;
 const IMAGE_TOKEN_ESTIMATE: i32 = 85; // Approximate tokens for low-detail image
 
+// Streaming recovery constants
+const MAX_STREAM_RESUME_ATTEMPTS: u32 = 2;
+pub const STREAM_TRUNCATED_MARKER: &str = "[truncated due to connection loss]";
+
 #[allow(clippy::too_many_arguments)]
 pub async fn send_chat_request_with_validation(
     client: &LLMClient,
@@ -66,6 +72,7 @@ pub async fn send_chat_request_with_validation(
                 model,
                 e
             );
+            crate::utils::spinner::clear();
             eprintln!(
                 "Warning: Failed to create token counter for model '{}': {}",
                 model, e
@@ -81,10 +88,13 @@ pub async fn send_chat_request_with_validation(
     // Validate context size if we have both metadata and token counter
     if let (Some(metadata), Some(ref counter)) = (&model_metadata, &token_counter) {
         if let Some(context_limit) = metadata.context_length {
+            let context_limit = crate::core::session_memory::available_context(context_limit);
             // Check if input exceeds context limit
             if counter.exceeds_context_limit(prompt, system_prompt, history, context_limit) {
+                crate::utils::spinner::clear();
                 println!(
-                    "⚠️  Input exceeds model context limit ({}k tokens). Truncating...",
+                    "{}  Input exceeds model context limit ({}k tokens). Truncating...",
+                    sym("⚠️"),
                     context_limit / 1000
                 );
 
@@ -101,16 +111,25 @@ pub async fn send_chat_request_with_validation(
                 final_history = truncated_history;
 
                 if final_history.len() < history.len() {
+                    crate::utils::spinner::clear();
                     println!(
-                        "📝 Truncated conversation history from {} to {} messages",
+                        "{} Truncated conversation history from {} to {} messages",
+                        sym("📝"),
                         history.len(),
                         final_history.len()
                     );
+                    final_prompt = crate::core::session_memory::recall_into_prompt(
+                        final_prompt,
+                        &history[..history.len() - final_history.len()],
+                    )
+                    .await;
                 }
 
                 if final_prompt.len() < prompt.len() {
+                    crate::utils::spinner::clear();
                     println!(
-                        "✂️  Truncated prompt from {} to {} characters",
+                        "{}  Truncated prompt from {} to {} characters",
+                        sym("✂️"),
                         prompt.len(),
                         final_prompt.len()
                     );
@@ -172,6 +191,7 @@ pub async fn send_chat_request_with_validation(
 
     // Send the request
     crate::debug_log!("Making API call to chat endpoint...");
+    crate::core::budget::check(provider_name, &request).await?;
     let response = client.chat(&request).await?;
 
     crate::debug_log!(
@@ -184,10 +204,14 @@ pub async fn send_chat_request_with_validation(
         .as_ref()
         .map(|counter| counter.count_tokens(&response) as i32);
 
+    report_timings(client, provider_name, model, output_tokens, 0);
+
     // Display token usage if available
     if let (Some(input), Some(output)) = (input_tokens, output_tokens) {
+        crate::utils::spinner::clear();
         println!(
-            "📊 Token usage: {} input + {} output = {} total",
+            "{} Token usage: {} input + {} output = {} total",
+            sym("📊"),
             input,
             output,
             input + output
@@ -201,9 +225,13 @@ pub async fn send_chat_request_with_validation(
                 let input_cost = (input as f64 / 1_000_000.0) * input_price;
                 let output_cost = (output as f64 / 1_000_000.0) * output_price;
                 let total_cost = input_cost + output_cost;
+                crate::utils::spinner::clear();
                 println!(
-                    "💰 Estimated cost: ${:.6} (${:.6} input + ${:.6} output)",
-                    total_cost, input_cost, output_cost
+                    "{} Estimated cost: ${:.6} (${:.6} input + ${:.6} output)",
+                    sym("💰"),
+                    total_cost,
+                    input_cost,
+                    output_cost
                 );
             }
         }
@@ -212,6 +240,84 @@ pub async fn send_chat_request_with_validation(
     Ok((response, input_tokens, output_tokens))
 }
 
+/// The reply to `prompt` checked against the `--schema` of this invocation, if any: the
+/// JSON it contains (pretty-printed) when it matches, otherwise the model is shown the
+/// problems and asked once to correct it. Replies are returned unchanged without a schema.
+/// The token counts returned cover the retry as well as the reply's own.
+#[allow(clippy::too_many_arguments)]
+pub async fn conform_to_schema(
+    client: &LLMClient,
+    model: &str,
+    prompt: &str,
+    (reply, input_tokens, output_tokens): (String, Option<i32>, Option<i32>),
+    system_prompt: Option<&str>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    provider_name: &str,
+) -> Result<(String, Option<i32>, Option<i32>)> {
+    use crate::core::structured_output;
+
+    let Some(schema) = structured_output::requested_schema() else {
+        return Ok((reply, input_tokens, output_tokens));
+    };
+    let errors = match structured_output::check_reply(&reply, &schema) {
+        Ok(value) => {
+            return Ok((
+                serde_json::to_string_pretty(&value)?,
+                input_tokens,
+                output_tokens,
+            ))
+        }
+        Err(errors) => errors,
+    };
+    crate::debug_log!("Reply does not match the schema, retrying: {:?}", errors);
+
+    let history = [ChatEntry {
+        chat_id: String::new(),
+        model: model.to_string(),
+        question: prompt.to_string(),
+        response: reply,
+        timestamp: Utc::now(),
+        input_tokens: None,
+        output_tokens: None,
+        provider: Some(provider_name.to_string()),
+    }];
+    let (retry, retry_input_tokens, retry_output_tokens) = structured_output::with_format(
+        structured_output::requested(),
+        send_chat_request_with_validation(
+            client,
+            model,
+            &structured_output::repair_prompt(&schema, &errors),
+            &history,
+            system_prompt,
+            max_tokens,
+            temperature,
+            provider_name,
+            None,
+        ),
+    )
+    .await?;
+    match structured_output::check_reply(&retry, &schema) {
+        Ok(value) => Ok((
+            serde_json::to_string_pretty(&value)?,
+            add_tokens(input_tokens, retry_input_tokens),
+            add_tokens(output_tokens, retry_output_tokens),
+        )),
+        Err(errors) => anyhow::bail!(
+            "The reply does not match the schema after a retry:\n  {}",
+            errors.join("\n  ")
+        ),
+    }
+}
+
+/// Token counts of two requests added up, as far as they are known
+pub fn add_tokens(first: Option<i32>, second: Option<i32>) -> Option<i32> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first + second),
+        (first, second) => first.or(second),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn send_chat_request_with_streaming(
     client: &LLMClient,
@@ -223,7 +329,7 @@ pub async fn send_chat_request_with_streaming(
     temperature: Option<f32>,
     provider_name: &str,
     tools: Option<Vec<crate::provider::Tool>>,
-) -> Result<()> {
+) -> Result<String> {
     crate::debug_log!("Sending streaming chat request - provider: '{}', model: '{}', prompt length: {}, history entries: {}",
                       provider_name, model, prompt.len(), history.len());
     crate::debug_log!(
@@ -264,6 +370,7 @@ pub async fn send_chat_request_with_streaming(
                 model,
                 e
             );
+            crate::utils::spinner::clear();
             eprintln!(
                 "Warning: Failed to create token counter for model '{}': {}",
                 model, e
@@ -278,10 +385,13 @@ pub async fn send_chat_request_with_streaming(
     // Validate context size if we have both metadata and token counter
     if let (Some(metadata), Some(ref counter)) = (&model_metadata, &token_counter) {
         if let Some(context_limit) = metadata.context_length {
+            let context_limit = crate::core::session_memory::available_context(context_limit);
             // Check if input exceeds context limit
             if counter.exceeds_context_limit(prompt, system_prompt, history, context_limit) {
+                crate::utils::spinner::clear();
                 println!(
-                    "⚠️  Input exceeds model context limit ({}k tokens). Truncating...",
+                    "{}  Input exceeds model context limit ({}k tokens). Truncating...",
+                    sym("⚠️"),
                     context_limit / 1000
                 );
 
@@ -298,16 +408,25 @@ pub async fn send_chat_request_with_streaming(
                 final_history = truncated_history;
 
                 if final_history.len() < history.len() {
+                    crate::utils::spinner::clear();
                     println!(
-                        "📝 Truncated conversation history from {} to {} messages",
+                        "{} Truncated conversation history from {} to {} messages",
+                        sym("📝"),
                         history.len(),
                         final_history.len()
                     );
+                    final_prompt = crate::core::session_memory::recall_into_prompt(
+                        final_prompt,
+                        &history[..history.len() - final_history.len()],
+                    )
+                    .await;
                 }
 
                 if final_prompt.len() < prompt.len() {
+                    crate::utils::spinner::clear();
                     println!(
-                        "✂️  Truncated prompt from {} to {} characters",
+                        "{}  Truncated prompt from {} to {} characters",
+                        sym("✂️"),
                         prompt.len(),
                         final_prompt.len()
                     );
@@ -358,9 +477,97 @@ pub async fn send_chat_request_with_streaming(
 
     // Send the streaming request
     crate::debug_log!("Making streaming API call to chat endpoint...");
-    client.chat_stream(&request).await?;
+    stream_with_recovery(client, provider_name, request).await
+}
 
-    Ok(())
+/// Print and record the timing report for the last request when `--timings` is set
+fn report_timings(
+    client: &LLMClient,
+    provider_name: &str,
+    model: &str,
+    output_tokens: Option<i32>,
+    retries: u32,
+) {
+    if let Some(mut timings) = client.take_timings() {
+        timings.retries += retries;
+        if let Err(e) = crate::core::timings::report(provider_name, model, &timings, output_tokens)
+        {
+            crate::debug_log!("Failed to record request timings: {}", e);
+        }
+    }
+}
+
+/// Stream a chat request, recovering from a connection that drops mid-response.
+///
+/// Providers that support it are asked to continue from the partial output (sent back
+/// as an assistant prefill). Otherwise, or once retries run out, the partial text is
+/// kept with a truncation marker so it can still be saved to history.
+async fn stream_with_recovery(
+    client: &LLMClient,
+    provider_name: &str,
+    request: ChatRequest,
+) -> Result<String> {
+    crate::core::budget::check(provider_name, &request).await?;
+    let mut outcome = client.chat_stream(&request).await?;
+    let mut text = outcome.text;
+    let mut attempts = 0;
+    let max_attempts = crate::config::Config::load()
+        .ok()
+        .and_then(|config| config.retry.max)
+        .unwrap_or(MAX_STREAM_RESUME_ATTEMPTS);
+
+    while outcome.truncated {
+        if attempts >= max_attempts || !client.supports_stream_resume() {
+            break;
+        }
+        attempts += 1;
+
+        crate::utils::spinner::clear();
+        eprintln!(
+            "\n{}  Connection lost, resuming response (attempt {}/{})...",
+            sym("⚠️"),
+            attempts,
+            max_attempts
+        );
+
+        let mut resume_request = request.clone();
+        resume_request
+            .messages
+            .push(Message::assistant(text.clone()));
+
+        match client.chat_stream(&resume_request).await {
+            Ok(next) => {
+                text.push_str(&next.text);
+                outcome = next;
+            }
+            Err(e) => {
+                crate::debug_log!("Failed to resume stream: {}", e);
+                break;
+            }
+        }
+    }
+
+    if crate::core::timings::is_enabled() {
+        let output_tokens = TokenCounter::new(&request.model)
+            .ok()
+            .map(|counter| counter.count_tokens(&text) as i32);
+        report_timings(
+            client,
+            provider_name,
+            &request.model,
+            output_tokens,
+            attempts,
+        );
+    }
+
+    if outcome.truncated {
+        crate::utils::spinner::clear();
+        println!("\n{}", STREAM_TRUNCATED_MARKER);
+        text.push_str("\n\n");
+        text.push_str(STREAM_TRUNCATED_MARKER);
+    }
+
+    Ok(text)
 }
 
 // Cache for provider model metadata to avoid repeated file reads and parsing
@@ -368,7 +575,7 @@ static PROVIDER_METADATA_CACHE: OnceLock<
     RwLock<HashMap<String, Vec<crate::model_metadata::ModelMetadata>>>,
 > = OnceLock::new();
 
-async fn get_model_metadata(
+pub(crate) async fn get_model_metadata(
     provider_name: &str,
     model_name: &str,
 ) -> Option<crate::model_metadata::ModelMetadata> {
@@ -417,6 +624,107 @@ async fn get_model_metadata(
     }
 }
 
+/// Determine whether a model accepts image input based on cached provider metadata.
+///
+/// Returns `None` when the answer is unknown: the model is not in the cache, or the
+/// provider doesn't advertise vision capability for any of its models (many `/models`
+/// endpoints carry no capability information at all).
+fn vision_support_from_metadata(
+    models: &[crate::model_metadata::ModelMetadata],
+    model_name: &str,
+) -> Option<bool> {
+    let metadata = models.iter().find(|m| m.id == model_name)?;
+    if metadata.supports_vision {
+        return Some(true);
+    }
+
+    if models.iter().any(|m| m.supports_vision) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Pre-flight check before attaching images to a request.
+///
+/// When cached metadata says the model can't handle images, the user is asked to
+/// confirm (or the request is refused when stdin isn't a terminal) so a paid
+/// request isn't wasted. Skipped entirely with `--force`.
+pub async fn ensure_vision_support(
+    provider_name: &str,
+    model_name: &str,
+    image_count: usize,
+) -> Result<()> {
+    if image_count == 0 || crate::FORCE_MODE.load(std::sync::atomic::Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let models = crate::unified_cache::UnifiedCache::load_provider_models(provider_name)
+        .await
+        .unwrap_or_default();
+
+    match vision_support_from_metadata(&models, model_name) {
+        Some(true) => Ok(()),
+        None => {
+            crate::debug_log!(
+                "Vision support for '{}:{}' is unknown, sending images anyway",
+                provider_name,
+                model_name
+            );
+            Ok(())
+        }
+        Some(false) => {
+            use colored::Colorize;
+            use std::io::Write;
+
+            // The question below waits for an answer, so the indicator can't keep running
+            crate::utils::spinner::stop();
+            eprintln!(
+                "{} Model '{}:{}' does not support image input according to its metadata ({} image(s) attached)",
+                sym("⚠️").yellow(),
+                provider_name,
+                model_name,
+                image_count
+            );
+
+            if !atty::is(atty::Stream::Stdin) {
+                anyhow::bail!(
+                    "Refusing to send images to a non-vision model. Use a vision-capable model or pass --force to send anyway"
+                );
+            }
+
+            eprint!("Send the request anyway? (y/N): ");
+            std::io::stderr().flush()?;
+
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+
+            if input.trim().eq_ignore_ascii_case("y") || input.trim().eq_ignore_ascii_case("yes") {
+                Ok(())
+            } else {
+                anyhow::bail!("Request cancelled (use --force to skip this check)")
+            }
+        }
+    }
+}
+
+/// Whether the model may accept images: true unless cached metadata says it doesn't
+pub async fn model_may_support_vision(provider_name: &str, model_name: &str) -> bool {
+    let models = crate::unified_cache::UnifiedCache::load_provider_models(provider_name)
+        .await
+        .unwrap_or_default();
+    vision_support_from_metadata(&models, model_name) != Some(false)
+}
+
+/// Whether cached metadata says the model accepts audio input directly in chat
+pub async fn model_supports_audio(provider_name: &str, model_name: &str) -> bool {
+    crate::unified_cache::UnifiedCache::load_provider_models(provider_name)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .any(|m| m.id == model_name && m.supports_audio)
+}
+
 pub async fn get_or_refresh_token(
     config: &mut Config,
     provider_name: &str,
@@ -565,11 +873,30 @@ pub async fn create_authenticated_client(
     config: &mut Config,
     provider_name: &str,
 ) -> Result<LLMClient> {
+    let mut client = build_authenticated_client(config, provider_name).await?;
+    // Clients of a group backend already carry the backend's name
+    if client.provider_name().is_none() {
+        client.set_provider_name(provider_name);
+    }
+    Ok(client)
+}
+
+async fn build_authenticated_client(config: &mut Config, provider_name: &str) -> Result<LLMClient> {
     crate::debug_log!(
         "Creating authenticated client for provider '{}'",
         provider_name
     );
 
+    // A provider group resolves to one of its backends for this request
+    if !config.has_provider(provider_name) {
+        if let Some(backend) = crate::provider_groups::select_backend(provider_name)? {
+            let mut client =
+                Box::pin(create_authenticated_client(config, &backend.provider)).await?;
+            client.set_group_backend(backend);
+            return Ok(client);
+        }
+    }
+
     // Get provider config with authentication from centralized keys
     let mut provider_config = config.get_provider_with_auth(provider_name)?;
 
@@ -623,6 +950,37 @@ pub async fn create_authenticated_client(
         return Ok(client);
     }
 
+    if crate::core::mock_provider::is_mock(&provider_config.endpoint) {
+        // Answered in-process; no credentials involved
+        return Ok(OpenAIClient::new_with_provider_config(
+            provider_config.endpoint.clone(),
+            String::new(),
+            provider_config.models_path.clone(),
+            provider_config.chat_path.clone(),
+            provider_config.headers.clone(),
+            provider_config.clone(),
+        ));
+    }
+
+    if provider_config.auth_type.as_deref() == Some(crate::core::request_signing::AUTH_TYPE) {
+        // HMAC-signed requests (enterprise gateways): the key signs, it is never sent
+        let signer = crate::core::request_signing::RequestSigner::for_provider(
+            provider_name,
+            provider_config.hmac.as_ref(),
+        )?;
+        provider_config.api_key = None;
+        let mut client = OpenAIClient::new_with_provider_config(
+            provider_config.endpoint.clone(),
+            String::new(),
+            provider_config.models_path.clone(),
+            provider_config.chat_path.clone(),
+            provider_config.headers.clone(),
+            provider_config.clone(),
+        );
+        client.set_signer(signer);
+        return Ok(client);
+    }
+
     // Regular authentication flow (API key or token URL)
     // Special-case: if headers already contain resolved auth (e.g., x-goog-api-key), we don't need a token
     let header_has_resolved_key = provider_config.headers.iter().any(|(k, v)| {
@@ -680,7 +1038,7 @@ pub async fn send_chat_request_with_tool_execution(
     system_prompt: Option<&str>,
     max_tokens: Option<u32>,
     temperature: Option<f32>,
-    _provider_name: &str,
+    provider_name: &str,
     tools: Option<Vec<crate::provider::Tool>>,
     mcp_server_names: &[&str],
     max_iterations: Option<u32>,
@@ -696,7 +1054,8 @@ pub async fn send_chat_request_with_tool_execution(
     let token_counter = TokenCounter::new(model).ok();
 
     // Build tool-to-server mapping for O(1) lookups
-    let tool_server_map = build_tool_server_map(&tools, mcp_server_names).await;
+    let mut tool_cache = ToolCache::for_current_session();
+    let tool_server_map = build_tool_server_map(&tools, mcp_server_names, &mut tool_cache).await;
 
     // Add system prompt if provided
     if let Some(sys_prompt) = system_prompt {
@@ -721,6 +1080,26 @@ pub async fn send_chat_request_with_tool_execution(
 
     // Use provided max_iterations or default
     let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+
+    if let Some(tool_defs) = tools.as_deref() {
+        if use_prompted_tools(provider_name, model).await {
+            return send_chat_request_with_prompted_tools(
+                client,
+                model,
+                conversation_messages,
+                max_tokens,
+                temperature,
+                provider_name,
+                tool_defs,
+                mcp_server_names,
+                &tool_server_map,
+                &tool_cache,
+                max_iterations,
+            )
+            .await;
+        }
+    }
+
     let mut iteration = 0;
 
     loop {
@@ -744,7 +1123,33 @@ pub async fn send_chat_request_with_tool_execution(
         };
 
         // Make the API call
-        let response = client.chat_with_tools(&request).await?;
+        crate::core::budget::check(provider_name, &request).await?;
+        let response = match client.chat_with_tools(&request).await {
+            Ok(response) => response,
+            Err(e) if iteration == 1 && crate::core::react::is_tool_support_error(&e) => {
+                if let Some(tool_defs) = tools.as_deref() {
+                    crate::debug_log!("Native tool calling rejected: {}", e);
+                    prompted_tools_notice(model, "rejected native tool calling");
+                    return send_chat_request_with_prompted_tools(
+                        client,
+                        model,
+                        conversation_messages,
+                        max_tokens,
+                        temperature,
+                        provider_name,
+                        tool_defs,
+                        mcp_server_names,
+                        &tool_server_map,
+                        &tool_cache,
+                        max_iterations,
+                    )
+                    .await;
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        report_timings(client, provider_name, model, None, 0);
 
         // Track input token usage if we have a counter
         if let Some(ref counter) = token_counter {
@@ -769,6 +1174,9 @@ pub async fn send_chat_request_with_tool_execution(
                                     // Typical vision models charge ~85 tokens per low-detail image
                                     input_tokens += IMAGE_TOKEN_ESTIMATE;
                                 }
+                                crate::provider::ContentPart::InputAudio { .. } => {
+                                    // Audio token cost depends on duration, which we don't know here
+                                }
                             }
                         }
                     }
@@ -812,6 +1220,11 @@ pub async fn send_chat_request_with_tool_execution(
 
                     // Execute tool calls concurrently for better performance
                     crate::debug_log!("Executing {} tool calls concurrently", tool_calls.len());
+                    let names: Vec<&str> = tool_calls
+                        .iter()
+                        .map(|call| call.function.name.as_str())
+                        .collect();
+                    crate::utils::spinner::tool_progress(iteration, max_iterations, &names);
 
                     let mut futures = Vec::new();
                     for tool_call in tool_calls.iter() {
@@ -820,6 +1233,7 @@ pub async fn send_chat_request_with_tool_execution(
                             tools.as_ref(),
                             mcp_server_names,
                             &tool_server_map,
+                            &tool_cache,
                         );
                         futures.push(future);
                     }
@@ -837,7 +1251,8 @@ pub async fn send_chat_request_with_tool_execution(
                                 ));
                             }
                             Err(e) => {
-                                eprintln!("⚠️  Tool execution error: {}", e);
+                                crate::utils::spinner::clear();
+                                eprintln!("{}  Tool execution error: {}", sym("⚠️"), e);
                                 crate::debug_log!("Tool execution error: {}", e);
                             }
                         }
@@ -902,6 +1317,209 @@ pub async fn send_chat_request_with_tool_execution(
     }
 }
 
+/// Determine whether a model supports native tool calling based on cached provider metadata.
+///
+/// Returns `None` when the answer is unknown, using the same rules as vision support.
+fn tool_support_from_metadata(
+    models: &[crate::model_metadata::ModelMetadata],
+    model_name: &str,
+) -> Option<bool> {
+    let metadata = models.iter().find(|m| m.id == model_name)?;
+    if metadata.supports_tools || metadata.supports_function_calling {
+        return Some(true);
+    }
+
+    if models
+        .iter()
+        .any(|m| m.supports_tools || m.supports_function_calling)
+    {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Whether `--tools` should be described in the prompt instead of sent natively
+async fn use_prompted_tools(provider_name: &str, model_name: &str) -> bool {
+    if crate::REACT_TOOLS_MODE.load(std::sync::atomic::Ordering::Relaxed) {
+        return true;
+    }
+
+    let models = crate::unified_cache::UnifiedCache::load_provider_models(provider_name)
+        .await
+        .unwrap_or_default();
+    if tool_support_from_metadata(&models, model_name) == Some(false) {
+        prompted_tools_notice(model_name, "does not support tool calling");
+        return true;
+    }
+    false
+}
+
+fn prompted_tools_notice(model_name: &str, reason: &str) {
+    use colored::Colorize;
+    crate::utils::spinner::clear();
+    eprintln!(
+        "{}",
+        format!(
+            "{}  '{}' {}; describing tools in the prompt instead",
+            sym("ℹ️"),
+            model_name,
+            reason
+        )
+        .dimmed()
+    );
+}
+
+fn count_text_tokens(counter: &TokenCounter, messages: &[Message]) -> i32 {
+    messages
+        .iter()
+        .map(|msg| match &msg.content_type {
+            MessageContent::Text { content } => content
+                .as_deref()
+                .map_or(0, |text| counter.count_tokens(text) as i32),
+            MessageContent::Multimodal { content } => content
+                .iter()
+                .map(|part| match part {
+                    crate::provider::ContentPart::Text { text } => {
+                        counter.count_tokens(text) as i32
+                    }
+                    crate::provider::ContentPart::ImageUrl { .. } => IMAGE_TOKEN_ESTIMATE,
+                    crate::provider::ContentPart::InputAudio { .. } => 0,
+                })
+                .sum(),
+        })
+        .sum()
+}
+
+/// Tool execution loop for models without native function calling.
+///
+/// Tools are described in the system prompt and requested by the model in text
+/// (see [`crate::core::react`]); results are sent back as user messages.
+#[allow(clippy::too_many_arguments)]
+async fn send_chat_request_with_prompted_tools(
+    client: &LLMClient,
+    model: &str,
+    mut conversation_messages: Vec<Message>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    provider_name: &str,
+    tools: &[crate::provider::Tool],
+    mcp_server_names: &[&str],
+    tool_server_map: &HashMap<String, String>,
+    tool_cache: &ToolCache,
+    max_iterations: u32,
+) -> Result<(String, Option<i32>, Option<i32>)> {
+    use crate::core::react::{self, ReactStep};
+    use crate::provider::{FunctionCall, ToolCall};
+
+    let instructions = react::tool_instructions(tools);
+    match conversation_messages.first_mut() {
+        Some(Message {
+            role,
+            content_type:
+                MessageContent::Text {
+                    content: Some(system),
+                },
+            ..
+        }) if role == "system" => {
+            system.push_str("\n\n");
+            system.push_str(&instructions);
+        }
+        _ => conversation_messages.insert(
+            0,
+            Message {
+                role: "system".to_string(),
+                content_type: MessageContent::Text {
+                    content: Some(instructions),
+                },
+                tool_calls: None,
+                tool_call_id: None,
+            },
+        ),
+    }
+
+    let tool_defs = tools.to_vec();
+    let token_counter = TokenCounter::new(model).ok();
+    let mut total_input_tokens = 0i32;
+    let mut total_output_tokens = 0i32;
+
+    for iteration in 1..=max_iterations {
+        crate::debug_log!("Prompted tool iteration {}/{}", iteration, max_iterations);
+
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages: conversation_messages.clone(),
+            max_tokens: max_tokens.or(Some(1024)),
+            temperature: temperature.or(Some(0.7)),
+            tools: None,
+            stream: None,
+        };
+
+        crate::core::budget::check(provider_name, &request).await?;
+        let response = client.chat_with_tools(&request).await?;
+        report_timings(client, provider_name, model, None, 0);
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .ok_or_else(|| anyhow::anyhow!("No response from API"))?;
+
+        if let Some(ref counter) = token_counter {
+            total_input_tokens += count_text_tokens(counter, &request.messages);
+            total_output_tokens += counter.count_tokens(&content) as i32;
+        }
+
+        let observation = match react::parse_step(&content) {
+            Ok(ReactStep::Final(answer)) => {
+                crate::debug_log!("Prompted tools finished after {} iterations", iteration);
+                let tokens = |total| token_counter.as_ref().map(|_| total);
+                return Ok((
+                    answer,
+                    tokens(total_input_tokens),
+                    tokens(total_output_tokens),
+                ));
+            }
+            Ok(ReactStep::Action { name, arguments }) => {
+                crate::debug_log!("Model requested tool '{}' with {}", name, arguments);
+                crate::utils::spinner::tool_progress(iteration, max_iterations, &[&name]);
+                let tool_call = ToolCall {
+                    id: format!("react_{}", iteration),
+                    call_type: "function".to_string(),
+                    function: FunctionCall {
+                        name,
+                        arguments: arguments.to_string(),
+                    },
+                };
+                match execute_single_tool_call(
+                    &tool_call,
+                    Some(&tool_defs),
+                    mcp_server_names,
+                    tool_server_map,
+                    tool_cache,
+                )
+                .await
+                {
+                    Ok(result) => result.result_content,
+                    Err(e) => format!("Error: {}", e),
+                }
+            }
+            Err(e) => format!(
+                "Error: {}. Reply with 'Action:' and 'Action Input:' (a JSON object), or 'Final Answer:'.",
+                e
+            ),
+        };
+
+        conversation_messages.push(Message::assistant(content));
+        conversation_messages.push(Message::user(react::observation(&observation)));
+    }
+
+    anyhow::bail!(
+        "Maximum tool execution iterations reached ({})",
+        max_iterations
+    )
+}
+
 /// Result of a single tool execution
 struct ToolExecutionResult {
     tool_call_id: String,
@@ -914,6 +1532,7 @@ async fn execute_single_tool_call(
     tools: Option<&Vec<crate::provider::Tool>>,
     mcp_server_names: &[&str],
     tool_server_map: &std::collections::HashMap<String, String>,
+    tool_cache: &ToolCache,
 ) -> Result<ToolExecutionResult> {
     use std::time::Duration;
 
@@ -933,7 +1552,8 @@ async fn execute_single_tool_call(
                 "Tool argument validation failed for '{}': {}",
                 tool_call.function.name, e
             );
-            eprintln!("⚠️  {}", error_msg);
+            crate::utils::spinner::clear();
+            eprintln!("{}  {}", sym("⚠️"), error_msg);
             crate::debug_log!("{}", error_msg);
 
             return Ok(ToolExecutionResult {
@@ -947,17 +1567,36 @@ async fn execute_single_tool_call(
         );
     }
 
+    // Repeated calls to a known server are answered from the session cache
+    let mapped_server = tool_server_map.get(&tool_call.function.name);
+    if let Some(server_name) = mapped_server {
+        if let Some(result_content) =
+            tool_cache.get(server_name, &tool_call.function.name, &args_value)
+        {
+            crate::debug_log!(
+                "Tool cache hit for '{}' on server '{}' with args: {}",
+                tool_call.function.name,
+                server_name,
+                args_value
+            );
+            tool_cache.record_call(server_name, &tool_call.function.name, true);
+            return Ok(ToolExecutionResult {
+                tool_call_id: tool_call.id.clone(),
+                result_content,
+            });
+        }
+    }
+
     // Find which MCP server has this function
     let daemon_client = crate::mcp_daemon::DaemonClient::new()?;
     let mut tool_result = None;
 
     // Use mapping if available for O(1) lookup, otherwise iterate
-    let servers_to_try: Vec<&str> =
-        if let Some(server_name) = tool_server_map.get(&tool_call.function.name) {
-            vec![server_name.as_str()]
-        } else {
-            mcp_server_names.to_vec()
-        };
+    let servers_to_try: Vec<&str> = if let Some(server_name) = mapped_server {
+        vec![server_name.as_str()]
+    } else {
+        mcp_server_names.to_vec()
+    };
 
     for server_name in servers_to_try {
         // Add timeout to prevent hanging
@@ -976,7 +1615,17 @@ async fn execute_single_tool_call(
                     server_name,
                     serde_json::to_string(&result).unwrap_or_else(|_| "invalid json".to_string())
                 );
-                tool_result = Some(format_tool_result(&result));
+                tool_cache.record_call(server_name, &tool_call.function.name, false);
+                let formatted = format_tool_result(&result);
+                if mapped_server.is_some() {
+                    tool_cache.put(
+                        server_name,
+                        &tool_call.function.name,
+                        &args_value,
+                        &formatted,
+                    );
+                }
+                tool_result = Some(formatted);
                 break;
             }
             Ok(Err(e)) => {
@@ -988,7 +1637,8 @@ async fn execute_single_tool_call(
                     "Tool call to '{}' on server '{}' timed out after {} seconds",
                     tool_call.function.name, server_name, TOOL_EXECUTION_TIMEOUT_SECS
                 );
-                eprintln!("⚠️  {}", timeout_msg);
+                crate::utils::spinner::clear();
+                eprintln!("{}  {}", sym("⚠️"), timeout_msg);
                 crate::debug_log!("{}", timeout_msg);
                 continue;
             }
@@ -1018,10 +1668,13 @@ async fn execute_single_tool_call(
     })
 }
 
-/// Build a mapping of tool names to server names for O(1) lookups
+/// Build a mapping of tool names to server names for O(1) lookups.
+///
+/// Only tools annotated as read-only are allowed in `tool_cache`.
 async fn build_tool_server_map(
     tools: &Option<Vec<crate::provider::Tool>>,
     mcp_server_names: &[&str],
+    tool_cache: &mut ToolCache,
 ) -> std::collections::HashMap<String, String> {
     use std::collections::HashMap;
 
@@ -1036,6 +1689,9 @@ async fn build_tool_server_map(
                         for tool in tools_from_server {
                             // Map tool name to server name
                             map.insert(tool.name.to_string(), server_name.to_string());
+                            if crate::core::tool_cache::is_cacheable(tool) {
+                                tool_cache.allow(&tool.name);
+                            }
                         }
                     }
                 }
@@ -1253,7 +1909,9 @@ pub async fn send_chat_request_with_validation_messages(
         stream: None,
     };
 
+    crate::core::budget::check(provider_name, &request).await?;
     let response = client.chat(&request).await?;
+    report_timings(client, provider_name, model, None, 0);
 
     // For now, return None for token counts as we'd need to implement multimodal token counting
     Ok((response, None, None))
@@ -1269,7 +1927,7 @@ pub async fn send_chat_request_with_streaming_messages(
     temperature: Option<f32>,
     provider_name: &str,
     tools: Option<Vec<crate::provider::Tool>>,
-) -> Result<()> {
+) -> Result<String> {
     crate::debug_log!(
         "Sending streaming chat request with messages - provider: '{}', model: '{}', messages: {}",
         provider_name,
@@ -1307,9 +1965,7 @@ pub async fn send_chat_request_with_streaming_messages(
         stream: Some(true),
     };
 
-    client.chat_stream(&request).await?;
-
-    Ok(())
+    stream_with_recovery(client, provider_name, request).await
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1331,7 +1987,8 @@ pub async fn send_chat_request_with_tool_execution_messages(
     let mut conversation_messages = Vec::new();
 
     // Build tool-to-server mapping for O(1) lookups
-    let tool_server_map = build_tool_server_map(&tools, mcp_server_names).await;
+    let mut tool_cache = ToolCache::for_current_session();
+    let tool_server_map = build_tool_server_map(&tools, mcp_server_names, &mut tool_cache).await;
 
     // Add system prompt if provided and not already in messages
     if let Some(sys_prompt) = system_prompt {
@@ -1353,6 +2010,26 @@ pub async fn send_chat_request_with_tool_execution_messages(
 
     // Use provided max_iterations or default
     let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+
+    if let Some(tool_defs) = tools.as_deref() {
+        if use_prompted_tools(provider_name, model).await {
+            return send_chat_request_with_prompted_tools(
+                client,
+                model,
+                conversation_messages,
+                max_tokens,
+                temperature,
+                provider_name,
+                tool_defs,
+                mcp_server_names,
+                &tool_server_map,
+                &tool_cache,
+                max_iterations,
+            )
+            .await;
+        }
+    }
+
     let mut iteration = 0;
 
     loop {
@@ -1373,7 +2050,33 @@ pub async fn send_chat_request_with_tool_execution_messages(
             stream: None,
         };
 
-        let response = client.chat_with_tools(&request).await?;
+        crate::core::budget::check(provider_name, &request).await?;
+        let response = match client.chat_with_tools(&request).await {
+            Ok(response) => response,
+            Err(e) if iteration == 1 && crate::core::react::is_tool_support_error(&e) => {
+                if let Some(tool_defs) = tools.as_deref() {
+                    crate::debug_log!("Native tool calling rejected: {}", e);
+                    prompted_tools_notice(model, "rejected native tool calling");
+                    return send_chat_request_with_prompted_tools(
+                        client,
+                        model,
+                        conversation_messages,
+                        max_tokens,
+                        temperature,
+                        provider_name,
+                        tool_defs,
+                        mcp_server_names,
+                        &tool_server_map,
+                        &tool_cache,
+                        max_iterations,
+                    )
+                    .await;
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        report_timings(client, provider_name, model, None, 0);
 
         if let Some(choice) = response.choices.first() {
             if let Some(tool_calls) = &choice.message.tool_calls {
@@ -1383,6 +2086,11 @@ pub async fn send_chat_request_with_tool_execution_messages(
 
                     // Execute tool calls concurrently for better performance
                     crate::debug_log!("Executing {} tool calls concurrently", tool_calls.len());
+                    let names: Vec<&str> = tool_calls
+                        .iter()
+                        .map(|call| call.function.name.as_str())
+                        .collect();
+                    crate::utils::spinner::tool_progress(iteration, max_iterations, &names);
 
                     let mut futures = Vec::new();
                     for tool_call in tool_calls.iter() {
@@ -1391,6 +2099,7 @@ pub async fn send_chat_request_with_tool_execution_messages(
                             tools.as_ref(),
                             mcp_server_names,
                             &tool_server_map,
+                            &tool_cache,
                         );
                         futures.push(future);
                     }
@@ -1408,7 +2117,8 @@ pub async fn send_chat_request_with_tool_execution_messages(
                                 ));
                             }
                             Err(e) => {
-                                eprintln!("⚠️  Tool execution error: {}", e);
+                                crate::utils::spinner::clear();
+                                eprintln!("{}  Tool execution error: {}", sym("⚠️"), e);
                                 crate::debug_log!("Tool execution error: {}", e);
                             }
                         }
@@ -1698,4 +2408,50 @@ mod tests {
         // First item should be included
         assert!(formatted.chars().filter(|&c| c == 'B').count() > 0);
     }
+
+    fn metadata(id: &str, supports_vision: bool) -> crate::model_metadata::ModelMetadata {
+        crate::model_metadata::ModelMetadata {
+            id: id.to_string(),
+            supports_vision,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_vision_support_from_metadata() {
+        let models = vec![metadata("gpt-4o", true), metadata("gpt-3.5-turbo", false)];
+
+        assert_eq!(vision_support_from_metadata(&models, "gpt-4o"), Some(true));
+        assert_eq!(
+            vision_support_from_metadata(&models, "gpt-3.5-turbo"),
+            Some(false)
+        );
+        assert_eq!(vision_support_from_metadata(&models, "unknown-model"), None);
+    }
+
+    #[test]
+    fn test_vision_support_unknown_without_capability_data() {
+        // Providers that never advertise vision tell us nothing about a single model
+        let models = vec![metadata("model-a", false), metadata("model-b", false)];
+
+        assert_eq!(vision_support_from_metadata(&models, "model-a"), None);
+    }
+
+    #[test]
+    fn test_tool_support_from_metadata() {
+        let with_tools = crate::model_metadata::ModelMetadata {
+            id: "qwen2.5".to_string(),
+            supports_tools: true,
+            ..Default::default()
+        };
+        let models = vec![with_tools, metadata("llama2", false)];
+
+        assert_eq!(tool_support_from_metadata(&models, "qwen2.5"), Some(true));
+        assert_eq!(tool_support_from_metadata(&models, "llama2"), Some(false));
+        assert_eq!(tool_support_from_metadata(&models, "unknown-model"), None);
+
+        // No capability data at all
+        let models = vec![metadata("model-a", false)];
+        assert_eq!(tool_support_from_metadata(&models, "model-a"), None);
+    }
 }
@@ -0,0 +1,122 @@
+//! `--deterministic`: repeatable runs for golden-file tests of lc-based scripts
+//!
+//! Requests carry a fixed `seed` (OpenAI-style APIs; the `seed_param` provider var
+//! renames it, e.g. `random_seed` for Mistral, or `none` leaves it out), new session
+//! IDs are numbered instead of random (continuing after the numbered IDs already in the
+//! log, so a run never picks up the history of an earlier one), printed timestamps are
+//! replaced with `-`, and the mock provider's simulated failures follow a fixed sequence. Models can still
+//! vary between runs; pair the flag with `--temperature 0` or the mock provider.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// The seed sent with requests and used for lc's own randomness
+pub const SEED: u64 = 42;
+
+/// What printed timestamps are replaced with
+pub const TIMESTAMP_PLACEHOLDER: &str = "-";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Numbered session IDs are an 8-digit number and this suffix, so the short IDs that
+/// listings print stay distinct
+const ID_SUFFIX: &str = "-0000-4000-8000-000000000000";
+
+static NEXT_ID: Mutex<Option<u64>> = Mutex::new(None);
+static RNG: Mutex<Option<StdRng>> = Mutex::new(None);
+
+/// Set `--deterministic` for this invocation
+pub fn set(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// A new session ID: a random UUID, or `00000001-0000-4000-8000-000000000000`,
+/// `00000002-...` and so on in deterministic mode. Numbering starts after the highest
+/// numbered ID in the log, so runs against the same log never share a session.
+pub fn new_id() -> String {
+    if !is_enabled() {
+        return uuid::Uuid::new_v4().to_string();
+    }
+    let mut next = NEXT_ID.lock().unwrap_or_else(|e| e.into_inner());
+    let n = next.get_or_insert_with(|| {
+        crate::database::Database::new()
+            .and_then(|db| db.session_ids_with_suffix(ID_SUFFIX))
+            .map(|ids| first_free_number(&ids))
+            .unwrap_or(1)
+    });
+    let id = numbered_id(*n);
+    *n += 1;
+    id
+}
+
+fn numbered_id(n: u64) -> String {
+    format!("{:08}{}", n, ID_SUFFIX)
+}
+
+/// The number after the highest numbered ID among `ids`
+fn first_free_number(ids: &[String]) -> u64 {
+    ids.iter()
+        .filter_map(|id| id.strip_suffix(ID_SUFFIX)?.parse::<u64>().ok())
+        .max()
+        .map_or(1, |n| n + 1)
+}
+
+/// A random number in [0, 1), from a generator seeded with [`SEED`] in deterministic mode
+pub fn random() -> f64 {
+    if !is_enabled() {
+        return rand::random();
+    }
+    let mut rng = RNG.lock().unwrap_or_else(|e| e.into_inner());
+    rng.get_or_insert_with(|| StdRng::seed_from_u64(SEED)).gen()
+}
+
+/// The request parameter the seed is sent as for a provider, if any
+pub fn seed_param(vars: Option<&HashMap<String, String>>) -> Option<String> {
+    if !is_enabled() {
+        return None;
+    }
+    seed_param_name(vars)
+}
+
+fn seed_param_name(vars: Option<&HashMap<String, String>>) -> Option<String> {
+    match vars
+        .and_then(|vars| vars.get("seed_param"))
+        .map(|v| v.trim())
+    {
+        Some("" | "none" | "off" | "false") => None,
+        Some(name) => Some(name.to_string()),
+        None => Some("seed".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_and_seed_param() {
+        assert_eq!(numbered_id(1), "00000001-0000-4000-8000-000000000000");
+        assert_ne!(
+            crate::database::short_id(&numbered_id(1)),
+            crate::database::short_id(&numbered_id(2))
+        );
+        assert!(uuid::Uuid::parse_str(&numbered_id(12)).is_ok());
+        assert_eq!(first_free_number(&[]), 1);
+        let ids = [numbered_id(1), numbered_id(7), "other".to_string()];
+        assert_eq!(first_free_number(&ids), 8);
+
+        let mut vars = HashMap::new();
+        assert_eq!(seed_param_name(Some(&vars)).as_deref(), Some("seed"));
+        assert_eq!(seed_param_name(None).as_deref(), Some("seed"));
+        vars.insert("seed_param".to_string(), "random_seed".to_string());
+        assert_eq!(seed_param_name(Some(&vars)).as_deref(), Some("random_seed"));
+        vars.insert("seed_param".to_string(), "none".to_string());
+        assert_eq!(seed_param_name(Some(&vars)), None);
+    }
+}
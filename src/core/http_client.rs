@@ -38,6 +38,297 @@ pub fn create_optimized_client() -> Result<Client> {
         .build()?)
 }
 
+/// Record-and-replay of provider HTTP interactions (VCR-style cassettes)
+///
+/// With a cassette in use, provider requests are answered from a JSON file of recorded
+/// interactions instead of the network, so tests run deterministically and without API
+/// keys. Select one with `LC_CASSETTE=<file>` (and `LC_CASSETTE_MODE`), or from code
+/// with [`cassette::use_cassette`]:
+///
+/// - `replay`: answer only from the cassette; an unrecorded request is an error
+/// - `record`: send every request and save the interactions, replacing the file
+/// - `auto` (default): replay what was recorded, send and record the rest
+///
+/// Requests match on method, URL and body (compared as JSON when it parses). Request
+/// headers are never stored, and query parameters that look like credentials (`key`,
+/// `token`, ...) are redacted before matching and saving.
+#[cfg(feature = "cassettes")]
+pub mod cassette {
+    use anyhow::{Context, Result};
+    use base64::{engine::general_purpose, Engine as _};
+    use serde::{Deserialize, Serialize};
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex, RwLock};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Mode {
+        Replay,
+        Record,
+        Auto,
+    }
+
+    impl std::str::FromStr for Mode {
+        type Err = anyhow::Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            match s.to_lowercase().as_str() {
+                "replay" => Ok(Mode::Replay),
+                "record" => Ok(Mode::Record),
+                "auto" => Ok(Mode::Auto),
+                _ => anyhow::bail!("Unknown cassette mode '{}'. Use replay, record or auto", s),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct RecordedRequest {
+        pub method: String,
+        pub url: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        pub body: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RecordedResponse {
+        pub status: u16,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub content_type: Option<String>,
+        /// The body as text, or base64 of it under `body_base64` for binary responses
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub body: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub body_base64: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Interaction {
+        pub request: RecordedRequest,
+        pub response: RecordedResponse,
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct CassetteFile {
+        interactions: Vec<Interaction>,
+    }
+
+    #[derive(Debug)]
+    pub struct Cassette {
+        path: PathBuf,
+        mode: Mode,
+        state: Mutex<State>,
+    }
+
+    #[derive(Debug, Default)]
+    struct State {
+        interactions: Vec<Interaction>,
+        used: Vec<bool>,
+    }
+
+    static ACTIVE: RwLock<Option<Arc<Cassette>>> = RwLock::new(None);
+    static FROM_ENV: std::sync::Once = std::sync::Once::new();
+
+    /// Use the cassette at `path` for all provider requests of this process
+    pub fn use_cassette(path: impl AsRef<Path>, mode: Mode) -> Result<()> {
+        let cassette = Cassette::open(path.as_ref(), mode)?;
+        if let Ok(mut active) = ACTIVE.write() {
+            *active = Some(Arc::new(cassette));
+        }
+        Ok(())
+    }
+
+    /// Stop using a cassette
+    pub fn eject() {
+        if let Ok(mut active) = ACTIVE.write() {
+            *active = None;
+        }
+    }
+
+    /// The cassette in use: one set with [`use_cassette`], or else `LC_CASSETTE`
+    pub fn active() -> Option<Arc<Cassette>> {
+        FROM_ENV.call_once(|| {
+            let Ok(path) = std::env::var("LC_CASSETTE") else {
+                return;
+            };
+            let mode = std::env::var("LC_CASSETTE_MODE")
+                .ok()
+                .map(|mode| mode.parse())
+                .transpose()
+                .and_then(|mode| Cassette::open(Path::new(&path), mode.unwrap_or(Mode::Auto)));
+            match mode {
+                Ok(cassette) => {
+                    if let Ok(mut active) = ACTIVE.write() {
+                        active.get_or_insert(Arc::new(cassette));
+                    }
+                }
+                Err(e) => eprintln!("Warning: ignoring LC_CASSETTE: {}", e),
+            }
+        });
+        ACTIVE.read().ok()?.clone()
+    }
+
+    impl Cassette {
+        fn open(path: &Path, mode: Mode) -> Result<Self> {
+            let interactions = if mode == Mode::Record || !path.exists() {
+                if mode == Mode::Replay {
+                    anyhow::bail!("Cassette '{}' does not exist", path.display());
+                }
+                Vec::new()
+            } else {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read cassette '{}'", path.display()))?;
+                serde_json::from_str::<CassetteFile>(&content)
+                    .with_context(|| format!("Invalid cassette '{}'", path.display()))?
+                    .interactions
+            };
+            let used = vec![false; interactions.len()];
+            Ok(Cassette {
+                path: path.to_path_buf(),
+                mode,
+                state: Mutex::new(State { interactions, used }),
+            })
+        }
+
+        /// The recorded response for `request`, if the mode allows replaying. Identical
+        /// requests get their recorded responses in order, the last one repeating.
+        pub fn replay(&self, request: &reqwest::Request) -> Result<Option<reqwest::Response>> {
+            if self.mode == Mode::Record {
+                return Ok(None);
+            }
+            let key = recorded_request(request);
+            let mut state = self
+                .state
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Cassette lock poisoned"))?;
+            let matches: Vec<usize> = (0..state.interactions.len())
+                .filter(|&i| same_request(&state.interactions[i].request, &key))
+                .collect();
+            let index = matches
+                .iter()
+                .copied()
+                .find(|&i| !state.used[i])
+                .or(matches.last().copied());
+            match index {
+                Some(index) => {
+                    state.used[index] = true;
+                    crate::debug_log!("Cassette: replaying {} {}", key.method, key.url);
+                    Ok(Some(to_response(&state.interactions[index].response)?))
+                }
+                None if self.mode == Mode::Replay => anyhow::bail!(
+                    "No recorded interaction for {} {} in cassette '{}'",
+                    key.method,
+                    key.url,
+                    self.path.display()
+                ),
+                None => Ok(None),
+            }
+        }
+
+        /// Save the interaction and return an equivalent response to the caller. The
+        /// body is read in full, so streamed responses arrive in one piece.
+        pub async fn record(
+            &self,
+            request: RecordedRequest,
+            response: reqwest::Response,
+        ) -> Result<reqwest::Response> {
+            let status = response.status().as_u16();
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let bytes = response.bytes().await?;
+            let (body, body_base64) = match std::str::from_utf8(&bytes) {
+                Ok(text) => (Some(text.to_string()), None),
+                Err(_) => (None, Some(general_purpose::STANDARD.encode(&bytes))),
+            };
+            let recorded = RecordedResponse {
+                status,
+                content_type,
+                body,
+                body_base64,
+            };
+            let response = to_response(&recorded)?;
+
+            let mut state = self
+                .state
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Cassette lock poisoned"))?;
+            crate::debug_log!("Cassette: recording {} {}", request.method, request.url);
+            state.interactions.push(Interaction {
+                request,
+                response: recorded,
+            });
+            state.used.push(true);
+            let file = CassetteFile {
+                interactions: state.interactions.clone(),
+            };
+            if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&self.path, serde_json::to_string_pretty(&file)?)
+                .with_context(|| format!("Failed to write cassette '{}'", self.path.display()))?;
+            Ok(response)
+        }
+    }
+
+    /// What is stored and matched for a request
+    pub fn recorded_request(request: &reqwest::Request) -> RecordedRequest {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .unwrap_or_default();
+        RecordedRequest {
+            method: request.method().to_string(),
+            url: redact_url(request.url()),
+            body,
+        }
+    }
+
+    fn redact_url(url: &reqwest::Url) -> String {
+        let mut url = url.clone();
+        if url.query().is_some() {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .map(|(name, value)| {
+                    let lower = name.to_lowercase();
+                    let secret = ["key", "token", "secret", "signature", "password"]
+                        .iter()
+                        .any(|word| lower.contains(word));
+                    let value = if secret { "REDACTED".into() } else { value };
+                    (name.into_owned(), value.into_owned())
+                })
+                .collect();
+            url.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+        url.to_string()
+    }
+
+    fn same_request(recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+        if recorded.method != request.method || recorded.url != request.url {
+            return false;
+        }
+        let json = |body: &str| serde_json::from_str::<serde_json::Value>(body).ok();
+        match (json(&recorded.body), json(&request.body)) {
+            (Some(a), Some(b)) => a == b,
+            _ => recorded.body == request.body,
+        }
+    }
+
+    fn to_response(recorded: &RecordedResponse) -> Result<reqwest::Response> {
+        let body = match (&recorded.body, &recorded.body_base64) {
+            (_, Some(encoded)) => general_purpose::STANDARD.decode(encoded)?,
+            (Some(text), None) => text.clone().into_bytes(),
+            (None, None) => Vec::new(),
+        };
+        let mut builder = http::Response::builder().status(recorded.status);
+        if let Some(content_type) = &recorded.content_type {
+            builder = builder.header("content-type", content_type);
+        }
+        Ok(reqwest::Response::from(builder.body(body)?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,383 @@
+//! Built-in mock provider for offline development (`lc providers add mock --builtin`)
+//!
+//! A provider whose endpoint is `mock://...` never touches the network: its requests are
+//! answered in-process with OpenAI-compatible responses, so scripts, pipelines and tests
+//! run without API keys or spend. Responses go through the same parsing as real ones,
+//! streaming included. Behavior is set with provider vars
+//! (`lc providers vars mock set <name> <value>`):
+//!
+//! - `response`: the answer, a template with `{prompt}`, `{model}`, `{system}` and
+//!   `{chars}` (default `Mock response to: {prompt}`)
+//! - `response_file`: read the answer template from a file instead
+//! - `latency_ms`: wait before answering
+//! - `stream_delay_ms`: wait between streamed words
+//! - `error_rate`: fraction of requests (0.0 to 1.0) that fail
+//! - `error_status`: HTTP status of those failures (default 503)
+//! - `embedding_dimensions`: size of the embeddings (default 64)
+//!
+//! Rerank requests are scored by the share of the query's words found in each document.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Endpoint scheme that marks a provider as the mock
+pub const SCHEME: &str = "mock://";
+/// Endpoint written by `lc providers add mock --builtin`
+pub const ENDPOINT: &str = "mock://local/v1";
+/// Models the mock lists; any other model name is accepted too
+pub const MODELS: &[&str] = &["mock-chat", "mock-embedding"];
+
+const DEFAULT_RESPONSE: &str = "Mock response to: {prompt}";
+const DEFAULT_ERROR_STATUS: u16 = 503;
+const DEFAULT_EMBEDDING_DIMENSIONS: usize = 64;
+
+/// Whether requests to `endpoint` are answered by the mock
+pub fn is_mock(endpoint: &str) -> bool {
+    endpoint.starts_with(SCHEME)
+}
+
+/// Answers the requests of one mock provider
+#[derive(Debug, Clone, Default)]
+pub struct MockProvider {
+    vars: HashMap<String, String>,
+}
+
+impl MockProvider {
+    pub fn new(vars: HashMap<String, String>) -> Self {
+        MockProvider { vars }
+    }
+
+    fn var<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.vars.get(name)?.trim().parse().ok()
+    }
+
+    /// The response a real provider would send to `request`
+    pub async fn respond(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        if let Some(latency) = self.var::<u64>("latency_ms") {
+            tokio::time::sleep(Duration::from_millis(latency)).await;
+        }
+
+        let error_rate = self.var::<f64>("error_rate").unwrap_or(0.0);
+        if error_rate > 0.0 && crate::core::deterministic::random() < error_rate {
+            let status = self
+                .var::<u16>("error_status")
+                .unwrap_or(DEFAULT_ERROR_STATUS);
+            crate::debug_log!("Mock provider: simulating a {} error", status);
+            return json_response(
+                status,
+                &json!({"error": {
+                    "message": "Simulated error from the mock provider",
+                    "type": "mock_error"
+                }}),
+            );
+        }
+
+        let body: Value = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok())
+            .unwrap_or(Value::Null);
+        let model = body["model"].as_str().unwrap_or(MODELS[0]).to_string();
+
+        if request.method() == reqwest::Method::GET {
+            let data: Vec<Value> = MODELS
+                .iter()
+                .map(|id| json!({"id": id, "object": "model", "owned_by": "lc"}))
+                .collect();
+            return json_response(200, &json!({"object": "list", "data": data}));
+        }
+        if let Some(messages) = body["messages"].as_array() {
+            let answer = self.answer(messages, &model)?;
+            if body["stream"].as_bool() == Some(true) {
+                return Ok(self.stream(answer, model));
+            }
+            return json_response(200, &completion(&answer, &model, messages));
+        }
+        if let Some(query) = body["query"].as_str() {
+            let documents = body["documents"].as_array().or(body["texts"].as_array());
+            return json_response(
+                200,
+                &rerank(query, documents.map_or(&[][..], Vec::as_slice)),
+            );
+        }
+        if !body["input"].is_null() {
+            return json_response(200, &self.embeddings(&body["input"], &model));
+        }
+        json_response(
+            404,
+            &json!({"error": {
+                "message": format!("The mock provider does not support {}", request.url().path()),
+                "type": "not_found"
+            }}),
+        )
+    }
+
+    fn answer(&self, messages: &[Value], model: &str) -> Result<String> {
+        let template = match self.vars.get("response_file") {
+            Some(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read mock response file '{}'", path))?,
+            None => self
+                .vars
+                .get("response")
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_RESPONSE.to_string()),
+        };
+        let text_of = |role: &str, last: bool| {
+            let mut found = messages.iter().filter(|m| m["role"] == role);
+            let message = if last {
+                found.next_back()
+            } else {
+                found.next()
+            };
+            message
+                .map(|m| message_text(&m["content"]))
+                .unwrap_or_default()
+        };
+        let prompt = text_of("user", true);
+        Ok(template
+            .replace("{model}", model)
+            .replace("{system}", &text_of("system", false))
+            .replace("{chars}", &prompt.chars().count().to_string())
+            .replace("{prompt}", &prompt))
+    }
+
+    /// The answer as an SSE stream, one word per chunk
+    fn stream(&self, answer: String, model: String) -> reqwest::Response {
+        use futures_util::StreamExt;
+
+        let delay = Duration::from_millis(self.var::<u64>("stream_delay_ms").unwrap_or(0));
+        let mut chunks: Vec<String> = answer
+            .split_inclusive(char::is_whitespace)
+            .map(|word| sse(&chunk(&model, json!({"content": word}), Value::Null)))
+            .collect();
+        chunks.push(sse(&chunk(&model, json!({}), json!("stop"))));
+        chunks.push("data: [DONE]\n\n".to_string());
+
+        let events = futures_util::stream::iter(chunks).then(move |event| async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            Ok::<_, std::io::Error>(event)
+        });
+        let response = http::Response::builder()
+            .status(200)
+            .header("content-type", "text/event-stream")
+            .body(reqwest::Body::wrap_stream(events))
+            .expect("static response parts are valid");
+        reqwest::Response::from(response)
+    }
+
+    fn embeddings(&self, input: &Value, model: &str) -> Value {
+        let dimensions = self
+            .var::<usize>("embedding_dimensions")
+            .unwrap_or(DEFAULT_EMBEDDING_DIMENSIONS)
+            .max(1);
+        // Images (`{"image": "<url>"}`) are embedded from their URL
+        let input_text = |item: &Value| match item["image"].as_str() {
+            Some(image) => image.to_string(),
+            None => message_text(item),
+        };
+        let inputs: Vec<String> = match input {
+            Value::Array(items) => items.iter().map(input_text).collect(),
+            other => vec![input_text(other)],
+        };
+        let tokens: usize = inputs.iter().map(|text| estimate_tokens(text)).sum();
+        let data: Vec<Value> = inputs
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                json!({"object": "embedding", "index": index, "embedding": embedding(text, dimensions)})
+            })
+            .collect();
+        json!({
+            "object": "list",
+            "data": data,
+            "model": model,
+            "usage": {"prompt_tokens": tokens, "total_tokens": tokens}
+        })
+    }
+}
+
+/// Text of a message content: a string, or the text parts of a multimodal array
+fn message_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str().or(part.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Rough token count, about four characters per token
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4).max(1)
+}
+
+fn completion(answer: &str, model: &str, messages: &[Value]) -> Value {
+    let prompt_tokens: usize = messages
+        .iter()
+        .map(|m| estimate_tokens(&message_text(&m["content"])))
+        .sum();
+    let completion_tokens = estimate_tokens(answer);
+    json!({
+        "id": "chatcmpl-mock",
+        "object": "chat.completion",
+        "created": chrono::Utc::now().timestamp(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": answer},
+            "finish_reason": "stop"
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens
+        }
+    })
+}
+
+fn chunk(model: &str, delta: Value, finish_reason: Value) -> Value {
+    json!({
+        "id": "chatcmpl-mock",
+        "object": "chat.completion.chunk",
+        "model": model,
+        "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}]
+    })
+}
+
+fn sse(event: &Value) -> String {
+    format!("data: {}\n\n", event)
+}
+
+/// Cohere-style rerank results: the share of the query's words in each document
+fn rerank(query: &str, documents: &[Value]) -> Value {
+    let words = |text: &str| -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let query_words = words(query);
+    let results: Vec<Value> = documents
+        .iter()
+        .enumerate()
+        .map(|(index, document)| {
+            let document_words = words(&message_text(document));
+            let found = query_words
+                .iter()
+                .filter(|word| document_words.contains(word))
+                .count();
+            let score = found as f64 / query_words.len().max(1) as f64;
+            json!({"index": index, "relevance_score": score})
+        })
+        .collect();
+    json!({"results": results})
+}
+
+/// A unit vector derived from the text's hash: equal texts get equal embeddings
+fn embedding(text: &str, dimensions: usize) -> Vec<f64> {
+    use sha2::{Digest, Sha256};
+    let mut values = Vec::with_capacity(dimensions);
+    let mut block = 0u32;
+    while values.len() < dimensions {
+        let digest = Sha256::new()
+            .chain_update(block.to_le_bytes())
+            .chain_update(text.as_bytes())
+            .finalize();
+        values.extend(
+            digest
+                .iter()
+                .take(dimensions - values.len())
+                .map(|byte| *byte as f64 / 127.5 - 1.0),
+        );
+        block += 1;
+    }
+    let norm = values.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        values.iter_mut().for_each(|v| *v /= norm);
+    }
+    values
+}
+
+fn json_response(status: u16, body: &Value) -> Result<reqwest::Response> {
+    let response = http::Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(body.to_string())?;
+    Ok(reqwest::Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_responses() {
+        let mock = MockProvider::new(HashMap::from([(
+            "response".to_string(),
+            "[{model}] {prompt} ({chars} chars)".to_string(),
+        )]));
+        let client = reqwest::Client::new();
+        let chat = client
+            .post("mock://local/v1/chat/completions")
+            .json(&json!({
+                "model": "mock-chat",
+                "messages": [
+                    {"role": "system", "content": "Be brief"},
+                    {"role": "user", "content": "Hello"}
+                ]
+            }))
+            .build()
+            .unwrap();
+        let reply: Value = mock.respond(chat).await.unwrap().json().await.unwrap();
+        assert_eq!(
+            reply["choices"][0]["message"]["content"],
+            "[mock-chat] Hello (5 chars)"
+        );
+
+        let stream = client
+            .post("mock://local/v1/chat/completions")
+            .json(&json!({"model": "m", "stream": true, "messages": [{"role": "user", "content": "a b"}]}))
+            .build()
+            .unwrap();
+        let text = mock.respond(stream).await.unwrap().text().await.unwrap();
+        // "[m] a b (3 chars)": one chunk per word, the finish chunk and [DONE]
+        assert_eq!(text.matches("data: ").count(), 7);
+        assert!(text.ends_with("data: [DONE]\n\n"));
+
+        let embed = client
+            .post("mock://local/v1/embeddings")
+            .json(
+                &json!({"model": "mock-embedding", "input": ["same", {"image": "same"}, "other"]}),
+            )
+            .build()
+            .unwrap();
+        let reply: Value = mock.respond(embed).await.unwrap().json().await.unwrap();
+        let vectors = reply["data"].as_array().unwrap();
+        assert_eq!(vectors[0]["embedding"].as_array().unwrap().len(), 64);
+        assert_eq!(vectors[0]["embedding"], vectors[1]["embedding"]);
+        assert_ne!(vectors[0]["embedding"], vectors[2]["embedding"]);
+
+        let rerank = client
+            .post("mock://local/v1/rerank")
+            .json(&json!({"model": "m", "query": "Rust borrow", "documents": ["borrow checker in Rust", "Python"]}))
+            .build()
+            .unwrap();
+        let reply: Value = mock.respond(rerank).await.unwrap().json().await.unwrap();
+        assert_eq!(reply["results"][0]["relevance_score"], 1.0);
+        assert_eq!(reply["results"][1]["relevance_score"], 0.0);
+
+        let failing = MockProvider::new(HashMap::from([
+            ("error_rate".to_string(), "1".to_string()),
+            ("error_status".to_string(), "429".to_string()),
+        ]));
+        let models = client.get("mock://local/v1/models").build().unwrap();
+        assert_eq!(failing.respond(models).await.unwrap().status(), 429);
+    }
+}
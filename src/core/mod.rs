@@ -1,7 +1,32 @@
 // Core functionality modules
+pub mod anthropic;
+pub mod attachment_budget;
+pub mod attachment_rag;
+pub mod auto_tag;
+pub mod budget;
 pub mod chat;
 pub mod completion;
+pub mod deterministic;
 pub mod http_client;
+pub mod mock_provider;
 pub mod provider;
 pub mod provider_installer;
+pub mod provider_tools;
+pub mod rate_limits;
+pub mod react;
+pub mod reasoning;
+pub mod redaction;
+pub mod refusal;
+pub mod request_metadata;
+pub mod request_signing;
+pub mod rerank;
+pub mod router;
+pub mod self_check;
+pub mod session_memory;
+pub mod structured_output;
+pub mod style;
+pub mod system_file;
+pub mod timings;
+pub mod tool_cache;
 pub mod tools;
+pub mod validation;
@@ -4,9 +4,13 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-use crate::template_processor::TemplateProcessor;
+use crate::core::anthropic;
+use crate::core::provider_tools::{ProviderTool, ToolsApi};
+use crate::core::reasoning;
+use crate::core::timings::{RequestTimings, TimingRecorder};
+use crate::template_processor::{StreamMappings, TemplateProcessor};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChatRequest {
     pub model: String,
     pub messages: Vec<Message>,
@@ -50,6 +54,43 @@ pub struct EmbeddingRequest {
     pub encoding_format: Option<String>,
 }
 
+/// A Cohere/Jina style rerank request
+#[derive(Debug, Serialize)]
+pub struct RerankRequest {
+    pub model: String,
+    pub query: String,
+    pub documents: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_n: Option<usize>,
+}
+
+/// Embeddings of images in the format of CLIP-style endpoints (Jina, CLIP servers):
+/// `{"input": [{"image": "<data or http URL>"}]}`
+#[derive(Debug, Serialize)]
+pub struct ImageEmbeddingRequest {
+    pub model: String,
+    pub input: Vec<ImageEmbeddingInput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<String>,
+}
+
+/// Several texts embedded in one request: `{"input": ["...", "..."]}`
+#[derive(Debug, Serialize)]
+struct BatchEmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    encoding_format: &'static str,
+}
+
+/// Texts sent per batch embedding request; below the limits of OpenAI (2048), Cohere
+/// (96) and Jina (2048)
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Serialize)]
+pub struct ImageEmbeddingInput {
+    pub image: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ImageGenerationRequest {
     pub prompt: String,
@@ -190,6 +231,8 @@ pub enum ContentPart {
     Text { text: String },
     #[serde(rename = "image_url")]
     ImageUrl { image_url: ImageUrl },
+    #[serde(rename = "input_audio")]
+    InputAudio { input_audio: InputAudio },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -199,6 +242,30 @@ pub struct ImageUrl {
     pub detail: Option<String>, // "low", "high", or "auto"
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InputAudio {
+    pub data: String,   // Base64 encoded audio (no data URL prefix)
+    pub format: String, // "wav", "mp3", ...
+}
+
+impl InputAudio {
+    /// Build an input audio part from a `data:audio/...;base64,` URL
+    pub fn from_data_url(data_url: &str) -> Option<Self> {
+        let (header, data) = data_url.strip_prefix("data:")?.split_once(',')?;
+        let mime_type = header.strip_suffix(";base64")?;
+        let format = match mime_type {
+            "audio/mpeg" | "audio/mp3" => "mp3",
+            "audio/mp4" => "m4a",
+            other => other.strip_prefix("audio/")?,
+        };
+
+        Some(Self {
+            data: data.to_string(),
+            format: format.to_string(),
+        })
+    }
+}
+
 impl Message {
     pub fn user(content: String) -> Self {
         Self {
@@ -231,6 +298,30 @@ impl Message {
         }
     }
 
+    pub fn user_with_images(text: String, images: &[String]) -> Self {
+        Self::user_with_media(text, images, &[])
+    }
+
+    pub fn user_with_media(text: String, images: &[String], audio: &[InputAudio]) -> Self {
+        let mut content = vec![ContentPart::Text { text }];
+        content.extend(images.iter().map(|url| ContentPart::ImageUrl {
+            image_url: ImageUrl {
+                url: url.clone(),
+                detail: Some("auto".to_string()),
+            },
+        }));
+        content.extend(audio.iter().map(|input_audio| ContentPart::InputAudio {
+            input_audio: input_audio.clone(),
+        }));
+
+        Self {
+            role: "user".to_string(),
+            content_type: MessageContent::Multimodal { content },
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
     pub fn assistant(content: String) -> Self {
         Self {
             role: "assistant".to_string(),
@@ -357,6 +448,15 @@ pub struct OpenAIClient {
     custom_headers: std::collections::HashMap<String, String>,
     provider_config: Option<crate::config::ProviderConfig>,
     template_processor: Option<TemplateProcessor>,
+    last_timings: std::sync::Mutex<Option<RequestTimings>>,
+    /// Set when the client serves a provider group, for backend health tracking
+    group_backend: Option<crate::provider_groups::SelectedBackend>,
+    /// Set for providers with `auth_type = "hmac"`; replaces the bearer token
+    signer: Option<crate::core::request_signing::RequestSigner>,
+    /// Set for `mock://` endpoints, which are answered in-process
+    mock: Option<crate::core::mock_provider::MockProvider>,
+    /// The configured provider, whose rate-limit headers are recorded
+    provider_name: Option<String>,
 }
 
 impl OpenAIClient {
@@ -373,11 +473,30 @@ impl OpenAIClient {
         // Create default headers including the required tracking headers
         let default_headers = Self::create_default_headers();
 
+        // `[http] timeout` in config.toml replaces both default timeouts
+        let timeout = crate::config::Config::load()
+            .ok()
+            .and_then(|config| config.http.timeout)
+            .map(Duration::from_secs);
+
         // Create optimized HTTP client with connection pooling and keep-alive settings
-        let client = Self::build_http_client(default_headers.clone(), Duration::from_secs(60))?;
+        let client = Self::build_http_client(
+            default_headers.clone(),
+            timeout.unwrap_or(Duration::from_secs(60)),
+        )?;
 
         // Create a separate streaming-optimized client with longer timeout
-        let streaming_client = Self::build_http_client(default_headers, Duration::from_secs(300))?;
+        let streaming_client =
+            Self::build_http_client(default_headers, timeout.unwrap_or(Duration::from_secs(300)))?;
+
+        let mock = crate::core::mock_provider::is_mock(&base_url).then(|| {
+            crate::core::mock_provider::MockProvider::new(
+                provider_config
+                    .as_ref()
+                    .map(|config| config.vars.clone())
+                    .unwrap_or_default(),
+            )
+        });
 
         // Create template processor if provider config has templates
         let template_processor = provider_config
@@ -394,6 +513,11 @@ impl OpenAIClient {
             custom_headers,
             provider_config,
             template_processor,
+            last_timings: std::sync::Mutex::new(None),
+            group_backend: None,
+            signer: None,
+            mock,
+            provider_name: None,
         })
     }
 
@@ -481,6 +605,12 @@ impl OpenAIClient {
             ))
             .default_headers(default_headers);
 
+        // Time the DNS lookups of requests made with `--timings`
+        if crate::core::timings::is_enabled() {
+            builder =
+                builder.dns_resolver(std::sync::Arc::new(crate::core::timings::TimingResolver));
+        }
+
         // Disable certificate verification for development/debugging (e.g., with Proxyman)
         if Self::should_disable_tls_verify() {
             static ONCE: std::sync::Once = std::sync::Once::new();
@@ -503,6 +633,16 @@ impl OpenAIClient {
             .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))
     }
 
+    /// Chat template configuration of the provider for a model
+    fn chat_template_config(
+        &self,
+        model: &str,
+    ) -> Option<&crate::template_processor::TemplateConfig> {
+        self.provider_config
+            .as_ref()?
+            .get_endpoint_template_config("chat", model)
+    }
+
     /// Creates a template processor if any templates are configured
     fn create_template_processor(
         config: &crate::config::ProviderConfig,
@@ -588,6 +728,13 @@ impl OpenAIClient {
                     format!("{}/embeddings", self.base_url)
                 }
             }
+            "rerank" => {
+                if let Some(ref config) = self.provider_config {
+                    config.get_rerank_url(model)
+                } else {
+                    format!("{}/rerank", self.base_url)
+                }
+            }
             "images" => {
                 if let Some(ref config) = self.provider_config {
                     config.get_images_url(model)
@@ -626,10 +773,8 @@ impl OpenAIClient {
     /// Helper method to add standard headers to a request builder
     fn add_standard_headers(&self, mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         // Add Authorization header unless a custom Authorization header is already present
-        if !self.custom_headers.contains_key("Authorization")
-            && !self.custom_headers.contains_key("authorization")
-        {
-            req = req.header("Authorization", format!("Bearer {}", self.api_key));
+        if let Some(authorization) = self.bearer_authorization() {
+            req = req.header("Authorization", authorization);
         }
 
         // Add custom headers
@@ -637,10 +782,251 @@ impl OpenAIClient {
             req = req.header(name, value);
         }
 
+        self.add_messages_api_headers(req)
+    }
+
+    /// The bearer `Authorization` header, unless a custom Authorization header, HMAC
+    /// signing or the Messages API's `x-api-key` takes its place
+    fn bearer_authorization(&self) -> Option<String> {
+        let custom = self
+            .custom_headers
+            .keys()
+            .any(|name| name.eq_ignore_ascii_case("Authorization"));
+        (!custom && self.signer.is_none() && !self.uses_messages_api())
+            .then(|| format!("Bearer {}", self.api_key))
+    }
+
+    /// Whether chat requests use the Anthropic Messages API (see `core::anthropic`)
+    fn uses_messages_api(&self) -> bool {
+        let (endpoint, chat_path) = self.provider_config.as_ref().map_or(
+            (self.base_url.as_str(), self.chat_path.as_str()),
+            |config| (config.endpoint.as_str(), config.chat_path.as_str()),
+        );
+        let chat_url = if chat_path.starts_with("http://") || chat_path.starts_with("https://") {
+            chat_path.to_string()
+        } else {
+            format!("{}{}", endpoint.trim_end_matches('/'), chat_path)
+        };
+        let vars = self.provider_config.as_ref().map(|config| &config.vars);
+        anthropic::is_messages_api(&chat_url, vars)
+    }
+
+    /// Whether a chat request for `model` is sent in the Messages schema: the provider
+    /// speaks it and no chat template translates the request
+    fn sends_messages_api(&self, model: &str) -> bool {
+        self.uses_messages_api()
+            && self
+                .provider_config
+                .as_ref()
+                .and_then(|config| config.get_endpoint_template("chat", model))
+                .is_none()
+    }
+
+    /// The `x-api-key` and `anthropic-version` headers of the Messages API, unless the
+    /// provider sets them itself
+    fn add_messages_api_headers(
+        &self,
+        mut req: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        if !self.uses_messages_api() {
+            return req;
+        }
+        let has = |header: &str| {
+            self.custom_headers
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case(header))
+        };
+        if !has("x-api-key") && !has("Authorization") && self.signer.is_none() {
+            req = req.header("x-api-key", &self.api_key);
+        }
+        if !has("anthropic-version") {
+            req = req.header("anthropic-version", anthropic::API_VERSION);
+        }
+        req
+    }
+
+    /// The Messages API body of a chat request, with the `user` as `metadata.user_id`
+    /// and structured output as a forced tool call (unless the request carries tools)
+    fn messages_api_body(&self, request: &ChatRequest) -> Result<serde_json::Value> {
+        let mut body = anthropic::request_body(request)?;
+        let vars = self.provider_config.as_ref().map(|config| &config.vars);
+        if let Some(object) = body.as_object_mut() {
+            if let Some(user) = crate::core::request_metadata::user_for(vars) {
+                object.insert(
+                    "metadata".to_string(),
+                    serde_json::json!({ "user_id": user }),
+                );
+            }
+            let has_tools = request
+                .tools
+                .as_ref()
+                .is_some_and(|tools| !tools.is_empty());
+            let schema = crate::core::structured_output::schema().filter(|_| !has_tools);
+            if let Some(schema) = schema {
+                let (tool, choice) =
+                    crate::core::structured_output::forced_tool_messages_api(&schema);
+                force_tool(object, tool, choice);
+            }
+        }
+        Ok(body)
+    }
+
+    /// Whether requests are signed with HMAC instead of carrying a bearer token
+    pub fn signs_requests(&self) -> bool {
+        self.signer.is_some()
+    }
+
+    /// Send a request, recording the rate-limit headers of the response (failed ones
+    /// included) for the provider
+    pub async fn send(&self, req: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = self.dispatch(req).await?;
+        if let (Some(provider), Some(limits)) = (
+            self.provider_name.as_deref(),
+            crate::core::rate_limits::RateLimits::from_headers(response.headers()),
+        ) {
+            crate::core::rate_limits::record(provider, limits);
+        }
+        Ok(response)
+    }
+
+    /// Send a request, signing it first for providers with HMAC auth. Requests to the
+    /// mock provider are answered without going to the network, and so are requests
+    /// recorded in the cassette in use (`cassettes` feature).
+    async fn dispatch(&self, req: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        #[cfg(feature = "cassettes")]
+        if let Some(cassette) = crate::http_client::cassette::active() {
+            let (client, request) = req.build_split();
+            let request = request?;
+            if let Some(response) = cassette.replay(&request)? {
+                return Ok(response);
+            }
+            let recorded = crate::http_client::cassette::recorded_request(&request);
+            let response = self.execute(client, request).await?;
+            return cassette.record(recorded, response).await;
+        }
+
+        if self.signer.is_none() && self.mock.is_none() {
+            return Ok(req.send().await?);
+        }
+        let (client, request) = req.build_split();
+        self.execute(client, request?).await
+    }
+
+    /// Sign and send a built request, or have the mock answer it
+    async fn execute(
+        &self,
+        client: reqwest::Client,
+        mut request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        if let Some(signer) = &self.signer {
+            signer.sign(&mut request)?;
+        }
+        match &self.mock {
+            Some(mock) => mock.respond(request).await,
+            None => Ok(client.execute(request).await?),
+        }
+    }
+
+    /// Serialize a chat request body, adding `user` (and `metadata` for providers that
+    /// opt in with the `send_metadata` var) for provider-side attribution, and the
+    /// `response_format` of structured output (a forced tool call for providers with the
+    /// `structured_output = "tool"` var, unless the request already carries tools)
+    fn with_request_metadata<T: Serialize>(&self, body: &T) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(body)?;
+        let vars = self.provider_config.as_ref().map(|config| &config.vars);
+        if let Some(object) = value.as_object_mut() {
+            if let Some(format) = crate::core::structured_output::current() {
+                let tool_schema = crate::core::structured_output::schema().filter(|_| {
+                    crate::core::structured_output::uses_tool(vars)
+                        && object.get("tools").is_none_or(|tools| tools.is_null())
+                });
+                match tool_schema {
+                    Some(schema) => {
+                        let (tool, choice) = crate::core::structured_output::forced_tool(&schema);
+                        force_tool(object, tool, choice);
+                    }
+                    None => {
+                        object.insert("response_format".to_string(), format);
+                    }
+                }
+            }
+            if let Some(user) = crate::core::request_metadata::user_for(vars) {
+                object.insert("user".to_string(), serde_json::Value::String(user));
+            }
+            if let Some(param) = crate::core::deterministic::seed_param(vars) {
+                object
+                    .entry(param)
+                    .or_insert(crate::core::deterministic::SEED.into());
+            }
+            let metadata = crate::core::request_metadata::current().metadata;
+            let send_metadata = vars
+                .and_then(|vars| vars.get("send_metadata"))
+                .is_some_and(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes" | "on"));
+            if send_metadata && !metadata.is_empty() {
+                object.insert("metadata".to_string(), serde_json::to_value(&metadata)?);
+            }
+        }
+        Ok(value)
+    }
+
+    /// The API and tools to use when `--provider-tools` is set
+    fn provider_tools(&self) -> Option<(ToolsApi, Vec<ProviderTool>)> {
+        let tools = crate::core::provider_tools::current();
+        if tools.is_empty() {
+            return None;
+        }
+        let endpoint = self
+            .provider_config
+            .as_ref()
+            .map_or(self.base_url.as_str(), |config| config.endpoint.as_str());
+        let vars = self.provider_config.as_ref().map(|config| &config.vars);
+        Some((ToolsApi::detect(endpoint, vars), tools))
+    }
+
+    /// Set the request body, adding the provider tool declarations (and any header
+    /// they need) when `--provider-tools` is set
+    fn json_with_provider_tools(
+        &self,
+        mut req: reqwest::RequestBuilder,
+        mut body: serde_json::Value,
+        provider_tools: Option<&(ToolsApi, Vec<ProviderTool>)>,
+    ) -> Result<reqwest::RequestBuilder> {
+        if let Some((api, tools)) = provider_tools {
+            crate::core::provider_tools::apply(*api, tools, &mut body)?;
+            if let Some((name, value)) = crate::core::provider_tools::beta_header(*api, tools) {
+                req = req.header(name, value);
+            }
+        }
+        Ok(req.json(&body))
+    }
+
+    /// `--metadata title=...` and `referer=...` replace the default `X-Title` and
+    /// `HTTP-Referer` headers that OpenRouter uses to attribute traffic to an app
+    fn add_attribution_headers(&self, mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let metadata = crate::core::request_metadata::current().metadata;
+        if let Some(title) = metadata.get("title") {
+            req = req.header("X-Title", title);
+        }
+        if let Some(referer) = metadata.get("referer") {
+            req = req.header("HTTP-Referer", referer);
+        }
         req
     }
 
     pub async fn chat(&self, request: &ChatRequest) -> Result<String> {
+        let result = self.send_chat(request).await;
+        self.report_group_backend(&result);
+        result.map(|content| match reasoning::split_think_tags(&content) {
+            Some((trace, answer)) => {
+                reasoning::show(&trace);
+                reasoning::record(trace);
+                answer
+            }
+            None => content,
+        })
+    }
+
+    async fn send_chat(&self, request: &ChatRequest) -> Result<String> {
         let url = self.get_chat_url(&request.model);
 
         let mut req = self
@@ -655,16 +1041,16 @@ impl OpenAIClient {
 
         // Add Authorization header unless a custom Authorization header is already present
         // This allows providers like Gemini to use custom authentication headers
-        if !self.custom_headers.contains_key("Authorization")
-            && !self.custom_headers.contains_key("authorization")
-        {
-            req = req.header("Authorization", format!("Bearer {}", self.api_key));
+        if let Some(authorization) = self.bearer_authorization() {
+            req = req.header("Authorization", authorization);
         }
 
         // Add custom headers
         for (name, value) in &self.custom_headers {
             req = req.header(name, value);
         }
+        req = self.add_messages_api_headers(req);
+        req = self.add_attribution_headers(req);
 
         // Check if we have a template for this provider/model/endpoint
         let request_body = if let Some(ref config) = &self.provider_config {
@@ -677,6 +1063,7 @@ impl OpenAIClient {
                     match processor.process_request(request, &template_str, &config.vars) {
                         Ok(json_value) => Some(json_value),
                         Err(e) => {
+                            crate::utils::spinner::clear();
                             eprintln!("Warning: Failed to process request template: {}. Falling back to default.", e);
                             None
                         }
@@ -691,9 +1078,17 @@ impl OpenAIClient {
             None
         };
 
+        let mut recorder = if crate::core::timings::is_enabled() {
+            Some(TimingRecorder::start())
+        } else {
+            None
+        };
+
         // Send request with template-processed body or fall back to default logic
-        let response = if let Some(json_body) = request_body {
-            req.json(&json_body).send().await?
+        let body = if let Some(json_body) = request_body {
+            json_body
+        } else if self.sends_messages_api(&request.model) {
+            self.messages_api_body(request)?
         } else {
             // Fall back to existing logic
             // Check if we should exclude model from payload (when model is in URL path)
@@ -706,20 +1101,88 @@ impl OpenAIClient {
             if should_exclude_model {
                 // Use ChatRequestWithoutModel for providers that specify model in URL
                 let request_without_model = ChatRequestWithoutModel::from(request);
-                req.json(&request_without_model).send().await?
+                self.with_request_metadata(&request_without_model)?
             } else {
-                req.json(request).send().await?
+                self.with_request_metadata(request)?
             }
         };
+        let provider_tools = self.provider_tools();
+        let response = self
+            .send(self.json_with_provider_tools(req, body, provider_tools.as_ref())?)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("API request failed with status {}: {}", status, text);
+            return Err(crate::error::api_error("API", status, &text, &url));
+        }
+
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.mark_headers();
         }
 
         // Get the response text first to handle different formats
         let response_text = response.text().await?;
+        self.store_timings(recorder);
+
+        // Reasoning models return their trace next to the answer
+        let response_json = serde_json::from_str::<serde_json::Value>(&response_text).ok();
+        if let Some(reasoning) = response_json.as_ref().and_then(reasoning::from_response) {
+            reasoning::show(&reasoning);
+            reasoning::record(reasoning);
+        }
+        if let Some(refusal) = response_json
+            .as_ref()
+            .filter(|_| crate::core::refusal::detects_answers())
+            .and_then(crate::core::refusal::from_response)
+        {
+            return Err(refusal.into());
+        }
+
+        // Answers from provider-side tools carry tool blocks and citations
+        if let Some((api, _)) = provider_tools {
+            if let Some(content) = crate::core::provider_tools::render_response(api, &response_text)
+            {
+                return Ok(content);
+            }
+        }
+
+        // A forced structured-output tool call carries the answer as its arguments
+        if let Some(answer) = response_json
+            .as_ref()
+            .and_then(crate::core::structured_output::forced_tool_answer)
+        {
+            return Ok(answer);
+        }
+
+        if let Some(answer) = response_json.as_ref().and_then(reasoning::anthropic_answer) {
+            return Ok(answer);
+        }
+
+        // Response mappings point at the answer directly
+        if let (Some(mappings), Some(response_json)) = (
+            self.chat_template_config(&request.model)
+                .and_then(|t| t.response_mappings.as_ref()),
+            response_json.as_ref(),
+        ) {
+            if let Some(error) = mappings.error(response_json) {
+                anyhow::bail!("API returned an error: {}", error);
+            }
+            if let Some(reasoning) = mappings.reasoning(response_json) {
+                reasoning::record(reasoning);
+            }
+            let tool_calls = mappings.tool_calls(response_json);
+            if !tool_calls.is_empty() {
+                let mut response = String::new();
+                response.push_str("🔧 **Tool Calls Made:**\n\n");
+                response.push_str(&format!("Tool calls: {:?}\n\n", tool_calls));
+                response.push_str("*Tool calls detected - execution handled by chat module*\n\n");
+                return Ok(response);
+            }
+            if let Some(content) = mappings.content(response_json) {
+                return Ok(content);
+            }
+        }
 
         // Check if we have a response template for this provider/model/endpoint
         if let Some(ref config) = &self.provider_config {
@@ -754,6 +1217,7 @@ impl OpenAIClient {
                                 }
                             }
                             Err(e) => {
+                                crate::utils::spinner::clear();
                                 eprintln!("Warning: Failed to process response template: {}. Falling back to default parsing.", e);
                             }
                         }
@@ -763,8 +1227,14 @@ impl OpenAIClient {
         }
 
         // Fall back to existing parsing logic
-        // Try to parse as standard OpenAI format (with "choices" array)
-        if let Ok(chat_response) = serde_json::from_str::<ChatResponse>(&response_text) {
+        // Try to parse as standard OpenAI format (with "choices" array), or as a Messages
+        // API response
+        let chat_response = if self.sends_messages_api(&request.model) {
+            response_json.as_ref().and_then(anthropic::to_chat_response)
+        } else {
+            serde_json::from_str::<ChatResponse>(&response_text).ok()
+        };
+        if let Some(chat_response) = chat_response {
             if let Some(choice) = chat_response.choices.first() {
                 // Handle tool calls - check if tool_calls exists AND is not empty
                 if let Some(tool_calls) = &choice.message.tool_calls {
@@ -822,13 +1292,13 @@ impl OpenAIClient {
         // Add standard headers using helper method
         req = self.add_standard_headers(req);
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
             crate::debug_log!("API request failed with status {}: {}", status, text);
-            anyhow::bail!("API request failed with status {}: {}", status, text);
+            return Err(crate::error::api_error("API", status, &text, &url));
         }
 
         // Get the response text first to handle different formats
@@ -921,6 +1391,12 @@ impl OpenAIClient {
 
     // New method that returns the full parsed response for tool handling
     pub async fn chat_with_tools(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        let result = self.send_chat_with_tools(request).await;
+        self.report_group_backend(&result);
+        result
+    }
+
+    async fn send_chat_with_tools(&self, request: &ChatRequest) -> Result<ChatResponse> {
         let url = self.get_chat_url(&request.model);
 
         let mut req = self
@@ -934,16 +1410,16 @@ impl OpenAIClient {
         }
 
         // Add Authorization header unless a custom Authorization header is already present
-        if !self.custom_headers.contains_key("Authorization")
-            && !self.custom_headers.contains_key("authorization")
-        {
-            req = req.header("Authorization", format!("Bearer {}", self.api_key));
+        if let Some(authorization) = self.bearer_authorization() {
+            req = req.header("Authorization", authorization);
         }
 
         // Add custom headers
         for (name, value) in &self.custom_headers {
             req = req.header(name, value);
         }
+        req = self.add_messages_api_headers(req);
+        req = self.add_attribution_headers(req);
 
         // Check if we should exclude model from payload (when model is in URL path)
         let should_exclude_model = if let Some(ref config) = self.provider_config {
@@ -952,22 +1428,62 @@ impl OpenAIClient {
             self.chat_path.contains("{model}")
         };
 
-        let response = if should_exclude_model {
+        let mut recorder = if crate::core::timings::is_enabled() {
+            Some(TimingRecorder::start())
+        } else {
+            None
+        };
+
+        let body = if self.sends_messages_api(&request.model) {
+            self.messages_api_body(request)?
+        } else if should_exclude_model {
             // Use ChatRequestWithoutModel for providers that specify model in URL
             let request_without_model = ChatRequestWithoutModel::from(request);
-            req.json(&request_without_model).send().await?
+            self.with_request_metadata(&request_without_model)?
         } else {
-            req.json(request).send().await?
+            self.with_request_metadata(request)?
         };
+        let response = self
+            .send(self.json_with_provider_tools(req, body, self.provider_tools().as_ref())?)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("API request failed with status {}: {}", status, text);
+            return Err(crate::error::api_error("API", status, &text, &url));
+        }
+
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.mark_headers();
         }
 
         // Get the response text first to handle different formats
         let response_text = response.text().await?;
+        self.store_timings(recorder);
+
+        let response_json = serde_json::from_str::<serde_json::Value>(&response_text).ok();
+        if let Some(reasoning) = response_json.as_ref().and_then(reasoning::from_response) {
+            reasoning::show(&reasoning);
+            reasoning::record(reasoning);
+        }
+        if let Some(refusal) = response_json
+            .as_ref()
+            .filter(|_| crate::core::refusal::detects_answers())
+            .and_then(crate::core::refusal::from_response)
+        {
+            return Err(refusal.into());
+        }
+
+        // Messages API responses carry tool calls as tool_use blocks
+        if self.sends_messages_api(&request.model) {
+            if let Some(chat_response) = serde_json::from_str::<serde_json::Value>(&response_text)
+                .ok()
+                .as_ref()
+                .and_then(anthropic::to_chat_response)
+            {
+                return Ok(chat_response);
+            }
+        }
 
         // Try to parse as standard OpenAI format (with "choices" array)
         if let Ok(chat_response) = serde_json::from_str::<ChatResponse>(&response_text) {
@@ -1042,19 +1558,20 @@ impl OpenAIClient {
 
         // Send request with template-processed body or fall back to default logic
         let response = if let Some(json_body) = request_body {
-            req.json(&json_body).send().await?
+            self.send(req.json(&json_body)).await?
         } else {
-            req.json(request).send().await?
+            self.send(req.json(request)).await?
         };
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Embeddings API request failed with status {}: {}",
+            return Err(crate::error::api_error(
+                "Embeddings API",
                 status,
-                text
-            );
+                &text,
+                &url,
+            ));
         }
 
         // Get the response text first to handle different formats
@@ -1095,6 +1612,141 @@ impl OpenAIClient {
         Ok(embedding_response)
     }
 
+    /// Embed several texts, in requests of up to [`EMBEDDING_BATCH_SIZE`] texts. Providers
+    /// with an embeddings request template get one request per text, since templates
+    /// take a single input. Returns one vector per text, in order.
+    pub async fn embed_batch(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f64>>> {
+        let templated = self
+            .provider_config
+            .as_ref()
+            .is_some_and(|config| config.get_endpoint_template("embeddings", model).is_some());
+        if templated {
+            let mut vectors = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                let request = EmbeddingRequest {
+                    model: model.to_string(),
+                    input: input.clone(),
+                    encoding_format: Some("float".to_string()),
+                };
+                let response = self.embeddings(&request).await?;
+                let vector = response
+                    .data
+                    .into_iter()
+                    .next()
+                    .map(|data| data.embedding)
+                    .ok_or_else(|| anyhow::anyhow!("No embedding data in response"))?;
+                vectors.push(vector);
+            }
+            return Ok(vectors);
+        }
+
+        #[derive(Deserialize)]
+        struct Indexed {
+            #[serde(default)]
+            index: Option<usize>,
+            embedding: Vec<f64>,
+        }
+        #[derive(Deserialize)]
+        struct BatchResponse {
+            data: Vec<Indexed>,
+        }
+
+        let url = self.build_url("embeddings", model, "/embeddings");
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(EMBEDDING_BATCH_SIZE) {
+            let req = self
+                .client
+                .post(&url)
+                .header("Content-Type", "application/json");
+            let req = self.add_standard_headers(req);
+            let body = BatchEmbeddingRequest {
+                model,
+                input: batch,
+                encoding_format: "float",
+            };
+            let response = self.send(req.json(&body)).await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(crate::error::api_error(
+                    "Embeddings API",
+                    status,
+                    &text,
+                    &url,
+                ));
+            }
+            let mut data = response.json::<BatchResponse>().await?.data;
+            if data.len() != batch.len() {
+                anyhow::bail!(
+                    "Expected {} embeddings in the response, got {}",
+                    batch.len(),
+                    data.len()
+                );
+            }
+            data.sort_by_key(|item| item.index);
+            vectors.extend(data.into_iter().map(|item| item.embedding));
+        }
+        Ok(vectors)
+    }
+
+    /// Embed images with a multimodal embedding model. The vectors share a space with
+    /// the model's text embeddings, so text queries retrieve images.
+    pub async fn image_embeddings(
+        &self,
+        request: &ImageEmbeddingRequest,
+    ) -> Result<EmbeddingResponse> {
+        let url = self.build_url("embeddings", &request.model, "/embeddings");
+        let req = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        let req = self.add_standard_headers(req);
+
+        let response = self.send(req.json(request)).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(crate::error::api_error(
+                "Embeddings API",
+                status,
+                &text,
+                &url,
+            ));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Score the documents of `request` against its query with a rerank model. Returns
+    /// the index and relevance score of each scored document.
+    pub async fn rerank(&self, request: &RerankRequest) -> Result<Vec<(usize, f64)>> {
+        let url = self.build_url("rerank", &request.model, "/rerank");
+        let req = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        let req = self.add_standard_headers(req);
+
+        // text-embeddings-inference takes the documents as `texts`
+        let tei = self
+            .provider_config
+            .as_ref()
+            .and_then(|config| config.vars.get("rerank_format"))
+            .is_some_and(|format| format.eq_ignore_ascii_case("tei"));
+        let response = if tei {
+            let body = serde_json::json!({"query": request.query, "texts": request.documents});
+            self.send(req.json(&body)).await?
+        } else {
+            self.send(req.json(request)).await?
+        };
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(crate::error::api_error("Rerank API", status, &text, &url));
+        }
+        let response: serde_json::Value = response.json().await?;
+        crate::core::rerank::parse_scores(&response)
+    }
+
     pub async fn generate_images(
         &self,
         request: &ImageGenerationRequest,
@@ -1139,19 +1791,20 @@ impl OpenAIClient {
 
         // Send request with template-processed body or fall back to default logic
         let response = if let Some(json_body) = request_body {
-            req.json(&json_body).send().await?
+            self.send(req.json(&json_body)).await?
         } else {
-            req.json(request).send().await?
+            self.send(req.json(request)).await?
         };
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Image generation API request failed with status {}: {}",
+            return Err(crate::error::api_error(
+                "Image generation API",
                 status,
-                text
-            );
+                &text,
+                &url,
+            ));
         }
 
         // Get the response text first to handle different formats
@@ -1273,16 +1926,17 @@ impl OpenAIClient {
         req = self.add_standard_headers(req);
 
         // Send multipart form request
-        let response = req.multipart(form).send().await?;
+        let response = self.send(req.multipart(form)).await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Audio transcription API request failed with status {}: {}",
+            return Err(crate::error::api_error(
+                "Audio transcription API",
                 status,
-                text
-            );
+                &text,
+                &url,
+            ));
         }
 
         // Get the response text first to handle different formats
@@ -1376,19 +2030,20 @@ impl OpenAIClient {
 
         // Send request with template-processed body or fall back to default logic
         let response = if let Some(json_body) = request_body {
-            req.json(&json_body).send().await?
+            self.send(req.json(&json_body)).await?
         } else {
-            req.json(request).send().await?
+            self.send(req.json(request)).await?
         };
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Speech generation API request failed with status {}: {}",
+            return Err(crate::error::api_error(
+                "Speech generation API",
                 status,
-                text
-            );
+                &text,
+                &url,
+            ));
         }
 
         // Get the response text first to handle different formats
@@ -1449,8 +2104,77 @@ impl OpenAIClient {
         Ok(response_text.into_bytes())
     }
 
-    pub async fn chat_stream(&self, request: &ChatRequest) -> Result<()> {
-        use std::io::{stdout, Write};
+    fn store_timings(&self, recorder: Option<TimingRecorder>) {
+        if let (Some(recorder), Ok(mut last)) = (recorder, self.last_timings.lock()) {
+            *last = Some(recorder.finish());
+        }
+    }
+
+    /// Mark this client as serving `backend` of a provider group
+    pub fn set_group_backend(&mut self, backend: crate::provider_groups::SelectedBackend) {
+        self.group_backend = Some(backend);
+    }
+
+    /// Sign every request with HMAC instead of sending a bearer token
+    pub fn set_signer(&mut self, signer: crate::core::request_signing::RequestSigner) {
+        self.signer = Some(signer);
+    }
+
+    /// Record the rate-limit headers of responses under `provider`
+    pub fn set_provider_name(&mut self, provider: &str) {
+        self.provider_name = Some(provider.to_string());
+    }
+
+    pub fn provider_name(&self) -> Option<&str> {
+        self.provider_name.as_deref()
+    }
+
+    pub fn group_backend(&self) -> Option<&crate::provider_groups::SelectedBackend> {
+        self.group_backend.as_ref()
+    }
+
+    /// Update the health of the group backend after a request. Errors caused by the
+    /// request itself (e.g. a 400) don't count against the backend.
+    fn report_group_backend<T>(&self, result: &Result<T>) {
+        let Some(backend) = &self.group_backend else {
+            return;
+        };
+        match result {
+            Ok(_) => crate::provider_groups::report(backend, true),
+            Err(e) if crate::provider_groups::is_backend_failure(e) => {
+                crate::provider_groups::report(backend, false)
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Take the timings recorded for the most recent chat request (with `--timings`)
+    pub fn take_timings(&self) -> Option<RequestTimings> {
+        self.last_timings
+            .lock()
+            .ok()
+            .and_then(|mut last| last.take())
+    }
+
+    /// Whether the provider can continue a dropped stream from an assistant prefill.
+    /// Opt in per provider with `lc providers vars <provider> set stream_resume true`.
+    pub fn supports_stream_resume(&self) -> bool {
+        self.provider_config
+            .as_ref()
+            .and_then(|config| config.vars.get("stream_resume"))
+            .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+            .unwrap_or(false)
+    }
+
+    pub async fn chat_stream(&self, request: &ChatRequest) -> Result<StreamOutcome> {
+        let result = self.send_chat_stream(request).await;
+        self.report_group_backend(&result);
+        result
+    }
+
+    async fn send_chat_stream(&self, request: &ChatRequest) -> Result<StreamOutcome> {
+        use colored::Colorize;
+        use std::io::stdout;
 
         let url = self.get_chat_url(&request.model);
 
@@ -1469,6 +2193,7 @@ impl OpenAIClient {
 
         // Add standard headers using helper method
         req = self.add_standard_headers(req);
+        req = self.add_attribution_headers(req);
 
         // Build request body using template if available (same logic as non-streaming chat)
         let request_body = if let Some(ref config) = &self.provider_config {
@@ -1481,6 +2206,7 @@ impl OpenAIClient {
                     match processor.process_request(request, &template_str, &config.vars) {
                         Ok(json_value) => Some(json_value),
                         Err(e) => {
+                            crate::utils::spinner::clear();
                             eprintln!("Warning: Failed to process request template: {}. Falling back to default.", e);
                             None
                         }
@@ -1502,21 +2228,36 @@ impl OpenAIClient {
             self.chat_path.contains("{model}")
         };
 
+        let mut recorder = if crate::core::timings::is_enabled() {
+            Some(TimingRecorder::start())
+        } else {
+            None
+        };
+
         // Send request with template-processed body or fall back to default logic
-        let response = if let Some(json_body) = request_body {
-            req.json(&json_body).send().await?
+        let body = if let Some(json_body) = request_body {
+            json_body
+        } else if self.sends_messages_api(&request.model) {
+            self.messages_api_body(request)?
         } else if should_exclude_model {
             // Use ChatRequestWithoutModel for providers that specify model in URL
             let request_without_model = ChatRequestWithoutModel::from(request);
-            req.json(&request_without_model).send().await?
+            self.with_request_metadata(&request_without_model)?
         } else {
-            req.json(request).send().await?
+            self.with_request_metadata(request)?
         };
+        let response = self
+            .send(self.json_with_provider_tools(req, body, self.provider_tools().as_ref())?)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("API request failed with status {}: {}", status, text);
+            return Err(crate::error::api_error("API", status, &text, &url));
+        }
+
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.mark_headers();
         }
 
         // Check for compression headers (silent check for potential issues)
@@ -1528,9 +2269,40 @@ impl OpenAIClient {
         let mut stream = response.bytes_stream();
 
         let mut buffer = String::new();
+        let mut parser = StreamParser {
+            mappings: self
+                .chat_template_config(&request.model)
+                .and_then(|t| t.stream_mappings.clone()),
+            ..Default::default()
+        };
+        let mut showing_reasoning = false;
+        // Secrets are masked before anything reaches the terminal
+        let mut redactor = crate::core::redaction::StreamRedactor::default();
+        let mut reasoning_redactor = crate::core::redaction::StreamRedactor::default();
+        // Code blocks and tables are printed once they are complete
+        let mut renderer = crate::utils::stream_render::StreamRenderer::for_stdout();
 
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) if !parser.text.is_empty() => {
+                    // Keep whatever already arrived instead of throwing it away
+                    crate::debug_log!(
+                        "Stream interrupted after {} bytes: {}",
+                        parser.text.len(),
+                        e
+                    );
+                    let rest = renderer.push(&redactor.finish()) + &renderer.finish();
+                    write_streamed(&mut handle, rest.as_bytes())?;
+                    self.store_timings(recorder);
+                    reasoning::record(parser.reasoning);
+                    return Ok(StreamOutcome {
+                        text: parser.text,
+                        truncated: true,
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            };
 
             let chunk_str = String::from_utf8_lossy(&chunk);
             buffer.push_str(&chunk_str);
@@ -1540,106 +2312,240 @@ impl OpenAIClient {
                 let line = buffer[..newline_pos].to_string();
                 buffer.drain(..=newline_pos);
 
-                // Handle Server-Sent Events format
-                if let Some(data) = line.strip_prefix("data: ") {
-                    // Remove "data: " prefix
-
-                    if data.trim() == "[DONE]" {
-                        handle.write_all(b"\n")?;
-                        handle.flush()?;
-                        return Ok(());
-                    }
-
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
-                        // Try direct "response" field format first
-                        if let Some(response) = json.get("response") {
-                            if let Some(text) = response.as_str() {
-                                if !text.is_empty() {
-                                    handle.write_all(text.as_bytes())?;
-                                    handle.flush()?;
-                                }
-                            }
-                        }
-                        // Try standard OpenAI streaming format
-                        else if let Some(choices) = json.get("choices") {
-                            if let Some(choice) = choices.get(0) {
-                                if let Some(delta) = choice.get("delta") {
-                                    if let Some(content) = delta.get("content") {
-                                        if let Some(text) = content.as_str() {
-                                            // Write directly to stdout and flush immediately
-                                            handle.write_all(text.as_bytes())?;
-                                            handle.flush()?;
-                                        }
-                                    }
-                                }
+                let event = parser.process_line(&line);
+                if !matches!(event, StreamEvent::None) {
+                    // The first token ends the wait
+                    crate::utils::spinner::stop();
+                }
+                match event {
+                    StreamEvent::Reasoning(text) => {
+                        if reasoning::is_shown() {
+                            if !showing_reasoning {
+                                eprintln!("{}", "Reasoning:".dimmed().bold());
+                                showing_reasoning = true;
                             }
+                            eprint!("{}", reasoning_redactor.push(&text).dimmed());
                         }
                     }
-                } else if line.trim().is_empty() {
-                    // Skip empty lines in SSE format
-                    continue;
-                } else {
-                    // Handle non-SSE format (direct JSON stream)
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                        // Try direct "response" field format first
-                        if let Some(response) = json.get("response") {
-                            if let Some(text) = response.as_str() {
-                                if !text.is_empty() {
-                                    handle.write_all(text.as_bytes())?;
-                                    handle.flush()?;
-                                }
-                            }
+                    StreamEvent::Text(text) => {
+                        if showing_reasoning {
+                            eprintln!("{}\n", reasoning_redactor.finish().dimmed());
+                            showing_reasoning = false;
                         }
-                        // Try standard OpenAI streaming format
-                        else if let Some(choices) = json.get("choices") {
-                            if let Some(choice) = choices.get(0) {
-                                if let Some(delta) = choice.get("delta") {
-                                    if let Some(content) = delta.get("content") {
-                                        if let Some(text) = content.as_str() {
-                                            handle.write_all(text.as_bytes())?;
-                                            handle.flush()?;
-                                        }
-                                    }
-                                }
-                            }
+                        if let Some(recorder) = recorder.as_mut() {
+                            recorder.mark_first_token();
+                        }
+                        // Write directly to stdout and flush immediately
+                        let ready = renderer.push(&redactor.push(&text));
+                        if !write_streamed(&mut handle, ready.as_bytes())? {
+                            // The reader closed the pipe (e.g. `| head`); stop the request
+                            crate::debug_log!("stdout closed, ending the stream early");
+                            self.store_timings(recorder);
+                            reasoning::record(parser.reasoning);
+                            return Ok(StreamOutcome {
+                                text: parser.text,
+                                truncated: false,
+                            });
                         }
                     }
+                    StreamEvent::Done => {
+                        let rest = renderer.push(&redactor.finish()) + &renderer.finish();
+                        write_streamed(&mut handle, rest.as_bytes())?;
+                        write_streamed(&mut handle, b"\n")?;
+                        self.store_timings(recorder);
+                        reasoning::record(parser.reasoning);
+                        return Ok(StreamOutcome {
+                            text: parser.text,
+                            truncated: false,
+                        });
+                    }
+                    StreamEvent::None => {}
                 }
             }
         }
 
+        crate::utils::spinner::stop();
         // Process any remaining data in buffer
         if !buffer.trim().is_empty() {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&buffer) {
-                // Try direct "response" field format first
-                if let Some(response) = json.get("response") {
-                    if let Some(text) = response.as_str() {
-                        if !text.is_empty() {
-                            handle.write_all(text.as_bytes())?;
-                            handle.flush()?;
-                        }
-                    }
+            if let StreamEvent::Text(text) = parser.process_line(&buffer) {
+                write_streamed(&mut handle, renderer.push(&redactor.push(&text)).as_bytes())?;
+            }
+        }
+        let rest = renderer.push(&redactor.finish()) + &renderer.finish();
+        write_streamed(&mut handle, rest.as_bytes())?;
+
+        // Add newline at the end
+        write_streamed(&mut handle, b"\n")?;
+        self.store_timings(recorder);
+        reasoning::record(parser.reasoning);
+        Ok(StreamOutcome {
+            text: parser.text,
+            truncated: false,
+        })
+    }
+}
+
+/// Add `tool` to a request body's tools and force the model to call it
+fn force_tool(
+    object: &mut serde_json::Map<String, serde_json::Value>,
+    tool: serde_json::Value,
+    choice: serde_json::Value,
+) {
+    match object
+        .get_mut("tools")
+        .and_then(|tools| tools.as_array_mut())
+    {
+        Some(tools) => tools.push(tool),
+        None => {
+            object.insert("tools".to_string(), serde_json::Value::Array(vec![tool]));
+        }
+    }
+    object.insert("tool_choice".to_string(), choice);
+}
+
+/// Write streamed text to stdout and flush it. A slow reader makes the write block, so
+/// the response is not read further ahead than the reader can keep up with. Returns
+/// `false` when the reader has closed the pipe.
+fn write_streamed(handle: &mut impl std::io::Write, bytes: &[u8]) -> std::io::Result<bool> {
+    match handle.write_all(bytes).and_then(|_| handle.flush()) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Result of a streaming chat request
+#[derive(Debug, Default, Clone)]
+pub struct StreamOutcome {
+    /// Text that was streamed to stdout
+    pub text: String,
+    /// Set when the connection dropped before the provider finished the response
+    pub truncated: bool,
+}
+
+enum StreamEvent {
+    Text(String),
+    Reasoning(String),
+    Done,
+    None,
+}
+
+/// Incremental parser for SSE and line-delimited JSON streams
+#[derive(Default)]
+struct StreamParser {
+    text: String,
+    reasoning: String,
+    // A data payload that didn't parse on its own, waiting for the rest of the JSON
+    pending: String,
+    // Where text, reasoning and the end marker live in chunks of a templated provider
+    mappings: Option<StreamMappings>,
+}
+
+impl StreamParser {
+    // Give up on reassembling a payload once it grows past this size
+    const MAX_PENDING_LEN: usize = 64 * 1024;
+
+    fn process_line(&mut self, line: &str) -> StreamEvent {
+        let line = line.trim_end_matches('\r');
+
+        // SSE comments (": keep-alive", ": OPENROUTER PROCESSING") only keep the connection open
+        if line.starts_with(':') {
+            crate::debug_log!("Ignoring SSE comment: {}", line);
+            return StreamEvent::None;
+        }
+
+        let data = if let Some(data) = line.strip_prefix("data:") {
+            data.strip_prefix(' ').unwrap_or(data)
+        } else if line.trim().is_empty()
+            || line.starts_with("event:")
+            || line.starts_with("id:")
+            || line.starts_with("retry:")
+        {
+            return StreamEvent::None;
+        } else {
+            // Non-SSE format (direct JSON stream)
+            line
+        };
+
+        if self.pending.is_empty() && data.trim() == "[DONE]" {
+            return StreamEvent::Done;
+        }
+
+        let payload = if self.pending.is_empty() {
+            data.to_string()
+        } else {
+            format!("{}{}", self.pending, data)
+        };
+
+        match serde_json::from_str::<serde_json::Value>(&payload) {
+            Ok(json) => {
+                self.pending.clear();
+                if let Some(mappings) = &self.mappings {
+                    return self.apply_mappings(&json, &mappings.clone());
                 }
-                // Try standard OpenAI streaming format
-                else if let Some(choices) = json.get("choices") {
-                    if let Some(choice) = choices.get(0) {
-                        if let Some(delta) = choice.get("delta") {
-                            if let Some(content) = delta.get("content") {
-                                if let Some(text) = content.as_str() {
-                                    handle.write_all(text.as_bytes())?;
-                                    handle.flush()?;
-                                }
-                            }
-                        }
+                if let Some(text) = reasoning::from_stream_chunk(&json).filter(|t| !t.is_empty()) {
+                    self.reasoning.push_str(text);
+                    return StreamEvent::Reasoning(text.to_string());
+                }
+                if anthropic::is_stream_end(&json) {
+                    return StreamEvent::Done;
+                }
+                match Self::extract_text(&json) {
+                    Some(text) if !text.is_empty() => {
+                        self.text.push_str(text);
+                        StreamEvent::Text(text.to_string())
                     }
+                    _ => StreamEvent::None,
                 }
             }
+            Err(e) if e.is_eof() && payload.len() < Self::MAX_PENDING_LEN => {
+                // JSON split across lines by a gateway; wait for the rest
+                self.pending = payload;
+                StreamEvent::None
+            }
+            Err(_) => {
+                self.pending.clear();
+                StreamEvent::None
+            }
         }
+    }
 
-        // Add newline at the end
-        handle.write_all(b"\n")?;
-        handle.flush()?;
-        Ok(())
+    fn apply_mappings(
+        &mut self,
+        json: &serde_json::Value,
+        mappings: &StreamMappings,
+    ) -> StreamEvent {
+        if let Some(text) = mappings.reasoning(json).filter(|t| !t.is_empty()) {
+            self.reasoning.push_str(&text);
+            return StreamEvent::Reasoning(text);
+        }
+        if let Some(text) = mappings.content(json).filter(|t| !t.is_empty()) {
+            self.text.push_str(&text);
+            return StreamEvent::Text(text);
+        }
+        if mappings.is_done(json) {
+            return StreamEvent::Done;
+        }
+        StreamEvent::None
+    }
+
+    fn extract_text(json: &serde_json::Value) -> Option<&str> {
+        // Try direct "response" field format first
+        if let Some(response) = json.get("response") {
+            return response.as_str();
+        }
+
+        // Anthropic Messages API events
+        if let Some(text) = anthropic::stream_text(json) {
+            return Some(text);
+        }
+
+        // Standard OpenAI streaming format
+        json.get("choices")?
+            .get(0)?
+            .get("delta")?
+            .get("content")?
+            .as_str()
     }
 }
 
@@ -1686,4 +2592,128 @@ mod tests {
         // Cleanup
         std::env::remove_var("LC_DISABLE_TLS_VERIFY");
     }
+
+    #[test]
+    fn test_input_audio_from_data_url() {
+        let audio = InputAudio::from_data_url("data:audio/mpeg;base64,AAAA").unwrap();
+        assert_eq!(audio.format, "mp3");
+        assert_eq!(audio.data, "AAAA");
+
+        let audio = InputAudio::from_data_url("data:audio/wav;base64,BBBB").unwrap();
+        assert_eq!(audio.format, "wav");
+
+        assert!(InputAudio::from_data_url("https://example.com/a.mp3").is_none());
+        assert!(InputAudio::from_data_url("data:image/png;base64,CCCC").is_none());
+    }
+
+    #[test]
+    fn test_input_audio_content_part_serialization() {
+        let message = Message::user_with_media(
+            "Describe this".to_string(),
+            &[],
+            &[InputAudio {
+                data: "AAAA".to_string(),
+                format: "wav".to_string(),
+            }],
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["content"][1]["type"], "input_audio");
+        assert_eq!(json["content"][1]["input_audio"]["format"], "wav");
+        assert_eq!(json["content"][1]["input_audio"]["data"], "AAAA");
+    }
+
+    #[test]
+    fn test_stream_parser_ignores_keep_alive_comments() {
+        let mut parser = StreamParser::default();
+
+        assert!(matches!(
+            parser.process_line(": keep-alive"),
+            StreamEvent::None
+        ));
+        assert!(matches!(
+            parser.process_line(": OPENROUTER PROCESSING\r"),
+            StreamEvent::None
+        ));
+        assert!(matches!(
+            parser.process_line("event: message"),
+            StreamEvent::None
+        ));
+
+        let event = parser.process_line(r#"data:{"choices":[{"delta":{"content":"Hello"}}]}"#);
+        assert!(matches!(event, StreamEvent::Text(ref t) if t == "Hello"));
+        assert!(matches!(
+            parser.process_line("data: [DONE]"),
+            StreamEvent::Done
+        ));
+        assert_eq!(parser.text, "Hello");
+    }
+
+    #[test]
+    fn test_stream_parser_uses_mappings() {
+        let mut parser = StreamParser {
+            mappings: Some(StreamMappings {
+                content: Some("/delta/text".to_string()),
+                reasoning: Some("/delta/thinking".to_string()),
+                done: Some("/type".to_string()),
+                done_value: Some("message_stop".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        let event = parser.process_line(r#"data: {"type":"delta","delta":{"thinking":"hmm"}}"#);
+        assert!(matches!(event, StreamEvent::Reasoning(ref t) if t == "hmm"));
+        let event = parser.process_line(r#"data: {"type":"delta","delta":{"text":"Hi"}}"#);
+        assert!(matches!(event, StreamEvent::Text(ref t) if t == "Hi"));
+        // The OpenAI shape is not consulted once mappings are set
+        assert!(matches!(
+            parser.process_line(r#"data: {"choices":[{"delta":{"content":"x"}}]}"#),
+            StreamEvent::None
+        ));
+        assert!(matches!(
+            parser.process_line(r#"data: {"type":"message_stop"}"#),
+            StreamEvent::Done
+        ));
+        assert_eq!(parser.text, "Hi");
+        assert_eq!(parser.reasoning, "hmm");
+    }
+
+    #[test]
+    fn test_stream_parser_reassembles_split_json() {
+        let mut parser = StreamParser::default();
+
+        assert!(matches!(
+            parser.process_line(r#"data: {"choices":[{"delta":{"con"#),
+            StreamEvent::None
+        ));
+        let event = parser.process_line(r#"tent":"world"}}]}"#);
+        assert!(matches!(event, StreamEvent::Text(ref t) if t == "world"));
+
+        // Garbage is dropped without poisoning later chunks
+        assert!(matches!(
+            parser.process_line("data: {not json}"),
+            StreamEvent::None
+        ));
+        let event = parser.process_line(r#"{"response":"!"}"#);
+        assert!(matches!(event, StreamEvent::Text(ref t) if t == "!"));
+        assert_eq!(parser.text, "world!");
+    }
+
+    #[test]
+    fn test_write_streamed_stops_on_closed_pipe() {
+        struct ClosedPipe;
+        impl std::io::Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut out = Vec::new();
+        assert!(write_streamed(&mut out, b"hello").unwrap());
+        assert_eq!(out, b"hello");
+        assert!(!write_streamed(&mut ClosedPipe, b"hello").unwrap());
+    }
 }
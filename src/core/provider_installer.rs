@@ -3,6 +3,7 @@
 //! This module handles downloading, installing, and updating provider configurations
 //! from a central repository, keeping API keys separate from the configurations.
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
@@ -10,6 +11,8 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::config::{Config, ProviderConfig};
+
 /// Provider registry that lists available providers and their metadata
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProviderRegistry {
@@ -56,6 +59,82 @@ pub struct ProviderMetadata {
     /// Minimum lc version required
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_version: Option<String>,
+
+    /// Known model IDs served by this provider, used for discovery before install
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+
+    /// Provider vars the config needs before it works, such as project or location
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_vars: Vec<String>,
+}
+
+impl ProviderMetadata {
+    /// Catalog models whose ID contains the query (case-insensitive)
+    pub fn matching_models(&self, query: &str) -> Vec<&str> {
+        let query = query.to_lowercase();
+        self.models
+            .iter()
+            .filter(|m| m.to_lowercase().contains(&query))
+            .map(|m| m.as_str())
+            .collect()
+    }
+
+    /// Name of the installed provider in config, the stem of its config file
+    pub fn config_name(&self) -> &str {
+        self.config_file
+            .strip_suffix(".toml")
+            .unwrap_or(&self.config_file)
+    }
+}
+
+/// Vars an installed provider needs: those listed in the registry and the `{placeholders}`
+/// in its endpoint and paths, other than the model
+pub fn required_vars(metadata: &ProviderMetadata, config: &ProviderConfig) -> Vec<String> {
+    let placeholder = crate::utils::regex_cache::get_regex(r"\{([A-Za-z_][A-Za-z0-9_]*)\}")
+        .expect("placeholder pattern is valid");
+    let urls = [
+        Some(&config.endpoint),
+        Some(&config.models_path),
+        Some(&config.chat_path),
+        config.images_path.as_ref(),
+        config.embeddings_path.as_ref(),
+        config.audio_path.as_ref(),
+        config.speech_path.as_ref(),
+        config.token_url.as_ref(),
+    ];
+    let mut vars = metadata.required_vars.clone();
+    for url in urls.into_iter().flatten() {
+        for caps in placeholder.captures_iter(url) {
+            let var = &caps[1];
+            if var != "model" && var != "model_name" && !vars.iter().any(|v| v == var) {
+                vars.push(var.to_string());
+            }
+        }
+    }
+    vars
+}
+
+/// `new_config` with the `[vars]` of `existing_config`, which take precedence over the
+/// registry's defaults
+fn keep_vars(existing_config: &str, new_config: String) -> Result<String> {
+    let existing_vars = toml::from_str::<toml::Value>(existing_config)
+        .ok()
+        .and_then(|existing| existing.get("vars")?.as_table().cloned())
+        .unwrap_or_default();
+    if existing_vars.is_empty() {
+        return Ok(new_config);
+    }
+    let mut config: toml::Table = toml::from_str(&new_config)?;
+    let vars = config
+        .entry("vars")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    if let Some(vars) = vars.as_table_mut() {
+        for (key, value) in existing_vars {
+            vars.insert(key, value);
+        }
+    }
+    Ok(toml::to_string_pretty(&config)?)
 }
 
 /// Types of authentication required by providers
@@ -223,15 +302,23 @@ impl ProviderInstaller {
         Ok(providers)
     }
 
-    /// Install a provider configuration
-    pub async fn install_provider(&self, provider_id: &str, force: bool) -> Result<()> {
-        println!("{} Installing provider '{}'...", "📦".blue(), provider_id);
+    /// Install a provider configuration, returning its registry entry
+    pub async fn install_provider(
+        &self,
+        provider_id: &str,
+        force: bool,
+    ) -> Result<ProviderMetadata> {
+        println!(
+            "{} Installing provider '{}'...",
+            sym("📦").blue(),
+            provider_id
+        );
 
         // Fetch registry
         let registry = if let Some(cached) = self.get_cached_registry()? {
             cached
         } else {
-            println!("{} Fetching provider registry...", "🔄".blue());
+            println!("{} Fetching provider registry...", sym("🔄").blue());
             self.fetch_registry().await?
         };
 
@@ -239,6 +326,7 @@ impl ProviderInstaller {
         let metadata = registry
             .providers
             .get(provider_id)
+            .cloned()
             .ok_or_else(|| anyhow::anyhow!("Provider '{}' not found in registry", provider_id))?;
 
         // Check if already installed
@@ -253,11 +341,11 @@ impl ProviderInstaller {
                         if existing_version == metadata.version {
                             println!(
                                 "{} Provider '{}' is already up to date (v{})",
-                                "✓".green(),
+                                sym("✓").green(),
                                 provider_id,
                                 metadata.version
                             );
-                            return Ok(());
+                            return Ok(metadata);
                         }
                     }
                 }
@@ -265,7 +353,7 @@ impl ProviderInstaller {
 
             println!(
                 "{} Provider '{}' already exists. Updating to v{}...",
-                "🔄".yellow(),
+                sym("🔄").yellow(),
                 provider_id,
                 metadata.version
             );
@@ -308,6 +396,12 @@ impl ProviderInstaller {
         // Validate the downloaded config
         self.validate_provider_config(&config_content)?;
 
+        // Keep the vars set for the version being replaced
+        let config_content = match fs::read_to_string(&target_file) {
+            Ok(existing) => keep_vars(&existing, config_content)?,
+            Err(_) => config_content,
+        };
+
         // Ensure providers directory exists
         fs::create_dir_all(&self.providers_dir)?;
 
@@ -336,31 +430,151 @@ impl ProviderInstaller {
 
         println!(
             "{} Provider '{}' installed successfully (v{})",
-            "✅".green(),
+            sym("✅").green(),
             provider_id,
             metadata.version
         );
 
-        // Show authentication instructions
-        self.show_auth_instructions(provider_id, metadata)?;
+        Ok(metadata)
+    }
+
+    /// Get a freshly installed provider working: ask for the vars it needs, check its
+    /// credentials with a request, and show how to start using it
+    pub async fn setup_provider(
+        &self,
+        provider_id: &str,
+        metadata: &ProviderMetadata,
+    ) -> Result<()> {
+        let name = metadata.config_name();
+        let mut config = Config::load()?;
+        let provider_config = config.get_provider(name)?.clone();
+
+        let missing: Vec<String> = required_vars(metadata, &provider_config)
+            .into_iter()
+            .filter(|var| !provider_config.vars.contains_key(var))
+            .collect();
+        let mut unset = Vec::new();
+        if !missing.is_empty() {
+            println!("\n{} Provider Variables", sym("⚙️").blue());
+            let interactive = atty::is(atty::Stream::Stdin);
+            for var in missing {
+                let value = if interactive {
+                    print!("{} for {}: ", var.bold(), name);
+                    std::io::Write::flush(&mut std::io::stdout())?;
+                    let mut input = String::new();
+                    std::io::stdin().read_line(&mut input)?;
+                    input.trim().to_string()
+                } else {
+                    String::new()
+                };
+                if value.is_empty() {
+                    unset.push(var);
+                } else {
+                    config.set_provider_var(name, &var, &value)?;
+                    println!("{} {} set to {}", sym("✓").green(), var, value);
+                }
+            }
+            for var in &unset {
+                println!(
+                    "{} {} is not set. Set it with: {}",
+                    sym("⚠️").yellow(),
+                    var,
+                    format!("lc providers vars {} set {} <value>", name, var).bold()
+                );
+            }
+        }
+
+        let keys = crate::keys::KeysConfig::load()?;
+        let authenticated = matches!(metadata.auth_type, AuthType::None)
+            || keys.has_auth(name)
+            || provider_config.api_key.is_some()
+            || !provider_config.headers.is_empty();
+        if !authenticated {
+            self.show_auth_instructions(name, metadata)?;
+        }
+
+        let mut models = Vec::new();
+        if authenticated && unset.is_empty() {
+            println!("\n{} Checking {}...", sym("🔄").blue(), name);
+            match self.validate_provider(&mut config, name).await {
+                Ok(listed) => {
+                    println!(
+                        "{} {} is working ({} models available)",
+                        sym("✅").green(),
+                        name,
+                        listed.len()
+                    );
+                    models = listed;
+                }
+                Err(e) => {
+                    println!(
+                        "{} {} did not respond as expected: {}",
+                        sym("⚠️").yellow(),
+                        name,
+                        e
+                    );
+                    println!(
+                        "  Check its credentials ({}) and vars ({})",
+                        format!("lc keys add {}", name).bold(),
+                        format!("lc providers vars {} list", name).bold()
+                    );
+                }
+            }
+        }
+
+        let examples = if metadata.models.is_empty() {
+            models
+        } else {
+            metadata.models.clone()
+        };
+        println!("\n{} Quick Start", sym("🚀").blue());
+        for model in examples.iter().take(3) {
+            println!("  {}", format!("lc -m {}:{} \"Hello\"", name, model).bold());
+        }
+        println!(
+            "  {}  {}",
+            format!("lc providers models {}", name).bold(),
+            "# list every model".dimmed()
+        );
+        if let Some(docs_url) = &metadata.docs_url {
+            println!("  Documentation: {}", docs_url.blue());
+        }
+        crate::debug_log!("Finished setup of provider '{}'", provider_id);
 
         Ok(())
     }
 
+    /// List the provider's models as a test request, returning their IDs
+    async fn validate_provider(&self, config: &mut Config, name: &str) -> Result<Vec<String>> {
+        let before = config.get_cached_token(name).cloned();
+        let client = crate::chat::create_authenticated_client(config, name).await?;
+        if config.get_cached_token(name) != before.as_ref() {
+            config.save()?;
+        }
+        let mut models: Vec<String> = client
+            .list_models()
+            .await?
+            .into_iter()
+            .map(|model| model.id)
+            .collect();
+        models.sort();
+        Ok(models)
+    }
+
     /// Update a provider configuration
     pub async fn update_provider(&self, provider_id: &str) -> Result<()> {
-        self.install_provider(provider_id, true).await
+        self.install_provider(provider_id, true).await.map(|_| ())
     }
 
     /// Update all installed providers
     pub async fn update_all_providers(&self) -> Result<()> {
-        println!("{} Updating all installed providers...", "🔄".blue());
+        println!("{} Updating all installed providers...", sym("🔄").blue());
 
         // Get list of installed providers
         let installed = self.list_installed_providers()?;
 
         if installed.is_empty() {
-            println!("{} No providers installed", "ℹ️".blue());
+            println!("{} No providers installed", sym("ℹ️").blue());
             return Ok(());
         }
 
@@ -371,7 +585,12 @@ impl ProviderInstaller {
             match self.update_provider(&provider_id).await {
                 Ok(_) => updated_count += 1,
                 Err(e) => {
-                    eprintln!("{} Failed to update '{}': {}", "❌".red(), provider_id, e);
+                    eprintln!(
+                        "{} Failed to update '{}': {}",
+                        sym("❌").red(),
+                        provider_id,
+                        e
+                    );
                     failed_count += 1;
                 }
             }
@@ -380,13 +599,13 @@ impl ProviderInstaller {
         if failed_count == 0 {
             println!(
                 "{} All {} providers updated successfully",
-                "✅".green(),
+                sym("✅").green(),
                 updated_count
             );
         } else {
             println!(
                 "{} Updated {} providers, {} failed",
-                "⚠️".yellow(),
+                sym("⚠️").yellow(),
                 updated_count,
                 failed_count
             );
@@ -432,7 +651,7 @@ impl ProviderInstaller {
 
         println!(
             "{} Provider '{}' uninstalled successfully",
-            "✅".green(),
+            sym("✅").green(),
             provider_id
         );
 
@@ -441,7 +660,7 @@ impl ProviderInstaller {
         if keys.has_auth(provider_id) {
             println!(
                 "{} Note: API keys for '{}' are still stored in keys.toml",
-                "ℹ️".blue(),
+                sym("ℹ️").blue(),
                 provider_id
             );
             println!("  To remove them, use: lc keys remove {}", provider_id);
@@ -470,7 +689,7 @@ impl ProviderInstaller {
 
     /// Show authentication instructions for a provider
     fn show_auth_instructions(&self, provider_id: &str, metadata: &ProviderMetadata) -> Result<()> {
-        println!("\n{} Authentication Setup", "🔑".yellow());
+        println!("\n{} Authentication Setup", sym("🔑").yellow());
 
         match metadata.auth_type {
             AuthType::ApiKey => {
@@ -538,6 +757,8 @@ pub fn create_sample_registry() -> ProviderRegistry {
             official: true,
             docs_url: Some("https://platform.openai.com/docs".to_string()),
             min_version: None,
+            models: vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()],
+            required_vars: Vec::new(),
         },
     );
 
@@ -557,6 +778,8 @@ pub fn create_sample_registry() -> ProviderRegistry {
             official: true,
             docs_url: Some("https://ai.google.dev/docs".to_string()),
             min_version: None,
+            models: vec!["gemini-1.5-pro".to_string(), "gemini-1.5-flash".to_string()],
+            required_vars: Vec::new(),
         },
     );
 
@@ -572,6 +795,8 @@ pub fn create_sample_registry() -> ProviderRegistry {
             official: true,
             docs_url: Some("https://docs.anthropic.com".to_string()),
             min_version: None,
+            models: vec!["claude-3-5-sonnet-20241022".to_string()],
+            required_vars: Vec::new(),
         },
     );
 
@@ -598,6 +823,8 @@ mod tests {
             official: false,
             docs_url: None,
             min_version: None,
+            models: Vec::new(),
+            required_vars: Vec::new(),
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -619,4 +846,52 @@ mod tests {
         assert_eq!(openai.name, "OpenAI");
         assert!(openai.official);
     }
+
+    #[test]
+    fn test_matching_models() {
+        let registry = create_sample_registry();
+
+        let gemini = &registry.providers["gemini"];
+        assert_eq!(gemini.matching_models("FLASH"), vec!["gemini-1.5-flash"]);
+        assert_eq!(gemini.matching_models("gemini").len(), 2);
+        assert!(gemini.matching_models("gpt").is_empty());
+
+        // Registries without a catalog still deserialize
+        let json = r#"{"name":"X","description":"","config_file":"x.toml","version":"1","auth_type":"none"}"#;
+        let metadata: ProviderMetadata = serde_json::from_str(json).unwrap();
+        assert!(metadata.models.is_empty());
+    }
+
+    #[test]
+    fn test_required_vars() {
+        let mut metadata = create_sample_registry().providers["gemini"].clone();
+        metadata.config_file = "vertex.toml".to_string();
+        metadata.required_vars = vec!["project".to_string()];
+        let config: ProviderConfig = toml::from_str(
+            r#"
+            endpoint = "https://{location}-aiplatform.googleapis.com/v1/projects/{project}"
+            models = []
+            chat_path = "/locations/{location}/publishers/google/models/{model}:generateContent"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(metadata.config_name(), "vertex");
+        assert_eq!(
+            required_vars(&metadata, &config),
+            vec!["project", "location"]
+        );
+    }
+
+    #[test]
+    fn test_keep_vars() {
+        let new = "endpoint = \"mock://{region}\"\n\n[vars]\nregion = \"eu\"\n".to_string();
+        let kept = keep_vars("[vars]\nregion = \"us\"\nproject = \"acme\"\n", new.clone()).unwrap();
+        let kept: toml::Value = toml::from_str(&kept).unwrap();
+        assert_eq!(kept["vars"]["region"].as_str(), Some("us"));
+        assert_eq!(kept["vars"]["project"].as_str(), Some("acme"));
+        assert_eq!(kept["endpoint"].as_str(), Some("mock://{region}"));
+
+        assert_eq!(keep_vars("endpoint = \"x\"", new.clone()).unwrap(), new);
+    }
 }
@@ -0,0 +1,408 @@
+//! Provider-side (built-in) tools enabled with `--provider-tools`
+//!
+//! Unlike MCP tools, these run on the provider's servers: lc only adds the tool
+//! declarations each API expects to the request body, and renders the sources the
+//! provider cites in its answer. The API is detected from the provider endpoint and
+//! can be set explicitly with the `tools_api` provider var (`openai`, `openrouter`,
+//! `anthropic` or `gemini`) for gateways in front of one of them.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderTool {
+    WebSearch,
+    CodeExecution,
+}
+
+impl ProviderTool {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "web_search" | "web" | "search" => Ok(Self::WebSearch),
+            "code" | "code_execution" | "code_interpreter" => Ok(Self::CodeExecution),
+            other => anyhow::bail!(
+                "Unknown provider tool '{}'. Available: web_search, code",
+                other
+            ),
+        }
+    }
+}
+
+/// Parse a comma-separated `--provider-tools` list
+pub fn parse_list(list: &str) -> Result<Vec<ProviderTool>> {
+    let mut tools = Vec::new();
+    for name in list.split(',').filter(|n| !n.trim().is_empty()) {
+        let tool = ProviderTool::parse(name)?;
+        if !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    Ok(tools)
+}
+
+static CURRENT: RwLock<Vec<ProviderTool>> = RwLock::new(Vec::new());
+
+/// Set the provider tools for requests made by this invocation
+pub fn set(tools: Vec<ProviderTool>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = tools;
+    }
+}
+
+/// The provider tools enabled for this invocation
+pub fn current() -> Vec<ProviderTool> {
+    CURRENT
+        .read()
+        .map(|current| current.clone())
+        .unwrap_or_default()
+}
+
+/// Request/response shape used for provider tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolsApi {
+    OpenAi,
+    OpenRouter,
+    Anthropic,
+    Gemini,
+}
+
+impl ToolsApi {
+    /// The API from the `tools_api` var, or from the provider endpoint
+    pub fn detect(endpoint: &str, vars: Option<&HashMap<String, String>>) -> Self {
+        let configured = vars.and_then(|vars| vars.get("tools_api"));
+        match configured.map(|api| api.to_lowercase()).as_deref() {
+            Some("openai") => return Self::OpenAi,
+            Some("openrouter") => return Self::OpenRouter,
+            Some("anthropic") | Some("claude") => return Self::Anthropic,
+            Some("gemini") | Some("google") => return Self::Gemini,
+            _ => {}
+        }
+
+        let endpoint = endpoint.to_lowercase();
+        if endpoint.contains("anthropic.com") {
+            Self::Anthropic
+        } else if endpoint.contains("generativelanguage.googleapis.com")
+            || endpoint.contains("aiplatform.googleapis.com")
+        {
+            Self::Gemini
+        } else if endpoint.contains("openrouter.ai") {
+            Self::OpenRouter
+        } else {
+            Self::OpenAi
+        }
+    }
+}
+
+/// Add the tool declarations for `tools` to a chat request body
+pub fn apply(api: ToolsApi, tools: &[ProviderTool], body: &mut Value) -> Result<()> {
+    let Some(object) = body.as_object_mut() else {
+        return Ok(());
+    };
+
+    for tool in tools {
+        match (api, tool) {
+            (ToolsApi::OpenAi, ProviderTool::WebSearch) => {
+                object.insert("web_search_options".to_string(), json!({}));
+            }
+            (ToolsApi::OpenRouter, ProviderTool::WebSearch) => {
+                let plugins = object.entry("plugins").or_insert_with(|| json!([]));
+                if let Some(plugins) = plugins.as_array_mut() {
+                    plugins.push(json!({ "id": "web" }));
+                }
+            }
+            (ToolsApi::Anthropic, ProviderTool::WebSearch) => push_tool(
+                object,
+                json!({ "type": "web_search_20250305", "name": "web_search", "max_uses": 5 }),
+            ),
+            (ToolsApi::Anthropic, ProviderTool::CodeExecution) => push_tool(
+                object,
+                json!({ "type": "code_execution_20250522", "name": "code_execution" }),
+            ),
+            (ToolsApi::Gemini, ProviderTool::WebSearch) => {
+                push_tool(object, json!({ "google_search": {} }))
+            }
+            (ToolsApi::Gemini, ProviderTool::CodeExecution) => {
+                push_tool(object, json!({ "code_execution": {} }))
+            }
+            (ToolsApi::OpenAi | ToolsApi::OpenRouter, ProviderTool::CodeExecution) => {
+                anyhow::bail!(
+                    "The 'code' provider tool isn't available through the chat completions API; \
+                     it is supported for Anthropic and Gemini providers"
+                )
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_tool(object: &mut serde_json::Map<String, Value>, tool: Value) {
+    let tools = object.entry("tools").or_insert_with(|| json!([]));
+    if let Some(tools) = tools.as_array_mut() {
+        tools.push(tool);
+    }
+}
+
+/// Extra header an API needs for the requested tools
+pub fn beta_header(api: ToolsApi, tools: &[ProviderTool]) -> Option<(&'static str, &'static str)> {
+    (api == ToolsApi::Anthropic && tools.contains(&ProviderTool::CodeExecution))
+        .then_some(("anthropic-beta", "code-execution-2025-05-22"))
+}
+
+/// A source cited in the answer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Citation {
+    pub title: Option<String>,
+    pub url: String,
+}
+
+/// The answer text of a response that used provider tools, followed by the cited
+/// sources. Returns `None` when the response doesn't have the expected shape, so
+/// the regular response parsing can handle it.
+pub fn render_response(api: ToolsApi, response_text: &str) -> Option<String> {
+    let response: Value = serde_json::from_str(response_text).ok()?;
+    let text = match api {
+        ToolsApi::OpenAi | ToolsApi::OpenRouter => response
+            .pointer("/choices/0/message/content")?
+            .as_str()?
+            .to_string(),
+        ToolsApi::Anthropic => anthropic_text(&response)?,
+        ToolsApi::Gemini => gemini_text(&response)?,
+    };
+    Some(format!(
+        "{}{}",
+        text,
+        format_citations(&citations(api, &response))
+    ))
+}
+
+/// Text blocks of an Anthropic response (server tool use and results are skipped),
+/// with code execution output shown as code blocks
+fn anthropic_text(response: &Value) -> Option<String> {
+    let blocks = response.get("content")?.as_array()?;
+    let mut text = String::new();
+    for block in blocks {
+        match block.get("type").and_then(|t| t.as_str()) {
+            Some("text") => text.push_str(block.get("text").and_then(|t| t.as_str()).unwrap_or("")),
+            Some("code_execution_tool_result") => {
+                if let Some(stdout) = block
+                    .pointer("/content/stdout")
+                    .and_then(|s| s.as_str())
+                    .filter(|s| !s.trim().is_empty())
+                {
+                    text.push_str(&format!("\n```\n{}\n```\n", stdout.trim_end()));
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(text)
+}
+
+/// Text parts of a Gemini response, with executed code and its output as code blocks
+fn gemini_text(response: &Value) -> Option<String> {
+    let parts = response
+        .pointer("/candidates/0/content/parts")?
+        .as_array()?;
+    let mut text = String::new();
+    for part in parts {
+        if let Some(part_text) = part.get("text").and_then(|t| t.as_str()) {
+            text.push_str(part_text);
+        } else if let Some(code) = part.get("executableCode") {
+            let language = code
+                .get("language")
+                .and_then(|l| l.as_str())
+                .unwrap_or("")
+                .to_lowercase();
+            let code = code.get("code").and_then(|c| c.as_str()).unwrap_or("");
+            text.push_str(&format!("\n```{}\n{}\n```\n", language, code.trim_end()));
+        } else if let Some(output) = part
+            .pointer("/codeExecutionResult/output")
+            .and_then(|o| o.as_str())
+        {
+            text.push_str(&format!("\n```\n{}\n```\n", output.trim_end()));
+        }
+    }
+    Some(text)
+}
+
+/// Sources cited in a response, without duplicates, in the order they appear
+pub fn citations(api: ToolsApi, response: &Value) -> Vec<Citation> {
+    let mut found = Vec::new();
+    match api {
+        ToolsApi::OpenAi | ToolsApi::OpenRouter => {
+            let annotations = response
+                .pointer("/choices/0/message/annotations")
+                .and_then(|a| a.as_array());
+            for annotation in annotations.into_iter().flatten() {
+                if let Some(citation) = annotation.get("url_citation") {
+                    push_citation(&mut found, citation, "url", "title");
+                }
+            }
+        }
+        ToolsApi::Anthropic => {
+            let blocks = response.get("content").and_then(|c| c.as_array());
+            for block in blocks.into_iter().flatten() {
+                let cited = block.get("citations").and_then(|c| c.as_array());
+                for citation in cited.into_iter().flatten() {
+                    push_citation(&mut found, citation, "url", "title");
+                }
+            }
+        }
+        ToolsApi::Gemini => {
+            let chunks = response
+                .pointer("/candidates/0/groundingMetadata/groundingChunks")
+                .and_then(|c| c.as_array());
+            for chunk in chunks.into_iter().flatten() {
+                if let Some(web) = chunk.get("web") {
+                    push_citation(&mut found, web, "uri", "title");
+                }
+            }
+        }
+    }
+    found
+}
+
+fn push_citation(found: &mut Vec<Citation>, value: &Value, url_key: &str, title_key: &str) {
+    let Some(url) = value.get(url_key).and_then(|u| u.as_str()) else {
+        return;
+    };
+    if found.iter().any(|c| c.url == url) {
+        return;
+    }
+    found.push(Citation {
+        title: value
+            .get(title_key)
+            .and_then(|t| t.as_str())
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string()),
+        url: url.to_string(),
+    });
+}
+
+/// A numbered "Sources" list to append to the answer (empty without citations)
+pub fn format_citations(citations: &[Citation]) -> String {
+    if citations.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("\n\nSources:\n");
+    for (index, citation) in citations.iter().enumerate() {
+        match &citation.title {
+            Some(title) => out.push_str(&format!("[{}] {} - {}\n", index + 1, title, citation.url)),
+            None => out.push_str(&format!("[{}] {}\n", index + 1, citation.url)),
+        }
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_api() {
+        assert_eq!(
+            ToolsApi::detect("https://api.anthropic.com/v1", None),
+            ToolsApi::Anthropic
+        );
+        assert_eq!(
+            ToolsApi::detect("https://generativelanguage.googleapis.com/v1beta", None),
+            ToolsApi::Gemini
+        );
+        assert_eq!(
+            ToolsApi::detect("https://openrouter.ai/api/v1", None),
+            ToolsApi::OpenRouter
+        );
+        let vars = HashMap::from([("tools_api".to_string(), "anthropic".to_string())]);
+        assert_eq!(
+            ToolsApi::detect("https://gateway.example.com", Some(&vars)),
+            ToolsApi::Anthropic
+        );
+        assert_eq!(
+            ToolsApi::detect("https://api.groq.com/openai/v1", None),
+            ToolsApi::OpenAi
+        );
+    }
+
+    #[test]
+    fn test_apply_tool_blocks() {
+        let tools = parse_list("web_search,code").unwrap();
+
+        let mut body = json!({ "model": "claude", "tools": [{ "name": "mcp_tool" }] });
+        apply(ToolsApi::Anthropic, &tools, &mut body).unwrap();
+        assert_eq!(body["tools"].as_array().unwrap().len(), 3);
+        assert_eq!(body["tools"][1]["type"], "web_search_20250305");
+        assert!(beta_header(ToolsApi::Anthropic, &tools).is_some());
+
+        let mut body = json!({ "contents": [] });
+        apply(ToolsApi::Gemini, &tools, &mut body).unwrap();
+        assert_eq!(
+            body["tools"],
+            json!([{ "google_search": {} }, { "code_execution": {} }])
+        );
+
+        let web = parse_list("web").unwrap();
+        let mut body = json!({ "model": "gpt-4o-search-preview" });
+        apply(ToolsApi::OpenAi, &web, &mut body).unwrap();
+        assert_eq!(body["web_search_options"], json!({}));
+        assert!(apply(ToolsApi::OpenAi, &tools, &mut body).is_err());
+
+        let mut body = json!({ "model": "x" });
+        apply(ToolsApi::OpenRouter, &web, &mut body).unwrap();
+        assert_eq!(body["plugins"], json!([{ "id": "web" }]));
+
+        assert!(parse_list("web_search,browser").is_err());
+    }
+
+    #[test]
+    fn test_render_openai_citations() {
+        let response = json!({
+            "choices": [{ "message": {
+                "content": "Rust 1.80 is out.",
+                "annotations": [
+                    { "type": "url_citation", "url_citation": { "url": "https://blog.rust-lang.org", "title": "Rust Blog" } },
+                    { "type": "url_citation", "url_citation": { "url": "https://blog.rust-lang.org" } }
+                ]
+            }}]
+        });
+        assert_eq!(
+            render_response(ToolsApi::OpenAi, &response.to_string()).unwrap(),
+            "Rust 1.80 is out.\n\nSources:\n[1] Rust Blog - https://blog.rust-lang.org"
+        );
+    }
+
+    #[test]
+    fn test_render_anthropic_skips_tool_blocks() {
+        let response = json!({
+            "content": [
+                { "type": "server_tool_use", "name": "web_search", "input": { "query": "rust" } },
+                { "type": "web_search_tool_result", "content": [] },
+                { "type": "text", "text": "The latest release is 1.80.",
+                  "citations": [{ "type": "web_search_result_location", "url": "https://rust-lang.org", "title": "Rust" }] }
+            ]
+        });
+        assert_eq!(
+            render_response(ToolsApi::Anthropic, &response.to_string()).unwrap(),
+            "The latest release is 1.80.\n\nSources:\n[1] Rust - https://rust-lang.org"
+        );
+    }
+
+    #[test]
+    fn test_render_gemini_code_execution_and_grounding() {
+        let response = json!({
+            "candidates": [{
+                "content": { "parts": [
+                    { "text": "Computing:" },
+                    { "executableCode": { "language": "PYTHON", "code": "print(2 + 2)" } },
+                    { "codeExecutionResult": { "outcome": "OUTCOME_OK", "output": "4\n" } }
+                ]},
+                "groundingMetadata": { "groundingChunks": [{ "web": { "uri": "https://example.com", "title": "" } }] }
+            }]
+        });
+        let rendered = render_response(ToolsApi::Gemini, &response.to_string()).unwrap();
+        assert!(rendered.starts_with("Computing:\n```python\nprint(2 + 2)\n```\n"));
+        assert!(rendered.contains("```\n4\n```"));
+        assert!(rendered.ends_with("Sources:\n[1] https://example.com"));
+    }
+}
@@ -0,0 +1,223 @@
+//! Rate-limit and quota headers of provider responses
+//!
+//! Every response is checked for the headers providers use to report quota:
+//! OpenAI-style `x-ratelimit-{limit,remaining,reset}-{requests,tokens}`, Anthropic's
+//! `anthropic-ratelimit-{requests,tokens,input-tokens,output-tokens}-{limit,remaining,reset}`,
+//! the IETF draft `ratelimit-{limit,remaining,reset}` and `retry-after`. The latest
+//! headers of each provider are kept in `rate_limits.json`, shown by
+//! `lc providers status`, and printed after each request in debug mode (`-d`).
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The rate-limit headers of one response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimits {
+    pub captured_at: DateTime<Utc>,
+    /// Header name (lowercase) to value
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Limit, remaining and reset of one kind of quota (requests, tokens, ...)
+#[derive(Debug, Default, PartialEq)]
+struct Quota {
+    limit: Option<String>,
+    remaining: Option<String>,
+    reset: Option<String>,
+}
+
+fn is_rate_limit_header(name: &str) -> bool {
+    name.starts_with("x-ratelimit-")
+        || name.starts_with("anthropic-ratelimit-")
+        || name.starts_with("ratelimit-")
+        || name == "retry-after"
+}
+
+impl RateLimits {
+    /// The rate-limit headers among `headers`, if there are any
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let headers: BTreeMap<String, String> = headers
+            .iter()
+            .filter(|(name, _)| is_rate_limit_header(name.as_str()))
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        (!headers.is_empty()).then(|| Self {
+            captured_at: Utc::now(),
+            headers,
+        })
+    }
+
+    /// Quotas by kind, e.g. "requests" and "tokens"
+    fn quotas(&self) -> BTreeMap<String, Quota> {
+        let mut quotas: BTreeMap<String, Quota> = BTreeMap::new();
+        for (name, value) in &self.headers {
+            let (field, kind) = if let Some(rest) = name.strip_prefix("x-ratelimit-") {
+                // remaining-requests
+                match rest.split_once('-') {
+                    Some((field, kind)) => (field, kind),
+                    None => (rest, ""),
+                }
+            } else if let Some(rest) = name.strip_prefix("anthropic-ratelimit-") {
+                // input-tokens-remaining
+                match rest.rsplit_once('-') {
+                    Some((kind, field)) => (field, kind),
+                    None => continue,
+                }
+            } else if let Some(field) = name.strip_prefix("ratelimit-") {
+                (field, "")
+            } else {
+                continue;
+            };
+            let quota = quotas.entry(kind.to_string()).or_default();
+            match field {
+                "limit" => quota.limit = Some(value.clone()),
+                "remaining" => quota.remaining = Some(value.clone()),
+                "reset" => quota.reset = Some(value.clone()),
+                _ => {}
+            }
+        }
+        quotas.retain(|_, quota| quota.remaining.is_some() || quota.limit.is_some());
+        quotas
+    }
+
+    /// One line such as "requests 49/50 (resets 1.2s), tokens 39000/40000 (resets 30s)"
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .quotas()
+            .into_iter()
+            .map(|(kind, quota)| {
+                let kind = if kind.is_empty() { "requests" } else { &kind };
+                let mut part = format!(
+                    "{} {}/{}",
+                    kind.replace('-', " "),
+                    quota.remaining.as_deref().unwrap_or("?"),
+                    quota.limit.as_deref().unwrap_or("?")
+                );
+                if let Some(reset) = quota.reset {
+                    part.push_str(&format!(" (resets {})", reset));
+                }
+                part
+            })
+            .collect();
+        if let Some(retry_after) = self.headers.get("retry-after") {
+            parts.push(format!("retry after {}s", retry_after));
+        }
+        parts.join(", ")
+    }
+
+    /// Whether a quota has run out
+    pub fn exhausted(&self) -> bool {
+        self.headers.contains_key("retry-after")
+            || self
+                .quotas()
+                .values()
+                .any(|quota| quota.remaining.as_deref() == Some("0"))
+    }
+}
+
+/// The latest rate limits of each provider, kept in `rate_limits.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimitStore {
+    #[serde(default)]
+    pub providers: BTreeMap<String, RateLimits>,
+}
+
+impl RateLimitStore {
+    pub fn load() -> Self {
+        let Ok(path) = Self::file_path() else {
+            return Self::default();
+        };
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn file_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::config_dir()?.join("rate_limits.json"))
+    }
+}
+
+/// Keep the rate limits of a response from `provider`, and print them in debug mode
+pub fn record(provider: &str, limits: RateLimits) {
+    if crate::utils::cli_utils::is_debug_mode() {
+        use colored::Colorize;
+        eprintln!(
+            "{}",
+            format!("Rate limits ({}): {}", provider, limits.summary()).dimmed()
+        );
+    }
+    if crate::lock::is_read_only() {
+        return;
+    }
+    // Under one lock, so responses of concurrent lc processes don't drop each other's limits
+    let result = RateLimitStore::file_path().and_then(|path| {
+        crate::lock::update_config_file(&path, |content| {
+            let mut store: RateLimitStore = content
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            store.providers.insert(provider.to_string(), limits);
+            Ok(serde_json::to_string_pretty(&store)?)
+        })
+    });
+    if let Err(e) = result {
+        crate::debug_log!("Failed to save rate limits: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_openai_headers() {
+        let limits = RateLimits::from_headers(&headers(&[
+            ("x-ratelimit-limit-requests", "50"),
+            ("x-ratelimit-remaining-requests", "49"),
+            ("x-ratelimit-reset-requests", "1.2s"),
+            ("x-ratelimit-limit-tokens", "40000"),
+            ("x-ratelimit-remaining-tokens", "39000"),
+            ("content-type", "application/json"),
+        ]))
+        .unwrap();
+        assert_eq!(limits.headers.len(), 5);
+        assert_eq!(
+            limits.summary(),
+            "requests 49/50 (resets 1.2s), tokens 39000/40000"
+        );
+        assert!(!limits.exhausted());
+        assert!(RateLimits::from_headers(&headers(&[("content-type", "text/plain")])).is_none());
+    }
+
+    #[test]
+    fn test_anthropic_and_retry_after() {
+        let limits = RateLimits::from_headers(&headers(&[
+            ("anthropic-ratelimit-requests-limit", "1000"),
+            ("anthropic-ratelimit-requests-remaining", "0"),
+            ("anthropic-ratelimit-requests-reset", "2026-10-17T12:00:30Z"),
+            ("anthropic-ratelimit-input-tokens-remaining", "79000"),
+            ("anthropic-ratelimit-input-tokens-limit", "80000"),
+            ("retry-after", "20"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            limits.summary(),
+            "input tokens 79000/80000, requests 0/1000 (resets 2026-10-17T12:00:30Z), retry after 20s"
+        );
+        assert!(limits.exhausted());
+    }
+}
@@ -0,0 +1,309 @@
+//! Prompted (ReAct-style) tool calling for models without native function calling
+//!
+//! The available tools are described in the system prompt and the model is asked to
+//! answer in this format:
+//!
+//! ```text
+//! Thought: I need the current weather
+//! Action: get_weather
+//! Action Input: {"city": "Paris"}
+//! ```
+//!
+//! lc runs the tool, sends the result back as `Observation: <result>` and loops until
+//! the model replies with `Final Answer: <answer>`.
+
+use crate::core::provider::Tool;
+use anyhow::Result;
+
+/// What the model asked for in a prompted tool-calling turn
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReactStep {
+    /// Run a tool with the given JSON arguments
+    Action {
+        name: String,
+        arguments: serde_json::Value,
+    },
+    /// The model is done
+    Final(String),
+}
+
+/// System prompt section describing the tools and the expected response format
+pub fn tool_instructions(tools: &[Tool]) -> String {
+    let mut prompt = String::from("You have access to the following tools:\n\n");
+    for tool in tools {
+        prompt.push_str(&format!(
+            "- {}: {}\n  Arguments (JSON schema): {}\n",
+            tool.function.name, tool.function.description, tool.function.parameters
+        ));
+    }
+
+    let names: Vec<&str> = tools.iter().map(|t| t.function.name.as_str()).collect();
+    prompt.push_str(&format!(
+        "\nTo use a tool, respond with exactly this format and then stop:\n\n\
+         Thought: <your reasoning>\n\
+         Action: <the tool name, one of: {}>\n\
+         Action Input: <the arguments as a single JSON object>\n\n\
+         The tool result will be sent back to you as \"Observation: <result>\". \
+         You can use tools as many times as needed. \
+         When you know the answer, respond with:\n\n\
+         Thought: <your reasoning>\n\
+         Final Answer: <your answer to the user>",
+        names.join(", ")
+    ));
+    prompt
+}
+
+/// Message sent back to the model with a tool result
+pub fn observation(result: &str) -> String {
+    format!("Observation: {}", result)
+}
+
+/// Parse a model reply into the next step.
+///
+/// Replies without an `Action:` or `Final Answer:` marker are treated as the final
+/// answer. Errors mean the model asked for a tool but the arguments could not be
+/// parsed; the message is meant to be sent back as an observation.
+pub fn parse_step(text: &str) -> Result<ReactStep> {
+    let action = find_marker(text, "Action:");
+    let final_answer = find_marker(text, "Final Answer:");
+
+    // Whichever marker comes first wins
+    match (action, final_answer) {
+        (Some(action_pos), Some(final_pos)) if final_pos < action_pos => Ok(ReactStep::Final(
+            text[final_pos + "Final Answer:".len()..].trim().to_string(),
+        )),
+        (Some(action_pos), _) => parse_action(&text[action_pos..]),
+        (None, Some(final_pos)) => Ok(ReactStep::Final(
+            text[final_pos + "Final Answer:".len()..].trim().to_string(),
+        )),
+        (None, None) => {
+            Ok(parse_json_tool_call(text)
+                .unwrap_or_else(|| ReactStep::Final(text.trim().to_string())))
+        }
+    }
+}
+
+/// Whether a provider error means the model/endpoint doesn't accept tool definitions
+pub fn is_tool_support_error(error: &anyhow::Error) -> bool {
+    let message = error.to_string().to_lowercase();
+    let mentions_tools = message.contains("tool") || message.contains("function");
+    let unsupported = [
+        "not support",
+        "unsupported",
+        "doesn't support",
+        "not supported",
+        "not available",
+        "not enabled",
+        "unrecognized request argument",
+        "extra inputs are not permitted",
+    ]
+    .iter()
+    .any(|phrase| message.contains(phrase));
+    mentions_tools && unsupported
+}
+
+/// Byte offset of a marker at the start of a line (ignoring markdown emphasis)
+fn find_marker(text: &str, marker: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start_matches(|c: char| c.is_whitespace() || c == '*');
+        if trimmed
+            .get(..marker.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(marker))
+        {
+            return Some(offset + (line.len() - trimmed.len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn parse_action(text: &str) -> Result<ReactStep> {
+    let after_marker = &text["Action:".len()..];
+    let (name_line, rest) = after_marker.split_once('\n').unwrap_or((after_marker, ""));
+    let name = name_line
+        .trim_matches(|c: char| c.is_whitespace() || c == '*' || c == '`' || c == '"')
+        .to_string();
+    if name.is_empty() {
+        anyhow::bail!("Missing tool name after 'Action:'");
+    }
+
+    // Anything the model invented after its request is ignored
+    let rest = match find_marker(rest, "Observation:") {
+        Some(pos) => &rest[..pos],
+        None => rest,
+    };
+
+    let input = match find_marker(rest, "Action Input:") {
+        Some(pos) => rest[pos + "Action Input:".len()..].trim(),
+        None => "",
+    };
+    let input = strip_code_fence(input.trim_start_matches('*').trim());
+
+    let arguments = if input.is_empty() {
+        serde_json::json!({})
+    } else {
+        parse_json_object(input).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Could not parse the Action Input for '{}' as a JSON object: {}",
+                name,
+                input
+            )
+        })?
+    };
+
+    Ok(ReactStep::Action { name, arguments })
+}
+
+/// Some models ignore the format and emit a bare JSON tool call, e.g.
+/// `{"name": "get_weather", "arguments": {"city": "Paris"}}`, optionally wrapped in
+/// `<tool_call>` tags or a code fence
+fn parse_json_tool_call(text: &str) -> Option<ReactStep> {
+    let text = text
+        .trim()
+        .trim_start_matches("<tool_call>")
+        .trim_end_matches("</tool_call>");
+    let value = parse_json_object(strip_code_fence(text.trim()))?;
+    let name = value
+        .get("name")
+        .or_else(|| value.get("tool"))
+        .and_then(|n| n.as_str())?
+        .to_string();
+    let arguments = value
+        .get("arguments")
+        .or_else(|| value.get("parameters"))
+        .or_else(|| value.get("args"))
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    // Arguments are sometimes double-encoded as a JSON string
+    let arguments = match arguments {
+        serde_json::Value::String(s) => parse_json_object(&s)?,
+        other => other,
+    };
+    Some(ReactStep::Action { name, arguments })
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let Some(inner) = text.strip_prefix("```") else {
+        return text;
+    };
+    // Drop the language tag on the opening fence
+    let inner = inner.split_once('\n').map_or(inner, |(_, body)| body);
+    match inner.rfind("```") {
+        Some(end) => inner[..end].trim(),
+        None => inner.trim(),
+    }
+}
+
+fn parse_json_object(text: &str) -> Option<serde_json::Value> {
+    if let Ok(value @ serde_json::Value::Object(_)) = serde_json::from_str(text) {
+        return Some(value);
+    }
+    // Tolerate text around the object
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    match serde_json::from_str(&text[start..=end]) {
+        Ok(value @ serde_json::Value::Object(_)) => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::provider::Function;
+    use serde_json::json;
+
+    fn weather_tool() -> Tool {
+        Tool {
+            tool_type: "function".to_string(),
+            function: Function {
+                name: "get_weather".to_string(),
+                description: "Get the current weather".to_string(),
+                parameters: json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+            },
+        }
+    }
+
+    #[test]
+    fn test_tool_instructions_lists_tools() {
+        let prompt = tool_instructions(&[weather_tool()]);
+        assert!(prompt.contains("- get_weather: Get the current weather"));
+        assert!(prompt.contains("one of: get_weather"));
+        assert!(prompt.contains("Final Answer:"));
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let step = parse_step(
+            "Thought: I need the weather\nAction: get_weather\nAction Input: {\"city\": \"Paris\"}",
+        )
+        .unwrap();
+        assert_eq!(
+            step,
+            ReactStep::Action {
+                name: "get_weather".to_string(),
+                arguments: json!({"city": "Paris"}),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_action_ignores_invented_observation() {
+        let step = parse_step(
+            "**Action:** `get_weather`\n**Action Input:**\n```json\n{\"city\": \"Oslo\"}\n```\nObservation: sunny\nFinal Answer: It is sunny",
+        )
+        .unwrap();
+        assert_eq!(
+            step,
+            ReactStep::Action {
+                name: "get_weather".to_string(),
+                arguments: json!({"city": "Oslo"}),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_final_answer() {
+        let step = parse_step("Thought: done\nFinal Answer: It is 21°C in Paris.").unwrap();
+        assert_eq!(step, ReactStep::Final("It is 21°C in Paris.".to_string()));
+
+        let step = parse_step("Just a plain answer").unwrap();
+        assert_eq!(step, ReactStep::Final("Just a plain answer".to_string()));
+    }
+
+    #[test]
+    fn test_parse_bare_json_tool_call() {
+        let step = parse_step(
+            "<tool_call>{\"name\": \"get_weather\", \"arguments\": \"{\\\"city\\\": \\\"Rome\\\"}\"}</tool_call>",
+        )
+        .unwrap();
+        assert_eq!(
+            step,
+            ReactStep::Action {
+                name: "get_weather".to_string(),
+                arguments: json!({"city": "Rome"}),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_action_input() {
+        let err = parse_step("Action: get_weather\nAction Input: city=Paris").unwrap_err();
+        assert!(err.to_string().contains("JSON object"));
+    }
+
+    #[test]
+    fn test_is_tool_support_error() {
+        let err = anyhow::anyhow!(
+            "API request failed with status 400 Bad Request: {{\"error\":\"llama2 does not support tools\"}}"
+        );
+        assert!(is_tool_support_error(&err));
+
+        let err = anyhow::anyhow!("API request failed with status 401 Unauthorized: bad key");
+        assert!(!is_tool_support_error(&err));
+    }
+}
@@ -0,0 +1,167 @@
+//! Reasoning traces from thinking models
+//!
+//! Reasoning arrives separately from the answer: `reasoning_content` (DeepSeek and
+//! most OpenAI-compatible hosts), `reasoning` (OpenRouter), Anthropic `thinking`
+//! blocks, or `<think>...</think>` at the start of the content (R1 distills served
+//! by Ollama, Groq and others). It is kept out of the answer, shown dimmed on stderr
+//! with `--show-reasoning`, and stored in its own column of the chat logs so it is
+//! never sent back as conversation history.
+
+use colored::Colorize;
+use serde_json::Value;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+/// Check whether `--show-reasoning` is enabled for this invocation
+pub fn is_shown() -> bool {
+    crate::SHOW_REASONING_MODE.load(Ordering::Relaxed)
+}
+
+// Reasoning of the latest response, waiting to be logged with it
+static LAST: Mutex<Option<String>> = Mutex::new(None);
+
+/// Keep the reasoning of the latest response so it is logged with that response
+pub fn record(reasoning: String) {
+    if reasoning.trim().is_empty() {
+        return;
+    }
+    if let Ok(mut last) = LAST.lock() {
+        *last = Some(reasoning.trim().to_string());
+    }
+}
+
+/// Take the reasoning recorded for the latest response
+pub fn take() -> Option<String> {
+    LAST.lock().ok().and_then(|mut last| last.take())
+}
+
+/// Print a complete reasoning trace when `--show-reasoning` is enabled
+pub fn show(reasoning: &str) {
+    if is_shown() && !reasoning.trim().is_empty() {
+        eprintln!("{}", "Reasoning:".dimmed().bold());
+        let reasoning = crate::core::redaction::redact(reasoning.trim());
+        eprintln!("{}\n", reasoning.dimmed());
+    }
+}
+
+/// Reasoning of a non-streaming chat response
+pub fn from_response(response: &Value) -> Option<String> {
+    let message = response.pointer("/choices/0/message");
+    if let Some(text) = message
+        .and_then(|m| m.get("reasoning_content").or_else(|| m.get("reasoning")))
+        .and_then(|r| r.as_str())
+        .filter(|r| !r.trim().is_empty())
+    {
+        return Some(text.to_string());
+    }
+
+    let thinking: Vec<&str> = response
+        .get("content")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("thinking"))
+        .filter_map(|block| block.get("thinking").and_then(|t| t.as_str()))
+        .collect();
+    (!thinking.is_empty()).then(|| thinking.join("\n\n"))
+}
+
+/// Answer text of an Anthropic response with thinking blocks, which the usual
+/// `content[0].text` extraction would miss
+pub fn anthropic_answer(response: &Value) -> Option<String> {
+    let blocks = response.get("content")?.as_array()?;
+    if !blocks
+        .iter()
+        .any(|block| block.get("type").and_then(|t| t.as_str()) == Some("thinking"))
+    {
+        return None;
+    }
+    Some(
+        blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join(""),
+    )
+}
+
+/// Reasoning delta of a streaming chunk
+pub fn from_stream_chunk(chunk: &Value) -> Option<&str> {
+    if let Some(delta) = chunk.pointer("/choices/0/delta") {
+        return delta
+            .get("reasoning_content")
+            .or_else(|| delta.get("reasoning"))
+            .and_then(|r| r.as_str());
+    }
+    // Anthropic: {"type":"content_block_delta","delta":{"type":"thinking_delta","thinking":"..."}}
+    let delta = chunk.get("delta")?;
+    if delta.get("type").and_then(|t| t.as_str()) == Some("thinking_delta") {
+        return delta.get("thinking").and_then(|t| t.as_str());
+    }
+    None
+}
+
+/// Split a leading `<think>...</think>` section from the answer
+pub fn split_think_tags(content: &str) -> Option<(String, String)> {
+    let rest = content.trim_start().strip_prefix("<think>")?;
+    let (reasoning, answer) = rest.split_once("</think>")?;
+    Some((
+        reasoning.trim().to_string(),
+        answer.trim_start().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_response_formats() {
+        let deepseek = json!({"choices": [{"message": {
+            "content": "42", "reasoning_content": "Six times seven."
+        }}]});
+        assert_eq!(
+            from_response(&deepseek).as_deref(),
+            Some("Six times seven.")
+        );
+
+        let openrouter = json!({"choices": [{"message": {"content": "42", "reasoning": "Hmm."}}]});
+        assert_eq!(from_response(&openrouter).as_deref(), Some("Hmm."));
+
+        let anthropic = json!({"content": [
+            {"type": "thinking", "thinking": "Let me think.", "signature": "x"},
+            {"type": "text", "text": "42"}
+        ]});
+        assert_eq!(from_response(&anthropic).as_deref(), Some("Let me think."));
+        assert_eq!(anthropic_answer(&anthropic).as_deref(), Some("42"));
+
+        let plain = json!({"choices": [{"message": {"content": "42"}}]});
+        assert_eq!(from_response(&plain), None);
+        assert_eq!(
+            anthropic_answer(&json!({"content": [{"type": "text", "text": "42"}]})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stream_chunks() {
+        let chunk = json!({"choices": [{"delta": {"reasoning_content": "step"}}]});
+        assert_eq!(from_stream_chunk(&chunk), Some("step"));
+        let chunk = json!({"type": "content_block_delta", "delta": {"type": "thinking_delta", "thinking": "hm"}});
+        assert_eq!(from_stream_chunk(&chunk), Some("hm"));
+        let chunk = json!({"choices": [{"delta": {"content": "answer"}}]});
+        assert_eq!(from_stream_chunk(&chunk), None);
+    }
+
+    #[test]
+    fn test_split_think_tags() {
+        assert_eq!(
+            split_think_tags("<think>\nadd them\n</think>\n\nThe sum is 4."),
+            Some(("add them".to_string(), "The sum is 4.".to_string()))
+        );
+        assert_eq!(split_think_tags("The sum is 4."), None);
+        assert_eq!(split_think_tags("<think>unterminated"), None);
+    }
+}
@@ -0,0 +1,235 @@
+//! Masking of secrets in printed answers, debug logs and stored history
+//!
+//! Models sometimes echo back what they were given, such as an attached `.env` or a
+//! provider config. Every value stored in keys.toml (API keys, tokens, service account
+//! private keys, sensitive headers) is replaced with [`MASK`] before an answer is printed
+//! or logged, as is any match of the regexes in the `[redaction]` section:
+//!
+//! ```toml
+//! [redaction]
+//! patterns = ["ghp_[A-Za-z0-9]{36}", "password\\s*=\\s*\"([^\"]+)\""]
+//! # enabled = false   # turn redaction off
+//! ```
+//!
+//! A pattern with a capture group only masks the group, so the name of the setting stays
+//! readable. Streamed answers are printed a word at a time once masked, or a line at a
+//! time when patterns are configured, since a pattern can span several words. The stored
+//! history is always masked as a whole.
+
+use crate::config::Config;
+use crate::keys::KeysConfig;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+/// What a secret is replaced with
+pub const MASK: &str = "[REDACTED]";
+
+/// Shorter stored values are not masked, so short tokens do not garble ordinary text
+const MIN_SECRET_CHARS: usize = 8;
+
+/// Longest text held back while streaming before it is printed without a word break
+const MAX_PENDING_CHARS: usize = 4096;
+
+/// The `[redaction]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RedactionSettings {
+    /// Set to false to print and store answers unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Regexes of further secrets to mask
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+}
+
+impl RedactionSettings {
+    pub fn is_empty(&self) -> bool {
+        self.enabled.is_none() && self.patterns.is_empty()
+    }
+}
+
+/// Masks known secret values and pattern matches
+#[derive(Debug, Default)]
+pub struct Redactor {
+    /// Longest first, so a secret containing another is masked whole
+    secrets: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    pub fn new(secrets: impl IntoIterator<Item = String>, patterns: &[String]) -> Result<Self> {
+        let mut secrets: Vec<String> = secrets
+            .into_iter()
+            .map(|secret| secret.trim().to_string())
+            .filter(|secret| secret.chars().count() >= MIN_SECRET_CHARS)
+            .collect();
+        secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        secrets.dedup();
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid redaction pattern '{}'", pattern))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Redactor { secrets, patterns })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty() && self.patterns.is_empty()
+    }
+
+    /// `text` with every secret replaced by [`MASK`]
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = Cow::Owned(text.replace(secret.as_str(), MASK));
+            }
+        }
+        for pattern in &self.patterns {
+            if !pattern.is_match(&text) {
+                continue;
+            }
+            let masked = pattern.replace_all(&text, |caps: &regex::Captures| {
+                let whole = caps.get(0).expect("group 0 is the whole match");
+                match caps.get(1) {
+                    Some(group) => format!(
+                        "{}{}{}",
+                        &whole.as_str()[..group.start() - whole.start()],
+                        MASK,
+                        &whole.as_str()[group.end() - whole.start()..]
+                    ),
+                    None => MASK.to_string(),
+                }
+            });
+            text = Cow::Owned(masked.into_owned());
+        }
+        text
+    }
+}
+
+static CURRENT: RwLock<Option<Arc<Redactor>>> = RwLock::new(None);
+
+/// Use `redactor` for the rest of this invocation (`None` to stop masking)
+pub fn set(redactor: Option<Redactor>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = redactor.filter(|r| !r.is_empty()).map(Arc::new);
+    }
+}
+
+fn current() -> Option<Arc<Redactor>> {
+    CURRENT.read().ok().and_then(|current| current.clone())
+}
+
+/// Mask the secrets in keys.toml and the configured patterns for this invocation
+pub fn init() -> Result<()> {
+    let config = Config::load()?;
+    if config.redaction.enabled == Some(false) {
+        set(None);
+        return Ok(());
+    }
+    let keys = KeysConfig::load()?;
+    let inline_keys = config
+        .providers
+        .values()
+        .filter_map(|provider| provider.api_key.clone());
+    set(Some(Redactor::new(
+        keys.secret_values().into_iter().chain(inline_keys),
+        &config.redaction.patterns,
+    )?));
+    Ok(())
+}
+
+/// `text` with the secrets of this invocation masked
+pub fn redact(text: &str) -> Cow<'_, str> {
+    match current() {
+        Some(redactor) => redactor.redact(text),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Masks streamed text, holding back the last partial word (or line, with patterns) until
+/// it is complete
+#[derive(Debug, Default)]
+pub struct StreamRedactor {
+    pending: String,
+}
+
+impl StreamRedactor {
+    /// The part of the stream that can be printed after adding `text`
+    pub fn push(&mut self, text: &str) -> String {
+        let Some(redactor) = current() else {
+            return text.to_string();
+        };
+        self.pending.push_str(text);
+        let boundary = if redactor.patterns.is_empty() {
+            self.pending.rfind(char::is_whitespace)
+        } else {
+            self.pending.rfind('\n')
+        };
+        let ready = match boundary {
+            Some(index) => {
+                index
+                    + self.pending[index..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8)
+            }
+            None if self.pending.len() > MAX_PENDING_CHARS => self.pending.len(),
+            None => return String::new(),
+        };
+        let ready: String = self.pending.drain(..ready).collect();
+        redactor.redact(&ready).into_owned()
+    }
+
+    /// The rest of the stream, once it has ended
+    pub fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.pending);
+        redact(&rest).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let redactor = Redactor::new(
+            ["sk-live-0123456789".to_string(), "short".to_string()],
+            &[
+                r#"password\s*=\s*"([^"]+)""#.to_string(),
+                "ghp_[a-z]{4}".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            redactor.redact("OPENAI_API_KEY=sk-live-0123456789 short"),
+            "OPENAI_API_KEY=[REDACTED] short"
+        );
+        assert_eq!(
+            redactor.redact(r#"password = "hunter2" token ghp_abcd"#),
+            r#"password = "[REDACTED]" token [REDACTED]"#
+        );
+        assert!(matches!(redactor.redact("nothing here"), Cow::Borrowed(_)));
+        assert!(Redactor::new(Vec::new(), &["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_stream_redactor() {
+        set(Some(
+            Redactor::new(["sk-live-0123456789".to_string()], &[]).unwrap(),
+        ));
+        let mut stream = StreamRedactor::default();
+        let mut printed = String::new();
+        for chunk in ["Your key is sk-li", "ve-01234", "56789 and", " more"] {
+            printed.push_str(&stream.push(chunk));
+        }
+        printed.push_str(&stream.finish());
+        set(None);
+        assert_eq!(printed, "Your key is [REDACTED] and more");
+    }
+}
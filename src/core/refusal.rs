@@ -0,0 +1,373 @@
+//! Recovery from provider refusals (`--on-refusal`, `--fallback-model`)
+//!
+//! In direct prompts, a request blocked by the provider's content filter, or answered
+//! with a tool call whose arguments are not valid JSON, fails with a [`Refusal`] instead
+//! of a generic error. The configured strategies are then tried in order, each starting
+//! from the original request, until one gets an answer:
+//!
+//! - `rephrase`: have the model reword the prompt in neutral terms and send that (for a
+//!   malformed tool call, send the prompt again with a reminder to emit valid JSON)
+//! - `strip-attachments`: send the prompt without the attached files, images and audio
+//! - `fallback`: send the prompt to the fallback model
+//!
+//! What was tried is printed on stderr. Without strategies, answers are handled as
+//! before and only failed requests are recognized as refusals. Strategies and the
+//! fallback model can also be set in config.toml:
+//!
+//! ```toml
+//! [refusal]
+//! strategies = ["rephrase", "fallback"]
+//! fallback_model = "anthropic:claude-sonnet-4-5"
+//! ```
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::sync::RwLock;
+
+/// Why the provider refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefusalKind {
+    /// The prompt or the answer was blocked by a content filter
+    ContentFilter,
+    /// The model called a tool with arguments that are not valid JSON
+    MalformedToolCall,
+}
+
+impl RefusalKind {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::ContentFilter => "The provider's content filter blocked the request",
+            Self::MalformedToolCall => "The model called a tool with invalid JSON arguments",
+        }
+    }
+}
+
+/// The error of a refused request
+#[derive(Debug)]
+pub struct Refusal {
+    pub kind: RefusalKind,
+    /// What the provider said
+    pub detail: String,
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind.describe(), self.detail)
+    }
+}
+
+impl std::error::Error for Refusal {}
+
+/// The refusal behind an error, if it is one
+pub fn kind_of(error: &anyhow::Error) -> Option<RefusalKind> {
+    error.downcast_ref::<Refusal>().map(|refusal| refusal.kind)
+}
+
+/// A way to recover from a refusal
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Reword the prompt, or ask again for valid tool-call JSON
+    Rephrase,
+    /// Send the prompt without attached files, images and audio
+    StripAttachments,
+    /// Send the prompt to the fallback model
+    Fallback,
+}
+
+impl Strategy {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rephrase => "rephrase",
+            Self::StripAttachments => "strip-attachments",
+            Self::Fallback => "fallback",
+        }
+    }
+}
+
+/// The `[refusal]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RefusalSettings {
+    /// Strategies tried in order when `--on-refusal` is not given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strategies: Vec<Strategy>,
+    /// Model for the `fallback` strategy (`provider:model` or alias)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_model: Option<String>,
+}
+
+impl RefusalSettings {
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty() && self.fallback_model.is_none()
+    }
+}
+
+static CURRENT: RwLock<Option<RefusalSettings>> = RwLock::new(None);
+
+tokio::task_local! {
+    static RECOVERING: bool;
+}
+
+/// Set the strategies and fallback model of this invocation (`--on-refusal`,
+/// `--fallback-model`); what is not given comes from `[refusal]`
+pub fn set(strategies: Vec<Strategy>, fallback_model: Option<String>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(RefusalSettings {
+            strategies,
+            fallback_model: fallback_model.filter(|model| !model.trim().is_empty()),
+        });
+    }
+}
+
+fn current() -> RefusalSettings {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|current| current.clone())
+        .unwrap_or_default()
+}
+
+/// Send the requests of `request` as a prompt that can recover from refusals: failed
+/// requests the provider refused become a [`Refusal`], and with `recover` so do answers
+/// it refused. Requests sent outside of it report refusals as before.
+pub async fn with_recovery<F: Future>(recover: bool, request: F) -> F::Output {
+    RECOVERING.scope(recover, request).await
+}
+
+/// Whether failed requests the provider refused are reported as a [`Refusal`]
+pub fn detects_failed() -> bool {
+    RECOVERING.try_with(|_| ()).is_ok()
+}
+
+/// Whether answers the provider refused are reported as a [`Refusal`]
+pub fn detects_answers() -> bool {
+    RECOVERING.try_with(|recover| *recover).unwrap_or(false)
+}
+
+/// The strategies to try and the fallback model, from the flags or `[refusal]`
+pub fn active(config: &Config) -> RefusalSettings {
+    let flags = current();
+    RefusalSettings {
+        strategies: if flags.strategies.is_empty() {
+            config.refusal.strategies.clone()
+        } else {
+            flags.strategies
+        },
+        fallback_model: flags
+            .fallback_model
+            .or_else(|| config.refusal.fallback_model.clone()),
+    }
+}
+
+/// Recognize a refusal in a failed request's response body
+pub fn from_error_response(status: u16, body: &str) -> Option<RefusalKind> {
+    if !(400..500).contains(&status) {
+        return None;
+    }
+    let lower = body.to_lowercase();
+    let json: Option<Value> = serde_json::from_str(body).ok();
+    let code = json
+        .as_ref()
+        .and_then(|json| json.get("error").unwrap_or(json).get("code"))
+        .and_then(Value::as_str)
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    if code == "content_filter"
+        || code == "content_policy_violation"
+        || lower.contains("responsibleaipolicyviolation")
+        || lower.contains("content management policy")
+        || lower.contains("content_policy_violation")
+    {
+        return Some(RefusalKind::ContentFilter);
+    }
+    if code == "tool_use_failed"
+        || lower.contains("failed to parse tool call")
+        || lower.contains("failed to call a function")
+        || lower.contains("malformed_function_call")
+    {
+        return Some(RefusalKind::MalformedToolCall);
+    }
+    None
+}
+
+/// Recognize a refusal in a successful chat response (OpenAI, Anthropic or Gemini format)
+pub fn from_response(json: &Value) -> Option<Refusal> {
+    let refusal = |kind, detail: &str| {
+        Some(Refusal {
+            kind,
+            detail: detail.to_string(),
+        })
+    };
+
+    // OpenAI-compatible
+    if let Some(choice) = json.pointer("/choices/0") {
+        let message = choice.get("message");
+        if let Some(text) = message
+            .and_then(|m| m.get("refusal"))
+            .and_then(Value::as_str)
+            .filter(|text| !text.trim().is_empty())
+        {
+            return refusal(RefusalKind::ContentFilter, text);
+        }
+        if choice.get("finish_reason").and_then(Value::as_str) == Some("content_filter") {
+            return refusal(
+                RefusalKind::ContentFilter,
+                "finish_reason is content_filter",
+            );
+        }
+        let tool_calls = message
+            .and_then(|m| m.get("tool_calls"))
+            .and_then(Value::as_array);
+        for call in tool_calls.into_iter().flatten() {
+            let Some(function) = call.get("function") else {
+                continue;
+            };
+            let arguments = function
+                .get("arguments")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if !arguments.trim().is_empty() && serde_json::from_str::<Value>(arguments).is_err() {
+                let name = function
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("tool");
+                return refusal(
+                    RefusalKind::MalformedToolCall,
+                    &format!("arguments of '{}' are not JSON: {}", name, arguments),
+                );
+            }
+        }
+    }
+
+    // Anthropic Messages API
+    if json.get("stop_reason").and_then(Value::as_str) == Some("refusal") {
+        return refusal(RefusalKind::ContentFilter, "stop_reason is refusal");
+    }
+
+    // Gemini
+    if let Some(reason) = json
+        .pointer("/promptFeedback/blockReason")
+        .and_then(Value::as_str)
+    {
+        return refusal(
+            RefusalKind::ContentFilter,
+            &format!("prompt blocked ({})", reason),
+        );
+    }
+    match json
+        .pointer("/candidates/0/finishReason")
+        .and_then(Value::as_str)
+    {
+        Some(
+            reason @ ("SAFETY" | "PROHIBITED_CONTENT" | "BLOCKLIST" | "SPII" | "IMAGE_SAFETY"
+            | "RECITATION"),
+        ) => refusal(
+            RefusalKind::ContentFilter,
+            &format!("finishReason is {}", reason),
+        ),
+        Some("MALFORMED_FUNCTION_CALL") => refusal(
+            RefusalKind::MalformedToolCall,
+            "finishReason is MALFORMED_FUNCTION_CALL",
+        ),
+        _ => None,
+    }
+}
+
+/// The request asking the model to reword a prompt that was blocked
+pub fn rephrase_request(prompt: &str) -> String {
+    format!(
+        "The request below was blocked by a content filter, probably because of its wording. \
+         Rewrite it so it asks for the same thing in neutral, professional language. Reply \
+         with the rewritten request only.\n\n<request>\n{}\n</request>",
+        prompt
+    )
+}
+
+/// The prompt sent again after a malformed tool call
+pub fn tool_call_reminder(prompt: &str) -> String {
+    format!(
+        "{}\n\n(When you call a tool, its arguments must be one valid JSON object that \
+         matches the tool's schema.)",
+        prompt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_refusals_in_error_responses() {
+        let azure = r#"{"error": {"code": "content_filter", "message": "The response was filtered due to the prompt triggering Azure OpenAI's content management policy."}}"#;
+        assert_eq!(
+            from_error_response(400, azure),
+            Some(RefusalKind::ContentFilter)
+        );
+        let groq = r#"{"error": {"message": "Failed to call a function. Please adjust your prompt.", "code": "tool_use_failed"}}"#;
+        assert_eq!(
+            from_error_response(400, groq),
+            Some(RefusalKind::MalformedToolCall)
+        );
+        assert_eq!(from_error_response(500, azure), None);
+        assert_eq!(
+            from_error_response(400, r#"{"error": {"code": "invalid_api_key"}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_refusals_in_responses() {
+        let filtered =
+            json!({"choices": [{"message": {"content": null}, "finish_reason": "content_filter"}]});
+        assert_eq!(
+            from_response(&filtered).map(|r| r.kind),
+            Some(RefusalKind::ContentFilter)
+        );
+        let malformed = json!({"choices": [{"message": {"tool_calls": [
+            {"id": "1", "type": "function", "function": {"name": "search", "arguments": "{\"q\": \"rust"}}
+        ]}, "finish_reason": "tool_calls"}]});
+        let refusal = from_response(&malformed).unwrap();
+        assert_eq!(refusal.kind, RefusalKind::MalformedToolCall);
+        assert!(refusal.detail.contains("search"));
+        let gemini = json!({"candidates": [{"finishReason": "SAFETY"}]});
+        assert_eq!(
+            from_response(&gemini).map(|r| r.kind),
+            Some(RefusalKind::ContentFilter)
+        );
+        assert_eq!(
+            from_response(&json!({"stop_reason": "refusal", "content": []})).map(|r| r.kind),
+            Some(RefusalKind::ContentFilter)
+        );
+
+        let answer = json!({"choices": [{"message": {"content": "Hi", "tool_calls": [
+            {"function": {"name": "now", "arguments": ""}}
+        ]}, "finish_reason": "stop"}]});
+        assert!(from_response(&answer).is_none());
+    }
+
+    #[test]
+    fn test_kind_of() {
+        let error = anyhow::Error::new(Refusal {
+            kind: RefusalKind::ContentFilter,
+            detail: "blocked".to_string(),
+        });
+        assert_eq!(kind_of(&error), Some(RefusalKind::ContentFilter));
+        assert_eq!(kind_of(&anyhow::anyhow!("other")), None);
+    }
+
+    #[tokio::test]
+    async fn test_detection_only_inside_scope() {
+        assert!(!detects_failed());
+        assert!(!detects_answers());
+        with_recovery(false, async {
+            assert!(detects_failed());
+            assert!(!detects_answers());
+        })
+        .await;
+        with_recovery(true, async { assert!(detects_answers()) }).await;
+    }
+}
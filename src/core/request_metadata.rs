@@ -0,0 +1,134 @@
+//! Per-request attribution: the `user` field and `--metadata key=value` pairs
+//!
+//! The values are set once per invocation from the command line (or `LC_USER`) and
+//! added to chat requests so provider dashboards can attribute traffic. `user` can
+//! also be configured per provider with `lc providers vars <provider> set user <id>`.
+//! Both are stored with each entry in the chat logs, along with the system prompt
+//! the request was sent with (see [`log_details`]).
+
+use crate::database::LogDetails;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestMetadata {
+    pub user: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+static CURRENT: RwLock<Option<RequestMetadata>> = RwLock::new(None);
+
+/// Set the user and metadata for requests made by this invocation
+pub fn set(user: Option<String>, metadata: BTreeMap<String, String>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(RequestMetadata {
+            user: user.filter(|u| !u.trim().is_empty()),
+            metadata,
+        });
+    }
+}
+
+/// The user and metadata set for this invocation
+pub fn current() -> RequestMetadata {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|current| current.clone())
+        .unwrap_or_default()
+}
+
+/// What to log with an answer from `provider` to a request sent with `system_prompt`:
+/// this invocation's user (or the provider's `user` var) and metadata, and the
+/// reasoning trace of the answer just received
+pub fn log_details(provider: &str, system_prompt: Option<&str>) -> LogDetails {
+    let request = current();
+    LogDetails {
+        user: request.user.or_else(|| configured_user(provider)),
+        metadata: request.metadata,
+        system_prompt: system_prompt.map(str::to_string),
+        reasoning: crate::core::reasoning::take(),
+        images: Vec::new(),
+    }
+}
+
+/// Parse `key=value` pairs from `--metadata`
+pub fn parse_entries(entries: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut metadata = BTreeMap::new();
+    for entry in entries {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid metadata '{}'. Use key=value", entry))?;
+        let key = key.trim();
+        if key.is_empty() {
+            anyhow::bail!("Invalid metadata '{}': the key is empty", entry);
+        }
+        metadata.insert(key.to_string(), value.trim().to_string());
+    }
+    Ok(metadata)
+}
+
+/// The user to send to a provider: `--user`/`LC_USER` first, then the provider's `user` var
+pub fn user_for(
+    provider_vars: Option<&std::collections::HashMap<String, String>>,
+) -> Option<String> {
+    current().user.or_else(|| {
+        provider_vars
+            .and_then(|vars| vars.get("user"))
+            .filter(|u| !u.trim().is_empty())
+            .cloned()
+    })
+}
+
+/// The provider's configured `user` var, for logging (read from the provider file
+/// without loading, and possibly migrating, the whole config)
+pub fn configured_user(provider: &str) -> Option<String> {
+    let path = crate::config::Config::provider_file_path(provider).ok()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let provider: crate::config::ProviderConfig = toml::from_str(&content).ok()?;
+    provider
+        .vars
+        .get("user")
+        .filter(|u| !u.trim().is_empty())
+        .cloned()
+}
+
+/// Metadata as stored in the logs (`None` when there is none)
+pub fn metadata_json(metadata: &BTreeMap<String, String>) -> Option<String> {
+    if metadata.is_empty() {
+        None
+    } else {
+        serde_json::to_string(metadata).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let metadata = parse_entries(&[
+            "team=search".to_string(),
+            "ticket = ABC-1 ".to_string(),
+            "note=a=b".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(metadata["team"], "search");
+        assert_eq!(metadata["ticket"], "ABC-1");
+        assert_eq!(metadata["note"], "a=b");
+
+        assert!(parse_entries(&["novalue".to_string()]).is_err());
+        assert!(parse_entries(&["=value".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_metadata_json() {
+        assert_eq!(metadata_json(&BTreeMap::new()), None);
+        let metadata = parse_entries(&["b=2".to_string(), "a=1".to_string()]).unwrap();
+        assert_eq!(
+            metadata_json(&metadata).as_deref(),
+            Some(r#"{"a":"1","b":"2"}"#)
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! HMAC request signing for gateways that authenticate requests by signature
+//!
+//! A provider with `auth_type = "hmac"` sends no bearer token. Every request instead
+//! carries a timestamp header and a signature header: the HMAC of a payload built from
+//! the timestamp, method, path and body, keyed with a secret from keys.toml.
+//!
+//! ```toml
+//! auth_type = "hmac"
+//!
+//! [hmac]
+//! algorithm = "sha256"                  # sha256, sha384 or sha512
+//! signature_header = "X-Signature"
+//! timestamp_header = "X-Timestamp"
+//! key = "gateway"                       # keys.toml entry, defaults to the provider name
+//! key_id = "team-a"                     # optional, sent in key_id_header
+//! encoding = "hex"                      # hex or base64
+//! payload = "{timestamp}\n{method}\n{path}\n{body}"
+//! ```
+
+use anyhow::{bail, Result};
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+
+/// `auth_type` value that turns signing on
+pub const AUTH_TYPE: &str = "hmac";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HmacAlgorithm {
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// The `[hmac]` table of a provider config
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HmacConfig {
+    #[serde(default)]
+    pub algorithm: HmacAlgorithm,
+    #[serde(default = "default_signature_header")]
+    pub signature_header: String,
+    #[serde(default = "default_timestamp_header")]
+    pub timestamp_header: String,
+    /// Name of the secret in keys.toml (`api_keys` or `tokens`); the provider name if unset
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Key identifier sent alongside the signature, for gateways with several keys
+    #[serde(default)]
+    pub key_id: Option<String>,
+    #[serde(default = "default_key_id_header")]
+    pub key_id_header: String,
+    #[serde(default)]
+    pub encoding: SignatureEncoding,
+    /// What is signed. Placeholders: `{timestamp}`, `{method}`, `{path}` (with the query
+    /// string), `{body}` and `{body_sha256}` (hex digest of the body)
+    #[serde(default = "default_payload")]
+    pub payload: String,
+}
+
+fn default_signature_header() -> String {
+    "X-Signature".to_string()
+}
+
+fn default_timestamp_header() -> String {
+    "X-Timestamp".to_string()
+}
+
+fn default_key_id_header() -> String {
+    "X-Key-Id".to_string()
+}
+
+fn default_payload() -> String {
+    "{timestamp}\n{method}\n{path}\n{body}".to_string()
+}
+
+impl Default for HmacConfig {
+    fn default() -> Self {
+        HmacConfig {
+            algorithm: HmacAlgorithm::default(),
+            signature_header: default_signature_header(),
+            timestamp_header: default_timestamp_header(),
+            key: None,
+            key_id: None,
+            key_id_header: default_key_id_header(),
+            encoding: SignatureEncoding::default(),
+            payload: default_payload(),
+        }
+    }
+}
+
+impl HmacConfig {
+    /// Name of the keys.toml entry holding the secret
+    pub fn key_name<'a>(&'a self, provider: &'a str) -> &'a str {
+        self.key.as_deref().unwrap_or(provider)
+    }
+
+    /// Headers signing a request made at `timestamp` (Unix seconds)
+    pub fn signature_headers(
+        &self,
+        secret: &str,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        timestamp: i64,
+    ) -> Result<Vec<(String, String)>> {
+        let timestamp = timestamp.to_string();
+        let payload = self
+            .payload
+            .replace("{timestamp}", &timestamp)
+            .replace("{method}", method)
+            .replace("{path}", path)
+            .replace("{body_sha256}", &sha256_hex(body));
+        // The body is substituted last, so placeholders inside it are left alone
+        let payload = match payload.split_once("{body}") {
+            Some((before, after)) => [before.as_bytes(), body, after.as_bytes()].concat(),
+            None => payload.into_bytes(),
+        };
+
+        let digest = match self.algorithm {
+            HmacAlgorithm::Sha256 => mac::<Hmac<sha2::Sha256>>(secret, &payload)?,
+            HmacAlgorithm::Sha384 => mac::<Hmac<sha2::Sha384>>(secret, &payload)?,
+            HmacAlgorithm::Sha512 => mac::<Hmac<sha2::Sha512>>(secret, &payload)?,
+        };
+        let signature = match self.encoding {
+            SignatureEncoding::Hex => digest.iter().map(|b| format!("{:02x}", b)).collect(),
+            SignatureEncoding::Base64 => general_purpose::STANDARD.encode(&digest),
+        };
+
+        let mut headers = vec![
+            (self.timestamp_header.clone(), timestamp),
+            (self.signature_header.clone(), signature),
+        ];
+        if let Some(key_id) = &self.key_id {
+            headers.push((self.key_id_header.clone(), key_id.clone()));
+        }
+        Ok(headers)
+    }
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(secret: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    let mut mac = <M as Mac>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Invalid HMAC key: {}", e))?;
+    mac.update(payload);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::Digest;
+    format!("{:x}", sha2::Sha256::digest(data))
+}
+
+/// Signs the requests of one provider
+#[derive(Clone)]
+pub struct RequestSigner {
+    config: HmacConfig,
+    secret: String,
+}
+
+impl std::fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestSigner")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RequestSigner {
+    /// Signer for `provider`, with its secret looked up in keys.toml
+    pub fn for_provider(provider: &str, config: Option<&HmacConfig>) -> Result<Self> {
+        let config = config.cloned().unwrap_or_default();
+        let name = config.key_name(provider);
+        let keys = crate::keys::KeysConfig::load()?;
+        let Some(secret) = keys.api_keys.get(name).or(keys.tokens.get(name)) else {
+            if name == provider {
+                bail!(
+                    "No HMAC key for provider '{}'. Add it with 'lc keys add {}'",
+                    provider,
+                    provider
+                );
+            }
+            bail!(
+                "No HMAC key '{}' for provider '{}'. Add it as '{}' under [tokens] in keys.toml",
+                name,
+                provider,
+                name
+            );
+        };
+        Ok(RequestSigner {
+            secret: secret.clone(),
+            config,
+        })
+    }
+
+    /// Add the signature headers to a request that is ready to send
+    pub fn sign(&self, request: &mut reqwest::Request) -> Result<()> {
+        let body = match request.body() {
+            None => &[][..],
+            Some(body) => match body.as_bytes() {
+                Some(bytes) => bytes,
+                None => bail!("Streamed request bodies (file uploads) cannot be HMAC-signed"),
+            },
+        };
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let headers = self.config.signature_headers(
+            &self.secret,
+            request.method().as_str(),
+            &path,
+            body,
+            chrono::Utc::now().timestamp(),
+        )?;
+        for (name, value) in headers {
+            request.headers_mut().insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes())?,
+                reqwest::header::HeaderValue::from_str(&value)?,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_headers() {
+        let config = HmacConfig {
+            key_id: Some("team-a".to_string()),
+            ..HmacConfig::default()
+        };
+        let headers = config
+            .signature_headers("secret", "POST", "/v1/chat/completions", b"{}", 1700000000)
+            .unwrap();
+        // printf '1700000000\nPOST\n/v1/chat/completions\n{}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            headers,
+            vec![
+                ("X-Timestamp".to_string(), "1700000000".to_string()),
+                (
+                    "X-Signature".to_string(),
+                    "0bf446532e8fe945529ee3e2f68ef473ae758c1351e7bc28c4352069045981e0".to_string()
+                ),
+                ("X-Key-Id".to_string(), "team-a".to_string()),
+            ]
+        );
+
+        let config: HmacConfig = toml::from_str(
+            "algorithm = \"sha512\"\nencoding = \"base64\"\npayload = \"{timestamp}.{body_sha256}\"",
+        )
+        .unwrap();
+        assert_eq!(config.signature_header, "X-Signature");
+        let headers = config
+            .signature_headers("secret", "GET", "/v1/models", b"", 1)
+            .unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(
+            general_purpose::STANDARD
+                .decode(&headers[1].1)
+                .unwrap()
+                .len(),
+            64
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Reranking of vector search results (`--rerank <provider:model>`)
+//!
+//! Embedding similarity ranks texts by how close they are in meaning, not by whether
+//! they answer the query. With a rerank model, `lc similar` and RAG retrieval take more
+//! candidates from the vector database and let the model score each against the query;
+//! the best scored are kept. Rerank models are called through a provider's rerank
+//! endpoint (`/rerank` unless set with `lc providers paths <provider> add --rerank`):
+//!
+//! - Cohere and Jina style: `{"model", "query", "documents", "top_n"}` answered with
+//!   `{"results": [{"index", "relevance_score"}]}`, also served by local cross-encoder
+//!   servers such as `llama-server --reranking` and Infinity
+//! - text-embeddings-inference style, with the provider var `rerank_format = "tei"`:
+//!   `{"query", "texts"}` answered with `[{"index", "score"}]`
+
+use crate::config::Config;
+use crate::provider::RerankRequest;
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::RwLock;
+
+/// Candidates taken from the vector database per requested result
+pub const CANDIDATES_PER_RESULT: usize = 4;
+
+static MODEL: RwLock<Option<String>> = RwLock::new(None);
+
+/// Set `--rerank` for this invocation
+pub fn set(model: Option<String>) {
+    if let Ok(mut current) = MODEL.write() {
+        *current = model.filter(|m| !m.trim().is_empty());
+    }
+}
+
+/// The rerank model (`provider:model` or alias), when results are reranked
+pub fn model() -> Option<String> {
+    MODEL.read().ok()?.clone()
+}
+
+/// Score `candidates` against `query` with the rerank model and keep the best `limit`,
+/// best first, each with its relevance score
+pub async fn rerank<T>(
+    config: &mut Config,
+    rerank_model: &str,
+    query: &str,
+    candidates: Vec<T>,
+    text: impl Fn(&T) -> &str,
+    limit: usize,
+) -> Result<Vec<(T, f64)>> {
+    if candidates.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+    let (provider, model) =
+        crate::utils::resolve_model_and_provider(config, None, Some(rerank_model.to_string()))?;
+    let client = crate::chat::create_authenticated_client(config, &provider).await?;
+    let request = RerankRequest {
+        model: model.clone(),
+        query: query.to_string(),
+        documents: candidates.iter().map(|c| text(c).to_string()).collect(),
+        top_n: Some(limit),
+    };
+    crate::debug_log!(
+        "Rerank: scoring {} candidates with {}:{}",
+        request.documents.len(),
+        provider,
+        model
+    );
+    let scores = client.rerank(&request).await?;
+    Ok(select(candidates, &scores, limit))
+}
+
+/// The `(index, score)` pairs of a rerank response, in either supported format
+pub fn parse_scores(response: &Value) -> Result<Vec<(usize, f64)>> {
+    let results = match response {
+        Value::Array(results) => results,
+        _ => response["results"]
+            .as_array()
+            .or_else(|| response["data"].as_array())
+            .ok_or_else(|| anyhow::anyhow!("Rerank response has no results"))?,
+    };
+    results
+        .iter()
+        .map(|result| {
+            let index = result["index"].as_u64();
+            let score = result["relevance_score"]
+                .as_f64()
+                .or_else(|| result["score"].as_f64());
+            match (index, score) {
+                (Some(index), Some(score)) => Ok((index as usize, score)),
+                _ => anyhow::bail!("Rerank result without index and score: {}", result),
+            }
+        })
+        .collect()
+}
+
+/// The scored candidates, best first, at most `limit`. Scores for unknown indices are
+/// ignored, as are repeated ones.
+fn select<T>(candidates: Vec<T>, scores: &[(usize, f64)], limit: usize) -> Vec<(T, f64)> {
+    let mut slots: Vec<Option<T>> = candidates.into_iter().map(Some).collect();
+    let mut scores = scores.to_vec();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scores
+        .into_iter()
+        .filter_map(|(index, score)| Some((slots.get_mut(index)?.take()?, score)))
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_scores() {
+        let cohere = json!({"results": [
+            {"index": 2, "relevance_score": 0.91},
+            {"index": 0, "relevance_score": 0.12}
+        ]});
+        assert_eq!(parse_scores(&cohere).unwrap(), [(2, 0.91), (0, 0.12)]);
+        let tei = json!([{"index": 1, "score": 0.5}]);
+        assert_eq!(parse_scores(&tei).unwrap(), [(1, 0.5)]);
+        assert!(parse_scores(&json!({"results": [{"index": 1}]})).is_err());
+        assert!(parse_scores(&json!({"error": "bad model"})).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let candidates = vec!["a", "b", "c", "d"];
+        let scores = [(3, 0.2), (1, 0.9), (9, 1.0), (1, 0.8), (0, 0.4)];
+        assert_eq!(
+            select(candidates.clone(), &scores, 2),
+            [("b", 0.9), ("a", 0.4)]
+        );
+        assert_eq!(select(candidates, &scores, 10).len(), 3);
+    }
+}
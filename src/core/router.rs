@@ -0,0 +1,270 @@
+//! Cost-optimizing model routing with `[router]`
+//!
+//! When `[router] cheap_model` is set and a direct prompt doesn't choose its model with
+//! `-m`, the prompt is classified as simple or complex. Simple prompts go to the cheap
+//! model and complex ones to the default model. Heuristics decide (length, code, attached
+//! files and tools, words asking for analysis or code) unless a `classifier_model` is set,
+//! which is asked instead:
+//!
+//! ```toml
+//! [router]
+//! cheap_model = "openai:gpt-4o-mini"
+//! classifier_model = "openai:gpt-4.1-nano"   # optional
+//! max_simple_tokens = 300                     # longer prompts are complex
+//! ```
+//!
+//! The decision is stored with each logged entry in its metadata: `route` (`cheap` or
+//! `premium`), `route_reason`, and for prompts sent to the cheap model the estimated
+//! saving over the default model, `route_saved_usd`, when both models have pricing.
+//! `lc logs show --filter route=cheap` lists the routed prompts. `--no-route` turns the
+//! router off for one invocation.
+
+use crate::config::Config;
+use crate::utils::regex_cache::get_regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Prompts estimated above this many tokens are complex unless configured otherwise
+const DEFAULT_MAX_SIMPLE_TOKENS: u32 = 300;
+
+/// Requests that need a stronger model
+const COMPLEX_WORDS: &str = r"(?i)\b(analy[sz]e|architecture|compare|debug|derive|design|explain why|implement|optimi[sz]e|proof|prove|refactor|review|step by step|trade-?offs?)\b";
+
+/// The `[router]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RouterSettings {
+    /// Model for simple prompts (`provider:model` or alias); the router is off if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cheap_model: Option<String>,
+    /// Model that classifies prompts; heuristics if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifier_model: Option<String>,
+    /// Estimated prompt tokens above which a prompt is complex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_simple_tokens: Option<u32>,
+}
+
+impl RouterSettings {
+    pub fn is_empty(&self) -> bool {
+        self.cheap_model.is_none()
+            && self.classifier_model.is_none()
+            && self.max_simple_tokens.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    Simple,
+    Complex,
+}
+
+impl Tier {
+    /// The `route` value stored in the logs
+    pub fn route(self) -> &'static str {
+        match self {
+            Tier::Simple => "cheap",
+            Tier::Complex => "premium",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    pub tier: Tier,
+    pub reason: String,
+}
+
+impl Classification {
+    pub fn new(tier: Tier, reason: impl Into<String>) -> Self {
+        Self {
+            tier,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// A routing decision and the (input, output) prices per million tokens of the cheap and
+/// default models, when known
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub classification: Classification,
+    pub cheap_prices: Option<(f64, f64)>,
+    pub default_prices: Option<(f64, f64)>,
+}
+
+impl Decision {
+    /// Estimated USD saved by sending a request of this size to the cheap model
+    pub fn saved_usd(&self, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+        if self.classification.tier != Tier::Simple {
+            return None;
+        }
+        let (cheap, default) = self.cheap_prices.zip(self.default_prices)?;
+        Some(
+            crate::core::budget::cost(input_tokens, output_tokens, default)
+                - crate::core::budget::cost(input_tokens, output_tokens, cheap),
+        )
+    }
+}
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn the router off for this invocation (`--no-route`)
+pub fn set_disabled(disabled: bool) {
+    DISABLED.store(disabled, Ordering::Relaxed);
+}
+
+/// The cheap model, when prompts of this invocation are routed
+pub fn cheap_model(config: &Config) -> Option<&str> {
+    if DISABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    config
+        .router
+        .cheap_model
+        .as_deref()
+        .filter(|m| !m.trim().is_empty())
+}
+
+/// Classify a prompt by heuristics. `has_extras` is set when files, images, audio, tools
+/// or retrieved context go with it.
+pub fn classify(prompt: &str, has_extras: bool, settings: &RouterSettings) -> Classification {
+    if has_extras {
+        return Classification::new(Tier::Complex, "files, tools or retrieval");
+    }
+    if prompt.contains("```") {
+        return Classification::new(Tier::Complex, "contains code");
+    }
+    let tokens = estimate_tokens(prompt);
+    let max = settings
+        .max_simple_tokens
+        .unwrap_or(DEFAULT_MAX_SIMPLE_TOKENS) as usize;
+    if tokens > max {
+        return Classification::new(Tier::Complex, format!("long prompt (~{} tokens)", tokens));
+    }
+    if let Some(word) = get_regex(COMPLEX_WORDS)
+        .ok()
+        .and_then(|regex| regex.find(prompt))
+    {
+        return Classification::new(
+            Tier::Complex,
+            format!("asks to {}", word.as_str().trim().to_lowercase()),
+        );
+    }
+    Classification::new(Tier::Simple, format!("short prompt (~{} tokens)", tokens))
+}
+
+/// Rough token count; the router runs before a model is chosen
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// The message asking the classifier model about a prompt
+pub fn classifier_prompt(prompt: &str) -> String {
+    format!(
+        "Decide which model should answer the request below. Reply with the single word \
+         SIMPLE if a small, inexpensive model can answer it well (greetings, short facts, \
+         definitions, simple rewording or conversions), or COMPLEX if it needs careful \
+         reasoning, coding, analysis or long writing.\n\n<request>\n{}\n</request>",
+        prompt.trim()
+    )
+}
+
+/// The tier in a classifier reply
+pub fn parse_classifier_reply(reply: &str) -> Option<Tier> {
+    let word: String = reply
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect();
+    match word.to_uppercase().as_str() {
+        "SIMPLE" => Some(Tier::Simple),
+        "COMPLEX" => Some(Tier::Complex),
+        _ => None,
+    }
+}
+
+/// Routing metadata logged with the answer to a routed prompt
+pub fn log_metadata(
+    decision: &Decision,
+    input_tokens: Option<i32>,
+    output_tokens: Option<i32>,
+) -> BTreeMap<String, String> {
+    let mut metadata = BTreeMap::new();
+    metadata.insert(
+        "route".to_string(),
+        decision.classification.tier.route().to_string(),
+    );
+    metadata.insert(
+        "route_reason".to_string(),
+        decision.classification.reason.clone(),
+    );
+    let tokens = input_tokens
+        .zip(output_tokens)
+        .map(|(input, output)| (input.max(0) as u64, output.max(0) as u64));
+    if let Some(saved) = tokens.and_then(|(input, output)| decision.saved_usd(input, output)) {
+        metadata.insert("route_saved_usd".to_string(), format!("{:.6}", saved));
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let settings = RouterSettings::default();
+        assert_eq!(
+            classify("What is the capital of France?", false, &settings).tier,
+            Tier::Simple
+        );
+        assert_eq!(
+            classify("What is the capital of France?", true, &settings).tier,
+            Tier::Complex
+        );
+        assert_eq!(
+            classify("Refactor this:\n```rust\nfn a() {}\n```", false, &settings).reason,
+            "contains code"
+        );
+        assert_eq!(
+            classify("Compare Postgres and MySQL for analytics", false, &settings).reason,
+            "asks to compare"
+        );
+        assert_eq!(
+            classify(&"word ".repeat(400), false, &settings).tier,
+            Tier::Complex
+        );
+        let settings = RouterSettings {
+            max_simple_tokens: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            classify("Translate 'good morning' to Spanish", false, &settings).tier,
+            Tier::Complex
+        );
+    }
+
+    #[test]
+    fn test_parse_classifier_reply() {
+        assert_eq!(parse_classifier_reply("SIMPLE"), Some(Tier::Simple));
+        assert_eq!(parse_classifier_reply(" complex."), Some(Tier::Complex));
+        assert_eq!(parse_classifier_reply("It depends"), None);
+    }
+
+    #[test]
+    fn test_saved_usd() {
+        let mut decision = Decision {
+            classification: Classification::new(Tier::Simple, "short prompt"),
+            cheap_prices: Some((0.15, 0.6)),
+            default_prices: Some((2.5, 10.0)),
+        };
+        let saved = decision.saved_usd(1_000_000, 1_000_000).unwrap();
+        assert!((saved - 11.75).abs() < 1e-9);
+
+        decision.default_prices = None;
+        assert_eq!(decision.saved_usd(100, 100), None);
+        decision.classification.tier = Tier::Complex;
+        assert_eq!(decision.saved_usd(100, 100), None);
+    }
+}
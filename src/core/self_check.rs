@@ -0,0 +1,147 @@
+//! Self-check pass with `--self-check`
+//!
+//! After the answer is printed, the same model (or the `[self_check]` model) is asked to
+//! verify its factual claims and code. A verdict of no issues prints a one-line note;
+//! otherwise the verifier's corrections are appended under the answer. Templates listed
+//! in `[self_check] templates` turn the pass on without the flag:
+//!
+//! ```toml
+//! [self_check]
+//! model = "openai:gpt-4o-mini"   # verifier, defaults to the answering model
+//! templates = ["code", "facts"]
+//! ```
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// First word of a verifier reply that found nothing to correct
+const VERIFIED: &str = "VERIFIED";
+
+/// The `[self_check]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SelfCheckSettings {
+    /// Verifier model (`provider:model` or alias); the answering model if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Templates whose answers are always checked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub templates: Vec<String>,
+}
+
+impl SelfCheckSettings {
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none() && self.templates.is_empty()
+    }
+}
+
+#[derive(Debug)]
+struct Selection {
+    enabled: bool,
+    model: Option<String>,
+    template: Option<String>,
+}
+
+static CURRENT: RwLock<Selection> = RwLock::new(Selection {
+    enabled: false,
+    model: None,
+    template: None,
+});
+
+/// Turn the pass on for this invocation (`--self-check`, `--self-check-model`)
+pub fn set(enabled: bool, model: Option<String>) {
+    if let Ok(mut current) = CURRENT.write() {
+        current.model = model.filter(|m| !m.trim().is_empty());
+        current.enabled = enabled || current.model.is_some();
+    }
+}
+
+/// Record the template used by this invocation, so `[self_check] templates` applies
+pub fn set_template(template: &str) {
+    if let Ok(mut current) = CURRENT.write() {
+        current.template = Some(template.to_string());
+    }
+}
+
+/// Whether the answer is checked, and by which model (`None`: the answering model)
+pub fn active(config: &Config) -> Option<Option<String>> {
+    let current = CURRENT.read().ok()?;
+    let by_template = current
+        .template
+        .as_ref()
+        .is_some_and(|template| config.self_check.templates.contains(template));
+    if !current.enabled && !by_template {
+        return None;
+    }
+    Some(
+        current
+            .model
+            .clone()
+            .or_else(|| config.self_check.model.clone()),
+    )
+}
+
+/// System prompt for the verifier
+pub fn verifier_system_prompt() -> String {
+    format!(
+        "You review answers written by an AI assistant. Check the factual claims and any \
+         code for errors. If you find nothing wrong, reply with the single word {} \
+         optionally followed by one short sentence. Otherwise list each problem as a bullet \
+         with its correction, most important first, without repeating the parts that are \
+         right. Be brief.",
+        VERIFIED
+    )
+}
+
+/// The message asking the verifier to check `answer` to `question`
+pub fn verification_prompt(question: &str, answer: &str) -> String {
+    format!(
+        "Question:\n<question>\n{}\n</question>\n\nAnswer to check:\n<answer>\n{}\n</answer>",
+        question.trim(),
+        answer.trim()
+    )
+}
+
+/// The verifier's reply without the verdict word, when it found no issues
+pub fn verified_remark(reply: &str) -> Option<&str> {
+    let reply = reply.trim();
+    let rest = reply.strip_prefix(VERIFIED)?;
+    if rest.starts_with(|c: char| c.is_alphanumeric()) {
+        return None;
+    }
+    Some(rest.trim_start_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verified_remark() {
+        assert_eq!(verified_remark("VERIFIED"), Some(""));
+        assert_eq!(
+            verified_remark(" VERIFIED. The dates are correct.\n"),
+            Some("The dates are correct.")
+        );
+        assert_eq!(
+            verified_remark("- The capital is Canberra, not Sydney"),
+            None
+        );
+        assert_eq!(verified_remark("VERIFIEDX"), None);
+
+        let config = Config {
+            self_check: SelfCheckSettings {
+                model: Some("openai:gpt-4o-mini".to_string()),
+                templates: vec!["facts".to_string()],
+            },
+            ..Default::default()
+        };
+        set(false, None);
+        assert_eq!(active(&config), None);
+        set_template("facts");
+        assert_eq!(
+            active(&config),
+            Some(Some("openai:gpt-4o-mini".to_string()))
+        );
+    }
+}
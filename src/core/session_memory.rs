@@ -0,0 +1,177 @@
+//! Vector memory of long chat sessions (`--session-memory <embedding model>`)
+//!
+//! When a conversation outgrows the model's context window, the oldest turns are
+//! dropped from the request. With session memory enabled, those turns are embedded
+//! into a vector database scoped to the session instead of being forgotten, and the
+//! past turns most relevant to the new prompt are recalled into it. Conversations can
+//! then go on indefinitely without losing what was said early on.
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+use crate::database::ChatEntry;
+use crate::provider::EmbeddingRequest;
+use crate::vector_db::VectorDatabase;
+
+/// Context kept free for recalled turns when the history is trimmed
+const RESERVED_TOKENS: u32 = 2_000;
+/// Number of past turns recalled per request
+const RECALLED_TURNS: usize = 3;
+/// Recalled turns longer than this many characters are cut off
+const MAX_TURN_CHARS: usize = 2_000;
+
+static CURRENT: RwLock<Option<String>> = RwLock::new(None);
+
+/// Set the embedding model (`provider:model` or a model alias) used for session memory
+pub fn set(embedding_model: Option<String>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = embedding_model.filter(|m| !m.trim().is_empty());
+    }
+}
+
+/// The embedding model of session memory, if enabled for this invocation
+pub fn current() -> Option<String> {
+    CURRENT.read().ok().and_then(|current| current.clone())
+}
+
+/// The context available to the history, leaving room for recalled turns when enabled
+pub fn available_context(context_limit: u32) -> u32 {
+    if current().is_some() {
+        context_limit
+            .saturating_sub(RESERVED_TOKENS)
+            .max(context_limit / 2)
+    } else {
+        context_limit
+    }
+}
+
+/// Store the turns trimmed from the history in the session's memory and prepend the
+/// past turns most relevant to the prompt. Failures are reported and leave the prompt
+/// unchanged.
+pub async fn recall_into_prompt(prompt: String, trimmed: &[ChatEntry]) -> String {
+    let Some(embedding_model) = current() else {
+        return prompt;
+    };
+    let Some(session_id) = trimmed.first().map(|entry| entry.chat_id.clone()) else {
+        return prompt;
+    };
+
+    match recall(&embedding_model, &session_id, &prompt, trimmed).await {
+        Ok(recalled) if !recalled.is_empty() => {
+            crate::utils::spinner::clear();
+            println!(
+                "{} Recalled {} earlier turns from session memory",
+                sym("🧠"),
+                recalled.len()
+            );
+            with_recalled_turns(&prompt, &recalled)
+        }
+        Ok(_) => prompt,
+        Err(e) => {
+            crate::utils::spinner::clear();
+            eprintln!("Warning: Session memory unavailable: {}", e);
+            prompt
+        }
+    }
+}
+
+async fn recall(
+    embedding_model: &str,
+    session_id: &str,
+    prompt: &str,
+    trimmed: &[ChatEntry],
+) -> Result<Vec<String>> {
+    let memory = VectorDatabase::for_session(session_id)?;
+
+    // Keep using the model the memory was started with, so all vectors are comparable
+    let mut config = crate::config::Config::load()?;
+    let (provider, model) = match memory.get_model_info()? {
+        Some((model, provider)) => (provider, model),
+        None => crate::utils::resolve_model_and_provider(
+            &config,
+            None,
+            Some(embedding_model.to_string()),
+        )?,
+    };
+    let client = crate::chat::create_authenticated_client(&mut config, &provider).await?;
+    let embed = |input: String| {
+        let request = EmbeddingRequest {
+            model: model.clone(),
+            input,
+            encoding_format: Some("float".to_string()),
+        };
+        let client = &client;
+        async move {
+            let response = client.embeddings(&request).await?;
+            response
+                .data
+                .into_iter()
+                .next()
+                .map(|data| data.embedding)
+                .ok_or_else(|| anyhow::anyhow!("No embedding data in response"))
+        }
+    };
+
+    // Turns stay trimmed once the conversation is this long, so store each one only once
+    let stored: HashSet<String> = memory
+        .get_all_vectors()?
+        .into_iter()
+        .map(|entry| entry.text)
+        .collect();
+    for entry in trimmed {
+        let text = turn_text(entry);
+        if stored.contains(&text) {
+            continue;
+        }
+        let vector = embed(text.clone()).await?;
+        memory.add_vector(&text, &vector, &model, &provider)?;
+    }
+
+    let query = embed(prompt.to_string()).await?;
+    Ok(memory
+        .find_similar(&query, RECALLED_TURNS)?
+        .into_iter()
+        .map(|(entry, _)| entry.text)
+        .collect())
+}
+
+/// A conversation turn as stored in memory
+fn turn_text(entry: &ChatEntry) -> String {
+    format!("User: {}\nAssistant: {}", entry.question, entry.response)
+}
+
+fn with_recalled_turns(prompt: &str, recalled: &[String]) -> String {
+    let turns: Vec<String> = recalled
+        .iter()
+        .map(|turn| match turn.char_indices().nth(MAX_TURN_CHARS) {
+            Some((end, _)) => format!("{}...", &turn[..end]),
+            None => turn.clone(),
+        })
+        .collect();
+    format!(
+        "Relevant earlier parts of this conversation:\n\n{}\n\n---\n\n{}",
+        turns.join("\n\n"),
+        prompt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_recalled_turns() {
+        let recalled = vec![
+            "User: my name is Ada\nAssistant: Nice to meet you, Ada.".to_string(),
+            format!("User: {}\nAssistant: ok", "x".repeat(3_000)),
+        ];
+        let prompt = with_recalled_turns("What is my name?", &recalled);
+        assert!(prompt
+            .starts_with("Relevant earlier parts of this conversation:\n\nUser: my name is Ada"));
+        assert!(prompt.ends_with("\n\n---\n\nWhat is my name?"));
+        assert!(prompt.contains(&format!("{}...", "x".repeat(MAX_TURN_CHARS - 6))));
+        assert!(!prompt.contains("Assistant: ok"));
+    }
+}
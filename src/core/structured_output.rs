@@ -0,0 +1,457 @@
+//! JSON-schema structured output (`lc extract`, `--schema`)
+//!
+//! The main request of an invocation (not the auxiliary ones such as routing, search
+//! query rewriting or self-checks) is sent inside [`with_format`], and carries an
+//! OpenAI-style `response_format` of type `json_schema`, so providers that support it
+//! constrain the answer to the schema. Providers on the Anthropic Messages API, and
+//! those with the `structured_output = "tool"` var, are instead given a single tool
+//! whose input is the schema and forced to call it, unless the request carries tools of
+//! its own; the tool's arguments are the answer. Replies are still parsed and checked
+//! locally with [`validate`], which covers the subset of JSON Schema used to describe
+//! records: `type`, `properties`, `required`, `additionalProperties: false`, `items`,
+//! `enum`, `const`, the `minimum`/`maximum` bounds and the
+//! `minItems`/`maxItems`/`minLength`/`maxLength` lengths. Other keywords are accepted
+//! and ignored.
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::RwLock;
+
+/// Name of the tool the model is forced to call when structured output goes through tools
+pub const ANSWER_TOOL: &str = "json_answer";
+
+static REQUESTED: RwLock<Option<Value>> = RwLock::new(None);
+
+tokio::task_local! {
+    static SCOPED: Option<Value>;
+}
+
+/// Set the `response_format` asked for with `--schema` (`None` to clear it)
+pub fn set(response_format: Option<Value>) {
+    if let Ok(mut requested) = REQUESTED.write() {
+        *requested = response_format;
+    }
+}
+
+/// The `response_format` asked for with `--schema`
+pub fn requested() -> Option<Value> {
+    REQUESTED
+        .read()
+        .ok()
+        .and_then(|requested| requested.clone())
+}
+
+/// The schema asked for with `--schema`
+pub fn requested_schema() -> Option<Value> {
+    requested().and_then(|format| schema_of(&format))
+}
+
+/// Send the chat requests of `request` with `response_format`; requests sent outside
+/// of it carry none
+pub async fn with_format<F: Future>(response_format: Option<Value>, request: F) -> F::Output {
+    SCOPED.scope(response_format, request).await
+}
+
+/// The `response_format` of the requests being sent
+pub fn current() -> Option<Value> {
+    SCOPED.try_with(Option::clone).ok().flatten()
+}
+
+/// The schema of the `response_format` of the requests being sent
+pub fn schema() -> Option<Value> {
+    current().and_then(|format| schema_of(&format))
+}
+
+fn schema_of(format: &Value) -> Option<Value> {
+    format["json_schema"].get("schema").cloned()
+}
+
+/// The schema given to `--schema`: inline JSON (starting with `{`) or the path of a JSON file
+pub fn load_schema(arg: &str) -> Result<Value> {
+    let text = if arg.trim_start().starts_with('{') {
+        arg.to_string()
+    } else {
+        std::fs::read_to_string(arg)
+            .map_err(|e| anyhow!("Failed to read schema file '{}': {}", arg, e))?
+    };
+    let schema: Value = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("The schema in '{}' is not valid JSON: {}", arg, e))?;
+    if !schema.is_object() {
+        return Err(anyhow!("The schema in '{}' must be a JSON object", arg));
+    }
+    Ok(schema)
+}
+
+/// Constrain this invocation's answers to the schema given to `--schema`
+pub fn set_schema(arg: &str) -> Result<()> {
+    let schema = load_schema(arg)?;
+    set(Some(response_format("response", &schema)));
+    Ok(())
+}
+
+/// Whether a provider takes structured output as a forced tool call rather than a
+/// `response_format` (the `structured_output = "tool"` provider var)
+pub fn uses_tool(vars: Option<&HashMap<String, String>>) -> bool {
+    vars.and_then(|vars| vars.get("structured_output"))
+        .is_some_and(|mode| mode.eq_ignore_ascii_case("tool"))
+}
+
+/// The OpenAI-style tool taking `schema` as its arguments, and the `tool_choice` forcing it
+pub fn forced_tool(schema: &Value) -> (Value, Value) {
+    (
+        json!({
+            "type": "function",
+            "function": {
+                "name": ANSWER_TOOL,
+                "description": "Give the answer as arguments matching the schema",
+                "parameters": schema,
+            }
+        }),
+        json!({"type": "function", "function": {"name": ANSWER_TOOL}}),
+    )
+}
+
+/// The Messages API tool taking `schema` as its input, and the `tool_choice` forcing it
+pub fn forced_tool_messages_api(schema: &Value) -> (Value, Value) {
+    (
+        json!({
+            "name": ANSWER_TOOL,
+            "description": "Give the answer as input matching the schema",
+            "input_schema": schema,
+        }),
+        json!({"type": "tool", "name": ANSWER_TOOL}),
+    )
+}
+
+/// The answer in a response to a forced [`ANSWER_TOOL`] call, as JSON text
+pub fn forced_tool_answer(response: &Value) -> Option<String> {
+    // OpenAI: arguments are a JSON string
+    let calls = response["choices"][0]["message"]["tool_calls"].as_array();
+    if let Some(call) = calls
+        .into_iter()
+        .flatten()
+        .find(|call| call["function"]["name"] == ANSWER_TOOL)
+    {
+        return match &call["function"]["arguments"] {
+            Value::String(arguments) => Some(arguments.clone()),
+            arguments => Some(arguments.to_string()),
+        };
+    }
+    // Messages API: input is an object
+    response["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|block| block["type"] == "tool_use" && block["name"] == ANSWER_TOOL)
+        .map(|block| block["input"].to_string())
+}
+
+/// The follow-up asking the model to fix a reply that didn't match `schema`
+pub fn repair_prompt(schema: &Value, errors: &[String]) -> String {
+    let mut prompt =
+        String::from("Your reply does not match the required JSON schema. Problems found:\n");
+    for error in errors {
+        prompt.push_str(&format!("- {}\n", error));
+    }
+    prompt.push_str(&format!(
+        "\nThe schema:\n{}\n\nReply with the corrected JSON only.",
+        serde_json::to_string_pretty(schema).unwrap_or_default()
+    ));
+    prompt
+}
+
+/// The JSON in `reply` if it matches `schema`, or the problems found
+pub fn check_reply(reply: &str, schema: &Value) -> std::result::Result<Value, Vec<String>> {
+    let value = parse_reply(reply).map_err(|e| vec![e.to_string()])?;
+    let errors = validate(&value, schema);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The `response_format` asking for JSON that matches `schema`
+pub fn response_format(name: &str, schema: &Value) -> Value {
+    // Providers only accept [a-zA-Z0-9_-] in the schema name
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect();
+    json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": if name.is_empty() { "record".to_string() } else { name },
+            "schema": schema,
+        }
+    })
+}
+
+/// The JSON value in a reply, tolerating code fences and text around it
+pub fn parse_reply(reply: &str) -> Result<Value> {
+    let reply = reply.trim();
+    if let Ok(value) = serde_json::from_str(reply) {
+        return Ok(value);
+    }
+    let start = reply
+        .find(['{', '['])
+        .ok_or_else(|| anyhow!("the reply contains no JSON"))?;
+    let close = if reply[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = reply
+        .rfind(close)
+        .filter(|end| *end > start)
+        .ok_or_else(|| anyhow!("the reply contains no complete JSON value"))?;
+    serde_json::from_str(&reply[start..=end])
+        .map_err(|e| anyhow!("the reply is not valid JSON: {}", e))
+}
+
+/// Where `value` does not match `schema`, one message per problem (empty if it matches)
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(value, schema, "$", &mut errors);
+    errors
+}
+
+fn type_matches(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn check(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|name| type_matches(value, name)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                path,
+                names.join(" or "),
+                value
+            ));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!(
+                "{}: {} is not one of {}",
+                path,
+                value,
+                Value::Array(options.clone())
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            errors.push(format!("{}: expected {}, got {}", path, constant, value));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                errors.push(format!("{}: {} is less than {}", path, number, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                errors.push(format!("{}: {} is greater than {}", path, number, maximum));
+            }
+        }
+    }
+
+    let length = |key: &str| schema.get(key).and_then(Value::as_u64).map(|n| n as usize);
+    if let Some(text) = value.as_str() {
+        let chars = text.chars().count();
+        if length("minLength").is_some_and(|min| chars < min) {
+            errors.push(format!(
+                "{}: string is shorter than {} characters",
+                path,
+                length("minLength").unwrap_or(0)
+            ));
+        }
+        if length("maxLength").is_some_and(|max| chars > max) {
+            errors.push(format!(
+                "{}: string is longer than {} characters",
+                path,
+                length("maxLength").unwrap_or(0)
+            ));
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        if length("minItems").is_some_and(|min| items.len() < min) {
+            errors.push(format!(
+                "{}: fewer than {} items",
+                path,
+                length("minItems").unwrap_or(0)
+            ));
+        }
+        if length("maxItems").is_some_and(|max| items.len() > max) {
+            errors.push(format!(
+                "{}: more than {} items",
+                path,
+                length("maxItems").unwrap_or(0)
+            ));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                check(item, item_schema, &format!("{}[{}]", path, index), errors);
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    errors.push(format!("{}: missing required property '{}'", path, key));
+                }
+            }
+        }
+        for (key, field) in object {
+            let field_path = format!("{}.{}", path, key);
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(field_schema) => check(field, field_schema, &field_path, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        errors.push(format!("{}: property is not allowed", field_path))
+                    }
+                    Some(extra @ Value::Object(_)) => check(field, extra, &field_path, errors),
+                    _ => {}
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "age": {"type": "integer", "minimum": 0},
+                "role": {"enum": ["engineer", "manager"]},
+                "skills": {"type": "array", "items": {"type": "string"}, "maxItems": 3}
+            },
+            "required": ["name", "age"],
+            "additionalProperties": false
+        });
+
+        let person = json!({"name": "Ada", "age": 36, "skills": ["math"]});
+        assert!(validate(&person, &schema).is_empty());
+
+        let wrong = json!({"name": "", "age": 1.5, "role": "intern", "skills": ["a", 2], "x": 1});
+        let errors = validate(&wrong, &schema);
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors.contains(&"$.x: property is not allowed".to_string()));
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("$.skills[1]: expected string")));
+
+        let missing = validate(&json!({"name": "Ada"}), &schema);
+        assert_eq!(missing, vec!["$: missing required property 'age'"]);
+        assert_eq!(validate(&json!([]), &schema).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(parse_reply(" {\"a\": 1} ").unwrap(), json!({"a": 1}));
+        assert_eq!(
+            parse_reply("Here it is:\n```json\n{\"a\": [1, 2]}\n```").unwrap(),
+            json!({"a": [1, 2]})
+        );
+        assert_eq!(parse_reply("```\n[1]\n```").unwrap(), json!([1]));
+        assert!(parse_reply("no json").is_err());
+
+        let format = response_format("person.schema", &json!({"type": "object"}));
+        assert_eq!(format["json_schema"]["name"], "person_schema");
+        assert_eq!(format["type"], "json_schema");
+    }
+
+    #[test]
+    fn test_forced_tool_answer() {
+        let openai = json!({"choices": [{"message": {"tool_calls": [
+            {"function": {"name": ANSWER_TOOL, "arguments": "{\"a\":1}"}}
+        ]}}]});
+        assert_eq!(forced_tool_answer(&openai).unwrap(), "{\"a\":1}");
+
+        let messages_api = json!({"content": [
+            {"type": "text", "text": "Sure"},
+            {"type": "tool_use", "name": ANSWER_TOOL, "input": {"a": 1}}
+        ]});
+        assert_eq!(forced_tool_answer(&messages_api).unwrap(), "{\"a\":1}");
+
+        let other = json!({"content": [{"type": "tool_use", "name": "search", "input": {}}]});
+        assert!(forced_tool_answer(&other).is_none());
+        assert!(
+            forced_tool_answer(&json!({"choices": [{"message": {"content": "hi"}}]})).is_none()
+        );
+    }
+
+    #[test]
+    fn test_load_and_check() {
+        let schema = load_schema(r#"{"type": "object", "required": ["a"]}"#).unwrap();
+        assert!(load_schema("[1]").is_err());
+        assert!(load_schema("/nonexistent/schema.json").is_err());
+
+        assert_eq!(
+            check_reply("```json\n{\"a\": 2}\n```", &schema).unwrap(),
+            json!({"a": 2})
+        );
+        assert_eq!(
+            check_reply("{}", &schema).unwrap_err(),
+            vec!["$: missing required property 'a'"]
+        );
+        assert!(check_reply("nope", &schema).is_err());
+
+        let vars = HashMap::from([("structured_output".to_string(), "tool".to_string())]);
+        assert!(uses_tool(Some(&vars)));
+        assert!(!uses_tool(None));
+    }
+
+    #[tokio::test]
+    async fn test_format_only_inside_scope() {
+        let format = response_format("response", &json!({"type": "object"}));
+        assert_eq!(current(), None);
+        let inside = with_format(Some(format.clone()), async { (current(), schema()) }).await;
+        assert_eq!(inside, (Some(format), Some(json!({"type": "object"}))));
+        assert_eq!(current(), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Style profiles (`lc styles`, `--style`)
+//!
+//! A style profile collects response rules: tone, language, formatting rules and banned
+//! phrases. The active profile is appended to the system prompt of every request as one
+//! instruction block. It is, in order of precedence, the one chosen with `--style`, the
+//! one bound to the template in use, or the configured default.
+//!
+//! Profiles with `check_language` also check the language of the answer to direct
+//! prompts and ask the model once more when it does not match.
+
+use crate::config::Config;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// `--style` value that turns styles off for one invocation
+pub const NONE: &str = "none";
+
+/// Answers shorter than this are not checked; detection is unreliable on a few words
+const MIN_CHECKED_CHARS: usize = 40;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct StyleProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formatting: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banned_phrases: Vec<String>,
+    /// Check the answer's language and ask again once when it differs
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub check_language: bool,
+}
+
+/// The `[styles]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct StyleSettings {
+    /// Profile used when neither `--style` nor the template picks one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, StyleProfile>,
+    /// template name -> profile name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub templates: HashMap<String, String>,
+}
+
+impl StyleSettings {
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.profiles.is_empty() && self.templates.is_empty()
+    }
+}
+
+impl StyleProfile {
+    /// The profile as an instruction block for the system prompt
+    pub fn instructions(&self) -> String {
+        let mut lines = vec!["Follow this response style:".to_string()];
+        if let Some(tone) = &self.tone {
+            lines.push(format!("- Tone: {}.", tone.trim_end_matches('.')));
+        }
+        if let Some(language) = &self.language {
+            lines.push(format!(
+                "- Always answer in {}, whatever the language of the question.",
+                language
+            ));
+        }
+        for rule in &self.formatting {
+            lines.push(format!("- {}", rule));
+        }
+        if !self.banned_phrases.is_empty() {
+            let phrases: Vec<String> = self
+                .banned_phrases
+                .iter()
+                .map(|phrase| format!("\"{}\"", phrase))
+                .collect();
+            lines.push(format!(
+                "- Never use these phrases: {}.",
+                phrases.join(", ")
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// The detected language of `answer` when it is reliably not the profile's language.
+    /// Code blocks are ignored, and so are languages the detector does not know.
+    pub fn language_mismatch(&self, answer: &str) -> Option<&'static str> {
+        let expected = parse_language(self.language.as_deref()?)?;
+        let prose = strip_code_blocks(answer);
+        if prose.trim().chars().count() < MIN_CHECKED_CHARS {
+            return None;
+        }
+        let detected = whatlang::detect(&prose)?;
+        (detected.is_reliable() && detected.lang() != expected).then(|| detected.lang().eng_name())
+    }
+}
+
+/// Follow-up message asking for the answer again in the profile's language
+pub fn language_retry_prompt(expected: &str, detected: &str) -> String {
+    format!(
+        "Your answer was written in {}. Please give the same answer again, written entirely in {}.",
+        detected, expected
+    )
+}
+
+/// A language given by English name ("German"), native name ("Deutsch") or ISO 639-3
+/// code ("deu")
+fn parse_language(name: &str) -> Option<whatlang::Lang> {
+    let name = name.trim().to_lowercase();
+    whatlang::Lang::from_code(name.as_str()).or_else(|| {
+        whatlang::Lang::all().iter().copied().find(|lang| {
+            lang.eng_name().to_lowercase() == name || lang.name().to_lowercase() == name
+        })
+    })
+}
+
+fn strip_code_blocks(text: &str) -> String {
+    let mut in_code = false;
+    text.lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                return false;
+            }
+            !in_code
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug)]
+struct Selection {
+    style: Option<String>,
+    template: Option<String>,
+}
+
+static CURRENT: RwLock<Selection> = RwLock::new(Selection {
+    style: None,
+    template: None,
+});
+
+/// Set the profile chosen with `--style` for this invocation
+pub fn set(style: Option<String>) {
+    if let Ok(mut current) = CURRENT.write() {
+        current.style = style.filter(|s| !s.trim().is_empty());
+    }
+}
+
+/// Record the template used by this invocation, so its bound profile applies
+pub fn set_template(template: &str) {
+    if let Ok(mut current) = CURRENT.write() {
+        current.template = Some(template.to_string());
+    }
+}
+
+/// The active profile, if any
+pub fn active(config: &Config) -> Result<Option<StyleProfile>> {
+    let (style, template) = match CURRENT.read() {
+        Ok(current) => (current.style.clone(), current.template.clone()),
+        Err(_) => (None, None),
+    };
+    let settings = &config.styles;
+
+    let name = match style {
+        Some(style) if style == NONE => return Ok(None),
+        Some(style) => {
+            if !settings.profiles.contains_key(&style) {
+                anyhow::bail!(
+                    "Style '{}' not found. Create it with 'lc styles set {}'",
+                    style,
+                    style
+                );
+            }
+            style
+        }
+        None => match template
+            .and_then(|template| settings.templates.get(&template))
+            .or(settings.default.as_ref())
+        {
+            Some(name) => name.clone(),
+            None => return Ok(None),
+        },
+    };
+
+    match settings.profiles.get(&name) {
+        Some(profile) => {
+            crate::debug_log!("Applying style '{}'", name);
+            Ok(Some(profile.clone()))
+        }
+        None => {
+            crate::debug_log!("Style '{}' is configured but not defined, ignoring", name);
+            Ok(None)
+        }
+    }
+}
+
+/// `system_prompt` with the profile's instructions appended
+pub fn compose(system_prompt: Option<String>, profile: Option<&StyleProfile>) -> Option<String> {
+    let Some(profile) = profile else {
+        return system_prompt;
+    };
+    Some(match system_prompt {
+        Some(prompt) if !prompt.trim().is_empty() => {
+            format!("{}\n\n{}", prompt.trim_end(), profile.instructions())
+        }
+        _ => profile.instructions(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instructions_and_language_check() {
+        let profile = StyleProfile {
+            tone: Some("friendly, concise".to_string()),
+            language: Some("German".to_string()),
+            formatting: vec!["Use bullet lists for steps".to_string()],
+            banned_phrases: vec!["delve".to_string(), "As an AI".to_string()],
+            check_language: true,
+        };
+        assert_eq!(
+            compose(Some("You are a reviewer.".to_string()), Some(&profile)).unwrap(),
+            "You are a reviewer.\n\nFollow this response style:\n\
+             - Tone: friendly, concise.\n\
+             - Always answer in German, whatever the language of the question.\n\
+             - Use bullet lists for steps\n\
+             - Never use these phrases: \"delve\", \"As an AI\"."
+        );
+
+        let english = "The configuration file is loaded once at startup, and every \
+                       command reads its settings from there before doing anything else.";
+        let german = "Die Konfigurationsdatei wird beim Start einmal geladen, und jeder \
+                      Befehl liest seine Einstellungen von dort, bevor er etwas anderes tut.";
+        assert_eq!(profile.language_mismatch(english), Some("English"));
+        assert_eq!(profile.language_mismatch(german), None);
+        assert_eq!(
+            profile.language_mismatch(&format!("{}\n```rust\n{}\n```", german, english)),
+            None
+        );
+
+        assert_eq!(parse_language("deutsch"), Some(whatlang::Lang::Deu));
+        assert_eq!(parse_language("fra"), Some(whatlang::Lang::Fra));
+        assert_eq!(parse_language("Klingon"), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Long prompts loaded from files (`--system-file`, `lc templates add --file`)
+//!
+//! Agent-style system prompts are often thousands of tokens, too long to pass with `-s`.
+//! They are read from the file on every request, so edits apply right away. The token
+//! count is printed when a file is first used and whenever it changes: providers with
+//! prompt caching (OpenAI, Anthropic, Gemini) reuse a long unchanged prefix across
+//! requests, and an edit means the next request pays for the whole prompt again.
+
+use crate::utils::terminal::sym;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Shortest prefix, in tokens, that providers cache (1024 for OpenAI and Anthropic)
+pub const MIN_CACHEABLE_TOKENS: usize = 1024;
+
+/// File remembering the content hash of each prompt file, to notice edits
+const SEEN_FILE: &str = "prompt_files.json";
+
+/// How the prompt file compares to the last time it was used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    New,
+    Changed,
+    Unchanged,
+}
+
+/// Read a prompt file, printing its token count when it is new or was edited
+pub fn load(path: &str, model: Option<&str>) -> Result<String> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read prompt file '{}'", path))?;
+    let content = content.trim_end().to_string();
+    if content.trim().is_empty() {
+        anyhow::bail!("Prompt file '{}' is empty", path);
+    }
+
+    let tokens = count_tokens(&content, model);
+    match remember(path, &content) {
+        Change::Unchanged => {
+            crate::debug_log!("Prompt file '{}' unchanged: {} tokens", path, tokens)
+        }
+        change => eprintln!("{} {}", sym("📄").blue(), describe(path, tokens, change)),
+    }
+    Ok(content)
+}
+
+/// Tokens in `content` for `model`, estimated at four characters per token when the
+/// model has no tokenizer
+pub fn count_tokens(content: &str, model: Option<&str>) -> usize {
+    match crate::token_utils::TokenCounter::new(model.unwrap_or("gpt-4o")) {
+        Ok(counter) => counter.count_tokens(content),
+        Err(_) => content.chars().count().div_ceil(4),
+    }
+}
+
+/// The note printed for a new or edited prompt file
+pub fn describe(path: &str, tokens: usize, change: Change) -> String {
+    let name = Path::new(path)
+        .file_name()
+        .map_or(path.into(), |name| name.to_string_lossy());
+    let mut note = format!("{}: {} tokens", name, tokens);
+    if tokens >= MIN_CACHEABLE_TOKENS {
+        note.push_str(match change {
+            Change::Changed => {
+                " (edited: providers with prompt caching start a new cache on the next request)"
+            }
+            _ => " (long enough for provider prompt caching; keep it unchanged between requests to reuse the cache)",
+        });
+    }
+    note
+}
+
+/// Record the hash of the file's content, returning how it compares to the last use
+fn remember(path: &str, content: &str) -> Change {
+    use sha2::{Digest, Sha256};
+
+    let key = std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+    let Ok(seen_path) = crate::config::Config::config_dir().map(|dir| dir.join(SEEN_FILE)) else {
+        return Change::New;
+    };
+
+    let mut seen: HashMap<String, String> = std::fs::read_to_string(&seen_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    let change = match seen.get(&key) {
+        Some(previous) if *previous == hash => return Change::Unchanged,
+        Some(_) => Change::Changed,
+        None => Change::New,
+    };
+    if !crate::lock::is_read_only() {
+        seen.insert(key, hash);
+        if let Ok(text) = serde_json::to_string_pretty(&seen) {
+            if let Err(e) = crate::lock::write_config_file(&seen_path, &text) {
+                crate::debug_log!("Could not record prompt file hash: {}", e);
+            }
+        }
+    }
+    change
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe("/tmp/agent.md", 12, Change::New),
+            "agent.md: 12 tokens"
+        );
+        assert!(describe("agent.md", 2000, Change::New).contains("keep it unchanged"));
+        assert!(describe("agent.md", 2000, Change::Changed).contains("new cache"));
+        assert_eq!(count_tokens("", None), 0);
+        assert!(count_tokens("You are a careful reviewer.", Some("gpt-4o")) > 3);
+    }
+}
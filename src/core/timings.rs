@@ -0,0 +1,201 @@
+//! Per-request timing measurements for `--timings`
+//!
+//! The provider client records phase timings for each chat request; the chat layer
+//! prints a report once output tokens are known and stores it in the usage database
+//! so `lc usage latency` can chart provider performance over time.
+
+use crate::database::{Database, RequestTimingEntry};
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Duration of the last DNS lookup made by a [`TimingResolver`]
+static LAST_LOOKUP: Mutex<Option<Duration>> = Mutex::new(None);
+
+/// Check whether `--timings` is enabled for this invocation
+pub fn is_enabled() -> bool {
+    crate::TIMINGS_MODE.load(Ordering::Relaxed)
+}
+
+/// Timing breakdown for a single chat request
+#[derive(Debug, Clone, Default)]
+pub struct RequestTimings {
+    /// DNS resolution of the provider host, when the request opened a new connection
+    pub dns: Option<Duration>,
+    /// Time until response headers arrived
+    pub ttfb: Option<Duration>,
+    /// Time until the first streamed token was printed
+    pub first_token: Option<Duration>,
+    /// Total request duration
+    pub total: Duration,
+    /// Number of retries (e.g. stream resumes)
+    pub retries: u32,
+}
+
+impl RequestTimings {
+    /// Output tokens per second, measured from the first token (or headers) to the end
+    pub fn tokens_per_sec(&self, output_tokens: Option<i32>) -> Option<f64> {
+        let tokens = output_tokens.filter(|t| *t > 0)? as f64;
+        let start = self.first_token.or(self.ttfb).unwrap_or_default();
+        let generation = self.total.checked_sub(start)?.as_secs_f64();
+        if generation <= 0.0 {
+            return None;
+        }
+        Some(tokens / generation)
+    }
+}
+
+/// Tracks the phases of a request while it is in flight
+pub struct TimingRecorder {
+    start: Instant,
+    timings: RequestTimings,
+}
+
+impl TimingRecorder {
+    /// Start timing a request
+    pub fn start() -> Self {
+        take_lookup();
+        Self {
+            start: Instant::now(),
+            timings: RequestTimings::default(),
+        }
+    }
+
+    pub fn mark_headers(&mut self) {
+        self.timings.ttfb = Some(self.start.elapsed());
+        self.timings.dns = take_lookup();
+    }
+
+    pub fn mark_first_token(&mut self) {
+        if self.timings.first_token.is_none() {
+            self.timings.first_token = Some(self.start.elapsed());
+        }
+    }
+
+    pub fn finish(mut self) -> RequestTimings {
+        self.timings.total = self.start.elapsed();
+        self.timings
+    }
+}
+
+fn take_lookup() -> Option<Duration> {
+    LAST_LOOKUP.lock().ok()?.take()
+}
+
+/// DNS resolver for the HTTP clients that records how long each lookup took
+///
+/// reqwest doesn't expose connection phases, so the lookup made for the request
+/// itself is timed here. Requests that reuse a pooled connection make no lookup.
+pub struct TimingResolver;
+
+impl reqwest::dns::Resolve for TimingResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let lookup_start = Instant::now();
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if let Ok(mut last) = LAST_LOOKUP.lock() {
+                *last = Some(lookup_start.elapsed());
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Print the timing report for a finished request and store it in the usage database
+pub fn report(
+    provider: &str,
+    model: &str,
+    timings: &RequestTimings,
+    output_tokens: Option<i32>,
+) -> Result<()> {
+    let fmt = |d: Option<Duration>| {
+        d.map(|d| format!("{}ms", d.as_millis()))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    eprintln!("\n{}", sym("⏱️  Timings").bold().blue());
+    eprintln!("  {} {}", "DNS:".bold(), fmt(timings.dns));
+    eprintln!(
+        "  {} {}  {} {}",
+        "TTFB:".bold(),
+        fmt(timings.ttfb),
+        "First token:".bold(),
+        fmt(timings.first_token)
+    );
+    eprintln!(
+        "  {} {}  {} {}  {} {}",
+        "Total:".bold(),
+        fmt(Some(timings.total)).green(),
+        "Tokens/sec:".bold(),
+        timings
+            .tokens_per_sec(output_tokens)
+            .map(|t| format!("{:.1}", t))
+            .unwrap_or_else(|| "-".to_string())
+            .yellow(),
+        "Retries:".bold(),
+        timings.retries
+    );
+
+    let db = Database::new()?;
+    db.save_request_timing(&RequestTimingEntry {
+        provider: provider.to_string(),
+        model: model.to_string(),
+        timestamp: chrono::Utc::now(),
+        dns_ms: timings.dns.map(|d| d.as_millis() as i64),
+        ttfb_ms: timings.ttfb.map(|d| d.as_millis() as i64),
+        first_token_ms: timings.first_token.map(|d| d.as_millis() as i64),
+        total_ms: timings.total.as_millis() as i64,
+        output_tokens,
+        retries: timings.retries as i32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_per_sec_from_first_token() {
+        let timings = RequestTimings {
+            ttfb: Some(Duration::from_millis(200)),
+            first_token: Some(Duration::from_millis(500)),
+            total: Duration::from_millis(2500),
+            ..Default::default()
+        };
+
+        let tps = timings.tokens_per_sec(Some(100)).unwrap();
+        assert!((tps - 50.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_tokens_per_sec_without_tokens() {
+        let timings = RequestTimings {
+            total: Duration::from_secs(1),
+            ..Default::default()
+        };
+
+        assert!(timings.tokens_per_sec(None).is_none());
+        assert!(timings.tokens_per_sec(Some(0)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_records_lookup() {
+        use reqwest::dns::Resolve;
+        use std::str::FromStr;
+
+        let recorder = TimingRecorder::start();
+        let name = hyper::client::connect::dns::Name::from_str("localhost").unwrap();
+        let addrs: Vec<_> = TimingResolver.resolve(name).await.unwrap().collect();
+        assert!(!addrs.is_empty());
+
+        let mut recorder = recorder;
+        recorder.mark_headers();
+        assert!(recorder.finish().dns.is_some());
+    }
+}
@@ -0,0 +1,209 @@
+//! Session-scoped cache for tool results
+//!
+//! Identical tool calls (same server, tool and arguments) are answered from the cache
+//! instead of running the tool again. Results are kept in memory for the current
+//! request and in `logs.db` for the rest of the chat session; the cache is dropped
+//! when a new session starts and can be cleared with `lc mcp clear-cache`.
+//!
+//! Every call is also recorded in the session's tool call log for the session summary.
+
+use crate::database::Database;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Tool result cache for the current chat session
+pub struct ToolCache {
+    memory: Mutex<HashMap<String, String>>,
+    /// Read-only tools whose results may be cached; every other tool is always executed
+    cacheable: HashSet<String>,
+    /// Persistent store, when the logs database and a session are available
+    session: Option<(Database, String)>,
+}
+
+impl ToolCache {
+    /// Cache backed by the current session in `logs.db` (memory-only without one)
+    pub fn for_current_session() -> Self {
+        let session = Database::new().ok().and_then(|db| {
+            let session_id = db.current_or_new_session_id().ok()?;
+            Some((db, session_id))
+        });
+        if session.is_none() {
+            crate::debug_log!("No chat session, tool results are only cached for this request");
+        }
+        Self {
+            memory: Mutex::new(HashMap::new()),
+            cacheable: HashSet::new(),
+            session,
+        }
+    }
+
+    /// In-memory cache only (used by tests)
+    pub fn in_memory() -> Self {
+        Self {
+            memory: Mutex::new(HashMap::new()),
+            cacheable: HashSet::new(),
+            session: None,
+        }
+    }
+
+    /// Cache results of `tool`
+    pub fn allow(&mut self, tool: &str) {
+        self.cacheable.insert(tool.to_string());
+    }
+
+    pub fn get(&self, server: &str, tool: &str, arguments: &serde_json::Value) -> Option<String> {
+        if !self.cacheable.contains(tool) {
+            return None;
+        }
+        let key = cache_key(server, tool, arguments);
+        if let Some(result) = self.memory.lock().ok()?.get(&key) {
+            return Some(result.clone());
+        }
+
+        let (db, session_id) = self.session.as_ref()?;
+        match db.get_cached_tool_result(session_id, &key) {
+            Ok(Some(result)) => {
+                if let Ok(mut memory) = self.memory.lock() {
+                    memory.insert(key, result.clone());
+                }
+                Some(result)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                crate::debug_log!("Tool cache lookup failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Record a tool call in the session's log
+    pub fn record_call(&self, server: &str, tool: &str, cached: bool) {
+        if let Some((db, session_id)) = &self.session {
+            if let Err(e) = db.save_tool_call(session_id, server, tool, cached) {
+                crate::debug_log!("Failed to record tool call: {}", e);
+            }
+        }
+    }
+
+    pub fn put(&self, server: &str, tool: &str, arguments: &serde_json::Value, result: &str) {
+        if !self.cacheable.contains(tool) {
+            return;
+        }
+        let key = cache_key(server, tool, arguments);
+        if let Some((db, session_id)) = &self.session {
+            if let Err(e) = db.save_tool_result(
+                session_id,
+                &key,
+                server,
+                tool,
+                &arguments.to_string(),
+                result,
+            ) {
+                crate::debug_log!("Failed to persist tool result: {}", e);
+            }
+        }
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.insert(key, result.to_string());
+        }
+    }
+}
+
+/// Whether results of an MCP tool may be cached.
+///
+/// Only tools the server annotates as read-only are cached; any other tool may change
+/// state, so its calls are always executed.
+pub fn is_cacheable(tool: &rmcp::model::Tool) -> bool {
+    tool.annotations
+        .as_ref()
+        .is_some_and(|annotations| annotations.read_only_hint == Some(true))
+}
+
+/// Stable key for a tool call. serde_json objects are sorted maps, so argument
+/// order doesn't matter.
+pub fn cache_key(server: &str, tool: &str, arguments: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(server.as_bytes());
+    hasher.update([0]);
+    hasher.update(tool.as_bytes());
+    hasher.update([0]);
+    hasher.update(arguments.to_string().as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cache_key_ignores_argument_order() {
+        let a = cache_key(
+            "fs",
+            "read_file",
+            &json!({"path": "a.txt", "encoding": "utf8"}),
+        );
+        let b = cache_key(
+            "fs",
+            "read_file",
+            &json!({"encoding": "utf8", "path": "a.txt"}),
+        );
+        assert_eq!(a, b);
+        assert_ne!(a, cache_key("fs", "read_file", &json!({"path": "b.txt"})));
+        assert_ne!(
+            a,
+            cache_key("other", "read_file", &json!({"path": "a.txt"}))
+        );
+    }
+
+    #[test]
+    fn test_in_memory_cache() {
+        let mut cache = ToolCache::in_memory();
+        cache.allow("fetch");
+        let args = json!({"url": "https://example.com"});
+        assert!(cache.get("fetch", "fetch", &args).is_none());
+
+        cache.put("fetch", "fetch", &args, "page content");
+        assert_eq!(
+            cache.get("fetch", "fetch", &args).as_deref(),
+            Some("page content")
+        );
+        assert!(cache.get("fetch", "fetch", &json!({})).is_none());
+    }
+
+    #[test]
+    fn test_only_allowed_tools_are_cached() {
+        let mut cache = ToolCache::in_memory();
+        cache.allow("read_file");
+        let args = json!({"path": "a.txt"});
+        cache.put("fs", "write_file", &args, "ok");
+        assert!(cache.get("fs", "write_file", &args).is_none());
+    }
+
+    #[test]
+    fn test_is_cacheable_respects_annotations() {
+        let schema = std::sync::Arc::new(serde_json::Map::new());
+        let tool = rmcp::model::Tool::new("read_file", "Read a file", schema.clone());
+        assert!(!is_cacheable(&tool));
+
+        let unhinted = rmcp::model::Tool::new("read_file", "Read a file", schema.clone())
+            .annotate(rmcp::model::ToolAnnotations::new().destructive(false));
+        assert!(!is_cacheable(&unhinted));
+
+        let read_only = rmcp::model::Tool::new("read_file", "Read a file", schema.clone())
+            .annotate(rmcp::model::ToolAnnotations::new().read_only(true));
+        assert!(is_cacheable(&read_only));
+
+        let writer = rmcp::model::Tool::new("write_file", "Write a file", schema.clone())
+            .annotate(rmcp::model::ToolAnnotations::new().read_only(false));
+        assert!(!is_cacheable(&writer));
+
+        let destructive = rmcp::model::Tool::new("delete", "Delete", schema)
+            .annotate(rmcp::model::ToolAnnotations::new().destructive(true));
+        assert!(!is_cacheable(&destructive));
+    }
+}
@@ -0,0 +1,178 @@
+//! Response validation with `--validate-cmd`
+//!
+//! The code blocks of an answer are written to a temporary workspace (named the way
+//! `lc logs recent answer code --write-to` names them) and the command is run there.
+//! When it fails, its output is sent back to the model, asking for a corrected
+//! answer, up to `--validate-retries` times.
+
+use crate::utils::code_blocks;
+use anyhow::Result;
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Default number of repair requests after a failed validation
+pub const DEFAULT_MAX_REPAIRS: usize = 3;
+
+/// How long a validation command may run before it counts as failed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Command output beyond this many characters is cut (keeping the end) before it is
+/// sent to the model
+const MAX_FEEDBACK_CHARS: usize = 8000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationOptions {
+    pub command: String,
+    pub max_repairs: usize,
+}
+
+static CURRENT: RwLock<Option<ValidationOptions>> = RwLock::new(None);
+
+/// Set the validation command for prompts sent by this invocation
+pub fn set(command: Option<String>, max_repairs: usize) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = command
+            .filter(|c| !c.trim().is_empty())
+            .map(|command| ValidationOptions {
+                command,
+                max_repairs,
+            });
+    }
+}
+
+/// The validation command for this invocation, if any
+pub fn current() -> Option<ValidationOptions> {
+    CURRENT.read().ok().and_then(|current| current.clone())
+}
+
+/// Result of running the validation command
+#[derive(Debug, Clone)]
+pub struct ValidationOutcome {
+    pub success: bool,
+    /// Combined stdout and stderr
+    pub output: String,
+}
+
+/// Write the answer's code blocks into `dir`, returning the relative file names.
+/// Returns an empty list when the answer has no code blocks.
+pub fn write_workspace(dir: &Path, answer: &str) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for (index, block) in code_blocks::extract(answer).iter().enumerate() {
+        let name = code_blocks::file_name_for(block, index);
+        let path = code_blocks::target_path(dir, &name)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, format!("{}\n", block.content))?;
+        files.push(name);
+    }
+    Ok(files)
+}
+
+/// Run the validation command through the shell with `dir` as working directory
+pub async fn run_command(command: &str, dir: &Path) -> Result<ValidationOutcome> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    cmd.current_dir(dir).kill_on_drop(true);
+
+    let output = match tokio::time::timeout(COMMAND_TIMEOUT, cmd.output()).await {
+        Ok(output) => output.map_err(|e| {
+            anyhow::anyhow!("Failed to run validation command '{}': {}", command, e)
+        })?,
+        Err(_) => {
+            return Ok(ValidationOutcome {
+                success: false,
+                output: format!(
+                    "Command timed out after {} seconds",
+                    COMMAND_TIMEOUT.as_secs()
+                ),
+            })
+        }
+    };
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&stderr);
+    }
+
+    Ok(ValidationOutcome {
+        success: output.status.success(),
+        output: text,
+    })
+}
+
+/// Follow-up prompt asking the model to fix the code that failed validation
+pub fn repair_prompt(command: &str, files: &[String], output: &str) -> String {
+    let output = output.trim();
+    let output = match output.char_indices().rev().nth(MAX_FEEDBACK_CHARS) {
+        Some((cut, _)) => format!("[...]\n{}", &output[cut..]),
+        None => output.to_string(),
+    };
+    format!(
+        "The code in your answer was written to {} and checked with `{}`, which failed:\n\n\
+         ```\n{}\n```\n\n\
+         Fix the errors and reply with the complete corrected code. Keep the same file \
+         names so the files can be checked again.",
+        files.join(", "),
+        command,
+        output
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_workspace_names_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let answer = "`src/lib.rs`:\n```rust\npub fn f() {}\n```\n\n```toml\n[package]\n```\n";
+        let files = write_workspace(dir.path(), answer).unwrap();
+        assert_eq!(files, vec!["src/lib.rs", "block_2.toml"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/lib.rs")).unwrap(),
+            "pub fn f() {}\n"
+        );
+        assert!(write_workspace(dir.path(), "no code here")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command_reports_failure_output() {
+        let dir = tempfile::tempdir().unwrap();
+        write_workspace(dir.path(), "```sh\necho hi\n```").unwrap();
+
+        let ok = run_command("test -f block_1.sh", dir.path()).await.unwrap();
+        assert!(ok.success);
+
+        let failed = run_command("echo broken >&2; exit 1", dir.path())
+            .await
+            .unwrap();
+        assert!(!failed.success);
+        assert_eq!(failed.output.trim(), "broken");
+    }
+
+    #[test]
+    fn test_repair_prompt_keeps_end_of_long_output() {
+        let output = format!("{}error: the real problem", "noise\n".repeat(5000));
+        let prompt = repair_prompt("cargo check", &["src/main.rs".to_string()], &output);
+        assert!(prompt.contains("`cargo check`"));
+        assert!(prompt.contains("src/main.rs"));
+        assert!(prompt.contains("error: the real problem"));
+        assert!(prompt.len() < MAX_FEEDBACK_CHARS + 500);
+    }
+}
@@ -0,0 +1,636 @@
+//! Automatic database backups and integrity checks
+//!
+//! logs.db and the vector databases are copied into `<config dir>/backups/<timestamp>/`
+//! at a configurable interval (checked on startup), keeping a configurable number of
+//! copies. Databases that changed since their last successful check are verified with
+//! `PRAGMA integrity_check` on startup, and a restore from the latest backup is offered
+//! when corruption is found.
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STATE_FILE: &str = "state.json";
+
+/// Backup settings stored in `backup.toml`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupConfig {
+    /// Create backups automatically on startup when one is due
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Minimum hours between automatic backups
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+    /// Number of backups to keep
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+    /// Check changed databases with `PRAGMA integrity_check` on startup
+    #[serde(default = "default_enabled")]
+    pub integrity_check: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+fn default_keep() -> usize {
+    7
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_hours: default_interval_hours(),
+            keep: default_keep(),
+            integrity_check: default_enabled(),
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn load() -> Result<Self> {
+        let path = Self::config_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        crate::lock::write_config_file(&Self::config_path()?, &toml::to_string_pretty(self)?)
+    }
+
+    fn config_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::config_dir()?.join("backup.toml"))
+    }
+}
+
+/// Bookkeeping for automatic backups and checks, stored in `backups/state.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BackupState {
+    last_backup: Option<DateTime<Utc>>,
+    /// Database (relative path) -> modification time (unix millis) that last passed a check
+    #[serde(default)]
+    verified: HashMap<String, i64>,
+}
+
+/// A backup directory and the databases it contains
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    pub id: String,
+    pub path: PathBuf,
+    /// Database paths relative to the config directory
+    pub files: Vec<String>,
+    pub size: u64,
+}
+
+/// Result of checking one database
+#[derive(Debug)]
+pub struct CheckResult {
+    pub database: String,
+    /// Problems reported by `PRAGMA integrity_check`; empty when the database is healthy
+    pub problems: Vec<String>,
+}
+
+impl CheckResult {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Backup manager for the databases in a config directory
+pub struct BackupManager {
+    config_dir: PathBuf,
+    config: BackupConfig,
+}
+
+impl BackupManager {
+    /// Manager for the lc config directory using the settings in backup.toml
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_config(
+            crate::config::Config::config_dir()?,
+            BackupConfig::load()?,
+        ))
+    }
+
+    pub fn with_config(config_dir: impl Into<PathBuf>, config: BackupConfig) -> Self {
+        Self {
+            config_dir: config_dir.into(),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &BackupConfig {
+        &self.config
+    }
+
+    fn backups_dir(&self) -> PathBuf {
+        self.config_dir.join("backups")
+    }
+
+    /// Databases covered by backups (relative path, absolute path)
+    pub fn databases(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut databases = Vec::new();
+        let logs = self.config_dir.join("logs.db");
+        if logs.is_file() {
+            databases.push(("logs.db".to_string(), logs));
+        }
+
+        let embeddings = self.config_dir.join("embeddings");
+        if embeddings.is_dir() {
+            let mut names: Vec<_> = fs::read_dir(&embeddings)?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("db"))
+                .collect();
+            names.sort();
+            for path in names {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                databases.push((format!("embeddings/{}", name), path));
+            }
+        }
+        Ok(databases)
+    }
+
+    /// Back up all databases, skipping any listed in `skip` (e.g. corrupted ones)
+    pub fn create_backup(&self, skip: &[String]) -> Result<Option<BackupInfo>> {
+        crate::lock::ensure_writable("create a backup")?;
+        let databases: Vec<_> = self
+            .databases()?
+            .into_iter()
+            .filter(|(rel, _)| !skip.contains(rel))
+            .collect();
+        if databases.is_empty() {
+            return Ok(None);
+        }
+
+        let id = Utc::now().format("%Y%m%dT%H%M%S%3f").to_string();
+        let dir = self.backups_dir().join(&id);
+        fs::create_dir_all(&dir)?;
+
+        let mut files = Vec::new();
+        for (rel, path) in databases {
+            let dest = dir.join(&rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            match snapshot_database(&path, &dest) {
+                Ok(()) => files.push(rel),
+                Err(e) => {
+                    crate::debug_log!("Skipping backup of {}: {}", rel, e);
+                    let _ = fs::remove_file(&dest);
+                }
+            }
+        }
+
+        if files.is_empty() {
+            fs::remove_dir_all(&dir)?;
+            return Ok(None);
+        }
+
+        let mut state = self.load_state();
+        state.last_backup = Some(Utc::now());
+        self.save_state(&state)?;
+        self.prune()?;
+
+        let size = dir_size(&dir);
+        Ok(Some(BackupInfo {
+            id,
+            path: dir,
+            files,
+            size,
+        }))
+    }
+
+    /// Backups, most recent first
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>> {
+        let dir = self.backups_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let id = path.file_name().unwrap().to_string_lossy().to_string();
+            let mut files = Vec::new();
+            if path.join("logs.db").is_file() {
+                files.push("logs.db".to_string());
+            }
+            if let Ok(entries) = fs::read_dir(path.join("embeddings")) {
+                let mut names: Vec<_> = entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| format!("embeddings/{}", e.file_name().to_string_lossy()))
+                    .collect();
+                names.sort();
+                files.extend(names);
+            }
+            let size = dir_size(&path);
+            backups.push(BackupInfo {
+                id,
+                path,
+                files,
+                size,
+            });
+        }
+
+        backups.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(backups)
+    }
+
+    /// Most recent backup containing the given database
+    pub fn latest_backup_of(&self, database: &str) -> Result<Option<BackupInfo>> {
+        Ok(self
+            .list_backups()?
+            .into_iter()
+            .find(|b| b.files.iter().any(|f| f == database)))
+    }
+
+    /// Restore databases from a backup (all of them, or only `database`).
+    ///
+    /// The current file is kept next to the original as `<name>.corrupt-<timestamp>`.
+    pub fn restore(&self, backup: &BackupInfo, database: Option<&str>) -> Result<Vec<String>> {
+        crate::lock::ensure_writable("restore a backup")?;
+        let files: Vec<&String> = match database {
+            Some(name) => {
+                let file = backup
+                    .files
+                    .iter()
+                    .find(|f| f.as_str() == name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Backup '{}' does not contain '{}'", backup.id, name)
+                    })?;
+                vec![file]
+            }
+            None => backup.files.iter().collect(),
+        };
+
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S");
+        let mut restored = Vec::new();
+        for rel in files {
+            let target = self.config_dir.join(rel);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if target.exists() {
+                fs::rename(&target, format!("{}.corrupt-{}", target.display(), stamp))?;
+            }
+            // Stale WAL files would be replayed onto the restored database
+            for suffix in ["-wal", "-shm"] {
+                let sidecar = PathBuf::from(format!("{}{}", target.display(), suffix));
+                if sidecar.exists() {
+                    fs::remove_file(&sidecar)?;
+                }
+            }
+            fs::copy(backup.path.join(rel), &target)?;
+            restored.push(rel.clone());
+        }
+
+        // Restored files must be re-checked on the next startup
+        let mut state = self.load_state();
+        for rel in &restored {
+            state.verified.remove(rel);
+        }
+        self.save_state(&state)?;
+
+        Ok(restored)
+    }
+
+    /// Run `PRAGMA integrity_check` on every database
+    pub fn check_all(&self) -> Result<Vec<CheckResult>> {
+        let mut results = Vec::new();
+        let mut state = self.load_state();
+        for (rel, path) in self.databases()? {
+            let problems = integrity_check(&path)?;
+            if problems.is_empty() {
+                if let Some(mtime) = modified_secs(&path) {
+                    state.verified.insert(rel.clone(), mtime);
+                }
+            }
+            results.push(CheckResult {
+                database: rel,
+                problems,
+            });
+        }
+        self.save_state(&state)?;
+        Ok(results)
+    }
+
+    /// Check databases modified since their last successful check
+    pub fn check_changed(&self) -> Result<Vec<CheckResult>> {
+        let mut results = Vec::new();
+        let mut state = self.load_state();
+        let mut dirty = false;
+        for (rel, path) in self.databases()? {
+            let Some(mtime) = modified_secs(&path) else {
+                continue;
+            };
+            if state.verified.get(&rel) == Some(&mtime) {
+                continue;
+            }
+            let problems = integrity_check(&path)?;
+            if problems.is_empty() {
+                state.verified.insert(rel.clone(), mtime);
+                dirty = true;
+            }
+            results.push(CheckResult {
+                database: rel,
+                problems,
+            });
+        }
+        if dirty {
+            self.save_state(&state)?;
+        }
+        Ok(results)
+    }
+
+    /// Whether an automatic backup is due
+    pub fn backup_due(&self) -> bool {
+        match self.load_state().last_backup {
+            Some(last) => {
+                Utc::now() - last >= chrono::Duration::hours(self.config.interval_hours as i64)
+            }
+            None => true,
+        }
+    }
+
+    fn prune(&self) -> Result<()> {
+        for backup in self
+            .list_backups()?
+            .into_iter()
+            .skip(self.config.keep.max(1))
+        {
+            crate::debug_log!("Pruning old backup {}", backup.id);
+            fs::remove_dir_all(&backup.path)?;
+        }
+        Ok(())
+    }
+
+    fn load_state(&self) -> BackupState {
+        fs::read_to_string(self.backups_dir().join(STATE_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_state(&self, state: &BackupState) -> Result<()> {
+        // Checks still run in read-only mode, they just aren't remembered
+        if crate::lock::is_read_only() {
+            return Ok(());
+        }
+        fs::create_dir_all(self.backups_dir())?;
+        fs::write(
+            self.backups_dir().join(STATE_FILE),
+            serde_json::to_string_pretty(state)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Write a consistent copy of a SQLite database (including WAL contents) to `dest`
+fn snapshot_database(source: &Path, dest: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.execute("VACUUM INTO ?1", [dest.to_string_lossy().as_ref()])?;
+    Ok(())
+}
+
+/// Run `PRAGMA integrity_check`, returning the reported problems (empty when healthy)
+pub fn integrity_check(path: &Path) -> Result<Vec<String>> {
+    let conn = match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(conn) => conn,
+        Err(e) => return Ok(vec![e.to_string()]),
+    };
+    let rows = conn.prepare("PRAGMA integrity_check").and_then(|mut stmt| {
+        stmt.query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+    });
+    match rows {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => Ok(Vec::new()),
+        // FTS5 checks its index with a write, which a read-only connection refuses; the
+        // keyword index of vector databases is derived from their texts anyway
+        Ok(rows) => Ok(rows
+            .into_iter()
+            .filter(|row| !(row.contains("FTS5") && row.contains("readonly database")))
+            .collect()),
+        // Files that aren't databases at all fail before the pragma can run
+        Err(e) => Ok(vec![e.to_string()]),
+    }
+}
+
+fn modified_secs(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Utc>::from(modified).timestamp_millis())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| {
+            let path = e.path();
+            if path.is_dir() {
+                dir_size(&path)
+            } else {
+                e.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Startup hook: check changed databases, offer restores, and back up when due.
+///
+/// Never fails the command being run; problems are reported as warnings.
+pub fn startup_maintenance() {
+    if crate::lock::is_read_only() {
+        crate::debug_log!("Backup maintenance skipped in read-only mode");
+        return;
+    }
+    let manager = match BackupManager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            crate::debug_log!("Backup maintenance skipped: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = manager.run_startup() {
+        crate::debug_log!("Backup maintenance failed: {}", e);
+    }
+}
+
+impl BackupManager {
+    fn run_startup(&self) -> Result<()> {
+        let mut corrupted = Vec::new();
+        if self.config.integrity_check {
+            for result in self.check_changed()? {
+                if !result.is_ok() {
+                    self.handle_corruption(&result)?;
+                    corrupted.push(result.database);
+                }
+            }
+        }
+
+        if self.config.enabled && self.backup_due() {
+            if let Some(backup) = self.create_backup(&corrupted)? {
+                crate::debug_log!(
+                    "Created automatic backup {} ({} databases)",
+                    backup.id,
+                    backup.files.len()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_corruption(&self, result: &CheckResult) -> Result<()> {
+        eprintln!(
+            "{} Database '{}' failed its integrity check: {}",
+            sym("⚠️").yellow(),
+            result.database,
+            result.problems.first().map(String::as_str).unwrap_or("")
+        );
+
+        let Some(backup) = self.latest_backup_of(&result.database)? else {
+            eprintln!("   No backup of '{}' is available.", result.database);
+            return Ok(());
+        };
+
+        let interactive = atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stderr);
+        if !interactive {
+            eprintln!(
+                "   Restore it with: {}",
+                format!(
+                    "lc backup restore {} --database {}",
+                    backup.id, result.database
+                )
+                .cyan()
+            );
+            return Ok(());
+        }
+
+        use std::io::{self, Write};
+        eprint!(
+            "   Restore '{}' from backup {}? (y/N): ",
+            result.database, backup.id
+        );
+        io::stderr().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if input.trim().to_lowercase().starts_with('y') {
+            self.restore(&backup, Some(&result.database))?;
+            eprintln!(
+                "   {} Restored '{}' (the damaged file was kept as {}.corrupt-*)",
+                sym("✓").green(),
+                result.database,
+                result.database
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_db(path: &Path, rows: i64) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute("CREATE TABLE t (x INTEGER)", []).unwrap();
+        for i in 0..rows {
+            conn.execute("INSERT INTO t VALUES (?1)", [i]).unwrap();
+        }
+    }
+
+    fn count(path: &Path) -> i64 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0))
+            .unwrap()
+    }
+
+    fn manager(dir: &Path, keep: usize) -> BackupManager {
+        BackupManager::with_config(
+            dir,
+            BackupConfig {
+                keep,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("embeddings")).unwrap();
+        create_db(&dir.path().join("logs.db"), 3);
+        create_db(&dir.path().join("embeddings/docs.db"), 5);
+
+        let manager = manager(dir.path(), 7);
+        assert!(manager.backup_due());
+        let backup = manager.create_backup(&[]).unwrap().unwrap();
+        assert_eq!(backup.files, vec!["logs.db", "embeddings/docs.db"]);
+        assert!(!manager.backup_due());
+
+        // Corrupt logs.db and restore it
+        fs::write(dir.path().join("logs.db"), b"garbage garbage garbage").unwrap();
+        assert!(!integrity_check(&dir.path().join("logs.db"))
+            .unwrap()
+            .is_empty());
+
+        let latest = manager.latest_backup_of("logs.db").unwrap().unwrap();
+        manager.restore(&latest, Some("logs.db")).unwrap();
+        assert_eq!(count(&dir.path().join("logs.db")), 3);
+        assert!(integrity_check(&dir.path().join("logs.db"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_retention() {
+        let dir = TempDir::new().unwrap();
+        create_db(&dir.path().join("logs.db"), 1);
+
+        let manager = manager(dir.path(), 2);
+        for _ in 0..4 {
+            manager.create_backup(&[]).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(manager.list_backups().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_check_changed_skips_verified_databases() {
+        let dir = TempDir::new().unwrap();
+        create_db(&dir.path().join("logs.db"), 1);
+
+        let manager = manager(dir.path(), 7);
+        let first = manager.check_changed().unwrap();
+        assert_eq!(first.len(), 1);
+        assert!(first[0].is_ok());
+        assert!(manager.check_changed().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_no_backup_without_databases() {
+        let dir = TempDir::new().unwrap();
+        let manager = manager(dir.path(), 7);
+        assert!(manager.create_backup(&[]).unwrap().is_none());
+        assert!(!dir.path().join("backups").exists());
+    }
+}
@@ -0,0 +1,354 @@
+//! Splitting files into chunks for `lc embed` (`--chunk-strategy`, `--chunk-size`,
+//! `--chunk-overlap`)
+//!
+//! Every strategy marks the places where the text may be split, ranked from the
+//! weakest (a line end) to the strongest (a markdown heading or a top-level code
+//! definition). A chunk grows up to the chunk size and ends at the strongest split
+//! point in its second half, so sections stay together when they fit. Text without
+//! any split point is cut at the size. Chunks keep their position in the source so
+//! search results can cite `file:line`.
+
+use std::path::Path;
+
+/// Chunk size in characters when `--chunk-size` is not given
+pub const DEFAULT_CHUNK_SIZE: usize = 1200;
+/// Characters shared by consecutive chunks when `--chunk-overlap` is not given
+pub const DEFAULT_CHUNK_OVERLAP: usize = 200;
+
+/// Where files are split
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChunkStrategy {
+    /// Markdown for .md and .epub files, code for source files, sentence for everything
+    /// else
+    #[default]
+    Auto,
+    /// Cut every `--chunk-size` characters, wherever that falls
+    Fixed,
+    /// Split between sentences and paragraphs
+    Sentence,
+    /// Split at headings, then between paragraphs and sentences
+    Markdown,
+    /// Split between top-level definitions, then at blank lines and line ends
+    Code,
+}
+
+impl ChunkStrategy {
+    /// The strategy for a file, resolving `Auto` from its extension
+    pub fn for_path(self, path: Option<&Path>) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        let extension = path
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("md" | "markdown" | "mdx" | "epub") => Self::Markdown,
+            Some(ext) if crate::utils::is_code_file(ext) => Self::Code,
+            _ => Self::Sentence,
+        }
+    }
+}
+
+/// How to chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOptions {
+    pub strategy: ChunkStrategy,
+    /// Maximum chunk length in characters
+    pub size: usize,
+    /// Characters repeated at the start of the next chunk
+    pub overlap: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            strategy: ChunkStrategy::Auto,
+            size: DEFAULT_CHUNK_SIZE,
+            overlap: DEFAULT_CHUNK_OVERLAP,
+        }
+    }
+}
+
+impl ChunkOptions {
+    pub fn new(strategy: ChunkStrategy, size: usize, overlap: usize) -> anyhow::Result<Self> {
+        if size == 0 {
+            anyhow::bail!("--chunk-size must be at least 1");
+        }
+        if overlap >= size {
+            anyhow::bail!(
+                "--chunk-overlap ({}) must be smaller than --chunk-size ({})",
+                overlap,
+                size
+            );
+        }
+        Ok(Self {
+            strategy,
+            size,
+            overlap,
+        })
+    }
+}
+
+/// A piece of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub text: String,
+    /// Byte offset of the chunk in the source
+    pub offset: usize,
+    /// Line of the source the chunk starts on, from 1
+    pub line: usize,
+}
+
+/// How good a place to split is, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Strength {
+    Line,
+    Sentence,
+    Paragraph,
+    Section,
+}
+
+/// Split `text` (the content of `path`, if it comes from a file) into chunks
+pub fn chunk(text: &str, options: &ChunkOptions, path: Option<&Path>) -> Vec<Chunk> {
+    let strategy = options.strategy.for_path(path);
+    let splits = split_points(text, strategy);
+    // Sizes are in characters, positions in bytes
+    let byte_at = |start: usize, chars: usize| {
+        text[start..]
+            .char_indices()
+            .nth(chars)
+            .map_or(text.len(), |(i, _)| start + i)
+    };
+
+    let mut chunks = Vec::new();
+    // Offset and line of the last chunk, so lines are counted from there
+    let mut cursor = (0, 1);
+    let mut start = 0;
+    while start < text.len() {
+        let limit = byte_at(start, options.size);
+        let (end, strength) = if limit >= text.len() {
+            (text.len(), Strength::Section)
+        } else {
+            let half = byte_at(start, options.size / 2);
+            let candidates = &splits[after(&splits, start)..after(&splits, limit)];
+            let best = candidates
+                .iter()
+                .filter(|(pos, _)| *pos >= half)
+                .max_by_key(|(pos, strength)| (*strength, *pos))
+                .or_else(|| candidates.last());
+            match best {
+                Some(&(pos, strength)) => (pos, strength),
+                None => (limit, Strength::Line),
+            }
+        };
+
+        push_chunk(&mut chunks, &mut cursor, text, start, end);
+        if end >= text.len() {
+            break;
+        }
+
+        // Chunks overlap, except across a section boundary
+        start = if strength == Strength::Section || options.overlap == 0 {
+            end
+        } else {
+            let from = back_chars(text, end, options.overlap);
+            let boundary = if strategy == ChunkStrategy::Fixed {
+                None
+            } else {
+                splits
+                    .get(splits.partition_point(|(pos, _)| *pos < from))
+                    .map(|(pos, _)| *pos)
+                    .filter(|pos| *pos < end)
+            };
+            boundary.unwrap_or(from).max(start + 1)
+        };
+        while !text.is_char_boundary(start) {
+            start += 1;
+        }
+    }
+    chunks
+}
+
+fn push_chunk(
+    chunks: &mut Vec<Chunk>,
+    cursor: &mut (usize, usize),
+    text: &str,
+    start: usize,
+    end: usize,
+) {
+    let raw = &text[start..end];
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return;
+    }
+    let offset = start + (raw.len() - raw.trim_start().len());
+    // Overlapping chunks start before the end of the previous one, never before its start
+    let line = cursor.1 + text[cursor.0..offset].matches('\n').count();
+    *cursor = (offset, line);
+    chunks.push(Chunk {
+        text: trimmed.to_string(),
+        offset,
+        line,
+    });
+}
+
+/// Index of the first split point after `pos`
+fn after(splits: &[(usize, Strength)], pos: usize) -> usize {
+    splits.partition_point(|(split, _)| *split <= pos)
+}
+
+/// The byte position `chars` characters before `pos`
+fn back_chars(text: &str, pos: usize, chars: usize) -> usize {
+    text[..pos]
+        .char_indices()
+        .rev()
+        .nth(chars.saturating_sub(1))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Byte positions where the text may be split, each at the start of what follows
+fn split_points(text: &str, strategy: ChunkStrategy) -> Vec<(usize, Strength)> {
+    let mut splits = Vec::new();
+    if strategy == ChunkStrategy::Fixed {
+        return splits;
+    }
+
+    let mut in_fence = false;
+    let mut previous_blank = true;
+    let mut pos = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end();
+        let blank = content.trim().is_empty();
+        if content.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let starts_section = match strategy {
+            ChunkStrategy::Markdown => !in_fence && content.starts_with('#') && is_heading(content),
+            ChunkStrategy::Code => {
+                previous_blank
+                    && !blank
+                    && !line.starts_with(char::is_whitespace)
+                    && !content.starts_with(['}', ')', ']'])
+            }
+            _ => false,
+        };
+        if pos > 0 {
+            let strength = if starts_section {
+                Strength::Section
+            } else if previous_blank && !blank {
+                Strength::Paragraph
+            } else {
+                Strength::Line
+            };
+            splits.push((pos, strength));
+        }
+        if strategy != ChunkStrategy::Code && !in_fence {
+            sentence_ends(line, pos, &mut splits);
+        }
+        previous_blank = blank;
+        pos += line.len();
+    }
+    splits.sort_by_key(|(pos, _)| *pos);
+    splits.dedup_by(|next, kept| {
+        if next.0 == kept.0 {
+            kept.1 = kept.1.max(next.1);
+            true
+        } else {
+            false
+        }
+    });
+    splits
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    hashes <= 6 && line[hashes..].starts_with(' ')
+}
+
+/// Positions after `. `, `! ` and `? ` within a line
+fn sentence_ends(line: &str, line_start: usize, splits: &mut Vec<(usize, Strength)>) {
+    let mut chars = line.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        if let Some(&(i, next)) = chars.peek() {
+            if next == ' ' || next == '\t' {
+                splits.push((line_start + i + 1, Strength::Sentence));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(chunks: &[Chunk]) -> Vec<&str> {
+        chunks.iter().map(|chunk| chunk.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let options = |strategy, size, overlap| ChunkOptions::new(strategy, size, overlap).unwrap();
+
+        let fixed = chunk("abcdefghij", &options(ChunkStrategy::Fixed, 4, 1), None);
+        assert_eq!(texts(&fixed), ["abcd", "defg", "ghij"]);
+        assert_eq!(fixed[1].offset, 3);
+
+        let prose = "One sentence here. Another one follows. And a third.";
+        let sentences = chunk(prose, &options(ChunkStrategy::Sentence, 25, 0), None);
+        assert_eq!(
+            texts(&sentences),
+            ["One sentence here.", "Another one follows.", "And a third."]
+        );
+
+        let markdown = "# Intro\nShort.\n\n## Setup\nInstall it.\n\n## Usage\nRun it.\n";
+        let sections = chunk(markdown, &options(ChunkStrategy::Markdown, 30, 10), None);
+        assert_eq!(
+            texts(&sections),
+            [
+                "# Intro\nShort.",
+                "## Setup\nInstall it.",
+                "## Usage\nRun it."
+            ]
+        );
+        assert_eq!(sections[1].line, 4);
+        assert_eq!(&markdown[sections[1].offset..][..8], "## Setup");
+
+        let code = "fn a() {\n    1\n}\n\nfn b() {\n    2\n}\n";
+        let functions = chunk(code, &options(ChunkStrategy::Code, 20, 5), None);
+        assert_eq!(
+            texts(&functions),
+            ["fn a() {\n    1\n}", "fn b() {\n    2\n}"]
+        );
+        assert_eq!(functions[1].line, 5);
+    }
+
+    #[test]
+    fn test_chunk_edge_cases() {
+        let options = ChunkOptions::new(ChunkStrategy::Sentence, 5, 2).unwrap();
+        // Multi-byte characters are never split
+        let chunks = chunk("ééééééééééé", &options, None);
+        assert!(chunks.iter().all(|chunk| chunk.text.chars().count() <= 5));
+        assert_eq!(chunk("", &options, None), Vec::new());
+        assert_eq!(chunk("   \n", &options, None), Vec::new());
+
+        assert!(ChunkOptions::new(ChunkStrategy::Auto, 100, 100).is_err());
+        assert!(ChunkOptions::new(ChunkStrategy::Auto, 0, 0).is_err());
+        assert_eq!(
+            ChunkStrategy::Auto.for_path(Some(Path::new("README.md"))),
+            ChunkStrategy::Markdown
+        );
+        assert_eq!(
+            ChunkStrategy::Auto.for_path(Some(Path::new("main.rs"))),
+            ChunkStrategy::Code
+        );
+        assert_eq!(ChunkStrategy::Auto.for_path(None), ChunkStrategy::Sentence);
+        assert_eq!(
+            ChunkStrategy::Fixed.for_path(Some(Path::new("main.rs"))),
+            ChunkStrategy::Fixed
+        );
+    }
+}
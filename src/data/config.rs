@@ -6,15 +6,21 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-#[cfg(unix)]
-use std::os::unix::fs::OpenOptionsExt;
+use std::fs;
 use std::path::PathBuf;
 
+use super::config_overrides;
+use crate::core::redaction::RedactionSettings;
+use crate::core::refusal::RefusalSettings;
+use crate::core::router::RouterSettings;
+use crate::core::self_check::SelfCheckSettings;
+use crate::core::style::StyleSettings;
+use crate::data::hybrid_search::HybridSearchSettings;
 use crate::template_processor::TemplateConfig;
+use crate::utils::time_display::TimeSettings;
+use crate::utils::web_cache::WebCacheSettings;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Config {
     pub providers: HashMap<String, ProviderConfig>,
     pub default_provider: Option<String>,
@@ -25,12 +31,83 @@ pub struct Config {
     pub system_prompt: Option<String>,
     #[serde(default)]
     pub templates: HashMap<String, String>, // template_name -> prompt_content
+    /// Prompt files of templates added with `--file` (template_name -> path)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub template_files: HashMap<String, String>,
     #[serde(default)]
     pub max_tokens: Option<u32>,
     #[serde(default)]
     pub temperature: Option<f32>,
     #[serde(default)]
     pub stream: Option<bool>,
+    #[serde(default, skip_serializing_if = "SpendLimits::is_unset")]
+    pub limits: SpendLimits,
+    #[serde(default, skip_serializing_if = "StyleSettings::is_empty")]
+    pub styles: StyleSettings,
+    #[serde(default, skip_serializing_if = "SelfCheckSettings::is_empty")]
+    pub self_check: SelfCheckSettings,
+    #[serde(default, skip_serializing_if = "RedactionSettings::is_empty")]
+    pub redaction: RedactionSettings,
+    #[serde(default, skip_serializing_if = "TimeSettings::is_empty")]
+    pub time: TimeSettings,
+    #[serde(default, skip_serializing_if = "WebCacheSettings::is_empty")]
+    pub web_cache: WebCacheSettings,
+    #[serde(default, skip_serializing_if = "RouterSettings::is_empty")]
+    pub router: RouterSettings,
+    #[serde(default, skip_serializing_if = "RefusalSettings::is_empty")]
+    pub refusal: RefusalSettings,
+    #[serde(default, skip_serializing_if = "HybridSearchSettings::is_empty")]
+    pub hybrid_search: HybridSearchSettings,
+    #[serde(default, skip_serializing_if = "HttpSettings::is_empty")]
+    pub http: HttpSettings,
+    #[serde(default, skip_serializing_if = "RetrySettings::is_empty")]
+    pub retry: RetrySettings,
+    /// Organization variables of templates and system prompts (`{{var.<name>}}`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub template_vars: HashMap<String, String>,
+}
+
+/// Spending limits in USD, enforced with the pricing in the models cache
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SpendLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_request_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_usd: Option<f64>,
+}
+
+impl SpendLimits {
+    pub fn is_unset(&self) -> bool {
+        self.per_request_usd.is_none() && self.daily_usd.is_none()
+    }
+}
+
+/// Timeouts of provider requests
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct HttpSettings {
+    /// Seconds a provider request may take, streamed or not (60 and 300 by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+impl HttpSettings {
+    pub fn is_empty(&self) -> bool {
+        self.timeout.is_none()
+    }
+}
+
+/// Retries of failed provider requests
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RetrySettings {
+    /// How often a streamed response that broke off is resumed (2 by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u32>,
+}
+
+impl RetrySettings {
+    pub fn is_empty(&self) -> bool {
+        self.max.is_none()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -50,14 +127,26 @@ pub struct ProviderConfig {
     pub audio_path: Option<String>,
     #[serde(default)]
     pub speech_path: Option<String>,
+    /// Rerank endpoint (Cohere/Jina style), `/rerank` when unset
+    #[serde(default)]
+    pub rerank_path: Option<String>,
     #[serde(default)]
     pub headers: HashMap<String, String>,
+    /// Organization sent as the `OpenAI-Organization` header
+    #[serde(default)]
+    pub organization: Option<String>,
+    /// Project sent as the `OpenAI-Project` header
+    #[serde(default)]
+    pub project: Option<String>,
     #[serde(default)]
     pub token_url: Option<String>,
     #[serde(default)]
     pub cached_token: Option<CachedToken>,
     #[serde(default)]
-    pub auth_type: Option<String>, // e.g., "google_sa_jwt"
+    pub auth_type: Option<String>, // e.g., "google_sa_jwt", "hmac"
+    /// Request signing settings for `auth_type = "hmac"`
+    #[serde(default)]
+    pub hmac: Option<crate::core::request_signing::HmacConfig>,
     #[serde(default)]
     pub vars: HashMap<String, String>, // arbitrary provider vars like project, location
     #[serde(default)]
@@ -74,7 +163,46 @@ pub struct ProviderConfig {
     pub speech_templates: Option<HashMap<String, TemplateConfig>>, // Speech generation endpoint templates
 }
 
+/// Header carrying `ProviderConfig::organization`
+pub const ORGANIZATION_HEADER: &str = "OpenAI-Organization";
+/// Header carrying `ProviderConfig::project`
+pub const PROJECT_HEADER: &str = "OpenAI-Project";
+
 impl ProviderConfig {
+    /// Whether the endpoint is Azure OpenAI, which scopes requests by resource and
+    /// deployment in the URL rather than by organization and project headers
+    pub fn is_azure(&self) -> bool {
+        reqwest::Url::parse(&self.endpoint)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .is_some_and(|host| {
+                host.ends_with(".openai.azure.com")
+                    || host.ends_with(".cognitiveservices.azure.com")
+                    || host.ends_with(".services.ai.azure.com")
+            })
+    }
+
+    /// Organization and project headers to send. Nothing is sent to Azure OpenAI, and
+    /// a header set explicitly in `headers` wins.
+    pub fn scope_headers(&self) -> Vec<(&'static str, String)> {
+        if self.is_azure() {
+            return Vec::new();
+        }
+        [
+            (ORGANIZATION_HEADER, &self.organization),
+            (PROJECT_HEADER, &self.project),
+        ]
+        .into_iter()
+        .filter_map(|(header, value)| Some((header, value.clone()?)))
+        .filter(|(header, _)| {
+            !self
+                .headers
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case(header))
+        })
+        .collect()
+    }
+
     /// Check if the chat_path is a full URL (starts with https://)
     pub fn is_chat_path_full_url(&self) -> bool {
         self.chat_path.starts_with("https://")
@@ -307,6 +435,26 @@ impl ProviderConfig {
         }
     }
 
+    /// Get the rerank URL, replacing {model_name} and template variables. Full URLs may
+    /// be plain `http://` for rerankers served locally.
+    pub fn get_rerank_url(&self, model_name: &str) -> String {
+        let Some(ref rerank_path) = self.rerank_path else {
+            return format!("{}/rerank", self.endpoint.trim_end_matches('/'));
+        };
+        let mut path = rerank_path
+            .replace("{model}", model_name)
+            .replace("{model_name}", model_name);
+        for (k, v) in &self.vars {
+            path = path.replace(&format!("{{{}}}", k), v);
+        }
+        crate::debug_log!("ProviderConfig::get_rerank_url: {}", path);
+        if path.starts_with("https://") || path.starts_with("http://") {
+            path
+        } else {
+            format!("{}{}", self.endpoint.trim_end_matches('/'), path)
+        }
+    }
+
     /// Get template for a specific endpoint and model
     pub fn get_endpoint_template(&self, endpoint: &str, model_name: &str) -> Option<String> {
         let endpoint_templates = match endpoint {
@@ -341,6 +489,25 @@ impl ProviderConfig {
         self.get_template_for_model(endpoint_templates, model_name, "response")
     }
 
+    /// Get the whole template configuration for a specific endpoint and model
+    pub fn get_endpoint_template_config(
+        &self,
+        endpoint: &str,
+        model_name: &str,
+    ) -> Option<&TemplateConfig> {
+        let endpoint_templates = match endpoint {
+            "chat" => self.chat_templates.as_ref()?,
+            "images" => self.images_templates.as_ref()?,
+            "embeddings" => self.embeddings_templates.as_ref()?,
+            "models" => self.models_templates.as_ref()?,
+            "audio" => self.audio_templates.as_ref()?,
+            "speech" => self.speech_templates.as_ref()?,
+            _ => return None,
+        };
+
+        Self::find_template_config(endpoint_templates, model_name)
+    }
+
     /// Get template for a specific model from endpoint templates
     fn get_template_for_model(
         &self,
@@ -348,14 +515,24 @@ impl ProviderConfig {
         model_name: &str,
         template_type: &str,
     ) -> Option<String> {
+        let template = Self::find_template_config(templates, model_name)?;
+        match template_type {
+            "request" => template.request.clone(),
+            "response" => template.response.clone(),
+            "stream_response" => template.stream_response.clone(),
+            _ => None,
+        }
+    }
+
+    /// Find the template configuration matching a model: exact name first, then
+    /// regex patterns, then the default (empty key)
+    fn find_template_config<'a>(
+        templates: &'a HashMap<String, TemplateConfig>,
+        model_name: &str,
+    ) -> Option<&'a TemplateConfig> {
         // First check exact match
         if let Some(template) = templates.get(model_name) {
-            return match template_type {
-                "request" => template.request.clone(),
-                "response" => template.response.clone(),
-                "stream_response" => template.stream_response.clone(),
-                _ => None,
-            };
+            return Some(template);
         }
 
         // Then check regex patterns (skip empty string which is the default)
@@ -363,28 +540,14 @@ impl ProviderConfig {
             if !pattern.is_empty() {
                 if let Ok(re) = crate::utils::regex_cache::get_regex(pattern) {
                     if re.is_match(model_name) {
-                        return match template_type {
-                            "request" => template.request.clone(),
-                            "response" => template.response.clone(),
-                            "stream_response" => template.stream_response.clone(),
-                            _ => None,
-                        };
+                        return Some(template);
                     }
                 }
             }
         }
 
         // Finally check for default template (empty key)
-        if let Some(template) = templates.get("") {
-            return match template_type {
-                "request" => template.request.clone(),
-                "response" => template.response.clone(),
-                "stream_response" => template.stream_response.clone(),
-                _ => None,
-            };
-        }
-
-        None
+        templates.get("")
     }
 }
 
@@ -408,6 +571,7 @@ pub struct ProviderPaths {
     pub chat_path: String,
     pub images_path: Option<String>,
     pub embeddings_path: Option<String>,
+    pub rerank_path: Option<String>,
 }
 
 impl Config {
@@ -420,27 +584,25 @@ impl Config {
             let mut config: Config = toml::from_str(&content)?;
 
             // If providers exist in main config, migrate them to separate files
-            if !config.providers.is_empty() {
+            if !config.providers.is_empty() && !crate::lock::is_read_only() {
                 Self::migrate_providers_to_separate_files(&mut config)?;
             }
 
             config
         } else {
             // Create default config
-            Config {
-                providers: HashMap::new(),
-                default_provider: None,
-                default_model: None,
-                aliases: HashMap::new(),
-                system_prompt: None,
-                templates: HashMap::new(),
-                max_tokens: None,
-                temperature: None,
-                stream: None,
-            }
+            Config::default()
         };
-        // Load providers from separate files
+        // Load providers from separate files (unmigrated ones stay in memory in read-only mode)
+        let inline_providers = std::mem::take(&mut config.providers);
         config.providers = Self::load_providers_from_files(&providers_dir)?;
+        for (name, provider) in inline_providers {
+            config.providers.entry(name).or_insert(provider);
+        }
+
+        if crate::lock::is_read_only() {
+            return config.with_overrides();
+        }
 
         // Ensure config directory exists
         if let Some(parent) = config_path.parent() {
@@ -459,6 +621,23 @@ impl Config {
             let _ = crate::keys::KeysConfig::migrate_from_provider_configs(&config);
         }
 
+        config.with_overrides()
+    }
+
+    /// The config with the `--set` overrides of this invocation applied
+    fn with_overrides(mut self) -> Result<Self> {
+        let overrides = config_overrides::current();
+        if overrides.is_empty() {
+            return Ok(self);
+        }
+        let providers = std::mem::take(&mut self.providers);
+        let mut value = toml::Value::try_from(&self)?;
+        config_overrides::apply(&mut value, &overrides)?;
+        let mut config: Config = value
+            .try_into()
+            .map_err(|e| anyhow::anyhow!("Invalid value in --set: {}", e))?;
+        config_overrides::check_known(&toml::Value::try_from(&config)?, &overrides)?;
+        config.providers = providers;
         Ok(config)
     }
 
@@ -486,31 +665,37 @@ impl Config {
             max_tokens: self.max_tokens,
             temperature: self.temperature,
             stream: self.stream,
+            limits: self.limits.clone(),
+            styles: self.styles.clone(),
+            self_check: self.self_check.clone(),
+            redaction: self.redaction.clone(),
+            time: self.time.clone(),
+            web_cache: self.web_cache.clone(),
+            router: self.router.clone(),
+            refusal: self.refusal.clone(),
+            hybrid_search: self.hybrid_search.clone(),
+            http: self.http.clone(),
+            retry: self.retry.clone(),
+            template_files: self.template_files.clone(),
+            template_vars: self.template_vars.clone(),
         };
 
-        let content = toml::to_string_pretty(&main_config)?;
-
-        let mut options = OpenOptions::new();
-        options.write(true).create(true).truncate(true);
-
-        #[cfg(unix)]
-        {
-            options.mode(0o600);
-        }
-
-        let mut file = options.open(&config_path)?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut permissions = file.metadata()?.permissions();
-            permissions.set_mode(0o600);
-            file.set_permissions(permissions)?;
-        }
-
-        file.write_all(content.as_bytes())?;
+        // Overridden keys keep the values they have in the file
+        let overrides = config_overrides::current();
+        let main_config = if overrides.is_empty() {
+            main_config
+        } else {
+            let original = match fs::read_to_string(&config_path) {
+                Ok(content) => toml::from_str(&content)?,
+                Err(_) => toml::Value::Table(toml::Table::new()),
+            };
+            let mut value = toml::Value::try_from(&main_config)?;
+            config_overrides::restore(&mut value, &original, &overrides);
+            value.try_into()?
+        };
 
-        Ok(())
+        let content = toml::to_string_pretty(&main_config)?;
+        crate::lock::write_config_file(&config_path, &content)
     }
 
     fn save_providers_to_files(&self) -> Result<()> {
@@ -633,6 +818,7 @@ impl Config {
             embeddings_path: None,
             audio_path: None,
             speech_path: None,
+            rerank_path: None,
             headers: HashMap::new(),
             token_url: None,
             cached_token: None,
@@ -644,6 +830,9 @@ impl Config {
             models_templates: None,
             audio_templates: None,
             speech_templates: None,
+            organization: None,
+            project: None,
+            hmac: None,
         };
 
         // Auto-detect Vertex AI host to mark google_sa_jwt
@@ -671,6 +860,51 @@ impl Config {
         Ok(())
     }
 
+    /// Copy a provider's configuration (paths, headers, templates, vars) under a new
+    /// name, optionally with a different endpoint. Full-URL paths that start with the
+    /// old endpoint are moved to the new one. Keys and cached tokens are not copied.
+    pub fn clone_provider(
+        &mut self,
+        source: &str,
+        name: String,
+        endpoint: Option<String>,
+    ) -> Result<()> {
+        let mut provider_config = self.get_provider(source)?.clone();
+        if self.has_provider(&name) {
+            anyhow::bail!("Provider '{}' already exists", name);
+        }
+
+        provider_config.api_key = None;
+        provider_config.cached_token = None;
+        if let Some(endpoint) = endpoint {
+            let old_endpoint = provider_config.endpoint.trim_end_matches('/').to_string();
+            let new_endpoint = endpoint.trim_end_matches('/');
+            let rebase = |path: &mut String| {
+                if let Some(rest) = path.strip_prefix(&old_endpoint) {
+                    *path = format!("{}{}", new_endpoint, rest);
+                }
+            };
+            rebase(&mut provider_config.chat_path);
+            rebase(&mut provider_config.models_path);
+            for path in [
+                &mut provider_config.images_path,
+                &mut provider_config.embeddings_path,
+                &mut provider_config.audio_path,
+                &mut provider_config.speech_path,
+                &mut provider_config.rerank_path,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                rebase(path);
+            }
+            provider_config.endpoint = endpoint;
+        }
+
+        self.providers.insert(name.clone(), provider_config.clone());
+        self.save_single_provider(&name, &provider_config)
+    }
+
     pub fn set_api_key(&mut self, provider: String, api_key: String) -> Result<()> {
         // First check if the provider exists
         if !self.has_provider(&provider) {
@@ -752,6 +986,10 @@ impl Config {
             }
         }
 
+        for (header, value) in provider_config.scope_headers() {
+            provider_config.headers.insert(header.to_string(), value);
+        }
+
         Ok(provider_config)
     }
 
@@ -850,12 +1088,22 @@ impl Config {
     }
 
     pub fn add_template(&mut self, template_name: String, prompt_content: String) -> Result<()> {
+        self.template_files.remove(&template_name);
         self.templates.insert(template_name, prompt_content);
         Ok(())
     }
 
+    /// Add a template whose prompt is read from `path` each time it is used
+    pub fn add_template_file(&mut self, template_name: String, path: String) -> Result<()> {
+        self.templates.remove(&template_name);
+        self.template_files.insert(template_name, path);
+        Ok(())
+    }
+
     pub fn remove_template(&mut self, template_name: String) -> Result<()> {
-        if self.templates.remove(&template_name).is_some() {
+        let inline = self.templates.remove(&template_name).is_some();
+        let file = self.template_files.remove(&template_name).is_some();
+        if inline || file {
             Ok(())
         } else {
             anyhow::bail!("Template '{}' not found", template_name);
@@ -866,23 +1114,57 @@ impl Config {
         self.templates.get(template_name)
     }
 
+    /// Whether a template exists, inline or file-backed
+    pub fn has_template(&self, template_name: &str) -> bool {
+        self.templates.contains_key(template_name)
+            || self.template_files.contains_key(template_name)
+    }
+
     pub fn list_templates(&self) -> &HashMap<String, String> {
         &self.templates
     }
 
+    /// The prompt of a template with its ambient variables filled in, for a request
+    pub fn template_content(&self, template_name: &str) -> Result<Option<String>> {
+        Ok(self
+            .template_source(template_name)?
+            .map(|content| self.render_prompt(&content)))
+    }
+
+    /// The prompt of a template as written, read from its file for templates added with
+    /// `--file`
+    pub fn template_source(&self, template_name: &str) -> Result<Option<String>> {
+        if let Some(template) = self.get_template(template_name) {
+            return Ok(Some(template.clone()));
+        }
+        match self.template_files.get(template_name) {
+            Some(path) => Ok(Some(crate::core::system_file::load(path, None)?)),
+            None => Ok(None),
+        }
+    }
+
     pub fn resolve_template_or_prompt(&self, input: &str) -> String {
         if let Some(template_name) = input.strip_prefix("t:") {
-            if let Some(template_content) = self.get_template(template_name) {
-                template_content.clone()
-            } else {
+            match self.template_content(template_name) {
+                Ok(Some(template_content)) => template_content,
                 // If template not found, return the original input
-                input.to_string()
+                Ok(None) => input.to_string(),
+                Err(e) => {
+                    eprintln!("Warning: template '{}': {}", template_name, e);
+                    input.to_string()
+                }
             }
         } else {
-            input.to_string()
+            self.render_prompt(input)
         }
     }
 
+    /// Fill in the ambient variables (`{{date}}`, `{{user.name}}`, `{{var.<name>}}`, ...)
+    /// of a prompt
+    pub fn render_prompt(&self, prompt: &str) -> String {
+        crate::utils::template_processor::render_ambient_vars(prompt, &self.template_vars)
+    }
+
     pub fn parse_max_tokens(input: &str) -> Result<u32> {
         let input = input.to_lowercase();
         if let Some(num_str) = input.strip_suffix('k') {
@@ -903,6 +1185,19 @@ impl Config {
             .map_err(|_| anyhow::anyhow!("Invalid temperature format: '{}'", input))
     }
 
+    /// Parse a spending limit in USD, with or without a leading `$`
+    pub fn parse_usd(input: &str) -> Result<f64> {
+        let value: f64 = input
+            .trim()
+            .trim_start_matches('$')
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid amount: '{}'", input))?;
+        if !value.is_finite() || value <= 0.0 {
+            anyhow::bail!("Spending limit must be greater than 0, got '{}'", input);
+        }
+        Ok(value)
+    }
+
     fn config_file_path() -> Result<PathBuf> {
         let config_dir = Self::config_dir()?;
         Ok(config_dir.join("config.toml"))
@@ -913,6 +1208,11 @@ impl Config {
         Ok(config_dir.join("providers"))
     }
 
+    /// Path of a provider's config file in the providers directory
+    pub fn provider_file_path(provider_name: &str) -> Result<PathBuf> {
+        Ok(Self::providers_dir()?.join(format!("{}.toml", provider_name)))
+    }
+
     pub fn config_dir() -> Result<PathBuf> {
         // Check for explicit test environment override first (highest priority)
         if let Ok(test_dir) = std::env::var("LC_TEST_CONFIG_DIR") {
@@ -1051,38 +1351,15 @@ impl Config {
     }
 
     fn save_single_provider_flat_static(
-        providers_dir: &PathBuf,
+        providers_dir: &std::path::Path,
         provider_name: &str,
         provider_config: &ProviderConfig,
     ) -> Result<()> {
-        fs::create_dir_all(providers_dir)?;
-
         let provider_file = providers_dir.join(format!("{}.toml", provider_name));
 
         // Use the new flat format - serialize the ProviderConfig directly
         let content = toml::to_string_pretty(provider_config)?;
-
-        let mut options = OpenOptions::new();
-        options.write(true).create(true).truncate(true);
-
-        #[cfg(unix)]
-        {
-            options.mode(0o600);
-        }
-
-        let mut file = options.open(&provider_file)?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut permissions = file.metadata()?.permissions();
-            permissions.set_mode(0o600);
-            file.set_permissions(permissions)?;
-        }
-
-        file.write_all(content.as_bytes())?;
-
-        Ok(())
+        crate::lock::write_config_file(&provider_file, &content)
     }
 
     pub fn set_token_url(&mut self, provider: String, token_url: String) -> Result<()> {
@@ -1167,6 +1444,17 @@ impl Config {
         }
     }
 
+    pub fn set_provider_rerank_path(&mut self, provider: &str, path: &str) -> Result<()> {
+        if let Some(pc) = self.providers.get_mut(provider) {
+            pc.rerank_path = Some(path.to_string());
+            let config_clone = pc.clone();
+            self.save_single_provider(provider, &config_clone)?;
+            Ok(())
+        } else {
+            anyhow::bail!("Provider '{}' not found", provider);
+        }
+    }
+
     pub fn reset_provider_models_path(&mut self, provider: &str) -> Result<()> {
         if let Some(pc) = self.providers.get_mut(provider) {
             pc.models_path = default_models_path();
@@ -1211,6 +1499,17 @@ impl Config {
         }
     }
 
+    pub fn reset_provider_rerank_path(&mut self, provider: &str) -> Result<()> {
+        if let Some(pc) = self.providers.get_mut(provider) {
+            pc.rerank_path = None;
+            let config_clone = pc.clone();
+            self.save_single_provider(provider, &config_clone)?;
+            Ok(())
+        } else {
+            anyhow::bail!("Provider '{}' not found", provider);
+        }
+    }
+
     pub fn list_provider_paths(&self, provider: &str) -> Result<ProviderPaths> {
         if let Some(pc) = self.providers.get(provider) {
             Ok(ProviderPaths {
@@ -1218,6 +1517,7 @@ impl Config {
                 chat_path: pc.chat_path.clone(),
                 images_path: pc.images_path.clone(),
                 embeddings_path: pc.embeddings_path.clone(),
+                rerank_path: pc.rerank_path.clone(),
             })
         } else {
             anyhow::bail!("Provider '{}' not found", provider);
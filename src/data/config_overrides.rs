@@ -0,0 +1,199 @@
+//! Temporary config overrides with `--set section.key=value`
+//!
+//! Overrides apply to the settings of config.toml for one invocation, e.g.
+//! `lc --set retry.max=0 --set http.timeout=300 ...`. Values are parsed as
+//! TOML (numbers, booleans, arrays) and taken as strings otherwise. They are applied every
+//! time the config is loaded and never written back: when a command saves the config, the
+//! overridden keys keep the values they have in the file.
+
+use anyhow::{bail, Context, Result};
+use std::sync::RwLock;
+use toml::Value;
+
+/// A key path in config.toml and the value it is set to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Override {
+    pub path: Vec<String>,
+    pub value: Value,
+}
+
+impl Override {
+    /// The dotted key, as given on the command line
+    pub fn key(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+static CURRENT: RwLock<Vec<Override>> = RwLock::new(Vec::new());
+
+/// Set the overrides for this invocation
+pub fn set(overrides: Vec<Override>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = overrides;
+    }
+}
+
+/// The overrides of this invocation
+pub fn current() -> Vec<Override> {
+    CURRENT
+        .read()
+        .map(|current| current.clone())
+        .unwrap_or_default()
+}
+
+/// Parse `section.key=value` entries from `--set`
+pub fn parse_entries(entries: &[String]) -> Result<Vec<Override>> {
+    entries.iter().map(|entry| parse(entry)).collect()
+}
+
+fn parse(entry: &str) -> Result<Override> {
+    let Some((key, value)) = entry.split_once('=') else {
+        bail!("Invalid --set '{}': expected 'section.key=value'", entry);
+    };
+    let path: Vec<String> = key
+        .trim()
+        .split('.')
+        .map(|s| s.trim().to_string())
+        .collect();
+    if path.iter().any(|segment| segment.is_empty()) {
+        bail!("Invalid --set '{}': empty key", entry);
+    }
+    if path[0] == "providers" {
+        bail!(
+            "Invalid --set '{}': provider settings can't be overridden, only config.toml settings",
+            entry
+        );
+    }
+    let value = value.trim();
+    // A bare word such as `gpt-4o` is not valid TOML and is taken as a string
+    let value = toml::from_str::<toml::Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_string()));
+    Ok(Override { path, value })
+}
+
+/// Set each override's key in a serialized config, creating the sections it is in
+pub fn apply(config: &mut Value, overrides: &[Override]) -> Result<()> {
+    for o in overrides {
+        let (last, sections) = o.path.split_last().context("Empty override key")?;
+        let mut table = config.as_table_mut().context("The config is not a table")?;
+        for section in sections {
+            table = table
+                .entry(section.clone())
+                .or_insert_with(|| Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("'{}' in --set {} is not a section", section, o.key()))?;
+        }
+        table.insert(last.clone(), o.value.clone());
+    }
+    Ok(())
+}
+
+/// Check that every overridden key is a config setting, given the config after the
+/// overrides were applied and serialized again (unknown keys are dropped on the way)
+pub fn check_known(config: &Value, overrides: &[Override]) -> Result<()> {
+    for o in overrides {
+        if get(config, &o.path).is_none() {
+            bail!("Unknown config key '{}' in --set", o.key());
+        }
+    }
+    Ok(())
+}
+
+/// Put back the values the overridden keys have in `original` (the config file), or
+/// remove the keys it doesn't have
+pub fn restore(config: &mut Value, original: &Value, overrides: &[Override]) {
+    for o in overrides {
+        let Some((last, sections)) = o.path.split_last() else {
+            continue;
+        };
+        let mut table = config.as_table_mut();
+        for section in sections {
+            table = table
+                .and_then(|t| t.get_mut(section))
+                .and_then(Value::as_table_mut);
+        }
+        let Some(table) = table else {
+            continue;
+        };
+        match get(original, &o.path) {
+            Some(value) => {
+                table.insert(last.clone(), value.clone());
+            }
+            None => {
+                table.remove(last);
+            }
+        }
+    }
+}
+
+fn get<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(value, |value, segment| value.as_table()?.get(segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_values() {
+        let overrides = parse_entries(&[
+            "limits.daily_usd=20".to_string(),
+            "web_cache.enabled = false".to_string(),
+            "default_model=gpt-4o-mini".to_string(),
+            "redaction.patterns=[\"sk-[a-z]+\"]".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(overrides[0].path, vec!["limits", "daily_usd"]);
+        assert_eq!(overrides[0].value, Value::Integer(20));
+        assert_eq!(overrides[1].value, Value::Boolean(false));
+        assert_eq!(overrides[2].value, Value::String("gpt-4o-mini".to_string()));
+        assert!(overrides[3].value.is_array());
+
+        assert!(parse("limits.daily_usd").is_err());
+        assert!(parse("limits..daily_usd=1").is_err());
+        assert!(parse("providers.openai.endpoint=http://x").is_err());
+    }
+
+    #[test]
+    fn test_apply_and_restore() {
+        let original: Value =
+            toml::from_str("max_tokens = 100\n[time]\ntimezone = \"UTC\"\n").unwrap();
+        let overrides = parse_entries(&[
+            "max_tokens=5".to_string(),
+            "web_cache.ttl_minutes=0".to_string(),
+        ])
+        .unwrap();
+
+        let mut config = original.clone();
+        apply(&mut config, &overrides).unwrap();
+        assert_eq!(config["max_tokens"], Value::Integer(5));
+        assert_eq!(config["web_cache"]["ttl_minutes"], Value::Integer(0));
+        check_known(&config, &overrides).unwrap();
+        assert!(check_known(&original, &overrides).is_err());
+
+        restore(&mut config, &original, &overrides);
+        assert_eq!(config["max_tokens"], Value::Integer(100));
+        assert!(config["web_cache"].as_table().unwrap().is_empty());
+        assert_eq!(config["time"]["timezone"], Value::String("UTC".to_string()));
+
+        let overrides = parse_entries(&["max_tokens.x=1".to_string()]).unwrap();
+        assert!(apply(&mut config, &overrides).is_err());
+    }
+
+    #[test]
+    fn test_retry_and_http_keys() {
+        let config: crate::config::Config = toml::from_str("[providers]\n").unwrap();
+        let overrides =
+            parse_entries(&["retry.max=0".to_string(), "http.timeout=300".to_string()]).unwrap();
+
+        let mut value = Value::try_from(&config).unwrap();
+        apply(&mut value, &overrides).unwrap();
+        let config: crate::config::Config = value.try_into().unwrap();
+        assert_eq!(config.retry.max, Some(0));
+        assert_eq!(config.http.timeout, Some(300));
+        check_known(&Value::try_from(&config).unwrap(), &overrides).unwrap();
+    }
+}
@@ -1,8 +1,42 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
-use std::path::PathBuf;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a connection waits for another lc process to release a SQLite lock
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Open a SQLite database with a busy timeout, so concurrent lc invocations wait
+/// for each other instead of failing with "database is locked". Under `--read-only`
+/// the database is opened read-only and is never created.
+pub fn open_connection(path: &Path) -> Result<Connection> {
+    let conn = if crate::lock::is_read_only() {
+        if !path.exists() {
+            anyhow::bail!(
+                "Database '{}' does not exist and cannot be created in read-only mode",
+                path.display()
+            );
+        }
+        Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?
+    } else {
+        Connection::open(path)?
+    };
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    Ok(conn)
+}
+
+/// The first 8 characters of a session ID, as listings show it
+pub fn short_id(chat_id: &str) -> &str {
+    match chat_id.char_indices().nth(8) {
+        Some((end, _)) => &chat_id[..end],
+        None => chat_id,
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ChatEntry {
@@ -13,6 +47,188 @@ pub struct ChatEntry {
     pub timestamp: DateTime<Utc>,
     pub input_tokens: Option<i32>,
     pub output_tokens: Option<i32>,
+    pub provider: Option<String>,
+}
+
+/// A log entry with the `user`, `--metadata`, system prompt and images its request was
+/// sent with, and the reasoning trace of the answer
+#[derive(Debug, Clone)]
+pub struct LoggedChatEntry {
+    pub entry: ChatEntry,
+    pub user: Option<String>,
+    pub metadata: std::collections::BTreeMap<String, String>,
+    pub system_prompt: Option<String>,
+    pub reasoning: Option<String>,
+    /// Local image files attached to the question, as absolute paths
+    pub images: Vec<String>,
+}
+
+/// What is logged with a turn besides the exchange itself
+#[derive(Debug, Clone, Default)]
+pub struct LogDetails {
+    /// The `user` the request was sent with
+    pub user: Option<String>,
+    /// `--metadata`, and lc's own entries such as the routing decision
+    pub metadata: std::collections::BTreeMap<String, String>,
+    /// The system prompt the request was sent with
+    pub system_prompt: Option<String>,
+    /// The reasoning trace of the answer
+    pub reasoning: Option<String>,
+    /// Local image files attached to the question, as absolute paths
+    pub images: Vec<String>,
+}
+
+/// Which log entries `lc logs show` and `lc logs recent` list; every filter is applied in SQL
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    pub chat_id: Option<String>,
+    pub provider: Option<String>,
+    /// A model name, or `provider:model`
+    pub model: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Text the question or answer contains, ignoring ASCII case
+    pub grep: Option<String>,
+    /// Only the newest entries, at most this many
+    pub limit: Option<usize>,
+}
+
+impl LogQuery {
+    /// Whether any filter beyond the session and limit is set
+    pub fn is_filtered(&self) -> bool {
+        self.provider.is_some()
+            || self.model.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+            || self.grep.is_some()
+    }
+}
+
+/// Overview of a chat session
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionInfo {
+    pub chat_id: String,
+    pub started: DateTime<Utc>,
+    pub last_active: DateTime<Utc>,
+    pub turns: i64,
+    pub first_question: String,
+    /// Bytes of text logged for the session (questions, answers, prompts, reasoning)
+    pub size_bytes: i64,
+    /// Name given with `lc chat --name`
+    pub name: Option<String>,
+}
+
+/// A tool call made during a chat session
+#[derive(Debug, Clone)]
+pub struct ToolCallEntry {
+    pub session_id: String,
+    pub server: String,
+    pub tool: String,
+    /// Answered from the session's tool cache
+    pub cached: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A whole session as `lc logs export --format json` writes it and `lc logs import`
+/// (and `lc logs prune` archives) read it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SessionArchive {
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub started: DateTime<Utc>,
+    pub last_active: DateTime<Utc>,
+    pub turns: Vec<ArchivedTurn>,
+    #[serde(default)]
+    pub tool_calls: Vec<ArchivedToolCall>,
+}
+
+/// One logged question and answer of a [`SessionArchive`]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedTurn {
+    pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    pub model: String,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    pub question: String,
+    pub response: String,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    #[serde(default)]
+    pub input_tokens: Option<i32>,
+    #[serde(default)]
+    pub output_tokens: Option<i32>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub metadata: std::collections::BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedToolCall {
+    pub timestamp: DateTime<Utc>,
+    pub server: String,
+    pub tool: String,
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// Tools and vector database a session was started with, re-applied by `-c`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSettings {
+    pub tools: Option<String>,
+    pub vectordb: Option<String>,
+}
+
+/// Session names are used on the command line (`--cid name:<name>`), so they are kept
+/// to letters, digits, `-`, `_` and `.`
+pub fn validate_session_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        anyhow::bail!(
+            "Invalid session name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// The `images` column of a turn: a JSON array, or NULL without images
+fn images_json(images: &[String]) -> Option<String> {
+    if images.is_empty() {
+        None
+    } else {
+        serde_json::to_string(images).ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestTimingEntry {
+    pub provider: String,
+    pub model: String,
+    pub timestamp: DateTime<Utc>,
+    pub dns_ms: Option<i64>,
+    pub ttfb_ms: Option<i64>,
+    pub first_token_ms: Option<i64>,
+    pub total_ms: i64,
+    pub output_tokens: Option<i32>,
+    pub retries: i32,
+}
+
+/// Tokens logged for one provider and model
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenUsage {
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
 }
 
 #[derive(Debug)]
@@ -37,7 +253,7 @@ impl ConnectionPool {
 
         // Pre-create initial connections
         for _ in 0..std::cmp::min(2, max_connections) {
-            let conn = Connection::open(&db_path)?;
+            let conn = open_connection(&db_path)?;
             Self::configure_connection(&conn)?;
             connections.push(conn);
         }
@@ -50,8 +266,10 @@ impl ConnectionPool {
     }
 
     fn configure_connection(conn: &Connection) -> Result<()> {
-        // Enable WAL mode for better concurrent performance
-        conn.pragma_update(None, "journal_mode", "WAL")?;
+        // Enable WAL mode so readers don't block the writer (persistent, set by writers only)
+        if !crate::lock::is_read_only() {
+            conn.pragma_update(None, "journal_mode", "WAL")?;
+        }
         // Increase cache size for better performance
         conn.pragma_update(None, "cache_size", 10000)?;
         // Enable foreign keys
@@ -74,7 +292,7 @@ impl ConnectionPool {
             })
         } else if connections.len() < self.max_connections {
             // Create new connection if under limit
-            let conn = Connection::open(&self.db_path)?;
+            let conn = open_connection(&self.db_path)?;
             Self::configure_connection(&conn)?;
             Ok(PooledConnection {
                 conn: Some(conn),
@@ -84,7 +302,7 @@ impl ConnectionPool {
             // Wait for a connection to become available
             // In a real implementation, you might want to use a condition variable
             // For now, create a new temporary connection
-            let conn = Connection::open(&self.db_path)?;
+            let conn = open_connection(&self.db_path)?;
             Self::configure_connection(&conn)?;
             Ok(PooledConnection {
                 conn: Some(conn),
@@ -146,8 +364,10 @@ impl Database {
         let pool = ConnectionPool::new(db_path, 5)?; // Max 5 connections
 
         // Initialize database schema
-        let conn = pool.get_connection()?;
-        Self::initialize_schema(&conn)?;
+        if !crate::lock::is_read_only() {
+            let conn = pool.get_connection()?;
+            Self::initialize_schema(&conn)?;
+        }
 
         Ok(Database { pool })
     }
@@ -171,6 +391,29 @@ impl Database {
         // Add token columns to existing table if they don't exist (migration)
         let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN input_tokens INTEGER", []);
         let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN output_tokens INTEGER", []);
+        let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN provider TEXT", []);
+        let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN user TEXT", []);
+        let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN metadata TEXT", []);
+        let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN reasoning TEXT", []);
+        let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN system_prompt TEXT", []);
+        let _ = conn.execute("ALTER TABLE chat_logs ADD COLUMN images TEXT", []);
+
+        // Create request_timings table for latency tracking (--timings)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS request_timings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+                dns_ms INTEGER,
+                ttfb_ms INTEGER,
+                first_token_ms INTEGER,
+                total_ms INTEGER NOT NULL,
+                output_tokens INTEGER,
+                retries INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
 
         // Create session_state table for tracking current session
         conn.execute(
@@ -181,6 +424,55 @@ impl Database {
             [],
         )?;
 
+        // Create session_settings table for the tools and vector DB of each session
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_settings (
+                session_id TEXT PRIMARY KEY,
+                tools TEXT,
+                vectordb TEXT
+            )",
+            [],
+        )?;
+        // Name given with `lc chat --name`, unique across sessions
+        let _ = conn.execute("ALTER TABLE session_settings ADD COLUMN name TEXT", []);
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_session_settings_name ON session_settings(name)",
+            [],
+        )?;
+
+        // Create tool_cache table for repeated tool calls within a session
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_cache (
+                session_id TEXT NOT NULL,
+                cache_key TEXT NOT NULL,
+                server TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                result TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (session_id, cache_key)
+            )",
+            [],
+        )?;
+
+        // Create tool_calls table for per-session summaries
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_calls (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                server TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                cached INTEGER NOT NULL DEFAULT 0,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tool_calls_session_id ON tool_calls(session_id)",
+            [],
+        )?;
+
         // Create optimized indexes for better performance
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_chat_logs_chat_id ON chat_logs(chat_id)",
@@ -201,25 +493,343 @@ impl Database {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn save_chat_entry_with_tokens(
         &self,
         chat_id: &str,
+        provider: &str,
         model: &str,
         question: &str,
         response: &str,
         input_tokens: Option<i32>,
         output_tokens: Option<i32>,
     ) -> Result<()> {
+        self.save_chat_entry_with_details(
+            chat_id,
+            provider,
+            model,
+            question,
+            response,
+            input_tokens,
+            output_tokens,
+            &LogDetails::default(),
+        )
+    }
+
+    /// Log a turn with the user, metadata, system prompt, images and reasoning of its request
+    #[allow(clippy::too_many_arguments)]
+    pub fn save_chat_entry_with_details(
+        &self,
+        chat_id: &str,
+        provider: &str,
+        model: &str,
+        question: &str,
+        response: &str,
+        input_tokens: Option<i32>,
+        output_tokens: Option<i32>,
+        details: &LogDetails,
+    ) -> Result<()> {
+        crate::lock::ensure_writable("log chats")?;
         let conn = self.pool.get_connection()?;
 
+        let user = details.user.as_deref();
+        let metadata = crate::core::request_metadata::metadata_json(&details.metadata);
+
+        // Secrets the model echoed back are not kept in the history
+        use crate::core::redaction::redact;
+        let question = redact(question);
+        let response = redact(response);
+        // Kept apart from the response so it is never replayed as history
+        let reasoning = details.reasoning.as_deref().map(redact);
+        let system_prompt = details
+            .system_prompt
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .map(redact);
+        let images = images_json(&details.images);
+
         conn.execute(
-            "INSERT INTO chat_logs (chat_id, model, question, response, timestamp, input_tokens, output_tokens)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![chat_id, model, question, response, Utc::now(), input_tokens, output_tokens]
+            "INSERT INTO chat_logs (chat_id, model, question, response, timestamp, input_tokens, output_tokens, provider, user, metadata, reasoning, system_prompt, images)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![chat_id, model, question, response, Utc::now(), input_tokens, output_tokens, provider, user, metadata, reasoning, system_prompt, images]
         )?;
         Ok(())
     }
 
+    /// A session with everything logged for it, ready to be written out
+    pub fn export_session(&self, chat_id: &str) -> Result<SessionArchive> {
+        let turns: Vec<ArchivedTurn> = self
+            .get_session_logs(chat_id)?
+            .into_iter()
+            .map(|logged| ArchivedTurn {
+                timestamp: logged.entry.timestamp,
+                provider: logged.entry.provider,
+                model: logged.entry.model,
+                system_prompt: logged.system_prompt,
+                question: logged.entry.question,
+                response: logged.entry.response,
+                reasoning: logged.reasoning,
+                input_tokens: logged.entry.input_tokens,
+                output_tokens: logged.entry.output_tokens,
+                user: logged.user,
+                metadata: logged.metadata,
+                images: logged.images,
+            })
+            .collect();
+        let (Some(first), Some(last)) = (turns.first(), turns.last()) else {
+            anyhow::bail!("Session '{}' has no log entries", chat_id);
+        };
+        let (started, last_active) = (first.timestamp, last.timestamp);
+        let tool_calls = self
+            .get_tool_calls(chat_id)?
+            .into_iter()
+            .map(|call| ArchivedToolCall {
+                timestamp: call.timestamp,
+                server: call.server,
+                tool: call.tool,
+                cached: call.cached,
+            })
+            .collect();
+        Ok(SessionArchive {
+            session_id: chat_id.to_string(),
+            name: self.get_session_name(chat_id)?,
+            started,
+            last_active,
+            turns,
+            tool_calls,
+        })
+    }
+
+    /// Restore an exported session under its own ID, with its original timestamps.
+    /// Fails if the ID is not a UUID or the log already has entries for that session.
+    pub fn import_session(&self, archive: &SessionArchive) -> Result<()> {
+        crate::lock::ensure_writable("import sessions")?;
+        if uuid::Uuid::parse_str(&archive.session_id).is_err() {
+            anyhow::bail!("Session ID '{}' is not a UUID", archive.session_id);
+        }
+        if archive.turns.is_empty() {
+            anyhow::bail!("Session '{}' has no turns", archive.session_id);
+        }
+        let conn = self.pool.get_connection()?;
+        let existing: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM chat_logs WHERE chat_id = ?1",
+            [&archive.session_id],
+            |row| row.get(0),
+        )?;
+        if existing > 0 {
+            anyhow::bail!(
+                "Session '{}' is already in the log ({} entries)",
+                archive.session_id,
+                existing
+            );
+        }
+
+        conn.execute("BEGIN TRANSACTION", [])?;
+        match (|| -> Result<()> {
+            for turn in &archive.turns {
+                let metadata = crate::core::request_metadata::metadata_json(&turn.metadata);
+                let images = images_json(&turn.images);
+                conn.execute(
+                    "INSERT INTO chat_logs (chat_id, model, question, response, timestamp, input_tokens, output_tokens, provider, user, metadata, reasoning, system_prompt, images)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    params![archive.session_id, turn.model, turn.question, turn.response, turn.timestamp, turn.input_tokens, turn.output_tokens, turn.provider, turn.user, metadata, turn.reasoning, turn.system_prompt, images],
+                )?;
+            }
+            // The name is kept unless another session here already has it
+            if let Some(name) = &archive.name {
+                conn.execute(
+                    "INSERT OR IGNORE INTO session_settings (session_id, name)
+                     SELECT ?1, ?2 WHERE NOT EXISTS (SELECT 1 FROM session_settings WHERE name = ?2)",
+                    params![archive.session_id, name],
+                )?;
+            }
+            for call in &archive.tool_calls {
+                conn.execute(
+                    "INSERT INTO tool_calls (session_id, server, tool, cached, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        archive.session_id,
+                        call.server,
+                        call.tool,
+                        call.cached,
+                        call.timestamp
+                    ],
+                )?;
+            }
+            Ok(())
+        })() {
+            Ok(_) => {
+                conn.execute("COMMIT", [])?;
+                Ok(())
+            }
+            Err(e) => {
+                conn.execute("ROLLBACK", [])?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Reasoning trace stored with the most recent log entry
+    pub fn get_last_reasoning(&self) -> Result<Option<String>> {
+        let conn = self.pool.get_connection()?;
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let reasoning = conn_ref
+            .query_row(
+                "SELECT reasoning FROM chat_logs ORDER BY timestamp DESC, id DESC LIMIT 1",
+                [],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?;
+        Ok(reasoning.flatten())
+    }
+
+    /// All log entries (newest first) with the user and metadata they were sent with
+    pub fn get_logs_with_metadata(&self) -> Result<Vec<LoggedChatEntry>> {
+        self.query_logged_entries("ORDER BY timestamp DESC", [])
+    }
+
+    /// The `limit` newest entries (newest first) with everything logged alongside them
+    pub fn get_recent_logged_entries(&self, limit: usize) -> Result<Vec<LoggedChatEntry>> {
+        self.query_logged_entries("ORDER BY timestamp DESC, id DESC LIMIT ?1", [limit as i64])
+    }
+
+    /// Entries matching `query` (newest first) with everything logged alongside them
+    pub fn query_logs(&self, query: &LogQuery) -> Result<Vec<LoggedChatEntry>> {
+        let mut conditions: Vec<String> = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        // `?` in a condition is the value it was pushed with
+        let mut push = |condition: &str, value: Box<dyn rusqlite::ToSql>| {
+            params.push(value);
+            conditions.push(condition.replace('?', &format!("?{}", params.len())));
+        };
+
+        if let Some(chat_id) = &query.chat_id {
+            push("chat_id = ?", Box::new(chat_id.clone()));
+        }
+        if let Some(provider) = &query.provider {
+            push("provider = ?", Box::new(provider.clone()));
+        }
+        if let Some(model) = &query.model {
+            push(
+                "(model = ? OR provider || ':' || model = ?)",
+                Box::new(model.clone()),
+            );
+        }
+        if let Some(since) = query.since {
+            push("timestamp >= ?", Box::new(since));
+        }
+        if let Some(until) = query.until {
+            push("timestamp <= ?", Box::new(until));
+        }
+        if let Some(grep) = &query.grep {
+            let escaped = grep
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            push(
+                "(question LIKE ? ESCAPE '\\' OR response LIKE ? ESCAPE '\\')",
+                Box::new(format!("%{}%", escaped)),
+            );
+        }
+        if let Some(limit) = query.limit {
+            params.push(Box::new(limit as i64));
+        }
+
+        let mut clause = String::new();
+        if !conditions.is_empty() {
+            clause.push_str(&format!("WHERE {} ", conditions.join(" AND ")));
+        }
+        clause.push_str("ORDER BY timestamp DESC, id DESC");
+        if query.limit.is_some() {
+            clause.push_str(&format!(" LIMIT ?{}", params.len()));
+        }
+        self.query_logged_entries(&clause, rusqlite::params_from_iter(params))
+    }
+
+    /// Entries of one session (oldest first) with everything logged alongside them
+    pub fn get_session_logs(&self, chat_id: &str) -> Result<Vec<LoggedChatEntry>> {
+        self.query_logged_entries(
+            "WHERE chat_id = ?1 ORDER BY timestamp ASC, id ASC",
+            [chat_id],
+        )
+    }
+
+    /// Full session ID for a unique prefix, such as the 8 characters `lc logs show` prints
+    pub fn resolve_session_id(&self, prefix: &str) -> Result<String> {
+        let conn = self.pool.get_connection()?;
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let mut stmt = conn_ref.prepare(
+            "SELECT DISTINCT chat_id FROM chat_logs WHERE substr(chat_id, 1, length(?1)) = ?1 LIMIT 2",
+        )?;
+        let matches = stmt
+            .query_map([prefix], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        match matches.as_slice() {
+            [chat_id] => Ok(chat_id.clone()),
+            [] => anyhow::bail!("No session found matching '{}'", prefix),
+            _ => anyhow::bail!(
+                "Session prefix '{}' is ambiguous; use more characters",
+                prefix
+            ),
+        }
+    }
+
+    fn query_logged_entries<P: rusqlite::Params>(
+        &self,
+        clause: &str,
+        params: P,
+    ) -> Result<Vec<LoggedChatEntry>> {
+        let conn = self.pool.get_connection()?;
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let mut stmt = conn_ref.prepare(&format!(
+            "SELECT chat_id, model, question, response, timestamp, input_tokens, output_tokens, provider, user, metadata, system_prompt, reasoning, images
+             FROM chat_logs
+             {}",
+            clause
+        ))?;
+
+        let rows = stmt.query_map(params, |row| {
+            let metadata: Option<String> = row.get(9)?;
+            let images: Option<String> = row.get(12)?;
+            Ok(LoggedChatEntry {
+                entry: ChatEntry {
+                    chat_id: row.get(0)?,
+                    model: row.get(1)?,
+                    question: row.get(2)?,
+                    response: row.get(3)?,
+                    timestamp: row.get(4)?,
+                    input_tokens: row.get(5).ok(),
+                    output_tokens: row.get(6).ok(),
+                    provider: row.get(7).ok(),
+                },
+                user: row.get(8)?,
+                metadata: metadata
+                    .and_then(|m| serde_json::from_str(&m).ok())
+                    .unwrap_or_default(),
+                system_prompt: row.get(10)?,
+                reasoning: row.get(11)?,
+                images: images
+                    .and_then(|i| serde_json::from_str(&i).ok())
+                    .unwrap_or_default(),
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        Ok(entries)
+    }
+
     pub fn get_chat_history(&self, chat_id: &str) -> Result<Vec<ChatEntry>> {
         let conn = self.pool.get_connection()?;
 
@@ -228,7 +838,7 @@ impl Database {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
         let mut stmt = conn_ref.prepare(
-            "SELECT id, chat_id, model, question, response, timestamp, input_tokens, output_tokens
+            "SELECT id, chat_id, model, question, response, timestamp, input_tokens, output_tokens, provider
              FROM chat_logs
              WHERE chat_id = ?1
              ORDER BY timestamp ASC",
@@ -243,6 +853,7 @@ impl Database {
                 timestamp: row.get(5)?,
                 input_tokens: row.get(6).ok(),
                 output_tokens: row.get(7).ok(),
+                provider: row.get(8).ok(),
             })
         })?;
 
@@ -254,24 +865,91 @@ impl Database {
         Ok(entries)
     }
 
+    /// Sessions ordered by most recent activity
+    pub fn list_sessions(&self, limit: usize) -> Result<Vec<SessionInfo>> {
+        let conn = self.pool.get_connection()?;
+
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let mut stmt = conn_ref.prepare(
+            "SELECT c.chat_id, MIN(c.timestamp), MAX(c.timestamp), COUNT(*),
+                    (SELECT question FROM chat_logs f WHERE f.chat_id = c.chat_id ORDER BY f.id ASC LIMIT 1),
+                    SUM(LENGTH(CAST(question AS BLOB)) + LENGTH(CAST(response AS BLOB))
+                        + COALESCE(LENGTH(CAST(system_prompt AS BLOB)), 0)
+                        + COALESCE(LENGTH(CAST(reasoning AS BLOB)), 0)),
+                    (SELECT name FROM session_settings s WHERE s.session_id = c.chat_id)
+             FROM chat_logs c
+             GROUP BY c.chat_id
+             ORDER BY MAX(c.timestamp) DESC, MAX(c.id) DESC
+             LIMIT ?1",
+        )?;
+
+        let rows = stmt.query_map([limit as i64], |row| {
+            Ok(SessionInfo {
+                chat_id: row.get(0)?,
+                started: row.get(1)?,
+                last_active: row.get(2)?,
+                turns: row.get(3)?,
+                first_question: row.get(4)?,
+                size_bytes: row.get(5)?,
+                name: row.get(6)?,
+            })
+        })?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+        Ok(sessions)
+    }
+
     // Optimized version with LIMIT for better performance on large datasets
     pub fn get_all_logs(&self) -> Result<Vec<ChatEntry>> {
         self.get_recent_logs(None)
     }
 
+    /// Tokens logged since `since`, summed per provider and model. Entries logged
+    /// without a provider are left out.
+    pub fn token_usage_since(&self, since: DateTime<Utc>) -> Result<Vec<TokenUsage>> {
+        let conn = self.pool.get_connection()?;
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let mut stmt = conn_ref.prepare(
+            "SELECT provider, model, COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0)
+             FROM chat_logs
+             WHERE timestamp >= ?1 AND provider IS NOT NULL
+             GROUP BY provider, model",
+        )?;
+        let usage = stmt
+            .query_map([since], |row| {
+                Ok(TokenUsage {
+                    provider: row.get(0)?,
+                    model: row.get(1)?,
+                    input_tokens: row.get(2)?,
+                    output_tokens: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(usage)
+    }
+
     pub fn get_recent_logs(&self, limit: Option<usize>) -> Result<Vec<ChatEntry>> {
         let conn = self.pool.get_connection()?;
 
         let sql = if let Some(limit) = limit {
             format!(
-                "SELECT id, chat_id, model, question, response, timestamp, input_tokens, output_tokens
+                "SELECT id, chat_id, model, question, response, timestamp, input_tokens, output_tokens, provider
                  FROM chat_logs
                  ORDER BY timestamp DESC
                  LIMIT {}",
                 limit
             )
         } else {
-            "SELECT id, chat_id, model, question, response, timestamp, input_tokens, output_tokens
+            "SELECT id, chat_id, model, question, response, timestamp, input_tokens, output_tokens, provider
              FROM chat_logs
              ORDER BY timestamp DESC"
                 .to_string()
@@ -292,6 +970,68 @@ impl Database {
                 timestamp: row.get(5)?,
                 input_tokens: row.get(6).ok(),
                 output_tokens: row.get(7).ok(),
+                provider: row.get(8).ok(),
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        Ok(entries)
+    }
+
+    pub fn save_request_timing(&self, entry: &RequestTimingEntry) -> Result<()> {
+        crate::lock::ensure_writable("record request timings")?;
+        let conn = self.pool.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO request_timings (provider, model, timestamp, dns_ms, ttfb_ms, first_token_ms, total_ms, output_tokens, retries)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                entry.provider,
+                entry.model,
+                entry.timestamp,
+                entry.dns_ms,
+                entry.ttfb_ms,
+                entry.first_token_ms,
+                entry.total_ms,
+                entry.output_tokens,
+                entry.retries
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_request_timings(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<RequestTimingEntry>> {
+        let conn = self.pool.get_connection()?;
+
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let mut stmt = conn_ref.prepare(
+            "SELECT provider, model, timestamp, dns_ms, ttfb_ms, first_token_ms, total_ms, output_tokens, retries
+             FROM request_timings
+             WHERE ?1 IS NULL OR timestamp >= ?1
+             ORDER BY timestamp ASC",
+        )?;
+
+        let rows = stmt.query_map([since], |row| {
+            Ok(RequestTimingEntry {
+                provider: row.get(0)?,
+                model: row.get(1)?,
+                timestamp: row.get(2)?,
+                dns_ms: row.get(3)?,
+                ttfb_ms: row.get(4)?,
+                first_token_ms: row.get(5)?,
+                total_ms: row.get(6)?,
+                output_tokens: row.get(7)?,
+                retries: row.get(8)?,
             })
         })?;
 
@@ -304,15 +1044,230 @@ impl Database {
     }
 
     pub fn set_current_session_id(&self, session_id: &str) -> Result<()> {
+        crate::lock::ensure_writable("change the current session")?;
         let conn = self.pool.get_connection()?;
 
         conn.execute(
             "INSERT OR REPLACE INTO session_state (key, value) VALUES ('current_session', ?1)",
             [session_id],
         )?;
+        // Cached tool results only live as long as their session
+        conn.execute(
+            "DELETE FROM tool_cache WHERE session_id != ?1",
+            [session_id],
+        )?;
         Ok(())
     }
 
+    /// Current session ID, starting a new session if there is none
+    pub fn current_or_new_session_id(&self) -> Result<String> {
+        if let Some(id) = self.get_current_session_id()? {
+            crate::debug_log!("Using existing session: {}", id);
+            return Ok(id);
+        }
+        let new_session_id = crate::core::deterministic::new_id();
+        self.set_current_session_id(&new_session_id)?;
+        crate::debug_log!("Created new session: {}", new_session_id);
+        Ok(new_session_id)
+    }
+
+    /// Remember the tools and vector database of a session; values that are `None`
+    /// keep what was stored before
+    pub fn save_session_settings(
+        &self,
+        session_id: &str,
+        settings: &SessionSettings,
+    ) -> Result<()> {
+        crate::lock::ensure_writable("save session settings")?;
+        let conn = self.pool.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO session_settings (session_id, tools, vectordb) VALUES (?1, ?2, ?3)
+             ON CONFLICT(session_id) DO UPDATE SET
+                 tools = COALESCE(excluded.tools, tools),
+                 vectordb = COALESCE(excluded.vectordb, vectordb)",
+            params![session_id, settings.tools, settings.vectordb],
+        )?;
+        Ok(())
+    }
+
+    /// Name a session; a name belongs to one session at a time
+    pub fn set_session_name(&self, session_id: &str, name: &str) -> Result<()> {
+        crate::lock::ensure_writable("name the session")?;
+        validate_session_name(name)?;
+        if let Some(owner) = self.find_session_by_name(name)? {
+            if owner != session_id {
+                anyhow::bail!(
+                    "The name '{}' is already used by session {}",
+                    name,
+                    short_id(&owner)
+                );
+            }
+        }
+        let conn = self.pool.get_connection()?;
+        conn.execute(
+            "INSERT INTO session_settings (session_id, name) VALUES (?1, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET name = excluded.name",
+            params![session_id, name],
+        )?;
+        Ok(())
+    }
+
+    /// The session with this name, if any
+    pub fn find_session_by_name(&self, name: &str) -> Result<Option<String>> {
+        let conn = self.pool.get_connection()?;
+        Ok(conn
+            .query_row(
+                "SELECT session_id FROM session_settings WHERE name = ?1",
+                [name],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn get_session_name(&self, session_id: &str) -> Result<Option<String>> {
+        let conn = self.pool.get_connection()?;
+        let name = conn
+            .query_row(
+                "SELECT name FROM session_settings WHERE session_id = ?1",
+                [session_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?;
+        Ok(name.flatten())
+    }
+
+    /// The session ID a `--cid` value refers to: `name:<name>` is looked up by name,
+    /// anything else is an ID
+    pub fn resolve_chat_id(&self, cid: &str) -> Result<String> {
+        match cid.strip_prefix("name:") {
+            Some(name) => self.find_session_by_name(name)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No session named '{}'. Start one with 'lc chat --name {}'",
+                    name,
+                    name
+                )
+            }),
+            None => Ok(cid.to_string()),
+        }
+    }
+
+    pub fn get_session_settings(&self, session_id: &str) -> Result<SessionSettings> {
+        let conn = self.pool.get_connection()?;
+
+        let settings = conn
+            .query_row(
+                "SELECT tools, vectordb FROM session_settings WHERE session_id = ?1",
+                [session_id],
+                |row| {
+                    Ok(SessionSettings {
+                        tools: row.get(0)?,
+                        vectordb: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(settings.unwrap_or_default())
+    }
+
+    pub fn save_tool_call(
+        &self,
+        session_id: &str,
+        server: &str,
+        tool: &str,
+        cached: bool,
+    ) -> Result<()> {
+        crate::lock::ensure_writable("log tool calls")?;
+        let conn = self.pool.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO tool_calls (session_id, server, tool, cached, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session_id, server, tool, cached, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_tool_calls(&self, session_id: &str) -> Result<Vec<ToolCallEntry>> {
+        let conn = self.pool.get_connection()?;
+
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let mut stmt = conn_ref.prepare(
+            "SELECT session_id, server, tool, cached, timestamp
+             FROM tool_calls
+             WHERE session_id = ?1
+             ORDER BY id ASC",
+        )?;
+
+        let rows = stmt.query_map([session_id], |row| {
+            Ok(ToolCallEntry {
+                session_id: row.get(0)?,
+                server: row.get(1)?,
+                tool: row.get(2)?,
+                cached: row.get(3)?,
+                timestamp: row.get(4)?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        Ok(entries)
+    }
+
+    pub fn get_cached_tool_result(
+        &self,
+        session_id: &str,
+        cache_key: &str,
+    ) -> Result<Option<String>> {
+        let conn = self.pool.get_connection()?;
+
+        let result = conn
+            .query_row(
+                "SELECT result FROM tool_cache WHERE session_id = ?1 AND cache_key = ?2",
+                params![session_id, cache_key],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn save_tool_result(
+        &self,
+        session_id: &str,
+        cache_key: &str,
+        server: &str,
+        tool: &str,
+        arguments: &str,
+        result: &str,
+    ) -> Result<()> {
+        crate::lock::ensure_writable("cache tool results")?;
+        let conn = self.pool.get_connection()?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO tool_cache (session_id, cache_key, server, tool, arguments, result)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![session_id, cache_key, server, tool, arguments, result],
+        )?;
+        Ok(())
+    }
+
+    /// Remove cached tool results, optionally only those of one MCP server
+    pub fn clear_tool_cache(&self, server: Option<&str>) -> Result<usize> {
+        crate::lock::ensure_writable("clear the tool cache")?;
+        let conn = self.pool.get_connection()?;
+
+        let removed = match server {
+            Some(server) => conn.execute("DELETE FROM tool_cache WHERE server = ?1", [server])?,
+            None => conn.execute("DELETE FROM tool_cache", [])?,
+        };
+        Ok(removed)
+    }
+
     pub fn get_current_session_id(&self) -> Result<Option<String>> {
         let conn = self.pool.get_connection()?;
 
@@ -332,7 +1287,36 @@ impl Database {
         }
     }
 
+    /// Session IDs ending with `suffix` in the log, the session settings or the current
+    /// session
+    pub fn session_ids_with_suffix(&self, suffix: &str) -> Result<Vec<String>> {
+        let conn = self.pool.get_connection()?;
+        let conn_ref = conn
+            .conn
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection not available"))?;
+        let pattern = format!("%{}", suffix.replace('%', "\\%").replace('_', "\\_"));
+        let mut stmt = conn_ref.prepare(
+            "SELECT chat_id FROM chat_logs WHERE chat_id LIKE ?1 ESCAPE '\\'
+             UNION SELECT session_id FROM session_settings WHERE session_id LIKE ?1 ESCAPE '\\'
+             UNION SELECT value FROM session_state
+                 WHERE key = 'current_session' AND value LIKE ?1 ESCAPE '\\'",
+        )?;
+        let ids = stmt
+            .query_map([pattern], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Write a consistent copy of the database (including WAL contents) to `dest`
+    pub fn snapshot_to(&self, dest: &std::path::Path) -> Result<()> {
+        let conn = self.pool.get_connection()?;
+        conn.execute("VACUUM INTO ?1", [dest.to_string_lossy().as_ref()])?;
+        Ok(())
+    }
+
     pub fn purge_all_logs(&self) -> Result<()> {
+        crate::lock::ensure_writable("purge logs")?;
         let conn = self.pool.get_connection()?;
 
         // Use transaction for atomic operation
@@ -341,6 +1325,9 @@ impl Database {
         match (|| -> Result<()> {
             conn.execute("DELETE FROM chat_logs", [])?;
             conn.execute("DELETE FROM session_state", [])?;
+            conn.execute("DELETE FROM tool_cache", [])?;
+            conn.execute("DELETE FROM tool_calls", [])?;
+            conn.execute("DELETE FROM session_settings", [])?;
             Ok(())
         })() {
             Ok(_) => {
@@ -356,6 +1343,7 @@ impl Database {
 
     /// Purge logs based on age (older than specified days)
     pub fn purge_logs_by_age(&self, days: u32) -> Result<usize> {
+        crate::lock::ensure_writable("purge logs")?;
         let conn = self.pool.get_connection()?;
 
         let cutoff_date = chrono::Utc::now() - chrono::Duration::days(days as i64);
@@ -368,6 +1356,7 @@ impl Database {
 
     /// Purge logs to keep only the most recent N entries
     pub fn purge_logs_keep_recent(&self, keep_count: usize) -> Result<usize> {
+        crate::lock::ensure_writable("purge logs")?;
         let conn = self.pool.get_connection()?;
 
         // First, get the total count
@@ -394,6 +1383,7 @@ impl Database {
 
     /// Purge logs when database size exceeds threshold (in MB)
     pub fn purge_logs_by_size(&self, max_size_mb: u64) -> Result<usize> {
+        crate::lock::ensure_writable("purge logs")?;
         let db_path = Self::database_path()?;
         let current_size = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
 
@@ -457,9 +1447,15 @@ impl Database {
     }
 
     pub fn clear_session(&self, session_id: &str) -> Result<()> {
+        crate::lock::ensure_writable("clear the session")?;
         let conn = self.pool.get_connection()?;
 
         conn.execute("DELETE FROM chat_logs WHERE chat_id = ?1", [session_id])?;
+        conn.execute("DELETE FROM tool_calls WHERE session_id = ?1", [session_id])?;
+        conn.execute(
+            "DELETE FROM session_settings WHERE session_id = ?1",
+            [session_id],
+        )?;
         Ok(())
     }
 
@@ -520,7 +1516,7 @@ impl Database {
         })
     }
 
-    fn database_path() -> Result<PathBuf> {
+    pub fn database_path() -> Result<PathBuf> {
         // Use the same config directory logic as Config::config_dir() for test isolation
         let config_dir = crate::config::Config::config_dir()?;
         std::fs::create_dir_all(&config_dir)?;
@@ -570,6 +1566,7 @@ mod tests {
         // Test saving and retrieving
         db.save_chat_entry_with_tokens(
             "test_session",
+            "test_provider",
             "test_model",
             "test question",
             "test response",
@@ -583,5 +1580,448 @@ mod tests {
         assert_eq!(history[0].question, "test question");
         assert_eq!(history[0].input_tokens, Some(100));
         assert_eq!(history[0].output_tokens, Some(50));
+        assert_eq!(history[0].provider.as_deref(), Some("test_provider"));
+    }
+
+    #[test]
+    fn test_request_timings_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let pool = ConnectionPool::new(db_path, 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        let entry = RequestTimingEntry {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            timestamp: Utc::now(),
+            dns_ms: Some(3),
+            ttfb_ms: Some(250),
+            first_token_ms: Some(300),
+            total_ms: 1200,
+            output_tokens: Some(90),
+            retries: 1,
+        };
+        db.save_request_timing(&entry).unwrap();
+
+        let all = db.get_request_timings(None).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].ttfb_ms, Some(250));
+        assert_eq!(all[0].total_ms, 1200);
+        assert_eq!(all[0].retries, 1);
+
+        let future = Utc::now() + chrono::Duration::hours(1);
+        assert!(db.get_request_timings(Some(future)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_session_settings() {
+        let temp_dir = tempdir().unwrap();
+        let pool = ConnectionPool::new(temp_dir.path().join("test.db"), 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        assert_eq!(
+            db.get_session_settings("s1").unwrap(),
+            SessionSettings::default()
+        );
+        let started = SessionSettings {
+            tools: Some("playwright".to_string()),
+            vectordb: Some("docs".to_string()),
+        };
+        db.save_session_settings("s1", &started).unwrap();
+        assert_eq!(db.get_session_settings("s1").unwrap(), started);
+
+        // A later override replaces only what it sets
+        let override_tools = SessionSettings {
+            tools: Some("fetch".to_string()),
+            vectordb: None,
+        };
+        db.save_session_settings("s1", &override_tools).unwrap();
+        let settings = db.get_session_settings("s1").unwrap();
+        assert_eq!(settings.tools.as_deref(), Some("fetch"));
+        assert_eq!(settings.vectordb.as_deref(), Some("docs"));
+
+        db.clear_session("s1").unwrap();
+        assert_eq!(
+            db.get_session_settings("s1").unwrap(),
+            SessionSettings::default()
+        );
+    }
+
+    #[test]
+    fn test_tool_cache_is_scoped_to_session() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let pool = ConnectionPool::new(db_path, 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        db.set_current_session_id("s1").unwrap();
+        db.save_tool_result("s1", "k1", "fs", "read_file", "{}", "contents")
+            .unwrap();
+        db.save_tool_result("s1", "k2", "fetch", "fetch", "{}", "page")
+            .unwrap();
+        assert_eq!(
+            db.get_cached_tool_result("s1", "k1").unwrap().as_deref(),
+            Some("contents")
+        );
+        assert!(db.get_cached_tool_result("s2", "k1").unwrap().is_none());
+
+        assert_eq!(db.clear_tool_cache(Some("fetch")).unwrap(), 1);
+        assert!(db.get_cached_tool_result("s1", "k2").unwrap().is_none());
+
+        // Starting a new session drops the old session's results
+        db.set_current_session_id("s2").unwrap();
+        assert!(db.get_cached_tool_result("s1", "k1").unwrap().is_none());
+        assert_eq!(db.clear_tool_cache(None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_tool_calls_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let pool = ConnectionPool::new(db_path, 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        let session_id = db.current_or_new_session_id().unwrap();
+        assert_eq!(db.current_or_new_session_id().unwrap(), session_id);
+
+        db.save_tool_call(&session_id, "fs", "read_file", false)
+            .unwrap();
+        db.save_tool_call(&session_id, "fs", "read_file", true)
+            .unwrap();
+        db.save_tool_call("other", "fetch", "fetch", false).unwrap();
+
+        let calls = db.get_tool_calls(&session_id).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert!(!calls[0].cached);
+        assert!(calls[1].cached);
+
+        db.clear_session(&session_id).unwrap();
+        assert!(db.get_tool_calls(&session_id).unwrap().is_empty());
+        assert_eq!(db.get_tool_calls("other").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_token_usage_since() {
+        let temp_dir = tempdir().unwrap();
+        let pool = ConnectionPool::new(temp_dir.path().join("test.db"), 3).unwrap();
+        let db = Database { pool };
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        let start = Utc::now();
+        db.save_chat_entry_with_tokens("s1", "openai", "gpt-4o", "q", "a", Some(10), Some(5))
+            .unwrap();
+        db.save_chat_entry_with_tokens("s1", "openai", "gpt-4o", "q", "a", Some(20), None)
+            .unwrap();
+        db.save_chat_entry_with_tokens("s2", "ollama", "llama3", "q", "a", Some(1), Some(2))
+            .unwrap();
+
+        let mut usage = db.token_usage_since(start).unwrap();
+        usage.sort_by(|a, b| a.provider.cmp(&b.provider));
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[1].model, "gpt-4o");
+        assert_eq!((usage[1].input_tokens, usage[1].output_tokens), (30, 5));
+        assert!(db
+            .token_usage_since(Utc::now() + chrono::Duration::hours(1))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_logs_with_metadata() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let pool = ConnectionPool::new(db_path, 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        let details = LogDetails {
+            user: Some("alice".to_string()),
+            metadata: [("team".to_string(), "search".to_string())].into(),
+            system_prompt: Some("Be brief".to_string()),
+            reasoning: Some("thinking".to_string()),
+            images: vec!["/tmp/plot.png".to_string()],
+        };
+        db.save_chat_entry_with_details("s1", "openai", "gpt-4o", "q", "a", None, None, &details)
+            .unwrap();
+        db.save_chat_entry_with_tokens("s1", "openai", "gpt-4o", "q2", "a2", None, None)
+            .unwrap();
+
+        let logged = db.get_logs_with_metadata().unwrap();
+        assert_eq!(logged.len(), 2);
+        let first = logged.iter().find(|l| l.entry.question == "q").unwrap();
+        assert_eq!(first.entry.model, "gpt-4o");
+        assert_eq!(first.user.as_deref(), Some("alice"));
+        assert_eq!(first.metadata["team"], "search");
+        assert_eq!(first.system_prompt.as_deref(), Some("Be brief"));
+        assert_eq!(first.reasoning.as_deref(), Some("thinking"));
+        assert_eq!(first.images, ["/tmp/plot.png"]);
+        let second = logged.iter().find(|l| l.entry.question == "q2").unwrap();
+        assert_eq!(second.user, None);
+        assert!(second.metadata.is_empty());
+        assert_eq!(second.system_prompt, None);
+        assert!(second.images.is_empty());
+    }
+
+    #[test]
+    fn test_list_sessions() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let pool = ConnectionPool::new(db_path, 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        for (session, question) in [("a", "first"), ("a", "second"), ("b", "other")] {
+            db.save_chat_entry_with_tokens(session, "p", "m", question, "r", None, None)
+                .unwrap();
+        }
+
+        let sessions = db.list_sessions(10).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].chat_id, "b");
+        assert_eq!(sessions[1].turns, 2);
+        assert_eq!(sessions[1].first_question, "first");
+        assert_eq!(
+            sessions[1].size_bytes,
+            "first".len() as i64 + "second".len() as i64 + 2
+        );
+        assert_eq!(db.list_sessions(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_session_logs_and_prefix() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let pool = ConnectionPool::new(db_path, 3).unwrap();
+        let db = Database { pool };
+
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        for (chat_id, question, system_prompt) in [
+            ("abc123", "first", Some("Be terse")),
+            ("abc123", "second", None),
+            ("abd456", "other", None),
+        ] {
+            conn.execute(
+                "INSERT INTO chat_logs (chat_id, model, question, response, timestamp, system_prompt)
+                 VALUES (?1, 'm', ?2, 'r', ?3, ?4)",
+                params![chat_id, question, Utc::now(), system_prompt],
+            )
+            .unwrap();
+        }
+        drop(conn);
+
+        let logs = db.get_session_logs("abc123").unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].entry.question, "first");
+        assert_eq!(logs[0].system_prompt.as_deref(), Some("Be terse"));
+        assert_eq!(logs[1].system_prompt, None);
+
+        let recent = db.get_recent_logged_entries(2).unwrap();
+        let questions: Vec<&str> = recent.iter().map(|l| l.entry.question.as_str()).collect();
+        assert_eq!(questions, ["other", "second"]);
+
+        assert_eq!(db.resolve_session_id("abc").unwrap(), "abc123");
+        assert!(db.resolve_session_id("ab").is_err());
+        assert!(db.resolve_session_id("zzz").is_err());
+    }
+
+    #[test]
+    fn test_query_logs_filters() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database {
+            pool: ConnectionPool::new(temp_dir.path().join("test.db"), 3).unwrap(),
+        };
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        let now = Utc::now();
+        for (days_ago, provider, model, question) in [
+            (10, "openai", "gpt-4o", "old rust question"),
+            (2, "openai", "gpt-4o-mini", "100% sure?"),
+            (1, "claude", "sonnet", "Rust lifetimes"),
+        ] {
+            conn.execute(
+                "INSERT INTO chat_logs (chat_id, model, question, response, timestamp, provider)
+                 VALUES ('s1', ?1, ?2, 'answer', ?3, ?4)",
+                params![
+                    model,
+                    question,
+                    now - chrono::Duration::days(days_ago),
+                    provider
+                ],
+            )
+            .unwrap();
+        }
+        drop(conn);
+
+        let questions = |query: LogQuery| -> Vec<String> {
+            db.query_logs(&query)
+                .unwrap()
+                .into_iter()
+                .map(|l| l.entry.question)
+                .collect()
+        };
+
+        assert_eq!(questions(LogQuery::default()).len(), 3);
+        assert_eq!(
+            questions(LogQuery {
+                provider: Some("openai".to_string()),
+                limit: Some(1),
+                ..Default::default()
+            }),
+            ["100% sure?"]
+        );
+        assert_eq!(
+            questions(LogQuery {
+                model: Some("openai:gpt-4o".to_string()),
+                ..Default::default()
+            }),
+            ["old rust question"]
+        );
+        assert_eq!(
+            questions(LogQuery {
+                grep: Some("RUST".to_string()),
+                since: Some(now - chrono::Duration::days(5)),
+                ..Default::default()
+            }),
+            ["Rust lifetimes"]
+        );
+        assert_eq!(
+            questions(LogQuery {
+                until: Some(now - chrono::Duration::days(5)),
+                ..Default::default()
+            }),
+            ["old rust question"]
+        );
+        // LIKE wildcards in the pattern are matched literally
+        assert!(questions(LogQuery {
+            grep: Some("s_r".to_string()),
+            ..Default::default()
+        })
+        .is_empty());
+        assert_eq!(
+            questions(LogQuery {
+                grep: Some("0% s".to_string()),
+                ..Default::default()
+            }),
+            ["100% sure?"]
+        );
+    }
+
+    #[test]
+    fn test_session_export_import_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database {
+            pool: ConnectionPool::new(temp_dir.path().join("a.db"), 3).unwrap(),
+        };
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+        let id = "3f2a9c1e-0000-4000-8000-000000000001";
+        db.save_chat_entry_with_tokens(id, "openai", "gpt-4o", "q1", "r1", Some(12), Some(30))
+            .unwrap();
+        db.save_chat_entry_with_tokens(id, "anthropic", "claude", "q2", "r2", None, Some(7))
+            .unwrap();
+        db.save_tool_call(id, "fs", "read_file", true).unwrap();
+        assert!(db.export_session("missing").is_err());
+
+        let archive = db.export_session(id).unwrap();
+        assert_eq!(archive.turns.len(), 2);
+        assert_eq!(archive.turns[0].input_tokens, Some(12));
+        assert_eq!(archive.turns[1].model, "claude");
+        assert_eq!(archive.started, archive.turns[0].timestamp);
+
+        let other = Database {
+            pool: ConnectionPool::new(temp_dir.path().join("b.db"), 3).unwrap(),
+        };
+        let conn = other.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+        let json = serde_json::to_string(&archive).unwrap();
+        other
+            .import_session(&serde_json::from_str(&json).unwrap())
+            .unwrap();
+        assert_eq!(other.export_session(id).unwrap(), archive);
+        assert!(other.import_session(&archive).is_err());
+
+        // IDs that are not UUIDs would break every listing that shows a short ID
+        let short = SessionArchive {
+            session_id: "abc".to_string(),
+            ..archive
+        };
+        assert!(other.import_session(&short).is_err());
+        assert!(other.export_session("abc").is_err());
+    }
+
+    #[test]
+    fn test_short_id() {
+        assert_eq!(short_id("3f2a9c1e-0000-4000"), "3f2a9c1e");
+        assert_eq!(short_id("abc"), "abc");
+        assert_eq!(short_id("éééééééééé"), "éééééééé");
+    }
+
+    #[test]
+    fn test_session_names() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database {
+            pool: ConnectionPool::new(temp_dir.path().join("test.db"), 3).unwrap(),
+        };
+        let conn = db.pool.get_connection().unwrap();
+        Database::initialize_schema(&conn).unwrap();
+        drop(conn);
+        db.save_chat_entry_with_tokens("s1", "p", "m", "q", "r", None, None)
+            .unwrap();
+        db.save_session_settings(
+            "s1",
+            &SessionSettings {
+                tools: Some("fs".to_string()),
+                vectordb: None,
+            },
+        )
+        .unwrap();
+
+        db.set_session_name("s1", "work-refactor").unwrap();
+        assert_eq!(db.resolve_chat_id("name:work-refactor").unwrap(), "s1");
+        assert_eq!(db.resolve_chat_id("s2").unwrap(), "s2");
+        assert!(db.resolve_chat_id("name:other").is_err());
+        assert!(db.set_session_name("s2", "work-refactor").is_err());
+        assert!(db.set_session_name("s2", "has space").is_err());
+        assert_eq!(
+            db.get_session_settings("s1").unwrap().tools.as_deref(),
+            Some("fs")
+        );
+        assert_eq!(
+            db.list_sessions(10).unwrap()[0].name.as_deref(),
+            Some("work-refactor")
+        );
     }
 }
@@ -0,0 +1,167 @@
+//! Hybrid keyword + vector retrieval (`--hybrid-alpha`)
+//!
+//! Every vector database keeps an SQLite FTS5 index of its texts next to the
+//! embeddings. With hybrid search on, `lc similar` and RAG retrieval gather candidates
+//! from both the embeddings and the keyword index, then rank them by
+//! `alpha * cosine + (1 - alpha) * keyword`, where the keyword score is the BM25 score
+//! relative to the best match among the candidates. Exact identifiers and rare terms
+//! that embeddings blur are found this way. `alpha = 1` is pure vector search,
+//! `alpha = 0` pure keyword search. The weight can also be set in config.toml:
+//!
+//! ```toml
+//! [hybrid_search]
+//! alpha = 0.7
+//! ```
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Candidates taken from each side per requested result
+pub const CANDIDATES_PER_RESULT: usize = 4;
+
+/// The `[hybrid_search]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct HybridSearchSettings {
+    /// Weight of vector similarity against keyword relevance, from 0 to 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f64>,
+}
+
+impl HybridSearchSettings {
+    pub fn is_empty(&self) -> bool {
+        self.alpha.is_none()
+    }
+}
+
+static ALPHA: RwLock<Option<f64>> = RwLock::new(None);
+
+/// Set `--hybrid-alpha` for this invocation
+pub fn set(alpha: Option<f64>) {
+    if let Ok(mut current) = ALPHA.write() {
+        *current = alpha;
+    }
+}
+
+/// The weight of vector similarity when hybrid search is on, from `--hybrid-alpha` or
+/// `[hybrid_search]`
+pub fn alpha(config: &Config) -> Option<f64> {
+    ALPHA
+        .read()
+        .ok()
+        .and_then(|alpha| *alpha)
+        .or(config.hybrid_search.alpha)
+        .filter(|alpha| (0.0..=1.0).contains(alpha))
+}
+
+/// Parse a `--hybrid-alpha` value
+pub fn parse_alpha(value: &str) -> Result<f64, String> {
+    let alpha: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err("must be between 0 (keywords only) and 1 (vectors only)".to_string());
+    }
+    Ok(alpha)
+}
+
+/// The FTS5 query matching any word of `text`, or `None` when it has no words.
+/// Words are quoted, so FTS operators in the text are searched for literally.
+pub fn keyword_query(text: &str) -> Option<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    if words.is_empty() {
+        return None;
+    }
+    Some(
+        words
+            .iter()
+            .map(|word| format!("\"{}\"", word))
+            .collect::<Vec<_>>()
+            .join(" OR "),
+    )
+}
+
+/// Rank candidates by the weighted sum of their cosine similarity and keyword score.
+///
+/// `similarities` holds the cosine similarity of every candidate, `bm25` the raw FTS5
+/// scores of the keyword matches (lower is better). Returns ids and scores, best first.
+pub fn combine(
+    similarities: &HashMap<i64, f64>,
+    bm25: &HashMap<i64, f64>,
+    alpha: f64,
+    limit: usize,
+) -> Vec<(i64, f64)> {
+    // FTS5 scores are negative, the best the lowest
+    let best = bm25.values().cloned().fold(0.0, f64::min);
+    let keyword_score = |id: &i64| match bm25.get(id) {
+        Some(score) if best < 0.0 => (score / best).clamp(0.0, 1.0),
+        Some(_) => 1.0,
+        None => 0.0,
+    };
+
+    let mut ranked: Vec<(i64, f64)> = similarities
+        .iter()
+        .map(|(id, similarity)| {
+            (
+                *id,
+                alpha * similarity.max(0.0) + (1.0 - alpha) * keyword_score(id),
+            )
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_query() {
+        assert_eq!(
+            keyword_query("Where is parse_rag_sources? parse_rag_sources").as_deref(),
+            Some("\"where\" OR \"is\" OR \"parse_rag_sources\"")
+        );
+        assert_eq!(
+            keyword_query("NEAR(\"a\" b)").as_deref(),
+            Some("\"near\" OR \"a\" OR \"b\"")
+        );
+        assert_eq!(keyword_query(" ?! "), None);
+        assert!(parse_alpha("0.7").is_ok());
+        assert!(parse_alpha("1.5").is_err());
+        assert!(parse_alpha("x").is_err());
+    }
+
+    #[test]
+    fn test_combine() {
+        // Entry 3 names the identifier but is far in embedding space
+        let similarities = HashMap::from([(1, 0.82), (2, 0.80), (3, 0.40)]);
+        let bm25 = HashMap::from([(3, -6.0), (2, -1.0)]);
+
+        let vector_only = combine(&similarities, &bm25, 1.0, 3);
+        assert_eq!(vector_only[0].0, 1);
+
+        let hybrid = combine(&similarities, &bm25, 0.5, 2);
+        assert_eq!(hybrid.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [3, 2]);
+        assert!((hybrid[0].1 - 0.7).abs() < 1e-9);
+
+        let keywords_only = combine(&similarities, &HashMap::from([(2, -3.0)]), 0.0, 3);
+        assert_eq!(keywords_only[0], (2, 1.0));
+    }
+}
@@ -3,13 +3,11 @@
 //! This module handles storing and retrieving API keys separately from provider configurations,
 //! allowing provider configs to be shared and version-controlled without exposing secrets.
 
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-#[cfg(unix)]
-use std::os::unix::fs::OpenOptionsExt;
+use std::fs;
 use std::path::PathBuf;
 
 /// Structure for storing API keys and secrets
@@ -53,6 +51,9 @@ impl KeysConfig {
         } else {
             // Create default empty keys config
             let config = KeysConfig::default();
+            if crate::lock::is_read_only() {
+                return Ok(config);
+            }
             // Ensure directory exists
             if let Some(parent) = keys_path.parent() {
                 fs::create_dir_all(parent)?;
@@ -65,39 +66,10 @@ impl KeysConfig {
     /// Save keys configuration to file
     pub fn save(&self) -> Result<()> {
         let keys_path = Self::keys_file_path()?;
-
-        // Ensure directory exists
-        if let Some(parent) = keys_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
         let content = toml::to_string_pretty(self)?;
 
-        // Use OpenOptions to set permissions atomically on creation (Unix)
-        // This avoids race conditions where the file exists with default permissions
-        let mut options = OpenOptions::new();
-        options.write(true).create(true).truncate(true);
-
-        #[cfg(unix)]
-        {
-            // Set mode 0o600 (read/write for owner only) before opening
-            options.mode(0o600);
-        }
-
-        let mut file = options.open(&keys_path)?;
-
-        // Ensure permissions are restricted even if file already existed
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut permissions = file.metadata()?.permissions();
-            permissions.set_mode(0o600);
-            file.set_permissions(permissions)?;
-        }
-
-        file.write_all(content.as_bytes())?;
-
-        Ok(())
+        // Written with owner-only permissions, atomically and under the config lock
+        crate::lock::write_config_file(&keys_path, &content)
     }
 
     /// Get the path to the keys.toml file
@@ -118,6 +90,31 @@ impl KeysConfig {
         self.api_keys.get(provider)
     }
 
+    /// Copy every secret stored for `source` (API key, service account, tokens and
+    /// sensitive headers) to `name`. Returns false when `source` has none.
+    pub fn copy_provider(&mut self, source: &str, name: &str) -> Result<bool> {
+        let mut copied = false;
+        for secrets in [
+            &mut self.api_keys,
+            &mut self.tokens,
+            &mut self.service_accounts,
+            &mut self.oauth_tokens,
+        ] {
+            if let Some(secret) = secrets.get(source).cloned() {
+                secrets.insert(name.to_string(), secret);
+                copied = true;
+            }
+        }
+        if let Some(headers) = self.custom_headers.get(source).cloned() {
+            self.custom_headers.insert(name.to_string(), headers);
+            copied = true;
+        }
+        if copied {
+            self.save()?;
+        }
+        Ok(copied)
+    }
+
     /// Remove an API key for a provider
     #[allow(dead_code)]
     pub fn remove_api_key(&mut self, provider: &str) -> Result<bool> {
@@ -242,6 +239,41 @@ impl KeysConfig {
             || self.tokens.contains_key(provider)
     }
 
+    /// Every stored secret, for masking in output. Service accounts contribute their
+    /// private key (whole, JSON-escaped and line by line) and its ID.
+    pub fn secret_values(&self) -> Vec<String> {
+        let mut secrets: Vec<String> = self
+            .api_keys
+            .values()
+            .chain(self.tokens.values())
+            .chain(self.oauth_tokens.values())
+            .chain(
+                self.custom_headers
+                    .values()
+                    .flat_map(|headers| headers.values()),
+            )
+            .cloned()
+            .collect();
+        for sa_json in self.service_accounts.values() {
+            let Ok(account) = serde_json::from_str::<serde_json::Value>(sa_json) else {
+                continue;
+            };
+            if let Some(id) = account["private_key_id"].as_str() {
+                secrets.push(id.to_string());
+            }
+            if let Some(key) = account["private_key"].as_str() {
+                secrets.push(key.to_string());
+                secrets.push(key.replace('\n', "\\n"));
+                secrets.extend(
+                    key.lines()
+                        .filter(|line| !line.starts_with("-----"))
+                        .map(str::to_string),
+                );
+            }
+        }
+        secrets
+    }
+
     /// Migrate keys from old provider configs to centralized keys.toml
     pub fn migrate_from_provider_configs(config: &crate::config::Config) -> Result<Self> {
         let mut keys_config = Self::load()?;
@@ -284,7 +316,8 @@ impl KeysConfig {
         if migrated_count > 0 {
             keys_config.save()?;
             println!(
-                "✓ Migrated {} authentication configurations to keys.toml",
+                "{} Migrated {} authentication configurations to keys.toml",
+                sym("✓"),
                 migrated_count
             );
         }
@@ -0,0 +1,234 @@
+//! Locking and read-only mode for concurrent lc invocations
+//!
+//! Several lc processes can run at the same time (scripts, the proxy, an open chat).
+//! Config files are written while holding an exclusive lock on `.lock` in their
+//! directory and are replaced atomically, so a reader never sees a half-written
+//! file and two writers never interleave. With `--read-only` every state change
+//! is refused instead.
+
+use anyhow::Result;
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// How long to wait for another lc process to release a lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const LOCK_FILE: &str = ".lock";
+
+/// Whether `--read-only` is active
+pub fn is_read_only() -> bool {
+    crate::READ_ONLY_MODE.load(Ordering::Relaxed)
+}
+
+/// Fail with a clear error when running in read-only mode
+pub fn ensure_writable(action: &str) -> Result<()> {
+    if is_read_only() {
+        anyhow::bail!(
+            "Cannot {}: lc is running in read-only mode (--read-only)",
+            action
+        );
+    }
+    Ok(())
+}
+
+/// Exclusive lock on a directory, released when dropped
+pub struct DirLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl DirLock {
+    /// Lock `dir`, waiting up to 10 seconds for other processes
+    pub fn acquire(dir: &Path) -> Result<Self> {
+        Self::acquire_with_timeout(dir, LOCK_TIMEOUT)
+    }
+
+    pub fn acquire_with_timeout(dir: &Path, timeout: Duration) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(LOCK_FILE);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let started = Instant::now();
+        while file.try_lock_exclusive().is_err() {
+            if started.elapsed() >= timeout {
+                anyhow::bail!(
+                    "Timed out waiting for another lc process to release '{}'",
+                    path.display()
+                );
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        crate::debug_log!("Acquired lock {}", path.display());
+        Ok(Self { file, path })
+    }
+}
+
+impl Drop for DirLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+        crate::debug_log!("Released lock {}", self.path.display());
+    }
+}
+
+/// Write a config file under the directory lock, replacing it atomically.
+///
+/// The file is created with owner-only permissions on Unix, as it may contain secrets.
+pub fn write_config_file(path: &Path, content: &str) -> Result<()> {
+    update_config_file(path, |_| Ok(content.to_string()))
+}
+
+/// Read, change and write a config file under one hold of the directory lock, so
+/// changes made by other lc processes in between are not lost. `update` gets the
+/// current content, or `None` when the file doesn't exist or can't be read.
+pub fn update_config_file(
+    path: &Path,
+    update: impl FnOnce(Option<String>) -> Result<String>,
+) -> Result<()> {
+    ensure_writable(&format!("write '{}'", path.display()))?;
+
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let _lock = DirLock::acquire(dir)?;
+
+    let existing = fs::read(path).ok();
+    let content = update(
+        existing
+            .as_deref()
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .map(str::to_string),
+    )?;
+
+    // Unchanged files are left alone so their mtime only moves on real changes
+    if existing.is_some_and(|existing| existing == content.as_bytes()) {
+        return Ok(());
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string());
+    let tmp_path = dir.join(format!(".{}.tmp-{}", file_name, std::process::id()));
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+
+    let result = (|| -> Result<()> {
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_config_file_replaces_content() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "old = true\nmore = 1\n").unwrap();
+
+        write_config_file(&path, "new = true\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new = true\n");
+
+        // Only the target and the lock file are left behind
+        let mut names: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![".lock", "config.toml"]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_lock_times_out_while_held() {
+        let dir = TempDir::new().unwrap();
+        let held = DirLock::acquire(dir.path()).unwrap();
+        assert!(DirLock::acquire_with_timeout(dir.path(), Duration::from_millis(100)).is_err());
+
+        drop(held);
+        assert!(DirLock::acquire_with_timeout(dir.path(), Duration::from_millis(100)).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_writers_never_tear_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let content = format!("writer = {}\n", i).repeat(1000);
+                    write_config_file(&path, &content).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let content = fs::read_to_string(&path).unwrap();
+        let first = content.lines().next().unwrap().to_string();
+        assert!(content.lines().all(|line| line == first));
+        assert_eq!(content.lines().count(), 1000);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("counter");
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_config_file(&path, |content| {
+                        let count: u32 = content.map_or(0, |c| c.parse().unwrap());
+                        Ok((count + 1).to_string())
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "8");
+    }
+}
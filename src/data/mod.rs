@@ -1,5 +1,13 @@
 // Data storage modules
+pub mod backup;
+pub mod chunking;
 pub mod config;
+pub mod config_overrides;
 pub mod database;
+pub mod hybrid_search;
 pub mod keys;
+pub mod lock;
+pub mod provider_groups;
+pub mod trash;
+pub mod vector_archive;
 pub mod vector_db;
@@ -0,0 +1,489 @@
+//! Load-balancing groups of providers
+//!
+//! A group presents several provider configs (e.g. one per API key or region) as a
+//! single logical provider: `lc -p openai-pool -m gpt-4o ...`. Each request picks a
+//! backend round-robin or by weight. A backend that fails several times in a row is
+//! ejected for a while and skipped until it expires or succeeds again.
+//!
+//! Groups live in `provider_groups.toml`; the rotation position and backend health
+//! are kept in `provider_groups_state.json` so they carry over between invocations.
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_MAX_FAILURES: u32 = 3;
+const DEFAULT_EJECT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    #[default]
+    RoundRobin,
+    Weighted,
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "round-robin" | "roundrobin" | "rr" => Ok(Strategy::RoundRobin),
+            "weighted" | "w" => Ok(Strategy::Weighted),
+            _ => anyhow::bail!("Invalid strategy '{}'. Use 'round-robin' or 'weighted'", s),
+        }
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::RoundRobin => write!(f, "round-robin"),
+            Strategy::Weighted => write!(f, "weighted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroupBackend {
+    pub provider: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+impl GroupBackend {
+    /// Parse `provider` or `provider=weight`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (provider, weight) = match spec.split_once('=') {
+            Some((provider, weight)) => {
+                let weight: u32 = weight
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid weight in '{}'", spec))?;
+                if weight == 0 {
+                    anyhow::bail!("Weight in '{}' must be at least 1", spec);
+                }
+                (provider.trim(), weight)
+            }
+            None => (spec.trim(), 1),
+        };
+        if provider.is_empty() {
+            anyhow::bail!("Missing provider name in '{}'", spec);
+        }
+        Ok(Self {
+            provider: provider.to_string(),
+            weight,
+        })
+    }
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_max_failures() -> u32 {
+    DEFAULT_MAX_FAILURES
+}
+
+fn default_eject_secs() -> u64 {
+    DEFAULT_EJECT_SECS
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderGroup {
+    #[serde(default)]
+    pub strategy: Strategy,
+    /// Consecutive failures before a backend is ejected
+    #[serde(default = "default_max_failures")]
+    pub max_failures: u32,
+    /// How long an ejected backend is skipped
+    #[serde(default = "default_eject_secs")]
+    pub eject_secs: u64,
+    pub backends: Vec<GroupBackend>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderGroupsConfig {
+    #[serde(default)]
+    pub groups: HashMap<String, ProviderGroup>,
+}
+
+impl ProviderGroupsConfig {
+    pub fn load() -> Result<Self> {
+        let path = Self::config_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = toml::to_string_pretty(self)?;
+        crate::lock::write_config_file(&Self::config_file_path()?, &content)
+    }
+
+    fn config_file_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::config_dir()?.join("provider_groups.toml"))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ProviderGroup> {
+        self.groups.get(name)
+    }
+}
+
+/// Health of one backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BackendHealth {
+    pub consecutive_failures: u32,
+    pub ejected_until: Option<DateTime<Utc>>,
+    pub requests: u64,
+    pub failures: u64,
+}
+
+impl BackendHealth {
+    pub fn is_ejected(&self, now: DateTime<Utc>) -> bool {
+        self.ejected_until.is_some_and(|until| until > now)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupHealth {
+    /// Position in the rotation
+    pub next: u64,
+    #[serde(default)]
+    pub backends: HashMap<String, BackendHealth>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupsState {
+    #[serde(default)]
+    pub groups: HashMap<String, GroupHealth>,
+}
+
+impl GroupsState {
+    pub fn load() -> Self {
+        let Ok(path) = Self::state_file_path() else {
+            return Self::default();
+        };
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the state; it's bookkeeping, so failures (and read-only mode) are only logged
+    pub fn save(&self) {
+        if crate::lock::is_read_only() {
+            return;
+        }
+        let result = Self::state_file_path().and_then(|path| {
+            let content = serde_json::to_string_pretty(self)?;
+            crate::lock::write_config_file(&path, &content)
+        });
+        if let Err(e) = result {
+            crate::debug_log!("Failed to save provider group state: {}", e);
+        }
+    }
+
+    fn state_file_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::config_dir()?.join("provider_groups_state.json"))
+    }
+}
+
+/// The backend a client was created for, reported back after each request
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectedBackend {
+    pub group: String,
+    pub provider: String,
+}
+
+/// Whether `name` is a provider or a provider group
+pub fn provider_exists(config: &crate::config::Config, name: &str) -> bool {
+    config.has_provider(name) || is_group(name)
+}
+
+pub fn is_group(name: &str) -> bool {
+    ProviderGroupsConfig::load()
+        .map(|groups| groups.groups.contains_key(name))
+        .unwrap_or(false)
+}
+
+/// Pick the backend for the next request to `group`, advancing the rotation.
+///
+/// Ejected backends are skipped; when every backend is ejected, the one whose ejection
+/// ends first is used so requests keep flowing.
+pub fn pick_backend(
+    group: &ProviderGroup,
+    health: &mut GroupHealth,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let healthy: Vec<&GroupBackend> = group
+        .backends
+        .iter()
+        .filter(|b| {
+            !health
+                .backends
+                .get(&b.provider)
+                .is_some_and(|h| h.is_ejected(now))
+        })
+        .collect();
+
+    if healthy.is_empty() {
+        return group
+            .backends
+            .iter()
+            .min_by_key(|b| {
+                health
+                    .backends
+                    .get(&b.provider)
+                    .and_then(|h| h.ejected_until)
+            })
+            .map(|b| b.provider.clone());
+    }
+
+    let position = health.next;
+    health.next = health.next.wrapping_add(1);
+
+    let chosen = match group.strategy {
+        Strategy::RoundRobin => healthy[(position % healthy.len() as u64) as usize],
+        Strategy::Weighted => {
+            let total: u64 = healthy.iter().map(|b| b.weight.max(1) as u64).sum();
+            let mut slot = position % total;
+            let mut chosen = healthy[0];
+            for backend in &healthy {
+                let weight = backend.weight.max(1) as u64;
+                if slot < weight {
+                    chosen = backend;
+                    break;
+                }
+                slot -= weight;
+            }
+            chosen
+        }
+    };
+    Some(chosen.provider.clone())
+}
+
+/// Record the outcome of a request to a backend. Returns true when this failure
+/// ejected the backend.
+pub fn record_outcome(
+    group: &ProviderGroup,
+    health: &mut GroupHealth,
+    provider: &str,
+    success: bool,
+    now: DateTime<Utc>,
+) -> bool {
+    let backend = health.backends.entry(provider.to_string()).or_default();
+    backend.requests += 1;
+    if success {
+        backend.consecutive_failures = 0;
+        backend.ejected_until = None;
+        return false;
+    }
+
+    backend.failures += 1;
+    backend.consecutive_failures += 1;
+    if backend.consecutive_failures >= group.max_failures.max(1) {
+        backend.consecutive_failures = 0;
+        backend.ejected_until = Some(now + Duration::seconds(group.eject_secs as i64));
+        return true;
+    }
+    false
+}
+
+/// Choose the backend for a request to `group_name`, or `None` if it isn't a group
+pub fn select_backend(group_name: &str) -> Result<Option<SelectedBackend>> {
+    let groups = ProviderGroupsConfig::load()?;
+    let Some(group) = groups.get(group_name) else {
+        return Ok(None);
+    };
+    if group.backends.is_empty() {
+        anyhow::bail!("Provider group '{}' has no backends", group_name);
+    }
+
+    let mut state = GroupsState::load();
+    let health = state.groups.entry(group_name.to_string()).or_default();
+    let provider = pick_backend(group, health, Utc::now())
+        .ok_or_else(|| anyhow::anyhow!("Provider group '{}' has no backends", group_name))?;
+    state.save();
+
+    crate::debug_log!(
+        "Provider group '{}' routed this request to '{}'",
+        group_name,
+        provider
+    );
+    Ok(Some(SelectedBackend {
+        group: group_name.to_string(),
+        provider,
+    }))
+}
+
+/// Whether an error means the backend itself is unhealthy (as opposed to a bad request)
+pub fn is_backend_failure(error: &anyhow::Error) -> bool {
+    if error.downcast_ref::<reqwest::Error>().is_some() {
+        return true;
+    }
+    let message = error.to_string();
+    let Some(rest) = message.split("failed with status ").nth(1) else {
+        return false;
+    };
+    let status: u16 = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0);
+    matches!(status, 401 | 403 | 408 | 429) || status >= 500
+}
+
+/// Update the health of a selected backend after a request
+pub fn report(selected: &SelectedBackend, success: bool) {
+    let Ok(groups) = ProviderGroupsConfig::load() else {
+        return;
+    };
+    let Some(group) = groups.get(&selected.group) else {
+        return;
+    };
+
+    let mut state = GroupsState::load();
+    let health = state.groups.entry(selected.group.clone()).or_default();
+    if record_outcome(group, health, &selected.provider, success, Utc::now()) {
+        eprintln!(
+            "{}  Provider '{}' in group '{}' failed {} time(s) in a row and is skipped for {}s",
+            sym("⚠️"),
+            selected.provider,
+            selected.group,
+            group.max_failures,
+            group.eject_secs
+        );
+    }
+    state.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(strategy: Strategy, backends: &[(&str, u32)]) -> ProviderGroup {
+        ProviderGroup {
+            strategy,
+            max_failures: 2,
+            eject_secs: 60,
+            backends: backends
+                .iter()
+                .map(|(provider, weight)| GroupBackend {
+                    provider: provider.to_string(),
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    fn picks(group: &ProviderGroup, health: &mut GroupHealth, n: usize) -> Vec<String> {
+        let now = Utc::now();
+        (0..n)
+            .map(|_| pick_backend(group, health, now).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_round_robin_rotates() {
+        let group = group(Strategy::RoundRobin, &[("a", 1), ("b", 5)]);
+        let mut health = GroupHealth::default();
+        assert_eq!(picks(&group, &mut health, 4), vec!["a", "b", "a", "b"]);
+    }
+
+    #[test]
+    fn test_weighted_follows_weights() {
+        let group = group(Strategy::Weighted, &[("a", 3), ("b", 1)]);
+        let mut health = GroupHealth::default();
+        let chosen = picks(&group, &mut health, 8);
+        assert_eq!(chosen.iter().filter(|p| *p == "a").count(), 6);
+        assert_eq!(chosen.iter().filter(|p| *p == "b").count(), 2);
+    }
+
+    #[test]
+    fn test_failing_backend_is_ejected_and_recovers() {
+        let group = group(Strategy::RoundRobin, &[("a", 1), ("b", 1)]);
+        let mut health = GroupHealth::default();
+        let now = Utc::now();
+
+        assert!(!record_outcome(&group, &mut health, "a", false, now));
+        assert!(record_outcome(&group, &mut health, "a", false, now));
+        assert_eq!(picks(&group, &mut health, 3), vec!["b", "b", "b"]);
+
+        // Once the ejection has expired the backend is back in rotation
+        let later = now + Duration::seconds(61);
+        let chosen: Vec<String> = (0..2)
+            .map(|_| pick_backend(&group, &mut health, later).unwrap())
+            .collect();
+        assert!(chosen.contains(&"a".to_string()));
+
+        // A success resets the failure count
+        record_outcome(&group, &mut health, "b", false, now);
+        record_outcome(&group, &mut health, "b", true, now);
+        assert_eq!(health.backends["b"].consecutive_failures, 0);
+        assert_eq!(health.backends["b"].failures, 1);
+    }
+
+    #[test]
+    fn test_all_ejected_uses_earliest_recovery() {
+        let group = group(Strategy::RoundRobin, &[("a", 1), ("b", 1)]);
+        let mut health = GroupHealth::default();
+        let now = Utc::now();
+        for provider in ["b", "a"] {
+            record_outcome(&group, &mut health, provider, false, now);
+            record_outcome(&group, &mut health, provider, false, now);
+        }
+        health.backends.get_mut("a").unwrap().ejected_until = Some(now + Duration::seconds(5));
+        assert_eq!(pick_backend(&group, &mut health, now).as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_is_backend_failure() {
+        let failure = |msg: &str| is_backend_failure(&anyhow::anyhow!(msg.to_string()));
+        assert!(failure(
+            "API request failed with status 503 Service Unavailable: down"
+        ));
+        assert!(failure(
+            "API request failed with status 429 Too Many Requests: slow down"
+        ));
+        assert!(failure(
+            "API request failed with status 401 Unauthorized: bad key"
+        ));
+        assert!(!failure(
+            "API request failed with status 400 Bad Request: bad input"
+        ));
+        assert!(!failure("Failed to parse chat response"));
+    }
+
+    #[test]
+    fn test_backend_parsing() {
+        assert_eq!(
+            GroupBackend::parse("openai-eu=3").unwrap(),
+            GroupBackend {
+                provider: "openai-eu".to_string(),
+                weight: 3
+            }
+        );
+        assert_eq!(GroupBackend::parse("openai-us").unwrap().weight, 1);
+        assert!(GroupBackend::parse("openai=0").is_err());
+        assert!(GroupBackend::parse("openai=x").is_err());
+        assert!(GroupBackend::parse("=2").is_err());
+    }
+
+    #[test]
+    fn test_strategy_parsing() {
+        assert_eq!(
+            "round-robin".parse::<Strategy>().unwrap(),
+            Strategy::RoundRobin
+        );
+        assert_eq!("weighted".parse::<Strategy>().unwrap(), Strategy::Weighted);
+        assert!("random".parse::<Strategy>().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! Trash area for destructive operations
+//!
+//! Deleted vector databases, purged logs and removed provider configs are kept in
+//! `<config dir>/trash/<timestamp>-<operation>/` together with a manifest, so that
+//! `lc undo` can put back whatever the most recent destructive command removed.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of trash entries kept; older ones are pruned when a new one is added
+const MAX_TRASH_ENTRIES: usize = 20;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// A file stored in a trash entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedFile {
+    /// Original location, relative to the config directory
+    pub original: PathBuf,
+    /// File name inside the trash entry directory
+    pub stored: String,
+}
+
+/// Description of a destructive operation stored in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashManifest {
+    /// Human readable description, e.g. "vectors delete docs"
+    pub operation: String,
+    pub created_at: DateTime<Utc>,
+    /// Whether restoring may overwrite files that exist again (snapshots such as logs.db)
+    #[serde(default)]
+    pub overwrite: bool,
+    pub files: Vec<TrashedFile>,
+}
+
+/// A trash entry on disk
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub id: String,
+    pub path: PathBuf,
+    pub manifest: TrashManifest,
+}
+
+/// Trash area rooted in a config directory
+pub struct Trash {
+    config_dir: PathBuf,
+}
+
+impl Trash {
+    /// Trash for the lc config directory
+    pub fn new() -> Result<Self> {
+        Ok(Self::in_dir(crate::config::Config::config_dir()?))
+    }
+
+    /// Trash for an explicit config directory (used by tests)
+    pub fn in_dir(config_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            config_dir: config_dir.into(),
+        }
+    }
+
+    fn trash_dir(&self) -> PathBuf {
+        self.config_dir.join("trash")
+    }
+
+    fn relative(&self, path: &Path) -> Result<PathBuf> {
+        path.strip_prefix(&self.config_dir)
+            .map(Path::to_path_buf)
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Cannot trash '{}': outside the config directory",
+                    path.display()
+                )
+            })
+    }
+
+    fn create_entry(&self, operation: &str) -> Result<(String, PathBuf)> {
+        let slug: String = operation
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let mut id = format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S%3f"), slug);
+        let mut path = self.trash_dir().join(&id);
+        // Two operations within the same millisecond
+        let mut suffix = 1;
+        while path.exists() {
+            suffix += 1;
+            id = format!(
+                "{}-{}-{}",
+                Utc::now().format("%Y%m%dT%H%M%S%3f"),
+                slug,
+                suffix
+            );
+            path = self.trash_dir().join(&id);
+        }
+        fs::create_dir_all(&path)?;
+        Ok((id, path))
+    }
+
+    fn write_manifest(&self, entry_dir: &Path, manifest: &TrashManifest) -> Result<()> {
+        fs::write(
+            entry_dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(manifest)?,
+        )?;
+        self.prune()
+    }
+
+    /// Move files into a new trash entry. Missing files are skipped.
+    pub fn move_files(&self, operation: &str, files: &[PathBuf]) -> Result<String> {
+        crate::lock::ensure_writable(&format!("run '{}'", operation))?;
+        let (id, entry_dir) = self.create_entry(operation)?;
+        let mut trashed = Vec::new();
+
+        for (index, file) in files.iter().enumerate() {
+            if !file.exists() {
+                continue;
+            }
+            let original = self.relative(file)?;
+            let stored = stored_name(index, file);
+            move_file(file, &entry_dir.join(&stored))?;
+            trashed.push(TrashedFile { original, stored });
+        }
+
+        self.write_manifest(
+            &entry_dir,
+            &TrashManifest {
+                operation: operation.to_string(),
+                created_at: Utc::now(),
+                overwrite: false,
+                files: trashed,
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// Store a snapshot produced by `write` for a file that is modified in place.
+    ///
+    /// `write` receives the destination path inside the trash entry.
+    pub fn snapshot_with(
+        &self,
+        operation: &str,
+        original: &Path,
+        write: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<String> {
+        crate::lock::ensure_writable(&format!("run '{}'", operation))?;
+        let (id, entry_dir) = self.create_entry(operation)?;
+        let stored = stored_name(0, original);
+        if let Err(e) = write(&entry_dir.join(&stored)) {
+            let _ = fs::remove_dir_all(&entry_dir);
+            return Err(e);
+        }
+
+        self.write_manifest(
+            &entry_dir,
+            &TrashManifest {
+                operation: operation.to_string(),
+                created_at: Utc::now(),
+                overwrite: true,
+                files: vec![TrashedFile {
+                    original: self.relative(original)?,
+                    stored,
+                }],
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// Delete a trash entry without restoring it
+    pub fn discard(&self, id: &str) -> Result<()> {
+        let path = self.trash_dir().join(id);
+        if path.exists() {
+            fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    /// Trash entries, most recent first
+    pub fn list(&self) -> Result<Vec<TrashEntry>> {
+        let trash_dir = self.trash_dir();
+        if !trash_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&trash_dir)? {
+            let path = entry?.path();
+            let manifest_path = path.join(MANIFEST_FILE);
+            let Ok(content) = fs::read_to_string(&manifest_path) else {
+                continue;
+            };
+            match serde_json::from_str::<TrashManifest>(&content) {
+                Ok(manifest) => entries.push(TrashEntry {
+                    id: path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    path,
+                    manifest,
+                }),
+                Err(e) => crate::debug_log!(
+                    "Ignoring trash entry with invalid manifest {}: {}",
+                    manifest_path.display(),
+                    e
+                ),
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            b.manifest
+                .created_at
+                .cmp(&a.manifest.created_at)
+                .then(b.id.cmp(&a.id))
+        });
+        Ok(entries)
+    }
+
+    /// Most recent trash entry
+    pub fn latest(&self) -> Result<Option<TrashEntry>> {
+        Ok(self.list()?.into_iter().next())
+    }
+
+    /// Restore a trash entry and remove it from the trash
+    pub fn restore(&self, entry: &TrashEntry) -> Result<()> {
+        crate::lock::ensure_writable("undo")?;
+        // Check for conflicts first so a restore is all-or-nothing
+        if !entry.manifest.overwrite {
+            for file in &entry.manifest.files {
+                let target = self.config_dir.join(&file.original);
+                if target.exists() {
+                    anyhow::bail!(
+                        "Cannot undo '{}': '{}' exists again. Move it away first.",
+                        entry.manifest.operation,
+                        file.original.display()
+                    );
+                }
+            }
+        }
+
+        for file in &entry.manifest.files {
+            let target = self.config_dir.join(&file.original);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if entry.manifest.overwrite {
+                // Drop stale SQLite WAL files so they aren't replayed onto the restored database
+                for suffix in ["-wal", "-shm"] {
+                    let sidecar = PathBuf::from(format!("{}{}", target.display(), suffix));
+                    if sidecar.exists() {
+                        fs::remove_file(&sidecar)?;
+                    }
+                }
+            }
+            move_file(&entry.path.join(&file.stored), &target)?;
+        }
+
+        fs::remove_dir_all(&entry.path)?;
+        Ok(())
+    }
+
+    fn prune(&self) -> Result<()> {
+        for entry in self.list()?.into_iter().skip(MAX_TRASH_ENTRIES) {
+            crate::debug_log!("Pruning old trash entry {}", entry.id);
+            fs::remove_dir_all(&entry.path)?;
+        }
+        Ok(())
+    }
+}
+
+fn stored_name(index: usize, path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "file".to_string());
+    format!("{}-{}", index, name)
+}
+
+// rename() fails across filesystems, so fall back to copy + remove
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_move_and_restore() {
+        let dir = TempDir::new().unwrap();
+        let embeddings = dir.path().join("embeddings");
+        fs::create_dir_all(&embeddings).unwrap();
+        let db = embeddings.join("docs.db");
+        fs::write(&db, b"vectors").unwrap();
+
+        let trash = Trash::in_dir(dir.path());
+        trash
+            .move_files("vectors delete docs", std::slice::from_ref(&db))
+            .unwrap();
+        assert!(!db.exists());
+
+        let entry = trash.latest().unwrap().unwrap();
+        assert_eq!(entry.manifest.operation, "vectors delete docs");
+        assert_eq!(
+            entry.manifest.files[0].original,
+            PathBuf::from("embeddings/docs.db")
+        );
+
+        trash.restore(&entry).unwrap();
+        assert_eq!(fs::read(&db).unwrap(), b"vectors");
+        assert!(trash.latest().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("openai.toml");
+        fs::write(&file, "old").unwrap();
+
+        let trash = Trash::in_dir(dir.path());
+        trash
+            .move_files("providers remove openai", std::slice::from_ref(&file))
+            .unwrap();
+        fs::write(&file, "new").unwrap();
+
+        let entry = trash.latest().unwrap().unwrap();
+        assert!(trash.restore(&entry).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_snapshot_restore_overwrites() {
+        let dir = TempDir::new().unwrap();
+        let logs = dir.path().join("logs.db");
+        fs::write(&logs, "before").unwrap();
+
+        let trash = Trash::in_dir(dir.path());
+        trash
+            .snapshot_with("logs purge", &logs, |dest| {
+                fs::copy(&logs, dest)?;
+                Ok(())
+            })
+            .unwrap();
+        fs::write(&logs, "after").unwrap();
+        fs::write(dir.path().join("logs.db-wal"), "stale").unwrap();
+
+        let entry = trash.latest().unwrap().unwrap();
+        trash.restore(&entry).unwrap();
+        assert_eq!(fs::read_to_string(&logs).unwrap(), "before");
+        assert!(!dir.path().join("logs.db-wal").exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_recent_entries() {
+        let dir = TempDir::new().unwrap();
+        let trash = Trash::in_dir(dir.path());
+        for i in 0..MAX_TRASH_ENTRIES + 3 {
+            trash.move_files(&format!("op {}", i), &[]).unwrap();
+        }
+        assert_eq!(trash.list().unwrap().len(), MAX_TRASH_ENTRIES);
+    }
+}
@@ -0,0 +1,283 @@
+//! Single-file archives of one vector database (`lc vectors backup` / `restore`)
+//!
+//! An archive is a gzipped tar holding `manifest.json` and a consistent snapshot of the
+//! database. The manifest records the embedding model and provider, the vector
+//! dimensions, the number of entries and the size and SHA-256 of the database file.
+//! On restore, everything is checked in a staging directory — checksum, SQLite
+//! integrity, entry count, model and dimensions — before the database is put in place.
+
+use super::vector_db::VectorDatabase;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const FORMAT_VERSION: u32 = 1;
+
+/// What an archive contains, checked on restore
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub lc_version: String,
+    pub model: Option<String>,
+    pub provider: Option<String>,
+    pub dimensions: Option<usize>,
+    pub entries: usize,
+    /// Name of the database file in the archive
+    pub file: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Archive the vector database `name` to `out`
+pub fn backup(name: &str, out: &Path) -> Result<Manifest> {
+    if !VectorDatabase::list_databases()?
+        .iter()
+        .any(|db| db == name)
+    {
+        bail!("Vector database '{}' not found", name);
+    }
+    write_archive(&VectorDatabase::new(name)?, name, out)
+}
+
+fn write_archive(db: &VectorDatabase, name: &str, out: &Path) -> Result<Manifest> {
+    let staging = tempfile::tempdir()?;
+    let snapshot = staging.path().join(format!("{}.db", name));
+    db.snapshot_to(&snapshot)?;
+    let content = std::fs::read(&snapshot)?;
+
+    let (model, provider) = match db.get_model_info()? {
+        Some((model, provider)) => (Some(model), Some(provider)),
+        None => (None, None),
+    };
+    let manifest = Manifest {
+        format: FORMAT_VERSION,
+        name: name.to_string(),
+        created_at: Utc::now(),
+        lc_version: env!("CARGO_PKG_VERSION").to_string(),
+        model,
+        provider,
+        dimensions: db.dimensions()?,
+        entries: db.count()?,
+        file: format!("{}.db", name),
+        size: content.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(&content)),
+    };
+
+    let file = std::fs::File::create(out)
+        .map_err(|e| anyhow::anyhow!("Failed to create '{}': {}", out.display(), e))?;
+    let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    for (path, data) in [
+        (MANIFEST_FILE, manifest_json.as_slice()),
+        (manifest.file.as_str(), content.as_slice()),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+        header.set_cksum();
+        tar.append_data(&mut header, path, data)?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Read and verify an archive, leaving the checked database in `staging`
+pub fn verify(archive: &Path, staging: &Path) -> Result<(Manifest, PathBuf)> {
+    let file = std::fs::File::open(archive)
+        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", archive.display(), e))?;
+    let mut manifest: Option<Manifest> = None;
+    let mut database: Option<(String, Vec<u8>)> = None;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    for entry in tar
+        .entries()
+        .map_err(|_| anyhow::anyhow!("'{}' is not a vector archive", archive.display()))?
+    {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        if path == MANIFEST_FILE {
+            manifest = Some(
+                serde_json::from_slice(&content)
+                    .map_err(|e| anyhow::anyhow!("The archive manifest is invalid: {}", e))?,
+            );
+        } else if path.ends_with(".db") && !path.contains('/') && !path.contains("..") {
+            database = Some((path, content));
+        }
+    }
+
+    let Some(manifest) = manifest else {
+        bail!(
+            "'{}' has no manifest; not a vector archive",
+            archive.display()
+        );
+    };
+    if manifest.format > FORMAT_VERSION {
+        bail!(
+            "The archive has format {}; upgrade lc to restore it",
+            manifest.format
+        );
+    }
+    let content = match database {
+        Some((path, content)) if path == manifest.file => content,
+        _ => bail!(
+            "The archive is missing its database file '{}'",
+            manifest.file
+        ),
+    };
+    if content.len() as u64 != manifest.size
+        || format!("{:x}", Sha256::digest(&content)) != manifest.sha256
+    {
+        bail!("Checksum mismatch: the archived database is damaged");
+    }
+
+    VectorDatabase::validate_name(&manifest.name)?;
+    let path = staging.join(format!("{}.db", manifest.name));
+    std::fs::write(&path, &content)?;
+    let problems = super::backup::integrity_check(&path)?;
+    if !problems.is_empty() {
+        bail!(
+            "The archived database failed its integrity check: {}",
+            problems.join("; ")
+        );
+    }
+
+    let db = VectorDatabase::open_in(staging, &manifest.name)?;
+    let entries = db.count()?;
+    if entries != manifest.entries {
+        bail!(
+            "The archived database has {} entries, the manifest says {}",
+            entries,
+            manifest.entries
+        );
+    }
+    let model_info = db.get_model_info()?;
+    if model_info.as_ref().map(|(model, _)| model) != manifest.model.as_ref()
+        || model_info.as_ref().map(|(_, provider)| provider) != manifest.provider.as_ref()
+    {
+        bail!("The archived database's embedding model does not match the manifest");
+    }
+    if db.dimensions()? != manifest.dimensions {
+        bail!("The archived database's vector dimensions do not match the manifest");
+    }
+    drop(db);
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(staging.join(format!("{}.db{}", manifest.name, suffix)));
+    }
+    Ok((manifest, path))
+}
+
+/// Restore a verified archive as the vector database `name` (the archived name by
+/// default). An existing database is only replaced with `force`, and goes to the trash;
+/// the returned flag tells whether one was replaced.
+pub fn restore(archive: &Path, name: Option<&str>, force: bool) -> Result<(Manifest, bool)> {
+    crate::lock::ensure_writable("restore a vector database")?;
+    let staging = tempfile::tempdir()?;
+    let (manifest, staged) = verify(archive, staging.path())?;
+    let name = name.unwrap_or(&manifest.name);
+    VectorDatabase::validate_name(name)?;
+
+    let replaced = install(&staged, &VectorDatabase::embeddings_dir()?, name, force)?;
+    Ok((manifest, replaced))
+}
+
+fn install(staged: &Path, dir: &Path, name: &str, force: bool) -> Result<bool> {
+    let target = dir.join(format!("{}.db", name));
+    let replaced = target.exists();
+    if replaced {
+        if !force {
+            bail!(
+                "Vector database '{}' already exists; pass --force to replace it (it can be restored with 'lc undo')",
+                name
+            );
+        }
+        let mut files = vec![target.clone()];
+        for suffix in ["-wal", "-shm"] {
+            let sidecar = dir.join(format!("{}.db{}", name, suffix));
+            if sidecar.exists() {
+                files.push(sidecar);
+            }
+        }
+        crate::trash::Trash::new()?.move_files(&format!("vectors restore {}", name), &files)?;
+    }
+    std::fs::create_dir_all(dir)?;
+    // The staging directory may be on another file system, so copy rather than rename
+    std::fs::copy(staged, &target)?;
+    Ok(replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        let db = VectorDatabase::open_in(source.path(), "notes").unwrap();
+        db.add_vector("first", &[0.1, 0.2, 0.3], "embed-small", "openai")
+            .unwrap();
+        db.add_vector("second", &[0.3, 0.2, 0.1], "embed-small", "openai")
+            .unwrap();
+
+        let archive = source.path().join("notes.vectors.tar.gz");
+        let manifest = write_archive(&db, "notes", &archive).unwrap();
+        assert_eq!(manifest.entries, 2);
+        assert_eq!(manifest.dimensions, Some(3));
+        assert_eq!(manifest.model.as_deref(), Some("embed-small"));
+        assert_eq!(manifest.provider.as_deref(), Some("openai"));
+
+        let staging = tempfile::tempdir().unwrap();
+        let (verified, staged) = verify(&archive, staging.path()).unwrap();
+        assert_eq!(verified, manifest);
+
+        let target = tempfile::tempdir().unwrap();
+        assert!(!install(&staged, target.path(), "copy", false).unwrap());
+        let copy = VectorDatabase::open_in(target.path(), "copy").unwrap();
+        assert_eq!(copy.count().unwrap(), 2);
+        assert!(install(&staged, target.path(), "copy", false).is_err());
+    }
+
+    #[test]
+    fn test_tampered_archive_is_rejected() {
+        let source = tempfile::tempdir().unwrap();
+        let db = VectorDatabase::open_in(source.path(), "notes").unwrap();
+        db.add_vector("first", &[0.1, 0.2], "embed-small", "openai")
+            .unwrap();
+        let archive = source.path().join("notes.vectors.tar.gz");
+        let mut manifest = write_archive(&db, "notes", &archive).unwrap();
+
+        // Rewrite the archive with a manifest that no longer matches the database
+        let staging = tempfile::tempdir().unwrap();
+        let (_, staged) = verify(&archive, staging.path()).unwrap();
+        let content = std::fs::read(&staged).unwrap();
+        manifest.sha256 = "0".repeat(64);
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+            std::fs::File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        ));
+        let manifest_json = serde_json::to_vec(&manifest).unwrap();
+        for (path, data) in [(MANIFEST_FILE, &manifest_json), ("notes.db", &content)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            tar.append_data(&mut header, path, data.as_slice()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let err = verify(&archive, tempfile::tempdir().unwrap().path()).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+
+        std::fs::write(&archive, b"not an archive").unwrap();
+        assert!(verify(&archive, staging.path()).is_err());
+    }
+}
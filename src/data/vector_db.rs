@@ -1,10 +1,11 @@
+use super::hybrid_search;
 use crate::debug_log; // Import debug_log macro
 use anyhow::Result;
 use dashmap::DashMap;
 use hnsw_rs::prelude::*;
 use parking_lot::RwLock;
 use rayon::prelude::*;
-use rusqlite::{params, Connection};
+use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -21,8 +22,55 @@ pub struct VectorEntry {
     pub file_path: Option<String>,
     pub chunk_index: Option<i32>,
     pub total_chunks: Option<i32>,
+    /// Byte offset of the chunk in its file
+    #[serde(default)]
+    pub chunk_offset: Option<i64>,
+    /// Line of its file the chunk starts on, from 1
+    #[serde(default)]
+    pub chunk_line: Option<i64>,
+    /// Topic tags from `lc embed --tag-model`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// One-sentence summary from `lc embed --tag-model`
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Type of an embedded image (its `file_path`); `None` for text
+    #[serde(default)]
+    pub mime_type: Option<String>,
 }
 
+impl VectorEntry {
+    /// Whether the entry carries every one of the (normalized) `tags`
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+
+    /// Where the entry comes from: `file:line`, or the file and chunk number when the
+    /// line is not known
+    pub fn source(&self) -> Option<String> {
+        let file_path = self.file_path.as_ref()?;
+        Some(
+            match (self.chunk_line, self.chunk_index, self.total_chunks) {
+                (Some(line), _, _) => format!("{}:{}", file_path, line),
+                (None, Some(chunk_index), Some(total_chunks)) => {
+                    format!("{} (chunk {}/{})", file_path, chunk_index + 1, total_chunks)
+                }
+                _ => file_path.clone(),
+            },
+        )
+    }
+
+    /// Whether the entry is an embedded image rather than text
+    pub fn is_image(&self) -> bool {
+        self.mime_type
+            .as_deref()
+            .is_some_and(|mime_type| mime_type.starts_with("image/"))
+    }
+}
+
+/// A stored entry of a file: `(id, chunk index, total chunks, text)`
+pub type StoredChunk = (i64, Option<i32>, Option<i32>, String);
+
 // HNSW index for fast approximate nearest neighbor search
 type HnswIndex = Hnsw<'static, f64, DistCosine>;
 
@@ -47,7 +95,7 @@ impl std::fmt::Debug for VectorDatabase {
 }
 
 impl VectorDatabase {
-    fn validate_name(name: &str) -> Result<()> {
+    pub(crate) fn validate_name(name: &str) -> Result<()> {
         if name.contains('/') || name.contains('\\') || name.contains("..") {
             anyhow::bail!("Invalid database name: contains path traversal characters");
         }
@@ -56,10 +104,38 @@ impl VectorDatabase {
 
     pub fn new(name: &str) -> Result<Self> {
         Self::validate_name(name)?;
-        let embeddings_dir = Self::embeddings_dir()?;
-        fs::create_dir_all(&embeddings_dir)?;
+        Self::open_in(&Self::embeddings_dir()?, name)
+    }
 
-        let db_path = embeddings_dir.join(format!("{}.db", name));
+    /// Open the memory of a chat session (`--session-memory`). Session memories live in
+    /// their own directory so they don't show up in `lc vectors list`.
+    pub fn for_session(session_id: &str) -> Result<Self> {
+        Self::validate_name(session_id)?;
+        Self::open_in(&Self::session_memory_dir()?, session_id)
+    }
+
+    /// Open the index of the chat history (`lc logs ask`), also kept out of
+    /// `lc vectors list`
+    pub fn for_history() -> Result<Self> {
+        Self::open_in(&Self::embeddings_dir()?.join("history"), "logs")
+    }
+
+    /// Directory of the session memories
+    pub fn session_memory_dir() -> Result<PathBuf> {
+        Ok(Self::embeddings_dir()?.join("sessions"))
+    }
+
+    pub(crate) fn open_in(dir: &std::path::Path, name: &str) -> Result<Self> {
+        let db_path = dir.join(format!("{}.db", name));
+        if crate::lock::is_read_only() && !db_path.exists() {
+            anyhow::bail!(
+                "Vector database '{}' does not exist and cannot be created in read-only mode",
+                name
+            );
+        }
+        if !crate::lock::is_read_only() {
+            fs::create_dir_all(dir)?;
+        }
 
         let db = Self {
             db_path,
@@ -68,7 +144,9 @@ impl VectorDatabase {
             index_dirty: Arc::new(RwLock::new(true)),
         };
 
-        db.initialize()?;
+        if !crate::lock::is_read_only() {
+            db.initialize()?;
+        }
         Ok(db)
     }
 
@@ -116,6 +194,7 @@ impl VectorDatabase {
 
     pub fn delete_database_in_dir(name: &str, embeddings_dir: &std::path::Path) -> Result<()> {
         Self::validate_name(name)?;
+        crate::lock::ensure_writable("delete a vector database")?;
         let db_path = embeddings_dir.join(format!("{}.db", name));
 
         match fs::remove_file(db_path) {
@@ -128,7 +207,9 @@ impl VectorDatabase {
     }
 
     fn initialize(&self) -> Result<()> {
-        let conn = Connection::open(&self.db_path)?;
+        let conn = crate::database::open_connection(&self.db_path)?;
+        // WAL lets searches run while another lc process is embedding into the same database
+        conn.pragma_update(None, "journal_mode", "WAL")?;
 
         // First, create the table with the basic schema if it doesn't exist
         conn.execute(
@@ -147,6 +228,11 @@ impl VectorDatabase {
         let mut has_file_path = false;
         let mut has_chunk_index = false;
         let mut has_total_chunks = false;
+        let mut has_tags = false;
+        let mut has_summary = false;
+        let mut has_mime_type = false;
+        let mut has_chunk_offset = false;
+        let mut has_chunk_line = false;
 
         // Query the table schema to see what columns exist
         let mut stmt = conn.prepare("PRAGMA table_info(vectors)")?;
@@ -161,6 +247,11 @@ impl VectorDatabase {
                 "file_path" => has_file_path = true,
                 "chunk_index" => has_chunk_index = true,
                 "total_chunks" => has_total_chunks = true,
+                "tags" => has_tags = true,
+                "summary" => has_summary = true,
+                "mime_type" => has_mime_type = true,
+                "chunk_offset" => has_chunk_offset = true,
+                "chunk_line" => has_chunk_line = true,
                 _ => {}
             }
         }
@@ -175,6 +266,21 @@ impl VectorDatabase {
         if !has_total_chunks {
             conn.execute("ALTER TABLE vectors ADD COLUMN total_chunks INTEGER", [])?;
         }
+        if !has_tags {
+            conn.execute("ALTER TABLE vectors ADD COLUMN tags TEXT", [])?;
+        }
+        if !has_summary {
+            conn.execute("ALTER TABLE vectors ADD COLUMN summary TEXT", [])?;
+        }
+        if !has_mime_type {
+            conn.execute("ALTER TABLE vectors ADD COLUMN mime_type TEXT", [])?;
+        }
+        if !has_chunk_offset {
+            conn.execute("ALTER TABLE vectors ADD COLUMN chunk_offset INTEGER", [])?;
+        }
+        if !has_chunk_line {
+            conn.execute("ALTER TABLE vectors ADD COLUMN chunk_line INTEGER", [])?;
+        }
 
         // Create index for faster similarity searches
         conn.execute(
@@ -188,6 +294,30 @@ impl VectorDatabase {
             [],
         )?;
 
+        // Keyword index for hybrid search, kept in sync with the texts by triggers.
+        // Databases created before it existed are indexed once here.
+        if !Self::has_table(&conn, "vectors_fts")? {
+            conn.execute_batch(
+                "BEGIN;
+                CREATE VIRTUAL TABLE IF NOT EXISTS vectors_fts USING fts5(
+                    text, content='vectors', content_rowid='id',
+                    tokenize=\"unicode61 tokenchars '_'\"
+                );
+                CREATE TRIGGER IF NOT EXISTS vectors_fts_insert AFTER INSERT ON vectors BEGIN
+                    INSERT INTO vectors_fts(rowid, text) VALUES (new.id, new.text);
+                END;
+                CREATE TRIGGER IF NOT EXISTS vectors_fts_delete AFTER DELETE ON vectors BEGIN
+                    INSERT INTO vectors_fts(vectors_fts, rowid, text) VALUES ('delete', old.id, old.text);
+                END;
+                CREATE TRIGGER IF NOT EXISTS vectors_fts_update AFTER UPDATE OF text ON vectors BEGIN
+                    INSERT INTO vectors_fts(vectors_fts, rowid, text) VALUES ('delete', old.id, old.text);
+                    INSERT INTO vectors_fts(rowid, text) VALUES (new.id, new.text);
+                END;
+                INSERT INTO vectors_fts(vectors_fts) VALUES ('rebuild');
+                COMMIT;",
+            )?;
+        }
+
         Ok(())
     }
 
@@ -212,15 +342,63 @@ impl VectorDatabase {
         chunk_index: Option<i32>,
         total_chunks: Option<i32>,
     ) -> Result<i64> {
-        let conn = Connection::open(&self.db_path)?;
+        self.insert(
+            text,
+            vector,
+            model,
+            provider,
+            file_path,
+            chunk_index,
+            total_chunks,
+            None,
+        )
+    }
+
+    /// Add the embedding of an image file. `description` stands in for the text where
+    /// entries are listed.
+    pub fn add_image(
+        &self,
+        description: &str,
+        vector: &[f64],
+        model: &str,
+        provider: &str,
+        file_path: &str,
+        mime_type: &str,
+    ) -> Result<i64> {
+        self.insert(
+            description,
+            vector,
+            model,
+            provider,
+            Some(file_path),
+            None,
+            None,
+            Some(mime_type),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert(
+        &self,
+        text: &str,
+        vector: &[f64],
+        model: &str,
+        provider: &str,
+        file_path: Option<&str>,
+        chunk_index: Option<i32>,
+        total_chunks: Option<i32>,
+        mime_type: Option<&str>,
+    ) -> Result<i64> {
+        crate::lock::ensure_writable("add to a vector database")?;
+        let conn = crate::database::open_connection(&self.db_path)?;
 
         // Serialize vector as JSON for storage
         let vector_json = serde_json::to_string(vector)?;
         let created_at = chrono::Utc::now().to_rfc3339();
 
         conn.execute(
-            "INSERT INTO vectors (text, vector, model, provider, created_at, file_path, chunk_index, total_chunks) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![text, vector_json, model, provider, created_at, file_path, chunk_index, total_chunks],
+            "INSERT INTO vectors (text, vector, model, provider, created_at, file_path, chunk_index, total_chunks, mime_type) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![text, vector_json, model, provider, created_at, file_path, chunk_index, total_chunks, mime_type],
         )?;
 
         let id = conn.last_insert_rowid();
@@ -236,6 +414,11 @@ impl VectorDatabase {
             file_path: file_path.map(|s| s.to_string()),
             chunk_index,
             total_chunks,
+            chunk_offset: None,
+            chunk_line: None,
+            tags: Vec::new(),
+            summary: None,
+            mime_type: mime_type.map(str::to_string),
         };
 
         // Add to cache
@@ -248,45 +431,13 @@ impl VectorDatabase {
     }
 
     pub fn get_all_vectors(&self) -> Result<Vec<VectorEntry>> {
-        let conn = Connection::open(&self.db_path)?;
-
-        let mut stmt = conn.prepare(
-            "SELECT id, text, vector, model, provider, created_at, file_path, chunk_index, total_chunks FROM vectors ORDER BY created_at DESC"
-        )?;
-
-        let vector_iter = stmt.query_map([], |row| {
-            let vector_json: String = row.get(2)?;
-            let vector: Vec<f64> = serde_json::from_str(&vector_json).map_err(|_e| {
-                rusqlite::Error::InvalidColumnType(
-                    2,
-                    "vector".to_string(),
-                    rusqlite::types::Type::Text,
-                )
-            })?;
+        let conn = crate::database::open_connection(&self.db_path)?;
 
-            let created_at_str: String = row.get(5)?;
-            let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(
-                        5,
-                        "created_at".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&chrono::Utc);
-
-            Ok(VectorEntry {
-                id: row.get(0)?,
-                text: row.get(1)?,
-                vector,
-                model: row.get(3)?,
-                provider: row.get(4)?,
-                created_at,
-                file_path: row.get(6).ok(),
-                chunk_index: row.get(7).ok(),
-                total_chunks: row.get(8).ok(),
-            })
-        })?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM vectors ORDER BY created_at DESC",
+            ENTRY_COLUMNS
+        ))?;
+        let vector_iter = stmt.query_map([], entry_from_row)?;
 
         let mut vectors = Vec::new();
         for vector in vector_iter {
@@ -296,8 +447,25 @@ impl VectorDatabase {
         Ok(vectors)
     }
 
+    /// The entries with the given ids that exist
+    pub fn get_vectors(&self, ids: &[i64]) -> Result<Vec<VectorEntry>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = crate::database::open_connection(&self.db_path)?;
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM vectors WHERE id IN ({})",
+            ENTRY_COLUMNS, placeholders
+        ))?;
+        let entries = stmt
+            .query_map(rusqlite::params_from_iter(ids), entry_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
     pub fn get_model_info(&self) -> Result<Option<(String, String)>> {
-        let conn = Connection::open(&self.db_path)?;
+        let conn = crate::database::open_connection(&self.db_path)?;
 
         let mut stmt = conn.prepare("SELECT model, provider FROM vectors LIMIT 1")?;
 
@@ -369,6 +537,164 @@ impl VectorDatabase {
         self.find_similar_linear_optimized(query_vector, limit)
     }
 
+    /// Like `find_similar`, among the entries carrying every one of `tags` only
+    pub fn find_similar_tagged(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        tags: &[String],
+    ) -> Result<Vec<(VectorEntry, f64)>> {
+        if tags.is_empty() {
+            return self.find_similar(query_vector, limit);
+        }
+        let query_norm = query_vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let mut similarities: Vec<(VectorEntry, f64)> = self
+            .get_all_vectors()?
+            .into_par_iter()
+            .filter(|entry| entry.has_tags(tags))
+            .map(|entry| {
+                let similarity =
+                    cosine_similarity_precomputed(query_vector, &entry.vector, query_norm);
+                (entry, similarity)
+            })
+            .collect();
+        similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        similarities.truncate(limit);
+        Ok(similarities)
+    }
+
+    /// Entries matching words of `query` in the keyword index, as ids with their BM25
+    /// scores (lower is better), best first
+    pub fn keyword_search(&self, query: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
+        let Some(keywords) = hybrid_search::keyword_query(query) else {
+            return Ok(Vec::new());
+        };
+        let conn = crate::database::open_connection(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT rowid, bm25(vectors_fts) FROM vectors_fts WHERE vectors_fts MATCH ?1
+             ORDER BY bm25(vectors_fts) LIMIT ?2",
+        )?;
+        let matches = stmt
+            .query_map(params![keywords, limit as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(matches)
+    }
+
+    /// Like `find_similar_tagged`, ranking the closest entries by embedding together with
+    /// the best keyword matches of `query`; `alpha` is the weight of vector similarity
+    /// (see `hybrid_search`)
+    pub fn find_hybrid(
+        &self,
+        query_vector: &[f64],
+        query: &str,
+        limit: usize,
+        alpha: f64,
+        tags: &[String],
+    ) -> Result<Vec<(VectorEntry, f64)>> {
+        let candidates = limit.saturating_mul(hybrid_search::CANDIDATES_PER_RESULT);
+        let mut entries: std::collections::HashMap<i64, VectorEntry> = self
+            .find_similar_tagged(query_vector, candidates, tags)?
+            .into_iter()
+            .map(|(entry, _)| (entry.id, entry))
+            .collect();
+
+        let keyword_matches = self.keyword_search(query, candidates)?;
+        let keyword_only: Vec<i64> = keyword_matches
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !entries.contains_key(id))
+            .collect();
+        for entry in self.get_vectors(&keyword_only)? {
+            if entry.has_tags(tags) {
+                entries.insert(entry.id, entry);
+            }
+        }
+        let bm25 = keyword_matches
+            .into_iter()
+            .filter(|(id, _)| entries.contains_key(id))
+            .collect();
+
+        let query_norm = query_vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let similarities = entries
+            .iter()
+            .map(|(id, entry)| {
+                let similarity =
+                    cosine_similarity_precomputed(query_vector, &entry.vector, query_norm);
+                (*id, similarity)
+            })
+            .collect();
+
+        Ok(hybrid_search::combine(&similarities, &bm25, alpha, limit)
+            .into_iter()
+            .filter_map(|(id, score)| entries.remove(&id).map(|entry| (entry, score)))
+            .collect())
+    }
+
+    /// Store the tags and summary of an entry
+    pub fn set_annotations(&self, id: i64, tags: &[String], summary: Option<&str>) -> Result<()> {
+        crate::lock::ensure_writable("tag vector database entries")?;
+        let conn = crate::database::open_connection(&self.db_path)?;
+        conn.execute(
+            "UPDATE vectors SET tags = ?1, summary = ?2 WHERE id = ?3",
+            params![serde_json::to_string(tags)?, summary, id],
+        )?;
+        if let Some(mut entry) = self.vector_cache.get_mut(&id) {
+            entry.tags = tags.to_vec();
+            entry.summary = summary.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    /// Store where a chunk starts in its file
+    pub fn set_chunk_position(&self, id: i64, offset: usize, line: usize) -> Result<()> {
+        crate::lock::ensure_writable("add to a vector database")?;
+        let conn = crate::database::open_connection(&self.db_path)?;
+        conn.execute(
+            "UPDATE vectors SET chunk_offset = ?1, chunk_line = ?2 WHERE id = ?3",
+            params![offset as i64, line as i64, id],
+        )?;
+        if let Some(mut entry) = self.vector_cache.get_mut(&id) {
+            entry.chunk_offset = Some(offset as i64);
+            entry.chunk_line = Some(line as i64);
+        }
+        Ok(())
+    }
+
+    /// Entries of a file stored with the given model, in chunk order. Images have no
+    /// chunk index.
+    pub fn file_chunks(
+        &self,
+        file_path: &str,
+        model: &str,
+        provider: &str,
+    ) -> Result<Vec<StoredChunk>> {
+        let conn = crate::database::open_connection(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, chunk_index, total_chunks, text FROM vectors
+             WHERE file_path = ?1 AND model = ?2 AND provider = ?3
+             ORDER BY chunk_index",
+        )?;
+        let rows = stmt.query_map(params![file_path, model, provider], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Number of entries carrying each tag, most used first
+    pub fn tag_counts(&self) -> Result<Vec<(String, usize)>> {
+        let mut counts: std::collections::HashMap<String, usize> = Default::default();
+        for entry in self.get_all_vectors()? {
+            for tag in entry.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(counts)
+    }
+
     /// Optimized linear search with parallel processing and SIMD
     fn find_similar_linear_optimized(
         &self,
@@ -504,12 +830,241 @@ impl VectorDatabase {
     }
 
     pub fn count(&self) -> Result<usize> {
-        let conn = Connection::open(&self.db_path)?;
+        let conn = crate::database::open_connection(&self.db_path)?;
 
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM vectors", [], |row| row.get(0))?;
 
         Ok(count as usize)
     }
+
+    /// Number of dimensions of the stored vectors, taken from the first one
+    pub fn dimensions(&self) -> Result<Option<usize>> {
+        let conn = crate::database::open_connection(&self.db_path)?;
+        let vector: Option<String> = conn
+            .query_row("SELECT vector FROM vectors LIMIT 1", [], |row| row.get(0))
+            .optional()?;
+        match vector {
+            Some(json) => Ok(Some(serde_json::from_str::<Vec<f64>>(&json)?.len())),
+            None => Ok(None),
+        }
+    }
+
+    /// Delete the vectors with the given ids, returning how many were removed
+    pub fn delete_vectors(&self, ids: &[i64]) -> Result<usize> {
+        crate::lock::ensure_writable("delete from a vector database")?;
+        let mut conn = crate::database::open_connection(&self.db_path)?;
+
+        let tx = conn.transaction()?;
+        let mut deleted = 0;
+        {
+            let mut stmt = tx.prepare("DELETE FROM vectors WHERE id = ?1")?;
+            for id in ids {
+                deleted += stmt.execute(params![id])?;
+                self.vector_cache.remove(id);
+            }
+        }
+        tx.commit()?;
+
+        *self.index_dirty.write() = true;
+        Ok(deleted)
+    }
+
+    /// Path of the database file
+    pub fn path(&self) -> &std::path::Path {
+        &self.db_path
+    }
+
+    /// Write a consistent copy of the database to `dest`
+    pub fn snapshot_to(&self, dest: &std::path::Path) -> Result<()> {
+        let conn = crate::database::open_connection(&self.db_path)?;
+        conn.execute("VACUUM INTO ?1", [dest.to_string_lossy().as_ref()])?;
+        Ok(())
+    }
+
+    /// Start re-embedding the database with another model (`lc vectors migrate`). The new
+    /// vectors are staged next to the old ones until `complete_migration` swaps them in.
+    /// Starting the same migration again resumes it.
+    pub fn begin_migration(&self, model: &str, provider: &str) -> Result<()> {
+        crate::lock::ensure_writable("migrate a vector database")?;
+        if let Some((current_model, current_provider)) = self.migration_target()? {
+            if current_model != model || current_provider != provider {
+                anyhow::bail!(
+                    "A migration to {}:{} is in progress; finish it or abort it with --abort first",
+                    current_provider,
+                    current_model
+                );
+            }
+            return Ok(());
+        }
+
+        let conn = crate::database::open_connection(&self.db_path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS migration (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                model TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                started_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS migrated_vectors (
+                id INTEGER PRIMARY KEY,
+                vector BLOB NOT NULL
+            );",
+        )?;
+        conn.execute(
+            "INSERT INTO migration (id, model, provider, started_at) VALUES (1, ?1, ?2, ?3)",
+            params![model, provider, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Model and provider of the migration in progress, if any
+    pub fn migration_target(&self) -> Result<Option<(String, String)>> {
+        let conn = crate::database::open_connection(&self.db_path)?;
+        if !Self::has_table(&conn, "migration")? {
+            return Ok(None);
+        }
+        let mut stmt = conn.prepare("SELECT model, provider FROM migration WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// Ids of the entries that already have a vector from the new model
+    pub fn migrated_ids(&self) -> Result<std::collections::HashSet<i64>> {
+        let conn = crate::database::open_connection(&self.db_path)?;
+        if !Self::has_table(&conn, "migrated_vectors")? {
+            return Ok(Default::default());
+        }
+        let mut stmt = conn.prepare("SELECT id FROM migrated_vectors")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Vectors from the new model staged so far, by entry id
+    pub fn migrated_vectors(&self) -> Result<std::collections::HashMap<i64, Vec<f64>>> {
+        let conn = crate::database::open_connection(&self.db_path)?;
+        if !Self::has_table(&conn, "migrated_vectors")? {
+            return Ok(Default::default());
+        }
+        let mut stmt = conn.prepare("SELECT id, vector FROM migrated_vectors")?;
+        let rows = stmt.query_map([], |row| {
+            let vector_json: String = row.get(1)?;
+            let vector: Vec<f64> = serde_json::from_str(&vector_json).map_err(|_e| {
+                rusqlite::Error::InvalidColumnType(
+                    1,
+                    "vector".to_string(),
+                    rusqlite::types::Type::Text,
+                )
+            })?;
+            Ok((row.get(0)?, vector))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Stage the new model's vector for an entry
+    pub fn stage_migrated_vector(&self, id: i64, vector: &[f64]) -> Result<()> {
+        crate::lock::ensure_writable("migrate a vector database")?;
+        let conn = crate::database::open_connection(&self.db_path)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO migrated_vectors (id, vector) VALUES (?1, ?2)",
+            params![id, serde_json::to_string(vector)?],
+        )?;
+        Ok(())
+    }
+
+    /// Replace every vector with its staged one in a single transaction, returning how
+    /// many were swapped. The old vectors are kept if anything fails.
+    pub fn complete_migration(&self) -> Result<usize> {
+        crate::lock::ensure_writable("migrate a vector database")?;
+        let (model, provider) = self
+            .migration_target()?
+            .ok_or_else(|| anyhow::anyhow!("No migration in progress"))?;
+        let mut conn = crate::database::open_connection(&self.db_path)?;
+
+        let tx = conn.transaction()?;
+        let missing: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM vectors WHERE id NOT IN (SELECT id FROM migrated_vectors)",
+            [],
+            |row| row.get(0),
+        )?;
+        if missing > 0 {
+            anyhow::bail!("{} vectors have not been re-embedded yet", missing);
+        }
+        let swapped = tx.execute(
+            "UPDATE vectors SET
+                vector = (SELECT vector FROM migrated_vectors WHERE migrated_vectors.id = vectors.id),
+                model = ?1,
+                provider = ?2",
+            params![model, provider],
+        )?;
+        tx.execute_batch("DROP TABLE migrated_vectors; DROP TABLE migration;")?;
+        tx.commit()?;
+
+        self.vector_cache.clear();
+        *self.index_dirty.write() = true;
+        Ok(swapped)
+    }
+
+    /// Drop the migration in progress and its staged vectors
+    pub fn abort_migration(&self) -> Result<()> {
+        crate::lock::ensure_writable("migrate a vector database")?;
+        let conn = crate::database::open_connection(&self.db_path)?;
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS migrated_vectors; DROP TABLE IF EXISTS migration;",
+        )?;
+        Ok(())
+    }
+
+    fn has_table(conn: &rusqlite::Connection, table: &str) -> Result<bool> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+}
+
+/// Columns read by `entry_from_row`
+const ENTRY_COLUMNS: &str = "id, text, vector, model, provider, created_at, file_path, chunk_index, total_chunks, tags, summary, mime_type, chunk_offset, chunk_line";
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<VectorEntry> {
+    let vector_json: String = row.get(2)?;
+    let vector: Vec<f64> = serde_json::from_str(&vector_json).map_err(|_e| {
+        rusqlite::Error::InvalidColumnType(2, "vector".to_string(), rusqlite::types::Type::Text)
+    })?;
+
+    let created_at_str: String = row.get(5)?;
+    let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
+        .map_err(|_| {
+            rusqlite::Error::InvalidColumnType(
+                5,
+                "created_at".to_string(),
+                rusqlite::types::Type::Text,
+            )
+        })?
+        .with_timezone(&chrono::Utc);
+
+    Ok(VectorEntry {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        vector,
+        model: row.get(3)?,
+        provider: row.get(4)?,
+        created_at,
+        file_path: row.get(6).ok(),
+        chunk_index: row.get(7).ok(),
+        total_chunks: row.get(8).ok(),
+        tags: row
+            .get::<_, Option<String>>(9)
+            .ok()
+            .flatten()
+            .and_then(|tags| serde_json::from_str(&tags).ok())
+            .unwrap_or_default(),
+        summary: row.get(10).ok().flatten(),
+        mime_type: row.get(11).ok().flatten(),
+        chunk_offset: row.get(12).ok().flatten(),
+        chunk_line: row.get(13).ok().flatten(),
+    })
 }
 
 // Optimized cosine similarity calculation with manual vectorization
@@ -608,6 +1163,10 @@ impl FileProcessor {
         // Check extension first
         if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
             let ext = ext.to_lowercase();
+            // Formats a reader converts to text
+            if crate::readers::get_document_reader(&ext).is_some() {
+                return true;
+            }
             match ext.as_str() {
                 // Text files
                 "txt" | "md" | "markdown" | "rst" | "org" | "tex" | "rtf" => true,
@@ -670,16 +1229,39 @@ impl FileProcessor {
         Ok(printable_ratio > 0.7) // At least 70% printable characters
     }
 
+    /// MIME type of an image file that can be embedded with a multimodal model
+    pub fn image_mime_type(path: &std::path::Path) -> Option<&'static str> {
+        let extension = path.extension()?.to_str()?;
+        crate::image_utils::ImageFormat::from_extension(extension).map(|format| format.mime_type())
+    }
+
     /// Expand glob patterns and filter for text files
     pub fn expand_file_patterns(patterns: &[String]) -> Result<Vec<std::path::PathBuf>> {
-        use glob::glob;
+        Self::expand_patterns(patterns, false)
+    }
+
+    /// Expand glob patterns and filter for text and image files
+    pub fn expand_embeddable_files(patterns: &[String]) -> Result<Vec<std::path::PathBuf>> {
+        Self::expand_patterns(patterns, true)
+    }
 
+    fn expand_patterns(
+        patterns: &[String],
+        include_images: bool,
+    ) -> Result<Vec<std::path::PathBuf>> {
+        use glob::{glob_with, MatchOptions};
+
+        // Windows file systems are case-insensitive, so patterns should be too
+        let options = MatchOptions {
+            case_sensitive: !cfg!(windows),
+            ..MatchOptions::new()
+        };
         let mut files = Vec::new();
 
         for pattern in patterns {
             debug_log!("Processing file pattern: {}", pattern);
 
-            match glob(pattern) {
+            match glob_with(pattern, options) {
                 Ok(paths) => {
                     for path_result in paths {
                         match path_result {
@@ -687,6 +1269,12 @@ impl FileProcessor {
                                 if path.is_file() && Self::is_text_file(&path) {
                                     debug_log!("Adding text file: {}", path.display());
                                     files.push(path);
+                                } else if include_images
+                                    && path.is_file()
+                                    && Self::image_mime_type(&path).is_some()
+                                {
+                                    debug_log!("Adding image file: {}", path.display());
+                                    files.push(path);
                                 } else if path.is_file() {
                                     debug_log!("Skipping non-text file: {}", path.display());
                                 } else {
@@ -714,126 +1302,68 @@ impl FileProcessor {
     }
 
     /// Split text into chunks with overlap for better context preservation
+    #[deprecated(note = "use `chunking::chunk`, which also keeps each chunk's position")]
     pub fn chunk_text(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
-        debug_log!(
-            "Chunking text: {} chars, chunk_size: {}, overlap: {}",
-            text.len(),
-            chunk_size,
-            overlap
-        );
-
-        if text.len() <= chunk_size {
-            debug_log!("Text is smaller than chunk size, returning single chunk");
-            return vec![text.to_string()];
-        }
-
-        let mut chunks = Vec::new();
-        let mut start = 0;
-        let mut iteration = 0;
-
-        while start < text.len() {
-            iteration += 1;
-            debug_log!(
-                "Chunk iteration {}: start={}, text.len()={}",
-                iteration,
-                start,
-                text.len()
-            );
-
-            let end = std::cmp::min(start + chunk_size, text.len());
-            let mut chunk_end = end;
-
-            // Try to break at sentence boundary
-            if end < text.len() {
-                if let Some(sentence_end) = text[start..end].rfind(". ") {
-                    chunk_end = start + sentence_end + 1;
-                } else if let Some(para_end) = text[start..end].rfind("\n\n") {
-                    chunk_end = start + para_end + 1;
-                } else if let Some(line_end) = text[start..end].rfind('\n') {
-                    chunk_end = start + line_end + 1;
-                }
-            }
-
-            let chunk = text[start..chunk_end].trim().to_string();
-            if !chunk.is_empty() {
-                let chunk_len = chunk.len();
-                chunks.push(chunk);
-                debug_log!("Added chunk {}: {} chars", chunks.len(), chunk_len);
-            }
-
-            // Move start position with overlap
-            if chunk_end >= text.len() {
-                debug_log!("Reached end of text, breaking");
-                break;
-            }
-
-            let new_start = if chunk_end > overlap {
-                chunk_end - overlap
-            } else {
-                chunk_end
-            };
-
-            // Ensure we're making progress - if new start is not greater than current start,
-            // move forward by at least 1 character to prevent infinite loops
-            if new_start <= start {
-                start += 1;
-                debug_log!(
-                    "Preventing infinite loop: moving start from {} to {}",
-                    new_start,
-                    start
-                );
-            } else {
-                start = new_start;
-            }
-
-            debug_log!("Next start position: {}", start);
-
-            // Safety check to prevent infinite loop
-            if iteration > 1000 {
-                debug_log!("WARNING: Too many iterations, breaking to prevent infinite loop");
-                break;
-            }
+        let options = crate::chunking::ChunkOptions {
+            strategy: crate::chunking::ChunkStrategy::Sentence,
+            size: chunk_size.max(1),
+            overlap: overlap.min(chunk_size.saturating_sub(1)),
+        };
+        let chunks: Vec<String> = crate::chunking::chunk(text, &options, None)
+            .into_iter()
+            .map(|chunk| chunk.text)
+            .collect();
+        // Text without content is still returned as a single chunk
+        if chunks.is_empty() {
+            vec![text.to_string()]
+        } else {
+            chunks
         }
-
-        debug_log!("Chunking complete: {} chunks created", chunks.len());
-        chunks
     }
 
     /// Read and chunk a file (synchronous version for compatibility)
-    pub fn process_file(path: &std::path::Path) -> Result<Vec<String>> {
+    pub fn process_file(
+        path: &std::path::Path,
+        options: &crate::chunking::ChunkOptions,
+    ) -> Result<Vec<crate::chunking::Chunk>> {
         // Try async version first, fall back to sync if no runtime available
         if let Ok(handle) = tokio::runtime::Handle::try_current() {
-            handle.block_on(Self::process_file_async(path))
+            handle.block_on(Self::process_file_async(path, options))
         } else {
             // Fallback to synchronous implementation for tests and non-async contexts
             debug_log!("Reading file synchronously: {}", path.display());
-            let content = std::fs::read_to_string(path)?;
-            debug_log!("File content length: {} characters", content.len());
-
-            // Use 1200 character chunks with 200 character overlap
-            debug_log!("Starting text chunking with 1200 char chunks, 200 char overlap");
-            let chunks = Self::chunk_text(&content, 1200, 200);
-
-            debug_log!(
-                "File '{}' split into {} chunks",
-                path.display(),
-                chunks.len()
-            );
-
-            Ok(chunks)
+            let content = match Self::document_reader(path) {
+                Some(reader) => reader.read_as_text(&path.to_string_lossy())?,
+                None => std::fs::read_to_string(path)?,
+            };
+            Ok(Self::chunk_file(path, &content, options))
         }
     }
 
     /// Async version of process_file with memory mapping optimization
-    pub async fn process_file_async(path: &std::path::Path) -> Result<Vec<String>> {
+    pub async fn process_file_async(
+        path: &std::path::Path,
+        options: &crate::chunking::ChunkOptions,
+    ) -> Result<Vec<crate::chunking::Chunk>> {
         debug_log!("Reading file: {}", path.display());
 
         let content = Self::read_file_optimized(path).await?;
-        debug_log!("File content length: {} characters", content.len());
+        Ok(Self::chunk_file(path, &content, options))
+    }
 
-        // Use 1200 character chunks with 200 character overlap
-        debug_log!("Starting text chunking with 1200 char chunks, 200 char overlap");
-        let chunks = Self::chunk_text(&content, 1200, 200);
+    fn chunk_file(
+        path: &std::path::Path,
+        content: &str,
+        options: &crate::chunking::ChunkOptions,
+    ) -> Vec<crate::chunking::Chunk> {
+        debug_log!("File content length: {} characters", content.len());
+        debug_log!(
+            "Chunking with strategy {:?}, {} char chunks, {} char overlap",
+            options.strategy.for_path(Some(path)),
+            options.size,
+            options.overlap
+        );
+        let chunks = crate::chunking::chunk(content, options, Some(path));
 
         debug_log!(
             "File '{}' split into {} chunks",
@@ -841,11 +1371,23 @@ impl FileProcessor {
             chunks.len()
         );
 
-        Ok(chunks)
+        chunks
+    }
+
+    /// The reader for the file's format, if any
+    fn document_reader(path: &std::path::Path) -> Option<Box<dyn crate::readers::FileReader>> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(crate::readers::get_document_reader)
     }
 
     /// Optimized file reading with memory mapping for large files
     async fn read_file_optimized(path: &std::path::Path) -> Result<String> {
+        if let Some(reader) = Self::document_reader(path) {
+            debug_log!("Reading {} with a format reader", path.display());
+            return reader.read_as_text(&path.to_string_lossy());
+        }
+
         let metadata = tokio::fs::metadata(path).await?;
         let file_size = metadata.len();
 
@@ -893,6 +1435,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_chunk_text() {
         let text = "This is sentence one. This is sentence two. This is sentence three.";
         let chunks = FileProcessor::chunk_text(text, 30, 10);
@@ -901,6 +1444,124 @@ mod tests {
         assert!(chunks[0].contains("sentence one"));
     }
 
+    #[test]
+    fn test_migration_swaps_vectors() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = VectorDatabase::open_in(dir.path(), "docs").unwrap();
+        let first = db.add_vector("alpha", &[1.0, 0.0], "old", "p1").unwrap();
+        let second = db.add_vector("beta", &[0.0, 1.0], "old", "p1").unwrap();
+
+        db.begin_migration("new", "p2").unwrap();
+        assert!(db.begin_migration("other", "p2").is_err());
+        db.stage_migrated_vector(first, &[0.5, 0.5, 0.5]).unwrap();
+
+        // Incomplete migrations keep the old vectors
+        assert!(db.complete_migration().is_err());
+        assert_eq!(db.migrated_ids().unwrap().len(), 1);
+        assert!(db
+            .get_all_vectors()
+            .unwrap()
+            .iter()
+            .all(|e| e.model == "old"));
+
+        db.stage_migrated_vector(second, &[0.1, 0.2, 0.3]).unwrap();
+        assert_eq!(db.complete_migration().unwrap(), 2);
+        assert_eq!(db.migration_target().unwrap(), None);
+        let entries = db.get_all_vectors().unwrap();
+        assert!(entries
+            .iter()
+            .all(|e| e.model == "new" && e.provider == "p2" && e.vector.len() == 3));
+
+        db.begin_migration("newer", "p2").unwrap();
+        db.abort_migration().unwrap();
+        assert_eq!(db.migration_target().unwrap(), None);
+        assert!(db.migrated_ids().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tagged_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = VectorDatabase::open_in(dir.path(), "docs").unwrap();
+        let rust = db.add_vector("tokio tasks", &[1.0, 0.0], "m", "p").unwrap();
+        db.add_vector("python asyncio", &[0.9, 0.1], "m", "p")
+            .unwrap();
+        db.set_annotations(
+            rust,
+            &["rust".to_string(), "async".to_string()],
+            Some("About tokio."),
+        )
+        .unwrap();
+
+        let tagged = db
+            .find_similar_tagged(&[1.0, 0.0], 5, &["rust".to_string()])
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].0.summary.as_deref(), Some("About tokio."));
+        assert!(db
+            .find_similar_tagged(&[1.0, 0.0], 5, &["rust".to_string(), "go".to_string()])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.find_similar_tagged(&[1.0, 0.0], 5, &[]).unwrap().len(),
+            2
+        );
+        assert_eq!(db.tag_counts().unwrap()[0], ("async".to_string(), 1));
+    }
+
+    #[test]
+    fn test_hybrid_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = VectorDatabase::open_in(dir.path(), "code").unwrap();
+        db.add_vector("Retrieval settings overview", &[1.0, 0.0], "m", "p")
+            .unwrap();
+        let exact = db
+            .add_vector("fn parse_rag_sources(spec: &str)", &[0.6, 0.8], "m", "p")
+            .unwrap();
+        let stale = db
+            .add_vector("parse_rag_sources was renamed", &[0.0, 1.0], "m", "p")
+            .unwrap();
+
+        let query = "where is parse_rag_sources defined";
+        let vector_only = db.find_hybrid(&[1.0, 0.0], query, 1, 1.0, &[]).unwrap();
+        assert_ne!(vector_only[0].0.id, exact);
+        let hybrid = db.find_hybrid(&[1.0, 0.0], query, 1, 0.5, &[]).unwrap();
+        assert_eq!(hybrid[0].0.id, exact);
+
+        // The keyword index follows deletions
+        db.delete_vectors(&[stale]).unwrap();
+        let matches = db.keyword_search("parse_rag_sources", 10).unwrap();
+        assert_eq!(
+            matches.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            [exact]
+        );
+        assert!(db.keyword_search("\"", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = VectorDatabase::open_in(dir.path(), "media").unwrap();
+        db.add_vector("a caption", &[0.0, 1.0], "clip", "p")
+            .unwrap();
+        db.add_image(
+            "Image: cat.png",
+            &[1.0, 0.0],
+            "clip",
+            "p",
+            "cat.png",
+            "image/png",
+        )
+        .unwrap();
+
+        let (entry, _) = db.find_similar(&[1.0, 0.1], 1).unwrap().remove(0);
+        assert!(entry.is_image());
+        assert_eq!(entry.file_path.as_deref(), Some("cat.png"));
+        assert_eq!(
+            FileProcessor::image_mime_type(std::path::Path::new("photo.JPG")),
+            Some("image/jpeg")
+        );
+    }
+
     #[test]
     fn test_is_text_file() {
         use std::path::Path;
@@ -1,2 +1,217 @@
-// This module is kept for potential future custom error types
-// Currently the codebase uses anyhow::Result for error handling
+//! Translation of provider error responses into explanations and next steps
+//!
+//! Providers answer failed requests with payloads like OpenAI's
+//! `{"error": {"code": "insufficient_quota", ...}}` or Anthropic's
+//! `{"type": "error", "error": {"type": "overloaded_error", ...}}`. The raw payload is
+//! kept in the error message (provider groups classify failures by its status), and a
+//! short explanation with the next step is appended when the failure is recognized —
+//! including common misconfigurations such as a wrong `chat_path` or an Azure endpoint
+//! without `api-version`.
+
+use serde_json::Value;
+
+/// What went wrong and what to do about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorHint {
+    pub explanation: String,
+    pub next_step: String,
+}
+
+impl ErrorHint {
+    fn new(explanation: &str, next_step: &str) -> Self {
+        Self {
+            explanation: explanation.to_string(),
+            next_step: next_step.to_string(),
+        }
+    }
+}
+
+/// Build the error for a failed API request: `"<kind> request failed with status
+/// <status>: <body>"` followed by the hint when the failure is recognized. Refusals in
+/// prompts that recover from them become a [`crate::core::refusal::Refusal`].
+pub fn api_error(kind: &str, status: reqwest::StatusCode, body: &str, url: &str) -> anyhow::Error {
+    let message = format!("{} request failed with status {}: {}", kind, status, body);
+    // Content filters and malformed tool calls can be recovered from with --on-refusal
+    if let Some(kind) = crate::core::refusal::from_error_response(status.as_u16(), body)
+        .filter(|_| crate::core::refusal::detects_failed())
+    {
+        return anyhow::Error::new(crate::core::refusal::Refusal {
+            kind,
+            detail: message,
+        });
+    }
+    match explain(status.as_u16(), body, url) {
+        Some(hint) => anyhow::anyhow!(
+            "{}\n\n{}\nNext step: {}",
+            message,
+            hint.explanation,
+            hint.next_step
+        ),
+        None => anyhow::anyhow!(message),
+    }
+}
+
+/// Recognize a provider error payload or a misconfiguration from the status, body and URL
+pub fn explain(status: u16, body: &str, url: &str) -> Option<ErrorHint> {
+    let json: Option<Value> = serde_json::from_str(body).ok();
+    let error = json.as_ref().map(|json| {
+        let error = json.get("error").unwrap_or(json);
+        // Google APIs answer with a list of errors
+        error.as_array().and_then(|a| a.first()).unwrap_or(error)
+    });
+    let field = |name: &str| {
+        error
+            .and_then(|e| e.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_lowercase)
+    };
+    let code = field("code").unwrap_or_default();
+    let kind = field("type").unwrap_or_default();
+    let google_status = field("status").unwrap_or_default();
+    let message = field("message").unwrap_or_else(|| body.to_lowercase());
+    let url = url.to_lowercase();
+
+    // OpenAI
+    if code == "insufficient_quota" || kind == "insufficient_quota" {
+        return Some(ErrorHint::new(
+            "The account has run out of credits or hit its monthly spend limit.",
+            "Add credits or raise the limit in the provider's billing settings, or use another provider with -p.",
+        ));
+    }
+    if code == "invalid_api_key" || kind == "authentication_error" || status == 401 {
+        return Some(ErrorHint::new(
+            "The API key was rejected.",
+            "Check the key with 'lc keys list' and set a new one with 'lc keys add <provider>'.",
+        ));
+    }
+    if code == "context_length_exceeded" || message.contains("maximum context length") {
+        return Some(ErrorHint::new(
+            "The conversation is longer than the model's context window.",
+            "Start a new session, shorten the input, or lower --max-tokens.",
+        ));
+    }
+    if code == "model_not_found"
+        || kind == "not_found_error" && message.contains("model")
+        || message.contains("model") && message.contains("does not exist")
+    {
+        return Some(ErrorHint::new(
+            "The provider does not know this model, or the key has no access to it.",
+            "List the available models with 'lc providers models <provider> --refresh'.",
+        ));
+    }
+
+    // Anthropic
+    if kind == "overloaded_error" || status == 529 {
+        return Some(ErrorHint::new(
+            "The provider is temporarily overloaded.",
+            "Retry in a moment, or spread requests over several providers with a provider group.",
+        ));
+    }
+    if code == "rate_limit_exceeded" || kind == "rate_limit_error" || status == 429 {
+        return Some(ErrorHint::new(
+            "Too many requests or tokens per minute for this key.",
+            "Wait a moment before retrying, lower --max-tokens, or use a provider group.",
+        ));
+    }
+
+    // Vertex AI and other Google APIs
+    if message.contains("service_disabled") || message.contains("has not been used in project") {
+        return Some(ErrorHint::new(
+            "The API is not enabled in the Google Cloud project.",
+            "Enable the Vertex AI API in the Cloud console for the project in the provider's vars.",
+        ));
+    }
+    if google_status == "permission_denied" || kind == "permission_error" || status == 403 {
+        let next_step = if url.contains("aiplatform.googleapis.com") {
+            "Grant the service account the 'Vertex AI User' role (roles/aiplatform.user) and check the project and location vars with 'lc providers vars <provider> list'."
+        } else {
+            "Check that the key has access to this model and endpoint."
+        };
+        return Some(ErrorHint::new(
+            "The credentials are not allowed to use this resource.",
+            next_step,
+        ));
+    }
+
+    // Misconfigured providers
+    if url.contains(".openai.azure.com") && !url.contains("api-version") {
+        return Some(ErrorHint::new(
+            "Azure OpenAI requests need an api-version query parameter.",
+            "Add it to the chat path, e.g. 'lc providers paths <provider> add -c \"/openai/deployments/{model}/chat/completions?api-version=2024-10-21\"'.",
+        ));
+    }
+    if status == 404 || status == 405 {
+        let next_step = if url.contains("/v1/v1/") {
+            "The endpoint already ends in /v1 and the path starts with /v1 again; fix the endpoint with 'lc providers update <provider> <url>' or the path with 'lc providers paths <provider> add -c <path>'."
+        } else {
+            "Check the chat_path with 'lc providers paths <provider> list'; most OpenAI-compatible APIs use '/chat/completions' under a '/v1' endpoint."
+        };
+        return Some(ErrorHint::new(
+            &format!("No API answered at {}.", url),
+            next_step,
+        ));
+    }
+    if body.trim_start().starts_with('<') {
+        return Some(ErrorHint::new(
+            "The endpoint answered with an HTML page instead of JSON.",
+            "Check the provider's endpoint URL with 'lc providers list'.",
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_provider_payloads() {
+        let openai = r#"{"error": {"message": "You exceeded your current quota", "type": "insufficient_quota", "code": "insufficient_quota"}}"#;
+        let hint = explain(429, openai, "https://api.openai.com/v1/chat/completions").unwrap();
+        assert!(hint.explanation.contains("credits"));
+
+        let anthropic =
+            r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
+        let hint = explain(529, anthropic, "https://api.anthropic.com/v1/messages").unwrap();
+        assert!(hint.explanation.contains("overloaded"));
+
+        let vertex = r#"{"error": {"code": 403, "message": "Permission 'aiplatform.endpoints.predict' denied", "status": "PERMISSION_DENIED"}}"#;
+        let hint = explain(
+            403,
+            vertex,
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/p/locations/us-central1/publishers/google/models/gemini:generateContent",
+        )
+        .unwrap();
+        assert!(hint.next_step.contains("roles/aiplatform.user"));
+
+        assert_eq!(
+            explain(500, r#"{"error": {"message": "boom"}}"#, "https://x/v1"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_explain_misconfiguration() {
+        let hint = explain(
+            404,
+            "Resource not found",
+            "https://me.openai.azure.com/openai/deployments/gpt-4o/chat/completions",
+        )
+        .unwrap();
+        assert!(hint.explanation.contains("api-version"));
+
+        let hint = explain(404, "", "https://api.example.com/v1/v1/chat/completions").unwrap();
+        assert!(hint.next_step.contains("/v1"));
+
+        let error = api_error(
+            "API",
+            reqwest::StatusCode::NOT_FOUND,
+            "not found",
+            "https://api.example.com/chat",
+        );
+        let message = error.to_string();
+        assert!(message.starts_with("API request failed with status 404 Not Found: not found"));
+        assert!(message.contains("chat_path"));
+    }
+}
@@ -55,9 +55,16 @@ pub use core::provider_installer;
 // Data modules
 pub mod data;
 // Re-export data modules at the top level for compatibility
+pub use data::backup;
+pub use data::chunking;
 pub use data::config;
 pub use data::database;
+pub use data::hybrid_search;
 pub use data::keys;
+pub use data::lock;
+pub use data::provider_groups;
+pub use data::trash;
+pub use data::vector_archive;
 pub use data::vector_db;
 
 // Model-related modules
@@ -99,18 +106,54 @@ pub mod readers;
 pub mod search;
 pub mod sync;
 
-// Global debug flag
 use std::sync::atomic::AtomicBool;
+
+// Global debug flag - set with the `--debug` filter; setting it directly turns on
+// debug_log! output for all modules
 pub static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
 
-// Debug logging macro
+// Global force flag - skips interactive capability pre-checks (e.g. vision support)
+pub static FORCE_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global timings flag - prints and records a latency breakdown after each request
+pub static TIMINGS_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global ReAct tools flag - tools are described in the prompt instead of sent natively
+pub static REACT_TOOLS_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global show-reasoning flag - reasoning traces are printed (dimmed) before the answer
+pub static SHOW_REASONING_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global read-only flag - config files and databases are never modified
+pub static READ_ONLY_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global auto-provider flag - a model given without provider is looked up in the models cache
+pub static AUTO_PROVIDER_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global override-budget flag - requests are sent even when they exceed the spending limits
+pub static OVERRIDE_BUDGET_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global auto-fetch-urls flag - URLs in the prompt are fetched and appended as context
+pub static AUTO_FETCH_URLS_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global fresh flag - cached web pages and search results are not used
+pub static FRESH_MODE: AtomicBool = AtomicBool::new(false);
+
+// Global ASCII flag - emoji and Unicode symbols in lc's output are replaced with ASCII
+pub static ASCII_MODE: AtomicBool = AtomicBool::new(false);
+
+// Debug logging macros - filtered by level and module with --debug (see utils::logging)
 #[macro_export]
 macro_rules! debug_log {
     ($($arg:tt)*) => {
-        if $crate::DEBUG_MODE.load(std::sync::atomic::Ordering::Relaxed) {
-            use colored::Colorize;
-            eprintln!("{} {}", "[DEBUG]".dimmed(), format!($($arg)*));
-        }
+        $crate::log_at!($crate::utils::logging::Level::Debug, $($arg)*)
+    };
+}
+
+#[macro_export]
+macro_rules! trace_log {
+    ($($arg:tt)*) => {
+        $crate::log_at!($crate::utils::logging::Level::Trace, $($arg)*)
     };
 }
 
@@ -78,6 +78,21 @@ async fn get_conversation_history(session_id: &str) -> Result<Vec<ChatMessage>>
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Values for the shell completion scripts (`lc __complete <kind>`). Handled before
+    // argument parsing so it stays fast and out of the generated completions.
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 && args[1] == cli::completion::COMPLETE_COMMAND {
+        return cli::completion::complete(args.get(2).map_or("", String::as_str));
+    }
+
+    // Git-style plugins: `lc foo ...` runs `lc-foo` from PATH unless foo is a built-in command
+    if let Some(code) = cli::plugins::run_if_plugin(args.get(1..).unwrap_or_default())? {
+        std::process::exit(code);
+    }
+
+    // Enable ANSI colors in Windows consoles
+    lc::utils::terminal::init();
+
     // Initialize model metadata configuration files
     if let Err(e) = model_metadata::initialize_model_metadata_config() {
         eprintln!("Warning: Failed to initialize model metadata config: {}", e);
@@ -95,13 +110,88 @@ async fn main() -> Result<()> {
         }
     }
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     // Set debug mode if flag is provided
-    cli::set_debug_mode(cli.debug);
+    cli::set_debug_filter(cli.debug.as_deref())?;
+    cli::set_force_mode(cli.prompt_args.force);
+    cli::set_react_tools_mode(cli.prompt_args.react_tools);
+    cli::set_timings_mode(cli.global_args.timings);
+    cli::set_show_reasoning_mode(cli.global_args.show_reasoning);
+    cli::set_read_only_mode(cli.global_args.read_only);
+    cli::set_auto_provider_mode(cli.global_args.auto_provider);
+    cli::set_override_budget_mode(cli.global_args.override_budget);
+    cli::set_auto_fetch_urls_mode(cli.global_args.auto_fetch_urls);
+    cli::set_fresh_mode(cli.global_args.fresh);
+    lc::core::deterministic::set(cli.global_args.deterministic);
+    lc::data::config_overrides::set(lc::data::config_overrides::parse_entries(
+        &cli.global_args.set,
+    )?);
+    if !cli.global_args.set.is_empty() {
+        // Report unknown keys and invalid values before anything else reads the config
+        lc::config::Config::load()?;
+    }
+    cli::set_ascii_mode(cli.global_args.ascii);
+    lc::core::provider_tools::set(match &cli.global_args.provider_tools {
+        Some(list) => lc::core::provider_tools::parse_list(list)?,
+        None => Vec::new(),
+    });
+    lc::core::validation::set(
+        cli.prompt_args.validate_cmd.clone(),
+        cli.prompt_args.validate_retries,
+    );
+    lc::core::style::set(cli.global_args.style.clone());
+    lc::core::self_check::set(
+        cli.prompt_args.self_check,
+        cli.prompt_args.self_check_model.clone(),
+    );
+    lc::core::refusal::set(
+        cli.global_args.on_refusal.clone(),
+        cli.global_args.fallback_model.clone(),
+    );
+    lc::hybrid_search::set(cli.global_args.hybrid_alpha);
+    lc::core::rerank::set(cli.global_args.rerank.clone());
+    lc::search::rewrite::set(cli.prompt_args.search_rewrite_model.clone());
+    lc::core::router::set_disabled(cli.prompt_args.no_route);
+    if let Some(schema) = &cli.prompt_args.schema {
+        lc::core::structured_output::set_schema(schema)?;
+    }
+    if let Some(cid) = cli
+        .prompt_args
+        .chat_id
+        .as_deref()
+        .filter(|cid| cid.starts_with("name:"))
+    {
+        cli.prompt_args.chat_id = Some(lc::database::Database::new()?.resolve_chat_id(cid)?);
+    }
+    lc::core::session_memory::set(cli.global_args.session_memory.clone());
+    lc::core::attachment_budget::set(
+        cli.global_args.attach_budget,
+        cli.global_args.attach_strategy,
+        cli.global_args.attach_embed_model.clone(),
+    );
+    lc::readers::table::set(cli.global_args.rows);
+    lc::core::request_metadata::set(
+        cli.global_args.user.clone(),
+        lc::core::request_metadata::parse_entries(&cli.global_args.metadata)?,
+    );
+    if let Err(e) = lc::core::redaction::init() {
+        eprintln!("Warning: secrets will not be redacted: {}", e);
+    }
+    if let Some(path) = &cli.prompt_args.system_file {
+        cli.prompt_args.system_prompt =
+            Some(lc::core::system_file::load(path, cli.model.as_deref())?);
+    }
+
+    // Integrity checks and scheduled backups (the backup command manages its own)
+    if !matches!(cli.command, Some(Commands::Backup { .. })) {
+        lc::backup::startup_maintenance();
+    }
 
     // Check for piped input first
-    let piped_input = check_for_piped_input()?;
+    let piped_input = check_for_piped_input(cli.global_args.max_stdin_mb)?;
+
+    let show_stats = cli.prompt_args.stats;
 
     // Handle direct prompt or subcommands
     match (cli.prompt.is_empty(), cli.command) {
@@ -113,48 +203,52 @@ async fn main() -> Result<()> {
             if let Some(template_name) = first_arg.strip_prefix("t:") {
                 // Load config to resolve template
                 let config = config::Config::load()?;
-                if let Some(template_content) = config.get_template(template_name) {
+                if let Some(template_content) = config.template_content(template_name)? {
+                    lc::core::style::set_template(template_name);
+                    lc::core::self_check::set_template(template_name);
                     if cli.prompt.len() > 1 {
                         // Use template as system prompt and remaining args as user prompt
                         let user_prompt = cli.prompt[1..].join(" ");
                         handle_prompt_with_optional_piped_input(
                             user_prompt,
-                            Some(template_content.clone()),
+                            Some(template_content),
                             piped_input,
                             cli.provider,
                             cli.model,
-                            cli.max_tokens,
-                            cli.temperature,
-                            cli.attachments,
-                            cli.images,
-                            cli.audio_files,
-                            cli.tools,
-                            cli.vectordb,
-                            cli.continue_session,
-                            cli.chat_id,
-                            cli.use_search,
-                            cli.stream,
+                            cli.prompt_args.max_tokens,
+                            cli.prompt_args.temperature,
+                            cli.prompt_args.attachments,
+                            cli.prompt_args.images,
+                            cli.prompt_args.audio_files,
+                            cli.prompt_args.audio_mode,
+                            cli.prompt_args.tools,
+                            cli.prompt_args.vectordb,
+                            cli.prompt_args.continue_session,
+                            cli.prompt_args.chat_id,
+                            cli.prompt_args.use_search,
+                            cli.prompt_args.stream,
                         )
                         .await?;
                     } else {
                         // Use template content as the prompt (no additional user prompt)
                         handle_prompt_with_optional_piped_input(
-                            template_content.clone(),
-                            cli.system_prompt,
+                            template_content,
+                            cli.prompt_args.system_prompt,
                             piped_input,
                             cli.provider,
                             cli.model,
-                            cli.max_tokens,
-                            cli.temperature,
-                            cli.attachments,
-                            cli.images,
-                            cli.audio_files,
-                            cli.tools,
-                            cli.vectordb,
-                            cli.continue_session,
-                            cli.chat_id,
-                            cli.use_search,
-                            cli.stream,
+                            cli.prompt_args.max_tokens,
+                            cli.prompt_args.temperature,
+                            cli.prompt_args.attachments,
+                            cli.prompt_args.images,
+                            cli.prompt_args.audio_files,
+                            cli.prompt_args.audio_mode,
+                            cli.prompt_args.tools,
+                            cli.prompt_args.vectordb,
+                            cli.prompt_args.continue_session,
+                            cli.prompt_args.chat_id,
+                            cli.prompt_args.use_search,
+                            cli.prompt_args.stream,
                         )
                         .await?;
                     }
@@ -166,24 +260,28 @@ async fn main() -> Result<()> {
                 let prompt = cli.prompt.join(" ");
                 handle_prompt_with_optional_piped_input(
                     prompt,
-                    cli.system_prompt,
+                    cli.prompt_args.system_prompt,
                     piped_input,
                     cli.provider,
                     cli.model,
-                    cli.max_tokens,
-                    cli.temperature,
-                    cli.attachments,
-                    cli.images,
-                    cli.audio_files,
-                    cli.tools,
-                    cli.vectordb,
-                    cli.continue_session,
-                    cli.chat_id,
-                    cli.use_search,
-                    cli.stream,
+                    cli.prompt_args.max_tokens,
+                    cli.prompt_args.temperature,
+                    cli.prompt_args.attachments,
+                    cli.prompt_args.images,
+                    cli.prompt_args.audio_files,
+                    cli.prompt_args.audio_mode,
+                    cli.prompt_args.tools,
+                    cli.prompt_args.vectordb,
+                    cli.prompt_args.continue_session,
+                    cli.prompt_args.chat_id,
+                    cli.prompt_args.use_search,
+                    cli.prompt_args.stream,
                 )
                 .await?;
             }
+            if show_stats {
+                lc::analytics::session_summary::print_current_session_summary().await;
+            }
         }
         (true, Some(Commands::Providers { command })) => {
             cli::providers::handle(command).await?;
@@ -192,21 +290,26 @@ async fn main() -> Result<()> {
             cli::keys::handle(command).await?;
         }
         (true, Some(Commands::Logs { command })) => {
-            cli::logging::handle(command).await?;
+            cli::logging::handle(*command, cli.provider.clone(), cli.model.clone()).await?;
         }
-        (
-            true,
-            Some(Commands::Usage {
+        (true, Some(Commands::Backup { command })) => {
+            cli::backup::handle(command)?;
+        }
+        (true, Some(Commands::Usage(args))) => {
+            let cli::UsageArgs {
                 command,
                 days,
+                since,
+                until,
                 tokens_only,
                 requests_only,
                 limit,
-            }),
-        ) => {
+            } = *args;
             cli::usage::handle(
                 command,
                 days.map(|d| d as u64),
+                since,
+                until,
                 tokens_only,
                 requests_only,
                 Some(limit),
@@ -216,37 +319,40 @@ async fn main() -> Result<()> {
         (true, Some(Commands::Config { command })) => {
             cli::config::handle(command).await?;
         }
-        (
-            true,
-            Some(Commands::Chat {
+        (true, Some(Commands::Chat(args))) => {
+            let cli::ChatArgs {
                 model,
                 provider,
                 cid,
+                name,
                 tools,
                 database,
                 debug,
                 images,
-            }),
-        ) => {
+                schema,
+            } = *args;
             // Merge subcommand-scoped flags with global flags so users can pass -m/-p before "chat"
             let effective_provider = provider.or_else(|| cli.provider.clone());
             let effective_model = model.or_else(|| cli.model.clone());
+            if let Some(schema) = &schema {
+                lc::core::structured_output::set_schema(schema)?;
+            }
 
             cli::chat::handle(
                 effective_model,
                 effective_provider,
                 cid,
+                name,
                 tools,
                 database,
                 debug,
-                !images.is_empty(), // Convert Vec<String> to bool
-                cli.stream,
+                images,
+                cli.prompt_args.stream,
             )
             .await?;
         }
-        (
-            true,
-            Some(Commands::Models {
+        (true, Some(Commands::Models(args))) => {
+            let cli::ModelsArgs {
                 command,
                 query,
                 tools,
@@ -254,13 +360,13 @@ async fn main() -> Result<()> {
                 vision,
                 audio,
                 code,
+                strict_filters,
                 context_length,
                 input_length,
                 output_length,
                 input_price,
                 output_price,
-            }),
-        ) => {
+            } = *args;
             // Convert individual boolean flags to tags string
             let mut tags = Vec::new();
             if tools {
@@ -287,8 +393,10 @@ async fn main() -> Result<()> {
 
             cli::models::handle(
                 command,
+                cli.provider.clone(),
                 query,
                 tags_string,
+                strict_filters,
                 context_length.map(|s| s.parse().unwrap_or(0)),
                 input_length.map(|s| s.parse().unwrap_or(0)),
                 output_length.map(|s| s.parse().unwrap_or(0)),
@@ -303,17 +411,20 @@ async fn main() -> Result<()> {
         (true, Some(Commands::Templates { command })) => {
             cli::templates::handle(command).await?;
         }
-        (
-            true,
-            Some(Commands::Proxy {
+        (true, Some(Commands::Styles { command })) => {
+            cli::styles::handle(command).await?;
+        }
+        (true, Some(Commands::Proxy(args))) => {
+            let cli::ProxyArgs {
                 port,
                 host,
                 provider,
                 model,
                 api_key,
                 generate_key,
-            }),
-        ) => {
+                admin_key,
+                generate_admin_key,
+            } = *args;
             cli::proxy::handle(
                 Some(port),
                 Some(host),
@@ -321,39 +432,68 @@ async fn main() -> Result<()> {
                 model,
                 api_key,
                 generate_key,
+                admin_key,
+                generate_admin_key,
             )
             .await?;
         }
         (true, Some(Commands::Mcp { command })) => {
             cli::mcp::handle(command).await?;
         }
-        (
-            true,
-            Some(Commands::Embed {
+        (true, Some(Commands::Embed(args))) => {
+            let cli::EmbedArgs {
                 model,
                 provider,
                 database,
                 files,
                 text,
                 debug,
-            }),
-        ) => {
-            cli::embed::handle_embed_command(model, provider, database, files, text, debug).await?;
+                tag_model,
+                chunk_strategy,
+                chunk_size,
+                chunk_overlap,
+                resume,
+            } = *args;
+            let chunk_options =
+                lc::chunking::ChunkOptions::new(chunk_strategy, chunk_size, chunk_overlap)?;
+            cli::embed::handle_embed_command(
+                model,
+                provider,
+                database,
+                files,
+                text,
+                debug,
+                tag_model,
+                chunk_options,
+                resume,
+            )
+            .await?;
         }
-        (
-            true,
-            Some(Commands::Similar {
+        (true, Some(Commands::Similar(args))) => {
+            let cli::SimilarArgs {
                 model,
                 provider,
                 database,
                 limit,
+                tags,
                 query,
-            }),
-        ) => {
-            cli::embed::handle_similar_command(model, provider, database, limit, query).await?;
+            } = *args;
+            cli::embed::handle_similar_command(model, provider, database, limit, tags, query)
+                .await?;
         }
         (true, Some(Commands::Vectors { command })) => {
-            cli::vectors::handle(command).await?;
+            cli::vectors::handle(command, cli.provider.clone(), cli.model.clone()).await?;
+        }
+        (true, Some(Commands::ServeWebui(args))) => {
+            let cli::ServeWebuiArgs {
+                port,
+                host,
+                provider,
+                model,
+                vectordb,
+                tools,
+            } = *args;
+            cli::webui::handle(port, host, provider, model, vectordb, tools).await?;
         }
         (true, Some(Commands::WebChatProxy { command })) => {
             cli::webchatproxy::handle(command).await?;
@@ -361,12 +501,14 @@ async fn main() -> Result<()> {
         (true, Some(Commands::Sync { command })) => {
             cli::sync::handle(command).await?;
         }
+        (true, Some(Commands::Undo { list, yes })) => {
+            cli::undo::handle(list, yes)?;
+        }
         (true, Some(Commands::Search { command })) => {
             cli::search::handle(command).await?;
         }
-        (
-            true,
-            Some(Commands::Image {
+        (true, Some(Commands::Image(args))) => {
+            let cli::ImageArgs {
                 prompt,
                 model,
                 provider,
@@ -374,8 +516,7 @@ async fn main() -> Result<()> {
                 count,
                 output,
                 debug,
-            }),
-        ) => {
+            } = *args;
             cli::image::handle(
                 vec![prompt],
                 model,
@@ -387,9 +528,97 @@ async fn main() -> Result<()> {
             )
             .await?;
         }
-        (
-            true,
-            Some(Commands::Transcribe {
+        (true, Some(Commands::Watch(args))) => {
+            let cli::WatchArgs {
+                prompt,
+                attachments,
+                system_prompt,
+                tools,
+                stream,
+                debounce,
+            } = *args;
+            cli::watch::handle(
+                cli::watch::WatchPrompt {
+                    prompt: prompt.join(" "),
+                    attachments,
+                    provider: cli.provider.clone(),
+                    model: cli.model.clone(),
+                    system_prompt: system_prompt.or(cli.prompt_args.system_prompt.clone()),
+                    max_tokens: cli.prompt_args.max_tokens.clone(),
+                    temperature: cli.prompt_args.temperature.clone(),
+                    tools: tools.or(cli.prompt_args.tools.clone()),
+                    stream: stream || cli.prompt_args.stream,
+                },
+                debounce,
+            )
+            .await?;
+        }
+        (true, Some(Commands::Duo(args))) => {
+            let cli::DuoArgs {
+                a,
+                b,
+                opening,
+                turns,
+                judge,
+            } = *args;
+            cli::duo::handle(cli::duo::DuoOptions {
+                a,
+                b,
+                opening,
+                turns,
+                judge,
+                system_prompt: cli.prompt_args.system_prompt.clone(),
+                max_tokens: cli.prompt_args.max_tokens.clone(),
+                temperature: cli.prompt_args.temperature.clone(),
+            })
+            .await?;
+        }
+        (true, Some(Commands::Extract(args))) => {
+            let cli::ExtractArgs {
+                schema,
+                attachments,
+                instructions,
+                output,
+                retries,
+                source_key,
+                no_response_format,
+            } = *args;
+            cli::extract::handle(cli::extract::ExtractOptions {
+                schema,
+                attachments,
+                instructions,
+                output,
+                retries,
+                source_key,
+                no_response_format,
+                provider: cli.provider.clone(),
+                model: cli.model.clone(),
+                system_prompt: cli.prompt_args.system_prompt.clone(),
+                max_tokens: cli.prompt_args.max_tokens.clone(),
+                temperature: cli.prompt_args.temperature.clone(),
+            })
+            .await?;
+        }
+        (true, Some(Commands::Quickfix(args))) => {
+            let cli::QuickfixArgs {
+                command,
+                rounds,
+                yes,
+            } = *args;
+            cli::quickfix::handle(cli::quickfix::QuickfixOptions {
+                command,
+                rounds,
+                yes,
+                provider: cli.provider.clone(),
+                model: cli.model.clone(),
+                system_prompt: cli.prompt_args.system_prompt.clone(),
+                max_tokens: cli.prompt_args.max_tokens.clone(),
+                temperature: cli.prompt_args.temperature.clone(),
+            })
+            .await?;
+        }
+        (true, Some(Commands::Transcribe(args))) => {
+            let cli::TranscribeArgs {
                 audio_files,
                 model,
                 provider,
@@ -399,8 +628,7 @@ async fn main() -> Result<()> {
                 temperature,
                 output,
                 debug,
-            }),
-        ) => {
+            } = *args;
             cli::audio::handle_transcribe(
                 audio_files,
                 model,
@@ -414,9 +642,8 @@ async fn main() -> Result<()> {
             )
             .await?;
         }
-        (
-            true,
-            Some(Commands::TTS {
+        (true, Some(Commands::TTS(args))) => {
+            let cli::TtsArgs {
                 text,
                 model,
                 provider,
@@ -425,8 +652,7 @@ async fn main() -> Result<()> {
                 speed,
                 output,
                 debug,
-            }),
-        ) => {
+            } = *args;
             cli::audio::handle_tts(
                 text,
                 model,
@@ -445,6 +671,21 @@ async fn main() -> Result<()> {
         (true, Some(Commands::Completions { shell })) => {
             cli::completion::handle(shell).await?;
         }
+        (true, Some(Commands::Plugins { command })) => {
+            cli::plugins::handle(command)?;
+        }
+        (true, Some(Commands::Doctor)) => {
+            cli::doctor::handle()?;
+        }
+        (true, Some(Commands::UpgradeSelf(args))) => {
+            let cli::UpgradeSelfArgs {
+                check,
+                version,
+                yes,
+                force,
+            } = *args;
+            cli::upgrade::handle(check, version, yes, force).await?;
+        }
         (true, None) => {
             // No subcommand or prompt provided, check if input is piped
             if let Some(piped_content) = piped_input {
@@ -452,22 +693,26 @@ async fn main() -> Result<()> {
                 if !piped_content.trim().is_empty() {
                     handle_prompt_with_optional_piped_input_continue(
                         piped_content,
-                        cli.system_prompt,
+                        cli.prompt_args.system_prompt,
                         cli.provider,
                         cli.model,
-                        cli.max_tokens,
-                        cli.temperature,
-                        cli.attachments,
-                        cli.images,
-                        cli.audio_files,
-                        cli.tools,
-                        cli.vectordb,
-                        cli.continue_session,
-                        cli.chat_id,
-                        cli.use_search,
-                        cli.stream,
+                        cli.prompt_args.max_tokens,
+                        cli.prompt_args.temperature,
+                        cli.prompt_args.attachments,
+                        cli.prompt_args.images,
+                        cli.prompt_args.audio_files,
+                        cli.prompt_args.audio_mode,
+                        cli.prompt_args.tools,
+                        cli.prompt_args.vectordb,
+                        cli.prompt_args.continue_session,
+                        cli.prompt_args.chat_id,
+                        cli.prompt_args.use_search,
+                        cli.prompt_args.stream,
                     )
                     .await?;
+                    if show_stats {
+                        lc::analytics::session_summary::print_current_session_summary().await;
+                    }
                 } else {
                     use clap::CommandFactory;
                     let mut cmd = Cli::command();
@@ -490,9 +735,7 @@ async fn main() -> Result<()> {
 }
 
 // Helper function to check for piped input
-fn check_for_piped_input() -> Result<Option<String>> {
-    use std::io::{self, Read};
-
+fn check_for_piped_input(max_mb: Option<u64>) -> Result<Option<String>> {
     // Check if stdin is a terminal (interactive) or piped
     if atty::is(atty::Stream::Stdin) {
         // stdin is a terminal, no piped input
@@ -500,13 +743,11 @@ fn check_for_piped_input() -> Result<Option<String>> {
     }
 
     // stdin is piped, read the content
-    let mut stdin = io::stdin();
-    let mut buffer = String::new();
-
-    match stdin.read_to_string(&mut buffer) {
-        Ok(0) => Ok(None),         // No input available
-        Ok(_) => Ok(Some(buffer)), // Input was piped
-        Err(_) => Ok(None),        // Error reading stdin
+    match lc::utils::piped_input::read_stdin(max_mb) {
+        Ok(buffer) if buffer.is_empty() => Ok(None), // No input available
+        Ok(buffer) => Ok(Some(buffer)),              // Input was piped
+        Err(e) if e.downcast_ref::<std::io::Error>().is_some() => Ok(None), // Error reading stdin
+        Err(e) => Err(e),                            // Input over --max-stdin-mb
     }
 }
 
@@ -523,6 +764,7 @@ async fn handle_prompt_with_optional_piped_input(
     attachments: Vec<String>,
     images: Vec<String>,
     audio_files: Vec<String>,
+    audio_mode: cli::AudioMode,
     tools: Option<String>,
     vectordb: Option<String>,
     continue_session: bool,
@@ -543,6 +785,7 @@ async fn handle_prompt_with_optional_piped_input(
             attachments,
             images,
             audio_files,
+            audio_mode,
             tools,
             vectordb,
             continue_session,
@@ -563,6 +806,7 @@ async fn handle_prompt_with_optional_piped_input(
             attachments,
             images,
             audio_files,
+            audio_mode,
             tools,
             vectordb,
             continue_session,
@@ -586,6 +830,7 @@ async fn handle_prompt_with_optional_piped_input_continue(
     attachments: Vec<String>,
     images: Vec<String>,
     audio_files: Vec<String>,
+    audio_mode: cli::AudioMode,
     tools: Option<String>,
     vectordb: Option<String>,
     continue_session: bool,
@@ -605,6 +850,7 @@ async fn handle_prompt_with_optional_piped_input_continue(
             attachments,
             images,
             audio_files,
+            audio_mode,
             tools,
             vectordb,
             continue_session,
@@ -625,6 +871,7 @@ async fn handle_prompt_with_optional_piped_input_continue(
             attachments,
             images,
             audio_files,
+            audio_mode,
             tools,
             vectordb,
             use_search,
@@ -645,6 +892,7 @@ async fn handle_direct_prompt_with_session(
     attachments: Vec<String>,
     images: Vec<String>,
     audio_files: Vec<String>,
+    audio_mode: cli::AudioMode,
     tools: Option<String>,
     vectordb: Option<String>,
     continue_session: bool,
@@ -652,6 +900,7 @@ async fn handle_direct_prompt_with_session(
     use_search: Option<String>,
     stream: bool,
 ) -> Result<()> {
+    let prompt = lc::readers::web::with_url_context(prompt).await;
     if continue_session {
         // Get or create session ID
         let session_id = if let Some(cid) = chat_id {
@@ -685,6 +934,24 @@ async fn handle_direct_prompt_with_session(
             return Ok(());
         }
 
+        // Tools and vector database the session was started with, unless given again
+        let settings = Database::new()
+            .and_then(|db| db.get_session_settings(&session_id))
+            .unwrap_or_else(|e| {
+                lc::debug_log!("Could not read session settings: {}", e);
+                Default::default()
+            });
+        let tools = tools.or_else(|| {
+            settings
+                .tools
+                .inspect(|tools| eprintln!("Note: using the session's tools '{}'", tools))
+        });
+        let vectordb = vectordb.or_else(|| {
+            settings
+                .vectordb
+                .inspect(|db| eprintln!("Note: using the session's vector database '{}'", db))
+        });
+
         // Use provided model/provider if available, otherwise try to infer from history
         let final_model = model.or_else(|| {
             // Get model from the first message in history
@@ -743,6 +1010,7 @@ async fn handle_direct_prompt_with_session(
             attachments,
             images,
             audio_files,
+            audio_mode,
             tools,
             vectordb,
             use_search,
@@ -760,11 +1028,11 @@ async fn handle_session_prompt(
     system_prompt: Option<String>,
     max_tokens: Option<String>,
     temperature: Option<String>,
-    _attachments: Vec<String>,
+    attachments: Vec<String>,
     _images: Vec<String>,
-    _tools: Option<String>,
-    _vectordb: Option<String>,
-    _session_id: String,
+    tools: Option<String>,
+    vectordb: Option<String>,
+    session_id: String,
     history: Vec<ChatMessage>,
     _use_search: Option<String>,
     _stream: bool,
@@ -784,6 +1052,7 @@ async fn handle_session_prompt(
                 timestamp: chrono::Utc::now(),
                 input_tokens: None,
                 output_tokens: None,
+                provider: None,
             };
             chat_entries.push(entry);
             i += 2;
@@ -884,6 +1153,8 @@ async fn handle_session_prompt(
     // Create authenticated client
     let mut config = config::Config::load()?;
     let client = chat::create_authenticated_client(&mut config, &provider_name).await?;
+    let style = lc::core::style::active(&config)?;
+    let system_prompt = lc::core::style::compose(system_prompt, style.as_ref());
 
     // Strip provider prefix from model name for API call
     // Handle cases where model name itself contains colons (e.g., gpt-oss:20b)
@@ -893,22 +1164,77 @@ async fn handle_session_prompt(
         model_name.clone()
     };
 
+    let prompt = if attachments.is_empty() {
+        prompt
+    } else {
+        let attached = lc::core::attachment_budget::prepare(
+            &attachments,
+            &prompt,
+            &client,
+            &api_model_name,
+            &provider_name,
+        )
+        .await?;
+        format!("{}\n\n{}", prompt, attached)
+    };
+
+    // Chunks from the session's vector database go before the question
+    let prompt = match &vectordb {
+        Some(db_name) => {
+            let context = lc::cli::embed::retrieve_rag_matches(db_name, &prompt).await?;
+            cli::prompts::with_rag_context(db_name, prompt, context, false).0
+        }
+        None => prompt,
+    };
+
+    let (mcp_tools, mcp_server_names) = match &tools {
+        Some(tools) => lc::core::tools::fetch_mcp_tools(tools).await?,
+        None => (None, Vec::new()),
+    };
+
     // Send chat request with history
-    let (response, _input_tokens, _output_tokens) = chat::send_chat_request_with_validation(
-        &client,
-        &api_model_name,
-        &prompt,
-        &chat_entries,
-        system_prompt.as_deref(),
-        max_tokens_parsed,
-        temperature_parsed,
-        &provider_name,
-        None, // No tools for now
-    )
-    .await?;
+    let (response, _input_tokens, _output_tokens) =
+        if mcp_tools.is_some() && !mcp_server_names.is_empty() {
+            let server_refs: Vec<&str> = mcp_server_names.iter().map(|s| s.as_str()).collect();
+            chat::send_chat_request_with_tool_execution(
+                &client,
+                &api_model_name,
+                &prompt,
+                &chat_entries,
+                system_prompt.as_deref(),
+                max_tokens_parsed,
+                temperature_parsed,
+                &provider_name,
+                mcp_tools,
+                &server_refs,
+                None, // Use default max_iterations
+            )
+            .await?
+        } else {
+            chat::send_chat_request_with_validation(
+                &client,
+                &api_model_name,
+                &prompt,
+                &chat_entries,
+                system_prompt.as_deref(),
+                max_tokens_parsed,
+                temperature_parsed,
+                &provider_name,
+                None,
+            )
+            .await?
+        };
+    let response = lc::services::wasm_plugins::post_process(response);
 
     // Print the response
-    println!("{}", response);
+    println!("{}", lc::core::redaction::redact(&response));
+
+    // Tools or a vector database given with this continuation replace the session's
+    let overrides = lc::database::SessionSettings { tools, vectordb };
+    if let Err(e) = Database::new().and_then(|db| db.save_session_settings(&session_id, &overrides))
+    {
+        lc::debug_log!("Failed to save session settings: {}", e);
+    }
 
     Ok(())
 }
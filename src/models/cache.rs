@@ -1,3 +1,4 @@
+use crate::utils::terminal::sym;
 use crate::{config::Config, provider::OpenAIClient};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -64,6 +65,10 @@ impl ModelsCache {
     }
 
     pub fn save(&mut self) -> Result<()> {
+        // The cache is rebuilt on demand, so it is simply not persisted in read-only mode
+        if crate::lock::is_read_only() {
+            return Ok(());
+        }
         let cache_path = Self::cache_file_path()?;
 
         // Ensure cache directory exists
@@ -129,10 +134,10 @@ impl ModelsCache {
                     new_models.insert(provider_name.clone(), model_names);
                     successful_providers += 1;
                     total_models += count;
-                    println!("✓ ({} models)", count);
+                    println!("{} ({} models)", sym("✓"), count);
                 }
                 Err(e) => {
-                    println!("✗ ({})", e);
+                    println!("{} ({})", sym("✗"), e);
                 }
             }
         }
@@ -0,0 +1,206 @@
+//! Model deprecation checks
+//!
+//! Deprecation flags, shutdown dates and replacements are extracted from provider
+//! `/models` responses (where published) into the models cache. This module looks
+//! them up for the models a user actually depends on — the default model, alias
+//! targets and template-pinned models — and prints migration hints.
+
+use crate::config::Config;
+use crate::model_metadata::ModelMetadata;
+use crate::unified_cache::UnifiedCache;
+use crate::utils::terminal::sym;
+use colored::Colorize;
+
+/// Deprecation details for a single provider model
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeprecationNotice {
+    pub provider: String,
+    pub model: String,
+    pub date: Option<String>,
+    pub replacement: Option<String>,
+}
+
+impl DeprecationNotice {
+    /// Build a notice when the metadata marks the model as deprecated or publishes a date
+    pub fn from_metadata(metadata: &ModelMetadata) -> Option<Self> {
+        if !metadata.is_deprecated && metadata.deprecation_date.is_none() {
+            return None;
+        }
+        Some(Self {
+            provider: metadata.provider.clone(),
+            model: metadata.id.clone(),
+            date: metadata.deprecation_date.clone(),
+            replacement: metadata.replacement_model.clone(),
+        })
+    }
+
+    /// Whether the published date has already passed
+    pub fn is_past_date(&self) -> bool {
+        self.date
+            .as_deref()
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .map(|d| d <= chrono::Utc::now().date_naive())
+            .unwrap_or(false)
+    }
+
+    /// One-line description, e.g. "is deprecated (shutdown on 2025-01-01); use gpt-4o instead"
+    pub fn describe(&self) -> String {
+        let mut text = match &self.date {
+            Some(date) if self.is_past_date() => format!("was retired on {}", date),
+            Some(date) => format!("is deprecated (shutdown on {})", date),
+            None => "is deprecated".to_string(),
+        };
+        if let Some(replacement) = &self.replacement {
+            text.push_str(&format!("; use {} instead", replacement));
+        }
+        text
+    }
+
+    /// Suggested command to move an alias or default to the replacement model
+    pub fn migration_hint(&self, origin: &ModelOrigin) -> Option<String> {
+        let replacement = self.replacement.as_ref()?;
+        match origin {
+            ModelOrigin::Default => Some(format!("lc config set model {}", replacement)),
+            ModelOrigin::Alias(name) => Some(format!(
+                "lc alias add {} {}:{}",
+                name, self.provider, replacement
+            )),
+            ModelOrigin::Template(_) | ModelOrigin::Explicit => None,
+        }
+    }
+}
+
+/// Where a model reference came from, used to word warnings
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelOrigin {
+    Default,
+    Alias(String),
+    Template(String),
+    Explicit,
+}
+
+impl ModelOrigin {
+    /// Determine the origin of the model a command will use from its `-m` argument
+    pub fn from_model_arg(config: &Config, model_arg: Option<&str>) -> Self {
+        match model_arg {
+            None => ModelOrigin::Default,
+            Some(m) if config.aliases.contains_key(m) => ModelOrigin::Alias(m.to_string()),
+            Some(_) => ModelOrigin::Explicit,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            ModelOrigin::Default => "Default model".to_string(),
+            ModelOrigin::Alias(name) => format!("Alias '{}' target", name),
+            ModelOrigin::Template(provider) => format!("Chat template pinned in '{}'", provider),
+            ModelOrigin::Explicit => "Model".to_string(),
+        }
+    }
+}
+
+/// Find the deprecation notice for a model in a provider's cached metadata
+pub fn find_in_models(models: &[ModelMetadata], model: &str) -> Option<DeprecationNotice> {
+    models
+        .iter()
+        .find(|m| m.id == model)
+        .and_then(DeprecationNotice::from_metadata)
+}
+
+/// Look up deprecation info for a model from the models cache (no network requests)
+pub async fn check_model(provider: &str, model: &str) -> Option<DeprecationNotice> {
+    // Models may be passed with a provider prefix (e.g. "openai:gpt-4")
+    let model = match model.split_once(':') {
+        Some((prefix, rest)) if prefix == provider => rest,
+        _ => model,
+    };
+    let models = UnifiedCache::load_provider_models(provider).await.ok()?;
+    find_in_models(&models, model)
+}
+
+/// Print a warning to stderr when the model about to be used is deprecated
+pub async fn warn_if_deprecated(provider: &str, model: &str, origin: &ModelOrigin) {
+    if let Some(notice) = check_model(provider, model).await {
+        print_warning(&notice, origin);
+    }
+}
+
+fn print_warning(notice: &DeprecationNotice, origin: &ModelOrigin) {
+    eprintln!(
+        "{} {} '{}:{}' {}",
+        sym("⚠️").yellow(),
+        origin.label(),
+        notice.provider,
+        notice.model,
+        notice.describe()
+    );
+    if let Some(hint) = notice.migration_hint(origin) {
+        eprintln!("   {} {}", "Migrate with:".dimmed(), hint.cyan());
+    }
+}
+
+/// Scan the default model, aliases and template-pinned models for deprecations
+pub async fn scan_config(config: &Config) -> Vec<(ModelOrigin, DeprecationNotice)> {
+    let mut references: Vec<(ModelOrigin, String, String)> = Vec::new();
+
+    if let (Some(provider), Some(model)) = (&config.default_provider, &config.default_model) {
+        references.push((ModelOrigin::Default, provider.clone(), model.clone()));
+    }
+
+    let mut aliases: Vec<_> = config.aliases.iter().collect();
+    aliases.sort();
+    for (name, target) in aliases {
+        if let Some((provider, model)) = target.split_once(':') {
+            references.push((
+                ModelOrigin::Alias(name.clone()),
+                provider.to_string(),
+                model.to_string(),
+            ));
+        }
+    }
+
+    let mut providers: Vec<_> = config.providers.iter().collect();
+    providers.sort_by(|a, b| a.0.cmp(b.0));
+    for (provider, provider_config) in providers {
+        if let Some(templates) = &provider_config.chat_templates {
+            let mut models: Vec<_> = templates.keys().collect();
+            models.sort();
+            for model in models {
+                references.push((
+                    ModelOrigin::Template(provider.clone()),
+                    provider.clone(),
+                    model.clone(),
+                ));
+            }
+        }
+    }
+
+    let mut notices = Vec::new();
+    for (origin, provider, model) in references {
+        if let Some(notice) = check_model(&provider, &model).await {
+            notices.push((origin, notice));
+        }
+    }
+    notices
+}
+
+/// Print the results of [`scan_config`]
+pub fn display_scan(notices: &[(ModelOrigin, DeprecationNotice)]) {
+    if notices.is_empty() {
+        println!(
+            "{} No deprecated models found in your default model, aliases or templates",
+            sym("✓").green()
+        );
+        println!(
+            "{}",
+            "Deprecation info comes from the models cache; run 'lc models refresh' to update it."
+                .dimmed()
+        );
+        return;
+    }
+
+    println!("\n{}", "Deprecated models in use:".bold().yellow());
+    for (origin, notice) in notices {
+        print_warning(notice, origin);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::Colorize;
 use serde_json::Value;
@@ -12,7 +13,7 @@ impl MetadataDumper {
 
         println!(
             "{} Dumping fresh raw metadata for all configured providers...",
-            "🔍".blue()
+            sym("🔍").blue()
         );
 
         let config = Config::load()?;
@@ -23,14 +24,14 @@ impl MetadataDumper {
             fs::create_dir_all(&models_raw_dir).await?;
             println!(
                 "{} Created directory: {}",
-                "📁".blue(),
+                sym("📁").blue(),
                 models_raw_dir.display()
             );
         }
 
         println!(
             "{} Raw models directory: {}",
-            "📁".blue(),
+            sym("📁").blue(),
             models_raw_dir.display()
         );
 
@@ -46,25 +47,33 @@ impl MetadataDumper {
 
             // Skip providers without API keys
             if provider_config.api_key.is_none() {
-                println!("{} Skipping {} (no API key)", "⚠️".yellow(), provider_name);
+                println!(
+                    "{} Skipping {} (no API key)",
+                    sym("⚠️").yellow(),
+                    provider_name
+                );
                 continue;
             }
 
             println!(
                 "{} Fetching fresh models from {}...",
-                "📡".blue(),
+                sym("📡").blue(),
                 provider_name
             );
 
             match Self::fetch_and_save_raw_metadata(&config, provider_name, &models_raw_dir).await {
                 Ok(_) => {
-                    println!("{} Saved {} raw models data", "✅".green(), provider_name);
+                    println!(
+                        "{} Saved {} raw models data",
+                        sym("✅").green(),
+                        provider_name
+                    );
                     successful_dumps += 1;
                 }
                 Err(e) => {
                     println!(
                         "{} Failed to fetch models from {}: {}",
-                        "❌".red(),
+                        sym("❌").red(),
                         provider_name,
                         e
                     );
@@ -72,13 +81,13 @@ impl MetadataDumper {
             }
         }
 
-        println!("\n{} Summary:", "📊".blue());
+        println!("\n{} Summary:", sym("📊").blue());
         println!("   Total providers: {}", total_providers);
         println!("   Successful dumps: {}", successful_dumps);
         println!("   Raw data saved to: {}", models_raw_dir.display());
 
         if successful_dumps > 0 {
-            println!("\n{} Raw metadata dump complete!", "🎉".green());
+            println!("\n{} Raw metadata dump complete!", sym("🎉").green());
             println!("   Next step: Analyze the JSON files to debug metadata patterns");
         }
 
@@ -91,7 +100,7 @@ impl MetadataDumper {
 
         println!(
             "{} Dumping fresh raw metadata for provider: {}",
-            "🔍".blue(),
+            sym("🔍").blue(),
             provider_name
         );
 
@@ -109,7 +118,7 @@ impl MetadataDumper {
             fs::create_dir_all(&models_raw_dir).await?;
             println!(
                 "{} Created directory: {}",
-                "📁".blue(),
+                sym("📁").blue(),
                 models_raw_dir.display()
             );
         }
@@ -118,7 +127,7 @@ impl MetadataDumper {
             Ok(_) => {
                 println!(
                     "\n{} Successfully dumped fresh raw metadata for {}",
-                    "✅".green(),
+                    sym("✅").green(),
                     provider_name
                 );
             }
@@ -134,7 +143,7 @@ impl MetadataDumper {
     pub async fn list_cached_metadata() -> Result<()> {
         let models_raw_dir = Self::get_models_raw_dir()?;
 
-        println!("{} Available raw metadata files:", "📋".blue());
+        println!("{} Available raw metadata files:", sym("📋").blue());
         println!();
 
         if !models_raw_dir.exists() {
@@ -174,7 +183,7 @@ impl MetadataDumper {
         for (provider_name, path, size) in files {
             println!(
                 "  {} {} - {} ({})",
-                "•".blue(),
+                sym("•").blue(),
                 provider_name,
                 path.display(),
                 size
@@ -183,7 +192,7 @@ impl MetadataDumper {
 
         println!(
             "\n{} Use 'lc dump <provider>' to fetch fresh raw data for a specific provider",
-            "💡".yellow()
+            sym("💡").yellow()
         );
 
         Ok(())
@@ -213,14 +222,18 @@ impl MetadataDumper {
 
         fs::write(&filepath, &raw_response).await?;
 
-        println!("{} Saved raw data to: {}", "💾".green(), filepath.display());
+        println!(
+            "{} Saved raw data to: {}",
+            sym("💾").green(),
+            filepath.display()
+        );
 
         Ok(())
     }
 
     /// Fetch raw models response from provider API
     async fn fetch_raw_models_response(
-        _client: &crate::chat::LLMClient,
+        client: &crate::chat::LLMClient,
         provider_config: &crate::config::ProviderConfig,
     ) -> Result<String> {
         // No need to import debug_log, it's a macro exported from lib.rs
@@ -258,7 +271,7 @@ impl MetadataDumper {
         }
 
         // Only add Authorization header if no custom headers are present
-        if !has_custom_headers {
+        if !has_custom_headers && !client.signs_requests() {
             let api_key = provider_config
                 .api_key
                 .as_ref()
@@ -270,7 +283,7 @@ impl MetadataDumper {
         }
 
         crate::debug_log!("Sending HTTP GET request...");
-        let response = req.send().await?;
+        let response = client.send(req).await?;
 
         let status = response.status();
         crate::debug_log!("Received response with status: {}", status);
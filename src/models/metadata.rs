@@ -2,7 +2,7 @@ use crate::provider::Provider;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::OnceLock;
@@ -42,11 +42,20 @@ pub struct ModelMetadata {
     pub supports_json_mode: bool,
     /// Only set to `true` when provider JSON explicitly indicates streaming support
     pub supports_streaming: bool,
+    /// Capabilities (`tools`, `vision`, ...) the provider JSON says nothing about either way
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unknown_capabilities: Vec<String>,
 
     // Model type and characteristics
     pub model_type: ModelType,
     /// Only set to `true` when provider JSON explicitly indicates the model is deprecated
     pub is_deprecated: bool,
+    /// Deprecation or shutdown date published by the provider (YYYY-MM-DD when parseable)
+    #[serde(default)]
+    pub deprecation_date: Option<String>,
+    /// Provider-recommended replacement model, if published
+    #[serde(default)]
+    pub replacement_model: Option<String>,
     /// Only set to `true` when provider JSON explicitly indicates the model supports fine-tuning
     pub is_fine_tunable: bool,
 
@@ -87,14 +96,56 @@ impl Default for ModelMetadata {
             supports_function_calling: false,
             supports_json_mode: false,
             supports_streaming: false,
+            unknown_capabilities: Vec::new(),
             model_type: ModelType::Chat,
             is_deprecated: false,
+            deprecation_date: None,
+            replacement_model: None,
             is_fine_tunable: false,
             raw_data: serde_json::Value::Null,
         }
     }
 }
 
+/// Capabilities that model filters understand, with the tags that can state each one
+pub const CAPABILITY_TAGS: &[(&str, &[&str])] = &[
+    ("tools", &["supports_tools", "supports_function_calling"]),
+    ("vision", &["supports_vision"]),
+    ("audio", &["supports_audio"]),
+    ("reasoning", &["supports_reasoning"]),
+    ("code", &["supports_code"]),
+];
+
+/// What a model's metadata says about one capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    Supported,
+    Unsupported,
+    /// The provider's metadata neither claims nor denies it
+    Unknown,
+}
+
+impl ModelMetadata {
+    /// State of a capability from [`CAPABILITY_TAGS`], or `None` for names filters don't know
+    pub fn capability(&self, name: &str) -> Option<Capability> {
+        let supported = match name {
+            "tools" => self.supports_tools || self.supports_function_calling,
+            "vision" => self.supports_vision,
+            "audio" => self.supports_audio,
+            "reasoning" => self.supports_reasoning,
+            "code" => self.supports_code,
+            _ => return None,
+        };
+        Some(if supported {
+            Capability::Supported
+        } else if self.unknown_capabilities.iter().any(|c| c == name) {
+            Capability::Unknown
+        } else {
+            Capability::Unsupported
+        })
+    }
+}
+
 // Configuration structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelPaths {
@@ -327,10 +378,63 @@ impl Default for TagConfig {
             },
         );
 
+        // Deprecation flag
+        tags.insert(
+            "is_deprecated".to_string(),
+            TagRule {
+                paths: vec![
+                    ".deprecated".to_string(),
+                    ".is_deprecated".to_string(),
+                    ".deprecation.deprecated".to_string(),
+                ],
+                value_type: "bool".to_string(),
+                transform: None,
+            },
+        );
+
+        // Deprecation / shutdown date
+        tags.insert(
+            "deprecation_date".to_string(),
+            TagRule {
+                paths: vec![
+                    ".deprecation_date".to_string(),
+                    ".deprecation.date".to_string(),
+                    ".deprecation.shutdown_date".to_string(),
+                    ".shutdown_date".to_string(),
+                    ".sunset_date".to_string(),
+                    ".retirement_date".to_string(),
+                    ".expiration_date".to_string(),
+                    ".deprecated_at".to_string(),
+                ],
+                value_type: "date".to_string(),
+                transform: None,
+            },
+        );
+
+        // Recommended replacement
+        tags.insert(
+            "replacement_model".to_string(),
+            TagRule {
+                paths: vec![
+                    ".replacement_model".to_string(),
+                    ".replacement".to_string(),
+                    ".deprecation.replacement".to_string(),
+                    ".deprecation.replacement_model".to_string(),
+                    ".successor".to_string(),
+                ],
+                value_type: "string".to_string(),
+                transform: None,
+            },
+        );
+
         Self { tags }
     }
 }
 
+/// Tags added after tags.toml was first generated; merged into existing user configs
+/// so deprecation info is extracted without regenerating the file
+const DEPRECATION_TAGS: [&str; 3] = ["is_deprecated", "deprecation_date", "replacement_model"];
+
 // Static caches for configuration
 static MODEL_PATHS_CACHE: OnceLock<ModelPaths> = OnceLock::new();
 static TAG_CONFIG_CACHE: OnceLock<TagConfig> = OnceLock::new();
@@ -376,6 +480,9 @@ impl ModelMetadataExtractor {
 
     /// Ensures that tags.toml and model_paths.toml exist with default values
     fn ensure_config_files_exist() -> Result<()> {
+        if crate::lock::is_read_only() {
+            return Ok(());
+        }
         let config_dir = Self::get_config_dir()?;
 
         // Ensure directory exists
@@ -433,8 +540,10 @@ impl ModelMetadataExtractor {
         } else {
             // Create default file
             let default = ModelPaths::default();
-            let content = toml::to_string_pretty(&default)?;
-            fs::write(&path, content)?;
+            if !crate::lock::is_read_only() {
+                let content = toml::to_string_pretty(&default)?;
+                fs::write(&path, content)?;
+            }
             Ok(default)
         }
     }
@@ -448,12 +557,24 @@ impl ModelMetadataExtractor {
 
         if path.exists() {
             let content = fs::read_to_string(&path)?;
-            toml::from_str(&content).context("Failed to parse tags.toml")
+            let mut config: TagConfig =
+                toml::from_str(&content).context("Failed to parse tags.toml")?;
+            let mut defaults = TagConfig::default();
+            for tag in DEPRECATION_TAGS {
+                if !config.tags.contains_key(tag) {
+                    if let Some(rule) = defaults.tags.remove(tag) {
+                        config.tags.insert(tag.to_string(), rule);
+                    }
+                }
+            }
+            Ok(config)
         } else {
             // Create default file
             let default = TagConfig::default();
-            let content = toml::to_string_pretty(&default)?;
-            fs::write(&path, content)?;
+            if !crate::lock::is_read_only() {
+                let content = toml::to_string_pretty(&default)?;
+                fs::write(&path, content)?;
+            }
             Ok(default)
         }
     }
@@ -680,11 +801,18 @@ impl ModelMetadataExtractor {
         }
 
         // Extract tags using configured rules
+        let mut stated_tags = std::collections::HashSet::new();
         for (tag_name, rule) in &self.tag_config.tags {
             if let Some(value) = self.extract_tag_value(model, rule) {
+                stated_tags.insert(tag_name.as_str());
                 self.apply_tag_value(&mut metadata, tag_name, value, &rule.value_type)?;
             }
         }
+        metadata.unknown_capabilities = CAPABILITY_TAGS
+            .iter()
+            .filter(|(_, tags)| !tags.iter().any(|tag| stated_tags.contains(tag)))
+            .map(|(name, _)| name.to_string())
+            .collect();
 
         // Determine model type based on model ID or name patterns
         metadata.model_type =
@@ -704,13 +832,12 @@ impl ModelMetadataExtractor {
                 let pattern = &path[15..path.len() - 1]; // Remove "@name_contains(" and ")"
                 let pattern = pattern.trim_matches('"'); // Remove quotes if present
 
+                // A name that doesn't match says nothing about the capability
                 if let Some(result) = self.check_name_contains(model, pattern) {
                     if is_bool_field && result {
                         return Some(Value::Bool(true));
                     } else if !is_bool_field {
                         return Some(Value::Bool(result));
-                    } else if !result {
-                        found_false = true;
                     }
                 }
                 continue;
@@ -725,8 +852,6 @@ impl ModelMetadataExtractor {
                         return Some(Value::Bool(true));
                     } else if !is_bool_field {
                         return Some(Value::Bool(result));
-                    } else if !result {
-                        found_false = true;
                     }
                 }
                 continue;
@@ -859,6 +984,16 @@ impl ModelMetadataExtractor {
                     metadata.is_fine_tunable = v;
                 }
             }
+            "deprecation_date" => {
+                if let Some(v) = self.parse_value_as_date(&value) {
+                    metadata.deprecation_date = Some(v);
+                }
+            }
+            "replacement_model" => {
+                if let Some(v) = value.as_str().filter(|s| !s.is_empty()) {
+                    metadata.replacement_model = Some(v.to_string());
+                }
+            }
             _ => {
                 // Unknown tag, ignore
             }
@@ -891,6 +1026,30 @@ impl ModelMetadataExtractor {
         }
     }
 
+    /// Normalize a date given as a unix timestamp or a date/datetime string to YYYY-MM-DD
+    fn parse_value_as_date(&self, value: &Value) -> Option<String> {
+        match value {
+            Value::Number(n) => n
+                .as_i64()
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                .map(|dt| dt.format("%Y-%m-%d").to_string()),
+            Value::String(s) if !s.is_empty() => {
+                if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+                    Some(dt.format("%Y-%m-%d").to_string())
+                } else if let Some(date) = s
+                    .get(..10)
+                    .filter(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok())
+                {
+                    Some(date.to_string())
+                } else {
+                    // Keep unrecognized formats as published rather than dropping them
+                    Some(s.clone())
+                }
+            }
+            _ => None,
+        }
+    }
+
     fn parse_value_as_f64(&self, value: &Value, _value_type: &str) -> Result<Option<f64>> {
         match value {
             Value::Number(n) => Ok(n.as_f64()),
@@ -1106,7 +1265,7 @@ pub fn add_model_path(path: String) -> Result<()> {
     if !paths.paths.contains(&path) {
         paths.paths.push(path);
         let content = toml::to_string_pretty(&paths)?;
-        fs::write(&file_path, content)?;
+        crate::lock::write_config_file(&file_path, &content)?;
         println!("Added model path");
     } else {
         println!("Path already exists");
@@ -1131,7 +1290,7 @@ pub fn remove_model_path(path: String) -> Result<()> {
     if let Some(pos) = paths.paths.iter().position(|p| p == &path) {
         paths.paths.remove(pos);
         let content = toml::to_string_pretty(&paths)?;
-        fs::write(&file_path, content)?;
+        crate::lock::write_config_file(&file_path, &content)?;
         println!("Removed model path");
     } else {
         println!("Path not found");
@@ -1185,7 +1344,7 @@ pub fn add_tag(
     );
 
     let content = toml::to_string_pretty(&config)?;
-    fs::write(&file_path, content)?;
+    crate::lock::write_config_file(&file_path, &content)?;
     println!("Added tag: {}", name);
 
     Ok(())
@@ -1225,6 +1384,145 @@ pub fn list_tags() -> Result<()> {
     Ok(())
 }
 
+/// Tag extraction rules and model paths in one file, for sharing them between
+/// machines with `lc models tags export` / `lc models tags import`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataRules {
+    pub model_paths: ModelPaths,
+    pub tags: HashMap<String, TagRule>,
+}
+
+impl MetadataRules {
+    /// The rules currently in `model_paths.toml` and `tags.toml`
+    pub fn load() -> Result<Self> {
+        Ok(Self {
+            model_paths: ModelMetadataExtractor::load_model_paths()?,
+            tags: ModelMetadataExtractor::load_tag_config()?.tags,
+        })
+    }
+
+    /// Combine imported rules with the current ones: new model paths are appended
+    /// and imported tags replace local tags with the same name
+    pub fn merge(mut self, imported: MetadataRules) -> Self {
+        for path in imported.model_paths.paths {
+            if !self.model_paths.paths.contains(&path) {
+                self.model_paths.paths.push(path);
+            }
+        }
+        for (field, imported_fields) in [
+            (
+                &mut self.model_paths.field_mappings.id_fields,
+                imported.model_paths.field_mappings.id_fields,
+            ),
+            (
+                &mut self.model_paths.field_mappings.name_fields,
+                imported.model_paths.field_mappings.name_fields,
+            ),
+        ] {
+            for name in imported_fields {
+                if !field.contains(&name) {
+                    field.push(name);
+                }
+            }
+        }
+        self.tags.extend(imported.tags);
+        self
+    }
+
+    /// Write the rules to `model_paths.toml` and `tags.toml`
+    pub fn save(&self) -> Result<()> {
+        let config_dir = ModelMetadataExtractor::get_config_dir()?;
+        crate::lock::write_config_file(
+            &config_dir.join("model_paths.toml"),
+            &toml::to_string_pretty(&self.model_paths)?,
+        )?;
+        let tags = TagConfig {
+            tags: self.tags.clone(),
+        };
+        crate::lock::write_config_file(
+            &config_dir.join("tags.toml"),
+            &toml::to_string_pretty(&tags)?,
+        )
+    }
+
+    /// Top-level model fields that extraction reads; everything else in a cached
+    /// model entry can be dropped when compacting the cache
+    pub fn used_fields(&self) -> HashSet<String> {
+        let mappings = &self.model_paths.field_mappings;
+        let mut fields: HashSet<String> = mappings
+            .id_fields
+            .iter()
+            .chain(&mappings.name_fields)
+            .cloned()
+            .collect();
+        // Read directly by extract_metadata and the HuggingFace expansion
+        for field in [
+            "description",
+            "owned_by",
+            "created",
+            "provider",
+            "providers",
+        ] {
+            fields.insert(field.to_string());
+        }
+        for path in self.tags.values().flat_map(|rule| &rule.paths) {
+            if path.starts_with('@') {
+                continue;
+            }
+            let field: String = path
+                .trim_start_matches('.')
+                .chars()
+                .take_while(|c| !matches!(c, '.' | '[' | ' ' | '|'))
+                .collect();
+            if !field.is_empty() {
+                fields.insert(field);
+            }
+        }
+        fields
+    }
+
+    /// Keep only the fields extraction reads from a model entry
+    pub fn compact_model(model: &Value, fields: &HashSet<String>) -> Value {
+        match model {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(key, _)| fields.contains(key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Compact the model entries of a raw `/models` response, found at the configured
+    /// model paths; other parts of the response are left as they are
+    pub fn compact_response(&self, response: &Value, fields: &HashSet<String>) -> Value {
+        let mut response = response.clone();
+        for path in &self.model_paths.paths {
+            let Some(array_path) = path.strip_suffix("[]") else {
+                if path == "." {
+                    if let Value::Array(models) = &mut response {
+                        for model in models.iter_mut() {
+                            *model = Self::compact_model(model, fields);
+                        }
+                    }
+                }
+                continue;
+            };
+            let mut current = Some(&mut response);
+            for part in array_path.split('.').filter(|s| !s.is_empty()) {
+                current = current.and_then(|value| value.get_mut(part));
+            }
+            if let Some(Value::Array(models)) = current {
+                for model in models.iter_mut() {
+                    *model = Self::compact_model(model, fields);
+                }
+            }
+        }
+        response
+    }
+}
+
 // Compatibility layer for existing code
 pub struct MetadataExtractor;
 
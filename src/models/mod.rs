@@ -1,7 +1,9 @@
 // Model-related modules
 pub mod cache;
+pub mod deprecation;
 pub mod dump_metadata;
 pub mod metadata;
+pub mod model_aliases;
 pub mod unified_cache;
 
 // Re-export with old names for compatibility
@@ -0,0 +1,344 @@
+//! Friendly names for versioned model IDs
+//!
+//! Providers publish dated model versions and rename them over time
+//! (`claude-3-5-sonnet-20240620` → `claude-3-5-sonnet-20241022`). When a provider's models
+//! cache is refreshed, a table mapping friendly names to the provider's current concrete IDs
+//! is saved next to the cache in `model_aliases.json`:
+//!
+//! - the ID without its date or `-latest` suffix: `claude-3-5-sonnet`, `gpt-4o`
+//! - the model family without version numbers: `claude-sonnet`, `gemini-pro`
+//!
+//! Each name points at the newest model carrying it. A requested model that the provider
+//! doesn't list is looked up in the table by itself and by its ID without the date, so
+//! `claude-sonnet` and retired dated IDs in configs keep working. A model is never
+//! switched to another model of its family (`claude-3-opus-20240229` to `claude-opus-4-1`)
+//! unless the family name is requested; a warning names the candidate instead. Model IDs
+//! the provider lists are never remapped.
+
+use crate::model_metadata::ModelMetadata;
+use crate::unified_cache::UnifiedCache;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The models of one provider and their friendly names
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderAliases {
+    /// Model IDs in the provider's models cache
+    pub ids: Vec<String>,
+    /// Friendly name -> current concrete ID
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl ProviderAliases {
+    /// The table for a provider's models
+    pub fn from_models(models: &[ModelMetadata]) -> Self {
+        let mut ids: Vec<String> = models.iter().map(|m| m.id.clone()).collect();
+        ids.sort();
+        ids.dedup();
+
+        let mut best: BTreeMap<String, &ModelMetadata> = BTreeMap::new();
+        for model in models {
+            for name in friendly_names(&model.id) {
+                if ids.binary_search(&name).is_ok() {
+                    continue;
+                }
+                let newer = best
+                    .get(&name)
+                    .is_none_or(|current| compare_versions(model, current) == Ordering::Greater);
+                if newer {
+                    best.insert(name, model);
+                }
+            }
+        }
+        let aliases = best
+            .into_iter()
+            .map(|(name, model)| (name, model.id.clone()))
+            .collect();
+        Self { ids, aliases }
+    }
+
+    /// The concrete ID for a requested model the provider doesn't list: the model the
+    /// name stands for, or the current version of the same model
+    pub fn resolve(&self, model: &str) -> Option<&str> {
+        if !self.is_unlisted(model) {
+            return None;
+        }
+        let (base, _) = split_version_suffix(model);
+        self.aliases
+            .get(model)
+            .or_else(|| self.aliases.get(base))
+            .map(String::as_str)
+    }
+
+    /// The newest model of the family of an unlisted model that [`Self::resolve`]
+    /// doesn't map, with the family name that selects it
+    pub fn family_candidate(&self, model: &str) -> Option<(&str, &str)> {
+        if !self.is_unlisted(model) || self.resolve(model).is_some() {
+            return None;
+        }
+        friendly_names(model)
+            .into_iter()
+            .find_map(|name| self.aliases.get_key_value(&name))
+            .map(|(name, id)| (name.as_str(), id.as_str()))
+    }
+
+    fn is_unlisted(&self, model: &str) -> bool {
+        !self.ids.is_empty()
+            && self
+                .ids
+                .binary_search_by(|id| id.as_str().cmp(model))
+                .is_err()
+    }
+}
+
+/// The ID without its date, revision or `-latest` suffix, and the family name without
+/// version numbers, when they differ from the ID
+pub fn friendly_names(id: &str) -> Vec<String> {
+    let (base, _) = split_version_suffix(id);
+    let family: Vec<&str> = base
+        .split('-')
+        .filter(|segment| !is_version_number(segment))
+        .collect();
+    let family = family.join("-");
+
+    let mut names = Vec::new();
+    for name in [base.to_string(), family] {
+        if !name.is_empty() && name != id && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// The ID without trailing date, revision and `latest` segments, and the digits of the
+/// date or revision (for ordering; empty without one)
+fn split_version_suffix(id: &str) -> (&str, String) {
+    let mut base = id;
+    let mut date = String::new();
+    // YYYY-MM-DD dates span three segments
+    if let Some(prefix) = base.get(..base.len().saturating_sub(11)) {
+        let suffix = &base[prefix.len()..];
+        if suffix.starts_with(['-', '@'])
+            && chrono::NaiveDate::parse_from_str(&suffix[1..], "%Y-%m-%d").is_ok()
+        {
+            date = suffix[1..].replace('-', "");
+            base = prefix;
+        }
+    }
+    while let Some(pos) = base.rfind(['-', '@']) {
+        let segment = &base[pos + 1..];
+        if segment == "latest" {
+            base = &base[..pos];
+        } else if date.is_empty()
+            && matches!(segment.len(), 3 | 4 | 8)
+            && segment.chars().all(|c| c.is_ascii_digit())
+        {
+            date = segment.to_string();
+            base = &base[..pos];
+        } else {
+            break;
+        }
+    }
+    (base, date)
+}
+
+/// Whether a segment is a version number such as `3`, `4o` is not, `1.5` is
+fn is_version_number(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && segment.chars().next().is_some_and(|c| c.is_ascii_digit())
+}
+
+/// The version numbers in an ID, e.g. `[4, 5]` for `claude-sonnet-4-5-20250929`
+fn version_numbers(id: &str) -> Vec<u64> {
+    let (base, _) = split_version_suffix(id);
+    base.split('-')
+        .filter(|segment| is_version_number(segment))
+        .flat_map(|segment| segment.split('.'))
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Order models by version numbers, then `-latest` over dated versions, then date
+/// suffix, then creation time
+fn compare_versions(a: &ModelMetadata, b: &ModelMetadata) -> Ordering {
+    version_numbers(&a.id)
+        .cmp(&version_numbers(&b.id))
+        .then_with(|| a.id.ends_with("latest").cmp(&b.id.ends_with("latest")))
+        .then_with(|| {
+            split_version_suffix(&a.id)
+                .1
+                .cmp(&split_version_suffix(&b.id).1)
+        })
+        .then_with(|| a.created.cmp(&b.created))
+}
+
+fn table_path() -> Result<PathBuf> {
+    // Not in the models directory, where every JSON file is a provider's cache
+    Ok(UnifiedCache::models_dir()?.with_file_name("model_aliases.json"))
+}
+
+/// The saved tables of all providers
+pub fn load() -> BTreeMap<String, ProviderAliases> {
+    table_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Replace a provider's table after its models cache was refreshed
+pub fn update(provider: &str, models: &[ModelMetadata]) -> Result<()> {
+    if crate::lock::is_read_only() {
+        return Ok(());
+    }
+    let mut tables = load();
+    tables.insert(provider.to_string(), ProviderAliases::from_models(models));
+    let path = table_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&tables)?)?;
+    Ok(())
+}
+
+/// The provider's current ID for `model`, or `model` itself. A versioned ID that is
+/// replaced by another version is reported, and so is a newer model of the same family
+/// that is not used.
+pub fn resolve(provider: &str, model: &str) -> String {
+    let tables = load();
+    let Some(table) = tables.get(provider) else {
+        return model.to_string();
+    };
+    let Some(resolved) = table.resolve(model) else {
+        if let Some((family, candidate)) = table.family_candidate(model) {
+            eprintln!(
+                "{}",
+                format!(
+                    "Warning: {} doesn't list '{}'. Its family's newest model is '{}'; request '{}' or '{}' to use it.",
+                    provider, model, candidate, family, candidate
+                )
+                .yellow()
+            );
+        }
+        return model.to_string();
+    };
+    if split_version_suffix(model).0 == model {
+        crate::debug_log!("Resolved model '{}' to '{}'", model, resolved);
+    } else {
+        eprintln!(
+            "{}",
+            format!(
+                "Note: {} doesn't list '{}', using '{}'",
+                provider, model, resolved
+            )
+            .dimmed()
+        );
+    }
+    resolved.to_string()
+}
+
+/// Print the friendly names of every provider that has any
+pub fn display(tables: &BTreeMap<String, ProviderAliases>) {
+    let tables: Vec<_> = tables
+        .iter()
+        .filter(|(_, table)| !table.aliases.is_empty())
+        .collect();
+    if tables.is_empty() {
+        println!(
+            "No model aliases yet; they are built when the models cache is refreshed ('lc models refresh')."
+        );
+        return;
+    }
+    for (provider, table) in tables {
+        println!("\n{}", provider.bold().blue());
+        for (name, id) in &table.aliases {
+            println!("  {} {} {}", name.bold(), sym("→").dimmed(), id);
+        }
+    }
+}
+
+/// [`resolve`] for a `provider:model` name
+pub fn resolve_prefixed(name: &str) -> String {
+    match name.split_once(':') {
+        Some((provider, model)) => format!("{}:{}", provider, resolve(provider, model)),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str) -> ModelMetadata {
+        ModelMetadata {
+            id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_friendly_names() {
+        assert_eq!(
+            friendly_names("claude-3-5-sonnet-20241022"),
+            vec!["claude-3-5-sonnet", "claude-sonnet"]
+        );
+        assert_eq!(friendly_names("gpt-4o-2024-08-06"), vec!["gpt-4o"]);
+        assert_eq!(
+            friendly_names("gemini-1.5-pro-002"),
+            vec!["gemini-1.5-pro", "gemini-pro"]
+        );
+        assert_eq!(
+            friendly_names("claude-3-opus-latest"),
+            vec!["claude-3-opus", "claude-opus"]
+        );
+        assert!(friendly_names("gpt-4o").is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let table = ProviderAliases::from_models(&[
+            model("claude-3-5-sonnet-20241022"),
+            model("claude-3-5-sonnet-latest"),
+            model("claude-sonnet-4-20250514"),
+            model("claude-sonnet-4-5-20250929"),
+            model("gpt-4o"),
+            model("gpt-4o-2024-08-06"),
+        ]);
+        assert_eq!(
+            table.resolve("claude-sonnet"),
+            Some("claude-sonnet-4-5-20250929")
+        );
+        assert_eq!(
+            table.resolve("claude-3-5-sonnet"),
+            Some("claude-3-5-sonnet-latest")
+        );
+        assert_eq!(
+            table.resolve("claude-sonnet-4"),
+            Some("claude-sonnet-4-20250514")
+        );
+        // A retired dated ID moves to the current version of the same model
+        assert_eq!(
+            table.resolve("claude-3-5-sonnet-20240620"),
+            Some("claude-3-5-sonnet-latest")
+        );
+        // but never to another model of its family
+        assert_eq!(table.resolve("claude-3-7-sonnet-20250219"), None);
+        assert_eq!(
+            table.family_candidate("claude-3-7-sonnet-20250219"),
+            Some(("claude-sonnet", "claude-sonnet-4-5-20250929"))
+        );
+        assert_eq!(table.family_candidate("claude-3-5-sonnet-20240620"), None);
+        assert_eq!(table.family_candidate("gpt-4o"), None);
+        // Listed IDs are never remapped
+        assert_eq!(table.resolve("gpt-4o"), None);
+        assert_eq!(table.resolve("claude-sonnet-4-20250514"), None);
+        assert_eq!(table.resolve("unknown-model"), None);
+        assert_eq!(ProviderAliases::default().resolve("claude-sonnet"), None);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::utils::terminal::sym;
 use crate::{
     config::Config,
     debug_log, // Import debug_log macro
     model_metadata::{extract_models_from_provider, ModelMetadata},
     provider::Provider,
+    trace_log,
 };
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -105,16 +107,16 @@ impl UnifiedCache {
 
     /// Check in-memory cache first, then file cache
     pub async fn is_cache_fresh(provider: &str) -> Result<bool> {
-        debug_log!("Checking cache freshness for provider '{}'", provider);
+        trace_log!("Checking cache freshness for provider '{}'", provider);
 
         // Check in-memory cache first
         if let Ok(cache) = MEMORY_CACHE.read() {
             if let Some(entry) = cache.get(provider) {
                 if !entry.is_expired() {
-                    debug_log!("Found fresh in-memory cache for provider '{}'", provider);
+                    trace_log!("Found fresh in-memory cache for provider '{}'", provider);
                     return Ok(true);
                 } else {
-                    debug_log!("In-memory cache expired for provider '{}'", provider);
+                    trace_log!("In-memory cache expired for provider '{}'", provider);
                 }
             }
         }
@@ -123,7 +125,7 @@ impl UnifiedCache {
         let cache_path = Self::provider_cache_path(provider)?;
 
         if !cache_path.exists() {
-            debug_log!("Cache file does not exist for provider '{}'", provider);
+            trace_log!("Cache file does not exist for provider '{}'", provider);
             return Ok(false);
         }
 
@@ -139,7 +141,7 @@ impl UnifiedCache {
         let age_seconds = now - cached_data.last_updated;
         let is_fresh = age_seconds < Self::CACHE_TTL;
 
-        debug_log!(
+        trace_log!(
             "File cache for provider '{}' is {} seconds old, fresh: {}",
             provider,
             age_seconds,
@@ -159,7 +161,7 @@ impl UnifiedCache {
         if let Ok(mut cache) = MEMORY_CACHE.write() {
             let entry = MemoryCacheEntry::new(data, Self::CACHE_TTL);
             cache.insert(provider.to_string(), entry);
-            debug_log!("Populated in-memory cache for provider '{}'", provider);
+            trace_log!("Populated in-memory cache for provider '{}'", provider);
         }
     }
 
@@ -167,7 +169,7 @@ impl UnifiedCache {
     pub fn invalidate_provider_cache(provider: &str) {
         if let Ok(mut cache) = MEMORY_CACHE.write() {
             cache.remove(provider);
-            debug_log!("Invalidated in-memory cache for provider '{}'", provider);
+            trace_log!("Invalidated in-memory cache for provider '{}'", provider);
         }
     }
 
@@ -176,7 +178,7 @@ impl UnifiedCache {
     pub fn clear_memory_cache() {
         if let Ok(mut cache) = MEMORY_CACHE.write() {
             cache.clear();
-            debug_log!("Cleared all in-memory cache");
+            trace_log!("Cleared all in-memory cache");
         }
     }
 
@@ -232,20 +234,20 @@ impl UnifiedCache {
 
     /// Load cached models for a provider (async with in-memory cache)
     pub async fn load_provider_models(provider: &str) -> Result<Vec<ModelMetadata>> {
-        debug_log!("Loading cached models for provider '{}'", provider);
+        trace_log!("Loading cached models for provider '{}'", provider);
 
         // Check in-memory cache first
         if let Ok(cache) = MEMORY_CACHE.read() {
             if let Some(entry) = cache.get(provider) {
                 if !entry.is_expired() {
-                    debug_log!(
+                    trace_log!(
                         "Loaded {} models from in-memory cache for provider '{}'",
                         entry.data.models.len(),
                         provider
                     );
                     return Ok(entry.data.models.clone());
                 } else {
-                    debug_log!("In-memory cache expired for provider '{}'", provider);
+                    trace_log!("In-memory cache expired for provider '{}'", provider);
                 }
             }
         }
@@ -284,6 +286,16 @@ impl UnifiedCache {
     pub async fn fetch_and_cache_provider_models(
         provider: &str,
         force_refresh: bool,
+    ) -> Result<Vec<ModelMetadata>> {
+        Self::fetch_provider_models(provider, force_refresh, true).await
+    }
+
+    /// Fetch and cache a provider's models; `announce` prints a progress line when
+    /// fetching (off for parallel refreshes, which print a summary instead)
+    async fn fetch_provider_models(
+        provider: &str,
+        force_refresh: bool,
+        announce: bool,
     ) -> Result<Vec<ModelMetadata>> {
         debug_log!(
             "Fetching models for provider '{}', force_refresh: {}",
@@ -304,7 +316,9 @@ impl UnifiedCache {
             "Cache is stale or refresh forced, fetching fresh models for provider '{}'",
             provider
         );
-        println!("Fetching models from provider '{}'...", provider);
+        if announce {
+            println!("Fetching models from provider '{}'...", provider);
+        }
 
         // Invalidate existing cache
         Self::invalidate_provider_cache(provider);
@@ -377,7 +391,7 @@ impl UnifiedCache {
         );
 
         // Debug log the full response when -d flag is used
-        debug_log!(
+        trace_log!(
             "Full response from provider '{}': {}",
             provider,
             raw_response
@@ -415,6 +429,9 @@ impl UnifiedCache {
         // Cache the data (both in-memory and file)
         debug_log!("Saving cache data for provider '{}'", provider);
         Self::save_provider_cache(provider, &raw_response, &models).await?;
+        if let Err(e) = crate::models::model_aliases::update(provider, &models) {
+            debug_log!("Failed to update model aliases for '{}': {}", provider, e);
+        }
 
         Ok(models)
     }
@@ -427,6 +444,13 @@ impl UnifiedCache {
     ) -> Result<()> {
         let cache_path = Self::provider_cache_path(provider)?;
 
+        // Keep the fetched data in memory only; nothing is persisted in read-only mode
+        if crate::lock::is_read_only() {
+            let cached_data = CachedProviderData::new(raw_response.to_string(), models.to_vec());
+            Self::populate_memory_cache(provider, cached_data);
+            return Ok(());
+        }
+
         debug_log!(
             "Saving cache for provider '{}' to: {}",
             provider,
@@ -503,41 +527,248 @@ impl UnifiedCache {
         Ok(all_models)
     }
 
-    /// Refresh all providers' caches
-    pub async fn refresh_all_providers() -> Result<()> {
-        let config = Config::load()?;
-        let mut successful_providers = 0;
-        let mut total_models = 0;
+    /// Configured providers that list a model with exactly this ID, in their models
+    /// cache or in the provider file's `models`, sorted by name. Stale caches count
+    /// too; nothing is fetched.
+    pub fn providers_with_model(config: &Config, model: &str) -> Vec<String> {
+        let mut providers: Vec<String> = config
+            .providers
+            .iter()
+            .filter(|(name, provider_config)| {
+                provider_config.models.iter().any(|m| m == model)
+                    || Self::provider_cache_path(name)
+                        .ok()
+                        .and_then(|path| std::fs::read_to_string(path).ok())
+                        .and_then(|content| {
+                            serde_json::from_str::<CachedProviderData>(&content).ok()
+                        })
+                        .is_some_and(|data| data.models.iter().any(|m| m.id == model))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        providers.sort();
+        debug_log!("Providers with model '{}': {:?}", model, providers);
+        providers
+    }
 
-        println!("Refreshing models cache for all providers...");
+    /// Strip raw JSON fields that the extraction rules don't read from every cache
+    /// file, and write the files without pretty-printing. Returns each provider with
+    /// its file size before and after, sorted by provider name.
+    pub async fn compact_caches() -> Result<Vec<(String, u64, u64)>> {
+        crate::lock::ensure_writable("compact the models cache")?;
+        let models_dir = Self::models_dir()?;
+        if !models_dir.exists() {
+            return Ok(Vec::new());
+        }
 
-        for provider_name in config.providers.keys() {
-            // Skip providers that have neither API key nor custom headers (after loading centralized auth)
-            let pc_auth = match config.get_provider_with_auth(provider_name) {
-                Ok(cfg) => cfg,
-                Err(_) => continue,
+        let rules = crate::model_metadata::MetadataRules::load()?;
+        let fields = rules.used_fields();
+        let mut results = Vec::new();
+
+        let mut entries = fs::read_dir(&models_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(provider) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
             };
-            if pc_auth.api_key.is_none() && pc_auth.headers.is_empty() {
+            let content = fs::read_to_string(&path).await?;
+            let Ok(mut data) = serde_json::from_str::<CachedProviderData>(&content) else {
+                debug_log!("Skipping unreadable cache file {}", path.display());
                 continue;
+            };
+
+            for model in &mut data.models {
+                model.raw_data =
+                    crate::model_metadata::MetadataRules::compact_model(&model.raw_data, &fields);
+            }
+            if let Ok(response) = serde_json::from_str::<serde_json::Value>(&data.raw_response) {
+                data.raw_response =
+                    serde_json::to_string(&rules.compact_response(&response, &fields))?;
             }
 
-            match Self::fetch_and_cache_provider_models(provider_name, true).await {
-                Ok(models) => {
-                    let count = models.len();
-                    successful_providers += 1;
-                    total_models += count;
-                    println!("✓ {} ({} models)", provider_name, count);
-                }
-                Err(e) => {
-                    println!("✗ {} ({})", provider_name, e);
+            let compacted = serde_json::to_string(&data)?;
+            crate::lock::write_config_file(&path, &compacted)?;
+            Self::invalidate_provider_cache(provider);
+            results.push((
+                provider.to_string(),
+                content.len() as u64,
+                compacted.len() as u64,
+            ));
+        }
+
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(results)
+    }
+
+    /// Refresh all providers' caches
+    pub async fn refresh_all_providers() -> Result<()> {
+        println!("Refreshing models cache for all providers...");
+        let results = Self::refresh_providers(&[], DEFAULT_REFRESH_JOBS).await?;
+        print_refresh_summary(&results);
+        Ok(())
+    }
+
+    /// Refresh the caches of `providers` (all configured providers when empty), at most
+    /// `jobs` at a time. Results are sorted by provider name.
+    ///
+    /// When refreshing everything, providers without an API key or custom headers are
+    /// skipped; providers named explicitly are always attempted.
+    pub async fn refresh_providers(
+        providers: &[String],
+        jobs: usize,
+    ) -> Result<Vec<ProviderRefreshResult>> {
+        use futures_util::stream::{self, StreamExt};
+
+        let config = Config::load()?;
+        let explicit = !providers.is_empty();
+        let names: Vec<String> = if explicit {
+            for name in providers {
+                if !config.has_provider(name) {
+                    anyhow::bail!("Provider '{}' not found", name);
                 }
             }
-        }
+            providers.to_vec()
+        } else {
+            config.providers.keys().cloned().collect()
+        };
+
+        let mut results: Vec<ProviderRefreshResult> = stream::iter(names)
+            .map(|name| {
+                let config = &config;
+                async move {
+                    let start = std::time::Instant::now();
+                    if !explicit && !has_credentials(config, &name) {
+                        return ProviderRefreshResult {
+                            provider: name,
+                            status: RefreshStatus::Skipped("no API key".to_string()),
+                            duration: start.elapsed(),
+                        };
+                    }
+                    let status = match Self::fetch_provider_models(&name, true, false).await {
+                        Ok(models) => RefreshStatus::Refreshed(models.len()),
+                        Err(e) => RefreshStatus::Failed(e.to_string()),
+                    };
+                    ProviderRefreshResult {
+                        provider: name,
+                        status,
+                        duration: start.elapsed(),
+                    }
+                }
+            })
+            .buffer_unordered(jobs.max(1))
+            .collect()
+            .await;
+
+        results.sort_by(|a, b| a.provider.cmp(&b.provider));
+        Ok(results)
+    }
+}
 
+/// Parallel fetches used by `lc models refresh` unless `--jobs` says otherwise
+pub const DEFAULT_REFRESH_JOBS: usize = 4;
+
+/// Outcome of refreshing one provider's models cache
+#[derive(Debug, Clone, PartialEq)]
+pub enum RefreshStatus {
+    Refreshed(usize),
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ProviderRefreshResult {
+    pub provider: String,
+    pub status: RefreshStatus,
+    pub duration: Duration,
+}
+
+/// Whether a provider has an API key or custom headers (after loading centralized auth)
+fn has_credentials(config: &Config, provider: &str) -> bool {
+    config
+        .get_provider_with_auth(provider)
+        .map(|pc| pc.api_key.is_some() || !pc.headers.is_empty())
+        .unwrap_or(false)
+}
+
+/// Print a table of per-provider refresh results followed by the totals
+pub fn print_refresh_summary(results: &[ProviderRefreshResult]) {
+    use colored::Colorize;
+    use tabled::{Table, Tabled};
+
+    #[derive(Tabled)]
+    struct Row {
+        #[tabled(rename = "Provider")]
+        provider: String,
+        #[tabled(rename = "Status")]
+        status: String,
+        #[tabled(rename = "Models")]
+        models: String,
+        #[tabled(rename = "Duration")]
+        duration: String,
+    }
+
+    if results.is_empty() {
+        println!("No providers to refresh");
+        return;
+    }
+
+    let rows: Vec<Row> = results
+        .iter()
+        .map(|result| {
+            let (status, models) = match &result.status {
+                RefreshStatus::Refreshed(count) => {
+                    (sym("✓ refreshed").to_string(), count.to_string())
+                }
+                RefreshStatus::Skipped(reason) => (format!("- skipped ({})", reason), "-".into()),
+                RefreshStatus::Failed(error) => {
+                    (format!("{} {}", sym("✗"), truncate(error, 60)), "-".into())
+                }
+            };
+            Row {
+                provider: result.provider.clone(),
+                status,
+                models,
+                duration: format!("{:.1}s", result.duration.as_secs_f64()),
+            }
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+
+    let refreshed: Vec<usize> = results
+        .iter()
+        .filter_map(|r| match r.status {
+            RefreshStatus::Refreshed(count) => Some(count),
+            _ => None,
+        })
+        .collect();
+    let failed = results
+        .iter()
+        .filter(|r| matches!(r.status, RefreshStatus::Failed(_)))
+        .count();
+    let summary = format!(
+        "Cache updated: {} providers, {} total models",
+        refreshed.len(),
+        refreshed.iter().sum::<usize>()
+    );
+    if failed > 0 {
         println!(
-            "\nCache updated: {} providers, {} total models",
-            successful_providers, total_models
+            "\n{} ({} failed)",
+            summary.green(),
+            failed.to_string().red()
         );
-        Ok(())
+    } else {
+        println!("\n{}", summary.green());
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    let text = text.lines().next().unwrap_or_default();
+    if text.chars().count() > max {
+        format!("{}...", text.chars().take(max).collect::<String>())
+    } else {
+        text.to_string()
     }
 }
@@ -0,0 +1,226 @@
+use super::html::HtmlReader;
+use super::FileReader;
+use anyhow::{Context, Result};
+use scraper::{ElementRef, Html};
+use std::io::Read;
+
+/// Reader for EPUB books: the chapters in reading order, each starting with its
+/// heading as a Markdown heading, so chunking splits the book between chapters and
+/// sections
+pub struct EpubReader;
+
+impl Default for EpubReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpubReader {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// The book's text, after its title and author
+fn read_book(bytes: &[u8]) -> Result<String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+        .context("Not a valid EPUB file (not a zip archive)")?;
+    let container = read_entry(&mut archive, "META-INF/container.xml")?;
+    let package_path = elements(&Html::parse_document(&container), "rootfile")
+        .find_map(|rootfile| rootfile.value().attr("full-path").map(str::to_string))
+        .context("EPUB container does not name its package file")?;
+    let package = Html::parse_document(&read_entry(&mut archive, &package_path)?);
+
+    let mut text = String::new();
+    for (name, label) in [("dc:title", "Title"), ("dc:creator", "Author")] {
+        if let Some(value) = elements(&package, name).next().map(element_text) {
+            text.push_str(&format!("{}: {}\n", label, value));
+        }
+    }
+
+    let chapters = elements(&package, "itemref")
+        .filter_map(|itemref| itemref.value().attr("idref"))
+        .filter_map(|idref| {
+            elements(&package, "item").find(|item| item.value().attr("id") == Some(idref))
+        })
+        .filter(|item| {
+            matches!(
+                item.value().attr("media-type"),
+                Some("application/xhtml+xml" | "text/html")
+            ) && !item
+                .value()
+                .attr("properties")
+                .is_some_and(|properties| properties.split_whitespace().any(|p| p == "nav"))
+        })
+        .filter_map(|item| item.value().attr("href"))
+        .map(|href| resolve(&package_path, href))
+        .collect::<Vec<_>>();
+    if chapters.is_empty() {
+        anyhow::bail!("EPUB has no chapters in its spine");
+    }
+
+    let reader = HtmlReader::for_documents();
+    for path in chapters {
+        let html = read_entry(&mut archive, &path)?;
+        let mut chapter = reader.extract(&html);
+        if chapter.is_empty() {
+            continue;
+        }
+        if !chapter.starts_with('#') {
+            if let Some(title) = reader.title(&html) {
+                chapter = format!("# {}\n\n{}", title, chapter);
+            }
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&chapter);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>, path: &str) -> Result<String> {
+    let mut entry = archive
+        .by_name(path)
+        .with_context(|| format!("EPUB is missing '{}'", path))?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Elements named `name` in a parsed XML document
+fn elements<'a>(document: &'a Html, name: &'a str) -> impl Iterator<Item = ElementRef<'a>> + 'a {
+    document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(move |element| element.value().name() == name)
+}
+
+fn element_text(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Path in the archive of an `href` relative to the package file
+fn resolve(package_path: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href);
+    let mut segments: Vec<&str> = package_path.split('/').collect();
+    segments.pop();
+    for segment in href.split('/') {
+        match segment {
+            "." | "" => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    percent_decode(&segments.join("/"))
+}
+
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl FileReader for EpubReader {
+    fn read_as_text(&self, file_path: &str) -> Result<String> {
+        let bytes = std::fs::read(file_path)
+            .with_context(|| format!("Failed to read EPUB file: {}", file_path))?;
+        self.read_as_text_from_bytes(&bytes)
+    }
+
+    fn read_as_text_from_bytes(&self, bytes: &[u8]) -> Result<String> {
+        read_book(bytes)
+    }
+
+    fn can_handle(&self, extension: &str) -> bool {
+        extension.eq_ignore_ascii_case("epub")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn epub(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_read_book() {
+        let container = r#"<?xml version="1.0"?>
+            <container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+              <rootfiles><rootfile full-path="OEBPS/content.opf"
+                media-type="application/oebps-package+xml"/></rootfiles>
+            </container>"#;
+        let package = r#"<?xml version="1.0"?>
+            <package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+              <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+                <dc:title>The Rust Tour</dc:title><dc:creator>Ferris</dc:creator>
+              </metadata>
+              <manifest>
+                <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+                <item id="two" href="Text/chapter%202.xhtml" media-type="application/xhtml+xml"/>
+                <item id="one" href="Text/one.xhtml" media-type="application/xhtml+xml"/>
+                <item id="css" href="style.css" media-type="text/css"/>
+              </manifest>
+              <spine><itemref idref="nav"/><itemref idref="one"/><itemref idref="two"/></spine>
+            </package>"#;
+        let book = epub(&[
+            ("mimetype", "application/epub+zip"),
+            ("META-INF/container.xml", container),
+            ("OEBPS/content.opf", package),
+            ("OEBPS/nav.xhtml", "<html><body><nav>Contents</nav></body></html>"),
+            (
+                "OEBPS/Text/one.xhtml",
+                "<html><body><header><h1>Ownership</h1></header>\
+                 <p>Values have one owner.</p><h2>Moves</h2><p>Ownership moves.</p></body></html>",
+            ),
+            (
+                "OEBPS/Text/chapter 2.xhtml",
+                "<html><head><title>Borrowing</title></head><body><p>References borrow.</p></body></html>",
+            ),
+        ]);
+        assert_eq!(
+            EpubReader::new().read_as_text_from_bytes(&book).unwrap(),
+            "Title: The Rust Tour\nAuthor: Ferris\n\n\
+             # Ownership\n\nValues have one owner.\n\n## Moves\n\nOwnership moves.\n\n\
+             # Borrowing\n\nReferences borrow.\n"
+        );
+        assert!(EpubReader::new()
+            .read_as_text_from_bytes(b"not a zip")
+            .is_err());
+    }
+}
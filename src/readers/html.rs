@@ -0,0 +1,300 @@
+use super::FileReader;
+use anyhow::{Context, Result};
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements whose text is never part of the readable content
+const SKIPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "iframe", "button",
+];
+
+/// Elements that are page chrome on web pages, but content in documents
+const CHROME: &[&str] = &["nav", "footer", "header", "aside", "form"];
+
+/// Elements that start a new line in the extracted text
+const BLOCKS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "br",
+    "li",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "pre",
+    "blockquote",
+    "table",
+    "ul",
+    "ol",
+    "dd",
+    "dt",
+    "figcaption",
+];
+
+/// Parts of `class` and `id` names that mark page chrome rather than content
+const BOILERPLATE: &[&str] = &[
+    "advert",
+    "banner",
+    "breadcrumb",
+    "comment",
+    "cookie",
+    "consent",
+    "menu",
+    "modal",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsor",
+    "subscribe",
+];
+
+/// Paragraph text a container needs to be taken as the page's content when there is no
+/// `<article>` or `<main>`
+const MIN_CONTENT_CHARS: usize = 200;
+
+/// Reader for HTML pages: extracts the readable text of `<article>`, `<main>`, the
+/// element holding most of the paragraphs, or the body, leaving out scripts, styles and
+/// page chrome like navigation, footers, sidebars and cookie banners
+pub struct HtmlReader {
+    document: bool,
+}
+
+impl Default for HtmlReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HtmlReader {
+    pub fn new() -> Self {
+        Self { document: false }
+    }
+
+    /// Reader for HTML that is a document rather than a web page, such as the chapters
+    /// of an EPUB: the whole body is read and headings are kept as Markdown headings
+    pub fn for_documents() -> Self {
+        Self { document: true }
+    }
+
+    /// The page's `<title>`
+    pub fn title(&self, html: &str) -> Option<String> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse("title").ok()?;
+        let title = document
+            .select(&selector)
+            .next()?
+            .text()
+            .collect::<String>();
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!title.is_empty()).then_some(title)
+    }
+
+    /// The readable text of a page
+    pub fn extract(&self, html: &str) -> String {
+        let document = Html::parse_document(html);
+        let first = |name: &str| {
+            Selector::parse(name)
+                .ok()
+                .and_then(|selector| document.select(&selector).next())
+        };
+        let root = if self.document {
+            first("body")
+        } else {
+            first("article")
+                .or_else(|| first("main"))
+                .or_else(|| densest_container(&document))
+                .or_else(|| first("body"))
+        }
+        .unwrap_or_else(|| document.root_element());
+
+        let mut text = String::new();
+        collect_text(root, self.document, &mut text);
+
+        // Collapse runs of blank lines and trailing spaces left by the markup
+        let mut lines: Vec<&str> = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+                continue;
+            }
+            lines.push(line);
+        }
+        lines.join("\n").trim().to_string()
+    }
+}
+
+/// The element whose own paragraphs hold the most text, as readability tools pick a
+/// page's content, when it has enough of it
+fn densest_container(document: &Html) -> Option<ElementRef<'_>> {
+    let paragraphs = Selector::parse("p").ok()?;
+    let mut scores: Vec<(ElementRef, usize)> = Vec::new();
+    for paragraph in document.select(&paragraphs) {
+        let Some(parent) = paragraph.parent().and_then(ElementRef::wrap) else {
+            continue;
+        };
+        if paragraph
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|el| {
+                let name = el.value().name();
+                SKIPPED.contains(&name) || CHROME.contains(&name) || is_boilerplate(el)
+            })
+        {
+            continue;
+        }
+        let chars = paragraph.text().map(|t| t.trim().len()).sum::<usize>();
+        match scores.iter_mut().find(|(el, _)| el.id() == parent.id()) {
+            Some((_, score)) => *score += chars,
+            None => scores.push((parent, chars)),
+        }
+    }
+    scores
+        .into_iter()
+        .filter(|(_, score)| *score >= MIN_CONTENT_CHARS)
+        .max_by_key(|(_, score)| *score)
+        .map(|(el, _)| el)
+}
+
+/// Level of a heading element
+fn heading_level(name: &str) -> Option<usize> {
+    match name.strip_prefix('h')?.parse() {
+        Ok(level @ 1..=6) => Some(level),
+        _ => None,
+    }
+}
+
+/// Whether the element's class or id names it as page chrome
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    [value.attr("class"), value.attr("id")]
+        .into_iter()
+        .flatten()
+        .flat_map(|names| names.split(|c: char| !c.is_ascii_alphanumeric()))
+        .map(str::to_ascii_lowercase)
+        .any(|name| {
+            matches!(name.as_str(), "ad" | "ads")
+                || BOILERPLATE.iter().any(|hint| name.starts_with(hint))
+        })
+}
+
+fn collect_text(element: ElementRef, document: bool, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => {
+                let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if collapsed.is_empty() {
+                    continue;
+                }
+                if text.starts_with(char::is_whitespace) && !out.ends_with(['\n', ' ']) {
+                    out.push(' ');
+                }
+                out.push_str(&collapsed);
+                if text.ends_with(char::is_whitespace) {
+                    out.push(' ');
+                }
+            }
+            Node::Element(el) => {
+                let name = el.name();
+                if SKIPPED.contains(&name) || (!document && CHROME.contains(&name)) {
+                    continue;
+                }
+                let Some(child) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                if !document && is_boilerplate(child) {
+                    continue;
+                }
+                let block = BLOCKS.contains(&name);
+                if block {
+                    out.push('\n');
+                }
+                if name == "li" {
+                    out.push_str("- ");
+                }
+                if let Some(level) = heading_level(name).filter(|_| document) {
+                    out.push_str(&"#".repeat(level));
+                    out.push(' ');
+                }
+                collect_text(child, document, out);
+                if block {
+                    out.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FileReader for HtmlReader {
+    fn read_as_text(&self, file_path: &str) -> Result<String> {
+        let bytes = std::fs::read(file_path)
+            .with_context(|| format!("Failed to read HTML file: {}", file_path))?;
+        self.read_as_text_from_bytes(&bytes)
+    }
+
+    fn read_as_text_from_bytes(&self, bytes: &[u8]) -> Result<String> {
+        Ok(self.extract(&String::from_utf8_lossy(bytes)))
+    }
+
+    fn can_handle(&self, extension: &str) -> bool {
+        matches!(extension.to_lowercase().as_str(), "html" | "htm" | "xhtml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_readable_text() {
+        let html = r#"<html><head><title>
+            Rust 2024 edition </title><style>body { color: red }</style></head>
+            <body>
+              <nav><a href="/">Home</a></nav>
+              <article>
+                <h1>The 2024 edition</h1>
+                <p>It is <b>stable</b> now.</p>
+                <script>track()</script>
+                <ul><li>let chains</li><li>gen blocks</li></ul>
+              </article>
+              <footer>Copyright</footer>
+            </body></html>"#;
+        let reader = HtmlReader::new();
+        assert_eq!(reader.title(html).as_deref(), Some("Rust 2024 edition"));
+        assert_eq!(
+            reader.extract(html),
+            "The 2024 edition\n\nIt is stable now.\n\n- let chains\n\n- gen blocks"
+        );
+    }
+
+    #[test]
+    fn test_extract_densest_container() {
+        let paragraph = "The release brings faster builds and a smaller binary. ".repeat(3);
+        let html = format!(
+            r#"<html><body>
+              <div class="top-menu"><p>Products</p><p>Pricing</p></div>
+              <div id="cookie-consent"><p>We use cookies to improve your experience.</p></div>
+              <div class="wrapper">
+                <div class="post"><h2>Release notes</h2><p>{p}</p><p>{p}</p>
+                  <div class="share-links">Share on social media</div>
+                </div>
+                <div class="sidebar"><p>Popular posts</p></div>
+              </div>
+            </body></html>"#,
+            p = paragraph.trim()
+        );
+        let text = HtmlReader::new().extract(&html);
+        assert!(text.starts_with("Release notes\n\nThe release brings"));
+        for chrome in ["Products", "cookies", "Share on", "Popular"] {
+            assert!(!text.contains(chrome), "{} in {}", chrome, text);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::FileReader;
+use anyhow::{Context, Result};
+
+/// Reader for Markdown files that normalizes their structure for chunking: setext
+/// headings (`Title` underlined with `===` or `---`) become `#` headings, and front
+/// matter and HTML comments are blanked. Every line stays on its line number, so
+/// chunks still cite the right `file:line`.
+pub struct MarkdownReader;
+
+impl Default for MarkdownReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownReader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The normalized text of a Markdown document
+    pub fn normalize(&self, markdown: &str) -> String {
+        let mut lines: Vec<String> = markdown.lines().map(str::to_string).collect();
+        blank_front_matter(&mut lines);
+        let code = code_lines(&lines);
+        blank_comments(&mut lines, &code);
+
+        for i in 1..lines.len() {
+            if code[i] || code[i - 1] {
+                continue;
+            }
+            let Some(level) = setext_level(&lines[i]) else {
+                continue;
+            };
+            let title = lines[i - 1].trim();
+            let starts_paragraph = i < 2 || lines[i - 2].trim().is_empty();
+            if is_paragraph_line(&lines[i - 1]) && starts_paragraph {
+                lines[i - 1] = format!("{} {}", "#".repeat(level), title);
+                lines[i].clear();
+            }
+        }
+
+        let mut text = lines.join("\n");
+        if markdown.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Which lines are in fenced code blocks, fences included
+fn code_lines(lines: &[String]) -> Vec<bool> {
+    let mut fence: Option<&str> = None;
+    lines
+        .iter()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            match fence {
+                Some(marker) => {
+                    if trimmed.starts_with(marker) {
+                        fence = None;
+                    }
+                    true
+                }
+                None => {
+                    fence = ["```", "~~~"]
+                        .into_iter()
+                        .find(|marker| trimmed.starts_with(marker));
+                    fence.is_some()
+                }
+            }
+        })
+        .collect()
+}
+
+/// Level of a setext heading underline: 1 for `===`, 2 for `---`
+fn setext_level(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    if line.len() - line.trim_start().len() > 3 || line.trim().is_empty() {
+        return None;
+    }
+    let line = line.trim_start();
+    if line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Whether a line is plain paragraph text, the only kind a setext underline turns into
+/// a heading
+fn is_paragraph_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty()
+        && line.len() - trimmed.len() < 4
+        && !trimmed.starts_with(['#', '>', '-', '*', '+', '|', '<'])
+        && !is_ordered_item(trimmed)
+}
+
+/// Whether a line starts an ordered list item, like `1.` or `2)`
+fn is_ordered_item(line: &str) -> bool {
+    line.split_once(['.', ')'])
+        .is_some_and(|(number, _)| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Blank YAML (`---`) or TOML (`+++`) front matter at the start of the document
+fn blank_front_matter(lines: &mut [String]) {
+    let Some(marker) = lines
+        .first()
+        .map(|line| line.trim_end())
+        .filter(|line| *line == "---" || *line == "+++")
+        .map(str::to_string)
+    else {
+        return;
+    };
+    let closes = |line: &String| {
+        let line = line.trim_end();
+        line == marker || (marker == "---" && line == "...")
+    };
+    let Some(end) = lines.iter().skip(1).position(closes) else {
+        return;
+    };
+    for line in &mut lines[..end + 2] {
+        line.clear();
+    }
+}
+
+/// Blank HTML comments outside code blocks, keeping the text around them on its line
+fn blank_comments(lines: &mut [String], code: &[bool]) {
+    let mut in_comment = false;
+    for (line, _) in lines.iter_mut().zip(code).filter(|(_, code)| !**code) {
+        let mut kept = String::new();
+        let mut rest = line.as_str();
+        loop {
+            if in_comment {
+                match rest.find("-->") {
+                    Some(end) => {
+                        rest = &rest[end + 3..];
+                        in_comment = false;
+                    }
+                    None => break,
+                }
+            } else {
+                match rest.find("<!--") {
+                    Some(start) => {
+                        kept.push_str(&rest[..start]);
+                        rest = &rest[start + 4..];
+                        in_comment = true;
+                    }
+                    None => {
+                        kept.push_str(rest);
+                        break;
+                    }
+                }
+            }
+        }
+        if kept.trim().is_empty() {
+            kept.clear();
+        }
+        *line = kept;
+    }
+}
+
+impl FileReader for MarkdownReader {
+    fn read_as_text(&self, file_path: &str) -> Result<String> {
+        let text = std::fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read Markdown file: {}", file_path))?;
+        Ok(self.normalize(&text))
+    }
+
+    fn read_as_text_from_bytes(&self, bytes: &[u8]) -> Result<String> {
+        Ok(self.normalize(&String::from_utf8_lossy(bytes)))
+    }
+
+    fn can_handle(&self, extension: &str) -> bool {
+        matches!(extension.to_lowercase().as_str(), "md" | "markdown")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let markdown = "---\ntitle: Guide\n# not a heading\n---\n\
+                        Install\n=======\n\nRun it. <!-- TODO: link -->\n\n\
+                        Usage\n-----\nSee below.\n\n---\n- item\n---\n\
+                        ```\nCode\n===\n<!-- kept -->\n```\n<!--\nhidden\n-->\nEnd\n";
+        let normalized = MarkdownReader::new().normalize(markdown);
+        assert_eq!(
+            normalized,
+            "\n\n\n\n# Install\n\n\nRun it. \n\n## Usage\n\nSee below.\n\n---\n- item\n---\n\
+             ```\nCode\n===\n<!-- kept -->\n```\n\n\n\nEnd\n"
+        );
+        assert_eq!(normalized.lines().count(), markdown.lines().count());
+    }
+}
@@ -1,5 +1,11 @@
+pub mod epub;
+pub mod html;
+pub mod markdown;
 #[cfg(feature = "pdf")]
 pub mod pdf;
+pub mod remote;
+pub mod table;
+pub mod web;
 
 use anyhow::{Context, Result};
 use std::io::Read;
@@ -33,11 +39,39 @@ pub trait FileReader {
     fn can_handle(&self, extension: &str) -> bool;
 }
 
-/// Get appropriate reader for file extension
+/// Get appropriate reader for file extension. WASM plugin readers take precedence over
+/// the built-in ones.
 pub fn get_reader_for_extension(extension: &str) -> Option<Box<dyn FileReader>> {
+    if let Some(reader) = crate::services::wasm_plugins::reader_for_extension(extension) {
+        return Some(reader);
+    }
     match extension.to_lowercase().as_str() {
         #[cfg(feature = "pdf")]
         "pdf" => Some(Box::new(pdf::PdfReader::new())),
+        "html" | "htm" | "xhtml" => Some(Box::new(html::HtmlReader::new())),
+        "epub" => Some(Box::new(epub::EpubReader::new())),
+        "md" | "markdown" => Some(Box::new(markdown::MarkdownReader::new())),
+        _ => None,
+    }
+}
+
+/// Reader for attached CSV, TSV and spreadsheet files, which sends their schema and
+/// first `--rows` rows
+pub fn get_table_reader(extension: &str) -> Option<Box<dyn FileReader>> {
+    table::TableReader::for_extension(extension)
+        .map(|reader| Box::new(reader) as Box<dyn FileReader>)
+}
+
+/// Reader for a local file that is attached or embedded: a WASM plugin's, or the
+/// built-in one for EPUB and Markdown. Other files, HTML included, are read as they
+/// are, since their source is what is asked about.
+pub fn get_document_reader(extension: &str) -> Option<Box<dyn FileReader>> {
+    if let Some(reader) = crate::services::wasm_plugins::reader_for_extension(extension) {
+        return Some(reader);
+    }
+    match extension.to_lowercase().as_str() {
+        "epub" => Some(Box::new(epub::EpubReader::new())),
+        "md" | "markdown" => Some(Box::new(markdown::MarkdownReader::new())),
         _ => None,
     }
 }
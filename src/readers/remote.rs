@@ -0,0 +1,257 @@
+//! Attachments from cloud storage and the web (`-a s3://...`, `-a gs://...`, `-a https://...`)
+//!
+//! Remote objects are downloaded and turned into text the same way local files are: with
+//! the reader for their extension (PDF, HTML, WASM plugins), then by the content type the
+//! server reports, and as UTF-8 text otherwise.
+//!
+//! - `s3://bucket/key` uses the sync configuration for the bucket (`lc sync configure`)
+//!   or the default AWS credential chain. Requires the `s3-sync` feature.
+//! - `gs://bucket/object` uses the sync configuration for the bucket when there is one
+//!   (GCS HMAC keys through its S3-compatible API), and the GCS JSON API otherwise, with
+//!   the token in `GOOGLE_OAUTH_ACCESS_TOKEN` or from `gcloud auth print-access-token`.
+//!   Public objects need no credentials.
+//! - `http(s)://` URLs are fetched without credentials.
+
+use crate::utils::terminal::sym;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::time::Duration;
+
+use crate::utils::cli_utils::Attachment;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+/// Objects larger than this are not downloaded
+const MAX_BYTES: usize = 50 * 1024 * 1024;
+const GCS_API: &str = "https://storage.googleapis.com/storage/v1/b";
+
+/// Where a remote attachment lives
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    S3 { bucket: String, key: String },
+    Gcs { bucket: String, object: String },
+    Http(String),
+}
+
+impl Source {
+    /// Parse an attachment argument, or `None` for a local path
+    pub fn parse(source: &str) -> Option<Source> {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            return reqwest::Url::parse(source)
+                .ok()
+                .map(|_| Source::Http(source.to_string()));
+        }
+        let (scheme, rest) = source.split_once("://")?;
+        let (bucket, key) = rest.split_once('/')?;
+        if bucket.is_empty() || key.is_empty() {
+            return None;
+        }
+        let (bucket, key) = (bucket.to_string(), key.to_string());
+        match scheme {
+            "s3" => Some(Source::S3 { bucket, key }),
+            "gs" => Some(Source::Gcs {
+                bucket,
+                object: key,
+            }),
+            _ => None,
+        }
+    }
+
+    /// File name used in the attachment header: the last path segment, or the host for
+    /// URLs without one
+    pub fn name(&self) -> String {
+        match self {
+            Source::S3 { key, .. } | Source::Gcs { object: key, .. } => {
+                key.rsplit('/').next().unwrap_or(key).to_string()
+            }
+            Source::Http(url) => {
+                let url = reqwest::Url::parse(url).ok();
+                url.as_ref()
+                    .and_then(|url| url.path_segments()?.rfind(|s| !s.is_empty()))
+                    .map(str::to_string)
+                    .or_else(|| url.as_ref()?.host_str().map(str::to_string))
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Whether an attachment argument names a remote object rather than a local file
+pub fn is_remote(source: &str) -> bool {
+    Source::parse(source).is_some()
+}
+
+/// Download a remote attachment and extract its text
+pub async fn read(source: &str) -> Result<Attachment> {
+    let parsed = Source::parse(source).context("Not a remote attachment")?;
+    eprintln!("{} {}", sym("🔗 Fetching").dimmed(), source.dimmed());
+    let (bytes, content_type) = download(&parsed)
+        .await
+        .with_context(|| format!("Failed to fetch attachment '{}'", source))?;
+
+    let name = parsed.name();
+    let extension = std::path::Path::new(&name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_string);
+    let (content, extracted) = to_text(extension.as_deref(), content_type.as_deref(), bytes)
+        .with_context(|| format!("Failed to read attachment '{}'", source))?;
+
+    Ok(Attachment {
+        name,
+        // Text extracted from HTML or PDF is not fenced as code in the prompt
+        extension: extension.filter(|_| !extracted),
+        content,
+    })
+}
+
+/// The object's bytes and, for HTTP downloads, its content type
+async fn download(source: &Source) -> Result<(Vec<u8>, Option<String>)> {
+    match source {
+        Source::S3 { bucket, key } => Ok((download_s3(bucket, key).await?, None)),
+        Source::Gcs { bucket, object } if has_sync_config(bucket) => {
+            Ok((download_s3(bucket, object).await?, None))
+        }
+        Source::Gcs { bucket, object } => {
+            let mut url = reqwest::Url::parse(GCS_API)?;
+            url.path_segments_mut()
+                .map_err(|_| anyhow::anyhow!("Invalid GCS API URL"))?
+                .extend([bucket.as_str(), "o", object.as_str()]);
+            url.set_query(Some("alt=media"));
+            download_http(url.as_str(), gcs_token()).await
+        }
+        Source::Http(url) => {
+            if let Some(cached) = crate::utils::web_cache::response(url) {
+                return Ok(cached);
+            }
+            let (bytes, content_type) = download_http(url, None).await?;
+            crate::utils::web_cache::put_response(url, &bytes, content_type.as_deref());
+            Ok((bytes, content_type))
+        }
+    }
+}
+
+#[cfg(feature = "s3-sync")]
+async fn download_s3(bucket: &str, key: &str) -> Result<Vec<u8>> {
+    crate::sync::providers::S3Provider::download_object(bucket, key, MAX_BYTES).await
+}
+
+#[cfg(not(feature = "s3-sync"))]
+async fn download_s3(_bucket: &str, _key: &str) -> Result<Vec<u8>> {
+    bail!("S3 attachments need lc built with the 's3-sync' feature")
+}
+
+/// Whether `lc sync configure` has credentials for this bucket
+fn has_sync_config(bucket: &str) -> bool {
+    use crate::sync::config::{ProviderConfig, SyncConfig};
+    SyncConfig::load().is_ok_and(|config| {
+        config.providers.values().any(
+            |provider| matches!(provider, ProviderConfig::S3 { bucket_name, .. } if bucket_name == bucket),
+        )
+    })
+}
+
+/// OAuth token for GCS, from `GOOGLE_OAUTH_ACCESS_TOKEN` or the gcloud CLI
+fn gcs_token() -> Option<String> {
+    if let Ok(token) = std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN") {
+        return Some(token);
+    }
+    let output = std::process::Command::new("gcloud")
+        .args(["auth", "print-access-token"])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let token = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !token.is_empty()).then_some(token)
+}
+
+async fn download_http(url: &str, token: Option<String>) -> Result<(Vec<u8>, Option<String>)> {
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()?;
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("server answered with status {}", status);
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_lowercase);
+    let bytes = super::web::read_body(response, MAX_BYTES).await?;
+    Ok((bytes, content_type))
+}
+
+/// Text of a downloaded object, and whether a reader extracted it. The reader for the
+/// extension is tried first, then the one for the content type.
+fn to_text(
+    extension: Option<&str>,
+    content_type: Option<&str>,
+    bytes: Vec<u8>,
+) -> Result<(String, bool)> {
+    let content_type = content_type.unwrap_or("");
+    let reader = match extension.and_then(|ext| {
+        super::get_reader_for_extension(ext).or_else(|| super::get_table_reader(ext))
+    }) {
+        Some(reader) => Some(reader),
+        None if content_type.contains("html") => super::get_reader_for_extension("html"),
+        None if content_type.contains("csv") => super::get_table_reader("csv"),
+        None if content_type.contains("pdf") => Some(
+            super::get_reader_for_extension("pdf")
+                .context("PDF support is not enabled in this build")?,
+        ),
+        None => None,
+    };
+    match reader {
+        Some(reader) => Ok((reader.read_as_text_from_bytes(&bytes)?, true)),
+        None => String::from_utf8(bytes)
+            .map(|text| (text, false))
+            .map_err(|_| anyhow::anyhow!("not a text file")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            Source::parse("s3://docs/reports/q3.pdf"),
+            Some(Source::S3 {
+                bucket: "docs".to_string(),
+                key: "reports/q3.pdf".to_string()
+            })
+        );
+        let gcs = Source::parse("gs://data/notes.md").unwrap();
+        assert_eq!(gcs.name(), "notes.md");
+        assert_eq!(
+            Source::parse("https://example.com/").unwrap().name(),
+            "example.com"
+        );
+        assert_eq!(
+            Source::parse("https://example.com/docs/guide.html?v=2")
+                .unwrap()
+                .name(),
+            "guide.html"
+        );
+        assert!(Source::parse("s3://bucket-only").is_none());
+        assert!(Source::parse("notes/readme.md").is_none());
+        assert!(Source::parse("ftp://host/file").is_none());
+
+        assert_eq!(
+            to_text(None, Some("text/html"), b"<p>Hi</p>".to_vec()).unwrap(),
+            ("Hi".to_string(), true)
+        );
+        assert!(to_text(Some("bin"), None, vec![0xff, 0xfe]).is_err());
+    }
+}
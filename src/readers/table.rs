@@ -0,0 +1,404 @@
+use super::FileReader;
+use anyhow::{Context, Result};
+use std::sync::RwLock;
+
+/// Rows of a large table that are attached when `--rows` is not given
+pub const DEFAULT_ROWS: usize = 20;
+
+/// Tables whose full Markdown rendering is at most this many characters are attached
+/// whole when `--rows` is not given
+pub const FULL_TABLE_CHARS: usize = 20_000;
+
+/// Longest cell shown in the sample, in characters
+const MAX_CELL_CHARS: usize = 100;
+
+const EXTENSIONS: &[&str] = &["csv", "tsv", "xlsx", "xlsm", "xls", "ods"];
+
+static ROWS: RwLock<Option<usize>> = RwLock::new(None);
+
+/// Set `--rows` for this invocation
+pub fn set(rows: Option<usize>) {
+    if let Ok(mut current) = ROWS.write() {
+        *current = rows;
+    }
+}
+
+/// Rows of an attached table sent after its schema, `None` when not set
+pub fn rows() -> Option<usize> {
+    ROWS.read().ok().and_then(|rows| *rows)
+}
+
+/// Reader for CSV, TSV and spreadsheet files: a schema summary (row count, column
+/// names and types) of each table or sheet, then its first rows as a Markdown table,
+/// so that large files fit in the context
+pub struct TableReader {
+    /// Rows shown after the schema; `None` shows small tables whole and the first
+    /// [`DEFAULT_ROWS`] of larger ones
+    rows: Option<usize>,
+}
+
+impl TableReader {
+    /// Reader that shows the first `rows` rows of each table
+    pub fn new(rows: usize) -> Self {
+        Self { rows: Some(rows) }
+    }
+
+    /// Reader that shows tables up to [`FULL_TABLE_CHARS`] whole and the first
+    /// [`DEFAULT_ROWS`] rows of larger ones
+    pub fn automatic() -> Self {
+        Self { rows: None }
+    }
+
+    /// Reader for attached files of a table format, showing `--rows` rows
+    pub fn for_extension(extension: &str) -> Option<Self> {
+        EXTENSIONS
+            .contains(&extension.to_lowercase().as_str())
+            .then(|| Self { rows: rows() })
+    }
+
+    fn render(&self, table: &Table) -> String {
+        match self.rows {
+            Some(limit) => table.render(limit),
+            None => {
+                let full = table.render(table.rows.len());
+                if full.chars().count() <= FULL_TABLE_CHARS {
+                    full
+                } else {
+                    table.render(DEFAULT_ROWS)
+                }
+            }
+        }
+    }
+}
+
+/// A cell, typed for the schema. Each variant keeps the cell's text as written in
+/// the file, so that values such as `02134` or `1.50` are shown unchanged.
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Empty,
+    Bool(String),
+    Int(String),
+    Float(String),
+    Date(String),
+    DateTime(String),
+    Text(String),
+}
+
+impl Cell {
+    /// The type of a cell read from text
+    fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() {
+            return Cell::Empty;
+        }
+        if value.parse::<i64>().is_ok() {
+            return Cell::Int(value.to_string());
+        }
+        if value.parse::<f64>().is_ok_and(f64::is_finite) {
+            return Cell::Float(value.to_string());
+        }
+        if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            return Cell::Bool(value.to_string());
+        }
+        if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+            return Cell::Date(value.to_string());
+        }
+        if chrono::DateTime::parse_from_rfc3339(value).is_ok()
+            || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").is_ok()
+        {
+            return Cell::DateTime(value.to_string());
+        }
+        Cell::Text(value.to_string())
+    }
+
+    fn from_sheet(data: &calamine::Data) -> Self {
+        use calamine::Data;
+        match data {
+            Data::Empty => Cell::Empty,
+            Data::Bool(value) => Cell::Bool(value.to_string()),
+            Data::Int(value) => Cell::Int(value.to_string()),
+            // Spreadsheets store every number as a float
+            Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                Cell::Int((*value as i64).to_string())
+            }
+            Data::Float(value) => Cell::Float(value.to_string()),
+            Data::DateTime(value) => match value.as_datetime() {
+                Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => {
+                    Cell::Date(datetime.format("%Y-%m-%d").to_string())
+                }
+                Some(datetime) => Cell::DateTime(datetime.format("%Y-%m-%d %H:%M:%S").to_string()),
+                None => Cell::Text(value.to_string()),
+            },
+            Data::DateTimeIso(value) => Cell::parse(value),
+            Data::String(value) => Cell::Text(value.trim().to_string()),
+            other => Cell::Text(other.to_string()),
+        }
+    }
+
+    fn type_name(&self) -> Option<&'static str> {
+        match self {
+            Cell::Empty => None,
+            Cell::Bool(_) => Some("boolean"),
+            Cell::Int(_) => Some("integer"),
+            Cell::Float(_) => Some("number"),
+            Cell::Date(_) => Some("date"),
+            Cell::DateTime(_) => Some("datetime"),
+            Cell::Text(_) => Some("text"),
+        }
+    }
+}
+
+impl std::fmt::Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cell::Empty => Ok(()),
+            Cell::Bool(value)
+            | Cell::Int(value)
+            | Cell::Float(value)
+            | Cell::Date(value)
+            | Cell::DateTime(value)
+            | Cell::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+/// A table whose first row is its header
+struct Table {
+    name: Option<String>,
+    columns: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    fn new(name: Option<String>, mut rows: Vec<Vec<Cell>>) -> Self {
+        rows.retain(|row| row.iter().any(|cell| *cell != Cell::Empty));
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let header = if rows.is_empty() {
+            Vec::new()
+        } else {
+            rows.remove(0)
+        };
+        let columns = (0..width)
+            .map(|i| match header.get(i) {
+                Some(Cell::Empty) | None => format!("column {}", i + 1),
+                Some(cell) => cell.to_string(),
+            })
+            .collect();
+        for row in &mut rows {
+            row.resize(width, Cell::Empty);
+        }
+        Self {
+            name,
+            columns,
+            rows,
+        }
+    }
+
+    /// Type of a column: the type all its cells share, `number` for integers mixed
+    /// with other numbers, `datetime` for dates mixed with times, `text` otherwise
+    fn column_type(&self, column: usize) -> &'static str {
+        let mut types = self.rows.iter().filter_map(|row| row[column].type_name());
+        let Some(first) = types.next() else {
+            return "empty";
+        };
+        types.fold(first, |current, next| match (current, next) {
+            (a, b) if a == b => a,
+            ("integer" | "number", "integer" | "number") => "number",
+            ("date" | "datetime", "date" | "datetime") => "datetime",
+            _ => "text",
+        })
+    }
+
+    /// The schema summary and the first `limit` rows
+    fn render(&self, limit: usize) -> String {
+        let mut out = String::new();
+        if let Some(name) = &self.name {
+            out.push_str(&format!("Sheet: {}\n", name));
+        }
+        out.push_str(&format!(
+            "Rows: {} (not counting the header)\nColumns:\n",
+            self.rows.len()
+        ));
+        for (i, column) in self.columns.iter().enumerate() {
+            let empty = self.rows.iter().filter(|row| row[i] == Cell::Empty).count();
+            out.push_str(&format!("- {} ({}", column, self.column_type(i)));
+            if empty > 0 && empty < self.rows.len() {
+                out.push_str(&format!(", {} empty", empty));
+            }
+            out.push_str(")\n");
+        }
+        if limit == 0 || self.rows.is_empty() {
+            return out;
+        }
+
+        if self.rows.len() > limit {
+            out.push_str(&format!("\nFirst {} rows:\n", limit));
+        } else {
+            out.push('\n');
+        }
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        out.push_str(&line(self.columns.iter().map(|c| escape(c)).collect()));
+        out.push_str(&line(vec!["---".to_string(); self.columns.len()]));
+        for row in self.rows.iter().take(limit) {
+            out.push_str(&line(row.iter().map(|c| escape(&c.to_string())).collect()));
+        }
+        out
+    }
+}
+
+/// A cell's text for a Markdown table: on one line, pipes escaped, long text cut
+fn escape(text: &str) -> String {
+    let mut text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((cut, _)) = text.char_indices().nth(MAX_CELL_CHARS) {
+        text.truncate(cut);
+        text.push('…');
+    }
+    text.replace('|', "\\|")
+}
+
+fn read_delimited(bytes: &[u8], delimiter: u8) -> Result<Table> {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(bytes);
+    let rows = reader
+        .byte_records()
+        .map(|record| {
+            let record = record.context("Invalid CSV")?;
+            Ok(record
+                .iter()
+                .map(|field| Cell::parse(&String::from_utf8_lossy(field)))
+                .collect())
+        })
+        .collect::<Result<_>>()?;
+    Ok(Table::new(None, rows))
+}
+
+fn read_workbook(bytes: &[u8]) -> Result<Vec<Table>> {
+    use calamine::Reader;
+    let mut workbook = calamine::open_workbook_auto_from_rs(std::io::Cursor::new(bytes.to_vec()))
+        .context("Invalid spreadsheet")?;
+    let tables = workbook
+        .worksheets()
+        .into_iter()
+        .map(|(name, range)| {
+            let rows = range
+                .rows()
+                .map(|row| row.iter().map(Cell::from_sheet).collect())
+                .collect();
+            Table::new(Some(name), rows)
+        })
+        .filter(|table| !table.columns.is_empty())
+        .collect();
+    Ok(tables)
+}
+
+impl FileReader for TableReader {
+    fn read_as_text(&self, file_path: &str) -> Result<String> {
+        let bytes = std::fs::read(file_path)
+            .with_context(|| format!("Failed to read table file: {}", file_path))?;
+        let extension = std::path::Path::new(file_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let tables = match extension.as_str() {
+            "csv" => vec![read_delimited(&bytes, b',')?],
+            "tsv" => vec![read_delimited(&bytes, b'\t')?],
+            _ => read_workbook(&bytes)?,
+        };
+        Ok(tables
+            .iter()
+            .map(|table| self.render(table))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn read_as_text_from_bytes(&self, bytes: &[u8]) -> Result<String> {
+        // Spreadsheets are zip (xlsx, ods) or OLE (xls) files; anything else is CSV
+        let tables = if bytes.starts_with(b"PK") || bytes.starts_with(b"\xd0\xcf\x11\xe0") {
+            read_workbook(bytes)?
+        } else {
+            let tabs = bytes
+                .iter()
+                .take_while(|&&b| b != b'\n')
+                .filter(|&&b| b == b'\t');
+            let delimiter = if tabs.count() > 0 { b'\t' } else { b',' };
+            vec![read_delimited(bytes, delimiter)?]
+        };
+        Ok(tables
+            .iter()
+            .map(|table| self.render(table))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn can_handle(&self, extension: &str) -> bool {
+        EXTENSIONS.contains(&extension.to_lowercase().as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_csv() {
+        let csv = "\u{feff}name,age,score,joined,notes\n\
+                   Ada,036,9.50,2024-01-02,\"likes | pipes\"\n\
+                   \n\
+                   Grace,45,8,2024-03-04 09:30:00,\n\
+                   Linus,,7,2024-05-06,extra,column\n";
+        let reader = TableReader::new(2);
+        assert_eq!(
+            reader.read_as_text_from_bytes(csv.as_bytes()).unwrap(),
+            "Rows: 3 (not counting the header)\n\
+             Columns:\n\
+             - name (text)\n\
+             - age (integer, 1 empty)\n\
+             - score (number)\n\
+             - joined (datetime)\n\
+             - notes (text, 1 empty)\n\
+             - column 6 (text, 2 empty)\n\
+             \n\
+             First 2 rows:\n\
+             | name | age | score | joined | notes | column 6 |\n\
+             | --- | --- | --- | --- | --- | --- |\n\
+             | Ada | 036 | 9.50 | 2024-01-02 | likes \\| pipes |  |\n\
+             | Grace | 45 | 8 | 2024-03-04 09:30:00 |  |  |\n"
+        );
+
+        let tsv = TableReader::new(0)
+            .read_as_text_from_bytes(b"id\tok\n1\ttrue\n")
+            .unwrap();
+        assert_eq!(
+            tsv,
+            "Rows: 1 (not counting the header)\nColumns:\n- id (integer)\n- ok (boolean)\n"
+        );
+    }
+
+    #[test]
+    fn test_automatic_rows() {
+        let mut small = String::from("id,zip\n");
+        for i in 0..30 {
+            small.push_str(&format!("{},0{}\n", i, 2100 + i));
+        }
+        let text = TableReader::automatic()
+            .read_as_text_from_bytes(small.as_bytes())
+            .unwrap();
+        assert!(!text.contains("First"));
+        assert!(text.contains("| 29 | 02129 |"));
+
+        let mut large = String::from("id,notes\n");
+        for i in 0..500 {
+            large.push_str(&format!("{},{}\n", i, "x".repeat(60)));
+        }
+        let text = TableReader::automatic()
+            .read_as_text_from_bytes(large.as_bytes())
+            .unwrap();
+        assert!(text.contains(&format!("First {} rows:", DEFAULT_ROWS)));
+        assert!(!text.contains(&format!("| {} |", DEFAULT_ROWS)));
+    }
+}
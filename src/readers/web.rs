@@ -0,0 +1,258 @@
+//! Context from URLs in a prompt (`--auto-fetch-urls`)
+//!
+//! URLs found in the prompt are fetched and their readable content is extracted with
+//! the reader for the response's content type (HTML pages, PDFs, plain text). Each
+//! page is appended to the prompt as a labeled block, the same way attached files
+//! are, so "what do you think of this article? https://..." just works.
+
+use crate::utils::terminal::sym;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use super::html::HtmlReader;
+use crate::utils::web_cache;
+
+/// At most this many URLs are fetched per prompt
+const MAX_URLS: usize = 5;
+/// Extracted content beyond this many characters is cut off
+const MAX_CHARS: usize = 20_000;
+/// Responses larger than this are not read
+const MAX_BYTES: usize = 10 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The extracted content of a fetched URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchedPage {
+    pub url: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub truncated: bool,
+}
+
+impl FetchedPage {
+    /// The page as a labeled context block
+    pub fn to_context_block(&self) -> String {
+        let mut block = match &self.title {
+            Some(title) => format!("=== URL: {} ({}) ===\n", self.url, title),
+            None => format!("=== URL: {} ===\n", self.url),
+        };
+        block.push_str(&self.content);
+        if self.truncated {
+            block.push_str("\n[content truncated]");
+        }
+        block.push('\n');
+        block
+    }
+}
+
+/// Check whether `--auto-fetch-urls` is enabled for this invocation
+pub fn is_enabled() -> bool {
+    crate::AUTO_FETCH_URLS_MODE.load(Ordering::Relaxed)
+}
+
+/// Append the content of the URLs in the prompt when `--auto-fetch-urls` is enabled.
+/// URLs that cannot be fetched are reported and left out.
+pub async fn with_url_context(prompt: String) -> String {
+    if !is_enabled() {
+        return prompt;
+    }
+    let urls = find_urls(&prompt);
+    if urls.is_empty() {
+        return prompt;
+    }
+
+    let mut blocks = Vec::new();
+    for url in urls {
+        eprintln!("{} {}", sym("🔗 Fetching").dimmed(), url.dimmed());
+        match fetch(&url).await {
+            Ok(page) => blocks.push(page.to_context_block()),
+            Err(e) => eprintln!("{} Could not fetch {}: {}", sym("⚠️").yellow(), url, e),
+        }
+    }
+    if blocks.is_empty() {
+        return prompt;
+    }
+    format!("{}\n\n{}", prompt, blocks.join("\n"))
+}
+
+/// The distinct http(s) URLs in a text, in order of appearance
+pub fn find_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`')) {
+        let Some(start) = word.find("https://").or_else(|| word.find("http://")) else {
+            continue;
+        };
+        let mut url = &word[start..];
+        // Punctuation around a URL in prose is not part of it
+        url = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'']);
+        if url.ends_with(')') && !url.contains('(') {
+            url = url.trim_end_matches(')');
+        }
+        if url.ends_with(']') && !url.contains('[') {
+            url = url.trim_end_matches(']');
+        }
+        if reqwest::Url::parse(url).is_ok() && !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+        if urls.len() == MAX_URLS {
+            break;
+        }
+    }
+    urls
+}
+
+/// Fetch a URL and extract its readable content. Pages in the web cache are not
+/// fetched again.
+pub async fn fetch(url: &str) -> Result<FetchedPage> {
+    let (bytes, content_type) = match web_cache::response(url) {
+        Some(cached) => cached,
+        None => {
+            let (bytes, content_type) = download(url).await?;
+            web_cache::put_response(url, &bytes, content_type.as_deref());
+            (bytes, content_type)
+        }
+    };
+    let content_type = content_type.unwrap_or_default();
+
+    let (title, content) = if content_type.contains("html") {
+        let html = String::from_utf8_lossy(&bytes);
+        let reader = HtmlReader::new();
+        (reader.title(&html), reader.extract(&html))
+    } else if content_type.contains("pdf") {
+        let reader = super::get_reader_for_extension("pdf")
+            .context("PDF support is not enabled in this build")?;
+        (None, reader.read_as_text_from_bytes(&bytes)?)
+    } else if content_type.is_empty()
+        || content_type.starts_with("text/")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+    {
+        (None, String::from_utf8_lossy(&bytes).trim().to_string())
+    } else {
+        bail!("unsupported content type '{}'", content_type);
+    };
+
+    if content.is_empty() {
+        bail!("no readable content");
+    }
+    let (content, truncated) = truncate(content, MAX_CHARS);
+    Ok(FetchedPage {
+        url: url.to_string(),
+        title,
+        content,
+        truncated,
+    })
+}
+
+async fn download(url: &str) -> Result<(Vec<u8>, Option<String>)> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()?;
+    let response = client.get(url).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("server answered with status {}", status);
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_lowercase);
+    let bytes = read_body(response, MAX_BYTES).await?;
+    Ok((bytes, content_type))
+}
+
+/// The body of `response`, read chunk by chunk so an oversized or endless response is
+/// rejected once it passes `max_bytes` instead of being buffered whole
+pub(crate) async fn read_body(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+) -> Result<Vec<u8>> {
+    if response
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        bail!("response is larger than {} MB", max_bytes / (1024 * 1024));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.context("failed to read response")? {
+        if body.len() + chunk.len() > max_bytes {
+            bail!("response is larger than {} MB", max_bytes / (1024 * 1024));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+fn truncate(content: String, max_chars: usize) -> (String, bool) {
+    match content.char_indices().nth(max_chars) {
+        Some((end, _)) => (content[..end].to_string(), true),
+        None => (content, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_urls() {
+        let prompt = "What do you think of this article (https://example.com/post?id=1)? \
+                      Compare with https://en.wikipedia.org/wiki/Rust_(programming_language), \
+                      and https://example.com/post?id=1 again. Not a URL: ftp://x.";
+        assert_eq!(
+            find_urls(prompt),
+            vec![
+                "https://example.com/post?id=1",
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+            ]
+        );
+        assert!(find_urls("no links here").is_empty());
+    }
+
+    #[test]
+    fn test_context_block() {
+        let page = FetchedPage {
+            url: "https://example.com".to_string(),
+            title: Some("Example".to_string()),
+            content: "Hello".to_string(),
+            truncated: true,
+        };
+        assert_eq!(
+            page.to_context_block(),
+            "=== URL: https://example.com (Example) ===\nHello\n[content truncated]\n"
+        );
+        assert_eq!(truncate("héllo".to_string(), 2), ("hé".to_string(), true));
+    }
+
+    #[tokio::test]
+    async fn test_read_body_stops_at_limit() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // No Content-Length, so only the chunk loop can notice the size
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = socket.read(&mut request).await;
+                let _ = socket.write_all(b"HTTP/1.0 200 OK\r\n\r\n").await;
+                let _ = socket.write_all(&[b'x'; 4096]).await;
+            }
+        });
+        let url = format!("http://{}", addr);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(read_body(response, 8192).await.unwrap().len(), 4096);
+        let response = reqwest::get(&url).await.unwrap();
+        let err = read_body(response, 1024).await.unwrap_err();
+        assert!(err.to_string().contains("larger than"));
+    }
+}
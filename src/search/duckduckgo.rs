@@ -82,7 +82,18 @@ impl DuckDuckGoProvider {
         Self { url, headers }
     }
 
+    /// Whether the URL is the HTML or lite search page rather than the Instant Answer API
+    fn is_web_search(&self) -> bool {
+        reqwest::Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .is_some_and(|host| host == "html.duckduckgo.com" || host == "lite.duckduckgo.com")
+    }
+
     pub async fn search(&self, query: &str, count: Option<usize>) -> Result<SearchResults> {
+        if self.is_web_search() {
+            return self.search_web(query, count).await;
+        }
         let client = reqwest::Client::new();
 
         // Build query parameters for DuckDuckGo Instant Answer API
@@ -249,6 +260,144 @@ impl DuckDuckGoProvider {
     }
 }
 
+impl DuckDuckGoProvider {
+    /// Web results from the HTML (`html.duckduckgo.com/html/`) or lite
+    /// (`lite.duckduckgo.com/lite/`) search page, which return ordinary search results
+    /// where the Instant Answer API only knows about topics
+    async fn search_web(&self, query: &str, count: Option<usize>) -> Result<SearchResults> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+        let mut request = client.post(&self.url).form(&[("q", query)]);
+        if !self
+            .headers
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("user-agent"))
+        {
+            request = request.header(reqwest::header::USER_AGENT, WEB_USER_AGENT);
+        }
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+
+        let start_time = std::time::Instant::now();
+        let response = request.send().await?;
+        let search_time_ms = start_time.elapsed().as_millis() as u64;
+        let status = response.status();
+        crate::debug_log!("DuckDuckGo: Received web page with status: {}", status);
+        if !status.is_success() {
+            anyhow::bail!("DuckDuckGo request failed with status {}", status);
+        }
+        let page = response.text().await?;
+
+        let mut results = parse_web_results(&page);
+        if results.is_empty() && page.contains("anomaly") {
+            anyhow::bail!(
+                "DuckDuckGo answered with a bot check instead of results; wait a while before searching again"
+            );
+        }
+        results.truncate(count.unwrap_or(10));
+        crate::debug_log!("DuckDuckGo: Extracted {} web results", results.len());
+
+        Ok(SearchResults {
+            query: query.to_string(),
+            provider: "DuckDuckGo".to_string(),
+            results,
+            total_results: None,
+            search_time_ms: Some(search_time_ms),
+        })
+    }
+}
+
+/// Sent to the HTML and lite pages, which turn away requests without a browser agent
+const WEB_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+/// Results of an HTML or lite search page, ads left out
+fn parse_web_results(page: &str) -> Vec<SearchResult> {
+    use scraper::{Html, Selector};
+    let document = Html::parse_document(page);
+    let text = |element: scraper::ElementRef| {
+        element
+            .text()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut results = Vec::new();
+
+    // HTML page: one div.result per hit, ads marked with result--ad
+    let result = Selector::parse("div.result").expect("valid selector");
+    let title = Selector::parse("a.result__a").expect("valid selector");
+    let snippet = Selector::parse(".result__snippet").expect("valid selector");
+    for hit in document.select(&result) {
+        if hit.value().classes().any(|class| class == "result--ad") {
+            continue;
+        }
+        let Some(link) = hit.select(&title).next() else {
+            continue;
+        };
+        let Some(url) = link.value().attr("href").and_then(result_url) else {
+            continue;
+        };
+        results.push(SearchResult {
+            title: text(link),
+            url,
+            snippet: hit.select(&snippet).next().map(text).unwrap_or_default(),
+            published_date: None,
+            author: None,
+            score: None,
+        });
+    }
+    if !results.is_empty() {
+        return results;
+    }
+
+    // Lite page: a table with the link of each hit in one row and its snippet in a
+    // following one
+    let link = Selector::parse("a.result-link").expect("valid selector");
+    let snippet = Selector::parse("td.result-snippet").expect("valid selector");
+    let links: Vec<_> = document.select(&link).collect();
+    let snippets: Vec<_> = document.select(&snippet).collect();
+    for (i, link) in links.into_iter().enumerate() {
+        let Some(url) = link.value().attr("href").and_then(result_url) else {
+            continue;
+        };
+        results.push(SearchResult {
+            title: text(link),
+            url,
+            snippet: snippets.get(i).map(|s| text(*s)).unwrap_or_default(),
+            published_date: None,
+            author: None,
+            score: None,
+        });
+    }
+    results
+}
+
+/// The address a result links to: DuckDuckGo wraps it in a `/l/?uddg=` redirect, and
+/// ads go through `/y.js`
+fn result_url(href: &str) -> Option<String> {
+    let url = reqwest::Url::parse("https://duckduckgo.com")
+        .ok()?
+        .join(href)
+        .ok()?;
+    let is_duckduckgo = url
+        .host_str()
+        .is_some_and(|host| host == "duckduckgo.com" || host.ends_with(".duckduckgo.com"));
+    if !is_duckduckgo {
+        return Some(url.to_string());
+    }
+    if url.path() == "/l/" {
+        return url
+            .query_pairs()
+            .find(|(key, _)| key == "uddg")
+            .map(|(_, target)| target.into_owned());
+    }
+    None
+}
+
 /// Search function that matches the interface used by other providers
 pub async fn search(
     provider_config: &super::SearchProviderConfig,
@@ -260,3 +409,37 @@ pub async fn search(
 
     provider.search(query, count).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_web_results() {
+        let html = r#"<html><body>
+            <div class="result results_links result--ad">
+              <a class="result__a" href="https://duckduckgo.com/y.js?ad_domain=shop.example">Buy now</a>
+            </div>
+            <div class="result results_links results_links_deep web-result">
+              <h2><a class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fdoc.rust-lang.org%2Fbook%2F&amp;rut=abc">The Rust <b>Book</b></a></h2>
+              <a class="result__snippet" href="//duckduckgo.com/l/?uddg=x">Learn   Rust from the ground up.</a>
+            </div>
+            <div class="result"><a class="result__a" href="https://example.com/direct">Direct</a></div>
+          </body></html>"#;
+        let results = parse_web_results(html);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "The Rust Book");
+        assert_eq!(results[0].url, "https://doc.rust-lang.org/book/");
+        assert_eq!(results[0].snippet, "Learn Rust from the ground up.");
+        assert_eq!(results[1].url, "https://example.com/direct");
+
+        let lite = r#"<table>
+            <tr><td><a rel="nofollow" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F" class="result-link">Rust</a></td></tr>
+            <tr><td class="result-snippet">A language empowering everyone.</td></tr>
+          </table>"#;
+        let results = parse_web_results(lite);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].snippet, "A language empowering everyone.");
+    }
+}
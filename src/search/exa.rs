@@ -3,6 +3,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct ExaSearchRequest {
     query: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -122,4 +123,17 @@ mod tests {
         assert_eq!(response.results[0].title, "Understanding AI Safety");
         assert_eq!(response.results[0].score, Some(0.95));
     }
+
+    #[test]
+    fn test_exa_request_serialization() {
+        let request = ExaSearchRequest {
+            query: "rust".to_string(),
+            num_results: Some(3),
+            contents: ExaContentsRequest { text: true },
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({"query": "rust", "numResults": 3, "contents": {"text": true}})
+        );
+    }
 }
@@ -4,7 +4,9 @@ pub mod duckduckgo;
 pub mod exa;
 pub mod jina;
 pub mod providers;
+pub mod rewrite;
 pub mod search_result;
+pub mod searxng;
 pub mod serpapi;
 pub mod serper;
 pub mod tavily;
@@ -33,8 +35,12 @@ impl SearchEngine {
         count: Option<usize>,
     ) -> Result<SearchResults> {
         let provider_config = self.config.get_provider(provider_name)?;
+        let cache_key = crate::utils::web_cache::search_key(provider_name, query, count);
+        if let Some(results) = crate::utils::web_cache::get::<SearchResults>(&cache_key) {
+            return Ok(results);
+        }
 
-        match provider_config.provider_type {
+        let results = match provider_config.provider_type {
             SearchProviderType::Brave => brave::search(provider_config, query, count).await,
             SearchProviderType::Exa => exa::search(provider_config, query, count).await,
             SearchProviderType::Serper => serper::search(provider_config, query, count).await,
@@ -44,7 +50,10 @@ impl SearchEngine {
             }
             SearchProviderType::Jina => jina::search(provider_config, query, count).await,
             SearchProviderType::Tavily => tavily::search(provider_config, query, count).await,
-        }
+            SearchProviderType::Searxng => searxng::search(provider_config, query, count).await,
+        }?;
+        crate::utils::web_cache::put(&cache_key, &results);
+        Ok(results)
     }
 
     pub fn format_results_json(&self, results: &SearchResults) -> Result<String> {
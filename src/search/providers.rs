@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
+#[value(rename_all = "lower")]
 pub enum SearchProviderType {
     Brave,
     Exa,
@@ -11,6 +12,8 @@ pub enum SearchProviderType {
     DuckDuckGo,
     Jina,
     Tavily,
+    /// A self-hosted SearXNG instance, through its JSON API
+    Searxng,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,6 +54,8 @@ impl SearchProviderType {
             Ok(SearchProviderType::Jina)
         } else if url_lower.contains("api.tavily.com") || url_lower.contains("tavily.com") {
             Ok(SearchProviderType::Tavily)
+        } else if url_lower.contains("searx") {
+            Ok(SearchProviderType::Searxng)
         } else {
             anyhow::bail!(
                 "Cannot auto-detect provider type from URL '{}'. \
@@ -59,14 +64,30 @@ impl SearchProviderType {
                 - Exa: api.exa.ai\n\
                 - Serper: google.serper.dev\n\
                 - SerpApi: serpapi.com\n\
-                - DuckDuckGo: api.duckduckgo.com\n\
+                - DuckDuckGo: html.duckduckgo.com, lite.duckduckgo.com, api.duckduckgo.com\n\
                 - Jina: s.jina.ai\n\
-                - Tavily: api.tavily.com",
+                - Tavily: api.tavily.com\n\
+                - SearXNG: any URL containing 'searx', or pass --type searxng",
                 url
             )
         }
     }
 
+    /// Search endpoint used when a provider is added with `--type` and no URL: the
+    /// hosted API, or a local instance for SearXNG
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            SearchProviderType::Brave => "https://api.search.brave.com/res/v1/web/search",
+            SearchProviderType::Exa => "https://api.exa.ai/search",
+            SearchProviderType::Serper => "https://google.serper.dev/search",
+            SearchProviderType::SerpApi => "https://serpapi.com/search",
+            SearchProviderType::DuckDuckGo => "https://html.duckduckgo.com/html/",
+            SearchProviderType::Jina => "https://s.jina.ai/",
+            SearchProviderType::Tavily => "https://api.tavily.com/search",
+            SearchProviderType::Searxng => "http://localhost:8080",
+        }
+    }
+
     /// Get the correct API key header name for this provider type
     pub fn api_key_header(&self) -> &'static str {
         match self {
@@ -77,6 +98,7 @@ impl SearchProviderType {
             SearchProviderType::DuckDuckGo => "", // No API key required
             SearchProviderType::Jina => "Authorization",
             SearchProviderType::Tavily => "Authorization",
+            SearchProviderType::Searxng => "", // No API key required
         }
     }
 }
@@ -0,0 +1,184 @@
+//! Search query rewriting for `--use-search` (`--search-rewrite-model`)
+//!
+//! A conversational prompt ("so what changed in the new borrow checker, and should I
+//! upgrade?") makes a poor search query. With a rewrite model, a (cheap) chat model first
+//! turns the prompt into one to three focused queries. Each is searched, the results are
+//! merged without duplicate URLs, and every source is labeled with the query that found it.
+
+use super::{SearchResult, SearchResults};
+use std::sync::RwLock;
+
+/// Most queries searched per prompt
+pub const MAX_QUERIES: usize = 3;
+
+/// Longest part of the prompt sent to the rewrite model, in characters
+const MAX_INPUT_CHARS: usize = 4000;
+
+static MODEL: RwLock<Option<String>> = RwLock::new(None);
+
+/// Set the rewrite model for this invocation (`--search-rewrite-model`)
+pub fn set(model: Option<String>) {
+    if let Ok(mut current) = MODEL.write() {
+        *current = model.filter(|m| !m.trim().is_empty());
+    }
+}
+
+/// The rewrite model (`provider:model` or alias), when queries are rewritten
+pub fn model() -> Option<String> {
+    MODEL.read().ok()?.clone()
+}
+
+/// The message asking the model for the search queries of `prompt`
+pub fn prompt(prompt: &str) -> String {
+    let prompt = match prompt.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((end, _)) => &prompt[..end],
+        None => prompt,
+    };
+    format!(
+        "Turn the user's message below into 1 to {} focused web search queries that find \
+         what is needed to answer it. Use keywords rather than questions, and only give \
+         several queries when the message asks about several things. Reply with a JSON \
+         array of strings only, like [\"first query\", \"second query\"].\n\n\
+         <message>\n{}\n</message>",
+        MAX_QUERIES,
+        prompt.trim()
+    )
+}
+
+/// The queries in the model's reply: a JSON array, or one query per line when the model
+/// ignored the format. Empty and repeated queries are dropped.
+pub fn parse(reply: &str) -> Vec<String> {
+    let from_json = reply
+        .find('[')
+        .zip(reply.rfind(']'))
+        .and_then(|(start, end)| {
+            (start < end)
+                .then(|| serde_json::from_str::<Vec<String>>(&reply[start..=end]).ok())
+                .flatten()
+        });
+    let candidates = from_json.unwrap_or_else(|| {
+        reply
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .trim_start_matches(|c: char| {
+                        c.is_ascii_digit() || matches!(c, '-' | '*' | '.' | ')')
+                    })
+                    .trim()
+                    .trim_matches('"')
+                    .to_string()
+            })
+            .collect()
+    });
+
+    let mut queries: Vec<String> = Vec::new();
+    for query in candidates.iter().map(|q| q.trim()) {
+        if !query.is_empty()
+            && !queries.iter().any(|q| q.eq_ignore_ascii_case(query))
+            && queries.len() < MAX_QUERIES
+        {
+            queries.push(query.to_string());
+        }
+    }
+    queries
+}
+
+/// A search result and the query that found it
+#[derive(Debug, Clone)]
+pub struct LabeledResult {
+    pub query: String,
+    pub result: SearchResult,
+}
+
+/// The results of all queries, in query order, keeping the first occurrence of each URL
+pub fn merge(searches: Vec<SearchResults>) -> Vec<LabeledResult> {
+    let mut merged: Vec<LabeledResult> = Vec::new();
+    for search in searches {
+        for result in search.results {
+            let url = normalize_url(&result.url);
+            if !merged.iter().any(|m| normalize_url(&m.result.url) == url) {
+                merged.push(LabeledResult {
+                    query: search.query.clone(),
+                    result,
+                });
+            }
+        }
+    }
+    merged
+}
+
+/// URL compared for duplicates: without fragment and trailing slash
+fn normalize_url(url: &str) -> &str {
+    let url = url.split('#').next().unwrap_or(url);
+    url.trim_end_matches('/')
+}
+
+/// The merged results as context for the model, each labeled with its query
+pub fn context_for_llm(results: &[LabeledResult], max_results: usize) -> String {
+    let mut context = String::new();
+    context.push_str("Web search results:\n\n");
+
+    for (i, labeled) in results.iter().take(max_results).enumerate() {
+        let result = &labeled.result;
+        context.push_str(&format!("{}. **{}**\n", i + 1, result.title));
+        context.push_str(&format!("   URL: {}\n", result.url));
+        context.push_str(&format!("   Query: {}\n", labeled.query));
+        context.push_str(&format!("   {}\n\n", result.snippet));
+    }
+
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            snippet: String::new(),
+            published_date: None,
+            author: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("Here you go:\n[\"rust 2024 edition changes\", \"Rust 2024 Edition changes\", \"\"]"),
+            vec!["rust 2024 edition changes"]
+        );
+        assert_eq!(
+            parse("1. tokio vs async-std\n2. \"tokio performance\"\n- a\n- b"),
+            vec!["tokio vs async-std", "tokio performance", "a"]
+        );
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn test_merge_labels_and_dedupes() {
+        let mut first = SearchResults::new("rust editions".to_string(), "brave".to_string());
+        first.add_result(result("https://a.com/"));
+        first.add_result(result("https://b.com"));
+        let mut second = SearchResults::new("rust 2024".to_string(), "brave".to_string());
+        second.add_result(result("https://a.com#intro"));
+        second.add_result(result("https://c.com"));
+
+        let merged = merge(vec![first, second]);
+        let labels: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|m| (m.result.url.as_str(), m.query.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("https://a.com/", "rust editions"),
+                ("https://b.com", "rust editions"),
+                ("https://c.com", "rust 2024"),
+            ]
+        );
+        assert!(context_for_llm(&merged, 5).contains("   Query: rust 2024\n"));
+    }
+}
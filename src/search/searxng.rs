@@ -0,0 +1,140 @@
+use super::{SearchProviderConfig, SearchResult, SearchResults};
+use anyhow::Result;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+    #[serde(default)]
+    number_of_results: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngResult {
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    score: Option<f64>,
+    #[serde(default, rename = "publishedDate")]
+    published_date: Option<String>,
+}
+
+/// Search endpoint of a SearXNG instance, from its base URL or the endpoint itself
+fn search_url(url: &str) -> String {
+    let base_url = url.trim_end_matches('/');
+    if base_url.ends_with("/search") {
+        base_url.to_string()
+    } else {
+        format!("{}/search", base_url)
+    }
+}
+
+pub async fn search(
+    provider_config: &SearchProviderConfig,
+    query: &str,
+    count: Option<usize>,
+) -> Result<SearchResults> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    let url = search_url(&provider_config.url);
+    let mut request = client.get(&url).query(&[("q", query), ("format", "json")]);
+
+    // Add headers (instances behind a proxy may need authentication)
+    for (name, value) in &provider_config.headers {
+        request = request.header(name, value);
+    }
+
+    let start_time = std::time::Instant::now();
+    let response = request.send().await?;
+    let search_time_ms = start_time.elapsed().as_millis() as u64;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN {
+        anyhow::bail!(
+            "SearXNG refused JSON results (403); enable them in the instance's settings.yml under search.formats: [html, json]"
+        );
+    }
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        anyhow::bail!("SearXNG search error ({}): {}", status, error_text);
+    }
+
+    let searxng_response: SearxngResponse = response.json().await?;
+    Ok(to_results(query, searxng_response, count, search_time_ms))
+}
+
+fn to_results(
+    query: &str,
+    response: SearxngResponse,
+    count: Option<usize>,
+    search_time_ms: u64,
+) -> SearchResults {
+    let mut results = SearchResults::new(query.to_string(), "SearXNG".to_string());
+    results.set_search_time(search_time_ms);
+    // Instances report 0 when their engines do not give a total
+    results.total_results = response
+        .number_of_results
+        .filter(|total| *total > 0.0)
+        .map(|total| total as u64);
+
+    for result in response.results.into_iter().take(count.unwrap_or(10)) {
+        results.add_result(SearchResult {
+            title: result.title,
+            url: result.url,
+            snippet: result.content.unwrap_or_default(),
+            published_date: result.published_date,
+            author: None,
+            score: result.score.map(|score| score as f32),
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_searxng_response_parsing() {
+        let json_response = r#"{
+            "query": "rust",
+            "number_of_results": 0,
+            "results": [
+                {
+                    "url": "https://www.rust-lang.org/",
+                    "title": "Rust Programming Language",
+                    "content": "A language empowering everyone.",
+                    "engine": "duckduckgo",
+                    "score": 4.5,
+                    "publishedDate": null
+                },
+                {"url": "https://doc.rust-lang.org/book/", "title": "The Book"}
+            ],
+            "answers": [],
+            "unresponsive_engines": []
+        }"#;
+
+        let response: SearxngResponse = serde_json::from_str(json_response).unwrap();
+        let results = to_results("rust", response, Some(1), 12);
+        assert_eq!(results.provider, "SearXNG");
+        assert_eq!(results.total_results, None);
+        assert_eq!(results.results.len(), 1);
+        assert_eq!(results.results[0].title, "Rust Programming Language");
+        assert_eq!(results.results[0].score, Some(4.5));
+
+        assert_eq!(
+            search_url("http://localhost:8080/"),
+            "http://localhost:8080/search"
+        );
+        assert_eq!(
+            search_url("https://searx.example/search"),
+            "https://searx.example/search"
+        );
+    }
+}
@@ -2,4 +2,9 @@
 pub mod mcp;
 pub mod mcp_daemon;
 pub mod proxy;
+pub mod proxy_admin;
+pub mod vector_server;
+pub mod wasm_plugins;
 pub mod webchatproxy;
+#[cfg(feature = "webui")]
+pub mod webui;
@@ -1,3 +1,4 @@
+use crate::utils::terminal::sym;
 use crate::{chat, config::Config, models_cache::ModelsCache, provider::ChatRequest};
 use anyhow::Result;
 use axum::{
@@ -9,7 +10,7 @@ use axum::{
 };
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tower_http::cors::CorsLayer;
 
 #[derive(Clone)]
@@ -20,6 +21,33 @@ pub struct ProxyState {
     pub model_filter: Option<String>,
 }
 
+/// State shared by the running proxy's handlers.
+///
+/// The management API (`/admin/*`) replaces the config in place, so changes apply
+/// to the next request without restarting the proxy.
+pub struct ProxyRuntime {
+    pub state: RwLock<ProxyState>,
+    /// Key for the management API; the API is disabled when unset
+    pub admin_key: Option<String>,
+}
+
+impl ProxyRuntime {
+    pub fn new(state: ProxyState, admin_key: Option<String>) -> Self {
+        Self {
+            state: RwLock::new(state),
+            admin_key,
+        }
+    }
+
+    /// Copy of the current state for handling one request
+    pub fn snapshot(&self) -> ProxyState {
+        match self.state.read() {
+            Ok(state) => state.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ProxyModelsQuery {
     #[serde(default)]
@@ -78,6 +106,7 @@ pub async fn start_proxy_server(
     provider_filter: Option<String>,
     model_filter: Option<String>,
     api_key: Option<String>,
+    admin_key: Option<String>,
 ) -> Result<()> {
     let config = Config::load()?;
 
@@ -91,41 +120,73 @@ pub async fn start_proxy_server(
         model_filter,
     };
 
-    let app = Router::new()
-        .route("/models", get(list_models))
-        .route("/v1/models", get(list_models))
-        .route("/chat/completions", post(chat_completions))
-        .route("/v1/chat/completions", post(chat_completions))
-        .layer(CorsLayer::permissive())
-        .with_state(Arc::new(state));
+    let admin_enabled = admin_key.is_some();
+    let app = router(Arc::new(ProxyRuntime::new(state, admin_key)));
 
     let addr = format!("{}:{}", host, port);
-    println!("{} Starting proxy server on {}", "🚀".blue(), addr.bold());
+    println!(
+        "{} Starting proxy server on {}",
+        sym("🚀").blue(),
+        addr.bold()
+    );
 
     if let Some(ref key) = final_api_key {
         println!(
             "{} Authentication enabled with API key: {}",
-            "🔐".yellow(),
+            sym("🔐").yellow(),
             key
         );
     } else {
-        println!("{} No authentication required", "⚠️".yellow());
+        println!("{} No authentication required", sym("⚠️").yellow());
+    }
+    if admin_enabled {
+        println!(
+            "{} Management API enabled under /admin (admin key required)",
+            sym("🛠️").blue()
+        );
     }
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    println!("{} Server listening on http://{}", "✓".green(), addr);
+    println!("{} Server listening on http://{}", sym("✓").green(), addr);
 
     axum::serve(listener, app).await?;
 
     Ok(())
 }
 
+/// Proxy routes, plus the management API when an admin key is configured
+pub fn router(runtime: Arc<ProxyRuntime>) -> Router {
+    let mut app = Router::new()
+        .route("/models", get(list_models))
+        .route("/v1/models", get(list_models))
+        .route("/chat/completions", post(chat_completions))
+        .route("/v1/chat/completions", post(chat_completions));
+    if runtime.admin_key.is_some() {
+        app = app.merge(super::proxy_admin::routes());
+    }
+    app.layer(CorsLayer::permissive()).with_state(runtime)
+}
+
+/// Compare a presented key with the expected one in constant time
+///
+/// Both are hashed first so the comparison doesn't leak the key's length either.
+pub(super) fn keys_match(presented: &str, expected: &str) -> bool {
+    use sha2::{Digest, Sha256};
+    let presented = Sha256::digest(presented.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    presented
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
 async fn authenticate(headers: &HeaderMap, state: &ProxyState) -> Result<(), StatusCode> {
     if let Some(expected_key) = &state.api_key {
         if let Some(auth_header) = headers.get("authorization") {
             if let Ok(auth_str) = auth_header.to_str() {
                 if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                    if token == expected_key {
+                    if keys_match(token, expected_key) {
                         return Ok(());
                     }
                 }
@@ -138,9 +199,11 @@ async fn authenticate(headers: &HeaderMap, state: &ProxyState) -> Result<(), Sta
 
 async fn list_models(
     Query(query): Query<ProxyModelsQuery>,
-    State(state): State<Arc<ProxyState>>,
+    State(runtime): State<Arc<ProxyRuntime>>,
     headers: HeaderMap,
 ) -> Result<Json<ProxyModelsResponse>, StatusCode> {
+    let state = runtime.snapshot();
+
     // Authenticate if API key is configured
     authenticate(&headers, &state).await?;
 
@@ -209,10 +272,12 @@ async fn list_models(
 }
 
 async fn chat_completions(
-    State(state): State<Arc<ProxyState>>,
+    State(runtime): State<Arc<ProxyRuntime>>,
     headers: HeaderMap,
     Json(request): Json<ProxyChatRequest>,
 ) -> Result<Json<ProxyChatResponse>, StatusCode> {
+    let state = runtime.snapshot();
+
     // Authenticate if API key is configured
     authenticate(&headers, &state).await?;
 
@@ -307,7 +372,7 @@ pub fn parse_model_string(model: &str, config: &Config) -> Result<(String, Strin
             let model_name = model_name.to_string();
 
             // Validate provider exists
-            if config.has_provider(&provider_name) {
+            if crate::provider_groups::provider_exists(config, &provider_name) {
                 return Ok((provider_name, model_name));
             }
         }
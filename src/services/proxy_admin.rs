@@ -0,0 +1,412 @@
+//! Management API for the proxy (`lc proxy --admin-key ...`)
+//!
+//! Lets the gateway's providers and keys be inspected and changed, and its usage
+//! read, while it is running. Every request needs `Authorization: Bearer <admin key>`;
+//! the regular proxy API key is not accepted. Changes are written to the lc config
+//! like the equivalent CLI commands, and key values are never returned.
+
+use super::proxy::{keys_match, ProxyRuntime};
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+    routing::{delete, get, put},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Serialize)]
+pub struct AdminProvider {
+    pub name: String,
+    pub endpoint: String,
+    pub models_path: String,
+    pub chat_path: String,
+    pub is_default: bool,
+    pub has_key: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AdminAddProvider {
+    pub name: String,
+    pub endpoint: String,
+    #[serde(default)]
+    pub models_path: Option<String>,
+    #[serde(default)]
+    pub chat_path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AdminKeyStatus {
+    pub provider: String,
+    pub configured: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AdminSetKey {
+    pub api_key: String,
+}
+
+#[derive(Deserialize)]
+pub struct AdminUsageQuery {
+    /// Only count the last N days
+    #[serde(default)]
+    pub days: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct AdminUsageEntry {
+    pub name: String,
+    pub requests: u64,
+    pub tokens: u64,
+}
+
+#[derive(Serialize)]
+pub struct AdminUsage {
+    pub total_requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub providers: Vec<AdminUsageEntry>,
+    pub models: Vec<AdminUsageEntry>,
+}
+
+#[derive(Serialize)]
+pub struct AdminError {
+    pub error: String,
+}
+
+type AdminResult<T> = Result<T, (StatusCode, Json<AdminError>)>;
+
+fn error(status: StatusCode, message: impl std::fmt::Display) -> (StatusCode, Json<AdminError>) {
+    (
+        status,
+        Json(AdminError {
+            error: message.to_string(),
+        }),
+    )
+}
+
+pub fn routes() -> Router<Arc<ProxyRuntime>> {
+    Router::new()
+        .route("/admin/providers", get(list_providers).post(add_provider))
+        .route("/admin/providers/:name", delete(remove_provider))
+        .route("/admin/keys", get(list_keys))
+        .route("/admin/keys/:provider", put(set_key).delete(remove_key))
+        .route("/admin/usage", get(usage))
+}
+
+fn authenticate_admin(headers: &HeaderMap, runtime: &ProxyRuntime) -> AdminResult<()> {
+    let Some(expected) = &runtime.admin_key else {
+        return Err(error(StatusCode::NOT_FOUND, "Management API is disabled"));
+    };
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token {
+        Some(token) if keys_match(token, expected) => Ok(()),
+        _ => Err(error(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or missing admin key",
+        )),
+    }
+}
+
+async fn list_providers(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+) -> AdminResult<Json<Vec<AdminProvider>>> {
+    authenticate_admin(&headers, &runtime)?;
+    let config = runtime.snapshot().config;
+    let keys = crate::keys::KeysConfig::load().unwrap_or_default();
+
+    let mut providers: Vec<AdminProvider> = config
+        .providers
+        .iter()
+        .map(|(name, provider)| AdminProvider {
+            name: name.clone(),
+            endpoint: provider.endpoint.clone(),
+            models_path: provider.models_path.clone(),
+            chat_path: provider.chat_path.clone(),
+            is_default: config.default_provider.as_deref() == Some(name.as_str()),
+            has_key: keys.has_auth(name),
+        })
+        .collect();
+    providers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(providers))
+}
+
+async fn add_provider(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+    Json(request): Json<AdminAddProvider>,
+) -> AdminResult<StatusCode> {
+    authenticate_admin(&headers, &runtime)?;
+    if request.name.trim().is_empty() || request.endpoint.trim().is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Provider name and endpoint are required",
+        ));
+    }
+    if !is_valid_provider_name(&request.name) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid provider name '{}': use only letters, digits, '-' and '_'",
+                request.name
+            ),
+        ));
+    }
+
+    let mut state = runtime
+        .state
+        .write()
+        .map_err(|_| error(StatusCode::INTERNAL_SERVER_ERROR, "Proxy state unavailable"))?;
+    if state.config.has_provider(&request.name) {
+        return Err(error(
+            StatusCode::CONFLICT,
+            format!("Provider '{}' already exists", request.name),
+        ));
+    }
+    state
+        .config
+        .add_provider_with_paths(
+            request.name.clone(),
+            request.endpoint,
+            request.models_path,
+            request.chat_path,
+        )
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    crate::debug_log!("Admin API added provider '{}'", request.name);
+    Ok(StatusCode::CREATED)
+}
+
+/// Provider names become file names in the providers directory, so they can't hold paths
+fn is_valid_provider_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+async fn remove_provider(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> AdminResult<StatusCode> {
+    authenticate_admin(&headers, &runtime)?;
+
+    let mut state = runtime
+        .state
+        .write()
+        .map_err(|_| error(StatusCode::INTERNAL_SERVER_ERROR, "Proxy state unavailable"))?;
+    if !state.config.has_provider(&name) {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("Provider '{}' not found", name),
+        ));
+    }
+
+    // Same as 'lc providers remove': the file goes to the trash for 'lc undo'
+    let removed = (|| -> anyhow::Result<()> {
+        crate::trash::Trash::new()?.move_files(
+            &format!("providers remove {}", name),
+            &[crate::config::Config::provider_file_path(&name)?],
+        )?;
+        state.config.providers.remove(&name);
+        state.config.save()
+    })();
+    removed.map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    crate::debug_log!("Admin API removed provider '{}'", name);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_keys(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+) -> AdminResult<Json<Vec<AdminKeyStatus>>> {
+    authenticate_admin(&headers, &runtime)?;
+    let config = runtime.snapshot().config;
+    let keys =
+        crate::keys::KeysConfig::load().map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let mut statuses: Vec<AdminKeyStatus> = config
+        .providers
+        .keys()
+        .map(|name| AdminKeyStatus {
+            provider: name.clone(),
+            configured: keys.has_auth(name),
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.provider.cmp(&b.provider));
+    Ok(Json(statuses))
+}
+
+async fn set_key(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+    Path(provider): Path<String>,
+    Json(request): Json<AdminSetKey>,
+) -> AdminResult<StatusCode> {
+    authenticate_admin(&headers, &runtime)?;
+    if request.api_key.trim().is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "api_key is required"));
+    }
+
+    let mut state = runtime
+        .state
+        .write()
+        .map_err(|_| error(StatusCode::INTERNAL_SERVER_ERROR, "Proxy state unavailable"))?;
+    if !state.config.has_provider(&provider) {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("Provider '{}' not found", provider),
+        ));
+    }
+    state
+        .config
+        .set_api_key(provider.clone(), request.api_key)
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    crate::debug_log!("Admin API set the API key for '{}'", provider);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn remove_key(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+    Path(provider): Path<String>,
+) -> AdminResult<StatusCode> {
+    authenticate_admin(&headers, &runtime)?;
+
+    let mut keys =
+        crate::keys::KeysConfig::load().map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let removed = keys
+        .remove_api_key(&provider)
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if !removed {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("No API key stored for '{}'", provider),
+        ));
+    }
+
+    crate::debug_log!("Admin API removed the API key for '{}'", provider);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn usage(
+    State(runtime): State<Arc<ProxyRuntime>>,
+    headers: HeaderMap,
+    Query(query): Query<AdminUsageQuery>,
+) -> AdminResult<Json<AdminUsage>> {
+    authenticate_admin(&headers, &runtime)?;
+    let stats = crate::usage_stats::UsageAnalyzer::new()
+        .and_then(|analyzer| analyzer.get_usage_stats(query.days))
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let entries = |usage: &[(String, u64, u64)]| {
+        usage
+            .iter()
+            .map(|(name, requests, tokens)| AdminUsageEntry {
+                name: name.clone(),
+                requests: *requests,
+                tokens: *tokens,
+            })
+            .collect()
+    };
+
+    Ok(Json(AdminUsage {
+        total_requests: stats.total_requests,
+        input_tokens: stats.input_tokens,
+        output_tokens: stats.output_tokens,
+        total_tokens: stats.total_tokens,
+        providers: entries(&stats.provider_usage),
+        models: entries(&stats.model_usage),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::proxy::{router, ProxyState};
+    use super::*;
+
+    async fn serve(admin_key: Option<&str>) -> String {
+        let state = ProxyState {
+            config: crate::config::Config::default(),
+            api_key: Some("proxy-key".to_string()),
+            provider_filter: None,
+            model_filter: None,
+        };
+        let runtime = Arc::new(ProxyRuntime::new(state, admin_key.map(String::from)));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router(runtime)).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_provider_names() {
+        assert!(is_valid_provider_name("open-router_2"));
+        assert!(!is_valid_provider_name("../evil"));
+        assert!(!is_valid_provider_name("a/b"));
+        assert!(!is_valid_provider_name(""));
+    }
+
+    #[test]
+    fn test_keys_match() {
+        assert!(keys_match("admin-key", "admin-key"));
+        assert!(!keys_match("admin-ke", "admin-key"));
+        assert!(!keys_match("admin-key-2", "admin-key"));
+        assert!(!keys_match("", "admin-key"));
+    }
+
+    #[tokio::test]
+    async fn test_admin_key_is_required() {
+        let base = serve(Some("admin-key")).await;
+        let client = reqwest::Client::new();
+
+        let missing = client
+            .get(format!("{}/admin/providers", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // The proxy's own API key doesn't grant admin access
+        let proxy_key = client
+            .get(format!("{}/admin/providers", base))
+            .bearer_auth("proxy-key")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(proxy_key.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let ok = client
+            .get(format!("{}/admin/providers", base))
+            .bearer_auth("admin-key")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(ok.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = ok.json().await.unwrap();
+        assert_eq!(body, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_absent_without_admin_key() {
+        let base = serve(None).await;
+        let response = reqwest::Client::new()
+            .get(format!("{}/admin/providers", base))
+            .bearer_auth("anything")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,526 @@
+//! Retrieval API over a vector database (`lc vectors serve`)
+//!
+//! Lets editors, bots and retrieval plugins search a knowledge base built with
+//! `lc embed`, and add to it, over HTTP. Requests and responses follow the shape of
+//! the common retrieval plugin API:
+//!
+//! - `POST /query` `{"queries": [{"query": "...", "top_k": 3, "filter": {"tags": ["..."]}}]}`
+//! - `POST /add` (or `/upsert`) `{"documents": [{"text": "...", "metadata": {"source": "..."}}]}`
+//! - `POST /delete` `{"ids": ["12"]}`
+//!
+//! Queries and new documents are embedded with the database's model. When an API key
+//! is set, every request needs `Authorization: Bearer <key>`.
+
+use crate::chat::LLMClient;
+use crate::data::vector_db::{VectorDatabase, VectorEntry};
+use crate::provider::EmbeddingRequest;
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    response::Json,
+    routing::post,
+    Router,
+};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tower_http::cors::CorsLayer;
+
+/// Results per query when `top_k` is not given
+const DEFAULT_TOP_K: usize = 3;
+/// Most results per query
+const MAX_TOP_K: usize = 100;
+
+pub struct VectorServerState {
+    pub db: VectorDatabase,
+    pub client: LLMClient,
+    /// Embedding model and its provider
+    pub model: String,
+    pub provider: String,
+    pub api_key: Option<String>,
+    /// Origins allowed to call the API from a browser; without any, no CORS headers are sent
+    pub cors_origins: Vec<HeaderValue>,
+}
+
+#[derive(Deserialize)]
+pub struct QueryRequest {
+    pub queries: Vec<Query>,
+}
+
+#[derive(Deserialize)]
+pub struct Query {
+    pub query: String,
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    #[serde(default)]
+    pub filter: Option<QueryFilter>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct QueryFilter {
+    /// Entries must carry all of these tags (see `lc embed --tag-model`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Entries embedded from this file
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct QueryResponse {
+    pub results: Vec<QueryResult>,
+}
+
+#[derive(Serialize)]
+pub struct QueryResult {
+    pub query: String,
+    pub results: Vec<DocumentResult>,
+}
+
+#[derive(Serialize)]
+pub struct DocumentResult {
+    pub id: String,
+    pub text: String,
+    pub score: f64,
+    pub metadata: DocumentMetadata,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct DocumentMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_index: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_chunks: Option<i32>,
+    /// Line of `source` the chunk starts on, from 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Byte offset of the chunk in `source`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// Type of an image entry, whose `source` is the image file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AddRequest {
+    pub documents: Vec<Document>,
+}
+
+#[derive(Deserialize)]
+pub struct Document {
+    pub text: String,
+    #[serde(default)]
+    pub metadata: DocumentMetadata,
+}
+
+#[derive(Serialize)]
+pub struct AddResponse {
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct DeleteResponse {
+    pub success: bool,
+    pub deleted: usize,
+}
+
+#[derive(Serialize)]
+pub struct ApiError {
+    pub error: String,
+}
+
+type ApiResult<T> = Result<T, (StatusCode, Json<ApiError>)>;
+
+fn error(status: StatusCode, message: impl std::fmt::Display) -> (StatusCode, Json<ApiError>) {
+    (
+        status,
+        Json(ApiError {
+            error: message.to_string(),
+        }),
+    )
+}
+
+pub async fn start_vector_server(
+    state: VectorServerState,
+    name: &str,
+    host: String,
+    port: u16,
+) -> Result<()> {
+    let auth = state.api_key.is_some();
+    let embedding_model = format!("{}:{}", state.provider, state.model);
+    let app = router(Arc::new(state));
+
+    let addr = format!("{}:{}", host, port);
+    println!(
+        "{} Serving vector database '{}' on {}",
+        sym("🚀").blue(),
+        name.bold(),
+        addr.bold()
+    );
+    println!("{} Embedding model: {}", sym("📊").blue(), embedding_model);
+    if auth {
+        println!("{} Authentication enabled", sym("🔐").yellow());
+    } else {
+        println!("{} No authentication required", sym("⚠️").yellow());
+    }
+
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    println!("{} Server listening on http://{}", sym("✓").green(), addr);
+    println!("   POST /query, /add, /delete");
+
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+pub fn router(state: Arc<VectorServerState>) -> Router {
+    let mut app = Router::new()
+        .route("/query", post(query))
+        .route("/add", post(add))
+        .route("/upsert", post(add))
+        .route("/delete", post(delete));
+    if !state.cors_origins.is_empty() {
+        app = app.layer(
+            CorsLayer::new()
+                .allow_origin(state.cors_origins.clone())
+                .allow_methods([Method::POST])
+                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]),
+        );
+    }
+    app.with_state(state)
+}
+
+fn authenticate(headers: &HeaderMap, state: &VectorServerState) -> ApiResult<()> {
+    let Some(expected) = &state.api_key else {
+        return Ok(());
+    };
+    let token = headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if token == Some(expected.as_str()) {
+        Ok(())
+    } else {
+        Err(error(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or missing API key",
+        ))
+    }
+}
+
+async fn embed(state: &VectorServerState, text: &str) -> ApiResult<Vec<f64>> {
+    let request = EmbeddingRequest {
+        model: state.model.clone(),
+        input: text.to_string(),
+        encoding_format: Some("float".to_string()),
+    };
+    let response = state
+        .client
+        .embeddings(&request)
+        .await
+        .map_err(|e| error(StatusCode::BAD_GATEWAY, e))?;
+    response
+        .data
+        .into_iter()
+        .next()
+        .map(|data| data.embedding)
+        .ok_or_else(|| error(StatusCode::BAD_GATEWAY, "No embedding data in response"))
+}
+
+async fn query(
+    State(state): State<Arc<VectorServerState>>,
+    headers: HeaderMap,
+    Json(request): Json<QueryRequest>,
+) -> ApiResult<Json<QueryResponse>> {
+    authenticate(&headers, &state)?;
+
+    let mut results = Vec::with_capacity(request.queries.len());
+    for query in request.queries {
+        let top_k = query.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+        let filter = query.filter.unwrap_or_default();
+        let tags: Vec<String> = filter
+            .tags
+            .iter()
+            .map(|tag| crate::core::auto_tag::normalize_tag(tag))
+            .collect();
+
+        let vector = embed(&state, &query.query).await?;
+        // Filtering by source needs every candidate, the other searches stop at top_k
+        let limit = match filter.source {
+            Some(_) => state
+                .db
+                .count()
+                .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?,
+            None => top_k,
+        };
+        let matches = state
+            .db
+            .find_similar_tagged(&vector, limit, &tags)
+            .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+        results.push(QueryResult {
+            query: query.query,
+            results: matches
+                .into_iter()
+                .filter(|(entry, _)| {
+                    filter
+                        .source
+                        .as_ref()
+                        .is_none_or(|source| entry.file_path.as_ref() == Some(source))
+                })
+                .take(top_k)
+                .map(|(entry, score)| document_result(entry, score))
+                .collect(),
+        });
+    }
+    Ok(Json(QueryResponse { results }))
+}
+
+fn document_result(entry: VectorEntry, score: f64) -> DocumentResult {
+    DocumentResult {
+        id: entry.id.to_string(),
+        text: entry.text,
+        score,
+        metadata: DocumentMetadata {
+            source: entry.file_path,
+            chunk_index: entry.chunk_index,
+            total_chunks: entry.total_chunks,
+            line: entry.chunk_line.map(|line| line as usize),
+            offset: entry.chunk_offset.map(|offset| offset as usize),
+            tags: entry.tags,
+            summary: entry.summary,
+            created_at: Some(entry.created_at.to_rfc3339()),
+            mime_type: entry.mime_type,
+        },
+    }
+}
+
+async fn add(
+    State(state): State<Arc<VectorServerState>>,
+    headers: HeaderMap,
+    Json(request): Json<AddRequest>,
+) -> ApiResult<Json<AddResponse>> {
+    authenticate(&headers, &state)?;
+    if request
+        .documents
+        .iter()
+        .any(|doc| doc.text.trim().is_empty())
+    {
+        return Err(error(StatusCode::BAD_REQUEST, "Documents need a text"));
+    }
+
+    let mut ids = Vec::with_capacity(request.documents.len());
+    for document in request.documents {
+        let vector = embed(&state, &document.text).await?;
+        let metadata = document.metadata;
+        let id = state
+            .db
+            .add_vector_with_metadata(
+                &document.text,
+                &vector,
+                &state.model,
+                &state.provider,
+                metadata.source.as_deref(),
+                metadata.chunk_index,
+                metadata.total_chunks,
+            )
+            .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        let tags: Vec<String> = metadata
+            .tags
+            .iter()
+            .map(|tag| crate::core::auto_tag::normalize_tag(tag))
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        if let (Some(offset), Some(line)) = (metadata.offset, metadata.line) {
+            state
+                .db
+                .set_chunk_position(id, offset, line)
+                .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        }
+        if !tags.is_empty() || metadata.summary.is_some() {
+            state
+                .db
+                .set_annotations(id, &tags, metadata.summary.as_deref())
+                .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        }
+        crate::debug_log!("Vector server added entry {}", id);
+        ids.push(id.to_string());
+    }
+    Ok(Json(AddResponse { ids }))
+}
+
+async fn delete(
+    State(state): State<Arc<VectorServerState>>,
+    headers: HeaderMap,
+    Json(request): Json<DeleteRequest>,
+) -> ApiResult<Json<DeleteResponse>> {
+    authenticate(&headers, &state)?;
+    let ids = request
+        .ids
+        .iter()
+        .map(|id| id.trim().parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| error(StatusCode::BAD_REQUEST, "Ids must be numbers"))?;
+    let deleted = state
+        .db
+        .delete_vectors(&ids)
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    crate::debug_log!("Vector server deleted {} entries", deleted);
+    Ok(Json(DeleteResponse {
+        success: true,
+        deleted,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    async fn serve(dir: &std::path::Path, api_key: Option<&str>, cors_origins: &[&str]) -> String {
+        let state = VectorServerState {
+            db: VectorDatabase::open_in(dir, "kb").unwrap(),
+            client: LLMClient::new_with_headers(
+                crate::core::mock_provider::ENDPOINT.to_string(),
+                String::new(),
+                "/models".to_string(),
+                "/chat/completions".to_string(),
+                HashMap::new(),
+            ),
+            model: "mock-embedding".to_string(),
+            provider: "mock".to_string(),
+            api_key: api_key.map(String::from),
+            cors_origins: cors_origins
+                .iter()
+                .map(|origin| HeaderValue::from_str(origin).unwrap())
+                .collect(),
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router(Arc::new(state)))
+                .await
+                .unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_add_query_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = serve(dir.path(), Some("kb-key"), &[]).await;
+        let client = reqwest::Client::new();
+
+        let unauthorized = client
+            .post(format!("{}/query", base))
+            .json(&json!({"queries": [{"query": "tokio"}]}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let added: serde_json::Value = client
+            .post(format!("{}/add", base))
+            .bearer_auth("kb-key")
+            .json(&json!({"documents": [
+                {"text": "tokio runs async tasks", "metadata": {"source": "rust.md", "tags": ["Rust"]}},
+                {"text": "asyncio runs coroutines", "metadata": {"source": "python.md"}}
+            ]}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let ids = added["ids"].as_array().unwrap();
+        assert_eq!(ids.len(), 2);
+
+        // The mock gives equal texts equal embeddings, so the exact text scores highest
+        let found: serde_json::Value = client
+            .post(format!("{}/query", base))
+            .bearer_auth("kb-key")
+            .json(&json!({"queries": [
+                {"query": "tokio runs async tasks", "top_k": 1},
+                {"query": "tokio runs async tasks", "filter": {"source": "python.md"}},
+                {"query": "anything", "filter": {"tags": ["rust"]}}
+            ]}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let results = found["results"].as_array().unwrap();
+        assert_eq!(results[0]["results"][0]["id"], ids[0]);
+        assert_eq!(results[0]["results"][0]["metadata"]["source"], "rust.md");
+        assert_eq!(results[1]["results"].as_array().unwrap().len(), 1);
+        assert_eq!(results[1]["results"][0]["id"], ids[1]);
+        assert_eq!(results[2]["results"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            results[2]["results"][0]["metadata"]["tags"],
+            json!(["rust"])
+        );
+
+        let deleted: serde_json::Value = client
+            .post(format!("{}/delete", base))
+            .bearer_auth("kb-key")
+            .json(&json!({"ids": [ids[0]]}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(deleted["deleted"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_cors_only_for_configured_origins() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = reqwest::Client::new();
+        let preflight = |base: String, origin: &'static str| {
+            client
+                .request(reqwest::Method::OPTIONS, format!("{}/query", base))
+                .header("Origin", origin)
+                .header("Access-Control-Request-Method", "POST")
+                .send()
+        };
+        let allow_origin = |response: reqwest::Response| {
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .map(|value| value.to_str().unwrap().to_string())
+        };
+
+        let base = serve(dir.path(), Some("kb-key"), &["https://app.example"]).await;
+        let allowed = preflight(base.clone(), "https://app.example")
+            .await
+            .unwrap();
+        assert_eq!(
+            allow_origin(allowed).as_deref(),
+            Some("https://app.example")
+        );
+        let other = preflight(base, "https://evil.example").await.unwrap();
+        assert_eq!(allow_origin(other), None);
+
+        let base = serve(dir.path(), Some("kb-key"), &[]).await;
+        let none = preflight(base, "https://app.example").await.unwrap();
+        assert_eq!(allow_origin(none), None);
+    }
+}
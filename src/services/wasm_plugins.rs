@@ -0,0 +1,409 @@
+//! WASM plugins: custom file readers and response post-processors
+//!
+//! `.wasm` modules in the `plugins/` directory of the config dir are loaded when lc is
+//! built with the `wasm-plugins` feature. They run sandboxed: modules get no host
+//! imports (no files, network or clock), a bounded amount of memory and a fuel budget
+//! per call, and each call runs in a fresh instance.
+//!
+//! A plugin exports `memory` and `lc_alloc(size: i32) -> i32`, which lc uses to pass
+//! input into the module, plus one or both of these hooks:
+//!
+//! - `lc_extensions() -> i64` and `lc_read(ptr: i32, len: i32) -> i64`: a file reader for
+//!   the comma-separated extensions returned by `lc_extensions`. `lc_read` receives the
+//!   file's bytes and returns its text.
+//! - `lc_post_process(ptr: i32, len: i32) -> i64`: receives a model response and returns
+//!   the text to show and log instead. Post-processors run in file name order.
+//!
+//! Strings returned to lc are UTF-8, packed as `(ptr << 32) | len`. A negative return
+//! value means the plugin failed.
+
+use crate::readers::FileReader;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Directory scanned for `.wasm` plugins
+pub fn plugins_dir() -> Result<PathBuf> {
+    Ok(crate::config::Config::config_dir()?.join("plugins"))
+}
+
+/// The `.wasm` files in the plugins directory, sorted by name
+pub fn installed() -> Result<Vec<PathBuf>> {
+    let dir = plugins_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// What an installed plugin provides (e.g. "reader for csv, tsv"), or why it is not loaded
+pub fn describe(path: &std::path::Path) -> Result<String, String> {
+    #[cfg(feature = "wasm-plugins")]
+    {
+        let runtime = runtime::get();
+        if let Some(plugin) = runtime.plugins.iter().find(|plugin| plugin.path == path) {
+            return Ok(plugin.capabilities());
+        }
+        Err(runtime
+            .errors
+            .iter()
+            .find(|(failed, _)| failed == path)
+            .map(|(_, error)| error.clone())
+            .unwrap_or_else(|| "not loaded".to_string()))
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    {
+        let _ = path;
+        Err("lc was built without the wasm-plugins feature".to_string())
+    }
+}
+
+/// A reader provided by a plugin for files with this extension
+pub fn reader_for_extension(extension: &str) -> Option<Box<dyn FileReader>> {
+    #[cfg(feature = "wasm-plugins")]
+    {
+        runtime::get()
+            .plugins
+            .iter()
+            .find(|plugin| plugin.can_read(extension))
+            .map(|plugin| Box::new(runtime::WasmReader(plugin)) as Box<dyn FileReader>)
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    {
+        let _ = extension;
+        None
+    }
+}
+
+/// Whether any plugin post-processes responses. Streamed responses can't be, so
+/// direct prompts are not streamed when this is the case.
+pub fn has_post_processors() -> bool {
+    #[cfg(feature = "wasm-plugins")]
+    {
+        runtime::get()
+            .plugins
+            .iter()
+            .any(|plugin| plugin.post_processes)
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    {
+        false
+    }
+}
+
+/// Run a response through the post-processing plugins. A failing plugin is reported on
+/// stderr and skipped.
+pub fn post_process(response: String) -> String {
+    #[cfg(feature = "wasm-plugins")]
+    {
+        let runtime = runtime::get();
+        let mut response = response;
+        for plugin in runtime
+            .plugins
+            .iter()
+            .filter(|plugin| plugin.post_processes)
+        {
+            match plugin.call("lc_post_process", response.as_bytes()) {
+                Ok(processed) => response = processed,
+                Err(e) => {
+                    crate::utils::spinner::clear();
+                    eprintln!("Warning: {}", e)
+                }
+            }
+        }
+        response
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    {
+        response
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+mod runtime {
+    use super::FileReader;
+    use anyhow::{Context, Result};
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+    use wasmtime::{Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+    /// Upper bound of a plugin's linear memory
+    const MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+    /// Instructions (roughly) a single plugin call may execute
+    const FUEL_PER_CALL: u64 = if cfg!(test) {
+        10_000_000
+    } else {
+        5_000_000_000
+    };
+
+    pub struct Runtime {
+        pub plugins: Vec<WasmPlugin>,
+        pub errors: Vec<(PathBuf, String)>,
+    }
+
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+    /// The plugins, loaded on first use
+    pub fn get() -> &'static Runtime {
+        RUNTIME.get_or_init(load)
+    }
+
+    fn load() -> Runtime {
+        let mut runtime = Runtime {
+            plugins: Vec::new(),
+            errors: Vec::new(),
+        };
+        let paths = match super::installed() {
+            Ok(paths) if !paths.is_empty() => paths,
+            _ => return runtime,
+        };
+        let engine = match engine() {
+            Ok(engine) => engine,
+            Err(e) => {
+                crate::utils::spinner::clear();
+                eprintln!("Warning: WASM plugins unavailable: {}", e);
+                return runtime;
+            }
+        };
+
+        for path in paths {
+            let loaded = std::fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| WasmPlugin::compile(&engine, path.clone(), &bytes));
+            match loaded {
+                Ok(plugin) => {
+                    crate::debug_log!(
+                        "Loaded WASM plugin {}: {}",
+                        plugin.name,
+                        plugin.capabilities()
+                    );
+                    runtime.plugins.push(plugin)
+                }
+                Err(e) => {
+                    crate::utils::spinner::clear();
+                    eprintln!(
+                        "Warning: Skipping WASM plugin '{}': {:#}",
+                        path.display(),
+                        e
+                    );
+                    runtime.errors.push((path, format!("{:#}", e)));
+                }
+            }
+        }
+        runtime
+    }
+
+    pub fn engine() -> Result<Engine> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config)
+    }
+
+    pub struct WasmPlugin {
+        pub name: String,
+        pub path: PathBuf,
+        engine: Engine,
+        module: Module,
+        pub extensions: Vec<String>,
+        pub post_processes: bool,
+    }
+
+    impl WasmPlugin {
+        pub fn compile(engine: &Engine, path: PathBuf, bytes: &[u8]) -> Result<Self> {
+            let module = Module::new(engine, bytes)?;
+            if let Some(import) = module.imports().next() {
+                anyhow::bail!(
+                    "imports '{}::{}', but plugins run without access to the host",
+                    import.module(),
+                    import.name()
+                );
+            }
+            let exports: Vec<String> = module
+                .exports()
+                .map(|export| export.name().to_string())
+                .collect();
+            let exports_hook = |name: &str| exports.iter().any(|export| export == name);
+            for required in ["memory", "lc_alloc"] {
+                if !exports_hook(required) {
+                    anyhow::bail!("does not export '{}'", required);
+                }
+            }
+
+            let mut plugin = Self {
+                name: path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or("plugin")
+                    .to_string(),
+                path,
+                engine: engine.clone(),
+                module,
+                extensions: Vec::new(),
+                post_processes: exports_hook("lc_post_process"),
+            };
+            if exports_hook("lc_read") {
+                plugin.extensions = plugin
+                    .call("lc_extensions", &[])?
+                    .split(',')
+                    .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+                    .filter(|extension| !extension.is_empty())
+                    .collect();
+            }
+            if plugin.extensions.is_empty() && !plugin.post_processes {
+                anyhow::bail!("exports neither lc_read with lc_extensions nor lc_post_process");
+            }
+            Ok(plugin)
+        }
+
+        pub fn can_read(&self, extension: &str) -> bool {
+            self.extensions
+                .iter()
+                .any(|handled| handled.eq_ignore_ascii_case(extension))
+        }
+
+        pub fn capabilities(&self) -> String {
+            let mut capabilities = Vec::new();
+            if !self.extensions.is_empty() {
+                capabilities.push(format!("reader for {}", self.extensions.join(", ")));
+            }
+            if self.post_processes {
+                capabilities.push("response post-processor".to_string());
+            }
+            capabilities.join(", ")
+        }
+
+        /// Call an exported hook in a fresh, sandboxed instance. `lc_extensions` takes no
+        /// input; the other hooks receive `input` copied into the module's memory.
+        pub fn call(&self, export: &str, input: &[u8]) -> Result<String> {
+            self.call_instance(export, input)
+                .with_context(|| format!("WASM plugin '{}' failed in {}", self.name, export))
+        }
+
+        fn call_instance(&self, export: &str, input: &[u8]) -> Result<String> {
+            let limits = StoreLimitsBuilder::new()
+                .memory_size(MAX_MEMORY_BYTES)
+                .build();
+            let mut store: Store<StoreLimits> = Store::new(&self.engine, limits);
+            store.limiter(|limits| limits);
+            store.set_fuel(FUEL_PER_CALL)?;
+
+            let instance = Instance::new(&mut store, &self.module, &[])?;
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .context("no exported memory")?;
+
+            let packed = if export == "lc_extensions" {
+                instance
+                    .get_typed_func::<(), i64>(&mut store, export)?
+                    .call(&mut store, ())?
+            } else {
+                let len = i32::try_from(input.len()).context("input too large")?;
+                let ptr = instance
+                    .get_typed_func::<i32, i32>(&mut store, "lc_alloc")?
+                    .call(&mut store, len)?;
+                memory.write(&mut store, ptr as u32 as usize, input)?;
+                instance
+                    .get_typed_func::<(i32, i32), i64>(&mut store, export)?
+                    .call(&mut store, (ptr, len))?
+            };
+
+            if packed < 0 {
+                anyhow::bail!("returned an error");
+            }
+            let ptr = (packed >> 32) as usize;
+            let len = (packed & 0xffff_ffff) as usize;
+            let mut output = vec![0; len];
+            memory.read(&store, ptr, &mut output)?;
+            String::from_utf8(output).context("returned invalid UTF-8")
+        }
+    }
+
+    /// A plugin's `lc_read` hook as a file reader
+    pub struct WasmReader(pub &'static WasmPlugin);
+
+    impl FileReader for WasmReader {
+        fn read_as_text(&self, file_path: &str) -> Result<String> {
+            let bytes = std::fs::read(Path::new(file_path))
+                .with_context(|| format!("Failed to read file '{}'", file_path))?;
+            self.read_as_text_from_bytes(&bytes)
+        }
+
+        fn read_as_text_from_bytes(&self, bytes: &[u8]) -> Result<String> {
+            self.0.call("lc_read", bytes)
+        }
+
+        fn can_handle(&self, extension: &str) -> bool {
+            self.0.can_read(extension)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const PLUGIN: &str = r#"
+            (module
+              (memory (export "memory") 1)
+              (global $next (mut i32) (i32.const 1024))
+              (data (i32.const 0) "demo, .Log")
+              (data (i32.const 16) "text from wasm")
+              (func (export "lc_alloc") (param $size i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $next))
+                (global.set $next (i32.add (global.get $next) (local.get $size)))
+                (local.get $ptr))
+              (func (export "lc_extensions") (result i64)
+                (i64.const 10))
+              (func (export "lc_read") (param i32 i32) (result i64)
+                (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 14)))
+              ;; Drops the first byte of the response
+              (func (export "lc_post_process") (param $ptr i32) (param $len i32) (result i64)
+                (i64.or
+                  (i64.shl (i64.extend_i32_u (i32.add (local.get $ptr) (i32.const 1))) (i64.const 32))
+                  (i64.extend_i32_u (i32.sub (local.get $len) (i32.const 1))))))
+        "#;
+
+        #[test]
+        fn test_plugin_hooks_and_sandbox() {
+            let engine = engine().unwrap();
+            let plugin =
+                WasmPlugin::compile(&engine, PathBuf::from("demo.wasm"), PLUGIN.as_bytes())
+                    .unwrap();
+            assert_eq!(plugin.name, "demo");
+            assert_eq!(plugin.extensions, vec!["demo", "log"]);
+            assert!(plugin.can_read("LOG") && plugin.post_processes);
+            assert_eq!(
+                plugin.call("lc_read", b"\x00\x01").unwrap(),
+                "text from wasm"
+            );
+            assert_eq!(plugin.call("lc_post_process", b"xHello").unwrap(), "Hello");
+
+            let importing = r#"(module (import "env" "f" (func)) (memory (export "memory") 1))"#;
+            let error = WasmPlugin::compile(&engine, PathBuf::from("x.wasm"), importing.as_bytes())
+                .err()
+                .unwrap();
+            assert!(error.to_string().contains("imports 'env::f'"));
+
+            // Runs out of fuel instead of hanging
+            let looping = r#"
+                (module
+                  (memory (export "memory") 1)
+                  (func (export "lc_alloc") (param i32) (result i32) (i32.const 0))
+                  (func (export "lc_extensions") (result i64) (loop $l (br $l)) (i64.const 0))
+                  (func (export "lc_read") (param i32 i32) (result i64) (i64.const 0)))
+            "#;
+            assert!(
+                WasmPlugin::compile(&engine, PathBuf::from("loop.wasm"), looping.as_bytes())
+                    .is_err()
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use axum::{
     extract::State,
@@ -54,30 +55,7 @@ impl WebChatProxyConfig {
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_file_path()?;
         let content = toml::to_string_pretty(self)?;
-
-        let mut options = std::fs::OpenOptions::new();
-        options.write(true).create(true).truncate(true);
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.mode(0o600);
-        }
-
-        let mut file = options.open(&config_path)?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut permissions = file.metadata()?.permissions();
-            permissions.set_mode(0o600);
-            file.set_permissions(permissions)?;
-        }
-
-        use std::io::Write;
-        file.write_all(content.as_bytes())?;
-
-        Ok(())
+        crate::lock::write_config_file(&config_path, &content)
     }
 
     pub fn set_provider_auth(&mut self, provider: &str, auth_token: &str) -> Result<()> {
@@ -261,30 +239,7 @@ impl DaemonRegistry {
         }
 
         let content = toml::to_string_pretty(self)?;
-
-        let mut options = std::fs::OpenOptions::new();
-        options.write(true).create(true).truncate(true);
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::OpenOptionsExt;
-            options.mode(0o600);
-        }
-
-        let mut file = options.open(&registry_path)?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut permissions = file.metadata()?.permissions();
-            permissions.set_mode(0o600);
-            file.set_permissions(permissions)?;
-        }
-
-        use std::io::Write;
-        file.write_all(content.as_bytes())?;
-
-        Ok(())
+        crate::lock::write_config_file(&registry_path, &content)
     }
 
     pub fn add_daemon(&mut self, provider: String, info: DaemonInfo) {
@@ -338,12 +293,12 @@ pub async fn start_webchatproxy_server(
     let addr = format!("{}:{}", host, port);
     println!(
         "{} Starting webchatproxy server on {}",
-        "🚀".blue(),
+        sym("🚀").blue(),
         addr.bold()
     );
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    println!("{} Server listening on http://{}", "✓".green(), addr);
+    println!("{} Server listening on http://{}", sym("✓").green(), addr);
 
     axum::serve(listener, app).await?;
 
@@ -374,20 +329,21 @@ async fn chat_completions(
     Json(request): Json<ChatCompletionRequest>,
 ) -> Result<Json<ChatCompletionResponse>, StatusCode> {
     println!(
-        "🔄 Received chat completion request for provider: {}",
+        "{} Received chat completion request for provider: {}",
+        sym("🔄"),
         state.provider
     );
 
     // Authenticate if API key is configured
     if let Err(e) = authenticate(&headers, &state).await {
-        println!("❌ Authentication failed");
+        println!("{} Authentication failed", sym("❌"));
         return Err(e);
     }
 
     match state.provider.as_str() {
         "kagi" => handle_kagi_request(&state, request).await,
         _ => {
-            println!("❌ Unsupported provider: {}", state.provider);
+            println!("{} Unsupported provider: {}", sym("❌"), state.provider);
             Err(StatusCode::BAD_REQUEST)
         }
     }
@@ -399,20 +355,21 @@ async fn list_models(
     headers: HeaderMap,
 ) -> Result<Json<ModelsListResponse>, StatusCode> {
     println!(
-        "🔄 Received models list request for provider: {}",
+        "{} Received models list request for provider: {}",
+        sym("🔄"),
         state.provider
     );
 
     // Authenticate if API key is configured
     if let Err(e) = authenticate(&headers, &state).await {
-        println!("❌ Authentication failed");
+        println!("{} Authentication failed", sym("❌"));
         return Err(e);
     }
 
     match state.provider.as_str() {
         "kagi" => handle_kagi_models_request(&state).await,
         _ => {
-            println!("❌ Unsupported provider: {}", state.provider);
+            println!("{} Unsupported provider: {}", sym("❌"), state.provider);
             Err(StatusCode::BAD_REQUEST)
         }
     }
@@ -445,13 +402,14 @@ async fn handle_kagi_models_request(
             };
 
             println!(
-                "✅ Successfully fetched {} Kagi models",
+                "{} Successfully fetched {} Kagi models",
+                sym("✅"),
                 response.data.len()
             );
             Ok(Json(response))
         }
         Err(e) => {
-            println!("❌ Failed to fetch Kagi models: {}", e);
+            println!("{} Failed to fetch Kagi models: {}", sym("❌"), e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -549,7 +507,7 @@ async fn handle_kagi_request(
         },
     };
 
-    println!("✅ Successfully processed Kagi request");
+    println!("{} Successfully processed Kagi request", sym("✅"));
     Ok(Json(openai_response))
 }
 
@@ -720,12 +678,12 @@ pub async fn start_webchatproxy_daemon(
 
                 println!(
                     "{} WebChatProxy daemon started for '{}' (PID: {})",
-                    "✓".green(),
+                    sym("✓").green(),
                     provider,
                     pid
                 );
-                println!("{} Server running on {}:{}", "🚀".blue(), host, port);
-                println!("{} Logs: {}", "📝".blue(), log_file.display());
+                println!("{} Server running on {}:{}", sym("🚀").blue(), host, port);
+                println!("{} Logs: {}", sym("📝").blue(), log_file.display());
 
                 Ok(())
             }
@@ -752,12 +710,12 @@ pub async fn start_webchatproxy_daemon(
 
         println!(
             "{} WebChatProxy daemon started for '{}' (PID: {})",
-            "✓".green(),
+            sym("✓").green(),
             provider,
             pid
         );
-        println!("{} Server running on {}:{}", "🚀".blue(), host, port);
-        println!("{} Logs: {}", "📝".blue(), log_file.display());
+        println!("{} Server running on {}:{}", sym("🚀").blue(), host, port);
+        println!("{} Logs: {}", sym("📝").blue(), log_file.display());
 
         Ok(())
     }
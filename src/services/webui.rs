@@ -0,0 +1,360 @@
+//! Minimal built-in web chat interface (`lc serve-webui`)
+//!
+//! Serves a single HTML page and a small JSON API on top of the regular chat
+//! machinery: conversations are stored as sessions in `logs.db` (so they show up in
+//! `lc logs` and can be continued with `lc -c`), and each turn can use a vector
+//! database for RAG and MCP servers for tools.
+
+use crate::utils::terminal::sym;
+use crate::{
+    chat,
+    config::Config,
+    database::{ChatEntry, Database},
+};
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const INDEX_HTML: &str = include_str!("webui.html");
+
+/// Defaults applied when a request doesn't choose its own
+#[derive(Clone, Default)]
+pub struct WebUiState {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub vectordb: Option<String>,
+    pub tools: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WebUiChatRequest {
+    /// Continue this session; a new one is started when missing
+    #[serde(default)]
+    pub session_id: Option<String>,
+    pub message: String,
+    /// Model or alias, optionally as provider:model
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub vectordb: Option<String>,
+    /// Comma-separated MCP server names
+    #[serde(default)]
+    pub tools: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct WebUiChatResponse {
+    pub session_id: String,
+    pub response: String,
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct WebUiMessage {
+    pub question: String,
+    pub response: String,
+    pub model: String,
+    pub timestamp: String,
+}
+
+#[derive(Serialize)]
+pub struct WebUiInfo {
+    pub model: Option<String>,
+    pub vectordb: Option<String>,
+    pub tools: Option<String>,
+    pub vector_databases: Vec<String>,
+    pub mcp_servers: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct WebUiError {
+    error: String,
+}
+
+fn error_response(status: StatusCode, error: impl std::fmt::Display) -> Response {
+    (
+        status,
+        Json(WebUiError {
+            error: error.to_string(),
+        }),
+    )
+        .into_response()
+}
+
+pub fn router(state: WebUiState) -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/api/info", get(info))
+        .route("/api/sessions", get(list_sessions))
+        .route("/api/sessions/:id", get(get_session).delete(delete_session))
+        .route("/api/chat", post(chat_turn))
+        .with_state(Arc::new(state))
+}
+
+pub async fn start_webui_server(host: String, port: u16, state: WebUiState) -> Result<()> {
+    let addr = format!("{}:{}", host, port);
+    println!("{} Starting web UI on {}", sym("🚀").blue(), addr.bold());
+    if host != "127.0.0.1" && host != "localhost" && host != "::1" {
+        println!(
+            "{} The web UI has no authentication; anyone who can reach {} can use your providers",
+            sym("⚠️").yellow(),
+            addr
+        );
+    }
+
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    println!("{} Open http://{} in your browser", sym("✓").green(), addr);
+
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+async fn index() -> impl IntoResponse {
+    ([(header::CACHE_CONTROL, "no-cache")], Html(INDEX_HTML))
+}
+
+async fn info(State(state): State<Arc<WebUiState>>) -> Json<WebUiInfo> {
+    let vector_databases = crate::vector_db::VectorDatabase::list_databases().unwrap_or_default();
+    let mut mcp_servers: Vec<String> = match crate::mcp::McpConfig::load().await {
+        Ok(config) => config.list_servers().into_keys().collect(),
+        Err(_) => Vec::new(),
+    };
+    mcp_servers.sort();
+
+    Json(WebUiInfo {
+        model: state.model.clone(),
+        vectordb: state.vectordb.clone(),
+        tools: state.tools.clone(),
+        vector_databases,
+        mcp_servers,
+    })
+}
+
+async fn list_sessions() -> Response {
+    match Database::new().and_then(|db| db.list_sessions(50)) {
+        Ok(sessions) => Json(sessions).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+async fn get_session(Path(id): Path<String>) -> Response {
+    match Database::new().and_then(|db| db.get_chat_history(&id)) {
+        Ok(entries) => Json(entries.iter().map(to_message).collect::<Vec<_>>()).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+async fn delete_session(Path(id): Path<String>) -> Response {
+    match Database::new().and_then(|db| db.clear_session(&id)) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+async fn chat_turn(
+    State(state): State<Arc<WebUiState>>,
+    Json(request): Json<WebUiChatRequest>,
+) -> Response {
+    if request.message.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Message is empty");
+    }
+    match run_turn(&state, request).await {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => {
+            crate::debug_log!("Web UI chat request failed: {}", e);
+            error_response(StatusCode::BAD_GATEWAY, e)
+        }
+    }
+}
+
+fn to_message(entry: &ChatEntry) -> WebUiMessage {
+    WebUiMessage {
+        question: entry.question.clone(),
+        response: entry.response.clone(),
+        model: entry.model.clone(),
+        timestamp: entry.timestamp.to_rfc3339(),
+    }
+}
+
+/// Empty strings from the form mean "not set"
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// One chat turn: history from the session, optional RAG context and tools, then
+/// the response is saved to the session
+async fn run_turn(state: &WebUiState, request: WebUiChatRequest) -> Result<WebUiChatResponse> {
+    let config = Config::load()?;
+    let model = non_empty(request.model).or_else(|| state.model.clone());
+    let vectordb = non_empty(request.vectordb).or_else(|| state.vectordb.clone());
+    let tools = non_empty(request.tools).or_else(|| state.tools.clone());
+
+    let (provider_name, model_name) = crate::utils::cli_utils::resolve_model_and_provider(
+        &config,
+        state.provider.clone(),
+        model,
+    )?;
+
+    let mut config_mut = config.clone();
+    let client = chat::create_authenticated_client(&mut config_mut, &provider_name).await?;
+    if config_mut.get_cached_token(&provider_name) != config.get_cached_token(&provider_name) {
+        config_mut.save()?;
+    }
+
+    let db = Database::new()?;
+    let session_id = match non_empty(request.session_id) {
+        Some(id) => id,
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            if let Err(e) = db.set_current_session_id(&id) {
+                crate::debug_log!("Not recording the new session: {}", e);
+            }
+            id
+        }
+    };
+    let history = db.get_chat_history(&session_id)?;
+
+    let prompt = match &vectordb {
+        Some(db_name) => {
+            let context = crate::cli::embed::retrieve_rag_context(
+                db_name,
+                &request.message,
+                &client,
+                &model_name,
+                &provider_name,
+            )
+            .await?;
+            if context.is_empty() {
+                request.message.clone()
+            } else {
+                format!(
+                    "Context from knowledge base:\n{}\n\nUser question: {}",
+                    context, request.message
+                )
+            }
+        }
+        None => request.message.clone(),
+    };
+
+    let (mcp_tools, mcp_server_names) = match &tools {
+        Some(tools) => crate::core::tools::fetch_mcp_tools(tools).await?,
+        None => (None, Vec::new()),
+    };
+
+    let system_prompt = config
+        .system_prompt
+        .as_ref()
+        .map(|system_prompt| config.resolve_template_or_prompt(system_prompt));
+
+    let (response, input_tokens, output_tokens) =
+        if mcp_tools.is_some() && !mcp_server_names.is_empty() {
+            let server_refs: Vec<&str> = mcp_server_names.iter().map(|s| s.as_str()).collect();
+            chat::send_chat_request_with_tool_execution(
+                &client,
+                &model_name,
+                &prompt,
+                &history,
+                system_prompt.as_deref(),
+                config.max_tokens,
+                config.temperature,
+                &provider_name,
+                mcp_tools,
+                &server_refs,
+                None,
+            )
+            .await?
+        } else {
+            chat::send_chat_request_with_validation(
+                &client,
+                &model_name,
+                &prompt,
+                &history,
+                system_prompt.as_deref(),
+                config.max_tokens,
+                config.temperature,
+                &provider_name,
+                None,
+            )
+            .await?
+        };
+
+    // Store the question as typed, without the retrieved context
+    if let Err(e) = db.save_chat_entry_with_details(
+        &session_id,
+        &provider_name,
+        &model_name,
+        &request.message,
+        &response,
+        input_tokens,
+        output_tokens,
+        &crate::core::request_metadata::log_details(&provider_name, system_prompt.as_deref()),
+    ) {
+        crate::debug_log!("Failed to save web UI chat entry: {}", e);
+    }
+
+    Ok(WebUiChatResponse {
+        session_id,
+        response,
+        provider: provider_name,
+        model: model_name,
+        input_tokens,
+        output_tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn serve() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router(WebUiState::default()))
+                .await
+                .unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_index_serves_html() {
+        let base = serve().await;
+        let response = reqwest::get(&base).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(response.text().await.unwrap().contains("/api/chat"));
+    }
+
+    #[tokio::test]
+    async fn test_empty_message_is_rejected() {
+        let base = serve().await;
+        let response = reqwest::Client::new()
+            .post(format!("{}/api/chat", base))
+            .json(&serde_json::json!({"message": "   "}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_non_empty() {
+        assert_eq!(non_empty(Some("  ".to_string())), None);
+        assert_eq!(
+            non_empty(Some("docs".to_string())),
+            Some("docs".to_string())
+        );
+        assert_eq!(non_empty(None), None);
+    }
+}
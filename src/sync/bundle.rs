@@ -0,0 +1,324 @@
+//! Portable config bundles (`lc config export` / `lc config import`)
+//!
+//! An offline alternative to cloud sync for moving to a new machine. A bundle holds
+//! the same files `lc sync to` uploads (config.toml with its templates and aliases,
+//! provider configs and the other settings files), optionally keys.toml and the logs
+//! database, as a gzipped tar encrypted with AES-256-GCM. The key is derived from a
+//! passphrase (`LC_BUNDLE_PASSWORD` or a prompt) with a random salt stored in the
+//! bundle header, so two bundles never share a key.
+
+use super::encryption::{decrypt_data, derive_key_with_salt, encrypt_data};
+use super::sync::{collect_sync_files, is_safe_file_name, verify_keys_file, ConfigFile};
+use super::KEYS_FILE;
+use crate::utils::terminal::sym;
+use anyhow::{bail, Result};
+use colored::Colorize;
+use rand::RngCore;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// First bytes of every bundle
+const MAGIC: &[u8] = b"LCBUNDLE";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+
+/// Groups of files offered one by one on import, in this order
+const GROUPS: &[&str] = &[
+    "config (defaults, templates, aliases)",
+    "providers",
+    "other settings",
+    "API keys",
+    "logs",
+];
+
+/// The import group of a bundled file
+fn group(name: &str) -> &'static str {
+    if name == "config.toml" {
+        GROUPS[0]
+    } else if name.starts_with("providers/") {
+        GROUPS[1]
+    } else if name == KEYS_FILE {
+        GROUPS[3]
+    } else if name.ends_with(".db") {
+        GROUPS[4]
+    } else {
+        GROUPS[2]
+    }
+}
+
+/// Get the bundle passphrase from LC_BUNDLE_PASSWORD or prompt for it
+fn passphrase(confirm: bool) -> Result<String> {
+    if let Ok(passphrase) = std::env::var("LC_BUNDLE_PASSWORD") {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("Enter bundle passphrase: ")?;
+    if passphrase.is_empty() {
+        bail!("Bundle passphrase cannot be empty");
+    }
+    if confirm {
+        let again = rpassword::prompt_password("Confirm bundle passphrase: ")?;
+        if again != passphrase {
+            bail!("Bundle passphrases do not match");
+        }
+    }
+    Ok(passphrase)
+}
+
+/// Handle `lc config export`
+pub fn export(path: &str, with_keys: bool, with_logs: bool) -> Result<()> {
+    let config_dir = crate::config::Config::config_dir()?;
+    let files: Vec<ConfigFile> = collect_sync_files(&config_dir, &[], with_keys)?
+        .into_iter()
+        .filter(|file| with_logs || !file.name.ends_with(".db"))
+        .collect();
+    if files.is_empty() {
+        bail!("Nothing to export from {}", config_dir.display());
+    }
+
+    let bundle = seal(&files, &passphrase(true)?)?;
+    std::fs::write(path, &bundle)
+        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path, e))?;
+
+    println!(
+        "{} Exported {} file{} ({:.1} KB) to {}",
+        sym("✓").green(),
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+        bundle.len() as f64 / 1024.0,
+        path
+    );
+    for name in GROUPS {
+        let count = files
+            .iter()
+            .filter(|file| group(&file.name) == *name)
+            .count();
+        if count > 0 {
+            println!("  {} {} ({})", sym("•").blue(), name, count);
+        }
+    }
+    if !with_keys {
+        println!(
+            "{}",
+            "API keys were left out; add --with-keys to include them".dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Handle `lc config import`: restore the bundle's files group by group, asking first
+/// unless `yes`
+pub fn import(path: &str, yes: bool) -> Result<()> {
+    crate::lock::ensure_writable("import a config bundle")?;
+    let bundle =
+        std::fs::read(path).map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path, e))?;
+    let files = open(&bundle, &passphrase(false)?)?;
+    let config_dir = crate::config::Config::config_dir()?;
+
+    let mut restored = 0;
+    for name in GROUPS {
+        let members: Vec<&ConfigFile> = files
+            .iter()
+            .filter(|file| group(&file.name) == *name)
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+        let replaced = members
+            .iter()
+            .filter(|file| config_dir.join(&file.name).exists())
+            .count();
+        let names: Vec<&str> = members.iter().map(|file| file.name.as_str()).collect();
+        println!("{} {}: {}", sym("•").blue(), name.bold(), names.join(", "));
+        if !yes {
+            let question = if replaced > 0 {
+                format!(
+                    "  Restore, replacing {} existing file(s)? (y/N): ",
+                    replaced
+                )
+            } else {
+                "  Restore? (y/N): ".to_string()
+            };
+            if !confirm(&question)? {
+                continue;
+            }
+        }
+        for file in members {
+            write_file(&config_dir, file)?;
+            restored += 1;
+        }
+    }
+
+    if restored == 0 {
+        println!("Nothing was restored.");
+    } else {
+        println!(
+            "{} Restored {} file{} into {}",
+            sym("✓").green(),
+            restored,
+            if restored == 1 { "" } else { "s" },
+            config_dir.display()
+        );
+    }
+    Ok(())
+}
+
+fn write_file(config_dir: &Path, file: &ConfigFile) -> Result<()> {
+    let target = config_dir.join(&file.name);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if file.name.ends_with(".db") {
+        // Drop stale SQLite WAL files so they aren't replayed onto the restored database
+        for suffix in ["-wal", "-shm"] {
+            let sidecar = config_dir.join(format!("{}{}", file.name, suffix));
+            if sidecar.exists() {
+                std::fs::remove_file(&sidecar)?;
+            }
+        }
+    }
+    std::fs::write(&target, &file.content)?;
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase().starts_with('y'))
+}
+
+/// The encrypted bundle of `files`
+pub fn seal(files: &[ConfigFile], passphrase: &str) -> Result<Vec<u8>> {
+    let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+    ));
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    for file in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(file.content.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(now);
+        header.set_cksum();
+        tar.append_data(&mut header, &file.name, file.content.as_slice())?;
+    }
+    let archive = tar.into_inner()?.finish()?;
+
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key = derive_key_with_salt(passphrase, &salt)?;
+
+    let mut bundle = Vec::with_capacity(MAGIC.len() + 1 + SALT_LEN + archive.len() + 28);
+    bundle.extend_from_slice(MAGIC);
+    bundle.push(VERSION);
+    bundle.extend_from_slice(&salt);
+    bundle.extend_from_slice(&encrypt_data(&archive, &key)?);
+    Ok(bundle)
+}
+
+/// The files in an encrypted bundle, checked before anything is written: names must
+/// stay inside the config directory and config.toml and keys.toml must parse
+pub fn open(bundle: &[u8], passphrase: &str) -> Result<Vec<ConfigFile>> {
+    let Some(rest) = bundle.strip_prefix(MAGIC) else {
+        bail!("Not an lc config bundle");
+    };
+    let Some((&version, rest)) = rest.split_first() else {
+        bail!("The bundle is truncated");
+    };
+    if version != VERSION {
+        bail!(
+            "Unsupported bundle version {}; upgrade lc to import it",
+            version
+        );
+    }
+    if rest.len() < SALT_LEN {
+        bail!("The bundle is truncated");
+    }
+    let (salt, encrypted) = rest.split_at(SALT_LEN);
+    let key = derive_key_with_salt(passphrase, salt)?;
+    let archive = decrypt_data(encrypted, &key).map_err(|_| {
+        anyhow::anyhow!("Failed to decrypt the bundle: wrong passphrase or damaged file")
+    })?;
+
+    let mut files = Vec::new();
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive.as_slice()));
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().replace('\\', "/");
+        if !is_safe_file_name(&name) {
+            bail!("The bundle contains an unsafe path '{}'", name);
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        files.push(ConfigFile { name, content });
+    }
+
+    for file in &files {
+        if file.name == KEYS_FILE {
+            verify_keys_file(file)?;
+        } else if file.name == "config.toml" {
+            let text = std::str::from_utf8(&file.content)
+                .map_err(|_| anyhow::anyhow!("The bundled config.toml is not valid UTF-8"))?;
+            toml::from_str::<crate::config::Config>(text)
+                .map_err(|e| anyhow::anyhow!("The bundled config.toml is invalid: {}", e))?;
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> ConfigFile {
+        ConfigFile {
+            name: name.to_string(),
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let files = vec![
+            file(
+                "providers/openai.toml",
+                "endpoint = \"https://api.openai.com/v1\"\n",
+            ),
+            file("mcp.toml", "[servers]\n"),
+        ];
+        let bundle = seal(&files, "correct horse").unwrap();
+        assert!(bundle.starts_with(MAGIC));
+
+        let opened = open(&bundle, "correct horse").unwrap();
+        let names: Vec<&str> = opened.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["providers/openai.toml", "mcp.toml"]);
+        assert_eq!(opened[1].content, b"[servers]\n");
+
+        let error = open(&bundle, "wrong").unwrap_err().to_string();
+        assert!(error.contains("wrong passphrase"), "{}", error);
+        assert!(open(b"not a bundle", "x").is_err());
+
+        // Two bundles of the same files use different salts
+        assert_ne!(
+            seal(&files, "correct horse").unwrap()[..MAGIC.len() + 1 + SALT_LEN],
+            bundle[..MAGIC.len() + 1 + SALT_LEN]
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_bad_contents() {
+        let bundle = seal(&[file("keys.toml", "not = [valid")], "pw").unwrap();
+        assert!(open(&bundle, "pw").is_err());
+
+        assert_eq!(group("config.toml"), GROUPS[0]);
+        assert_eq!(group("providers/groq.toml"), "providers");
+        assert_eq!(group("keys.toml"), "API keys");
+        assert_eq!(group("logs.db"), "logs");
+        assert_eq!(group("mcp.toml"), "other settings");
+    }
+}
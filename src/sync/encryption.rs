@@ -10,16 +10,27 @@ use base64::{engine::general_purpose, Engine as _};
 /// Derive a 256-bit key from a password using a simple approach
 /// In production, you might want to use PBKDF2, scrypt, or Argon2
 pub fn derive_key_from_password(password: &str) -> Result<[u8; 32]> {
+    // Using a constant application-specific salt to maintain backward compatibility
+    // with the existing API signature, while significantly improving the key derivation.
+    // In a future major refactoring, per-user/per-file random salts should be implemented.
+    derive_key_with_salt(password, b"lc_sync_encryption_salt_v1")
+}
+
+/// Derive the key used to encrypt synced API keys (`--with-keys`).
+///
+/// Uses its own salt so keys.toml never shares an encryption key with the rest of
+/// the config, even if the same passphrase is reused.
+pub fn derive_keys_key_from_password(password: &str) -> Result<[u8; 32]> {
+    derive_key_with_salt(password, b"lc_sync_keys_salt_v1")
+}
+
+/// Derive a 256-bit key from a password and salt with PBKDF2-HMAC-SHA256
+pub fn derive_key_with_salt(password: &str, salt: &[u8]) -> Result<[u8; 32]> {
     use pbkdf2::pbkdf2_hmac;
     use sha2::Sha256;
 
     // Use PBKDF2 with HMAC-SHA256 for secure key derivation
     let mut key = [0u8; 32];
-
-    // Using a constant application-specific salt to maintain backward compatibility
-    // with the existing API signature, while significantly improving the key derivation.
-    // In a future major refactoring, per-user/per-file random salts should be implemented.
-    let salt = b"lc_sync_encryption_salt_v1";
     let iterations = 100_000;
 
     pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
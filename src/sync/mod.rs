@@ -1,5 +1,6 @@
 //! Synchronization functionality for lc configurations
 
+pub mod bundle;
 pub mod config;
 pub mod encryption;
 pub mod providers;
@@ -12,8 +13,9 @@ pub mod sync;
 
 // Re-export main sync functions from sync module
 pub use sync::{
-    decrypt_files, encrypt_files, handle_sync_from, handle_sync_providers, handle_sync_to,
-    ConfigFile,
+    collect_sync_files, compress_file, decompress_file, decrypt_files, decrypt_keys_file,
+    encrypt_files, encrypt_keys_file, handle_sync_from, handle_sync_providers, handle_sync_to,
+    is_selected, ConfigFile, ENCRYPTED_KEYS_FILE, KEYS_FILE,
 };
 
 // Re-export config handler from config module
@@ -21,5 +23,6 @@ pub use config::handle_sync_configure;
 
 // Re-export encryption utilities from encryption module
 pub use encryption::{
-    decode_base64, decrypt_data, derive_key_from_password, encode_base64, encrypt_data,
+    decode_base64, decrypt_data, derive_key_from_password, derive_keys_key_from_password,
+    encode_base64, encrypt_data,
 };
@@ -1,5 +1,7 @@
 //! Cloud provider implementations for configuration synchronization
 
+#[cfg(feature = "s3-sync")]
+use crate::utils::terminal::sym;
 #[cfg(feature = "s3-sync")]
 use anyhow::Result;
 #[cfg(feature = "s3-sync")]
@@ -11,9 +13,34 @@ use std::collections::HashMap;
 use super::{decode_base64, encode_base64, ConfigFile};
 
 #[cfg(feature = "s3-sync")]
-use aws_config::BehaviorVersion;
+use super::config::{ProviderConfig, ServerSideEncryption};
+#[cfg(feature = "s3-sync")]
+use aws_config::{retry::RetryConfig, timeout::TimeoutConfig, BehaviorVersion};
+#[cfg(feature = "s3-sync")]
+use aws_sdk_s3::{
+    config::Credentials,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
+    Client,
+};
+#[cfg(feature = "s3-sync")]
+use std::time::Duration;
+
+/// Objects larger than this are uploaded in parts
+#[cfg(feature = "s3-sync")]
+const MULTIPART_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Part size for multipart uploads (S3 requires at least 5 MiB for all but the last part)
+#[cfg(feature = "s3-sync")]
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Default attempts per request, including the first one
+#[cfg(feature = "s3-sync")]
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+/// Metadata key holding the SHA-256 of the original (decoded) file content
 #[cfg(feature = "s3-sync")]
-use aws_sdk_s3::{config::Credentials, primitives::ByteStream, Client};
+const CHECKSUM_METADATA_KEY: &str = "content-sha256";
 
 /// S3 configuration for sync operations
 #[cfg(feature = "s3-sync")]
@@ -24,6 +51,38 @@ pub struct S3Config {
     pub access_key_id: String,
     pub secret_access_key: String,
     pub endpoint_url: Option<String>,
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    pub sse_kms_key_id: Option<String>,
+    pub force_path_style: bool,
+    pub max_attempts: Option<u32>,
+}
+
+#[cfg(feature = "s3-sync")]
+impl From<&ProviderConfig> for S3Config {
+    fn from(config: &ProviderConfig) -> Self {
+        let ProviderConfig::S3 {
+            bucket_name,
+            region,
+            access_key_id,
+            secret_access_key,
+            endpoint_url,
+            server_side_encryption,
+            sse_kms_key_id,
+            force_path_style,
+            max_attempts,
+        } = config;
+        S3Config {
+            bucket_name: bucket_name.clone(),
+            region: region.clone(),
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret_access_key.clone(),
+            endpoint_url: endpoint_url.clone(),
+            server_side_encryption: *server_side_encryption,
+            sse_kms_key_id: sse_kms_key_id.clone(),
+            force_path_style: *force_path_style,
+            max_attempts: *max_attempts,
+        }
+    }
 }
 
 /// S3 provider for configuration synchronization
@@ -32,6 +91,29 @@ pub struct S3Provider {
     client: Client,
     bucket_name: String,
     folder_prefix: String,
+    server_side_encryption: Option<ServerSideEncryption>,
+    sse_kms_key_id: Option<String>,
+}
+
+/// Hex-encoded SHA-256 of the given bytes
+#[cfg(feature = "s3-sync")]
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Split a body into multipart upload chunks
+#[cfg(feature = "s3-sync")]
+fn split_parts(body: &[u8], part_size: usize) -> Vec<&[u8]> {
+    body.chunks(part_size.max(1)).collect()
+}
+
+#[cfg(feature = "s3-sync")]
+fn sdk_encryption(sse: ServerSideEncryption) -> aws_sdk_s3::types::ServerSideEncryption {
+    match sse {
+        ServerSideEncryption::SseS3 => aws_sdk_s3::types::ServerSideEncryption::Aes256,
+        ServerSideEncryption::SseKms => aws_sdk_s3::types::ServerSideEncryption::AwsKms,
+    }
 }
 
 #[cfg(feature = "s3-sync")]
@@ -39,7 +121,21 @@ impl S3Provider {
     /// Create a new S3 provider instance with a specific provider name
     pub async fn new_with_provider(provider_name: &str) -> Result<Self> {
         let s3_config = Self::get_s3_config(provider_name).await?;
+        let client = Self::build_client(&s3_config).await?;
+
+        let folder_prefix = "llm_client_config".to_string();
 
+        Ok(Self {
+            client,
+            bucket_name: s3_config.bucket_name,
+            folder_prefix,
+            server_side_encryption: s3_config.server_side_encryption,
+            sse_kms_key_id: s3_config.sse_kms_key_id,
+        })
+    }
+
+    /// Build an S3 client for the given configuration
+    async fn build_client(s3_config: &S3Config) -> Result<Client> {
         // Build AWS config with custom settings
         let mut config_builder = aws_config::defaults(BehaviorVersion::latest())
             .region(aws_config::Region::new(s3_config.region.clone()))
@@ -51,71 +147,154 @@ impl S3Provider {
                 "lc-sync",
             ));
 
+        // Retry throttling and transient network errors with exponential backoff
+        let max_attempts = s3_config
+            .max_attempts
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+            .max(1);
+        config_builder = config_builder
+            .retry_config(
+                RetryConfig::standard()
+                    .with_max_attempts(max_attempts)
+                    .with_initial_backoff(Duration::from_millis(500))
+                    .with_max_backoff(Duration::from_secs(20)),
+            )
+            .timeout_config(
+                TimeoutConfig::builder()
+                    .connect_timeout(Duration::from_secs(10))
+                    .build(),
+            );
+
         // Set custom endpoint if provided (for S3-compatible services)
         if let Some(endpoint_url) = &s3_config.endpoint_url {
+            if let Some(warning) = super::config::validate_endpoint_url(endpoint_url)? {
+                eprintln!("{} {}", sym("⚠️").yellow(), warning);
+            }
             config_builder = config_builder.endpoint_url(endpoint_url);
         }
 
         let config = config_builder.load().await;
-        let client = Client::new(&config);
+        let s3_client_config = aws_sdk_s3::config::Builder::from(&config)
+            .force_path_style(s3_config.force_path_style)
+            .build();
+        Ok(Client::from_conf(s3_client_config))
+    }
 
-        let folder_prefix = "llm_client_config".to_string();
+    /// Download a single object, such as an attachment given as `s3://bucket/key`. Uses
+    /// the stored sync configuration for the bucket when there is one, and the default
+    /// AWS credential chain (environment variables, ~/.aws) otherwise.
+    pub async fn download_object(bucket: &str, key: &str, max_bytes: usize) -> Result<Vec<u8>> {
+        let stored = crate::sync::config::SyncConfig::load()
+            .ok()
+            .and_then(|sync_config| {
+                sync_config
+                    .providers
+                    .values()
+                    .find(|provider| {
+                        matches!(provider, ProviderConfig::S3 { bucket_name, .. } if bucket_name == bucket)
+                    })
+                    .map(S3Config::from)
+            });
+
+        let client = match stored {
+            Some(s3_config) => Self::build_client(&s3_config).await?,
+            None => {
+                let region = aws_config::meta::region::RegionProviderChain::default_provider()
+                    .or_else("us-east-1");
+                let config = aws_config::defaults(BehaviorVersion::latest())
+                    .region(region)
+                    .load()
+                    .await;
+                Client::new(&config)
+            }
+        };
 
-        Ok(Self {
-            client,
-            bucket_name: s3_config.bucket_name,
-            folder_prefix,
-        })
+        let response = client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to download s3://{}/{}: {}",
+                    bucket,
+                    key,
+                    aws_sdk_s3::error::DisplayErrorContext(e)
+                )
+            })?;
+        let too_large = || {
+            anyhow::anyhow!(
+                "s3://{}/{} is larger than {} MB",
+                bucket,
+                key,
+                max_bytes / (1024 * 1024)
+            )
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > max_bytes as i64)
+        {
+            return Err(too_large());
+        }
+        let mut stream = response.body;
+        let mut body = Vec::new();
+        while let Some(chunk) = stream
+            .try_next()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read object body: {}", e))?
+        {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
     }
 
     /// Get S3 configuration from stored config, environment variables, or user input
     async fn get_s3_config(provider_name: &str) -> Result<S3Config> {
-        use crate::sync::config::{ProviderConfig, SyncConfig};
+        use crate::sync::config::SyncConfig;
         use std::io::{self, Write};
 
         // First, try to load from stored configuration
         if let Ok(sync_config) = SyncConfig::load() {
-            if let Some(ProviderConfig::S3 {
-                bucket_name,
-                region,
-                access_key_id,
-                secret_access_key,
-                endpoint_url,
-            }) = sync_config.get_provider(provider_name)
-            {
+            if let Some(provider_config) = sync_config.get_provider(provider_name) {
                 println!(
                     "{} Using stored S3 configuration for '{}'",
-                    "✓".green(),
+                    sym("✓").green(),
                     provider_name
                 );
-                return Ok(S3Config {
-                    bucket_name: bucket_name.clone(),
-                    region: region.clone(),
-                    access_key_id: access_key_id.clone(),
-                    secret_access_key: secret_access_key.clone(),
-                    endpoint_url: endpoint_url.clone(),
-                });
+                return Ok(S3Config::from(provider_config));
             }
         }
 
         println!(
             "{} S3 Configuration Setup for '{}'",
-            "🔧".blue(),
+            sym("🔧").blue(),
             provider_name
         );
-        println!("{} No stored configuration found. You can:", "💡".yellow());
+        println!(
+            "{} No stored configuration found. You can:",
+            sym("💡").yellow()
+        );
         println!(
             "  - Set up configuration: {}",
             format!("lc sync configure {} setup", provider_name).dimmed()
         );
         println!("  - Use environment variables:");
         println!("    LC_S3_BUCKET, LC_S3_REGION, AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, LC_S3_ENDPOINT");
+        println!("    LC_S3_SSE, LC_S3_KMS_KEY_ID, LC_S3_FORCE_PATH_STYLE (optional)");
         println!("  - Enter credentials interactively (below)");
         println!();
 
         // Try to get from environment variables first
         let bucket_name = if let Ok(bucket) = std::env::var("LC_S3_BUCKET") {
-            println!("{} Using bucket from LC_S3_BUCKET: {}", "✓".green(), bucket);
+            println!(
+                "{} Using bucket from LC_S3_BUCKET: {}",
+                sym("✓").green(),
+                bucket
+            );
             bucket
         } else {
             print!("Enter S3 bucket name: ");
@@ -131,7 +310,11 @@ impl S3Provider {
         };
 
         let region = if let Ok(region) = std::env::var("LC_S3_REGION") {
-            println!("{} Using region from LC_S3_REGION: {}", "✓".green(), region);
+            println!(
+                "{} Using region from LC_S3_REGION: {}",
+                sym("✓").green(),
+                region
+            );
             region
         } else {
             print!("Enter AWS region (default: us-east-1): ");
@@ -148,7 +331,10 @@ impl S3Provider {
         };
 
         let access_key_id = if let Ok(key) = std::env::var("AWS_ACCESS_KEY_ID") {
-            println!("{} Using access key from AWS_ACCESS_KEY_ID", "✓".green());
+            println!(
+                "{} Using access key from AWS_ACCESS_KEY_ID",
+                sym("✓").green()
+            );
             key
         } else {
             print!("Enter AWS Access Key ID: ");
@@ -166,7 +352,7 @@ impl S3Provider {
         let secret_access_key = if let Ok(secret) = std::env::var("AWS_SECRET_ACCESS_KEY") {
             println!(
                 "{} Using secret key from AWS_SECRET_ACCESS_KEY",
-                "✓".green()
+                sym("✓").green()
             );
             secret
         } else {
@@ -183,7 +369,7 @@ impl S3Provider {
         let endpoint_url = if let Ok(endpoint) = std::env::var("LC_S3_ENDPOINT") {
             println!(
                 "{} Using custom endpoint from LC_S3_ENDPOINT: {}",
-                "✓".green(),
+                sym("✓").green(),
                 endpoint
             );
             Some(endpoint)
@@ -201,20 +387,196 @@ impl S3Provider {
             }
         };
 
+        let server_side_encryption = match std::env::var("LC_S3_SSE") {
+            Ok(value) if !value.trim().is_empty() => Some(value.parse()?),
+            _ => None,
+        };
+        let sse_kms_key_id = std::env::var("LC_S3_KMS_KEY_ID")
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+        let force_path_style = std::env::var("LC_S3_FORCE_PATH_STYLE")
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
         Ok(S3Config {
             bucket_name,
             region,
             access_key_id,
             secret_access_key,
             endpoint_url,
+            server_side_encryption,
+            sse_kms_key_id,
+            force_path_style,
+            max_attempts: None,
         })
     }
 
+    /// Upload a single object, switching to multipart upload for large bodies
+    async fn put_body(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        metadata: HashMap<String, String>,
+    ) -> Result<()> {
+        if body.len() > MULTIPART_THRESHOLD {
+            return self.put_multipart(key, &body, metadata).await;
+        }
+
+        let mut request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .body(ByteStream::from(body))
+            .content_type("text/plain")
+            .set_metadata(Some(metadata));
+        if let Some(sse) = self.server_side_encryption {
+            request = request.server_side_encryption(sdk_encryption(sse));
+            if sse == ServerSideEncryption::SseKms {
+                request = request.set_ssekms_key_id(self.sse_kms_key_id.clone());
+            }
+        }
+
+        request.send().await?;
+        Ok(())
+    }
+
+    /// Upload a large body in parts, aborting the upload if any part fails
+    async fn put_multipart(
+        &self,
+        key: &str,
+        body: &[u8],
+        metadata: HashMap<String, String>,
+    ) -> Result<()> {
+        let mut request = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .content_type("text/plain")
+            .set_metadata(Some(metadata));
+        if let Some(sse) = self.server_side_encryption {
+            request = request.server_side_encryption(sdk_encryption(sse));
+            if sse == ServerSideEncryption::SseKms {
+                request = request.set_ssekms_key_id(self.sse_kms_key_id.clone());
+            }
+        }
+
+        let upload = request.send().await?;
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| anyhow::anyhow!("S3 did not return a multipart upload id"))?
+            .to_string();
+
+        match self.upload_parts(key, &upload_id, body).await {
+            Ok(parts) => {
+                self.client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(key)
+                    .upload_id(&upload_id)
+                    .multipart_upload(
+                        CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
+                    )
+                    .send()
+                    .await?;
+                Ok(())
+            }
+            Err(e) => {
+                // Don't leave orphaned parts behind (they are billed until aborted)
+                if let Err(abort_err) = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(key)
+                    .upload_id(&upload_id)
+                    .send()
+                    .await
+                {
+                    crate::debug_log!(
+                        "Failed to abort multipart upload {}: {}",
+                        upload_id,
+                        abort_err
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        body: &[u8],
+    ) -> Result<Vec<CompletedPart>> {
+        let chunks = split_parts(body, MULTIPART_PART_SIZE);
+        let total = chunks.len();
+        let mut parts = Vec::with_capacity(total);
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let part_number = (index + 1) as i32;
+            crate::debug_log!("Uploading part {}/{} of {}", part_number, total, key);
+
+            let response = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket_name)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(chunk.to_vec()))
+                .send()
+                .await?;
+
+            parts.push(
+                CompletedPart::builder()
+                    .part_number(part_number)
+                    .set_e_tag(response.e_tag().map(|t| t.to_string()))
+                    .build(),
+            );
+        }
+
+        Ok(parts)
+    }
+
+    /// Confirm an uploaded object has the expected size and checksum metadata
+    async fn verify_upload(&self, key: &str, expected_len: usize, checksum: &str) -> Result<()> {
+        let head = self
+            .client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await?;
+
+        let actual_len = head.content_length().unwrap_or_default();
+        if actual_len != expected_len as i64 {
+            anyhow::bail!(
+                "size mismatch after upload (expected {} bytes, found {})",
+                expected_len,
+                actual_len
+            );
+        }
+
+        let stored = head
+            .metadata()
+            .and_then(|m| m.get(CHECKSUM_METADATA_KEY))
+            .map(|s| s.as_str());
+        if stored != Some(checksum) {
+            anyhow::bail!("checksum metadata mismatch after upload");
+        }
+
+        Ok(())
+    }
+
     /// Upload configuration files to S3
     pub async fn upload_configs(&self, files: &[ConfigFile], encrypted: bool) -> Result<()> {
         println!(
             "{} Uploading to S3 bucket: {}",
-            "📤".blue(),
+            sym("📤").blue(),
             self.bucket_name
         );
 
@@ -227,7 +589,7 @@ impl S3Provider {
             .await
         {
             Ok(_) => {
-                println!("{} Bucket access verified", "✓".green());
+                println!("{} Bucket access verified", sym("✓").green());
             }
             Err(e) => {
                 anyhow::bail!("Cannot access S3 bucket '{}': {}. Please check your AWS credentials and bucket permissions.", self.bucket_name, e);
@@ -267,24 +629,26 @@ impl S3Provider {
             // Add file size for monitoring
             metadata.insert("file-size".to_string(), file.content.len().to_string());
 
-            match self
-                .client
-                .put_object()
-                .bucket(&self.bucket_name)
-                .key(&key)
-                .body(ByteStream::from(content_b64.into_bytes()))
-                .content_type("text/plain")
-                .set_metadata(Some(metadata))
-                .send()
-                .await
-            {
-                Ok(_) => {
-                    println!("  {} Uploaded: {}", "✓".green(), file.name);
+            // Checksum of the original content, verified after upload and download
+            let checksum = sha256_hex(&file.content);
+            metadata.insert(CHECKSUM_METADATA_KEY.to_string(), checksum.clone());
+
+            let body = content_b64.into_bytes();
+            let body_len = body.len();
+
+            let result = match self.put_body(&key, body, metadata).await {
+                Ok(()) => self.verify_upload(&key, body_len, &checksum).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    println!("  {} Uploaded: {}", sym("✓").green(), file.name);
                     uploaded_count += 1;
                 }
                 Err(e) => {
                     crate::debug_log!("Failed to upload {}: {}", file.name, e);
-                    eprintln!("  {} Failed to upload {}: {}", "✗".red(), file.name, e);
+                    eprintln!("  {} Failed to upload {}: {}", sym("✗").red(), file.name, e);
                 }
             }
         }
@@ -292,13 +656,13 @@ impl S3Provider {
         if uploaded_count == files.len() {
             println!(
                 "{} All {} files uploaded successfully",
-                "🎉".green(),
+                sym("🎉").green(),
                 uploaded_count
             );
         } else {
             println!(
                 "{} Uploaded {}/{} files",
-                "⚠️".yellow(),
+                sym("⚠️").yellow(),
                 uploaded_count,
                 files.len()
             );
@@ -308,10 +672,14 @@ impl S3Provider {
     }
 
     /// Download configuration files from S3
-    pub async fn download_configs(&self, encrypted: bool) -> Result<Vec<ConfigFile>> {
+    pub async fn download_configs(
+        &self,
+        encrypted: bool,
+        wanted: impl Fn(&str) -> bool,
+    ) -> Result<Vec<ConfigFile>> {
         println!(
             "{} Downloading from S3 bucket: {}",
-            "📥".blue(),
+            sym("📥").blue(),
             self.bucket_name
         );
 
@@ -334,11 +702,11 @@ impl S3Provider {
         let objects = list_response.contents();
 
         if objects.is_empty() {
-            println!("{} No configuration files found in S3", "ℹ️".blue());
+            println!("{} No configuration files found in S3", sym("ℹ️").blue());
             return Ok(Vec::new());
         }
 
-        println!("{} Found {} objects in S3", "📁".blue(), objects.len());
+        println!("{} Found {} objects in S3", sym("📁").blue(), objects.len());
 
         let mut downloaded_files = Vec::new();
 
@@ -355,6 +723,12 @@ impl S3Provider {
                     .unwrap_or(key)
                     .to_string();
 
+                // Skip optional data (vectors, models cache) that wasn't requested
+                if !wanted(&filename) {
+                    crate::debug_log!("Skipping {} (not selected)", filename);
+                    continue;
+                }
+
                 match self
                     .client
                     .get_object()
@@ -390,6 +764,18 @@ impl S3Provider {
                             )
                         })?;
 
+                        // Objects uploaded by older versions carry no checksum
+                        if let Some(expected) = metadata.get(CHECKSUM_METADATA_KEY) {
+                            if sha256_hex(&content) != *expected {
+                                eprintln!(
+                                    "  {} Checksum mismatch for {}, skipping corrupted download",
+                                    sym("✗").red(),
+                                    filename
+                                );
+                                continue;
+                            }
+                        }
+
                         if encrypted && !is_encrypted {
                             crate::debug_log!(
                                 "Warning: {} is not encrypted but --encrypted flag was used",
@@ -397,7 +783,7 @@ impl S3Provider {
                             );
                             eprintln!(
                                 "  {} Warning: {} is not encrypted but --encrypted flag was used",
-                                "⚠️".yellow(),
+                                sym("⚠️").yellow(),
                                 filename
                             );
                         } else if !encrypted && is_encrypted {
@@ -407,7 +793,7 @@ impl S3Provider {
                             );
                             eprintln!(
                                 "  {} Warning: {} is encrypted but --encrypted flag was not used",
-                                "⚠️".yellow(),
+                                sym("⚠️").yellow(),
                                 filename
                             );
                         }
@@ -417,11 +803,16 @@ impl S3Provider {
                             content,
                         });
 
-                        println!("  {} Downloaded: {}", "✓".green(), filename);
+                        println!("  {} Downloaded: {}", sym("✓").green(), filename);
                     }
                     Err(e) => {
                         crate::debug_log!("Failed to download {}: {}", filename, e);
-                        eprintln!("  {} Failed to download {}: {}", "✗".red(), filename, e);
+                        eprintln!(
+                            "  {} Failed to download {}: {}",
+                            sym("✗").red(),
+                            filename,
+                            e
+                        );
                     }
                 }
             }
@@ -429,7 +820,7 @@ impl S3Provider {
 
         println!(
             "{} Downloaded {} files successfully",
-            "🎉".green(),
+            sym("🎉").green(),
             downloaded_files.len()
         );
 
@@ -480,11 +871,11 @@ impl S3Provider {
                 .await
             {
                 Ok(_) => {
-                    println!("  {} Deleted: {}", "✓".green(), filename);
+                    println!("  {} Deleted: {}", sym("✓").green(), filename);
                 }
                 Err(e) => {
                     crate::debug_log!("Failed to delete {}: {}", filename, e);
-                    eprintln!("  {} Failed to delete {}: {}", "✗".red(), filename, e);
+                    eprintln!("  {} Failed to delete {}: {}", sym("✗").red(), filename, e);
                 }
             }
         }
@@ -512,6 +903,10 @@ mod tests {
             access_key_id: "test-key".to_string(),
             secret_access_key: "test-secret".to_string(),
             endpoint_url: None,
+            server_side_encryption: Some(ServerSideEncryption::SseKms),
+            sse_kms_key_id: None,
+            force_path_style: false,
+            max_attempts: None,
         };
 
         assert_eq!(config.bucket_name, "test-bucket");
@@ -519,5 +914,27 @@ mod tests {
         assert_eq!(config.access_key_id, "test-key");
         assert_eq!(config.secret_access_key, "test-secret");
         assert!(config.endpoint_url.is_none());
+        assert_eq!(
+            sdk_encryption(config.server_side_encryption.unwrap()),
+            aws_sdk_s3::types::ServerSideEncryption::AwsKms
+        );
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_split_parts() {
+        let body = vec![0u8; MULTIPART_PART_SIZE * 2 + 10];
+        let parts = split_parts(&body, MULTIPART_PART_SIZE);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), MULTIPART_PART_SIZE);
+        assert_eq!(parts[2].len(), 10);
+        assert_eq!(parts.iter().map(|p| p.len()).sum::<usize>(), body.len());
     }
 }
@@ -21,16 +21,21 @@ pub async fn upload_to_s3_provider(
     s3_provider.upload_configs(files, encrypted).await
 }
 
-/// Download configuration files from S3 using specified provider
+/// Download configuration files from S3 using specified provider, keeping files
+/// whose names pass `wanted`
 #[cfg(feature = "s3-sync")]
-pub async fn download_from_s3_provider(provider: &str, encrypted: bool) -> Result<Vec<ConfigFile>> {
+pub async fn download_from_s3_provider(
+    provider: &str,
+    encrypted: bool,
+    wanted: impl Fn(&str) -> bool,
+) -> Result<Vec<ConfigFile>> {
     use super::providers::S3Provider;
 
     // Create S3 provider with the specified provider name
     let s3_provider = S3Provider::new_with_provider(provider).await?;
 
     // Download configs with correct encryption status
-    s3_provider.download_configs(encrypted).await
+    s3_provider.download_configs(encrypted, wanted).await
 }
 
 // Keep the old functions for backward compatibility (deprecated)
@@ -43,5 +48,5 @@ pub async fn upload_to_s3(files: &[ConfigFile]) -> Result<()> {
 #[cfg(feature = "s3-sync")]
 #[deprecated(note = "Use download_from_s3_provider instead")]
 pub async fn download_from_s3() -> Result<Vec<ConfigFile>> {
-    download_from_s3_provider("s3", false).await
+    download_from_s3_provider("s3", false, |_| true).await
 }
@@ -1,7 +1,19 @@
 //! Sync command handlers for configuration synchronization
 
+use crate::cli::SyncInclude;
+use crate::utils::terminal::sym;
 use anyhow::Result;
 use colored::*;
+use std::path::Path;
+
+/// Suffix for optional data files that are gzip-compressed before upload
+const COMPRESSED_SUFFIX: &str = ".gz";
+
+/// API keys file, only synced with `--with-keys`
+pub const KEYS_FILE: &str = "keys.toml";
+
+/// Remote name of keys.toml, always encrypted with the separate key-sync passphrase
+pub const ENCRYPTED_KEYS_FILE: &str = "keys.toml.enc";
 
 /// Configuration file structure for sync operations
 #[derive(Debug, Clone)]
@@ -10,6 +22,251 @@ pub struct ConfigFile {
     pub content: Vec<u8>,
 }
 
+/// Optional data set a synced file belongs to, or `None` for core configuration
+pub fn optional_category(name: &str) -> Option<SyncInclude> {
+    let name = name.strip_suffix(COMPRESSED_SUFFIX).unwrap_or(name);
+    if name.starts_with("embeddings/") {
+        Some(SyncInclude::Vectors)
+    } else if name.starts_with("models/") || name == "models_cache.json" {
+        Some(SyncInclude::Models)
+    } else {
+        None
+    }
+}
+
+/// Whether a file should be synced given the requested optional data sets
+pub fn is_selected(name: &str, include: &[SyncInclude], with_keys: bool) -> bool {
+    if name == KEYS_FILE || name == ENCRYPTED_KEYS_FILE {
+        return with_keys;
+    }
+    optional_category(name)
+        .map(|category| include.contains(&category))
+        .unwrap_or(true)
+}
+
+/// Get the key-sync passphrase from LC_SYNC_KEYS_PASSWORD or prompt for it
+fn keys_passphrase(confirm: bool) -> Result<String> {
+    if let Ok(passphrase) = std::env::var("LC_SYNC_KEYS_PASSWORD") {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("Enter key sync passphrase: ")?;
+    if passphrase.is_empty() {
+        anyhow::bail!("Key sync passphrase cannot be empty");
+    }
+    if confirm {
+        let again = rpassword::prompt_password("Confirm key sync passphrase: ")?;
+        if again != passphrase {
+            anyhow::bail!("Key sync passphrases do not match");
+        }
+    }
+    Ok(passphrase)
+}
+
+/// Encrypt keys.toml under the key-sync passphrase for upload as `keys.toml.enc`
+pub fn encrypt_keys_file(file: &ConfigFile, passphrase: &str) -> Result<ConfigFile> {
+    use super::encryption::{derive_keys_key_from_password, encrypt_data};
+
+    if std::env::var("LC_SYNC_PASSWORD").is_ok_and(|p| p == passphrase) {
+        anyhow::bail!(
+            "The key sync passphrase must differ from the config sync password (LC_SYNC_PASSWORD)"
+        );
+    }
+
+    let key = derive_keys_key_from_password(passphrase)?;
+    Ok(ConfigFile {
+        name: ENCRYPTED_KEYS_FILE.to_string(),
+        content: encrypt_data(&file.content, &key)?,
+    })
+}
+
+/// Decrypt and validate a downloaded `keys.toml.enc`.
+///
+/// Fails without side effects if the passphrase is wrong or the result isn't a valid
+/// keys.toml, so the local keys are never overwritten with garbage.
+pub fn decrypt_keys_file(file: &ConfigFile, passphrase: &str) -> Result<ConfigFile> {
+    use super::encryption::{decrypt_data, derive_keys_key_from_password};
+
+    let key = derive_keys_key_from_password(passphrase)?;
+    let content = decrypt_data(&file.content, &key).map_err(|_| {
+        anyhow::anyhow!("Failed to decrypt synced keys: wrong key sync passphrase?")
+    })?;
+    let keys_file = ConfigFile {
+        name: KEYS_FILE.to_string(),
+        content,
+    };
+    verify_keys_file(&keys_file)?;
+    Ok(keys_file)
+}
+
+pub(crate) fn verify_keys_file(file: &ConfigFile) -> Result<()> {
+    let text = std::str::from_utf8(&file.content)
+        .map_err(|_| anyhow::anyhow!("Synced keys are not valid UTF-8"))?;
+    toml::from_str::<crate::keys::KeysConfig>(text)
+        .map_err(|e| anyhow::anyhow!("Synced keys are not a valid keys.toml: {}", e))?;
+    Ok(())
+}
+
+/// Gzip a file's content, appending `.gz` to its name
+pub fn compress_file(file: &ConfigFile) -> Result<ConfigFile> {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&file.content)?;
+    Ok(ConfigFile {
+        name: format!("{}{}", file.name, COMPRESSED_SUFFIX),
+        content: encoder.finish()?,
+    })
+}
+
+/// Reverse [`compress_file`] for compressed optional data; other files are returned as-is
+pub fn decompress_file(file: ConfigFile) -> Result<ConfigFile> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let Some(name) = file.name.strip_suffix(COMPRESSED_SUFFIX) else {
+        return Ok(file);
+    };
+    if optional_category(name).is_none() {
+        return Ok(file);
+    }
+
+    let mut content = Vec::new();
+    GzDecoder::new(file.content.as_slice())
+        .read_to_end(&mut content)
+        .map_err(|e| anyhow::anyhow!("Failed to decompress {}: {}", file.name, e))?;
+    Ok(ConfigFile {
+        name: name.to_string(),
+        content,
+    })
+}
+
+/// Whether a synced file name stays inside the config directory once joined to it.
+/// Names use `/` on every platform; drive prefixes, roots, `..` and backslashes are rejected.
+pub(crate) fn is_safe_file_name(name: &str) -> bool {
+    use std::path::Component;
+
+    !name.is_empty()
+        && !name.contains('\\')
+        && Path::new(name)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn collect_dir(
+    dir: &Path,
+    prefix: &str,
+    extension: &str,
+    files: &mut Vec<ConfigFile>,
+) -> Result<()> {
+    use std::fs;
+
+    if !dir.exists() {
+        return Ok(());
+    }
+    let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some(extension) {
+            let content = fs::read(&path)?;
+            let name = format!("{}/{}", prefix, path.file_name().unwrap().to_string_lossy());
+            files.push(ConfigFile { name, content });
+        }
+    }
+    Ok(())
+}
+
+/// Collect the files to sync from the config directory.
+///
+/// Configuration (`*.toml`, `*.db` and provider configs) is always included, except
+/// keys.toml which needs `with_keys`. Vector databases and the models cache are only
+/// included when requested and are compressed.
+pub fn collect_sync_files(
+    config_dir: &Path,
+    include: &[SyncInclude],
+    with_keys: bool,
+) -> Result<Vec<ConfigFile>> {
+    use std::fs;
+
+    let mut config_files = Vec::new();
+
+    // First, collect all .toml and .db files from the main config directory
+    let mut entries: Vec<_> = fs::read_dir(config_dir)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+
+        if path.is_file() {
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown");
+            let extension = path.extension().and_then(|e| e.to_str());
+
+            // Include all .toml files and .db files (logs.db, etc.)
+            let should_include = extension.map(|e| e == "toml" || e == "db").unwrap_or(false)
+                && (with_keys || file_name != KEYS_FILE);
+
+            if should_include {
+                let content = fs::read(&path)?;
+                config_files.push(ConfigFile {
+                    name: file_name.to_string(),
+                    content,
+                });
+            }
+        }
+    }
+
+    // Collect provider configs from providers/ subdirectory
+    collect_dir(
+        &config_dir.join("providers"),
+        "providers",
+        "toml",
+        &mut config_files,
+    )?;
+
+    let mut optional_files = Vec::new();
+    if include.contains(&SyncInclude::Vectors) {
+        collect_dir(
+            &config_dir.join("embeddings"),
+            "embeddings",
+            "db",
+            &mut optional_files,
+        )?;
+    }
+    if include.contains(&SyncInclude::Models) {
+        collect_dir(
+            &config_dir.join("models"),
+            "models",
+            "json",
+            &mut optional_files,
+        )?;
+        let legacy_cache = config_dir.join("models_cache.json");
+        if legacy_cache.is_file() {
+            optional_files.push(ConfigFile {
+                name: "models_cache.json".to_string(),
+                content: fs::read(&legacy_cache)?,
+            });
+        }
+    }
+
+    for file in &optional_files {
+        config_files.push(compress_file(file)?);
+    }
+
+    Ok(config_files)
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
 /// Encrypt multiple configuration files
 pub fn encrypt_files(config_files: &[ConfigFile]) -> Result<Vec<ConfigFile>> {
     use super::encryption::{derive_key_from_password, encrypt_data};
@@ -61,11 +318,15 @@ pub fn decrypt_files(encrypted_files: &[ConfigFile]) -> Result<Vec<ConfigFile>>
 /// List available sync providers
 pub async fn handle_sync_providers() -> Result<()> {
     println!("{}", "Available sync providers:".bold());
-    println!("  • {} - Amazon S3 and S3-compatible storage", "s3".cyan());
-    println!("  • {} - Amazon S3", "amazon-s3".cyan());
-    println!("  • {} - AWS S3", "aws-s3".cyan());
-    println!("  • {} - Cloudflare R2", "cloudflare".cyan());
-    println!("  • {} - Backblaze B2", "backblaze".cyan());
+    println!(
+        "  {} {} - Amazon S3 and S3-compatible storage",
+        sym("•"),
+        "s3".cyan()
+    );
+    println!("  {} {} - Amazon S3", sym("•"), "amazon-s3".cyan());
+    println!("  {} {} - AWS S3", sym("•"), "aws-s3".cyan());
+    println!("  {} {} - Cloudflare R2", sym("•"), "cloudflare".cyan());
+    println!("  {} {} - Backblaze B2", sym("•"), "backblaze".cyan());
     println!(
         "\n{}",
         "Configure a provider with: lc sync configure <provider>".italic()
@@ -84,12 +345,18 @@ fn validate_sync_provider(provider: &str) -> Result<()> {
 }
 
 /// Sync configuration files to cloud storage
-pub async fn handle_sync_to(provider: &str, encrypted: bool, yes: bool) -> Result<()> {
-    use std::fs;
+pub async fn handle_sync_to(
+    provider: &str,
+    encrypted: bool,
+    yes: bool,
+    include: &[SyncInclude],
+    with_keys: bool,
+) -> Result<()> {
     use std::io::{self, Write};
 
     println!(
-        "📤 {} configuration to {}...",
+        "{} {} configuration to {}...",
+        sym("📤"),
         "Syncing".cyan(),
         provider.bold()
     );
@@ -106,64 +373,34 @@ pub async fn handle_sync_to(provider: &str, encrypted: bool, yes: bool) -> Resul
         anyhow::bail!("Configuration directory does not exist: {:?}", config_dir);
     }
 
-    // Collect all configuration files
-    let mut config_files = Vec::new();
-
-    // First, collect all .toml and .db files from the main config directory
-    for entry in fs::read_dir(&config_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() {
-            let file_name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown");
-            let extension = path.extension().and_then(|e| e.to_str());
-
-            // Include all .toml files and .db files (logs.db, embeddings.db, etc.)
-            let should_include = extension.map(|e| e == "toml" || e == "db").unwrap_or(false);
-
-            if should_include {
-                let content = fs::read(&path)?;
-                config_files.push(ConfigFile {
-                    name: file_name.to_string(),
-                    content,
-                });
-            }
-        }
-    }
-
-    // Collect provider configs from providers/ subdirectory
-    let providers_dir = config_dir.join("providers");
-    if providers_dir.exists() {
-        for entry in fs::read_dir(&providers_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("toml") {
-                let content = fs::read(&path)?;
-                let name = format!("providers/{}", path.file_name().unwrap().to_string_lossy());
-                config_files.push(ConfigFile { name, content });
-            }
-        }
-    }
-
-    // Check for embeddings directory and include any database files there
-    let embeddings_dir = config_dir.join("embeddings");
-    if embeddings_dir.exists() {
-        for entry in fs::read_dir(&embeddings_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("db") {
-                let content = fs::read(&path)?;
-                let name = format!("embeddings/{}", path.file_name().unwrap().to_string_lossy());
-                config_files.push(ConfigFile { name, content });
+    // Collect all configuration files (plus any requested optional data)
+    let mut config_files = collect_sync_files(&config_dir, include, with_keys)?;
+
+    if with_keys {
+        if config_files.iter().any(|f| f.name == KEYS_FILE) {
+            println!(
+                "{} Encrypting API keys with the key sync passphrase...",
+                sym("🔑")
+            );
+            let passphrase = keys_passphrase(true)?;
+            for file in config_files.iter_mut().filter(|f| f.name == KEYS_FILE) {
+                *file = encrypt_keys_file(file, &passphrase)?;
             }
+        } else {
+            println!(
+                "{} No keys.toml found, nothing to sync for --with-keys",
+                sym("ℹ️").blue()
+            );
         }
+    } else if config_dir.join(KEYS_FILE).exists() {
+        println!(
+            "{} keys.toml is not synced; use --with-keys to include API keys",
+            sym("ℹ️").blue()
+        );
     }
 
     if config_files.is_empty() {
-        println!("{} No configuration files found to sync", "ℹ️".blue());
+        println!("{} No configuration files found to sync", sym("ℹ️").blue());
         return Ok(());
     }
 
@@ -173,7 +410,16 @@ pub async fn handle_sync_to(provider: &str, encrypted: bool, yes: bool) -> Resul
     if !yes {
         println!("\nFiles to sync:");
         for file in &config_files {
-            println!("  • {}", file.name);
+            if optional_category(&file.name).is_some() {
+                println!(
+                    "  {} {} {}",
+                    sym("•"),
+                    file.name,
+                    format!("({} compressed)", format_size(file.content.len())).dimmed()
+                );
+            } else {
+                println!("  {} {}", sym("•"), file.name);
+            }
         }
 
         print!("\nContinue with sync? [y/N]: ");
@@ -190,7 +436,7 @@ pub async fn handle_sync_to(provider: &str, encrypted: bool, yes: bool) -> Resul
 
     // Encrypt files if requested
     let _files_to_upload = if encrypted {
-        println!("🔐 Encrypting configuration files...");
+        println!("{} Encrypting configuration files...", sym("🔐"));
         encrypt_files(&config_files)?
     } else {
         config_files
@@ -200,7 +446,7 @@ pub async fn handle_sync_to(provider: &str, encrypted: bool, yes: bool) -> Resul
     {
         use super::s3::upload_to_s3_provider;
         upload_to_s3_provider(&_files_to_upload, provider, encrypted).await?;
-        println!("{} Configuration synced successfully!", "✅".green());
+        println!("{} Configuration synced successfully!", sym("✅").green());
         Ok(())
     }
 
@@ -211,12 +457,21 @@ pub async fn handle_sync_to(provider: &str, encrypted: bool, yes: bool) -> Resul
 }
 
 /// Sync configuration files from cloud storage
-pub async fn handle_sync_from(provider: &str, _encrypted: bool, yes: bool) -> Result<()> {
+pub async fn handle_sync_from(
+    provider: &str,
+    _encrypted: bool,
+    yes: bool,
+    _include: &[SyncInclude],
+    _with_keys: bool,
+) -> Result<()> {
     use std::fs;
     use std::io::{self, Write};
 
+    crate::lock::ensure_writable("sync configuration from the cloud")?;
+
     println!(
-        "📥 {} configuration from {}...",
+        "{} {} configuration from {}...",
+        sym("📥"),
         "Syncing".cyan(),
         provider.bold()
     );
@@ -237,7 +492,8 @@ pub async fn handle_sync_from(provider: &str, _encrypted: bool, yes: bool) -> Re
     // Confirm before syncing
     if !yes {
         println!(
-            "\n⚠️  {} This will overwrite local configuration files!",
+            "\n{}  {} This will overwrite local configuration files!",
+            sym("⚠️"),
             "Warning:".yellow()
         );
         print!("Continue with sync? [y/N]: ");
@@ -256,26 +512,61 @@ pub async fn handle_sync_from(provider: &str, _encrypted: bool, yes: bool) -> Re
     {
         use super::s3::download_from_s3_provider;
         let _downloaded_files: Vec<ConfigFile> =
-            download_from_s3_provider(provider, _encrypted).await?;
+            download_from_s3_provider(provider, _encrypted, |name| {
+                is_selected(name, _include, _with_keys)
+            })
+            .await?;
 
         println!("Downloaded {} configuration files", _downloaded_files.len());
 
         // Decrypt files if they were encrypted
-        let files_to_save = if _encrypted {
-            println!("🔓 Decrypting configuration files...");
+        let mut files_to_save = if _encrypted {
+            println!("{} Decrypting configuration files...", sym("🔓"));
             decrypt_files(&_downloaded_files)?
         } else {
             _downloaded_files
         };
 
+        // Decrypt and verify synced keys before anything is written locally
+        if _with_keys {
+            if files_to_save
+                .iter()
+                .any(|f| f.name == ENCRYPTED_KEYS_FILE || f.name == KEYS_FILE)
+            {
+                let passphrase = if files_to_save.iter().any(|f| f.name == ENCRYPTED_KEYS_FILE) {
+                    Some(keys_passphrase(false)?)
+                } else {
+                    None
+                };
+                for file in files_to_save.iter_mut() {
+                    if file.name == ENCRYPTED_KEYS_FILE {
+                        *file = decrypt_keys_file(file, passphrase.as_deref().unwrap_or_default())
+                            .map_err(|e| {
+                                anyhow::anyhow!("{}. Local keys.toml was left unchanged", e)
+                            })?;
+                        println!("  {} Verified synced API keys", sym("🔑").green());
+                    } else if file.name == KEYS_FILE {
+                        // Plain keys.toml uploaded by older versions
+                        verify_keys_file(file)?;
+                    }
+                }
+            } else {
+                println!(
+                    "{} No synced keys found; local keys.toml left unchanged",
+                    sym("ℹ️").blue()
+                );
+            }
+        }
+
         // Save files to config directory
         for file in files_to_save {
+            let file = decompress_file(file)?;
+
             // Validate file name to prevent path traversal
-            if file.name.contains("..") || file.name.starts_with('/') || file.name.starts_with('\\')
-            {
+            if !is_safe_file_name(&file.name) {
                 println!(
                     "  {} Skipped invalid file name: {}",
-                    "⚠️".yellow(),
+                    sym("⚠️").yellow(),
                     file.name
                 );
                 continue;
@@ -298,10 +589,10 @@ pub async fn handle_sync_from(provider: &str, _encrypted: bool, yes: bool) -> Re
 
             let mut f = options.open(&file_path)?;
             f.write_all(&file.content)?;
-            println!("  ✓ Saved {}", file.name);
+            println!("  {} Saved {}", sym("✓"), file.name);
         }
 
-        println!("{} Configuration synced successfully!", "✅".green());
+        println!("{} Configuration synced successfully!", sym("✅").green());
         Ok(())
     }
 
@@ -4,21 +4,18 @@ use anyhow::{anyhow, Result};
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::Ordering;
 
 use crate::config::Config;
 
-/// Global debug mode flag
-static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
-
-/// Set the global debug mode
+/// Set the global debug mode (all modules at the debug level)
 pub fn set_debug_mode(enabled: bool) {
-    DEBUG_MODE.store(enabled, Ordering::Relaxed);
+    crate::utils::logging::set_enabled(enabled);
 }
 
 /// Check if debug mode is enabled
 pub fn is_debug_mode() -> bool {
-    DEBUG_MODE.load(Ordering::Relaxed)
+    crate::utils::logging::is_enabled()
 }
 
 /// Determine if a file extension represents a code file
@@ -80,65 +77,165 @@ pub fn is_code_file(ext: &str) -> bool {
     code_extensions.contains(&ext.to_lowercase().as_str())
 }
 
-/// Read and format attachment files for inclusion in prompts
-pub fn read_and_format_attachments(attachments: &[String]) -> Result<String> {
-    if attachments.is_empty() {
-        return Ok(String::new());
-    }
+/// An attached file's name and contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub name: String,
+    pub extension: Option<String>,
+    pub content: String,
+}
 
-    let mut result = String::new();
+/// Read attachment files
+pub fn read_attachments(attachments: &[String]) -> Result<Vec<Attachment>> {
+    attachments
+        .iter()
+        .map(|attachment_path| {
+            let path = Path::new(attachment_path);
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_string);
+
+            // Read file content, through the reader for formats that have one
+            let content = match extension.as_deref().and_then(|ext| {
+                crate::readers::get_document_reader(ext)
+                    .or_else(|| crate::readers::get_table_reader(ext))
+            }) {
+                Some(reader) => reader.read_as_text(attachment_path)?,
+                None => fs::read_to_string(path)
+                    .map_err(|e| anyhow!("Failed to read file '{}': {}", attachment_path, e))?,
+            };
+
+            Ok(Attachment {
+                name,
+                extension,
+                content,
+            })
+        })
+        .collect()
+}
 
-    for attachment_path in attachments {
-        let path = Path::new(attachment_path);
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown");
+/// Read attachments, downloading the ones given as `s3://`, `gs://` or `http(s)://` URLs
+pub async fn load_attachments(sources: &[String]) -> Result<Vec<Attachment>> {
+    let mut attachments = Vec::with_capacity(sources.len());
+    for source in sources {
+        if crate::readers::remote::is_remote(source) {
+            attachments.push(crate::readers::remote::read(source).await?);
+        } else {
+            attachments.extend(read_attachments(std::slice::from_ref(source))?);
+        }
+    }
+    Ok(attachments)
+}
 
-        // Read file content
-        let content = fs::read_to_string(path)
-            .map_err(|e| anyhow!("Failed to read file '{}': {}", attachment_path, e))?;
+/// Format attachments for inclusion in prompts
+pub fn format_attachments(attachments: &[Attachment]) -> String {
+    let mut result = String::new();
 
+    for attachment in attachments {
         // Add file header
-        result.push_str(&format!("=== File: {} ===\n", filename));
+        result.push_str(&format!("=== File: {} ===\n", attachment.name));
 
         // Check if this is a code file based on extension
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            if is_code_file(ext) {
-                result.push_str(&format!("```{}\n{}\n```\n", ext.to_lowercase(), content));
-            } else {
-                result.push_str(&content);
+        match attachment.extension.as_deref() {
+            Some(ext) if is_code_file(ext) => {
+                result.push_str(&format!(
+                    "```{}\n{}\n```\n",
+                    ext.to_lowercase(),
+                    attachment.content
+                ));
             }
-        } else {
-            result.push_str(&content);
+            _ => result.push_str(&attachment.content),
         }
 
         result.push('\n');
     }
 
-    Ok(result)
+    result
+}
+
+/// Read and format attachment files for inclusion in prompts
+pub fn read_and_format_attachments(attachments: &[String]) -> Result<String> {
+    Ok(format_attachments(&read_attachments(attachments)?))
+}
+
+/// Check if auto-provider mode is enabled for this invocation
+pub fn is_auto_provider_mode() -> bool {
+    crate::AUTO_PROVIDER_MODE.load(Ordering::Relaxed)
+}
+
+/// Provider for a model given without provider prefix, when `--auto-provider` is on
+/// and exactly one configured provider has the model in its models cache. Prints a
+/// note naming the provider; when several have it, the usual default applies.
+pub fn auto_detect_provider(config: &Config, model: &str) -> Option<String> {
+    if !is_auto_provider_mode() || model.contains(':') || config.aliases.contains_key(model) {
+        return None;
+    }
+
+    let providers = crate::unified_cache::UnifiedCache::providers_with_model(config, model);
+    match providers.as_slice() {
+        [provider] => {
+            eprintln!(
+                "Note: using provider '{}' for model '{}' (the only provider that has it)",
+                provider, model
+            );
+            Some(provider.clone())
+        }
+        [] => None,
+        several => {
+            eprintln!(
+                "Note: model '{}' is available from {}; pass -p or provider:model to choose",
+                model,
+                several.join(", ")
+            );
+            None
+        }
+    }
 }
 
-/// Resolve model and provider from configuration and CLI overrides
+/// Resolve model and provider from configuration and CLI overrides. Models the provider
+/// no longer lists are mapped to its current ID (see `models::model_aliases`).
 pub fn resolve_model_and_provider(
     config: &Config,
     provider_override: Option<String>,
     model_override: Option<String>,
+) -> Result<(String, String)> {
+    let (provider, model) = resolve_configured_model(config, provider_override, model_override)?;
+    let model = crate::models::model_aliases::resolve(&provider, &model);
+    Ok((provider, model))
+}
+
+fn resolve_configured_model(
+    config: &Config,
+    provider_override: Option<String>,
+    model_override: Option<String>,
 ) -> Result<(String, String)> {
     // Store whether we have explicit provider override to avoid borrow issues
     let has_provider_override = provider_override.is_some();
 
     let provider = match provider_override {
         Some(p) => {
-            if !config.providers.contains_key(&p) {
+            if !crate::provider_groups::provider_exists(config, &p) {
                 return Err(anyhow!("Provider '{}' not found in configuration", p));
             }
             p
         }
-        None => config
-            .default_provider
-            .clone()
-            .ok_or_else(|| anyhow!("No default provider configured and none specified"))?,
+        None => match model_override
+            .as_deref()
+            .and_then(|m| auto_detect_provider(config, m))
+        {
+            Some(p) => p,
+            None => config
+                .default_provider
+                .clone()
+                .ok_or_else(|| anyhow!("No default provider configured and none specified"))?,
+        },
     };
 
     let model = match model_override {
@@ -150,7 +247,7 @@ pub fn resolve_model_and_provider(
                     let alias_model = model.to_string();
 
                     // Verify provider exists
-                    if !config.providers.contains_key(&alias_provider) {
+                    if !crate::provider_groups::provider_exists(config, &alias_provider) {
                         return Err(anyhow!(
                             "Provider '{}' not found in configuration",
                             alias_provider
@@ -169,7 +266,7 @@ pub fn resolve_model_and_provider(
                         let alias_model = model.to_string();
 
                         // Verify provider exists
-                        if !config.providers.contains_key(&alias_provider) {
+                        if !crate::provider_groups::provider_exists(config, &alias_provider) {
                             return Err(anyhow!(
                                 "Provider '{}' from alias not found in configuration",
                                 alias_provider
@@ -227,15 +324,9 @@ mod tests {
     #[test]
     fn test_resolve_model_basic() {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         config.providers.insert(
@@ -259,8 +350,12 @@ mod tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -268,4 +363,55 @@ mod tests {
         assert_eq!(provider, "openai");
         assert_eq!(model, "gpt-4");
     }
+
+    #[test]
+    fn test_providers_with_model() {
+        let provider_with = |models: &[&str]| ProviderConfig {
+            endpoint: "https://api.example.com".to_string(),
+            models_path: "/v1/models".to_string(),
+            chat_path: "/v1/chat/completions".to_string(),
+            images_path: None,
+            embeddings_path: None,
+            api_key: None,
+            models: models.iter().map(|m| m.to_string()).collect(),
+            headers: HashMap::new(),
+            token_url: None,
+            cached_token: None,
+            auth_type: None,
+            vars: HashMap::new(),
+            chat_templates: None,
+            images_templates: None,
+            embeddings_templates: None,
+            models_templates: None,
+            audio_path: None,
+            speech_path: None,
+            rerank_path: None,
+            audio_templates: None,
+            speech_templates: None,
+            organization: None,
+            project: None,
+            hmac: None,
+        };
+
+        let mut config = Config::default();
+        config.providers.insert(
+            "lc-auto-test-a".to_string(),
+            provider_with(&["lc-auto-only-a", "lc-auto-shared"]),
+        );
+        config.providers.insert(
+            "lc-auto-test-b".to_string(),
+            provider_with(&["lc-auto-shared"]),
+        );
+
+        let providers_with =
+            |model: &str| crate::unified_cache::UnifiedCache::providers_with_model(&config, model);
+        assert_eq!(providers_with("lc-auto-only-a"), vec!["lc-auto-test-a"]);
+        assert_eq!(
+            providers_with("lc-auto-shared"),
+            vec!["lc-auto-test-a", "lc-auto-test-b"]
+        );
+        assert!(providers_with("lc-auto-missing").is_empty());
+        // Without --auto-provider nothing is detected
+        assert_eq!(auto_detect_provider(&config, "lc-auto-only-a"), None);
+    }
 }
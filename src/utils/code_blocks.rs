@@ -0,0 +1,278 @@
+//! Fenced code block extraction from markdown answers
+//!
+//! Used by `lc logs recent answer code`. Besides the code itself, each block keeps
+//! the fence language and a filename hint when the answer names the file, either in
+//! the fence info (```` ```rust src/main.rs ```` or ```` ```rust title="main.rs" ````),
+//! on the line just before the fence (`**src/main.rs**`, `` `main.rs`: ``,
+//! `File: main.rs`), or in a first-line comment inside the block (`// main.rs`).
+
+use anyhow::Result;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub filename: Option<String>,
+    pub content: String,
+}
+
+/// Extract the fenced code blocks from markdown text, skipping empty ones
+pub fn extract(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, Option<String>, String)> = None;
+    let mut previous_line = "";
+
+    for line in text.lines() {
+        if let Some(info) = line.strip_prefix("```") {
+            match current.take() {
+                Some((language, filename, content)) => {
+                    push_block(&mut blocks, language, filename, content)
+                }
+                None => {
+                    let (language, filename) = parse_fence_info(info);
+                    let filename = filename.or_else(|| filename_from_heading(previous_line));
+                    current = Some((language, filename, String::new()));
+                }
+            }
+        } else if let Some((_, _, content)) = current.as_mut() {
+            content.push_str(line);
+            content.push('\n');
+        }
+        if !line.trim().is_empty() {
+            previous_line = line;
+        }
+    }
+
+    // Handle case where code block doesn't end properly
+    if let Some((language, filename, content)) = current {
+        push_block(&mut blocks, language, filename, content);
+    }
+
+    blocks
+}
+
+fn push_block(
+    blocks: &mut Vec<CodeBlock>,
+    language: Option<String>,
+    filename: Option<String>,
+    content: String,
+) {
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return;
+    }
+    let filename = filename.or_else(|| filename_from_comment(&content));
+    blocks.push(CodeBlock {
+        language,
+        filename,
+        content,
+    });
+}
+
+/// Language and filename from a fence info string such as `rust`, `rust:src/lib.rs`,
+/// `python title="app.py"` or a bare `src/main.rs`
+fn parse_fence_info(info: &str) -> (Option<String>, Option<String>) {
+    let mut words = info.split_whitespace();
+    let Some(first) = words.next() else {
+        return (None, None);
+    };
+
+    let (language, mut filename) = match first.split_once(':') {
+        Some((language, file)) if looks_like_filename(file) => {
+            (Some(language.to_string()), Some(file.to_string()))
+        }
+        _ if looks_like_filename(first) => (None, Some(first.to_string())),
+        _ => (Some(first.to_string()), None),
+    };
+
+    for word in words {
+        if filename.is_some() {
+            break;
+        }
+        let value = word
+            .strip_prefix("title=")
+            .or_else(|| word.strip_prefix("file="))
+            .or_else(|| word.strip_prefix("filename="))
+            .unwrap_or(word)
+            .trim_matches(|c| c == '"' || c == '\'');
+        if looks_like_filename(value) {
+            filename = Some(value.to_string());
+        }
+    }
+
+    let language = language.map(|l| l.to_lowercase()).filter(|l| !l.is_empty());
+    (language, filename)
+}
+
+/// A filename given on the line before a fence: `**src/main.rs**`, `` `main.rs`: ``,
+/// `File: main.rs` or `### main.rs`
+fn filename_from_heading(line: &str) -> Option<String> {
+    let mut line = line.trim().trim_start_matches('#').trim();
+    for prefix in ["File:", "file:", "Filename:", "filename:"] {
+        if let Some(rest) = line.strip_prefix(prefix) {
+            line = rest.trim();
+        }
+    }
+    let candidate = line
+        .trim_end_matches(':')
+        .trim_matches(|c| c == '*' || c == '`' || c == '"' || c == '_');
+    if candidate.contains(char::is_whitespace) {
+        return None;
+    }
+    looks_like_filename(candidate).then(|| candidate.to_string())
+}
+
+/// A filename in a first-line comment such as `// src/main.rs` or `# filename: app.py`
+fn filename_from_comment(content: &str) -> Option<String> {
+    let first = content.lines().next()?.trim();
+    let comment = ["//", "#", "--", "/*", "<!--"]
+        .iter()
+        .find_map(|marker| first.strip_prefix(marker))?;
+    let comment = comment
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim();
+    let candidate = ["filename:", "file:", "File:", "Filename:"]
+        .iter()
+        .find_map(|prefix| comment.strip_prefix(prefix))
+        .unwrap_or(comment)
+        .trim();
+    if candidate.contains(char::is_whitespace) {
+        return None;
+    }
+    looks_like_filename(candidate).then(|| candidate.to_string())
+}
+
+/// Whether a string looks like a relative file path with an extension
+fn looks_like_filename(s: &str) -> bool {
+    let Some((stem, ext)) = s.rsplit_once('.') else {
+        return false;
+    };
+    !stem.is_empty()
+        && !stem.ends_with('/')
+        && !ext.is_empty()
+        && ext.len() <= 10
+        && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'))
+}
+
+/// File extension for a fence language, falling back to `txt`
+pub fn extension_for_language(language: Option<&str>) -> &'static str {
+    match language.unwrap_or_default() {
+        "rust" | "rs" => "rs",
+        "python" | "py" | "python3" => "py",
+        "javascript" | "js" | "node" => "js",
+        "typescript" | "ts" => "ts",
+        "tsx" => "tsx",
+        "jsx" => "jsx",
+        "go" | "golang" => "go",
+        "java" => "java",
+        "kotlin" | "kt" => "kt",
+        "swift" => "swift",
+        "c" => "c",
+        "cpp" | "c++" | "cxx" => "cpp",
+        "csharp" | "cs" | "c#" => "cs",
+        "ruby" | "rb" => "rb",
+        "php" => "php",
+        "scala" => "scala",
+        "bash" | "sh" | "shell" | "zsh" | "console" => "sh",
+        "fish" => "fish",
+        "powershell" | "ps1" | "pwsh" => "ps1",
+        "html" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "xml" => "xml",
+        "sql" => "sql",
+        "markdown" | "md" => "md",
+        "dockerfile" | "docker" => "dockerfile",
+        "makefile" | "make" => "mk",
+        "lua" => "lua",
+        "r" => "r",
+        "haskell" | "hs" => "hs",
+        "elixir" | "ex" => "ex",
+        "zig" => "zig",
+        "dart" => "dart",
+        _ => "txt",
+    }
+}
+
+/// Relative path a block is written to: its filename hint, or `block_<n>.<ext>`
+pub fn file_name_for(block: &CodeBlock, index: usize) -> String {
+    block.filename.clone().unwrap_or_else(|| {
+        format!(
+            "block_{}.{}",
+            index + 1,
+            extension_for_language(block.language.as_deref())
+        )
+    })
+}
+
+/// Resolve a block's relative path under `dir`, refusing paths that would escape it
+pub fn target_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let relative = Path::new(name);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        anyhow::bail!("Refusing to write '{}' outside the output directory", name);
+    }
+    Ok(dir.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_languages_and_filename_hints() {
+        let answer = "Here is the crate:\n\n\
+            **src/main.rs**\n```rust\nfn main() {}\n```\n\n\
+            ```python title=\"tools/gen.py\"\nprint(1)\n```\n\n\
+            ```js\n// web/app.js\nconsole.log(1)\n```\n\n\
+            ```bash\necho hi\n```\n\n```\n\n```\n";
+        let blocks = extract(answer);
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[0].filename.as_deref(), Some("src/main.rs"));
+        assert_eq!(blocks[0].content, "fn main() {}");
+        assert_eq!(blocks[1].filename.as_deref(), Some("tools/gen.py"));
+        assert_eq!(blocks[2].filename.as_deref(), Some("web/app.js"));
+        assert_eq!(blocks[3].filename, None);
+        assert_eq!(file_name_for(&blocks[3], 3), "block_4.sh");
+    }
+
+    #[test]
+    fn test_fence_info_forms() {
+        assert_eq!(
+            parse_fence_info("rust:src/lib.rs"),
+            (Some("rust".to_string()), Some("src/lib.rs".to_string()))
+        );
+        assert_eq!(
+            parse_fence_info("Cargo.toml"),
+            (None, Some("Cargo.toml".to_string()))
+        );
+        assert_eq!(
+            parse_fence_info("TypeScript"),
+            (Some("typescript".to_string()), None)
+        );
+        assert_eq!(parse_fence_info(""), (None, None));
+        // Prose before a fence is not a filename
+        assert_eq!(filename_from_heading("Run this in main.rs:"), None);
+    }
+
+    #[test]
+    fn test_target_path_stays_in_dir() {
+        let dir = Path::new("out");
+        assert_eq!(
+            target_path(dir, "src/main.rs").unwrap(),
+            Path::new("out/src/main.rs")
+        );
+        assert!(target_path(dir, "../evil.sh").is_err());
+        assert!(target_path(dir, "/etc/passwd").is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Leveled debug logging with module filters
+//!
+//! `--debug` (or `LC_DEBUG`) takes an optional comma-separated filter. Each item is one of:
+//!
+//! - a level (`error`, `warn`, `info`, `debug`, `trace`): the level for everything else
+//! - a module (`mcp`, `http`, `core::chat`): log only the listed modules
+//! - `module=level`: log a module at its own level
+//! - `-module`: never log a module
+//!
+//! A module name matches any segment of the call site's module path, or any `_`-separated
+//! word of a segment, so `http` covers `core::http_client` and `cache` covers
+//! `models::unified_cache`. Without module items every module is logged. Plain `--debug`
+//! logs everything at the `debug` level, as before, and so do `LC_DEBUG=1`, `true` and
+//! `on`; `LC_DEBUG=0`, `false` and `off` turn logging off.
+//!
+//! Messages are written with [`debug_log!`](crate::debug_log) and
+//! [`trace_log!`](crate::trace_log) (or [`log_at!`](crate::log_at) for another level).
+
+use anyhow::{bail, Result};
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+impl std::str::FromStr for Level {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => bail!(
+                "Unknown log level '{}'. Use error, warn, info, debug or trace",
+                s
+            ),
+        }
+    }
+}
+
+/// Which messages are logged, parsed from a `--debug` value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    level: Level,
+    modules: Vec<(String, Option<Level>)>,
+    excluded: Vec<String>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            level: Level::Debug,
+            modules: Vec::new(),
+            excluded: Vec::new(),
+        }
+    }
+}
+
+impl Filter {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut filter = Filter::default();
+        for item in spec.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            if item == "all" || item == "*" {
+                continue;
+            }
+            if let Some(module) = item.strip_prefix('-') {
+                filter.excluded.push(module_name(module)?);
+            } else if let Some((module, level)) = item.split_once('=') {
+                filter
+                    .modules
+                    .push((module_name(module)?, Some(level.trim().parse()?)));
+            } else if let Ok(level) = item.parse() {
+                filter.level = level;
+            } else {
+                filter.modules.push((module_name(item)?, None));
+            }
+        }
+        Ok(filter)
+    }
+
+    /// The most verbose level logged for `module_path`, if any
+    pub fn level_for(&self, module_path: &str) -> Option<Level> {
+        if self.excluded.iter().any(|m| matches(m, module_path)) {
+            return None;
+        }
+        if self.modules.is_empty() {
+            return Some(self.level);
+        }
+        self.modules
+            .iter()
+            .find(|(m, _)| matches(m, module_path))
+            .map(|(_, level)| level.unwrap_or(self.level))
+    }
+
+    fn max_level(&self) -> Level {
+        self.modules
+            .iter()
+            .map(|(_, level)| level.unwrap_or(self.level))
+            .max()
+            .unwrap_or(self.level)
+    }
+}
+
+fn module_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+    {
+        bail!("Invalid module '{}' in debug filter", name);
+    }
+    Ok(name.to_lowercase())
+}
+
+fn matches(module: &str, module_path: &str) -> bool {
+    let path = short_path(module_path);
+    if module.contains("::") {
+        return path == module
+            || path.starts_with(&format!("{}::", module))
+            || path.contains(&format!("::{}", module));
+    }
+    path.split("::")
+        .any(|segment| segment == module || segment.split('_').any(|word| word == module))
+}
+
+/// The module path without the crate name
+fn short_path(module_path: &str) -> &str {
+    module_path
+        .split_once("::")
+        .map_or(module_path, |(_, rest)| rest)
+}
+
+// 0 when logging is off, otherwise the most verbose level any module is logged at
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+/// Turn logging on with a `--debug` filter, or off with `None`
+pub fn configure(spec: Option<&str>) -> Result<()> {
+    let filter = spec.and_then(switch).map(Filter::parse).transpose()?;
+    set_filter(filter);
+    Ok(())
+}
+
+/// The filter of a `--debug` value, taking `LC_DEBUG=1` and `LC_DEBUG=0` as on and off
+fn switch(spec: &str) -> Option<&str> {
+    match spec.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some("all"),
+        "0" | "false" | "no" | "off" => None,
+        _ => Some(spec),
+    }
+}
+
+fn set_filter(filter: Option<Filter>) {
+    let max = filter.as_ref().map_or(0, |f| f.max_level() as u8);
+    if let Ok(mut current) = FILTER.write() {
+        *current = filter;
+    }
+    MAX_LEVEL.store(max, Ordering::Relaxed);
+    crate::DEBUG_MODE.store(max > 0, Ordering::Relaxed);
+}
+
+/// The old on/off switch: on logs everything at `debug` unless a filter is already set
+pub fn set_enabled(enabled: bool) {
+    if !enabled {
+        set_filter(None);
+    } else if !is_enabled() {
+        set_filter(Some(Filter::default()));
+    }
+}
+
+/// Whether any logging is on
+pub fn is_enabled() -> bool {
+    MAX_LEVEL.load(Ordering::Relaxed) > 0 || crate::DEBUG_MODE.load(Ordering::Relaxed)
+}
+
+/// Whether a message at `level` from `module_path` is logged
+pub fn enabled(level: Level, module_path: &str) -> bool {
+    let max = MAX_LEVEL.load(Ordering::Relaxed);
+    if max == 0 {
+        // `DEBUG_MODE` set by a library user, without a filter
+        return level <= Level::Debug && crate::DEBUG_MODE.load(Ordering::Relaxed);
+    }
+    if (level as u8) > max {
+        return false;
+    }
+    FILTER
+        .read()
+        .ok()
+        .and_then(|filter| filter.as_ref()?.level_for(module_path))
+        .is_some_and(|max| level <= max)
+}
+
+pub fn write(level: Level, module_path: &str, args: fmt::Arguments) {
+    use colored::Colorize;
+    let tag = format!("[{} {}]", level.label(), short_path(module_path));
+    let message = args.to_string();
+    eprintln!(
+        "{} {}",
+        tag.dimmed(),
+        crate::core::redaction::redact(&message)
+    );
+}
+
+/// Log at the given [`Level`] if the `--debug` filter allows it for the calling module
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::utils::logging::enabled($level, module_path!()) {
+            $crate::utils::logging::write($level, module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let all = Filter::parse("all").unwrap();
+        assert_eq!(
+            all.level_for("lc::models::unified_cache"),
+            Some(Level::Debug)
+        );
+
+        let filter = Filter::parse("mcp,http=trace,-mcp_daemon").unwrap();
+        assert_eq!(filter.level_for("lc::services::mcp"), Some(Level::Debug));
+        assert_eq!(
+            filter.level_for("lc::core::http_client"),
+            Some(Level::Trace)
+        );
+        assert_eq!(filter.level_for("lc::services::mcp_daemon"), None);
+        assert_eq!(filter.level_for("lc::models::unified_cache"), None);
+        assert_eq!(filter.max_level(), Level::Trace);
+
+        let filter = Filter::parse("trace,-cache,core::chat").unwrap();
+        assert_eq!(filter.level_for("lc::core::chat"), Some(Level::Trace));
+        assert_eq!(filter.level_for("lc::cli::chat"), None);
+        assert_eq!(filter.level_for("lc::core::tool_cache"), None);
+
+        assert!(Filter::parse("mcp=loud").is_err());
+        assert!(Filter::parse("mcp/http").is_err());
+    }
+
+    #[test]
+    fn test_env_switch() {
+        assert_eq!(switch("1"), Some("all"));
+        assert_eq!(switch("TRUE"), Some("all"));
+        assert_eq!(switch("0"), None);
+        assert_eq!(switch("off"), None);
+        assert_eq!(switch("mcp,http"), Some("mcp,http"));
+    }
+}
@@ -1,12 +1,22 @@
 // Utility modules
 pub mod audio;
 pub mod cli_utils;
+pub mod code_blocks;
 pub mod image;
 pub mod input;
+pub mod logging;
+pub mod piped_input;
 pub mod regex_cache;
+pub mod share_html;
+pub mod spinner;
+pub mod stream_render;
 pub mod template_processor;
+pub mod terminal;
 pub mod test;
+pub mod time_display;
 pub mod token;
+pub mod unified_diff;
+pub mod web_cache;
 
 // Re-export with old names for compatibility
 pub use audio as audio_utils;
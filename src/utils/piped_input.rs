@@ -0,0 +1,117 @@
+//! Reading prompts piped into lc
+//!
+//! Stdin is read in chunks rather than in one `read_to_string`, so a multi-MB pipe
+//! reports its progress (size and estimated tokens) on stderr while it is still
+//! arriving. With `--max-stdin-mb`, input beyond that size is rejected instead of being
+//! buffered without bound.
+
+use crate::utils::terminal::sym;
+use anyhow::Result;
+use std::io::{Read, Write};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Inputs from this size on report their progress while they are read
+const PROGRESS_THRESHOLD: usize = 1024 * 1024;
+
+/// Read piped input until EOF, failing once it exceeds `max_bytes`. `progress` is called
+/// with the number of bytes read so far after each chunk.
+pub fn read_limited(
+    mut reader: impl Read,
+    max_bytes: usize,
+    mut progress: impl FnMut(usize),
+) -> Result<String> {
+    let mut bytes = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if bytes.len().saturating_add(read) > max_bytes {
+            anyhow::bail!(
+                "Piped input is larger than {}. Raise the limit with --max-stdin-mb, or pass the file with -a instead",
+                format_size(max_bytes)
+            );
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        progress(bytes.len());
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Read piped stdin, showing progress on stderr for large inputs. Without `max_mb`, the
+/// input is not limited.
+pub fn read_stdin(max_mb: Option<u64>) -> Result<String> {
+    let max_bytes = max_mb.map_or(usize::MAX, |max_mb| {
+        usize::try_from(max_mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX)
+    });
+    let show_progress = atty::is(atty::Stream::Stderr);
+    let mut reported = 0;
+
+    let content = read_limited(std::io::stdin().lock(), max_bytes, |read| {
+        // Update at most once per MB, starting from the first MB
+        if show_progress && read >= PROGRESS_THRESHOLD && read - reported >= PROGRESS_THRESHOLD {
+            reported = read;
+            eprint!("\r{} Reading stdin: {}", sym("📥"), describe(read));
+            let _ = std::io::stderr().flush();
+        }
+    })?;
+
+    if content.len() >= PROGRESS_THRESHOLD {
+        let line = format!("{} Read {} from stdin", sym("📥"), describe(content.len()));
+        if show_progress {
+            eprintln!("\r{}", line);
+        } else {
+            crate::debug_log!("{}", line);
+        }
+    }
+    Ok(content)
+}
+
+/// Size and estimated token count of `bytes` of text (about 4 bytes per token)
+fn describe(bytes: usize) -> String {
+    format!(
+        "{} (~{} tokens)",
+        format_size(bytes),
+        format_tokens(bytes / 4)
+    )
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+fn format_tokens(tokens: usize) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{}k", tokens / 1_000)
+    } else {
+        tokens.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_limited() {
+        let input = "é".repeat(100_000);
+        let mut calls = 0;
+        let content = read_limited(input.as_bytes(), 1024 * 1024, |_| calls += 1).unwrap();
+        assert_eq!(content, input);
+        assert!(calls >= 3);
+
+        let error = read_limited(input.as_bytes(), 1024, |_| {}).unwrap_err();
+        assert!(error.to_string().contains("--max-stdin-mb"));
+
+        assert_eq!(describe(4 * 1024 * 1024), "4.0 MB (~1.0M tokens)");
+    }
+}
@@ -0,0 +1,517 @@
+//! Self-contained HTML export of a logged conversation for `lc logs share`
+//!
+//! Messages are rendered from markdown. Code blocks are highlighted at export time and
+//! the images attached to the session's questions are embedded as data URLs, so the page
+//! works offline, without scripts. Other local files are never read, even when a message
+//! points at them. Raw HTML in messages is shown as text.
+
+use crate::database::{short_id, LoggedChatEntry};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default)]
+pub struct ShareOptions {
+    /// Leave system prompts out of the page
+    pub redact_system_prompt: bool,
+}
+
+/// Render a session's log entries (oldest first) as a standalone HTML page
+pub fn render_session(
+    chat_id: &str,
+    entries: &[LoggedChatEntry],
+    options: &ShareOptions,
+) -> String {
+    let title = entries
+        .first()
+        .and_then(|first| first.entry.question.lines().find(|l| !l.trim().is_empty()))
+        .map(|line| truncate(line.trim(), 80))
+        .unwrap_or_else(|| format!("Conversation {}", short_id(chat_id)));
+
+    let mut models: Vec<&str> = Vec::new();
+    for logged in entries {
+        if !models.contains(&logged.entry.model.as_str()) {
+            models.push(&logged.entry.model);
+        }
+    }
+    let period = match (entries.first(), entries.last()) {
+        (Some(first), Some(last)) => {
+            let start = first.entry.timestamp.format("%Y-%m-%d %H:%M");
+            let end = last.entry.timestamp.format("%Y-%m-%d %H:%M");
+            if start.to_string() == end.to_string() {
+                format!("{} UTC", start)
+            } else {
+                format!("{} – {} UTC", start, end)
+            }
+        }
+        _ => String::new(),
+    };
+
+    // Only files the session attached may be embedded
+    let attached: HashSet<&str> = entries
+        .iter()
+        .flat_map(|logged| logged.images.iter().map(String::as_str))
+        .collect();
+
+    let mut body = String::new();
+    let mut previous_system: Option<&str> = None;
+    let mut redacted = false;
+    for logged in entries {
+        if let Some(system) = logged.system_prompt.as_deref() {
+            if options.redact_system_prompt {
+                redacted = true;
+            } else if previous_system != Some(system) {
+                body.push_str(&format!(
+                    "<section class=\"message system\"><div class=\"label\">System prompt</div>\
+                     <div class=\"content\">{}</div></section>\n",
+                    render_markdown(system, &attached)
+                ));
+            }
+        }
+        previous_system = logged.system_prompt.as_deref();
+
+        let images: String = logged
+            .images
+            .iter()
+            .filter_map(|path| {
+                let name = Path::new(path).file_name()?.to_string_lossy();
+                Some(format!(
+                    "<img src=\"{}\" alt=\"{}\">",
+                    escape(&embed_local_image(path, &attached)?),
+                    escape(&name)
+                ))
+            })
+            .collect();
+        body.push_str(&format!(
+            "<section class=\"message user\"><div class=\"label\">User</div>\
+             <div class=\"content\">{}{}</div></section>\n",
+            render_markdown(&logged.entry.question, &attached),
+            images
+        ));
+
+        let reasoning = logged
+            .reasoning
+            .as_deref()
+            .filter(|r| !r.trim().is_empty())
+            .map(|r| {
+                format!(
+                    "<details class=\"reasoning\"><summary>Reasoning</summary>{}</details>",
+                    render_markdown(r, &attached)
+                )
+            })
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "<section class=\"message assistant\"><div class=\"label\">{} <span class=\"time\">{}</span></div>\
+             {}<div class=\"content\">{}</div></section>\n",
+            escape(&logged.entry.model),
+            logged.entry.timestamp.format("%H:%M"),
+            reasoning,
+            render_markdown(&logged.entry.response, &attached)
+        ));
+    }
+
+    let note = if redacted {
+        " · system prompt redacted"
+    } else {
+        ""
+    };
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n\
+         <header><h1>{title}</h1><p class=\"meta\">{period} · {models} · {turns} turn{plural}{note}</p></header>\n\
+         {body}<footer>Session {session} · exported with lc</footer>\n</main>\n</body>\n</html>\n",
+        title = escape(&title),
+        period = escape(&period),
+        models = escape(&models.join(", ")),
+        turns = entries.len(),
+        plural = if entries.len() == 1 { "" } else { "s" },
+        note = note,
+        body = body,
+        session = escape(short_id(chat_id)),
+    )
+}
+
+/// Render markdown to HTML with highlighted code, the `attached` local images embedded,
+/// and raw HTML shown as text
+pub fn render_markdown(text: &str, attached: &HashSet<&str>) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut events = Vec::new();
+    let mut code: Option<(Option<String>, String)> = None;
+
+    for event in Parser::new_ext(text, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().map(|l| l.to_lowercase())
+                    }
+                    CodeBlockKind::Indented => None,
+                };
+                code = Some((language, String::new()));
+            }
+            Event::Text(text) if code.is_some() => {
+                if let Some((_, content)) = code.as_mut() {
+                    content.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, content)) = code.take() {
+                    let class = language
+                        .as_deref()
+                        .map(|l| format!(" class=\"language-{}\"", escape(l)))
+                        .unwrap_or_default();
+                    events.push(Event::Html(CowStr::from(format!(
+                        "<pre><code{}>{}</code></pre>\n",
+                        class,
+                        highlight(&content, language.as_deref())
+                    ))));
+                }
+            }
+            Event::Html(html) | Event::InlineHtml(html) => events.push(Event::Text(html)),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => events.push(Event::Start(Tag::Image {
+                link_type,
+                dest_url: if is_local(&dest_url) {
+                    CowStr::from(embed_local_image(&dest_url, attached).unwrap_or_default())
+                } else {
+                    dest_url
+                },
+                title,
+                id,
+            })),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                let dest_url = if is_script_url(&dest_url) {
+                    CowStr::from("#")
+                } else {
+                    dest_url
+                };
+                events.push(Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }))
+            }
+            other => events.push(other),
+        }
+    }
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+    html
+}
+
+fn is_local(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    !(lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("data:"))
+}
+
+/// A data URL for a local image file the session attached, when its content is an image
+fn embed_local_image(url: &str, attached: &HashSet<&str>) -> Option<String> {
+    let path = std::path::absolute(url.strip_prefix("file://").unwrap_or(url)).ok()?;
+    if !attached.contains(path.to_str()?) {
+        return None;
+    }
+    let data = std::fs::read(&path).ok()?;
+    let mime_type = image_mime_type(&data)?;
+    use base64::Engine as _;
+    Some(format!(
+        "data:{};base64,{}",
+        mime_type,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    ))
+}
+
+/// The MIME type of image data, from its signature
+fn image_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+fn is_script_url(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    url.starts_with("javascript:") || url.starts_with("vbscript:")
+}
+
+const KEYWORDS: &str =
+    "abstract and as async await break case catch class const continue crate def \
+     default defer del do done dyn elif else end enum esac export extends extern \
+     false fi final finally fn for from func function go if impl import in interface \
+     is lambda let local loop match mod move mut new nil none not null or package \
+     pass private protected pub raise ref return select self static struct super \
+     switch then this throw trait true try type undefined unsafe use var void where \
+     while with yield";
+
+/// Highlight comments, strings, numbers and keywords with `<span>` classes. Unknown or
+/// missing languages are only escaped.
+pub fn highlight(code: &str, language: Option<&str>) -> String {
+    let language = language.unwrap_or_default();
+    let line_comment = match language {
+        "python" | "py" | "ruby" | "rb" | "bash" | "sh" | "shell" | "zsh" | "fish" | "yaml"
+        | "yml" | "toml" | "r" | "perl" | "elixir" | "ex" | "dockerfile" | "makefile"
+        | "powershell" | "ps1" => "#",
+        "sql" | "lua" | "haskell" | "hs" => "--",
+        "rust" | "rs" | "javascript" | "js" | "typescript" | "ts" | "tsx" | "jsx" | "go"
+        | "java" | "c" | "cpp" | "c++" | "csharp" | "cs" | "swift" | "kotlin" | "kt" | "scala"
+        | "php" | "dart" | "zig" | "json" | "jsonc" => "//",
+        _ => return escape(code),
+    };
+    let block_comments = line_comment == "//";
+    // Rust and friends use ' for lifetimes and chars, which would open runaway strings
+    let single_quote_strings = !matches!(language, "rust" | "rs" | "ocaml");
+    let case_insensitive = language == "sql";
+
+    let chars: Vec<char> = code.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        let c = chars[i];
+
+        let span = if rest.starts_with(line_comment) {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |p| i + p);
+            Some(("c", end))
+        } else if block_comments && rest == "/*" {
+            let end = (i + 2..chars.len().saturating_sub(1))
+                .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                .map_or(chars.len(), |j| j + 2);
+            Some(("c", end))
+        } else if c == '"' || c == '`' || (c == '\'' && single_quote_strings) {
+            let mut j = i + 1;
+            while j < chars.len() && chars[j] != c {
+                if chars[j] == '\\' {
+                    j += 1;
+                }
+                j += 1;
+            }
+            Some(("s", (j + 1).min(chars.len())))
+        } else if c.is_ascii_digit() && (i == 0 || !is_word_char(chars[i - 1])) {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| !(c.is_ascii_alphanumeric() || c == '.' || c == '_'))
+                .map_or(chars.len(), |p| i + p);
+            Some(("n", end))
+        } else if is_word_char(c) && (i == 0 || !is_word_char(chars[i - 1])) {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| !is_word_char(c))
+                .map_or(chars.len(), |p| i + p);
+            let word: String = chars[i..end].iter().collect();
+            let word = if case_insensitive {
+                word.to_lowercase()
+            } else {
+                word
+            };
+            if is_keyword(KEYWORDS, &word) || (case_insensitive && is_keyword(SQL_KEYWORDS, &word))
+            {
+                Some(("k", end))
+            } else {
+                out.push_str(&escape(&chars[i..end].iter().collect::<String>()));
+                i = end;
+                continue;
+            }
+        } else {
+            None
+        };
+
+        match span {
+            Some((class, end)) => {
+                let text: String = chars[i..end].iter().collect();
+                out.push_str(&format!(
+                    "<span class=\"{}\">{}</span>",
+                    class,
+                    escape(&text)
+                ));
+                i = end;
+            }
+            None => {
+                out.push_str(&escape(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+const SQL_KEYWORDS: &str =
+    "by create delete desc asc distinct drop group having insert into join left limit \
+     on order right set table union update values when";
+
+fn is_keyword(keywords: &str, word: &str) -> bool {
+    keywords.split_whitespace().any(|k| k == word)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+const STYLE: &str = r#"
+:root { --bg: #ffffff; --fg: #1f2328; --muted: #656d76; --border: #d0d7de; --user: #f6f8fa; --code: #f6f8fa; --k: #cf222e; --s: #0a3069; --c: #6e7781; --n: #0550ae; }
+@media (prefers-color-scheme: dark) {
+  :root { --bg: #0d1117; --fg: #e6edf3; --muted: #8d96a0; --border: #30363d; --user: #161b22; --code: #161b22; --k: #ff7b72; --s: #a5d6ff; --c: #8b949e; --n: #79c0ff; }
+}
+* { box-sizing: border-box; }
+body { margin: 0; background: var(--bg); color: var(--fg); font: 16px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; }
+main { max-width: 860px; margin: 0 auto; padding: 32px 20px; }
+header h1 { font-size: 1.5em; margin: 0 0 4px; }
+.meta, footer, .label .time { color: var(--muted); font-size: 0.85em; }
+.message { border: 1px solid var(--border); border-radius: 8px; padding: 12px 16px; margin: 16px 0; }
+.message.user { background: var(--user); }
+.message.system { border-style: dashed; }
+.label { font-weight: 600; font-size: 0.85em; margin-bottom: 4px; }
+.content > :first-child { margin-top: 0; }
+.content > :last-child { margin-bottom: 0; }
+.content img { max-width: 100%; }
+.content table { border-collapse: collapse; }
+.content th, .content td { border: 1px solid var(--border); padding: 4px 8px; }
+pre { background: var(--code); border: 1px solid var(--border); border-radius: 6px; padding: 12px; overflow-x: auto; }
+code { font: 0.9em/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+:not(pre) > code { background: var(--code); padding: 0.1em 0.3em; border-radius: 4px; }
+.k { color: var(--k); } .s { color: var(--s); } .c { color: var(--c); font-style: italic; } .n { color: var(--n); }
+details.reasoning { color: var(--muted); border-left: 3px solid var(--border); padding-left: 12px; margin-bottom: 8px; }
+details.reasoning summary { cursor: pointer; }
+footer { margin-top: 32px; text-align: center; }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ChatEntry;
+
+    fn logged(question: &str, response: &str, system_prompt: Option<&str>) -> LoggedChatEntry {
+        LoggedChatEntry {
+            entry: ChatEntry {
+                chat_id: "0123456789abcdef".to_string(),
+                model: "gpt-4o".to_string(),
+                question: question.to_string(),
+                response: response.to_string(),
+                timestamp: chrono::Utc::now(),
+                input_tokens: None,
+                output_tokens: None,
+                provider: Some("openai".to_string()),
+            },
+            user: None,
+            metadata: Default::default(),
+            system_prompt: system_prompt.map(str::to_string),
+            reasoning: None,
+            images: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_html_and_highlights_code() {
+        let html = render_markdown(
+            "Hi <script>alert(1)</script> [x](javascript:alert(1))\n\n```rust\nlet s = \"a<b\"; // note\n```\n",
+            &HashSet::new(),
+        );
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<code class=\"language-rust\">"));
+        assert!(html.contains("<span class=\"k\">let</span>"));
+        assert!(html.contains("<span class=\"s\">&quot;a&lt;b&quot;</span>"));
+        assert!(html.contains("<span class=\"c\">// note</span>"));
+    }
+
+    #[test]
+    fn test_only_attached_images_are_embedded() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("plot.png");
+        std::fs::write(&image, [0x89, b'P', b'N', b'G']).unwrap();
+        let fake = dir.path().join("secret.png");
+        std::fs::write(&fake, "password=hunter2").unwrap();
+        let other = dir.path().join("other.png");
+        std::fs::write(&other, [0x89, b'P', b'N', b'G']).unwrap();
+        let attached: HashSet<&str> = [image.to_str().unwrap(), fake.to_str().unwrap()]
+            .into_iter()
+            .collect();
+
+        let html = render_markdown(&format!("![plot]({})", image.display()), &attached);
+        assert!(html.contains("src=\"data:image/png;base64,"));
+        // Attached, but not an image
+        let html = render_markdown(&format!("![x]({})", fake.display()), &attached);
+        assert!(!html.contains("data:"));
+        // An image the session didn't attach
+        let html = render_markdown(&format!("![x]({})", other.display()), &attached);
+        assert!(!html.contains("data:"));
+        assert!(!html.contains("other.png\""));
+        let remote = render_markdown("![logo](https://example.com/logo.png)", &attached);
+        assert!(remote.contains("src=\"https://example.com/logo.png\""));
+
+        let mut entry = logged("Plot this", "Done", None);
+        entry.images = vec![image.to_string_lossy().into_owned()];
+        let page = render_session("0123456789abcdef", &[entry], &ShareOptions::default());
+        assert!(page.contains("alt=\"plot.png\""));
+    }
+
+    #[test]
+    fn test_render_session_redacts_system_prompt() {
+        let entries = vec![
+            logged("First question", "**Answer**", Some("Be terse")),
+            logged("Second", "More", Some("Be terse")),
+        ];
+
+        let page = render_session("0123456789abcdef", &entries, &ShareOptions::default());
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>First question</title>"));
+        assert!(page.contains("<strong>Answer</strong>"));
+        // An unchanged system prompt is shown once
+        assert_eq!(page.matches("Be terse").count(), 1);
+
+        let redacted = render_session(
+            "0123456789abcdef",
+            &entries,
+            &ShareOptions {
+                redact_system_prompt: true,
+            },
+        );
+        assert!(!redacted.contains("Be terse"));
+        assert!(redacted.contains("system prompt redacted"));
+    }
+}
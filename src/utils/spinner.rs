@@ -0,0 +1,249 @@
+//! Live indicator shown on stderr while waiting for a model's answer
+//!
+//! Until the first token arrives, a spinner shows the model and the seconds elapsed, and
+//! once the wait gets long, that Ctrl+C aborts. While tools run, it turns into a progress
+//! line with the step, the tools called and a bar of the iterations used. Code printing
+//! meanwhile calls [`clear`] first, so the indicator is redrawn after that output instead
+//! of being mixed into it. Nothing is shown when stderr is not a terminal, with `--debug`
+//! or with `--deterministic`.
+
+use colored::Colorize;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(100);
+/// Wait after which the Ctrl+C hint is shown
+const ABORT_HINT_AFTER: Duration = Duration::from_secs(5);
+const FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const ASCII_FRAMES: &[char] = &['|', '/', '-', '\\'];
+const BAR_WIDTH: u32 = 10;
+/// Tool names listed on the progress line; the others are counted
+const TOOLS_SHOWN: usize = 2;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+struct State {
+    generation: u64,
+    model: String,
+    started: Instant,
+    phase: Phase,
+    frame: usize,
+    /// Width of what is on screen, erased before the next draw
+    drawn: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Phase {
+    Waiting,
+    Tools {
+        step: u32,
+        max: u32,
+        tools: Vec<String>,
+    },
+}
+
+/// Keeps the indicator running until dropped
+#[must_use = "the indicator stops when the guard is dropped"]
+pub struct Spinner {
+    owner: bool,
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        if self.owner {
+            stop();
+        }
+    }
+}
+
+/// Show the indicator for a request to `model` until the returned guard is dropped or
+/// [`stop`] is called. While an indicator is running, this only changes its model.
+pub fn start(model: &str) -> Spinner {
+    if !enabled() {
+        return Spinner { owner: false };
+    }
+    let mut state = lock();
+    if let Some(state) = state.as_mut() {
+        state.model = model.to_string();
+        return Spinner { owner: false };
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    *state = Some(State {
+        generation,
+        model: model.to_string(),
+        started: Instant::now(),
+        phase: Phase::Waiting,
+        frame: 0,
+        drawn: 0,
+    });
+    ACTIVE.store(true, Ordering::Relaxed);
+    drop(state);
+    std::thread::spawn(move || tick(generation));
+    Spinner { owner: true }
+}
+
+/// Switch the indicator to the progress of a tool loop: `step` of at most `max`,
+/// running `tools`
+pub fn tool_progress(step: u32, max: u32, tools: &[&str]) {
+    if !ACTIVE.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(state) = lock().as_mut() {
+        state.phase = Phase::Tools {
+            step,
+            max,
+            tools: tools.iter().map(|tool| tool.to_string()).collect(),
+        };
+    }
+}
+
+/// Remove the indicator, e.g. before the first token is printed
+pub fn stop() {
+    if !ACTIVE.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    if let Some(state) = lock().take() {
+        erase(&mut std::io::stderr().lock(), state.drawn);
+    }
+}
+
+/// Erase the indicator so other output can be printed; it is drawn again on the next tick
+pub fn clear() {
+    if !ACTIVE.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(state) = lock().as_mut() {
+        erase(&mut std::io::stderr().lock(), state.drawn);
+        state.drawn = 0;
+    }
+}
+
+fn enabled() -> bool {
+    atty::is(atty::Stream::Stderr)
+        && !crate::utils::logging::is_enabled()
+        && !crate::core::deterministic::is_enabled()
+}
+
+fn lock() -> MutexGuard<'static, Option<State>> {
+    STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Redraw the indicator until it is stopped. The first draw waits one tick, so fast
+/// answers never show it.
+fn tick(generation: u64) {
+    loop {
+        std::thread::sleep(TICK);
+        let mut state = lock();
+        let Some(state) = state
+            .as_mut()
+            .filter(|state| state.generation == generation)
+        else {
+            return;
+        };
+        let line = render(
+            &state.model,
+            &state.phase,
+            state.started.elapsed(),
+            state.frame,
+            crate::utils::terminal::ascii_mode(),
+        );
+        let mut stderr = std::io::stderr().lock();
+        erase(&mut stderr, state.drawn);
+        let _ = write!(stderr, "{}", line.dimmed());
+        let _ = stderr.flush();
+        state.drawn = line.chars().count();
+        state.frame += 1;
+    }
+}
+
+/// Move back over the `width` characters drawn and clear to the end of the line, which
+/// keeps whatever was printed before the indicator on the same line
+fn erase(out: &mut impl Write, width: usize) {
+    if width > 0 {
+        let _ = write!(out, "\x1b[{}D\x1b[K", width);
+        let _ = out.flush();
+    }
+}
+
+fn render(model: &str, phase: &Phase, elapsed: Duration, frame: usize, ascii: bool) -> String {
+    let frames = if ascii { ASCII_FRAMES } else { FRAMES };
+    let spinner = frames[frame % frames.len()];
+    let seconds = format!("{:.1}s", elapsed.as_secs_f64());
+    let mut line = match phase {
+        Phase::Waiting => format!("{} Waiting for {} · {}", spinner, model, seconds),
+        Phase::Tools { step, max, tools } => {
+            let filled = (step * BAR_WIDTH / (*max).max(1)).min(BAR_WIDTH) as usize;
+            let mut names = tools
+                .iter()
+                .take(TOOLS_SHOWN)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            if tools.len() > TOOLS_SHOWN {
+                names.push_str(&format!(" +{}", tools.len() - TOOLS_SHOWN));
+            }
+            format!(
+                "{} [{}{}] Tool step {}/{} with {}: {} · {}",
+                spinner,
+                "█".repeat(filled),
+                "░".repeat(BAR_WIDTH as usize - filled),
+                step,
+                max,
+                model,
+                names,
+                seconds
+            )
+        }
+    };
+    if elapsed >= ABORT_HINT_AFTER {
+        line.push_str(" · Ctrl+C to abort");
+    }
+    if ascii {
+        crate::utils::terminal::to_ascii(&line)
+    } else {
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(
+                "gpt-4o",
+                &Phase::Waiting,
+                Duration::from_millis(1200),
+                1,
+                false
+            ),
+            "⠙ Waiting for gpt-4o · 1.2s"
+        );
+        assert_eq!(
+            render("gpt-4o", &Phase::Waiting, Duration::from_secs(7), 0, true),
+            "| Waiting for gpt-4o - 7.0s - Ctrl+C to abort"
+        );
+
+        let tools = Phase::Tools {
+            step: 3,
+            max: 10,
+            tools: vec!["search".into(), "fetch".into(), "read".into()],
+        };
+        assert_eq!(
+            render("gpt-4o", &tools, Duration::from_secs(2), 0, false),
+            "⠋ [███░░░░░░░] Tool step 3/10 with gpt-4o: search, fetch +1 · 2.0s"
+        );
+        assert_eq!(
+            render("gpt-4o", &tools, Duration::from_secs(2), 2, true),
+            "- [###.......] Tool step 3/10 with gpt-4o: search, fetch +1 - 2.0s"
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Markdown-aware rendering of streamed answers
+//!
+//! Prose is printed as it arrives, but fenced code blocks and tables are held back
+//! until they are complete and then printed at once: a code block when its closing
+//! fence arrives (with the fences dimmed), a table when a line that isn't a row follows
+//! it (with its columns aligned). Half-written fences and rows never reach the
+//! terminal, so nothing flickers or has to be redrawn. A code block longer than
+//! [`MAX_HELD_LINES`] is streamed from there on, line by line.
+//!
+//! Only used when stdout is a terminal; piped output is passed through unchanged.
+
+use colored::Colorize;
+
+/// Lines of a code block held back before it is streamed anyway
+pub const MAX_HELD_LINES: usize = 200;
+
+#[derive(Debug, Default)]
+enum Block {
+    #[default]
+    None,
+    /// An open code fence: its marker (e.g. "```"), the lines so far, and whether the
+    /// block was too long to hold and is being streamed
+    Fence {
+        marker: String,
+        lines: Vec<String>,
+        streaming: bool,
+    },
+    /// Table rows so far
+    Table(Vec<String>),
+}
+
+#[derive(Debug, Default)]
+pub struct StreamRenderer {
+    enabled: bool,
+    /// Text not printed yet, at most one partial line outside held blocks
+    pending: String,
+    /// The current line is prose, so the rest of it is printed as it arrives
+    in_prose_line: bool,
+    block: Block,
+}
+
+impl StreamRenderer {
+    /// A renderer that holds back blocks when `enabled`, and passes text through otherwise
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// A renderer for stdout, enabled when it is a terminal
+    pub fn for_stdout() -> Self {
+        Self::new(atty::is(atty::Stream::Stdout))
+    }
+
+    /// The part of the stream that can be printed after adding `text`
+    pub fn push(&mut self, text: &str) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        self.pending.push_str(text);
+        let mut out = String::new();
+        loop {
+            let newline = self.pending.find('\n');
+            if self.in_prose_line {
+                let end = newline.map_or(self.pending.len(), |i| i + 1);
+                out.extend(self.pending.drain(..end));
+                if newline.is_none() {
+                    break;
+                }
+                self.in_prose_line = false;
+                continue;
+            }
+            if let Some(i) = newline {
+                let line: String = self.pending.drain(..=i).collect();
+                self.complete_line(line, &mut out);
+                continue;
+            }
+            // A partial line: wait until it is clear whether it opens or continues a block
+            let partial = self.pending.as_str();
+            match &mut self.block {
+                Block::None if !partial.is_empty() && !may_open_block(partial) => {
+                    self.in_prose_line = true;
+                    continue;
+                }
+                Block::Table(rows) if !partial.trim().is_empty() && !may_be_row(partial) => {
+                    out.push_str(&render_table(rows));
+                    self.block = Block::None;
+                    continue;
+                }
+                _ => break,
+            }
+        }
+        out
+    }
+
+    /// The rest of the stream, once it has ended; an unclosed block is printed as it is
+    pub fn finish(&mut self) -> String {
+        let mut out = String::new();
+        match std::mem::take(&mut self.block) {
+            Block::None => {}
+            Block::Fence { lines, .. } => out.push_str(&render_fence(&lines)),
+            Block::Table(rows) => out.push_str(&render_table(&rows)),
+        }
+        out.push_str(&std::mem::take(&mut self.pending));
+        self.in_prose_line = false;
+        out
+    }
+
+    fn complete_line(&mut self, line: String, out: &mut String) {
+        match std::mem::take(&mut self.block) {
+            Block::None => {
+                if let Some(marker) = fence_marker(&line) {
+                    self.block = Block::Fence {
+                        marker,
+                        lines: vec![line],
+                        streaming: false,
+                    };
+                } else if is_row(&line) {
+                    self.block = Block::Table(vec![line]);
+                } else {
+                    out.push_str(&line);
+                }
+            }
+            Block::Fence {
+                marker,
+                mut lines,
+                streaming,
+            } => {
+                let closes = closes_fence(&line, &marker);
+                if streaming {
+                    out.push_str(&if closes { dim_line(&line) } else { line });
+                } else {
+                    lines.push(line);
+                }
+                if closes {
+                    if !streaming {
+                        out.push_str(&render_fence(&lines));
+                    }
+                } else if !streaming && lines.len() > MAX_HELD_LINES {
+                    out.push_str(&render_fence(&lines));
+                    self.block = Block::Fence {
+                        marker,
+                        lines: Vec::new(),
+                        streaming: true,
+                    };
+                } else {
+                    self.block = Block::Fence {
+                        marker,
+                        lines,
+                        streaming,
+                    };
+                }
+            }
+            Block::Table(mut rows) => {
+                if is_row(&line) {
+                    rows.push(line);
+                    self.block = Block::Table(rows);
+                } else {
+                    out.push_str(&render_table(&rows));
+                    self.complete_line(line, out);
+                }
+            }
+        }
+    }
+}
+
+/// Whether a partial line could still turn out to open a fence or a table
+fn may_open_block(partial: &str) -> bool {
+    let text = partial.trim_start_matches(' ');
+    if partial.len() - text.len() > 3 {
+        return false;
+    }
+    text.is_empty()
+        || text.starts_with('|')
+        || ["```", "~~~"]
+            .iter()
+            .any(|fence| fence.starts_with(text) || text.starts_with(fence))
+}
+
+fn may_be_row(partial: &str) -> bool {
+    partial.trim_start().starts_with('|')
+}
+
+fn is_row(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('|') && line.len() > 1
+}
+
+/// The fence of a line that opens a code block, e.g. "```" for "```rust"
+fn fence_marker(line: &str) -> Option<String> {
+    let text = line.trim_start_matches(' ');
+    if line.len() - text.len() > 3 {
+        return None;
+    }
+    let fence_char = text.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let marker: String = text.chars().take_while(|&c| c == fence_char).collect();
+    (marker.len() >= 3).then_some(marker)
+}
+
+fn closes_fence(line: &str, marker: &str) -> bool {
+    let text = line.trim();
+    let fence_char = marker.chars().next().unwrap_or('`');
+    text.len() >= marker.len() && text.chars().all(|c| c == fence_char)
+}
+
+fn dim_line(line: &str) -> String {
+    let content = line.trim_end_matches(['\n', '\r']);
+    format!("{}{}", content.dimmed(), &line[content.len()..])
+}
+
+/// A code block with its fences dimmed
+fn render_fence(lines: &[String]) -> String {
+    let last = lines.len().saturating_sub(1);
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let is_fence = i == 0 || (i == last && fence_marker(line).is_some());
+            if is_fence {
+                dim_line(line)
+            } else {
+                line.clone()
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A table with its columns padded to the same width; rows without a header separator
+/// are printed as they are
+fn render_table(rows: &[String]) -> String {
+    let cells: Vec<Vec<String>> = rows.iter().map(|row| split_row(row)).collect();
+    let Some(aligns) = cells.get(1).and_then(|sep| separator_aligns(sep)) else {
+        return rows.concat();
+    };
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let mut widths = vec![3; columns];
+    // The separator row doesn't count towards the widths
+    for row in cells.iter().take(1).chain(cells.iter().skip(2)) {
+        for (column, cell) in row.iter().enumerate() {
+            widths[column] = widths[column].max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    for (i, row) in cells.iter().enumerate() {
+        let rendered: Vec<String> = (0..columns)
+            .map(|column| {
+                let width = widths[column];
+                let align = aligns.get(column).copied().unwrap_or(Align::Left);
+                if i == 1 {
+                    return match align {
+                        Align::Left => "-".repeat(width),
+                        Align::Center => format!(":{}:", "-".repeat(width - 2)),
+                        Align::Right => format!("{}:", "-".repeat(width - 1)),
+                    };
+                }
+                let cell = row.get(column).map(String::as_str).unwrap_or("");
+                let padding = width - cell.chars().count();
+                let (left, right) = match align {
+                    Align::Left => (0, padding),
+                    Align::Center => (padding / 2, padding - padding / 2),
+                    Align::Right => (padding, 0),
+                };
+                format!("{}{}{}", " ".repeat(left), cell, " ".repeat(right))
+            })
+            .collect();
+        let line = format!("| {} |", rendered.join(" | "));
+        if i == 1 {
+            out.push_str(&line.dimmed().to_string());
+        } else {
+            out.push_str(&line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Cells of a row, splitting on `|` that isn't escaped
+fn split_row(row: &str) -> Vec<String> {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = if row.ends_with('|') && !row.ends_with("\\|") {
+        &row[..row.len() - 1]
+    } else {
+        row
+    };
+    let mut cells = vec![String::new()];
+    let mut escaped = false;
+    for c in row.chars() {
+        if c == '|' && !escaped {
+            cells.push(String::new());
+        } else if let Some(cell) = cells.last_mut() {
+            cell.push(c);
+        }
+        escaped = c == '\\';
+    }
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+/// Column alignments of a `|---|:-:|--:|` separator row
+fn separator_aligns(cells: &[String]) -> Option<Vec<Align>> {
+    cells
+        .iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Align::Center,
+                (false, true) => Align::Right,
+                _ => Align::Left,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(chunks: &[&str]) -> Vec<String> {
+        colored::control::set_override(false);
+        let mut renderer = StreamRenderer::new(true);
+        let mut outputs: Vec<String> = chunks.iter().map(|c| renderer.push(c)).collect();
+        outputs.push(renderer.finish());
+        outputs
+    }
+
+    #[test]
+    fn test_prose_streams_and_code_is_held() {
+        let out = render(&[
+            "Here is",
+            " the code:\n``",
+            "`rust\nfn main() {\n",
+            "}\n``",
+            "`\nDone.",
+        ]);
+        assert_eq!(out[0], "Here is");
+        assert_eq!(out[1], " the code:\n");
+        assert_eq!(out[2], "");
+        assert_eq!(out[3], "");
+        assert_eq!(out[4], "```rust\nfn main() {\n}\n```\nDone.");
+        assert_eq!(out[5], "");
+    }
+
+    #[test]
+    fn test_table_is_aligned() {
+        let out = render(&[
+            "| Name | Qty |\n|---|--:|\n| apple | 3 |\n",
+            "| kiwi | 12 |\nTotal",
+        ]);
+        assert_eq!(out[0], "");
+        assert_eq!(
+            out[1],
+            "| Name  | Qty |\n| ----- | --: |\n| apple |   3 |\n| kiwi  |  12 |\nTotal"
+        );
+        // Without a separator row the lines are printed as they are
+        assert_eq!(
+            render(&["| not a table\n", "ok"]).concat(),
+            "| not a table\nok"
+        );
+    }
+
+    #[test]
+    fn test_unclosed_block_and_passthrough() {
+        assert_eq!(
+            render(&["```\nlet x = 1;\n", "let y"]).concat(),
+            "```\nlet x = 1;\nlet y"
+        );
+        let mut renderer = StreamRenderer::new(false);
+        assert_eq!(renderer.push("```\npartial"), "```\npartial");
+        assert_eq!(split_row("| a \\| b | c |"), vec!["a \\| b", "c"]);
+    }
+}
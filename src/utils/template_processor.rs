@@ -30,7 +30,7 @@ pub struct EndpointTemplates {
 }
 
 /// Template configuration for request/response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TemplateConfig {
     /// Request transformation template
     pub request: Option<String>,
@@ -38,6 +38,160 @@ pub struct TemplateConfig {
     pub response: Option<String>,
     /// Streaming response parsing template
     pub stream_response: Option<String>,
+    /// JSON pointers locating the answer in a response, checked before `response`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_mappings: Option<ResponseMappings>,
+    /// JSON pointers locating the text in each streamed chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_mappings: Option<StreamMappings>,
+}
+
+/// Where the parts of a chat response live, as JSON pointers (`/output/0/text`).
+/// A `*` segment matches every element of an array, so `/content/*/text` joins the
+/// text of all content blocks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMappings {
+    /// Answer text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Reasoning trace of thinking models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Array of tool calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<String>,
+    /// Error message of APIs that report failures with a success status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ResponseMappings {
+    pub fn content(&self, response: &JsonValue) -> Option<String> {
+        select_text(response, self.content.as_deref()?)
+    }
+
+    pub fn reasoning(&self, response: &JsonValue) -> Option<String> {
+        select_text(response, self.reasoning.as_deref()?)
+    }
+
+    pub fn tool_calls(&self, response: &JsonValue) -> Vec<JsonValue> {
+        let Some(pointer) = self.tool_calls.as_deref() else {
+            return Vec::new();
+        };
+        select(response, pointer)
+            .into_iter()
+            .flat_map(|value| match value {
+                JsonValue::Array(calls) => calls.clone(),
+                JsonValue::Null => Vec::new(),
+                other => vec![other.clone()],
+            })
+            .collect()
+    }
+
+    pub fn error(&self, response: &JsonValue) -> Option<String> {
+        let values = select(response, self.error.as_deref()?);
+        let messages: Vec<String> = values
+            .into_iter()
+            .filter(|value| is_truthy(value))
+            .map(|value| match value {
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        (!messages.is_empty()).then(|| messages.join("; "))
+    }
+}
+
+/// Where the parts of each streamed chunk live, as JSON pointers. Chunks are the
+/// `data:` payloads of an SSE stream or the lines of a line-delimited JSON stream.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamMappings {
+    /// Text delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Reasoning delta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Value marking the last chunk; the stream ends when it is truthy, or equal to
+    /// `done_value` when that is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done_value: Option<String>,
+}
+
+impl StreamMappings {
+    pub fn content(&self, chunk: &JsonValue) -> Option<String> {
+        select_text(chunk, self.content.as_deref()?)
+    }
+
+    pub fn reasoning(&self, chunk: &JsonValue) -> Option<String> {
+        select_text(chunk, self.reasoning.as_deref()?)
+    }
+
+    pub fn is_done(&self, chunk: &JsonValue) -> bool {
+        let Some(pointer) = self.done.as_deref() else {
+            return false;
+        };
+        select(chunk, pointer)
+            .into_iter()
+            .any(|value| match (&self.done_value, value) {
+                (Some(expected), JsonValue::String(s)) => s == expected,
+                (Some(expected), other) => &other.to_string() == expected,
+                (None, other) => is_truthy(other),
+            })
+    }
+}
+
+/// Values at a JSON pointer, where a `*` segment matches every element of an array
+/// (or every value of an object). The empty pointer selects the whole document.
+pub fn select<'a>(value: &'a JsonValue, pointer: &str) -> Vec<&'a JsonValue> {
+    let pointer = pointer.trim();
+    if pointer.is_empty() || pointer == "/" {
+        return vec![value];
+    }
+
+    let mut current = vec![value];
+    for segment in pointer.strip_prefix('/').unwrap_or(pointer).split('/') {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&JsonValue> {
+                match (value, segment.as_str()) {
+                    (JsonValue::Array(items), "*") => items.iter().collect(),
+                    (JsonValue::Object(map), "*") => map.values().collect(),
+                    (JsonValue::Array(items), index) => index
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| items.get(i))
+                        .into_iter()
+                        .collect(),
+                    (JsonValue::Object(map), key) => map.get(key).into_iter().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+/// Text at a JSON pointer, joining the strings matched through `*` segments.
+/// Returns `None` when the pointer matches no string.
+pub fn select_text(value: &JsonValue, pointer: &str) -> Option<String> {
+    let parts: Vec<&str> = select(value, pointer)
+        .into_iter()
+        .filter_map(|value| value.as_str())
+        .collect();
+    (!parts.is_empty()).then(|| parts.concat())
+}
+
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(b) => *b,
+        JsonValue::String(s) => !s.is_empty(),
+        _ => true,
+    }
 }
 
 /// Model-specific endpoint templates (for backward compatibility)
@@ -381,6 +535,7 @@ impl TemplateProcessor {
                 role: message.role.clone(),
                 content: None,
                 images: Vec::new(),
+                audio: Vec::new(),
                 tool_calls: message.tool_calls.clone(),
                 tool_call_id: message.tool_call_id.clone(),
             };
@@ -422,6 +577,19 @@ impl TemplateProcessor {
                                     });
                                 }
                             }
+                            ContentPart::InputAudio { input_audio } => {
+                                let mime_type = match input_audio.format.as_str() {
+                                    "mp3" => "audio/mpeg".to_string(),
+                                    "m4a" => "audio/mp4".to_string(),
+                                    other => format!("audio/{}", other),
+                                };
+
+                                proc_msg.audio.push(ProcessedAudio {
+                                    mime_type,
+                                    data: input_audio.data.clone(),
+                                    format: input_audio.format.clone(),
+                                });
+                            }
                         }
                     }
                 }
@@ -434,12 +602,98 @@ impl TemplateProcessor {
     }
 }
 
+/// Fill in the ambient variables of a prompt template or system prompt:
+///
+/// - `{{user.name}}`, `{{user.email}}`: `LC_USER_NAME` / `LC_USER_EMAIL`, else git's
+///   `user.name` / `user.email` (the login name stands in for a missing name)
+/// - `{{user.login}}`: the login name
+/// - `{{date}}`, `{{time}}`, `{{weekday}}`: now, in the `[time]` timezone
+/// - `{{os}}`, `{{arch}}`, `{{cwd}}`
+/// - `{{git.branch}}`, `{{git.commit}}`, `{{git.repo}}`: of the current directory
+/// - `{{var.<name>}}`: organization variables, from `LC_VAR_<NAME>` (set by login
+///   scripts or SSO tooling) or else `[template_vars]` in config.toml
+///
+/// Variables are resolved when the prompt is rendered, and only those used. Unknown
+/// names and variables without a value are left as written, so other `{{...}}` text in
+/// prompts is kept.
+pub fn render_ambient_vars(text: &str, template_vars: &HashMap<String, String>) -> String {
+    if !text.contains("{{") {
+        return text.to_string();
+    }
+    let Ok(pattern) = crate::utils::regex_cache::get_regex(
+        r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z0-9_-]+)?)\s*\}\}",
+    ) else {
+        return text.to_string();
+    };
+    let mut values: HashMap<String, Option<String>> = HashMap::new();
+    pattern
+        .replace_all(text, |captures: &regex::Captures| {
+            values
+                .entry(captures[1].to_string())
+                .or_insert_with(|| ambient_var(&captures[1], template_vars))
+                .clone()
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+fn ambient_var(name: &str, template_vars: &HashMap<String, String>) -> Option<String> {
+    let env = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    };
+    let login = || env("USER").or_else(|| env("USERNAME"));
+    let now = |pattern: &str| {
+        crate::utils::time_display::current().format_as(&chrono::Utc::now(), pattern)
+    };
+    match name {
+        "user.name" => env("LC_USER_NAME")
+            .or_else(|| git(&["config", "user.name"]))
+            .or_else(login),
+        "user.email" => env("LC_USER_EMAIL").or_else(|| git(&["config", "user.email"])),
+        "user.login" => login(),
+        "date" => Some(now("%Y-%m-%d")),
+        "time" => Some(now("%H:%M")),
+        "weekday" => Some(now("%A")),
+        "os" => Some(std::env::consts::OS.to_string()),
+        "arch" => Some(std::env::consts::ARCH.to_string()),
+        "cwd" => std::env::current_dir()
+            .ok()
+            .map(|dir| dir.display().to_string()),
+        "git.branch" => git(&["rev-parse", "--abbrev-ref", "HEAD"]),
+        "git.commit" => git(&["rev-parse", "--short", "HEAD"]),
+        "git.repo" => git(&["rev-parse", "--show-toplevel"]).and_then(|top| {
+            std::path::Path::new(&top)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        }),
+        _ => {
+            let var = name.strip_prefix("var.")?;
+            env(&format!("LC_VAR_{}", var.to_uppercase().replace('-', "_")))
+                .or_else(|| template_vars.get(var).cloned())
+        }
+    }
+}
+
+/// Trimmed output of a git command in the current directory, if it succeeds
+fn git(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
 /// Processed message format for templates
 #[derive(Debug, Serialize)]
 struct ProcessedMessage {
     role: String,
     content: Option<String>,
     images: Vec<ProcessedImage>,
+    audio: Vec<ProcessedAudio>,
     tool_calls: Option<Vec<crate::provider::ToolCall>>,
     tool_call_id: Option<String>,
 }
@@ -451,6 +705,13 @@ struct ProcessedImage {
     url: String,
 }
 
+#[derive(Debug, Serialize)]
+struct ProcessedAudio {
+    mime_type: String,
+    data: String,
+    format: String,
+}
+
 /// Custom filter to convert values to JSON
 struct JsonFilter;
 
@@ -882,6 +1143,25 @@ impl Filter for GeminiMessagesFilter {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_render_ambient_vars() {
+        let vars = HashMap::from([("team".to_string(), "Search".to_string())]);
+        let rendered = render_ambient_vars(
+            "Team {{ var.team }} on {{os}}, {{var.unset_here}}, {{ unknown }}, {% raw %}",
+            &vars,
+        );
+        assert_eq!(
+            rendered,
+            format!(
+                "Team Search on {}, {{{{var.unset_here}}}}, {{{{ unknown }}}}, {{% raw %}}",
+                std::env::consts::OS
+            )
+        );
+        let date = render_ambient_vars("{{date}}", &vars);
+        assert_eq!(date.len(), 10);
+        assert_eq!(render_ambient_vars("no variables", &vars), "no variables");
+    }
+
     #[test]
     fn test_json_filter() {
         let filter = JsonFilter;
@@ -921,6 +1201,65 @@ mod tests {
         assert_eq!(result, Value::String("existing".to_string()));
     }
 
+    #[test]
+    fn test_select_pointers_with_wildcards() {
+        let response = serde_json::json!({
+            "content": [
+                {"type": "text", "text": "Hello"},
+                {"type": "tool_use", "name": "f"},
+                {"type": "text", "text": " world"}
+            ],
+            "meta": {"a/b": 1}
+        });
+        assert_eq!(
+            select_text(&response, "/content/*/text").as_deref(),
+            Some("Hello world")
+        );
+        assert_eq!(
+            select_text(&response, "/content/0/text").as_deref(),
+            Some("Hello")
+        );
+        assert_eq!(select(&response, "/meta/a~1b"), vec![&serde_json::json!(1)]);
+        assert!(select(&response, "/content/9/text").is_empty());
+        assert_eq!(select_text(&response, "/missing"), None);
+        assert_eq!(select(&response, "").len(), 1);
+    }
+
+    #[test]
+    fn test_response_and_stream_mappings() {
+        let mappings = ResponseMappings {
+            content: Some("/output/text".to_string()),
+            tool_calls: Some("/output/calls".to_string()),
+            error: Some("/error/message".to_string()),
+            ..Default::default()
+        };
+        let response = serde_json::json!({"output": {"text": "42", "calls": [{"name": "f"}]}});
+        assert_eq!(mappings.content(&response).as_deref(), Some("42"));
+        assert_eq!(mappings.tool_calls(&response).len(), 1);
+        assert_eq!(mappings.reasoning(&response), None);
+        assert_eq!(mappings.error(&response), None);
+        let failed = serde_json::json!({"error": {"message": "quota exceeded"}});
+        assert_eq!(mappings.error(&failed).as_deref(), Some("quota exceeded"));
+
+        let stream = StreamMappings {
+            content: Some("/delta/text".to_string()),
+            done: Some("/type".to_string()),
+            done_value: Some("message_stop".to_string()),
+            ..Default::default()
+        };
+        let chunk = serde_json::json!({"type": "delta", "delta": {"text": "Hi"}});
+        assert_eq!(stream.content(&chunk).as_deref(), Some("Hi"));
+        assert!(!stream.is_done(&chunk));
+        assert!(stream.is_done(&serde_json::json!({"type": "message_stop"})));
+
+        let flag = StreamMappings {
+            done: Some("/finished".to_string()),
+            ..Default::default()
+        };
+        assert!(flag.is_done(&serde_json::json!({"finished": true})));
+        assert!(!flag.is_done(&serde_json::json!({"finished": false})));
+    }
+
     #[test]
     fn test_template_registration() {
         let mut processor = TemplateProcessor::new().unwrap();
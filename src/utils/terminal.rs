@@ -0,0 +1,189 @@
+//! Terminal capabilities and ASCII fallbacks
+//!
+//! lc decorates its output with emoji and Unicode symbols (✓, ⚠️, •, box lines). Legacy
+//! Windows consoles (conhost, cmd.exe and PowerShell outside Windows Terminal) cannot
+//! render most of them. In ASCII mode, enabled with `--ascii` or detected for those
+//! consoles, the status symbols lc prints go through [`sym`], which replaces them with
+//! plain ASCII. Payload output such as model answers, `--json` and exports is never
+//! rewritten.
+
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Mutex, OnceLock};
+
+/// ASCII replacements of the symbols used in lc's own output
+const REPLACEMENTS: &[(char, &str)] = &[
+    ('✓', "+"),
+    ('✔', "+"),
+    ('✅', "+"),
+    ('✗', "x"),
+    ('✘', "x"),
+    ('❌', "x"),
+    ('⚠', "!"),
+    ('ℹ', "i"),
+    ('💡', ">"),
+    ('•', "*"),
+    ('·', "-"),
+    ('─', "-"),
+    ('│', "|"),
+    ('→', "->"),
+    ('←', "<-"),
+    ('—', "--"),
+    ('–', "-"),
+    ('…', "..."),
+    ('≈', "~"),
+    ('×', "x"),
+    ('█', "#"),
+    ('▓', "#"),
+    ('▒', ":"),
+    ('░', "."),
+];
+
+/// Enable ANSI escape sequences on Windows 10+ consoles. Colors are turned off when the
+/// console does not support them, instead of printing raw escape codes.
+pub fn init() {
+    #[cfg(windows)]
+    if colored::control::set_virtual_terminal(true).is_err() {
+        colored::control::set_override(false);
+    }
+}
+
+/// Whether the terminal is expected to render emoji and Unicode symbols.
+///
+/// Windows consoles only do when running in Windows Terminal, VS Code, ConEmu or a
+/// Unix-like terminal emulator (mintty, MSYS2); other platforms always do.
+pub fn supports_unicode() -> bool {
+    if cfg!(windows) {
+        std::env::var_os("WT_SESSION").is_some()
+            || std::env::var_os("TERM_PROGRAM").is_some()
+            || std::env::var("ConEmuANSI").is_ok_and(|value| value == "ON")
+            || std::env::var_os("TERM").is_some()
+    } else {
+        true
+    }
+}
+
+/// Whether output is restricted to ASCII for this invocation
+pub fn ascii_mode() -> bool {
+    crate::ASCII_MODE.load(Ordering::Relaxed)
+}
+
+/// A status symbol (or a short label starting with one) as it should be printed: the
+/// symbol itself, or its ASCII fallback in ASCII mode. Decorative emoji without a
+/// replacement become `*`.
+pub fn sym(symbol: &'static str) -> &'static str {
+    static FALLBACKS: OnceLock<Mutex<HashMap<&'static str, &'static str>>> = OnceLock::new();
+
+    if !ascii_mode() {
+        return symbol;
+    }
+    let mut fallbacks = FALLBACKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    // Each distinct literal is converted once; the set of literals is fixed
+    fallbacks.entry(symbol).or_insert_with(|| {
+        let ascii = to_ascii(symbol);
+        if ascii.trim().is_empty() {
+            "*"
+        } else {
+            Box::leak(ascii.into_boxed_str())
+        }
+    })
+}
+
+/// Replace the symbols lc prints with ASCII and drop decorative emoji (together with the
+/// space that follows them). Other non-ASCII text, such as accented letters, is kept, and
+/// so are ANSI color codes.
+pub fn to_ascii(text: &str) -> String {
+    let mut output = AsciiOutput::default();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some((_, replacement)) = REPLACEMENTS.iter().find(|(symbol, _)| *symbol == c) {
+            output.push_str(replacement);
+        } else if is_emoji(c) {
+            // Emoji sequences: variation selectors and joined emoji are dropped too
+            while chars
+                .peek()
+                .is_some_and(|&next| is_emoji_modifier(next) || is_emoji(next))
+            {
+                chars.next();
+            }
+            // Keep the color codes around a colored emoji, then its separating space
+            while chars.peek() == Some(&'\x1b') {
+                for next in chars.by_ref() {
+                    output.push(next);
+                    if next.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            if output.at_word_start() && chars.peek() == Some(&' ') {
+                chars.next();
+            }
+        } else if !is_emoji_modifier(c) {
+            output.push(c);
+        }
+    }
+    output.text
+}
+
+/// Output of `to_ascii`, tracking the last visible character outside of escape codes
+#[derive(Default)]
+struct AsciiOutput {
+    text: String,
+    last_visible: Option<char>,
+    in_escape: bool,
+}
+
+impl AsciiOutput {
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        if c == '\x1b' {
+            self.in_escape = true;
+        } else if self.in_escape {
+            self.in_escape = !c.is_ascii_alphabetic();
+        } else {
+            self.last_visible = Some(c);
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        s.chars().for_each(|c| self.push(c));
+    }
+
+    fn at_word_start(&self) -> bool {
+        self.last_visible.is_none_or(char::is_whitespace)
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x23E9..=0x23FA)
+}
+
+/// Variation selectors and zero-width joiners that are part of emoji sequences
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c, '\u{FE0E}' | '\u{FE0F}' | '\u{200D}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("✓ Provider added"), "+ Provider added");
+        assert_eq!(to_ascii("  ⚠️ Low budget • 80%"), "  ! Low budget * 80%");
+        assert_eq!(to_ascii("📊 Usage ─── café"), "Usage --- café");
+        assert_eq!(
+            to_ascii("Status: 🧠 Recalled\n🛠️ Tools"),
+            "Status: Recalled\nTools"
+        );
+        assert_eq!(to_ascii("done 🎉"), "done ");
+        assert_eq!(to_ascii("plain text"), "plain text");
+        assert_eq!(
+            to_ascii("\x1b[33m📊\x1b[0m Press Ctrl+C"),
+            "\x1b[33m\x1b[0mPress Ctrl+C"
+        );
+    }
+}
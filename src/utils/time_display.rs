@@ -0,0 +1,222 @@
+//! Timestamps as shown by `lc logs`
+//!
+//! The logs database and JSON exports keep timestamps in UTC. When printed, they are
+//! converted to the zone of the `[time]` section, the system's local time by default,
+//! and `lc logs recent` adds how long ago each turn was logged:
+//!
+//! ```toml
+//! [time]
+//! timezone = "Europe/Berlin"   # "local" (default), "UTC" or an IANA zone name
+//! format = "%d.%m.%Y %H:%M"    # strftime pattern of full timestamps
+//! relative = false             # no "2h ago" in `lc logs recent`
+//! ```
+//!
+//! With `--deterministic`, timestamps are printed as `-`.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// Pattern of full timestamps when none is configured
+pub const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// The `[time]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TimeSettings {
+    /// "local", "UTC" or an IANA zone name such as "America/New_York"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// strftime pattern of full timestamps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Set to false to leave relative times out of `lc logs recent`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative: Option<bool>,
+}
+
+impl TimeSettings {
+    pub fn is_empty(&self) -> bool {
+        self.timezone.is_none() && self.format.is_none() && self.relative.is_none()
+    }
+}
+
+/// The zone timestamps are shown in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl Zone {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim() {
+            "" | "local" | "Local" => Ok(Zone::Local),
+            "UTC" | "utc" | "Z" => Ok(Zone::Utc),
+            name => name.parse::<Tz>().map(Zone::Named).map_err(|_| {
+                anyhow!(
+                    "Unknown timezone '{}'. Use 'local', 'UTC' or an IANA name such as 'Europe/Berlin'",
+                    name
+                )
+            }),
+        }
+    }
+}
+
+/// How timestamps are rendered for this invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeDisplay {
+    zone: Zone,
+    format: String,
+    relative: bool,
+}
+
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        TimeDisplay {
+            zone: Zone::Local,
+            format: DEFAULT_FORMAT.to_string(),
+            relative: true,
+        }
+    }
+}
+
+impl TimeDisplay {
+    pub fn from_settings(settings: &TimeSettings) -> Result<Self> {
+        let zone = match &settings.timezone {
+            Some(name) => Zone::parse(name)?,
+            None => Zone::Local,
+        };
+        let format = match &settings.format {
+            Some(format) => {
+                if chrono::format::StrftimeItems::new(format)
+                    .any(|item| matches!(item, chrono::format::Item::Error))
+                {
+                    anyhow::bail!("Invalid time format '{}'", format);
+                }
+                format.clone()
+            }
+            None => DEFAULT_FORMAT.to_string(),
+        };
+        Ok(TimeDisplay {
+            zone,
+            format,
+            relative: settings.relative.unwrap_or(true),
+        })
+    }
+
+    /// `timestamp` in the configured zone and format
+    pub fn format(&self, timestamp: &DateTime<Utc>) -> String {
+        self.format_as(timestamp, &self.format)
+    }
+
+    /// `timestamp` in the configured zone with a fixed `pattern`, for compact columns
+    pub fn format_as(&self, timestamp: &DateTime<Utc>, pattern: &str) -> String {
+        if crate::core::deterministic::is_enabled() {
+            return crate::core::deterministic::TIMESTAMP_PLACEHOLDER.to_string();
+        }
+        match self.zone {
+            Zone::Local => timestamp.with_timezone(&Local).format(pattern).to_string(),
+            Zone::Utc => timestamp.format(pattern).to_string(),
+            Zone::Named(tz) => timestamp.with_timezone(&tz).format(pattern).to_string(),
+        }
+    }
+
+    /// `timestamp` followed by how long ago it was, when relative times are on
+    pub fn format_with_age(&self, timestamp: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+        if self.relative {
+            format!("{}, {}", self.format(timestamp), relative(timestamp, now))
+        } else {
+            self.format(timestamp)
+        }
+    }
+}
+
+/// How long before `now` `timestamp` was, such as "2h ago"
+pub fn relative(timestamp: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    if crate::core::deterministic::is_enabled() {
+        return crate::core::deterministic::TIMESTAMP_PLACEHOLDER.to_string();
+    }
+    let seconds = (*now - *timestamp).num_seconds();
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3_600 => format!("{}m ago", s / 60),
+        s if s < 86_400 => format!("{}h ago", s / 3_600),
+        s if s < 30 * 86_400 => format!("{}d ago", s / 86_400),
+        s if s < 365 * 86_400 => format!("{}mo ago", s / (30 * 86_400)),
+        s => format!("{}y ago", s / (365 * 86_400)),
+    }
+}
+
+static CURRENT: RwLock<Option<TimeDisplay>> = RwLock::new(None);
+
+/// Render timestamps with `display` for the rest of this invocation
+pub fn set(display: TimeDisplay) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(display);
+    }
+}
+
+/// The display set for this invocation, local time by default
+pub fn current() -> TimeDisplay {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|current| current.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_relative() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 9, 12, 0, 0).unwrap();
+        let ago = |seconds: i64| relative(&(now - chrono::Duration::seconds(seconds)), &now);
+        assert_eq!(ago(-30), "just now");
+        assert_eq!(ago(59), "just now");
+        assert_eq!(ago(5 * 60), "5m ago");
+        assert_eq!(ago(2 * 3_600 + 59), "2h ago");
+        assert_eq!(ago(3 * 86_400), "3d ago");
+        assert_eq!(ago(65 * 86_400), "2mo ago");
+        assert_eq!(ago(800 * 86_400), "2y ago");
+    }
+
+    #[test]
+    fn test_format() {
+        let timestamp = Utc.with_ymd_and_hms(2026, 1, 15, 23, 30, 0).unwrap();
+        let display = TimeDisplay::from_settings(&TimeSettings {
+            timezone: Some("Europe/Berlin".to_string()),
+            format: Some("%d.%m.%Y %H:%M %Z".to_string()),
+            relative: Some(false),
+        })
+        .unwrap();
+        assert_eq!(display.format(&timestamp), "16.01.2026 00:30 CET");
+        assert_eq!(display.format_as(&timestamp, "%H:%M"), "00:30");
+        assert_eq!(
+            display.format_with_age(&timestamp, &timestamp),
+            "16.01.2026 00:30 CET"
+        );
+
+        let utc = TimeDisplay::from_settings(&TimeSettings {
+            timezone: Some("UTC".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            utc.format_with_age(&timestamp, &(timestamp + chrono::Duration::hours(2))),
+            "2026-01-15 23:30:00, 2h ago"
+        );
+
+        assert!(Zone::parse("Mars/Olympus").is_err());
+        assert!(TimeDisplay::from_settings(&TimeSettings {
+            format: Some("%Q".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
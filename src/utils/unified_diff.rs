@@ -0,0 +1,302 @@
+//! Unified diffs proposed by models (`lc quickfix`)
+//!
+//! Parses `--- a/path` / `+++ b/path` / `@@ -l,n +l,n @@` diffs and applies them to
+//! file contents. Models often get the hunk line numbers wrong, so a hunk is placed
+//! where its context and removed lines match the file (the match nearest the stated
+//! line wins), comparing lines without trailing whitespace. Hunk counts are ignored.
+//! A hunk that matches nowhere fails the whole file, so nothing is half-applied.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// First line of the hunk in the original file, 1-based as in the header
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+/// The changes to one file; a path is `None` for `/dev/null` (created or deleted files)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// The file the patch writes, or deletes when the new path is `/dev/null`
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+
+    pub fn deletes(&self) -> bool {
+        self.new_path.is_none()
+    }
+}
+
+/// The diff in a model's answer: its first ```` ```diff ```` (or `patch`) block, or a
+/// block or answer that starts like a diff
+pub fn from_answer(answer: &str) -> Option<String> {
+    let blocks = super::code_blocks::extract(answer);
+    let looks_like_diff = |text: &str| {
+        let text = text.trim_start();
+        text.starts_with("--- ") || text.starts_with("diff --git")
+    };
+    blocks
+        .iter()
+        .find(|block| matches!(block.language.as_deref(), Some("diff" | "patch" | "udiff")))
+        .or_else(|| blocks.iter().find(|block| looks_like_diff(&block.content)))
+        .map(|block| block.content.clone())
+        .or_else(|| looks_like_diff(answer).then(|| answer.to_string()))
+}
+
+/// The answer without its diff blocks: the model's explanation
+pub fn explanation(answer: &str) -> String {
+    let mut text = String::new();
+    let mut in_diff = false;
+    let mut in_other = false;
+    for line in answer.lines() {
+        if let Some(info) = line.trim_start().strip_prefix("```") {
+            if in_diff {
+                in_diff = false;
+                continue;
+            }
+            if !in_other && matches!(info.trim(), "diff" | "patch" | "udiff") {
+                in_diff = true;
+                continue;
+            }
+            in_other = !in_other;
+        }
+        if !in_diff {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Parse a unified diff into per-file patches
+pub fn parse(diff: &str) -> Result<Vec<FilePatch>> {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let next = lines.get(i + 1).copied().unwrap_or_default();
+        if let (Some(old), Some(new)) = (line.strip_prefix("--- "), next.strip_prefix("+++ ")) {
+            patches.push(FilePatch {
+                old_path: diff_path(old),
+                new_path: diff_path(new),
+                hunks: Vec::new(),
+            });
+            i += 2;
+            continue;
+        }
+        if line.starts_with("@@") {
+            let Some(patch) = patches.last_mut() else {
+                bail!("The diff has a hunk before any '--- '/'+++ ' file header");
+            };
+            patch.hunks.push(Hunk {
+                old_start: hunk_start(line)?,
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = patches.last_mut().and_then(|p| p.hunks.last_mut()) {
+            match line.chars().next() {
+                Some(' ') => hunk.lines.push(HunkLine::Context(line[1..].to_string())),
+                Some('-') => hunk.lines.push(HunkLine::Remove(line[1..].to_string())),
+                Some('+') => hunk.lines.push(HunkLine::Add(line[1..].to_string())),
+                // Models (and editors) strip the space of empty context lines
+                None => hunk.lines.push(HunkLine::Context(String::new())),
+                // "\ No newline at end of file", "diff --git", "index ..." and the like
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+
+    patches.retain(|patch| !patch.hunks.is_empty() || patch.deletes());
+    if patches.is_empty() {
+        bail!("The diff contains no changes");
+    }
+    Ok(patches)
+}
+
+/// A path from a `---`/`+++` header, without the `a/`/`b/` prefix and timestamp
+fn diff_path(header: &str) -> Option<String> {
+    let path = header.split('\t').next().unwrap_or(header).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// The old start line of a `@@ -12,7 +12,8 @@` header
+fn hunk_start(header: &str) -> Result<usize> {
+    header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('-'))
+        .and_then(|range| range.split(',').next())
+        .and_then(|start| start.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Malformed hunk header '{}'", header))
+}
+
+/// `original` with the patch's hunks applied
+pub fn apply(original: &str, patch: &FilePatch) -> Result<String> {
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    // Where the next hunk may start, and how far earlier hunks moved the line numbers
+    let (mut cursor, mut shift) = (0usize, 0isize);
+
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let old: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect();
+        let expected = (hunk.old_start.saturating_sub(1) as isize + shift).max(0) as usize;
+        let Some(at) = find_hunk(&lines, &old, cursor, expected) else {
+            bail!(
+                "Hunk {} of '{}' (at line {}) does not match the file",
+                index + 1,
+                patch.path(),
+                hunk.old_start
+            );
+        };
+
+        let mut replacement = Vec::new();
+        let mut position = at;
+        for line in &hunk.lines {
+            match line {
+                // Keep the file's own context lines, whitespace included
+                HunkLine::Context(_) => {
+                    replacement.push(lines[position].clone());
+                    position += 1;
+                }
+                HunkLine::Remove(_) => position += 1,
+                HunkLine::Add(text) => replacement.push(text.clone()),
+            }
+        }
+        let added = replacement.len();
+        lines.splice(at..at + old.len(), replacement);
+        cursor = at + added;
+        shift += added as isize - old.len() as isize;
+    }
+
+    let mut patched = lines.join("\n");
+    if !lines.is_empty() && (original.is_empty() || original.ends_with('\n')) {
+        patched.push('\n');
+    }
+    Ok(patched)
+}
+
+/// The start of the match of `old` at or after `from` nearest to `expected`
+fn find_hunk(lines: &[String], old: &[&str], from: usize, expected: usize) -> Option<usize> {
+    if old.is_empty() {
+        return Some(expected.clamp(from, lines.len()));
+    }
+    let matches_at = |start: usize| {
+        old.iter()
+            .zip(&lines[start..])
+            .all(|(want, have)| want.trim_end() == have.trim_end())
+    };
+    (from..=lines.len().saturating_sub(old.len()))
+        .filter(|&start| start + old.len() <= lines.len() && matches_at(start))
+        .min_by_key(|&start| start.abs_diff(expected))
+}
+
+/// A diff with added lines in green, removed lines in red and headers dimmed
+pub fn colorize(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("+++") || line.starts_with("---") {
+                line.bold().to_string()
+            } else if line.starts_with("@@") {
+                line.cyan().to_string()
+            } else if line.starts_with('+') {
+                line.green().to_string()
+            } else if line.starts_with('-') {
+                line.red().to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str =
+        "fn main() {\n    let x = 1;\n    println!(\"{}\", y);\n}\n\nfn other() {}\n";
+
+    #[test]
+    fn test_parse_and_apply() {
+        // Wrong line numbers and counts, and an empty context line without its space
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n\
+            --- a/src/main.rs\n+++ b/src/main.rs\n\
+            @@ -5,3 +5,3 @@ fn main\n     let x = 1;\n-    println!(\"{}\", y);\n+    println!(\"{}\", x);\n }\n\
+            @@ -9,2 +9,3 @@\n\n fn other() {}\n+fn third() {}\n";
+        let patches = parse(diff).unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].path(), "src/main.rs");
+        assert_eq!(patches[0].hunks.len(), 2);
+        assert_eq!(
+            apply(FILE, &patches[0]).unwrap(),
+            "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n\nfn other() {}\nfn third() {}\n"
+        );
+
+        let stale = parse("--- a/f\n+++ b/f\n@@ -1 +1 @@\n-missing line\n+new\n").unwrap();
+        let error = apply(FILE, &stale[0]).unwrap_err().to_string();
+        assert!(error.contains("does not match"), "{}", error);
+    }
+
+    #[test]
+    fn test_new_and_deleted_files() {
+        let patches = parse(
+            "--- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1,2 @@\n+pub fn a() {}\n+pub fn b() {}\n\
+             --- a/old.rs\n+++ /dev/null\n",
+        )
+        .unwrap();
+        assert_eq!(patches.len(), 2);
+        assert_eq!(
+            apply("", &patches[0]).unwrap(),
+            "pub fn a() {}\npub fn b() {}\n"
+        );
+        assert!(patches[1].deletes());
+        assert_eq!(patches[1].path(), "old.rs");
+        assert!(parse("no diff here").is_err());
+    }
+
+    #[test]
+    fn test_answer_parts() {
+        let answer = "The variable is misspelled.\n\n```diff\n--- a/f\n+++ b/f\n@@ -1 +1 @@\n-y\n+x\n```\n\nRe-run the build.";
+        assert_eq!(
+            from_answer(answer).unwrap(),
+            "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-y\n+x"
+        );
+        assert_eq!(
+            explanation(answer),
+            "The variable is misspelled.\n\n\nRe-run the build."
+        );
+        assert!(from_answer("No fix needed.").is_none());
+    }
+}
@@ -0,0 +1,244 @@
+//! Local cache of fetched web pages and search results
+//!
+//! Pages fetched for `--auto-fetch-urls` and `-a https://...` and the results of
+//! `--use-search` and `lc search` are kept in `<config dir>/web_cache`, so asking again
+//! during a working session does not hit the search API or the website again. `--fresh`
+//! skips the cache for one run and stores what it fetched instead:
+//!
+//! ```toml
+//! [web_cache]
+//! ttl_minutes = 60     # how long an entry is used (default 60)
+//! max_size_mb = 100    # oldest entries are removed beyond this (default 100)
+//! # enabled = false    # never cache
+//! ```
+
+use anyhow::Result;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+const DEFAULT_TTL_MINUTES: u64 = 60;
+const DEFAULT_MAX_SIZE_MB: u64 = 100;
+
+/// The `[web_cache]` section of config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct WebCacheSettings {
+    /// Set to false to always fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Minutes an entry is used after it was fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_minutes: Option<u64>,
+    /// Size of the cache beyond which the oldest entries are removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+}
+
+impl WebCacheSettings {
+    pub fn is_empty(&self) -> bool {
+        self.enabled.is_none() && self.ttl_minutes.is_none() && self.max_size_mb.is_none()
+    }
+}
+
+/// A cached value and when it was fetched
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    key: String,
+    fetched_at: DateTime<Utc>,
+    value: T,
+}
+
+/// An HTTP response body, base64-encoded so binary pages such as PDFs survive JSON
+#[derive(Serialize, Deserialize)]
+struct Response {
+    content_type: Option<String>,
+    body: String,
+}
+
+/// Entries in a directory, used for `ttl` and kept under `max_bytes`
+pub struct WebCache {
+    dir: PathBuf,
+    ttl: chrono::Duration,
+    max_bytes: u64,
+}
+
+impl WebCache {
+    pub fn new(dir: PathBuf, settings: &WebCacheSettings) -> Self {
+        let ttl_minutes = settings.ttl_minutes.unwrap_or(DEFAULT_TTL_MINUTES);
+        let max_size_mb = settings.max_size_mb.unwrap_or(DEFAULT_MAX_SIZE_MB);
+        WebCache {
+            dir,
+            ttl: chrono::Duration::minutes(ttl_minutes.min(i64::MAX as u64 / 60_000) as i64),
+            max_bytes: max_size_mb.saturating_mul(1024 * 1024),
+        }
+    }
+
+    /// The cache configured in config.toml, or `None` when it is disabled
+    pub fn open() -> Option<Self> {
+        let config = crate::config::Config::load().ok()?;
+        if config.web_cache.enabled == Some(false) {
+            return None;
+        }
+        let dir = crate::config::Config::config_dir().ok()?.join("web_cache");
+        Some(WebCache::new(dir, &config.web_cache))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = format!("{:x}", Sha256::digest(key.as_bytes()));
+        self.dir.join(format!("{}.json", hash))
+    }
+
+    /// The value stored under `key`, unless it has expired
+    pub fn get<T: DeserializeOwned>(&self, key: &str, now: DateTime<Utc>) -> Option<T> {
+        let content = fs::read_to_string(self.path(key)).ok()?;
+        let entry: Entry<T> = serde_json::from_str(&content).ok()?;
+        (entry.key == key && now - entry.fetched_at < self.ttl).then_some(entry.value)
+    }
+
+    /// Store `value` under `key`, then remove expired entries and the oldest ones beyond
+    /// the size cap
+    pub fn put<T: Serialize>(&self, key: &str, value: &T, now: DateTime<Utc>) -> Result<()> {
+        let entry = Entry {
+            key: key.to_string(),
+            fetched_at: now,
+            value,
+        };
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(key);
+        fs::write(&path, serde_json::to_string(&entry)?)?;
+        self.prune(&path, now)
+    }
+
+    /// Remove expired entries and the oldest ones beyond the size cap, except `keep`
+    fn prune(&self, keep: &std::path::Path, now: DateTime<Utc>) -> Result<()> {
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.dir)?.flatten() {
+            let Ok(metadata) = file.metadata() else {
+                continue;
+            };
+            let modified: DateTime<Utc> = match metadata.modified() {
+                Ok(modified) => modified.into(),
+                Err(_) => continue,
+            };
+            if now - modified >= self.ttl && file.path() != keep {
+                let _ = fs::remove_file(file.path());
+            } else {
+                entries.push((modified, metadata.len(), file.path()));
+            }
+        }
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        for (_, size, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if path != keep && fs::remove_file(&path).is_ok() {
+                total -= size;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `--fresh` was given, so cached entries are not used
+pub fn is_fresh() -> bool {
+    crate::FRESH_MODE.load(Ordering::Relaxed)
+}
+
+/// A cached value for this invocation, unless `--fresh` was given
+pub fn get<T: DeserializeOwned>(key: &str) -> Option<T> {
+    if is_fresh() {
+        return None;
+    }
+    let value = WebCache::open()?.get(key, Utc::now());
+    if value.is_some() {
+        crate::debug_log!("Web cache hit for '{}'", key);
+    }
+    value
+}
+
+/// Cache a value; failures only affect later runs and are not reported
+pub fn put<T: Serialize>(key: &str, value: &T) {
+    if crate::data::lock::is_read_only() {
+        return;
+    }
+    if let Some(cache) = WebCache::open() {
+        if let Err(e) = cache.put(key, value, Utc::now()) {
+            crate::debug_log!("Failed to cache '{}': {}", key, e);
+        }
+    }
+}
+
+/// The cached body and content type of a URL
+pub fn response(url: &str) -> Option<(Vec<u8>, Option<String>)> {
+    let response: Response = get(&format!("url:{}", url))?;
+    let body = base64::engine::general_purpose::STANDARD
+        .decode(response.body)
+        .ok()?;
+    Some((body, response.content_type))
+}
+
+/// Cache the body and content type of a URL
+pub fn put_response(url: &str, body: &[u8], content_type: Option<&str>) {
+    let response = Response {
+        content_type: content_type.map(str::to_string),
+        body: base64::engine::general_purpose::STANDARD.encode(body),
+    };
+    put(&format!("url:{}", url), &response);
+}
+
+/// Cache key of a search
+pub fn search_key(provider: &str, query: &str, count: Option<usize>) -> String {
+    match count {
+        Some(count) => format!("search:{}:{}:{}", provider, count, query),
+        None => format!("search:{}::{}", provider, query),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_and_size_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = WebCache::new(
+            dir.path().to_path_buf(),
+            &WebCacheSettings {
+                ttl_minutes: Some(10),
+                ..Default::default()
+            },
+        );
+        let now = Utc::now();
+        cache.put("url:https://example.com", &"page", now).unwrap();
+        assert_eq!(
+            cache.get::<String>(
+                "url:https://example.com",
+                now + chrono::Duration::minutes(9)
+            ),
+            Some("page".to_string())
+        );
+        assert_eq!(
+            cache.get::<String>(
+                "url:https://example.com",
+                now + chrono::Duration::minutes(10)
+            ),
+            None
+        );
+        assert_eq!(cache.get::<String>("url:https://other.com", now), None);
+
+        // With no room left, each new entry replaces the older ones
+        let tiny = WebCache {
+            max_bytes: 0,
+            ..cache
+        };
+        tiny.put("a", &"x".repeat(100), now).unwrap();
+        tiny.put("b", &"y".repeat(100), now).unwrap();
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
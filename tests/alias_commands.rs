@@ -13,17 +13,7 @@ mod alias_add_tests {
     use super::*;
 
     fn create_config_with_providers() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add some test providers with test- prefix
         config.providers.insert(
@@ -47,8 +37,12 @@ mod alias_add_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -73,8 +67,12 @@ mod alias_add_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -238,17 +236,7 @@ mod alias_delete_tests {
     use super::*;
 
     fn create_config_with_aliases() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add test providers
         config.providers.insert(
@@ -272,8 +260,12 @@ mod alias_delete_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -391,17 +383,7 @@ mod alias_list_tests {
 
     #[test]
     fn test_alias_list_empty() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         let aliases = config.list_aliases();
         assert!(aliases.is_empty());
@@ -410,17 +392,7 @@ mod alias_list_tests {
 
     #[test]
     fn test_alias_list_with_aliases() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add some aliases
         config
@@ -451,17 +423,7 @@ mod alias_list_tests {
 
     #[test]
     fn test_alias_list_ordering() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add aliases in specific order
         config
@@ -485,17 +447,7 @@ mod alias_list_tests {
 
     #[test]
     fn test_alias_list_immutable() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
         config
             .aliases
             .insert("test".to_string(), "provider:model".to_string());
@@ -515,17 +467,7 @@ mod alias_validation_tests {
 
     #[test]
     fn test_alias_target_format_validation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Valid formats
         let valid_targets = vec![
@@ -549,17 +491,7 @@ mod alias_validation_tests {
 
     #[test]
     fn test_alias_target_invalid_formats() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Invalid formats (no colon)
         let invalid_targets = vec![
@@ -582,17 +514,7 @@ mod alias_validation_tests {
 
     #[test]
     fn test_alias_target_multiple_colons() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add a provider first
         config.providers.insert(
@@ -616,8 +538,12 @@ mod alias_validation_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -631,17 +557,7 @@ mod alias_validation_tests {
 
     #[test]
     fn test_alias_name_edge_cases() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add a provider first
         config.providers.insert(
@@ -665,8 +581,12 @@ mod alias_validation_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -696,17 +616,7 @@ mod alias_resolution_tests {
     use super::*;
 
     fn create_config_with_aliases_and_providers() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add providers
         config.providers.insert(
@@ -730,8 +640,12 @@ mod alias_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -756,8 +670,12 @@ mod alias_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -829,17 +747,7 @@ mod alias_resolution_tests {
 
     #[test]
     fn test_alias_with_special_model_names() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add provider
         config.providers.insert(
@@ -863,8 +771,12 @@ mod alias_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -898,17 +810,7 @@ mod alias_integration_tests {
 
     #[test]
     fn test_alias_workflow_complete() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add provider
         config.providers.insert(
@@ -932,8 +834,12 @@ mod alias_integration_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -986,17 +892,7 @@ mod alias_integration_tests {
     #[test]
     fn test_alias_persistence_simulation() {
         // Simulate config save/load cycle
-        let mut config1 = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config1 = Config::default();
 
         // Add provider
         config1.providers.insert(
@@ -1020,8 +916,12 @@ mod alias_integration_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -1034,19 +934,11 @@ mod alias_integration_tests {
             .unwrap();
 
         // Simulate serialization/deserialization by cloning the aliases
-        let mut config2 = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+        let config2 = Config {
+            providers: config1.providers.clone(),
+            aliases: config1.aliases.clone(),
+            ..Default::default()
         };
-        config2.providers = config1.providers.clone();
-        config2.aliases = config1.aliases.clone();
 
         // Verify aliases persisted
         assert_eq!(config2.list_aliases().len(), 2);
@@ -1059,17 +951,7 @@ mod alias_integration_tests {
 
     #[test]
     fn test_alias_with_provider_removal() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add provider and alias
         config.providers.insert(
@@ -1093,8 +975,12 @@ mod alias_integration_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
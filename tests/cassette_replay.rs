@@ -0,0 +1,58 @@
+//! Record-and-replay cassettes (`cargo test --features cassettes`)
+
+#![cfg(feature = "cassettes")]
+
+use lc::http_client::cassette::{self, Mode};
+use lc::provider::{ChatRequest, Message, OpenAIClient};
+use std::collections::HashMap;
+
+fn client() -> OpenAIClient {
+    OpenAIClient::new_with_headers(
+        "mock://local/v1".to_string(),
+        String::new(),
+        "/models".to_string(),
+        "/chat/completions".to_string(),
+        HashMap::new(),
+    )
+}
+
+fn request(prompt: &str) -> ChatRequest {
+    ChatRequest {
+        model: "mock-chat".to_string(),
+        messages: vec![Message::user(prompt.to_string())],
+        max_tokens: None,
+        temperature: None,
+        tools: None,
+        stream: None,
+    }
+}
+
+#[tokio::test]
+async fn test_record_then_replay() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chat.json");
+
+    cassette::use_cassette(&path, Mode::Record).unwrap();
+    let recorded = client().chat(&request("Hello")).await.unwrap();
+    assert_eq!(recorded, "Mock response to: Hello");
+
+    // Edit the recording so a replayed answer is distinguishable from a live one
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("\"interactions\""));
+    std::fs::write(
+        &path,
+        content.replace("Mock response to", "Recorded answer to"),
+    )
+    .unwrap();
+
+    cassette::use_cassette(&path, Mode::Replay).unwrap();
+    let replayed = client().chat(&request("Hello")).await.unwrap();
+    assert_eq!(replayed, "Recorded answer to: Hello");
+
+    let missing = client().chat(&request("Something else")).await;
+    assert!(missing
+        .unwrap_err()
+        .to_string()
+        .contains("No recorded interaction"));
+    cassette::eject();
+}
@@ -18,15 +18,9 @@ mod chat_model_resolution_tests {
 
     fn create_test_config_with_providers() -> Config {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add test providers
@@ -46,8 +40,12 @@ mod chat_model_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
                 headers: HashMap::new(),
                 token_url: None,
                 cached_token: None,
@@ -72,8 +70,12 @@ mod chat_model_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
                 headers: HashMap::new(),
                 token_url: None,
                 cached_token: None,
@@ -322,6 +324,7 @@ mod chat_message_handling_tests {
                 timestamp: Utc::now(),
                 input_tokens: Some(10),
                 output_tokens: Some(5),
+                provider: None,
             },
             ChatEntry {
                 chat_id: "test-session".to_string(),
@@ -331,6 +334,7 @@ mod chat_message_handling_tests {
                 timestamp: Utc::now(),
                 input_tokens: Some(15),
                 output_tokens: Some(8),
+                provider: None,
             },
         ];
 
@@ -453,15 +457,10 @@ mod chat_parameter_validation_tests {
     #[test]
     fn test_parameter_precedence() {
         let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
             system_prompt: Some("Default system prompt".to_string()),
-            templates: HashMap::new(),
             max_tokens: Some(1000),
             temperature: Some(0.5),
-            stream: None,
+            ..Default::default()
         };
 
         // Test that CLI overrides take precedence over config
@@ -497,17 +496,7 @@ mod chat_template_resolution_tests {
     use super::*;
 
     fn create_config_with_templates() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add templates
         config.templates.insert(
@@ -715,6 +704,7 @@ mod chat_session_management_tests {
             timestamp: Utc::now(),
             input_tokens: Some(10),
             output_tokens: Some(25),
+            provider: None,
         };
 
         assert_eq!(entry.chat_id, "test-session");
@@ -740,6 +730,7 @@ mod chat_session_management_tests {
                 timestamp: Utc::now(),
                 input_tokens: Some(10),
                 output_tokens: Some(15),
+                provider: None,
             });
         }
 
@@ -769,17 +760,7 @@ mod chat_error_handling_tests {
 
     #[test]
     fn test_invalid_model_resolution() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         // Test with no providers configured
         let result = lc::utils::resolve_model_and_provider(&config, None, None);
@@ -794,15 +775,8 @@ mod chat_error_handling_tests {
     #[test]
     fn test_invalid_alias_format() {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add provider
@@ -822,8 +796,12 @@ mod chat_error_handling_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
                 headers: HashMap::new(),
                 token_url: None,
                 cached_token: None,
@@ -845,15 +823,9 @@ mod chat_error_handling_tests {
     #[test]
     fn test_missing_api_key_error() {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add provider without API key
@@ -873,8 +845,12 @@ mod chat_error_handling_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
                 headers: HashMap::new(),
                 token_url: None,
                 cached_token: None,
@@ -913,15 +889,12 @@ mod chat_integration_tests {
     #[test]
     fn test_complete_chat_workflow_simulation() {
         let config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
             system_prompt: Some("You are a helpful assistant.".to_string()),
-            templates: HashMap::new(),
             max_tokens: Some(1000),
             temperature: Some(0.7),
-            stream: None,
+            ..Default::default()
         };
 
         // Simulate chat workflow
@@ -980,15 +953,9 @@ mod chat_integration_tests {
     #[test]
     fn test_chat_with_template_and_alias() {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add provider
@@ -1008,8 +975,12 @@ mod chat_integration_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
                 headers: HashMap::new(),
                 token_url: None,
                 cached_token: None,
@@ -1077,15 +1048,12 @@ mod chat_integration_tests {
     #[test]
     fn test_chat_parameter_override_workflow() {
         let config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
             system_prompt: Some("Default system prompt".to_string()),
-            templates: HashMap::new(),
             max_tokens: Some(1000),
             temperature: Some(0.5),
-            stream: None,
+            ..Default::default()
         };
 
         // Test CLI parameter overrides
@@ -1118,17 +1086,7 @@ mod chat_integration_tests {
 
     #[test]
     fn test_chat_error_recovery_workflow() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test error when no providers configured
         let result = lc::utils::resolve_model_and_provider(&config, None, None);
@@ -1151,8 +1109,12 @@ mod chat_integration_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
                 headers: HashMap::new(),
                 token_url: None,
                 cached_token: None,
@@ -1187,7 +1149,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert!(cli.continue_session);
+        assert!(cli.prompt_args.continue_session);
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
 
         // Test with -c short flag
@@ -1195,7 +1157,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert!(cli.continue_session);
+        assert!(cli.prompt_args.continue_session);
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
 
         // Test without continue flag
@@ -1203,7 +1165,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert!(!cli.continue_session);
+        assert!(!cli.prompt_args.continue_session);
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
     }
 
@@ -1216,7 +1178,10 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert_eq!(cli.chat_id, Some("test-session-123".to_string()));
+        assert_eq!(
+            cli.prompt_args.chat_id,
+            Some("test-session-123".to_string())
+        );
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
 
         // Test without --cid option
@@ -1224,7 +1189,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert_eq!(cli.chat_id, None);
+        assert_eq!(cli.prompt_args.chat_id, None);
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
     }
 
@@ -1237,8 +1202,8 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert!(cli.continue_session);
-        assert_eq!(cli.chat_id, Some("session-456".to_string()));
+        assert!(cli.prompt_args.continue_session);
+        assert_eq!(cli.prompt_args.chat_id, Some("session-456".to_string()));
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
     }
 
@@ -1252,9 +1217,9 @@ mod chat_session_continuation_tests {
         assert!(cli.is_ok());
         let cli = cli.unwrap();
 
-        if let Some(lc::cli::Commands::Chat { model, cid, .. }) = cli.command {
-            assert_eq!(model, Some("gpt-4".to_string()));
-            assert_eq!(cid, Some("chat-session-789".to_string()));
+        if let Some(lc::cli::Commands::Chat(args)) = cli.command {
+            assert_eq!(args.model, Some("gpt-4".to_string()));
+            assert_eq!(args.cid, Some("chat-session-789".to_string()));
         } else {
             panic!("Expected Chat command");
         }
@@ -1277,7 +1242,7 @@ mod chat_session_continuation_tests {
             let cli = lc::cli::Cli::try_parse_from(args);
             assert!(cli.is_ok(), "Session ID '{}' should be valid", session_id);
             let cli = cli.unwrap();
-            assert_eq!(cli.chat_id, Some(session_id.to_string()));
+            assert_eq!(cli.prompt_args.chat_id, Some(session_id.to_string()));
         }
     }
 
@@ -1381,7 +1346,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert!(cli.continue_session);
+        assert!(cli.prompt_args.continue_session);
         assert_eq!(cli.model, Some("gpt-4".to_string()));
         assert_eq!(cli.prompt, vec!["What", "is", "AI?"]);
 
@@ -1399,7 +1364,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok());
         let cli = cli.unwrap();
-        assert_eq!(cli.chat_id, Some("my-session".to_string()));
+        assert_eq!(cli.prompt_args.chat_id, Some("my-session".to_string()));
         assert_eq!(cli.model, Some("gpt-4".to_string()));
         assert_eq!(cli.prompt, vec!["What", "is", "AI?"]);
     }
@@ -1439,18 +1404,21 @@ mod chat_session_continuation_tests {
         let cli = cli.unwrap();
 
         // Verify session options
-        assert!(cli.continue_session);
-        assert_eq!(cli.chat_id, Some("test-session".to_string()));
+        assert!(cli.prompt_args.continue_session);
+        assert_eq!(cli.prompt_args.chat_id, Some("test-session".to_string()));
 
         // Verify other options are preserved
         assert_eq!(cli.provider, Some("openai".to_string()));
         assert_eq!(cli.model, Some("gpt-4".to_string()));
-        assert_eq!(cli.system_prompt, Some("You are helpful".to_string()));
-        assert_eq!(cli.max_tokens, Some("1000".to_string()));
-        assert_eq!(cli.temperature, Some("0.7".to_string()));
-        assert_eq!(cli.attachments, vec!["file.txt"]);
-        assert_eq!(cli.tools, Some("mcp-server".to_string()));
-        assert_eq!(cli.vectordb, Some("vector-db".to_string()));
+        assert_eq!(
+            cli.prompt_args.system_prompt,
+            Some("You are helpful".to_string())
+        );
+        assert_eq!(cli.prompt_args.max_tokens, Some("1000".to_string()));
+        assert_eq!(cli.prompt_args.temperature, Some("0.7".to_string()));
+        assert_eq!(cli.prompt_args.attachments, vec!["file.txt"]);
+        assert_eq!(cli.prompt_args.tools, Some("mcp-server".to_string()));
+        assert_eq!(cli.prompt_args.vectordb, Some("vector-db".to_string()));
         assert_eq!(cli.prompt, vec!["Analyze", "this"]);
     }
 
@@ -1468,7 +1436,7 @@ mod chat_session_continuation_tests {
         let cli = lc::cli::Cli::try_parse_from(args);
         assert!(cli.is_ok(), "--continue alone should be valid");
         let cli = cli.unwrap();
-        assert!(cli.continue_session);
+        assert!(cli.prompt_args.continue_session);
         assert!(cli.prompt.is_empty());
     }
 
@@ -1521,6 +1489,7 @@ mod chat_session_continuation_tests {
                 timestamp: Utc::now(),
                 input_tokens: Some(10),
                 output_tokens: Some(15),
+                provider: None,
             };
 
             assert_eq!(entry.chat_id, session_id_1);
@@ -110,17 +110,7 @@ pub fn get_test_provider_name(base_name: &str) -> String {
 /// Helper function to create a temporary config for testing
 #[allow(dead_code)]
 pub fn create_test_config() -> Config {
-    Config {
-        providers: HashMap::new(),
-        default_provider: None,
-        default_model: None,
-        aliases: HashMap::new(),
-        system_prompt: None,
-        templates: HashMap::new(),
-        max_tokens: None,
-        temperature: None,
-        stream: None,
-    }
+    Config::default()
 }
 
 /// Helper function to verify test environment isolation
@@ -143,6 +133,7 @@ pub fn create_test_provider_config(endpoint: &str) -> ProviderConfig {
         embeddings_path: Some("/embeddings".to_string()),
         audio_path: Some("/audio/transcriptions".to_string()),
         speech_path: Some("/audio/speech".to_string()),
+        rerank_path: None,
         headers: HashMap::new(),
         token_url: None,
         cached_token: None,
@@ -154,23 +145,16 @@ pub fn create_test_provider_config(endpoint: &str) -> ProviderConfig {
         models_templates: None,
         audio_templates: None,
         speech_templates: None,
+        organization: None,
+        project: None,
+        hmac: None,
     }
 }
 
 /// Helper function to create a config with test providers using test- prefix
 #[allow(dead_code)]
 pub fn create_config_with_providers() -> Config {
-    let mut config = Config {
-        providers: HashMap::new(),
-        default_provider: None,
-        default_model: None,
-        aliases: HashMap::new(),
-        system_prompt: None,
-        templates: HashMap::new(),
-        max_tokens: None,
-        temperature: None,
-        stream: None,
-    };
+    let mut config = Config::default();
 
     // Add test providers with test- prefix
     let openai_name = get_test_provider_name("openai");
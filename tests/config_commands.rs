@@ -7,7 +7,6 @@ mod common;
 
 use common::create_config_with_providers;
 use lc::config::Config;
-use std::collections::HashMap;
 
 #[cfg(test)]
 mod config_set_tests {
@@ -96,17 +95,7 @@ mod config_get_tests {
 
     #[test]
     fn test_config_get_unset_values() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         // Verify all values are None
         assert!(config.default_provider.is_none());
@@ -227,8 +216,12 @@ mod provider_config_url_tests {
             models_templates: None,
             audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
         };
 
         pc.vars.insert("project".to_string(), "my-proj".to_string());
@@ -272,8 +265,12 @@ mod provider_config_url_tests {
             models_templates: None,
             audio_path: None,
             speech_path: None,
+            rerank_path: None,
             audio_templates: None,
             speech_templates: None,
+            organization: None,
+            project: None,
+            hmac: None,
         };
 
         // For non-full URLs, no interpolation or model replacement occurs here
@@ -281,3 +278,82 @@ mod provider_config_url_tests {
         assert_eq!(url, "https://api.openai.com/v1/chat/completions");
     }
 }
+
+#[cfg(test)]
+mod read_only_tests {
+    use super::common::get_test_binary_path;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn lc(config_dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(get_test_binary_path())
+            .args(args)
+            .env("LC_TEST_CONFIG_DIR", config_dir)
+            .env_remove("LC_READ_ONLY")
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("Failed to execute command")
+    }
+
+    fn setup() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let providers = dir.path().join("providers");
+        fs::create_dir_all(&providers).unwrap();
+        fs::write(
+            providers.join("local.toml"),
+            "endpoint = \"http://localhost:1\"\nmodels = []\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_read_only_refuses_config_changes() {
+        let dir = setup();
+        assert!(lc(dir.path(), &["config", "set", "provider", "local"])
+            .status
+            .success());
+        let config_path = dir.path().join("config.toml");
+        let before = fs::read_to_string(&config_path).unwrap();
+
+        let output = lc(
+            dir.path(),
+            &["--read-only", "config", "set", "model", "other"],
+        );
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("read-only mode"));
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), before);
+
+        let output = lc(dir.path(), &["--read-only", "providers", "remove", "local"]);
+        assert!(!output.status.success());
+        assert!(dir.path().join("providers/local.toml").exists());
+    }
+
+    #[test]
+    fn test_read_only_never_creates_databases() {
+        let dir = setup();
+        let output = Command::new(get_test_binary_path())
+            .args(["logs", "recent"])
+            .env("LC_TEST_CONFIG_DIR", dir.path())
+            .env("LC_READ_ONLY", "true")
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(!dir.path().join("logs.db").exists());
+        assert!(!dir.path().join("backups").exists());
+    }
+
+    #[test]
+    fn test_read_only_allows_reading_logs() {
+        let dir = setup();
+        assert!(lc(dir.path(), &["logs", "recent"]).status.success());
+
+        let output = lc(dir.path(), &["--read-only", "logs", "stats"]);
+        assert!(output.status.success());
+
+        let output = lc(dir.path(), &["--read-only", "logs", "purge", "--yes"]);
+        assert!(!output.status.success());
+    }
+}
@@ -8,7 +8,6 @@ mod common;
 use common::{assertions, create_config_with_providers, get_test_provider_name};
 use lc::config::Config;
 use serial_test::serial;
-use std::collections::HashMap;
 
 #[cfg(test)]
 mod key_add_tests {
@@ -66,17 +65,7 @@ mod key_list_tests {
     #[test]
     #[serial]
     fn test_key_list_empty() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         assert!(config.providers.is_empty());
     }
@@ -6,7 +6,7 @@
 mod common;
 use common::get_test_binary_path;
 
-use lc::model_metadata::{ModelMetadata, ModelType};
+use lc::model_metadata::{Capability, ModelMetadata, ModelType};
 use lc::unified_cache::UnifiedCache;
 
 #[cfg(test)]
@@ -187,6 +187,25 @@ mod models_metadata_tests {
         assert!(matches!(moderation_model, ModelType::Moderation));
         assert!(matches!(other_model, ModelType::Other(_)));
     }
+
+    #[test]
+    fn test_model_capability_states() {
+        let metadata = ModelMetadata {
+            supports_function_calling: true,
+            unknown_capabilities: vec!["vision".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(metadata.capability("tools"), Some(Capability::Supported));
+        assert_eq!(metadata.capability("vision"), Some(Capability::Unknown));
+        assert_eq!(metadata.capability("audio"), Some(Capability::Unsupported));
+        assert_eq!(metadata.capability("ctx>100k"), None);
+
+        // Caches written before capability tracking deserialize as all stated
+        let json = r#"{"id":"m","provider":"p","display_name":null,"description":null,"owned_by":null,"created":null,"context_length":null,"max_input_tokens":null,"max_output_tokens":null,"input_price_per_m":null,"output_price_per_m":null,"supports_tools":false,"supports_vision":false,"supports_audio":false,"supports_reasoning":false,"supports_code":false,"supports_function_calling":false,"supports_json_mode":false,"supports_streaming":false,"model_type":"Chat","is_deprecated":false,"is_fine_tunable":false,"raw_data":null}"#;
+        let cached: ModelMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(cached.capability("vision"), Some(Capability::Unsupported));
+    }
 }
 
 #[cfg(test)]
@@ -898,6 +917,39 @@ mod model_metadata_config_tests {
         );
     }
 
+    #[test]
+    fn test_extract_deprecation_metadata() {
+        use lc::model_metadata::ModelMetadataExtractor;
+        use lc::provider::Provider;
+        use serde_json::json;
+
+        let (_temp_dir, _config_dir, _guard) = setup_test_config_dir();
+        let extractor = ModelMetadataExtractor::new().unwrap();
+        let provider = Provider {
+            provider: "openai".to_string(),
+            status: "live".to_string(),
+            supports_tools: false,
+            supports_structured_output: false,
+        };
+
+        let model = json!({
+            "id": "gpt-4-0314",
+            "deprecated": true,
+            "shutdown_date": "2024-06-13T00:00:00Z",
+            "replacement": "gpt-4o"
+        });
+        let metadata = extractor.extract_metadata(&provider, &model).unwrap();
+        assert!(metadata.is_deprecated);
+        assert_eq!(metadata.deprecation_date.as_deref(), Some("2024-06-13"));
+        assert_eq!(metadata.replacement_model.as_deref(), Some("gpt-4o"));
+
+        let current = json!({ "id": "gpt-4o" });
+        let metadata = extractor.extract_metadata(&provider, &current).unwrap();
+        assert!(!metadata.is_deprecated);
+        assert!(metadata.deprecation_date.is_none());
+        assert!(metadata.replacement_model.is_none());
+    }
+
     #[test]
     fn test_jq_path_array_filtering() {
         use lc::model_metadata::ModelMetadataExtractor;
@@ -1128,3 +1180,254 @@ mod models_validation_tests {
         assert!(!validate_provider_name("provider@special"));
     }
 }
+
+#[cfg(test)]
+mod models_deprecation_tests {
+    use super::*;
+    use lc::models::deprecation::{find_in_models, DeprecationNotice, ModelOrigin};
+
+    fn deprecated_model(date: Option<&str>, replacement: Option<&str>) -> ModelMetadata {
+        ModelMetadata {
+            id: "old-model".to_string(),
+            provider: "openai".to_string(),
+            is_deprecated: true,
+            deprecation_date: date.map(|d| d.to_string()),
+            replacement_model: replacement.map(|r| r.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_notice_only_for_deprecated_models() {
+        let current = ModelMetadata {
+            id: "gpt-4o".to_string(),
+            ..Default::default()
+        };
+        assert!(DeprecationNotice::from_metadata(&current).is_none());
+
+        // A published shutdown date alone is enough to warn
+        let scheduled = ModelMetadata {
+            id: "scheduled".to_string(),
+            deprecation_date: Some("2999-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(DeprecationNotice::from_metadata(&scheduled).is_some());
+    }
+
+    #[test]
+    fn test_notice_description() {
+        let past = DeprecationNotice::from_metadata(&deprecated_model(
+            Some("2020-01-01"),
+            Some("new-model"),
+        ))
+        .unwrap();
+        assert!(past.is_past_date());
+        assert_eq!(
+            past.describe(),
+            "was retired on 2020-01-01; use new-model instead"
+        );
+
+        let future =
+            DeprecationNotice::from_metadata(&deprecated_model(Some("2999-01-01"), None)).unwrap();
+        assert!(!future.is_past_date());
+        assert_eq!(future.describe(), "is deprecated (shutdown on 2999-01-01)");
+    }
+
+    #[test]
+    fn test_migration_hints() {
+        let notice =
+            DeprecationNotice::from_metadata(&deprecated_model(None, Some("new-model"))).unwrap();
+
+        assert_eq!(
+            notice.migration_hint(&ModelOrigin::Default).as_deref(),
+            Some("lc config set model new-model")
+        );
+        assert_eq!(
+            notice
+                .migration_hint(&ModelOrigin::Alias("fast".to_string()))
+                .as_deref(),
+            Some("lc alias add fast openai:new-model")
+        );
+        assert!(notice.migration_hint(&ModelOrigin::Explicit).is_none());
+    }
+
+    #[test]
+    fn test_find_in_models() {
+        let models = vec![
+            deprecated_model(None, None),
+            ModelMetadata {
+                id: "gpt-4o".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        assert!(find_in_models(&models, "old-model").is_some());
+        assert!(find_in_models(&models, "gpt-4o").is_none());
+        assert!(find_in_models(&models, "missing").is_none());
+    }
+}
+
+#[cfg(test)]
+mod models_refresh_tests {
+    use super::common::get_test_binary_path;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn lc(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(get_test_binary_path())
+            .args(args)
+            .env("LC_TEST_CONFIG_DIR", dir)
+            .env("XDG_CONFIG_HOME", dir.join("xdg"))
+            .env_remove("LC_READ_ONLY")
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("Failed to execute command")
+    }
+
+    async fn models_server() -> String {
+        use axum::{routing::get, Json, Router};
+        let app = Router::new().route(
+            "/models",
+            get(|| async { Json(serde_json::json!({"data": [{"id": "m1"}, {"id": "m2"}]})) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_summary_and_provider_selection() {
+        let endpoint = models_server().await;
+        let dir = TempDir::new().unwrap();
+        let providers = dir.path().join("providers");
+        fs::create_dir_all(&providers).unwrap();
+        for (name, endpoint) in [
+            ("one", endpoint.as_str()),
+            ("two", endpoint.as_str()),
+            ("down", "http://127.0.0.1:1"),
+            ("nokey", endpoint.as_str()),
+        ] {
+            fs::write(
+                providers.join(format!("{}.toml", name)),
+                format!("endpoint = \"{}\"\nmodels = []\n", endpoint),
+            )
+            .unwrap();
+        }
+        fs::write(
+            dir.path().join("keys.toml"),
+            "[api_keys]\none = \"k\"\ntwo = \"k\"\ndown = \"k\"\n",
+        )
+        .unwrap();
+
+        let path = dir.path().to_path_buf();
+        let (all, selected, unknown) = tokio::task::spawn_blocking(move || {
+            (
+                lc(&path, &["models", "refresh", "--jobs", "2"]),
+                lc(&path, &["-p", "one", "models", "refresh"]),
+                lc(&path, &["-p", "missing", "models", "refresh"]),
+            )
+        })
+        .await
+        .unwrap();
+
+        let all = String::from_utf8_lossy(&all.stdout).to_string();
+        assert!(all.contains("skipped (no API key)"), "{}", all);
+        assert!(
+            all.contains("Cache updated: 2 providers, 4 total models"),
+            "{}",
+            all
+        );
+        assert!(all.contains("1 failed"), "{}", all);
+
+        let selected = String::from_utf8_lossy(&selected.stdout).to_string();
+        assert!(selected.contains("one"));
+        assert!(!selected.contains("two"), "{}", selected);
+        assert!(selected.contains("Cache updated: 1 providers, 2 total models"));
+
+        assert!(!unknown.status.success());
+    }
+}
+
+#[cfg(test)]
+mod metadata_rules_tests {
+    use lc::model_metadata::{MetadataRules, ModelPaths, TagRule};
+    use std::collections::HashMap;
+
+    fn rules(paths: &[&str], tags: &[(&str, &[&str])]) -> MetadataRules {
+        MetadataRules {
+            model_paths: ModelPaths {
+                paths: paths.iter().map(|p| p.to_string()).collect(),
+                ..ModelPaths::default()
+            },
+            tags: tags
+                .iter()
+                .map(|(name, paths)| {
+                    (
+                        name.to_string(),
+                        TagRule {
+                            paths: paths.iter().map(|p| p.to_string()).collect(),
+                            value_type: "string".to_string(),
+                            transform: None,
+                        },
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_merge_appends_paths_and_overrides_tags() {
+        let local = rules(
+            &[".data[]"],
+            &[("context_length", &[".context_length"]), ("mine", &[".a"])],
+        );
+        let imported = rules(
+            &[".data[]", ".result.models[]"],
+            &[("context_length", &[".limits.context"])],
+        );
+
+        let merged = local.merge(imported);
+        assert_eq!(
+            merged.model_paths.paths,
+            vec![".data[]", ".result.models[]"]
+        );
+        assert_eq!(merged.tags["context_length"].paths, vec![".limits.context"]);
+        assert!(merged.tags.contains_key("mine"));
+    }
+
+    #[test]
+    fn test_compact_keeps_only_used_fields() {
+        let rules = rules(
+            &[".data[]"],
+            &[
+                ("context_length", &[".limits.max_input_tokens"]),
+                ("supports_tools", &["@name_contains(\"tool\")"]),
+            ],
+        );
+        let fields = rules.used_fields();
+        assert!(fields.contains("limits"));
+        assert!(fields.contains("id"));
+        assert!(!fields.iter().any(|f| f.starts_with('@')));
+
+        let response = serde_json::json!({
+            "object": "list",
+            "data": [{
+                "id": "m1",
+                "limits": {"max_input_tokens": 8000},
+                "permission": [{"allow_sampling": true}]
+            }]
+        });
+        let compacted = rules.compact_response(&response, &fields);
+        assert_eq!(
+            compacted,
+            serde_json::json!({
+                "object": "list",
+                "data": [{"id": "m1", "limits": {"max_input_tokens": 8000}}]
+            })
+        );
+    }
+}
@@ -4,6 +4,7 @@ use lc::readers::FileReader;
 #[cfg(feature = "pdf")]
 use lc::readers::{pdf::PdfReader, FileReader};
 use std::fs;
+#[cfg(feature = "pdf")]
 use std::path::Path;
 use tempfile::TempDir;
 
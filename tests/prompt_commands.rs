@@ -8,17 +8,7 @@ use tempfile::TempDir;
 
 // Helper function to create a comprehensive test config
 fn create_comprehensive_config() -> Config {
-    let mut config = Config {
-        providers: HashMap::new(),
-        default_provider: None,
-        default_model: None,
-        aliases: HashMap::new(),
-        system_prompt: None,
-        templates: HashMap::new(),
-        max_tokens: None,
-        temperature: None,
-        stream: None,
-    };
+    let mut config = Config::default();
 
     // Add multiple providers
     config.providers.insert(
@@ -43,8 +33,12 @@ fn create_comprehensive_config() -> Config {
             models_templates: None,
             audio_path: None,
             speech_path: None,
+            rerank_path: None,
             audio_templates: None,
             speech_templates: None,
+            organization: None,
+            project: None,
+            hmac: None,
         },
     );
 
@@ -70,8 +64,12 @@ fn create_comprehensive_config() -> Config {
             models_templates: None,
             audio_path: None,
             speech_path: None,
+            rerank_path: None,
             audio_templates: None,
             speech_templates: None,
+            organization: None,
+            project: None,
+            hmac: None,
         },
     );
 
@@ -115,12 +113,12 @@ mod prompt_argument_parsing_tests {
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
         assert!(cli.provider.is_none());
         assert!(cli.model.is_none());
-        assert!(cli.system_prompt.is_none());
-        assert!(cli.max_tokens.is_none());
-        assert!(cli.temperature.is_none());
-        assert!(cli.attachments.is_empty());
-        assert!(cli.tools.is_none());
-        assert!(!cli.debug);
+        assert!(cli.prompt_args.system_prompt.is_none());
+        assert!(cli.prompt_args.max_tokens.is_none());
+        assert!(cli.prompt_args.temperature.is_none());
+        assert!(cli.prompt_args.attachments.is_empty());
+        assert!(cli.prompt_args.tools.is_none());
+        assert!(cli.debug.is_none());
         assert!(cli.command.is_none());
     }
 
@@ -151,7 +149,7 @@ mod prompt_argument_parsing_tests {
 
         assert_eq!(cli.prompt, vec!["Hello"]);
         assert_eq!(
-            cli.system_prompt,
+            cli.prompt_args.system_prompt,
             Some("You are a helpful assistant".to_string())
         );
     }
@@ -162,7 +160,7 @@ mod prompt_argument_parsing_tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         assert_eq!(cli.prompt, vec!["Hello"]);
-        assert_eq!(cli.max_tokens, Some("2000".to_string()));
+        assert_eq!(cli.prompt_args.max_tokens, Some("2000".to_string()));
     }
 
     #[test]
@@ -171,7 +169,7 @@ mod prompt_argument_parsing_tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         assert_eq!(cli.prompt, vec!["Hello"]);
-        assert_eq!(cli.temperature, Some("0.7".to_string()));
+        assert_eq!(cli.prompt_args.temperature, Some("0.7".to_string()));
     }
 
     #[test]
@@ -180,7 +178,7 @@ mod prompt_argument_parsing_tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         assert_eq!(cli.prompt, vec!["Hello"]);
-        assert_eq!(cli.attachments, vec!["file1.txt", "file2.py"]);
+        assert_eq!(cli.prompt_args.attachments, vec!["file1.txt", "file2.py"]);
     }
 
     #[test]
@@ -189,7 +187,7 @@ mod prompt_argument_parsing_tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         assert_eq!(cli.prompt, vec!["Hello"]);
-        assert_eq!(cli.tools, Some("server1,server2".to_string()));
+        assert_eq!(cli.prompt_args.tools, Some("server1,server2".to_string()));
     }
 
     #[test]
@@ -198,7 +196,7 @@ mod prompt_argument_parsing_tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         assert_eq!(cli.prompt, vec!["Hello"]);
-        assert!(cli.debug);
+        assert_eq!(cli.debug.as_deref(), Some("all"));
     }
 
     #[test]
@@ -228,12 +226,15 @@ mod prompt_argument_parsing_tests {
         assert_eq!(cli.prompt, vec!["Hello", "world"]);
         assert_eq!(cli.provider, Some("openai".to_string()));
         assert_eq!(cli.model, Some("gpt-4".to_string()));
-        assert_eq!(cli.system_prompt, Some("You are helpful".to_string()));
-        assert_eq!(cli.max_tokens, Some("1000".to_string()));
-        assert_eq!(cli.temperature, Some("0.8".to_string()));
-        assert_eq!(cli.attachments, vec!["file.txt"]);
-        assert_eq!(cli.tools, Some("server1".to_string()));
-        assert!(cli.debug);
+        assert_eq!(
+            cli.prompt_args.system_prompt,
+            Some("You are helpful".to_string())
+        );
+        assert_eq!(cli.prompt_args.max_tokens, Some("1000".to_string()));
+        assert_eq!(cli.prompt_args.temperature, Some("0.8".to_string()));
+        assert_eq!(cli.prompt_args.attachments, vec!["file.txt"]);
+        assert_eq!(cli.prompt_args.tools, Some("server1".to_string()));
+        assert_eq!(cli.debug.as_deref(), Some("all"));
     }
 
     #[test]
@@ -269,10 +270,13 @@ mod prompt_argument_parsing_tests {
         assert_eq!(cli.prompt, vec!["Explain", "this", "code"]);
         assert_eq!(cli.provider, Some("anthropic".to_string()));
         assert_eq!(cli.model, Some("claude-3".to_string()));
-        assert_eq!(cli.system_prompt, Some("Be concise".to_string()));
-        assert_eq!(cli.attachments, vec!["code.rs"]);
-        assert_eq!(cli.tools, Some("mcp-server".to_string()));
-        assert!(cli.debug);
+        assert_eq!(
+            cli.prompt_args.system_prompt,
+            Some("Be concise".to_string())
+        );
+        assert_eq!(cli.prompt_args.attachments, vec!["code.rs"]);
+        assert_eq!(cli.prompt_args.tools, Some("mcp-server".to_string()));
+        assert_eq!(cli.debug.as_deref(), Some("all"));
     }
 }
 
@@ -280,17 +284,7 @@ mod prompt_model_resolution_tests {
     use super::*;
 
     fn create_test_config() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add test providers
         config.providers.insert(
@@ -315,8 +309,12 @@ mod prompt_model_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -342,8 +340,12 @@ mod prompt_model_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -681,7 +683,7 @@ mod prompt_tools_integration_tests {
         let args = vec!["lc", "-t", "server1", "Hello"];
         let cli = Cli::try_parse_from(args).unwrap();
 
-        assert_eq!(cli.tools, Some("server1".to_string()));
+        assert_eq!(cli.prompt_args.tools, Some("server1".to_string()));
     }
 
     #[test]
@@ -689,7 +691,10 @@ mod prompt_tools_integration_tests {
         let args = vec!["lc", "-t", "server1,server2,server3", "Hello"];
         let cli = Cli::try_parse_from(args).unwrap();
 
-        assert_eq!(cli.tools, Some("server1,server2,server3".to_string()));
+        assert_eq!(
+            cli.prompt_args.tools,
+            Some("server1,server2,server3".to_string())
+        );
     }
 
     #[test]
@@ -697,7 +702,10 @@ mod prompt_tools_integration_tests {
         let args = vec!["lc", "-t", "server1, server2, server3", "Hello"];
         let cli = Cli::try_parse_from(args).unwrap();
 
-        assert_eq!(cli.tools, Some("server1, server2, server3".to_string()));
+        assert_eq!(
+            cli.prompt_args.tools,
+            Some("server1, server2, server3".to_string())
+        );
     }
 
     #[test]
@@ -705,7 +713,7 @@ mod prompt_tools_integration_tests {
         let args = vec!["lc", "-t", "", "Hello"];
         let cli = Cli::try_parse_from(args).unwrap();
 
-        assert_eq!(cli.tools, Some("".to_string()));
+        assert_eq!(cli.prompt_args.tools, Some("".to_string()));
     }
 }
 
@@ -714,17 +722,7 @@ mod prompt_error_handling_tests {
 
     #[test]
     fn test_resolve_model_with_invalid_provider_model_format() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         let result =
             resolve_model_and_provider(&config, None, Some("provider:model:extra".to_string()));
@@ -735,17 +733,7 @@ mod prompt_error_handling_tests {
 
     #[test]
     fn test_resolve_model_with_colon_in_model_name() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
         config.providers.insert(
             "test".to_string(),
             ProviderConfig {
@@ -768,8 +756,12 @@ mod prompt_error_handling_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
         config.default_provider = Some("test".to_string());
@@ -985,7 +977,7 @@ mod prompt_debug_mode_tests {
         let args = vec!["lc", "-d", "Hello"];
         let cli = Cli::try_parse_from(args).unwrap();
 
-        assert!(cli.debug);
+        assert_eq!(cli.debug.as_deref(), Some("all"));
         assert_eq!(cli.prompt, vec!["Hello"]);
     }
 
@@ -994,7 +986,16 @@ mod prompt_debug_mode_tests {
         let args = vec!["lc", "--debug", "Hello"];
         let cli = Cli::try_parse_from(args).unwrap();
 
-        assert!(cli.debug);
+        assert_eq!(cli.debug.as_deref(), Some("all"));
+        assert_eq!(cli.prompt, vec!["Hello"]);
+    }
+
+    #[test]
+    fn test_debug_flag_with_filter() {
+        let args = vec!["lc", "--debug=mcp,http", "Hello"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert_eq!(cli.debug.as_deref(), Some("mcp,http"));
         assert_eq!(cli.prompt, vec!["Hello"]);
     }
 
@@ -1256,12 +1257,12 @@ mod prompt_comprehensive_integration_tests {
         assert_eq!(cli.prompt, vec!["Analyze", "these", "files"]);
         assert_eq!(cli.provider, Some("anthropic".to_string()));
         assert_eq!(cli.model, Some("claude-3".to_string()));
-        assert_eq!(cli.system_prompt, Some("t:code".to_string()));
-        assert_eq!(cli.max_tokens, Some("2k".to_string()));
-        assert_eq!(cli.temperature, Some("0.8".to_string()));
-        assert_eq!(cli.attachments.len(), 3);
-        assert_eq!(cli.tools, Some("server1,server2".to_string()));
-        assert!(cli.debug);
+        assert_eq!(cli.prompt_args.system_prompt, Some("t:code".to_string()));
+        assert_eq!(cli.prompt_args.max_tokens, Some("2k".to_string()));
+        assert_eq!(cli.prompt_args.temperature, Some("0.8".to_string()));
+        assert_eq!(cli.prompt_args.attachments.len(), 3);
+        assert_eq!(cli.prompt_args.tools, Some("server1,server2".to_string()));
+        assert_eq!(cli.debug.as_deref(), Some("all"));
 
         // Test model resolution
         let (provider, model) =
@@ -1270,17 +1271,18 @@ mod prompt_comprehensive_integration_tests {
         assert_eq!(model, "claude-3");
 
         // Test parameter parsing
-        let max_tokens = Config::parse_max_tokens(&cli.max_tokens.unwrap()).unwrap();
-        let temperature = Config::parse_temperature(&cli.temperature.unwrap()).unwrap();
+        let max_tokens = Config::parse_max_tokens(&cli.prompt_args.max_tokens.unwrap()).unwrap();
+        let temperature = Config::parse_temperature(&cli.prompt_args.temperature.unwrap()).unwrap();
         assert_eq!(max_tokens, 2000);
         assert_eq!(temperature, 0.8);
 
         // Test system prompt resolution
-        let system_prompt = config.resolve_template_or_prompt(&cli.system_prompt.unwrap());
+        let system_prompt =
+            config.resolve_template_or_prompt(&cli.prompt_args.system_prompt.unwrap());
         assert_eq!(system_prompt, "Explain this code: ");
 
         // Test attachment processing
-        let attachment_content = read_and_format_attachments(&cli.attachments).unwrap();
+        let attachment_content = read_and_format_attachments(&cli.prompt_args.attachments).unwrap();
 
         // Verify all files are included with proper formatting
         assert!(attachment_content.contains("```py"));
@@ -10,7 +10,6 @@ use common::{assertions, create_config_with_providers, test_data};
 use lc::config::Config;
 use lc::test_utils::get_test_provider_name;
 use serial_test::serial;
-use std::collections::HashMap;
 
 // Import test utilities
 use lc::test_with_cleanup;
@@ -22,17 +21,7 @@ mod provider_add_tests {
     #[test]
     #[serial]
     fn test_provider_add_basic() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test adding a basic provider
         let result = config.add_provider(
@@ -55,17 +44,7 @@ mod provider_add_tests {
     #[test]
     #[serial]
     fn test_provider_add_with_custom_paths() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Test adding a provider with custom paths
         let result = config.add_provider_with_paths(
@@ -87,17 +66,7 @@ mod provider_add_tests {
     #[test]
     #[serial]
     fn test_provider_add_multiple_providers() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add multiple providers from test data
         for (name, url) in test_data::TEST_PROVIDERS {
@@ -220,17 +189,7 @@ mod provider_list_tests {
     #[test]
     #[serial]
     fn test_provider_list_empty() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         assert!(config.providers.is_empty());
     }
@@ -252,17 +211,7 @@ mod provider_list_tests {
     #[test]
     #[serial]
     fn test_provider_list_ordering() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add providers in specific order
         let providers = vec![
@@ -555,3 +504,208 @@ mod provider_token_url_tests {
         });
     }
 }
+
+#[cfg(test)]
+mod provider_clone_tests {
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn test_provider_clone_copies_configuration() {
+        test_with_cleanup!({
+            let mut config = create_config_with_providers();
+            let openai_name = get_test_provider_name("openai");
+            let gateway_name = get_test_provider_name("gateway");
+            {
+                let source = config.providers.get_mut(&openai_name).unwrap();
+                source.chat_path = "https://api.openai.com/v1/chat/completions".to_string();
+                source
+                    .vars
+                    .insert("project".to_string(), "demo".to_string());
+                source.models_path = "https://api.openai.com/v1/models".to_string();
+            }
+
+            config
+                .clone_provider(
+                    &openai_name,
+                    gateway_name.clone(),
+                    Some("https://gateway.example.com/".to_string()),
+                )
+                .unwrap();
+
+            let clone = config.get_provider(&gateway_name).unwrap();
+            assert_eq!(clone.endpoint, "https://gateway.example.com/");
+            assert_eq!(
+                clone.chat_path,
+                "https://gateway.example.com/v1/chat/completions"
+            );
+            assert_eq!(clone.models_path, "https://gateway.example.com/v1/models");
+            assert_eq!(clone.vars.get("project").map(String::as_str), Some("demo"));
+            assert!(clone.api_key.is_none());
+            assert!(clone.cached_token.is_none());
+
+            // The source is left untouched
+            let source = config.get_provider(&openai_name).unwrap();
+            assert_eq!(
+                source.chat_path,
+                "https://api.openai.com/v1/chat/completions"
+            );
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn test_provider_clone_rejects_existing_and_missing() {
+        test_with_cleanup!({
+            let mut config = create_config_with_providers();
+            let openai_name = get_test_provider_name("openai");
+            let anthropic_name = get_test_provider_name("anthropic");
+
+            let result = config.clone_provider(&openai_name, anthropic_name, None);
+            assert!(result.unwrap_err().to_string().contains("already exists"));
+
+            let result = config.clone_provider("nonexistent", get_test_provider_name("copy"), None);
+            assert!(result.unwrap_err().to_string().contains("not found"));
+        });
+    }
+}
+
+#[cfg(test)]
+mod provider_group_tests {
+    use super::common::get_test_binary_path;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn lc(config_dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(get_test_binary_path())
+            .args(args)
+            .env("LC_TEST_CONFIG_DIR", config_dir)
+            .env_remove("LC_READ_ONLY")
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("Failed to execute command")
+    }
+
+    /// A backend that answers every chat request with `status` and `reply`
+    async fn backend(status: u16, reply: &'static str) -> String {
+        use axum::{http::StatusCode, routing::post, Json, Router};
+        let app = Router::new().route(
+            "/chat/completions",
+            post(move || async move {
+                (
+                    StatusCode::from_u16(status).unwrap(),
+                    Json(serde_json::json!({
+                        "choices": [{"message": {"role": "assistant", "content": reply}}]
+                    })),
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    fn add_provider(dir: &std::path::Path, name: &str, endpoint: &str) {
+        let providers = dir.join("providers");
+        fs::create_dir_all(&providers).unwrap();
+        fs::write(
+            providers.join(format!("{}.toml", name)),
+            format!("endpoint = \"{}\"\nmodels = []\n", endpoint),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_group_add_validates_backends() {
+        let dir = TempDir::new().unwrap();
+        add_provider(dir.path(), "local", "http://127.0.0.1:1");
+
+        let output = lc(
+            dir.path(),
+            &["providers", "group", "add", "pool", "-b", "missing"],
+        );
+        assert!(!output.status.success());
+
+        let output = lc(
+            dir.path(),
+            &["providers", "group", "add", "local", "-b", "local"],
+        );
+        assert!(!output.status.success());
+
+        let output = lc(
+            dir.path(),
+            &[
+                "providers",
+                "group",
+                "add",
+                "pool",
+                "-b",
+                "local=2",
+                "-s",
+                "weighted",
+            ],
+        );
+        assert!(output.status.success());
+        let list = String::from_utf8_lossy(&lc(dir.path(), &["providers", "group", "list"]).stdout)
+            .to_string();
+        assert!(list.contains("pool"));
+        assert!(list.contains("local=2"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_failing_backend_is_ejected() {
+        let failing = backend(503, "down").await;
+        let healthy = backend(200, "hello from healthy").await;
+
+        let dir = TempDir::new().unwrap();
+        add_provider(dir.path(), "failing", &failing);
+        add_provider(dir.path(), "healthy", &healthy);
+        fs::write(
+            dir.path().join("keys.toml"),
+            "[api_keys]\nfailing = \"k1\"\nhealthy = \"k2\"\n",
+        )
+        .unwrap();
+
+        let path = dir.path().to_path_buf();
+        let outputs = tokio::task::spawn_blocking(move || {
+            let added = lc(
+                &path,
+                &[
+                    "providers",
+                    "group",
+                    "add",
+                    "pool",
+                    "-b",
+                    "failing",
+                    "-b",
+                    "healthy",
+                    "--max-failures",
+                    "1",
+                ],
+            );
+            assert!(added.status.success());
+
+            let prompts: Vec<_> = (0..3)
+                .map(|_| lc(&path, &["-p", "pool", "-m", "test-model", "hi"]))
+                .collect();
+            let status = lc(&path, &["providers", "group", "status", "pool"]);
+            (prompts, status)
+        })
+        .await
+        .unwrap();
+        let (prompts, status) = outputs;
+
+        // The first request goes to the failing backend, which is then ejected
+        assert!(!prompts[0].status.success());
+        for output in &prompts[1..] {
+            assert!(output.status.success());
+            assert!(String::from_utf8_lossy(&output.stdout).contains("hello from healthy"));
+        }
+        let status = String::from_utf8_lossy(&status.stdout).to_string();
+        assert!(status.contains("ejected"), "{}", status);
+    }
+}
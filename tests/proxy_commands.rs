@@ -18,17 +18,7 @@ mod proxy_state_tests {
     use super::*;
 
     fn create_test_config() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add test providers
         config.providers.insert(
@@ -52,8 +42,12 @@ mod proxy_state_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -78,8 +72,12 @@ mod proxy_state_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -145,15 +143,8 @@ mod proxy_model_parsing_tests {
 
     fn create_config_with_aliases() -> Config {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add providers
@@ -178,8 +169,12 @@ mod proxy_model_parsing_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -204,8 +199,12 @@ mod proxy_model_parsing_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -518,15 +517,8 @@ mod proxy_filtering_tests {
         model_filter: Option<String>,
     ) -> ProxyState {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         config.providers.insert(
@@ -550,8 +542,12 @@ mod proxy_filtering_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -576,8 +572,12 @@ mod proxy_filtering_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -835,15 +835,9 @@ mod proxy_integration_tests {
     #[test]
     fn test_proxy_state_with_real_config() {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("gpt-4".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add realistic provider configuration
@@ -868,8 +862,12 @@ mod proxy_integration_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -903,15 +901,8 @@ mod proxy_integration_tests {
     #[test]
     fn test_proxy_workflow_simulation() {
         let config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Simulate proxy server startup
@@ -940,17 +931,7 @@ mod proxy_integration_tests {
 
     #[test]
     fn test_proxy_error_scenarios() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         // Test error cases
         let error_cases = vec!["nonexistent:model", "model-without-provider"];
@@ -1029,34 +1010,14 @@ mod proxy_authentication_tests {
     #[test]
     fn test_authentication_scenarios() {
         let state_with_auth = ProxyState {
-            config: Config {
-                providers: HashMap::new(),
-                default_provider: None,
-                default_model: None,
-                aliases: HashMap::new(),
-                system_prompt: None,
-                templates: HashMap::new(),
-                max_tokens: None,
-                temperature: None,
-                stream: None,
-            },
+            config: Config::default(),
             api_key: Some("sk-test123".to_string()),
             provider_filter: None,
             model_filter: None,
         };
 
         let state_without_auth = ProxyState {
-            config: Config {
-                providers: HashMap::new(),
-                default_provider: None,
-                default_model: None,
-                aliases: HashMap::new(),
-                system_prompt: None,
-                templates: HashMap::new(),
-                max_tokens: None,
-                temperature: None,
-                stream: None,
-            },
+            config: Config::default(),
             api_key: None,
             provider_filter: None,
             model_filter: None,
@@ -1167,17 +1128,7 @@ mod proxy_error_handling_tests {
 
     #[test]
     fn test_model_parsing_errors() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         let error_cases = vec!["nonexistent:model", "invalid-provider:model", ""];
 
@@ -1218,15 +1169,8 @@ mod proxy_error_handling_tests {
     #[test]
     fn test_provider_filter_errors() {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add only openai provider
@@ -1251,8 +1195,12 @@ mod proxy_error_handling_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
         // Simulate alias insertions
@@ -262,15 +262,9 @@ mod rag_model_consistency_tests {
     fn test_rag_provider_client_separation() {
         // Test that RAG can use different providers for embedding vs chat
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("venice".to_string()), // Chat provider
             default_model: Some("llama-3.3-70b".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add chat provider (Venice)
@@ -295,8 +289,12 @@ mod rag_model_consistency_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -322,8 +320,12 @@ mod rag_model_consistency_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -220,15 +220,9 @@ mod similar_model_resolution_tests {
 
     fn create_test_config_for_similarity() -> Config {
         let mut config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("text-embedding-3-small".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         // Add OpenAI provider
@@ -256,8 +250,12 @@ mod similar_model_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -283,8 +281,12 @@ mod similar_model_resolution_tests {
                 models_templates: None,
                 audio_path: None,
                 speech_path: None,
+                rerank_path: None,
                 audio_templates: None,
                 speech_templates: None,
+                organization: None,
+                project: None,
+                hmac: None,
             },
         );
 
@@ -553,17 +555,7 @@ mod similar_error_handling_tests {
 
     #[test]
     fn test_similar_with_invalid_model_info() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         // Test with empty config (no providers)
         let result = lc::utils::resolve_model_and_provider(&config, None, None);
@@ -665,15 +657,9 @@ mod similar_integration_tests {
     #[test]
     fn test_complete_similarity_workflow() {
         let _config = Config {
-            providers: HashMap::new(),
             default_provider: Some("openai".to_string()),
             default_model: Some("text-embedding-3-small".to_string()),
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+            ..Default::default()
         };
 
         let db_name = format!("similarity_workflow_test_{}", std::process::id());
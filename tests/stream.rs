@@ -7,15 +7,8 @@ fn test_lc_stream() {
     // Verify that the stream field exists in Config
     use lc::config::Config;
     let config = Config {
-        providers: std::collections::HashMap::new(),
-        default_provider: None,
-        default_model: None,
-        aliases: std::collections::HashMap::new(),
-        system_prompt: None,
-        templates: std::collections::HashMap::new(),
-        max_tokens: None,
-        temperature: None,
         stream: Some(true), // This verifies the stream field exists
+        ..Default::default()
     };
 
     // Test that we can access the stream setting
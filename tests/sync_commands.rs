@@ -37,6 +37,172 @@ fn test_config_resolver_with_temp_dir() -> Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+mod optional_data_tests {
+    use super::*;
+    use lc::cli::SyncInclude;
+    use lc::sync::{collect_sync_files, compress_file, decompress_file, is_selected};
+
+    fn setup_config_dir() -> Result<TempDir> {
+        let temp_dir = TempDir::new()?;
+        let dir = temp_dir.path();
+        fs::write(dir.join("config.toml"), "default_provider = \"openai\"")?;
+        fs::write(dir.join("logs.db"), b"sqlite")?;
+        fs::create_dir_all(dir.join("providers"))?;
+        fs::write(dir.join("providers/openai.toml"), "endpoint = \"x\"")?;
+        fs::create_dir_all(dir.join("embeddings"))?;
+        fs::write(dir.join("embeddings/docs.db"), vec![7u8; 4096])?;
+        fs::create_dir_all(dir.join("models"))?;
+        fs::write(dir.join("models/openai.json"), "{\"models\": []}")?;
+        Ok(temp_dir)
+    }
+
+    #[test]
+    fn test_collect_excludes_optional_data_by_default() -> Result<()> {
+        let temp_dir = setup_config_dir()?;
+        let files = collect_sync_files(temp_dir.path(), &[], false)?;
+        let names: Vec<_> = files.iter().map(|f| f.name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec!["config.toml", "logs.db", "providers/openai.toml"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_includes_compressed_vectors_and_models() -> Result<()> {
+        let temp_dir = setup_config_dir()?;
+        let files = collect_sync_files(
+            temp_dir.path(),
+            &[SyncInclude::Vectors, SyncInclude::Models],
+            false,
+        )?;
+
+        let vectors = files
+            .iter()
+            .find(|f| f.name == "embeddings/docs.db.gz")
+            .expect("vector db should be included");
+        assert!(vectors.content.len() < 4096);
+        assert!(files.iter().any(|f| f.name == "models/openai.json.gz"));
+
+        let restored = decompress_file(vectors.clone())?;
+        assert_eq!(restored.name, "embeddings/docs.db");
+        assert_eq!(restored.content, vec![7u8; 4096]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_leaves_config_files_untouched() -> Result<()> {
+        let file = ConfigFile {
+            name: "notes.toml.gz".to_string(),
+            content: b"not gzip".to_vec(),
+        };
+        let result = decompress_file(file)?;
+        assert_eq!(result.name, "notes.toml.gz");
+
+        let compressed = compress_file(&ConfigFile {
+            name: "models_cache.json".to_string(),
+            content: b"{}".to_vec(),
+        })?;
+        assert_eq!(decompress_file(compressed)?.content, b"{}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_selected() {
+        assert!(is_selected("config.toml", &[], false));
+        assert!(is_selected("logs.db", &[], false));
+        assert!(!is_selected("embeddings/docs.db.gz", &[], false));
+        assert!(is_selected(
+            "embeddings/docs.db.gz",
+            &[SyncInclude::Vectors],
+            false
+        ));
+        assert!(!is_selected(
+            "models/openai.json.gz",
+            &[SyncInclude::Vectors],
+            false
+        ));
+        assert!(is_selected(
+            "models/openai.json.gz",
+            &[SyncInclude::Models],
+            false
+        ));
+    }
+}
+
+#[cfg(test)]
+mod key_sync_tests {
+    use super::*;
+    use lc::sync::{
+        collect_sync_files, decrypt_keys_file, derive_key_from_password,
+        derive_keys_key_from_password, encrypt_keys_file, is_selected, ENCRYPTED_KEYS_FILE,
+        KEYS_FILE,
+    };
+
+    fn keys_file() -> ConfigFile {
+        ConfigFile {
+            name: KEYS_FILE.to_string(),
+            content: b"[api_keys]\nopenai = \"sk-test\"\n".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_keys_excluded_unless_with_keys() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(temp_dir.path().join("config.toml"), "")?;
+        fs::write(temp_dir.path().join(KEYS_FILE), "[api_keys]")?;
+
+        let without = collect_sync_files(temp_dir.path(), &[], false)?;
+        assert!(without.iter().all(|f| f.name != KEYS_FILE));
+
+        let with = collect_sync_files(temp_dir.path(), &[], true)?;
+        assert!(with.iter().any(|f| f.name == KEYS_FILE));
+
+        assert!(!is_selected(ENCRYPTED_KEYS_FILE, &[], false));
+        assert!(!is_selected(KEYS_FILE, &[], false));
+        assert!(is_selected(ENCRYPTED_KEYS_FILE, &[], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_key_differs_from_config_key() {
+        let passphrase = "same-passphrase";
+        assert_ne!(
+            derive_key_from_password(passphrase).unwrap(),
+            derive_keys_key_from_password(passphrase).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_keys_roundtrip_and_wrong_passphrase() -> Result<()> {
+        let encrypted = encrypt_keys_file(&keys_file(), "key-passphrase")?;
+        assert_eq!(encrypted.name, ENCRYPTED_KEYS_FILE);
+        assert_ne!(encrypted.content, keys_file().content);
+
+        let decrypted = decrypt_keys_file(&encrypted, "key-passphrase")?;
+        assert_eq!(decrypted.name, KEYS_FILE);
+        assert_eq!(decrypted.content, keys_file().content);
+
+        let err = decrypt_keys_file(&encrypted, "wrong").unwrap_err();
+        assert!(err.to_string().contains("passphrase"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypted_keys_must_be_valid_toml() -> Result<()> {
+        let invalid = ConfigFile {
+            name: KEYS_FILE.to_string(),
+            content: b"api_keys = [not toml".to_vec(),
+        };
+        let encrypted = encrypt_keys_file(&invalid, "key-passphrase")?;
+        let err = decrypt_keys_file(&encrypted, "key-passphrase").unwrap_err();
+        assert!(err.to_string().contains("not a valid keys.toml"));
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod encryption_tests {
     use lc::sync::{
@@ -127,7 +293,7 @@ mod integration_tests {
     #[tokio::test]
     async fn test_sync_to_invalid_provider() {
         // Test with encrypted=false, yes=true to skip confirmation
-        let result = lc::sync::handle_sync_to("invalid_provider", false, true).await;
+        let result = lc::sync::handle_sync_to("invalid_provider", false, true, &[], false).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -138,7 +304,7 @@ mod integration_tests {
     #[tokio::test]
     async fn test_sync_from_invalid_provider() {
         // Test with encrypted=false, yes=true to skip confirmation
-        let result = lc::sync::handle_sync_from("invalid_provider", false, true).await;
+        let result = lc::sync::handle_sync_from("invalid_provider", false, true, &[], false).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -185,7 +351,7 @@ mod cli_integration_tests {
     async fn test_sync_invalid_provider() {
         // Test invalid provider handling using direct API
         // Use encrypted=false, yes=true to avoid hanging on stdin prompt
-        let result = lc::sync::handle_sync_to("invalid_provider", false, true).await;
+        let result = lc::sync::handle_sync_to("invalid_provider", false, true, &[], false).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
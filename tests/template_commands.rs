@@ -6,24 +6,13 @@
 mod common;
 
 use lc::config::Config;
-use std::collections::HashMap;
 
 #[cfg(test)]
 mod template_add_tests {
     use super::*;
 
     fn create_empty_config() -> Config {
-        Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        }
+        Config::default()
     }
 
     #[test]
@@ -183,6 +172,41 @@ mod template_add_tests {
         );
     }
 
+    #[test]
+    fn test_template_prompt_starting_with_file_is_not_a_path() {
+        let mut config = create_empty_config();
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("reviewer.md");
+        std::fs::write(&path, "You review Rust code.").unwrap();
+
+        config
+            .add_template("notes".to_string(), "file: summarize this".to_string())
+            .unwrap();
+        config
+            .add_template_file("reviewer".to_string(), path.display().to_string())
+            .unwrap();
+
+        assert_eq!(
+            config.template_source("notes").unwrap(),
+            Some("file: summarize this".to_string())
+        );
+        assert_eq!(
+            config.template_source("reviewer").unwrap(),
+            Some("You review Rust code.".to_string())
+        );
+        assert!(config.has_template("reviewer"));
+
+        // Adding a prompt under the same name replaces the file-backed template
+        config
+            .add_template("reviewer".to_string(), "Inline".to_string())
+            .unwrap();
+        assert_eq!(
+            config.template_source("reviewer").unwrap(),
+            Some("Inline".to_string())
+        );
+        assert!(config.template_files.is_empty());
+    }
+
     #[test]
     fn test_template_add_multiline_content() {
         let mut config = create_empty_config();
@@ -220,17 +244,7 @@ mod template_delete_tests {
     use super::*;
 
     fn create_config_with_templates() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add test templates
         config.templates.insert(
@@ -360,17 +374,7 @@ mod template_list_tests {
 
     #[test]
     fn test_template_list_empty() {
-        let config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let config = Config::default();
 
         let templates = config.list_templates();
         assert!(templates.is_empty());
@@ -379,17 +383,7 @@ mod template_list_tests {
 
     #[test]
     fn test_template_list_with_templates() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add some templates
         config.templates.insert(
@@ -425,17 +419,7 @@ mod template_list_tests {
 
     #[test]
     fn test_template_list_ordering() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add templates in specific order
         config
@@ -459,17 +443,7 @@ mod template_list_tests {
 
     #[test]
     fn test_template_list_immutable() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
         config
             .templates
             .insert("test".to_string(), "Test template".to_string());
@@ -488,17 +462,7 @@ mod template_resolution_tests {
     use super::*;
 
     fn create_config_with_templates() -> Config {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add test templates
         config.templates.insert(
@@ -672,17 +636,7 @@ mod template_validation_tests {
 
     #[test]
     fn test_template_name_validation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Various template names should be allowed
         let valid_names = vec![
@@ -708,17 +662,7 @@ mod template_validation_tests {
 
     #[test]
     fn test_template_content_validation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Various content types should be allowed
         let long_content = "Very long content ".repeat(100);
@@ -750,17 +694,7 @@ mod template_validation_tests {
 
     #[test]
     fn test_template_name_uniqueness() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add template
         config
@@ -791,17 +725,7 @@ mod template_integration_tests {
 
     #[test]
     fn test_template_workflow_complete() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Start with empty templates
         assert!(config.list_templates().is_empty());
@@ -874,17 +798,7 @@ mod template_integration_tests {
     #[test]
     fn test_template_persistence_simulation() {
         // Simulate config save/load cycle
-        let mut config1 = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config1 = Config::default();
 
         // Add templates
         config1
@@ -901,18 +815,10 @@ mod template_integration_tests {
             .unwrap();
 
         // Simulate serialization/deserialization by cloning the templates
-        let mut config2 = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
+        let config2 = Config {
+            templates: config1.templates.clone(),
+            ..Default::default()
         };
-        config2.templates = config1.templates.clone();
 
         // Verify templates persisted
         assert_eq!(config2.list_templates().len(), 2);
@@ -934,17 +840,7 @@ mod template_integration_tests {
 
     #[test]
     fn test_template_with_system_prompt_simulation() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add template
         config
@@ -969,17 +865,7 @@ mod template_integration_tests {
 
     #[test]
     fn test_template_complex_scenarios() {
-        let mut config = Config {
-            providers: HashMap::new(),
-            default_provider: None,
-            default_model: None,
-            aliases: HashMap::new(),
-            system_prompt: None,
-            templates: HashMap::new(),
-            max_tokens: None,
-            temperature: None,
-            stream: None,
-        };
+        let mut config = Config::default();
 
         // Add templates with various complexities
         config
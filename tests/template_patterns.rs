@@ -29,8 +29,12 @@ fn test_regex_pattern_matching() {
         models_templates: None,
         audio_path: None,
         speech_path: None,
+        rerank_path: None,
         audio_templates: None,
         speech_templates: None,
+        organization: None,
+        project: None,
+        hmac: None,
     };
 
     // Create chat endpoint templates
@@ -43,6 +47,8 @@ fn test_regex_pattern_matching() {
         ),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert("gpt-5.*".to_string(), gpt5_template.clone());
 
@@ -51,6 +57,8 @@ fn test_regex_pattern_matching() {
         request: Some(r#"{"model": "{{ model }}", "nano_tokens": {{ max_tokens }}}"#.to_string()),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert("gpt-5-nano".to_string(), gpt5_nano_template.clone());
 
@@ -118,8 +126,12 @@ fn test_multiple_patterns() {
         models_templates: None,
         audio_path: None,
         speech_path: None,
+        rerank_path: None,
         audio_templates: None,
         speech_templates: None,
+        organization: None,
+        project: None,
+        hmac: None,
     };
 
     // Create chat endpoint templates
@@ -130,6 +142,8 @@ fn test_multiple_patterns() {
         request: Some(r#"{"version": "v1"}"#.to_string()),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert(".*-v1$".to_string(), v1_template.clone());
 
@@ -137,6 +151,8 @@ fn test_multiple_patterns() {
         request: Some(r#"{"version": "v2"}"#.to_string()),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert(".*-v2$".to_string(), v2_template.clone());
 
@@ -144,6 +160,8 @@ fn test_multiple_patterns() {
         request: Some(r#"{"version": "other"}"#.to_string()),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert("other-.*".to_string(), other_template.clone());
 
@@ -190,8 +208,12 @@ fn test_default_template_fallback() {
         models_templates: None,
         audio_path: None,
         speech_path: None,
+        rerank_path: None,
         audio_templates: None,
         speech_templates: None,
+        organization: None,
+        project: None,
+        hmac: None,
     };
 
     // Create chat endpoint templates with default
@@ -202,6 +224,8 @@ fn test_default_template_fallback() {
         request: Some(r#"{"type": "default"}"#.to_string()),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert("".to_string(), default_template);
 
@@ -210,6 +234,8 @@ fn test_default_template_fallback() {
         request: Some(r#"{"type": "specific"}"#.to_string()),
         response: None,
         stream_response: None,
+        response_mappings: None,
+        stream_mappings: None,
     };
     chat_templates.insert("specific-.*".to_string(), specific_template);
 
@@ -247,8 +273,12 @@ fn test_endpoint_specific_templates() {
         models_templates: None,
         audio_path: None,
         speech_path: None,
+        rerank_path: None,
         audio_templates: None,
         speech_templates: None,
+        organization: None,
+        project: None,
+        hmac: None,
     };
 
     // Create different templates for different endpoints
@@ -259,6 +289,8 @@ fn test_endpoint_specific_templates() {
             request: Some(r#"{"endpoint": "chat"}"#.to_string()),
             response: None,
             stream_response: None,
+            response_mappings: None,
+            stream_mappings: None,
         },
     );
 
@@ -269,6 +301,8 @@ fn test_endpoint_specific_templates() {
             request: Some(r#"{"endpoint": "images"}"#.to_string()),
             response: None,
             stream_response: None,
+            response_mappings: None,
+            stream_mappings: None,
         },
     );
 
@@ -289,3 +323,49 @@ fn test_endpoint_specific_templates() {
     let template = provider_config.get_endpoint_template("embeddings", "test-model");
     assert!(template.is_none());
 }
+
+#[test]
+fn test_mappings_from_provider_file() {
+    let provider_config: ProviderConfig = toml::from_str(
+        r#"
+endpoint = "https://api.example.com"
+models = []
+chat_path = "/v2/generate"
+
+[chat_templates.""]
+request = '{"model_id": "{{ model }}"}'
+
+[chat_templates."".response_mappings]
+content = "/output/text"
+
+[chat_templates."special".stream_mappings]
+content = "/text"
+done = "/event"
+done_value = "end"
+"#,
+    )
+    .unwrap();
+
+    let default = provider_config
+        .get_endpoint_template_config("chat", "any-model")
+        .unwrap();
+    assert_eq!(
+        default
+            .response_mappings
+            .as_ref()
+            .unwrap()
+            .content
+            .as_deref(),
+        Some("/output/text")
+    );
+    assert!(default.stream_mappings.is_none());
+
+    let special = provider_config
+        .get_endpoint_template_config("chat", "special")
+        .unwrap();
+    let stream = special.stream_mappings.as_ref().unwrap();
+    assert!(stream.is_done(&serde_json::json!({"event": "end"})));
+    assert!(provider_config
+        .get_endpoint_template_config("images", "special")
+        .is_none());
+}